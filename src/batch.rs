@@ -15,7 +15,7 @@ use crate::strategy::{derive_defending_range, derive_opening_range, PotType};
 // Representative flop boards (~50 covering major textures)
 // ---------------------------------------------------------------------------
 
-const REPRESENTATIVE_FLOPS: &[&str] = &[
+pub const REPRESENTATIVE_FLOPS: &[&str] = &[
     // High dry
     "As7d2c", "Kh8d3c", "Qd6s2h", "Js7c3d", "Ah9c4d",
     "Kd5s2c", "Qs8d3h", "Jh6c2s",
@@ -0,0 +1,280 @@
+//! Texture features of a board in the u8 card encoding (rank = `c >> 2`,
+//! suit = `c & 3`), for choosing bet sizes per flop and grouping flop
+//! reports. Flop, turn and river boards are all accepted.
+
+/// How the board's suits are distributed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuitPattern {
+    /// Every card shares one suit.
+    Monotone,
+    /// At least two cards share a suit, but not all of them.
+    TwoTone,
+    /// No two cards share a suit.
+    Rainbow,
+}
+
+impl SuitPattern {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SuitPattern::Monotone => "monotone",
+            SuitPattern::TwoTone => "two-tone",
+            SuitPattern::Rainbow => "rainbow",
+        }
+    }
+}
+
+/// Which ranks the board repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pairing {
+    Unpaired,
+    Paired,
+    DoublePaired,
+    Trips,
+    /// Trips and a pair (turn or river).
+    FullHouse,
+    Quads,
+}
+
+impl Pairing {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Pairing::Unpaired => "unpaired",
+            Pairing::Paired => "paired",
+            Pairing::DoublePaired => "double-paired",
+            Pairing::Trips => "trips",
+            Pairing::FullHouse => "full house",
+            Pairing::Quads => "quads",
+        }
+    }
+}
+
+/// How high the board runs, counting distinct ranks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HighCardClass {
+    /// Two or more ranks Ten or better (A-K-x, K-Q-J, ...).
+    BroadwayHeavy,
+    /// An ace with nothing else Ten or better.
+    AceHigh,
+    /// One King, Queen, Jack or Ten, the rest below.
+    BroadwayHigh,
+    /// Nine-high or lower.
+    Low,
+}
+
+impl HighCardClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HighCardClass::BroadwayHeavy => "broadway-heavy",
+            HighCardClass::AceHigh => "ace-high",
+            HighCardClass::BroadwayHigh => "broadway-high",
+            HighCardClass::Low => "low",
+        }
+    }
+}
+
+/// Rank offset of a Ten (0 = Two, 12 = Ace).
+const TEN: u8 = 8;
+/// Rank offset of an Ace.
+const ACE: u8 = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardTexture {
+    pub suit_pattern: SuitPattern,
+    /// Two or more cards share a rank.
+    pub is_paired: bool,
+    pub pairing: Pairing,
+    /// Number of distinct straights (wheel included) that two hole cards
+    /// could complete with this board: 0 for a disconnected board, 3 for
+    /// 9-8-7.
+    pub connectedness: u8,
+    /// Rank offset of the highest card, 0 = Two through 12 = Ace.
+    pub high_card: u8,
+    pub high_card_class: HighCardClass,
+    /// Three or more cards of one suit, so two hole cards can make a flush.
+    pub flush_possible: bool,
+    /// Two hole cards can make a straight (`connectedness > 0`).
+    pub straight_possible: bool,
+}
+
+impl BoardTexture {
+    /// Whether the top card is a Ten or better.
+    pub fn is_high(&self) -> bool {
+        self.high_card >= TEN
+    }
+
+    /// One-line description, e.g. "ace-high, two-tone, unpaired, 1 straight".
+    pub fn summary(&self) -> String {
+        let straights = match self.connectedness {
+            0 => "no straights".to_string(),
+            1 => "1 straight".to_string(),
+            n => format!("{} straights", n),
+        };
+        format!(
+            "{}, {}, {}, {}",
+            self.high_card_class.as_str(),
+            self.suit_pattern.as_str(),
+            self.pairing.as_str(),
+            straights
+        )
+    }
+}
+
+/// Classify a board given as u8 card indices (3-5 cards).
+pub fn classify_board(board: &[u8]) -> BoardTexture {
+    let mut suit_counts = [0u8; 4];
+    let mut rank_counts = [0u8; 13];
+    let mut rank_mask: u16 = 0;
+    for &c in board {
+        let rank = c >> 2;
+        suit_counts[(c & 3) as usize] += 1;
+        rank_counts[rank as usize] += 1;
+        rank_mask |= 1 << rank;
+    }
+
+    let max_suit = suit_counts.iter().copied().max().unwrap_or(0) as usize;
+    let suit_pattern = if max_suit == board.len() {
+        SuitPattern::Monotone
+    } else if max_suit >= 2 {
+        SuitPattern::TwoTone
+    } else {
+        SuitPattern::Rainbow
+    };
+
+    let count_of = |n: u8| rank_counts.iter().filter(|&&c| c == n).count();
+    let pairing = match (count_of(4), count_of(3), count_of(2)) {
+        (1, _, _) => Pairing::Quads,
+        (_, 1, 0) => Pairing::Trips,
+        (_, 1, _) => Pairing::FullHouse,
+        (_, _, 0) => Pairing::Unpaired,
+        (_, _, 1) => Pairing::Paired,
+        _ => Pairing::DoublePaired,
+    };
+
+    // Bit 0 is an ace playing low so the wheel counts like any straight
+    let mut straight_mask = rank_mask << 1;
+    if rank_mask & (1 << ACE) != 0 {
+        straight_mask |= 1;
+    }
+    let connectedness = (0..=9)
+        .filter(|&low| (straight_mask >> low & 0x1f).count_ones() >= 3)
+        .count() as u8;
+
+    let broadways = (rank_mask >> TEN).count_ones();
+    let high_card_class = if broadways >= 2 {
+        HighCardClass::BroadwayHeavy
+    } else if rank_mask & (1 << ACE) != 0 {
+        HighCardClass::AceHigh
+    } else if broadways == 1 {
+        HighCardClass::BroadwayHigh
+    } else {
+        HighCardClass::Low
+    };
+
+    BoardTexture {
+        suit_pattern,
+        is_paired: pairing != Pairing::Unpaired,
+        pairing,
+        connectedness,
+        high_card: board.iter().map(|&c| c >> 2).max().unwrap_or(0),
+        high_card_class,
+        flush_possible: max_suit >= 3,
+        straight_possible: connectedness > 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_encoding::cards_to_indices;
+    use crate::cards::parse_board;
+
+    fn texture(board: &str) -> BoardTexture {
+        classify_board(&cards_to_indices(&parse_board(board).unwrap()))
+    }
+
+    #[test]
+    fn monotone_high_board() {
+        let t = texture("AhKhQh");
+        assert_eq!(t.suit_pattern, SuitPattern::Monotone);
+        assert!(!t.is_paired);
+        assert_eq!(t.high_card, 12);
+        assert!(t.is_high());
+        // Only broadway (JT) completes a straight
+        assert_eq!(t.connectedness, 1);
+    }
+
+    #[test]
+    fn paired_board() {
+        let t = texture("2s2d7c");
+        assert_eq!(t.suit_pattern, SuitPattern::Rainbow);
+        assert!(t.is_paired);
+        assert_eq!(t.connectedness, 0);
+        assert!(!t.is_high());
+    }
+
+    #[test]
+    fn connected_board() {
+        let t = texture("9s8d7h");
+        assert_eq!(t.suit_pattern, SuitPattern::Rainbow);
+        assert!(!t.is_paired);
+        // 5-9, 6-T and 7-J
+        assert_eq!(t.connectedness, 3);
+        assert_eq!(texture("9s8s7h").suit_pattern, SuitPattern::TwoTone);
+    }
+
+    #[test]
+    fn wheel_counts_as_connected() {
+        assert_eq!(texture("As2d3c").connectedness, 1);
+        assert_eq!(texture("As5d9c").connectedness, 0);
+    }
+
+    #[test]
+    fn reference_boards() {
+        use HighCardClass::*;
+        use Pairing::*;
+        use SuitPattern::*;
+
+        // board, suits, pairing, high cards, straights, flush possible
+        let table = [
+            ("Ks9d4c", Rainbow, Unpaired, BroadwayHigh, 0, false),
+            ("AhKhQh", Monotone, Unpaired, BroadwayHeavy, 1, true),
+            ("2s2d7c", Rainbow, Paired, Low, 0, false),
+            ("9s8d7h", Rainbow, Unpaired, Low, 3, false),
+            ("As2d3c", Rainbow, Unpaired, AceHigh, 1, false),
+            ("7h7d7c", Rainbow, Trips, Low, 0, false),
+            ("Ah7h2d", TwoTone, Unpaired, AceHigh, 0, false),
+            ("QsJsTs", Monotone, Unpaired, BroadwayHeavy, 3, true),
+            ("Jd8d3d", Monotone, Unpaired, BroadwayHigh, 0, true),
+            ("5h4d2c", Rainbow, Unpaired, Low, 2, false),
+            ("AdAcKh", Rainbow, Paired, BroadwayHeavy, 0, false),
+            // Turn
+            ("KdKc5s5h", Rainbow, DoublePaired, BroadwayHigh, 0, false),
+            ("6c5c4c3c", Monotone, Unpaired, Low, 4, true),
+            ("AsKd7h2c", Rainbow, Unpaired, BroadwayHeavy, 0, false),
+            ("JhJd4h4s", TwoTone, DoublePaired, BroadwayHigh, 0, false),
+            ("8h8d8c2s", Rainbow, Trips, Low, 0, false),
+            // River
+            ("9h9d9c4s4h", TwoTone, FullHouse, Low, 0, false),
+            ("QcQdQhQs2c", TwoTone, Quads, BroadwayHigh, 0, false),
+            ("Ts9s8s7s6s", Monotone, Unpaired, BroadwayHigh, 5, true),
+            ("Ah5d9c3s7h", TwoTone, Unpaired, AceHigh, 3, false),
+            ("KsQd2h2c5s", TwoTone, Paired, BroadwayHeavy, 0, false),
+        ];
+        for (board, suits, pairing, high, straights, flush) in table {
+            let t = texture(board);
+            assert_eq!(t.suit_pattern, suits, "{}", board);
+            assert_eq!(t.pairing, pairing, "{}", board);
+            assert_eq!(t.is_paired, pairing != Unpaired, "{}", board);
+            assert_eq!(t.high_card_class, high, "{}", board);
+            assert_eq!(t.connectedness, straights, "{}", board);
+            assert_eq!(t.straight_possible, straights > 0, "{}", board);
+            assert_eq!(t.flush_possible, flush, "{}", board);
+        }
+    }
+
+    #[test]
+    fn summary_reads_like_a_description() {
+        assert_eq!(texture("Ah7h2d").summary(), "ace-high, two-tone, unpaired, no straights");
+        assert_eq!(texture("9s8d7h").summary(), "low, rainbow, unpaired, 3 straights");
+    }
+}
@@ -3,10 +3,30 @@
 //! Groups hand combos into equal-frequency equity buckets to reduce the
 //! information set count from ~1000 combos to ~200 buckets. Equity is
 //! computed via Monte Carlo sampling against a uniform random opponent.
+//!
+//! The potential-aware `Ehs2` mode instead clusters combos on the mean and
+//! spread of their river equity across every runout, so draws and weak made
+//! hands with the same average equity are kept apart.
+//!
+//! The `Draws` mode first splits combos into hand classes (flush draw,
+//! straight draw, pair) and only then bins by equity within each class.
+//!
+//! The `Histogram` mode runs k-means on each combo's whole histogram of
+//! river equities (see `assign_buckets_kmeans`).
+//!
+//! Flop and turn assignments can be cached on disk under
+//! `~/.gto-cli/buckets/` (see `set_bucket_cache_enabled`).
+
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use rand::Rng;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::lookup_eval::evaluate_fast;
+use crate::card_encoding::index_to_card;
+use crate::draws::detect_draws;
+use crate::lookup_table::{evaluate_board, evaluate_seven};
 
 /// Compute equity of a specific combo (c0, c1) against a uniformly random
 /// opponent hand on the given board, using Monte Carlo sampling.
@@ -14,6 +34,17 @@ use crate::lookup_eval::evaluate_fast;
 /// For river (5 cards): exhaustive enumeration over all possible opponent hands.
 /// For flop/turn (3-4 cards): Monte Carlo sampling of runouts + opponents.
 pub fn combo_equity_vs_random(c0: u8, c1: u8, board: &[u8], num_samples: usize) -> f64 {
+    combo_equity_with_rng(c0, c1, board, num_samples, &mut rand::thread_rng())
+}
+
+/// `combo_equity_vs_random` drawing Monte Carlo samples from `rng`.
+fn combo_equity_with_rng<R: Rng>(
+    c0: u8,
+    c1: u8,
+    board: &[u8],
+    num_samples: usize,
+    rng: &mut R,
+) -> f64 {
     let board_len = board.len();
 
     // Build dead card set
@@ -29,57 +60,67 @@ pub fn combo_equity_vs_random(c0: u8, c1: u8, board: &[u8], num_samples: usize)
         exhaustive_river_equity(c0, c1, board, &dead)
     } else {
         // Flop or turn: Monte Carlo
-        monte_carlo_equity(c0, c1, board, &dead, num_samples)
+        monte_carlo_equity(c0, c1, board, &dead, num_samples, rng)
     }
 }
 
 /// Exhaustive equity on the river (5-card board).
 fn exhaustive_river_equity(c0: u8, c1: u8, board: &[u8], dead: &[bool; 52]) -> f64 {
-    let my_score = evaluate_fast(&[c0, c1, board[0], board[1], board[2], board[3], board[4]]);
-
+    let board = [board[0], board[1], board[2], board[3], board[4]];
     let live: Vec<u8> = (0..52u8).filter(|&c| !dead[c as usize]).collect();
     let n = live.len();
-    let mut wins = 0.0;
-    let mut total = 0.0;
-
+    let mut opponents = Vec::with_capacity(n * n.saturating_sub(1) / 2);
     for i in 0..n {
         for j in (i + 1)..n {
-            let opp_score = evaluate_fast(&[
-                live[i],
-                live[j],
-                board[0],
-                board[1],
-                board[2],
-                board[3],
-                board[4],
-            ]);
-            total += 1.0;
-            if my_score > opp_score {
-                wins += 1.0;
-            } else if my_score == opp_score {
-                wins += 0.5;
-            }
+            opponents.push([live[i], live[j]]);
         }
     }
+    // Built from live cards only, so there is nothing left to filter
+    river_equity_vs_live_hands([c0, c1], &board, &opponents).unwrap_or(0.5)
+}
 
-    if total > 0.0 {
-        wins / total
-    } else {
-        0.5
+/// Exact equity of `hero` on a complete board against each of `opponents`
+/// that shares no card with it or the board, counted equally. `None` when
+/// none are left.
+pub(crate) fn river_equity_vs_hands(hero: [u8; 2], board: &[u8; 5], opponents: &[[u8; 2]]) -> Option<f64> {
+    let live: Vec<[u8; 2]> = opponents
+        .iter()
+        .filter(|o| o.iter().all(|c| !hero.contains(c) && !board.contains(c)))
+        .copied()
+        .collect();
+    river_equity_vs_live_hands(hero, board, &live)
+}
+
+/// `river_equity_vs_hands` for opponents already known to share no card
+/// with `hero` or the board.
+fn river_equity_vs_live_hands(hero: [u8; 2], board: &[u8; 5], live: &[[u8; 2]]) -> Option<f64> {
+    if live.is_empty() {
+        return None;
     }
+    let my_score = evaluate_board(board, &[hero])[0];
+
+    let mut wins = 0.0;
+    for opp_score in evaluate_board(board, live) {
+        if my_score > opp_score {
+            wins += 1.0;
+        } else if my_score == opp_score {
+            wins += 0.5;
+        }
+    }
+    Some(wins / live.len() as f64)
 }
 
 /// Monte Carlo equity for flop/turn boards.
-fn monte_carlo_equity(
+fn monte_carlo_equity<R: Rng>(
     c0: u8,
     c1: u8,
     board: &[u8],
     dead: &[bool; 52],
     num_samples: usize,
+    rng: &mut R,
 ) -> f64 {
     let live: Vec<u8> = (0..52u8).filter(|&c| !dead[c as usize]).collect();
     let cards_needed = 5 - board.len(); // cards to complete the board
-    let mut rng = rand::thread_rng();
 
     let mut wins = 0.0;
     let mut total = 0.0;
@@ -111,7 +152,7 @@ fn monte_carlo_equity(
         let opp0 = deck[cards_needed];
         let opp1 = deck[cards_needed + 1];
 
-        let my_score = evaluate_fast(&[
+        let my_score = evaluate_seven(&[
             c0,
             c1,
             full_board[0],
@@ -120,7 +161,7 @@ fn monte_carlo_equity(
             full_board[3],
             full_board[4],
         ]);
-        let opp_score = evaluate_fast(&[
+        let opp_score = evaluate_seven(&[
             opp0,
             opp1,
             full_board[0],
@@ -158,20 +199,85 @@ pub fn assign_buckets(
     num_buckets: usize,
     num_samples: usize,
 ) -> Vec<u16> {
-    let n = combos.len();
-    if n == 0 {
-        return vec![];
+    assign_buckets_seeded(combos, board, num_buckets, num_samples, None)
+}
+
+/// `assign_buckets` with an optional RNG seed for reproducible Monte Carlo
+/// equities. Checks the on-disk cache first when it is enabled.
+pub fn assign_buckets_seeded(
+    combos: &[(u8, u8)],
+    board: &[u8],
+    num_buckets: usize,
+    num_samples: usize,
+    seed: Option<u64>,
+) -> Vec<u16> {
+    ehs_buckets_with_equity(combos, board, num_buckets, num_samples, seed).0
+}
+
+fn ehs_buckets_with_equity(
+    combos: &[(u8, u8)],
+    board: &[u8],
+    num_buckets: usize,
+    num_samples: usize,
+    seed: Option<u64>,
+) -> (Vec<u16>, Vec<f64>) {
+    if combos.is_empty() {
+        return (vec![], vec![]);
     }
 
-    // Compute equity for each combo
-    let equities: Vec<f64> = combos
-        .iter()
-        .map(|&(c0, c1)| combo_equity_vs_random(c0, c1, board, num_samples))
-        .collect();
+    let key = BucketCacheKey {
+        board,
+        combos,
+        num_buckets,
+        num_samples,
+        mode: BucketingMode::Ehs,
+        seed,
+    };
+    cached_buckets(&key, || {
+        let equities = combo_equities(combos, board, num_samples, seed, true);
+        (equal_frequency_buckets(&equities, combos, num_buckets), equities)
+    })
+}
+
+/// Equity vs a random hand for every combo, optionally across rayon threads.
+///
+/// With a seed, combo `h` samples from its own `SmallRng` seeded from
+/// (`seed`, `h`), so the result is the same serial or parallel and does not
+/// depend on thread scheduling. Without one, each thread uses `thread_rng`.
+fn combo_equities(
+    combos: &[(u8, u8)],
+    board: &[u8],
+    num_samples: usize,
+    seed: Option<u64>,
+    parallel: bool,
+) -> Vec<f64> {
+    let equity = |h: usize| {
+        let (c0, c1) = combos[h];
+        match seed {
+            Some(seed) => {
+                let stream = (h as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+                let mut rng = SmallRng::seed_from_u64(seed ^ stream);
+                combo_equity_with_rng(c0, c1, board, num_samples, &mut rng)
+            }
+            None => combo_equity_vs_random(c0, c1, board, num_samples),
+        }
+    };
+    if parallel {
+        (0..combos.len()).into_par_iter().map(equity).collect()
+    } else {
+        (0..combos.len()).map(equity).collect()
+    }
+}
+
+/// Equal-frequency binning of combos by equity. Equal equities are ordered
+/// by the combos' card indices, so a tie straddling a bucket boundary splits
+/// the same way however the combos were listed.
+fn equal_frequency_buckets(equities: &[f64], combos: &[(u8, u8)], num_buckets: usize) -> Vec<u16> {
+    let n = equities.len();
 
     // Sort by equity, keeping track of original indices
     let mut indexed: Vec<(usize, f64)> = equities.iter().enumerate().map(|(i, &e)| (i, e)).collect();
-    indexed.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+    indexed.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| combos[a.0].cmp(&combos[b.0])));
 
     // Equal-frequency binning
     let actual_buckets = num_buckets.min(n);
@@ -185,6 +291,739 @@ pub fn assign_buckets(
     result
 }
 
+// ---------------------------------------------------------------------------
+// Potential-aware (EHS²) bucketing
+// ---------------------------------------------------------------------------
+
+/// How combos are grouped into buckets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BucketingMode {
+    /// Equal-frequency bins over mean equity vs a random hand.
+    #[default]
+    Ehs,
+    /// k-means over (mean, std-dev) of river equity across runouts.
+    Ehs2,
+    /// No abstraction: every combo is its own bucket (see `unabstracted_bucket`).
+    Unabstracted,
+    /// Equity bins within each draw/pair feature class (see `combo_features`).
+    Draws,
+    /// k-means over river equity histograms (see `assign_buckets_kmeans`).
+    Histogram,
+}
+
+impl BucketingMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BucketingMode::Ehs => "ehs",
+            BucketingMode::Ehs2 => "ehs2",
+            BucketingMode::Unabstracted => "none",
+            BucketingMode::Draws => "draws",
+            BucketingMode::Histogram => "hist",
+        }
+    }
+}
+
+/// Bucket combos using the given mode.
+///
+/// `num_samples` is used by `Ehs` and `Draws`; `Ehs2` and `Histogram`
+/// enumerate every runout. `seed` makes the sampled equities reproducible,
+/// as in `assign_buckets_seeded`. On a complete board there is no equity
+/// spread or draw left, so all four modes are identical.
+pub fn bucket_combos(
+    mode: BucketingMode,
+    combos: &[(u8, u8)],
+    board: &[u8],
+    num_buckets: usize,
+    num_samples: usize,
+    seed: Option<u64>,
+) -> Vec<u16> {
+    match mode {
+        BucketingMode::Unabstracted => (0..combos.len())
+            .map(|h| unabstracted_bucket(h, combos.len(), board))
+            .collect(),
+        _ => bucket_combos_with_equity(mode, combos, board, num_buckets, num_samples, seed).0,
+    }
+}
+
+/// `bucket_combos` that also returns each combo's mean equity vs a random hand.
+pub fn bucket_combos_with_equity(
+    mode: BucketingMode,
+    combos: &[(u8, u8)],
+    board: &[u8],
+    num_buckets: usize,
+    num_samples: usize,
+    seed: Option<u64>,
+) -> (Vec<u16>, Vec<f64>) {
+    match mode {
+        BucketingMode::Ehs2 if board.len() < 5 => {
+            ehs2_buckets_with_equity(combos, board, num_buckets)
+        }
+        BucketingMode::Draws if board.len() < 5 => {
+            draw_buckets_with_equity(combos, board, num_buckets, num_samples, seed)
+        }
+        BucketingMode::Histogram if board.len() < 5 => {
+            histogram_buckets_with_equity(combos, board, num_buckets)
+        }
+        BucketingMode::Unabstracted => {
+            let (_, equities) = ehs_buckets_with_equity(combos, board, 1, num_samples, seed);
+            let buckets = (0..combos.len())
+                .map(|h| unabstracted_bucket(h, combos.len(), board))
+                .collect();
+            (buckets, equities)
+        }
+        _ => ehs_buckets_with_equity(combos, board, num_buckets, num_samples, seed),
+    }
+}
+
+/// Bucket of combo `h` (out of `num_combos`) with no abstraction.
+///
+/// Turn and river template strategies are shared across runouts, so the
+/// bucket also encodes which card just fell (its slot among the 49 cards left
+/// after the flop). That gives 49 × `num_combos` buckets past the flop.
+pub fn unabstracted_bucket(h: usize, num_combos: usize, board: &[u8]) -> u16 {
+    let slot = match board {
+        [flop @ .., last] if board.len() > 3 => (0..52u8)
+            .filter(|c| !flop[..3].contains(c))
+            .position(|c| c == *last)
+            .unwrap_or(0),
+        _ => 0,
+    };
+    (slot * num_combos + h) as u16
+}
+
+/// Member combo indices (strongest first) and mean equity of every bucket,
+/// indexed by bucket id. Empty buckets have no members and zero equity.
+pub fn bucket_members(buckets: &[u16], equities: &[f64]) -> Vec<(Vec<usize>, f64)> {
+    let num_buckets = buckets.iter().max().map_or(0, |&b| b as usize + 1);
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); num_buckets];
+    for (i, &b) in buckets.iter().enumerate() {
+        members[b as usize].push(i);
+    }
+    members
+        .into_iter()
+        .map(|mut idx| {
+            idx.sort_by(|&a, &b| equities[b].partial_cmp(&equities[a]).unwrap());
+            let mean = if idx.is_empty() {
+                0.0
+            } else {
+                idx.iter().map(|&i| equities[i]).sum::<f64>() / idx.len() as f64
+            };
+            (idx, mean)
+        })
+        .collect()
+}
+
+/// Mean and standard deviation of each combo's river equity vs a random hand,
+/// taken over every runout of `board` that doesn't overlap the combo.
+pub fn equity_distributions(combos: &[(u8, u8)], board: &[u8]) -> Vec<(f64, f64)> {
+    let mut sum = vec![0.0f64; combos.len()];
+    let mut sum_sq = vec![0.0f64; combos.len()];
+    let mut count = vec![0usize; combos.len()];
+
+    for_each_runout_equity(combos, board, |i, eq| {
+        sum[i] += eq;
+        sum_sq[i] += eq * eq;
+        count[i] += 1;
+    });
+
+    (0..combos.len())
+        .map(|i| {
+            if count[i] == 0 {
+                return (0.5, 0.0);
+            }
+            let n = count[i] as f64;
+            let mean = sum[i] / n;
+            let var = (sum_sq[i] / n - mean * mean).max(0.0);
+            (mean, var.sqrt())
+        })
+        .collect()
+}
+
+/// Call `visit(combo_index, equity)` with each combo's exact river equity on
+/// every runout of `board` that doesn't overlap the combo.
+fn for_each_runout_equity<F: FnMut(usize, f64)>(combos: &[(u8, u8)], board: &[u8], mut visit: F) {
+    let mut dead = [false; 52];
+    for &b in board {
+        dead[b as usize] = true;
+    }
+
+    for runout in &runouts(board, &dead) {
+        let mut full_board = [0u8; 5];
+        for (i, &c) in board.iter().chain(runout.iter()).enumerate() {
+            full_board[i] = c;
+        }
+        let equities = river_equities(combos, &full_board);
+        for (i, eq) in equities.into_iter().enumerate() {
+            if let Some(eq) = eq {
+                visit(i, eq);
+            }
+        }
+    }
+}
+
+/// Every set of cards that completes `board` (a flop or later) to five,
+/// drawn from the cards not marked `dead`. A complete board has the one
+/// empty runout.
+pub(crate) fn runouts(board: &[u8], dead: &[bool; 52]) -> Vec<Vec<u8>> {
+    let live: Vec<u8> = (0..52u8).filter(|&c| !dead[c as usize]).collect();
+
+    let mut runouts: Vec<Vec<u8>> = Vec::new();
+    match 5usize.saturating_sub(board.len()) {
+        0 => runouts.push(Vec::new()),
+        1 => runouts.extend(live.iter().map(|&c| vec![c])),
+        _ => {
+            for i in 0..live.len() {
+                for j in (i + 1)..live.len() {
+                    runouts.push(vec![live[i], live[j]]);
+                }
+            }
+        }
+    }
+    runouts
+}
+
+/// Exact river equity vs a uniformly random opponent for every combo on a
+/// complete board. Every live hand is scored once and shared across combos;
+/// combos that overlap the board get `None`.
+fn river_equities(combos: &[(u8, u8)], board: &[u8; 5]) -> Vec<Option<f64>> {
+    let mut dead = [false; 52];
+    for &b in board {
+        dead[b as usize] = true;
+    }
+    let live: Vec<u8> = (0..52u8).filter(|&c| !dead[c as usize]).collect();
+
+    // score[a * 52 + b] for every live pair, plus the sorted list of all scores
+    let mut score = vec![0u32; 52 * 52];
+    let mut sorted = Vec::with_capacity(live.len() * (live.len() - 1) / 2);
+    for i in 0..live.len() {
+        for j in (i + 1)..live.len() {
+            let (a, b) = (live[i], live[j]);
+            let s = evaluate_seven(&[a, b, board[0], board[1], board[2], board[3], board[4]]);
+            score[a as usize * 52 + b as usize] = s;
+            score[b as usize * 52 + a as usize] = s;
+            sorted.push(s);
+        }
+    }
+    sorted.sort_unstable();
+
+    // Opponent hands that don't share a card with ours
+    let num_opp = ((live.len() - 2) * (live.len() - 3) / 2) as f64;
+
+    combos
+        .iter()
+        .map(|&(c0, c1)| {
+            if dead[c0 as usize] || dead[c1 as usize] {
+                return None;
+            }
+            let mine = score[c0 as usize * 52 + c1 as usize];
+            let below = sorted.partition_point(|&s| s < mine);
+            let tied = sorted.partition_point(|&s| s <= mine) - below;
+            let mut wins = below as f64 + 0.5 * tied as f64;
+
+            // Remove hands that share a card with ours (including ours itself)
+            let mut remove = |s: u32| {
+                if s < mine {
+                    wins -= 1.0;
+                } else if s == mine {
+                    wins -= 0.5;
+                }
+            };
+            remove(mine);
+            for &x in &live {
+                if x != c0 && x != c1 {
+                    remove(score[c0 as usize * 52 + x as usize]);
+                    remove(score[c1 as usize * 52 + x as usize]);
+                }
+            }
+            Some(wins / num_opp)
+        })
+        .collect()
+}
+
+/// Assign combos to potential-aware buckets via k-means on (mean, std-dev)
+/// of river equity.
+///
+/// Centroids start at equal-frequency positions in equity order, so with no
+/// spread this reduces to plain equity bucketing. Buckets are renumbered in
+/// order of increasing centroid equity and empty clusters are dropped.
+pub fn assign_buckets_ehs2(combos: &[(u8, u8)], board: &[u8], num_buckets: usize) -> Vec<u16> {
+    ehs2_buckets_with_equity(combos, board, num_buckets).0
+}
+
+fn ehs2_buckets_with_equity(
+    combos: &[(u8, u8)],
+    board: &[u8],
+    num_buckets: usize,
+) -> (Vec<u16>, Vec<f64>) {
+    let n = combos.len();
+    if n == 0 || num_buckets == 0 {
+        return (vec![0; n], vec![0.0; n]);
+    }
+
+    let key = BucketCacheKey {
+        board,
+        combos,
+        num_buckets,
+        num_samples: 0,
+        mode: BucketingMode::Ehs2,
+        seed: None,
+    };
+    cached_buckets(&key, || {
+        let points = equity_distributions(combos, board);
+        let means = points.iter().map(|p| p.0).collect();
+        (kmeans_buckets(&points, num_buckets), means)
+    })
+}
+
+/// k-means over (mean, std-dev) points; see `assign_buckets_ehs2`.
+fn kmeans_buckets(points: &[(f64, f64)], num_buckets: usize) -> Vec<u16> {
+    let n = points.len();
+    let k = num_buckets.min(n);
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| {
+        points[a]
+            .0
+            .partial_cmp(&points[b].0)
+            .unwrap()
+            .then(points[a].1.partial_cmp(&points[b].1).unwrap())
+    });
+    let mut centroids: Vec<(f64, f64)> =
+        (0..k).map(|c| points[order[(2 * c + 1) * n / (2 * k)]]).collect();
+
+    let mut assignment = vec![usize::MAX; n];
+    for _ in 0..100 {
+        let mut changed = false;
+        for (i, p) in points.iter().enumerate() {
+            let nearest = (0..k)
+                .min_by(|&a, &b| {
+                    dist_sq(*p, centroids[a])
+                        .partial_cmp(&dist_sq(*p, centroids[b]))
+                        .unwrap()
+                })
+                .unwrap();
+            if assignment[i] != nearest {
+                assignment[i] = nearest;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        let mut sums = vec![(0.0, 0.0, 0usize); k];
+        for (i, p) in points.iter().enumerate() {
+            let s = &mut sums[assignment[i]];
+            s.0 += p.0;
+            s.1 += p.1;
+            s.2 += 1;
+        }
+        for (c, s) in sums.iter().enumerate() {
+            if s.2 > 0 {
+                centroids[c] = (s.0 / s.2 as f64, s.1 / s.2 as f64);
+            }
+        }
+    }
+
+    // Compact to 0..m, ordered by centroid equity
+    let mut used: Vec<usize> = (0..k).filter(|c| assignment.contains(c)).collect();
+    used.sort_by(|&a, &b| centroids[a].0.partial_cmp(&centroids[b].0).unwrap());
+    let mut remap = vec![0u16; k];
+    for (new_id, &c) in used.iter().enumerate() {
+        remap[c] = new_id as u16;
+    }
+    assignment.iter().map(|&c| remap[c]).collect()
+}
+
+fn dist_sq(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)
+}
+
+// ---------------------------------------------------------------------------
+// Equity-histogram bucketing
+// ---------------------------------------------------------------------------
+
+/// Number of equal-width equity bins in `equity_histograms`.
+pub const EQUITY_HISTOGRAM_BINS: usize = 10;
+
+/// Distance between two equity histograms in `assign_buckets_kmeans`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HistogramDistance {
+    /// Squared Euclidean distance between bin masses.
+    Euclidean,
+    /// Earth mover's distance: how far (in bins) mass must move to turn one
+    /// histogram into the other. Unlike Euclidean, a hand whose outcomes
+    /// shift by one bin stays closer than one whose outcomes shift by five.
+    #[default]
+    EarthMovers,
+}
+
+impl HistogramDistance {
+    fn between(&self, a: &[f64], b: &[f64]) -> f64 {
+        match self {
+            HistogramDistance::Euclidean => a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum(),
+            HistogramDistance::EarthMovers => {
+                // 1-D EMD is the L1 distance between the cumulative histograms
+                let mut carried = 0.0f64;
+                let mut total = 0.0;
+                for (x, y) in a.iter().zip(b) {
+                    carried += x - y;
+                    total += carried.abs();
+                }
+                total
+            }
+        }
+    }
+}
+
+/// Histogram of each combo's river equity vs a random hand over every runout
+/// of `board`, as the fraction of runouts landing in each of `bins`
+/// equal-width equity bins. Combos with no runouts put all mass at 0.5.
+pub fn equity_histograms(combos: &[(u8, u8)], board: &[u8], bins: usize) -> Vec<Vec<f64>> {
+    runout_histograms(combos, board, bins).0
+}
+
+/// `equity_histograms` plus each combo's mean equity over the same runouts.
+fn runout_histograms(combos: &[(u8, u8)], board: &[u8], bins: usize) -> (Vec<Vec<f64>>, Vec<f64>) {
+    let bin_of = |eq: f64| ((eq * bins as f64) as usize).min(bins - 1);
+    let mut histograms = vec![vec![0.0f64; bins]; combos.len()];
+    let mut sum = vec![0.0f64; combos.len()];
+    let mut count = vec![0usize; combos.len()];
+
+    for_each_runout_equity(combos, board, |i, eq| {
+        histograms[i][bin_of(eq)] += 1.0;
+        sum[i] += eq;
+        count[i] += 1;
+    });
+
+    let mut means = vec![0.5; combos.len()];
+    for (i, hist) in histograms.iter_mut().enumerate() {
+        if count[i] == 0 {
+            hist[bin_of(0.5)] = 1.0;
+            continue;
+        }
+        let n = count[i] as f64;
+        hist.iter_mut().for_each(|m| *m /= n);
+        means[i] = sum[i] / n;
+    }
+    (histograms, means)
+}
+
+/// Assign combos to buckets via k-means on their equity histograms (see
+/// `equity_histograms`) under `distance`.
+///
+/// A made hand and a draw with the same average equity have very different
+/// histograms, so they are kept apart where equal-frequency binning would
+/// merge them. Centroids start at equal-frequency positions in mean-equity
+/// order; buckets are renumbered by increasing mean equity and empty
+/// clusters are dropped.
+pub fn assign_buckets_kmeans(
+    combos: &[(u8, u8)],
+    board: &[u8],
+    num_buckets: usize,
+    distance: HistogramDistance,
+) -> Vec<u16> {
+    let n = combos.len();
+    if n == 0 || num_buckets == 0 {
+        return vec![0; n];
+    }
+    let (histograms, means) = runout_histograms(combos, board, EQUITY_HISTOGRAM_BINS);
+    kmeans_histogram_buckets(&histograms, &means, num_buckets, distance)
+}
+
+fn histogram_buckets_with_equity(
+    combos: &[(u8, u8)],
+    board: &[u8],
+    num_buckets: usize,
+) -> (Vec<u16>, Vec<f64>) {
+    let n = combos.len();
+    if n == 0 || num_buckets == 0 {
+        return (vec![0; n], vec![0.0; n]);
+    }
+
+    let key = BucketCacheKey {
+        board,
+        combos,
+        num_buckets,
+        num_samples: 0,
+        mode: BucketingMode::Histogram,
+        seed: None,
+    };
+    cached_buckets(&key, || {
+        let (histograms, means) = runout_histograms(combos, board, EQUITY_HISTOGRAM_BINS);
+        let buckets = kmeans_histogram_buckets(
+            &histograms,
+            &means,
+            num_buckets,
+            HistogramDistance::EarthMovers,
+        );
+        (buckets, means)
+    })
+}
+
+/// k-means over histogram points; see `assign_buckets_kmeans`.
+fn kmeans_histogram_buckets(
+    points: &[Vec<f64>],
+    means: &[f64],
+    num_buckets: usize,
+    distance: HistogramDistance,
+) -> Vec<u16> {
+    let n = points.len();
+    let k = num_buckets.min(n);
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| means[a].total_cmp(&means[b]));
+    let mut centroids: Vec<Vec<f64>> =
+        (0..k).map(|c| points[order[(2 * c + 1) * n / (2 * k)]].clone()).collect();
+
+    let mut assignment = vec![usize::MAX; n];
+    for _ in 0..100 {
+        let mut changed = false;
+        for (i, p) in points.iter().enumerate() {
+            let nearest = (0..k)
+                .min_by(|&a, &b| {
+                    distance
+                        .between(p, &centroids[a])
+                        .total_cmp(&distance.between(p, &centroids[b]))
+                })
+                .unwrap();
+            if assignment[i] != nearest {
+                assignment[i] = nearest;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        let mut sums = vec![(vec![0.0f64; points[0].len()], 0usize); k];
+        for (i, p) in points.iter().enumerate() {
+            let s = &mut sums[assignment[i]];
+            s.0.iter_mut().zip(p).for_each(|(acc, x)| *acc += x);
+            s.1 += 1;
+        }
+        for (c, (sum, count)) in sums.into_iter().enumerate() {
+            if count > 0 {
+                centroids[c] = sum.into_iter().map(|x| x / count as f64).collect();
+            }
+        }
+    }
+
+    // Compact to 0..m, ordered by the clusters' mean equity
+    let mut equity = vec![(0.0f64, 0usize); k];
+    for (i, &c) in assignment.iter().enumerate() {
+        equity[c].0 += means[i];
+        equity[c].1 += 1;
+    }
+    let mut used: Vec<usize> = (0..k).filter(|&c| equity[c].1 > 0).collect();
+    used.sort_by(|&a, &b| {
+        (equity[a].0 / equity[a].1 as f64).total_cmp(&(equity[b].0 / equity[b].1 as f64))
+    });
+    let mut remap = vec![0u16; k];
+    for (new_id, &c) in used.iter().enumerate() {
+        remap[c] = new_id as u16;
+    }
+    assignment.iter().map(|&c| remap[c]).collect()
+}
+
+// ---------------------------------------------------------------------------
+// Draw-aware bucketing
+// ---------------------------------------------------------------------------
+
+/// Hand-class features of a combo on a flop or turn board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DrawFeatures {
+    /// Four to a flush, at least one of them a hole card.
+    pub flush_draw: bool,
+    /// Four ranks in a row that either end completes.
+    pub open_ended: bool,
+    /// Exactly one rank completes a straight.
+    pub gutshot: bool,
+    /// Two or more ranks complete a straight without four in a row.
+    pub double_gutshot: bool,
+    /// A pocket pair or a hole card pairing the board.
+    pub pair: bool,
+}
+
+impl DrawFeatures {
+    /// Number of distinct feature classes.
+    pub const NUM_CLASSES: usize = 12;
+
+    /// Feature class in `0..NUM_CLASSES`: flush draw × straight draw × pair.
+    /// A double gutshot has an open-ender's outs, so it shares its class.
+    pub fn class(&self) -> usize {
+        let straight = if self.open_ended || self.double_gutshot {
+            2
+        } else if self.gutshot {
+            1
+        } else {
+            0
+        };
+        (self.flush_draw as usize * 3 + straight) * 2 + self.pair as usize
+    }
+
+    pub fn is_draw(&self) -> bool {
+        self.flush_draw || self.open_ended || self.gutshot || self.double_gutshot
+    }
+}
+
+/// Draws (from `draws::detect_draws`) and pairs for hole cards (c0, c1).
+/// On a complete board there is nothing left to draw to.
+pub fn combo_features(c0: u8, c1: u8, board: &[u8]) -> DrawFeatures {
+    let rank = |c: u8| c / 4;
+    let pair = rank(c0) == rank(c1)
+        || board
+            .iter()
+            .any(|&b| rank(b) == rank(c0) || rank(b) == rank(c1));
+    let draws = detect_draws(&[c0, c1], board);
+    DrawFeatures {
+        flush_draw: draws.flush_draw,
+        open_ended: draws.open_ended,
+        gutshot: draws.gutshot,
+        double_gutshot: draws.double_gutshot,
+        pair,
+    }
+}
+
+/// Partition combos by `DrawFeatures::class`, then equity-bin within each
+/// class. Every class present gets at least one bucket and the rest are
+/// shared out by class size, so a flush draw never shares a bucket with a
+/// non-draw of the same equity. Needs at least one bucket per class present;
+/// with fewer, the surplus classes share the last bucket.
+fn draw_buckets_with_equity(
+    combos: &[(u8, u8)],
+    board: &[u8],
+    num_buckets: usize,
+    num_samples: usize,
+    seed: Option<u64>,
+) -> (Vec<u16>, Vec<f64>) {
+    let n = combos.len();
+    if n == 0 || num_buckets == 0 {
+        return (vec![0; n], vec![0.0; n]);
+    }
+
+    let key = BucketCacheKey {
+        board,
+        combos,
+        num_buckets,
+        num_samples,
+        mode: BucketingMode::Draws,
+        seed,
+    };
+    cached_buckets(&key, || {
+        let (_, equities) = ehs_buckets_with_equity(combos, board, 1, num_samples, seed);
+
+        let mut classes: Vec<Vec<usize>> = vec![Vec::new(); DrawFeatures::NUM_CLASSES];
+        for (h, &(c0, c1)) in combos.iter().enumerate() {
+            classes[combo_features(c0, c1, board).class()].push(h);
+        }
+        classes.retain(|members| !members.is_empty());
+
+        let spare = num_buckets.saturating_sub(classes.len());
+        let mut buckets = vec![0u16; n];
+        let mut offset = 0;
+        for members in &classes {
+            let alloc = (1 + spare * members.len() / n).min(members.len());
+            let class_equities: Vec<f64> = members.iter().map(|&h| equities[h]).collect();
+            let class_combos: Vec<(u8, u8)> = members.iter().map(|&h| combos[h]).collect();
+            let class_buckets = equal_frequency_buckets(&class_equities, &class_combos, alloc);
+            for (&h, b) in members.iter().zip(class_buckets) {
+                buckets[h] = (offset + b as usize).min(num_buckets - 1) as u16;
+            }
+            offset += alloc;
+        }
+        (buckets, equities)
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Bucket cache
+// ---------------------------------------------------------------------------
+
+static BUCKET_CACHE_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn the on-disk bucket cache on or off for this process. It is off by
+/// default so library callers and tests never touch the home directory.
+pub fn set_bucket_cache_enabled(enabled: bool) {
+    BUCKET_CACHE_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Everything that determines a bucket assignment.
+pub struct BucketCacheKey<'a> {
+    pub board: &'a [u8],
+    pub combos: &'a [(u8, u8)],
+    pub num_buckets: usize,
+    pub num_samples: usize,
+    pub mode: BucketingMode,
+    /// Only set when Monte Carlo sampling used a deterministic seed.
+    pub seed: Option<u64>,
+}
+
+impl BucketCacheKey<'_> {
+    /// File name for this key. The combo set is reduced to a stable FNV-1a hash.
+    pub fn file_name(&self) -> String {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for &(c0, c1) in self.combos {
+            for b in [c0, c1] {
+                hash ^= b as u64;
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+        let board: String = self.board.iter().map(|&c| index_to_card(c).to_string()).collect();
+        let seed = self.seed.map_or(String::new(), |s| format!("_s{}", s));
+        format!(
+            "{}_{}_{}b_{}s_{:016x}{}.bin",
+            board,
+            self.mode.as_str(),
+            self.num_buckets,
+            self.num_samples,
+            hash,
+            seed
+        )
+    }
+}
+
+/// A cached assignment plus the equities it was built from.
+#[derive(Serialize, Deserialize)]
+struct BucketCacheEntry {
+    buckets: Vec<u16>,
+    equities: Vec<f64>,
+}
+
+fn bucket_cache_dir() -> std::path::PathBuf {
+    crate::checkpoint::gto_home().join("buckets")
+}
+
+/// Load the assignment for `key` from disk, or compute and store it.
+///
+/// River boards use exact equity and are cheap, so they bypass the cache.
+fn cached_buckets<F>(key: &BucketCacheKey, compute: F) -> (Vec<u16>, Vec<f64>)
+where
+    F: FnOnce() -> (Vec<u16>, Vec<f64>),
+{
+    if !BUCKET_CACHE_ENABLED.load(Ordering::Relaxed) || key.board.len() >= 5 {
+        return compute();
+    }
+
+    let path = bucket_cache_dir().join(key.file_name());
+    if let Some(entry) = std::fs::read(&path)
+        .ok()
+        .and_then(|data| bincode::deserialize::<BucketCacheEntry>(&data).ok())
+    {
+        if entry.buckets.len() == key.combos.len() && entry.equities.len() == key.combos.len() {
+            return (entry.buckets, entry.equities);
+        }
+    }
+
+    let (buckets, equities) = compute();
+    let entry = BucketCacheEntry { buckets, equities };
+    if let Ok(data) = bincode::serialize(&entry) {
+        std::fs::create_dir_all(bucket_cache_dir()).ok();
+        std::fs::write(&path, data).ok();
+    }
+    (entry.buckets, entry.equities)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,4 +1102,271 @@ mod tests {
         let buckets = assign_buckets(&combos, &board, 10, 0);
         assert!(buckets.is_empty());
     }
+
+    #[test]
+    fn ehs2_separates_draw_from_weak_pair() {
+        let board = board_indices("Kh9h4c");
+        let card = |s: &str| card_to_index(&crate::cards::parse_card(s).unwrap());
+        let draw = (card("8h"), card("7h"));
+        let pair = (card("4d"), card("3s"));
+
+        // Same average equity, very different spread
+        let dist = equity_distributions(&[draw, pair], &board);
+        assert!((dist[0].0 - dist[1].0).abs() < 0.02, "means differ: {:?}", dist);
+        assert!(dist[0].1 > dist[1].1 + 0.1, "draw should be more volatile: {:?}", dist);
+
+        let mut combos = Vec::new();
+        for a in 0..52u8 {
+            for b in (a + 1)..52u8 {
+                if !board.contains(&a) && !board.contains(&b) {
+                    combos.push((a, b));
+                }
+            }
+        }
+        let idx = |c: (u8, u8)| {
+            let key = (c.0.min(c.1), c.0.max(c.1));
+            combos.iter().position(|&x| x == key).unwrap()
+        };
+        let buckets = bucket_combos(BucketingMode::Ehs2, &combos, &board, 20, 0, None);
+        assert_ne!(buckets[idx(draw)], buckets[idx(pair)]);
+
+        // Bucket ids are contiguous
+        let distinct: std::collections::HashSet<u16> = buckets.iter().copied().collect();
+        assert_eq!(*buckets.iter().max().unwrap() as usize + 1, distinct.len());
+    }
+
+    #[test]
+    fn kmeans_histograms_separate_draw_from_made_hand() {
+        let board = board_indices("Kh9h4c");
+        let card = |s: &str| card_to_index(&crate::cards::parse_card(s).unwrap());
+        let draw = (card("8h"), card("7h"));
+        let pair = (card("4d"), card("3s"));
+
+        // Equal raw equity, but the draw's mass sits at both ends
+        let hist = equity_histograms(&[draw, pair], &board, EQUITY_HISTOGRAM_BINS);
+        for h in &hist {
+            assert!((h.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+        let tails = |h: &[f64]| h[0] + h[EQUITY_HISTOGRAM_BINS - 1];
+        assert!(tails(&hist[0]) > tails(&hist[1]) + 0.2, "{:?}", hist);
+
+        let mut combos = Vec::new();
+        for a in 0..52u8 {
+            for b in (a + 1)..52u8 {
+                if !board.contains(&a) && !board.contains(&b) {
+                    combos.push((a, b));
+                }
+            }
+        }
+        let idx = |c: (u8, u8)| {
+            let key = (c.0.min(c.1), c.0.max(c.1));
+            combos.iter().position(|&x| x == key).unwrap()
+        };
+        for distance in [HistogramDistance::EarthMovers, HistogramDistance::Euclidean] {
+            let buckets = assign_buckets_kmeans(&combos, &board, 20, distance);
+            assert_eq!(buckets.len(), combos.len());
+            assert_ne!(buckets[idx(draw)], buckets[idx(pair)], "{:?}", distance);
+
+            let distinct: std::collections::HashSet<u16> = buckets.iter().copied().collect();
+            assert_eq!(*buckets.iter().max().unwrap() as usize + 1, distinct.len());
+        }
+
+        // ...despite the same raw equity a scalar bin would merge them on
+        let equities = equity_distributions(&[draw, pair], &board);
+        assert!((equities[0].0 - equities[1].0).abs() < 0.02);
+    }
+
+    #[test]
+    fn earth_movers_distance_grows_with_shift() {
+        let at = |bin: usize| {
+            let mut h = vec![0.0; EQUITY_HISTOGRAM_BINS];
+            h[bin] = 1.0;
+            h
+        };
+        let emd = HistogramDistance::EarthMovers;
+        assert_eq!(emd.between(&at(3), &at(3)), 0.0);
+        assert!((emd.between(&at(2), &at(3)) - 1.0).abs() < 1e-12);
+        assert!((emd.between(&at(2), &at(7)) - 5.0).abs() < 1e-12);
+        // Euclidean can't tell a small shift from a large one
+        let l2 = HistogramDistance::Euclidean;
+        assert_eq!(l2.between(&at(2), &at(3)), l2.between(&at(2), &at(7)));
+    }
+
+    #[test]
+    fn ehs2_exact_equity_matches_exhaustive() {
+        let board = board_indices("2s3h4d5c8h");
+        let card = |s: &str| card_to_index(&crate::cards::parse_card(s).unwrap());
+        let combos = vec![
+            (card("As"), card("Ac")),
+            (card("7h"), card("6h")),
+            (card("Kd"), card("2c")),
+        ];
+        let dist = equity_distributions(&combos, &board);
+        for (&(c0, c1), &(mean, std)) in combos.iter().zip(&dist) {
+            let exact = combo_equity_vs_random(c0, c1, &board, 0);
+            assert!((mean - exact).abs() < 1e-9, "{} vs {}", mean, exact);
+            assert_eq!(std, 0.0);
+        }
+    }
+
+    #[test]
+    fn seeded_assignment_is_reproducible() {
+        let board = board_indices("Kh9h4c");
+        let card = |s: &str| card_to_index(&crate::cards::parse_card(s).unwrap());
+        let combos = vec![
+            (card("8h"), card("7h")),
+            (card("Ad"), card("Kd")),
+            (card("4d"), card("3s")),
+            (card("Qs"), card("Jc")),
+        ];
+        let a = assign_buckets_seeded(&combos, &board, 2, 200, Some(7));
+        let b = assign_buckets_seeded(&combos, &board, 2, 200, Some(7));
+        assert_eq!(a, b);
+
+        // Few samples leave many combos on equal equities; ties must still
+        // split across bucket boundaries the same way every run
+        let all: Vec<(u8, u8)> = (0..52u8)
+            .flat_map(|a| ((a + 1)..52).map(move |b| (a, b)))
+            .filter(|(a, b)| !board.contains(a) && !board.contains(b))
+            .collect();
+        let a = assign_buckets_seeded(&all, &board, 30, 8, Some(11));
+        let b = assign_buckets_seeded(&all, &board, 30, 8, Some(11));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn tied_equities_bucket_by_cards_not_input_order() {
+        let combos = [(0, 1), (2, 3), (4, 5), (6, 7)];
+        let equities = [0.5; 4];
+        let buckets = equal_frequency_buckets(&equities, &combos, 2);
+        assert_eq!(buckets, vec![0, 0, 1, 1]);
+
+        let reversed: Vec<(u8, u8)> = combos.iter().rev().copied().collect();
+        let reversed_buckets = equal_frequency_buckets(&equities, &reversed, 2);
+        assert_eq!(reversed_buckets, vec![1, 1, 0, 0], "each combo keeps its bucket");
+    }
+
+    #[test]
+    fn parallel_equities_match_serial() {
+        let board = board_indices("Kh9h4c");
+        let mut combos = Vec::new();
+        for a in 0..52u8 {
+            for b in (a + 1)..52u8 {
+                if !board.contains(&a) && !board.contains(&b) {
+                    combos.push((a, b));
+                }
+            }
+        }
+
+        let serial = combo_equities(&combos, &board, 200, Some(42), false);
+        let parallel = combo_equities(&combos, &board, 200, Some(42), true);
+        assert_eq!(serial, parallel);
+        let buckets = assign_buckets_seeded(&combos, &board, 50, 200, Some(42));
+        assert_eq!(buckets, equal_frequency_buckets(&serial, &combos, 50));
+    }
+
+    #[test]
+    fn cache_key_distinguishes_seed_and_mode() {
+        let board = board_indices("Kh9h4c");
+        let card = |s: &str| card_to_index(&crate::cards::parse_card(s).unwrap());
+        let combos = vec![(card("8h"), card("7h")), (card("Ad"), card("Kd"))];
+        let key = |mode, seed| BucketCacheKey {
+            board: &board,
+            combos: &combos,
+            num_buckets: 10,
+            num_samples: 500,
+            mode,
+            seed,
+        };
+        let unseeded = key(BucketingMode::Ehs, None).file_name();
+        assert!(unseeded.starts_with("Kh9h4c_ehs_"));
+        assert_ne!(unseeded, key(BucketingMode::Ehs, Some(1)).file_name());
+        assert_ne!(unseeded, key(BucketingMode::Ehs2, None).file_name());
+        assert_ne!(
+            key(BucketingMode::Ehs, Some(1)).file_name(),
+            key(BucketingMode::Ehs, Some(2)).file_name()
+        );
+    }
+
+    #[test]
+    fn bucket_members_groups_and_averages() {
+        let buckets = [1u16, 0, 1, 0];
+        let equities = [0.7, 0.2, 0.9, 0.4];
+        let members = bucket_members(&buckets, &equities);
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].0, vec![3, 1]);
+        assert!((members[0].1 - 0.3).abs() < 1e-12);
+        assert_eq!(members[1].0, vec![2, 0]);
+        assert!((members[1].1 - 0.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn combo_features_detect_draws() {
+        let card = |s: &str| card_to_index(&crate::cards::parse_card(s).unwrap());
+        let f = combo_features(card("Ah"), card("5h"), &board_indices("KhTh2c"));
+        assert!(f.flush_draw && !f.open_ended && !f.gutshot && !f.pair, "{:?}", f);
+
+        let f = combo_features(card("Ad"), card("5c"), &board_indices("KhTh2c"));
+        assert!(!f.is_draw(), "{:?}", f);
+
+        let f = combo_features(card("9h"), card("8c"), &board_indices("Th7d2s"));
+        assert!(f.open_ended && !f.gutshot, "{:?}", f);
+
+        let f = combo_features(card("9h"), card("7c"), &board_indices("Th6d2s"));
+        assert!(f.gutshot && !f.open_ended, "{:?}", f);
+
+        // Wheel draw plays the ace low; a made straight is not a draw
+        let f = combo_features(card("Ac"), card("3d"), &board_indices("4h5s9c"));
+        assert!(f.gutshot, "{:?}", f);
+        let f = combo_features(card("6c"), card("3d"), &board_indices("4h5s7c"));
+        assert!(!f.is_draw(), "{:?}", f);
+
+        // Pair plus draw
+        let f = combo_features(card("Kc"), card("Qh"), &board_indices("KhTh9h"));
+        assert!(f.flush_draw && f.gutshot && f.pair, "{:?}", f);
+    }
+
+    #[test]
+    fn combo_features_keep_double_gutshots_apart_from_open_enders() {
+        let card = |s: &str| card_to_index(&crate::cards::parse_card(s).unwrap());
+        // Either a ten or a six completes 9-7 on J-8-5, but not four in a row
+        let f = combo_features(card("9s"), card("7d"), &board_indices("Jc8h5s"));
+        assert!(f.double_gutshot && !f.open_ended && !f.gutshot, "{:?}", f);
+        assert!(f.is_draw());
+
+        let oesd = combo_features(card("9h"), card("8c"), &board_indices("Th7d2s"));
+        assert_eq!(f.class(), oesd.class());
+    }
+
+    #[test]
+    fn draw_buckets_keep_flush_draws_apart() {
+        let board = board_indices("KhTh2c");
+        let card = |s: &str| card_to_index(&crate::cards::parse_card(s).unwrap());
+        let mut combos = Vec::new();
+        for a in 0..52u8 {
+            for b in (a + 1)..52u8 {
+                if !board.contains(&a) && !board.contains(&b) {
+                    combos.push((a, b));
+                }
+            }
+        }
+        let buckets = bucket_combos(BucketingMode::Draws, &combos, &board, 50, 100, None);
+        assert!(buckets.iter().all(|&b| b < 50));
+
+        let idx = |a: &str, b: &str| {
+            let (a, b) = (card(a), card(b));
+            combos.iter().position(|&x| x == (a.min(b), a.max(b))).unwrap()
+        };
+        let suited = buckets[idx("Ah", "5h")];
+        for (a, b) in [("Ad", "5c"), ("Ac", "5d"), ("As", "5h"), ("Ah", "5s")] {
+            assert_ne!(suited, buckets[idx(a, b)], "Ah5h shares a bucket with {}{}", a, b);
+        }
+
+        // No bucket mixes flush draws with anything else
+        let mut flush_draw_in_bucket = [None; 50];
+        for (&(c0, c1), &b) in combos.iter().zip(&buckets) {
+            let fd = combo_features(c0, c1, &board).flush_draw;
+            assert_eq!(*flush_draw_in_bucket[b as usize].get_or_insert(fd), fd);
+        }
+    }
 }
@@ -34,6 +34,22 @@ pub fn remaining_deck(dead: &[u8]) -> Vec<u8> {
     (0..52u8).filter(|&c| !dead_set[c as usize]).collect()
 }
 
+/// Rank offset of the Six, the lowest rank left in a short (6+) deck.
+pub const SHORT_DECK_LOWEST_RANK: u8 = 4;
+
+/// Whether a card index survives in a short deck (no 2-5).
+pub fn is_short_deck_card(index: u8) -> bool {
+    index / 4 >= SHORT_DECK_LOWEST_RANK
+}
+
+/// Build a 36-card short deck excluding the given dead cards.
+pub fn remaining_short_deck(dead: &[u8]) -> Vec<u8> {
+    remaining_deck(dead)
+        .into_iter()
+        .filter(|&c| is_short_deck_card(c))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,4 +81,13 @@ mod tests {
         assert!(!deck.contains(&3));
         assert!(deck.contains(&4));
     }
+
+    #[test]
+    fn short_deck_starts_at_six() {
+        let deck = remaining_short_deck(&[]);
+        assert_eq!(deck.len(), 36);
+        assert_eq!(index_to_card(deck[0]), Card::new(Rank::Six, Suit::Spades));
+        assert!(deck.iter().all(|&c| index_to_card(c).rank >= Rank::Six));
+        assert_eq!(remaining_short_deck(&[deck[0], 0]).len(), 35);
+    }
 }
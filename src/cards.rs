@@ -304,6 +304,9 @@ pub fn hand_combos(notation: &str) -> GtoResult<Vec<(Card, Card)>> {
     if chars.len() == 4 {
         let c1 = parse_card(&notation[..2])?;
         let c2 = parse_card(&notation[2..])?;
+        if c1 == c2 {
+            return Err(GtoError::InvalidHandNotation(notation.to_string()));
+        }
         return Ok(vec![(c1, c2)]);
     }
 
@@ -2,108 +2,506 @@
 //!
 //! Each information set tracks cumulative regret per action and cumulative
 //! strategy weights. The average strategy over all iterations converges to
-//! a Nash equilibrium.
+//! a Nash equilibrium. Discounted and linear CFR are available through
+//! `CfrVariant`; `SolverAlgorithm` picks full or sampled traversals.
 
 use std::collections::HashMap;
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Div, Mul, Sub};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::checkpoint::{self, CheckpointHeader};
+
+/// Floating-point type used to store regrets and strategy sums.
+///
+/// `f64` is the default; `f32` halves memory for large solves. Traversal
+/// utilities stay in `f64` and are converted at the info-set boundary.
+pub trait CfrFloat:
+    Copy
+    + Debug
+    + Default
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + AddAssign
+    + Send
+    + Sync
+    + Serialize
+    + DeserializeOwned
+    + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+    fn from_f64(v: f64) -> Self;
+    fn to_f64(self) -> f64;
+    fn from_usize(v: usize) -> Self;
+}
+
+impl CfrFloat for f64 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+    #[inline]
+    fn from_f64(v: f64) -> Self {
+        v
+    }
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self
+    }
+    #[inline]
+    fn from_usize(v: usize) -> Self {
+        v as f64
+    }
+}
+
+impl CfrFloat for f32 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+    #[inline]
+    fn from_f64(v: f64) -> Self {
+        v as f32
+    }
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    #[inline]
+    fn from_usize(v: usize) -> Self {
+        v as f32
+    }
+}
+
+#[inline]
+fn floor_zero<F: CfrFloat>(v: F) -> F {
+    if v > F::ZERO {
+        v
+    } else {
+        F::ZERO
+    }
+}
+
+/// How cumulative regrets and strategy sums are carried between iterations.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum CfrVariant {
+    /// Regrets floored at zero, no discounting.
+    #[default]
+    CfrPlus,
+    /// Discounted CFR: before iteration `t` adds its values, positive
+    /// regrets are scaled by `(t-1)^alpha / ((t-1)^alpha + 1)`, negative ones
+    /// by the same with `beta`, and strategy sums by `((t-1)/t)^gamma`.
+    Dcfr { alpha: f64, beta: f64, gamma: f64 },
+    /// Iteration `t` weighted by `t` in both regrets and strategy sums, the
+    /// same as DCFR with all three parameters at 1.
+    LinearCfr,
+}
+
+impl CfrVariant {
+    /// DCFR with the usual parameters: alpha = 1.5, beta = 0, gamma = 2.
+    pub const DCFR: CfrVariant = CfrVariant::Dcfr { alpha: 1.5, beta: 0.0, gamma: 2.0 };
+
+    /// Multipliers for positive regrets, negative regrets and strategy sums
+    /// before iteration `t` (1-based) is added. CFR+ discounts nothing.
+    fn discounts(self, t: usize) -> (f64, f64, f64) {
+        let (alpha, beta, gamma) = match self {
+            CfrVariant::CfrPlus => return (1.0, 1.0, 1.0),
+            CfrVariant::Dcfr { alpha, beta, gamma } => (alpha, beta, gamma),
+            CfrVariant::LinearCfr => (1.0, 1.0, 1.0),
+        };
+        if t <= 1 {
+            return (1.0, 1.0, 1.0);
+        }
+        let prev = (t - 1) as f64;
+        let scale = |exp: f64| {
+            let w = prev.powf(exp);
+            w / (w + 1.0)
+        };
+        (scale(alpha), scale(beta), (prev / t as f64).powf(gamma))
+    }
+}
+
+impl std::fmt::Display for CfrVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CfrVariant::CfrPlus => write!(f, "cfr+"),
+            CfrVariant::Dcfr { alpha, beta, gamma } => {
+                write!(f, "dcfr (alpha {}, beta {}, gamma {})", alpha, beta, gamma)
+            }
+            CfrVariant::LinearCfr => write!(f, "linear"),
+        }
+    }
+}
+
+impl std::str::FromStr for CfrVariant {
+    type Err = String;
+
+    /// Parse "cfr+", "dcfr" (standard parameters) or "linear".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "cfr+" | "cfrplus" | "cfr-plus" => Ok(CfrVariant::CfrPlus),
+            "dcfr" => Ok(CfrVariant::DCFR),
+            "linear" | "lcfr" => Ok(CfrVariant::LinearCfr),
+            _ => Err(format!("Unknown CFR variant '{}' (expected cfr+, dcfr or linear)", s)),
+        }
+    }
+}
+
+/// Which traversal a solver runs each iteration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SolverAlgorithm {
+    /// Every traverser hand against the opponent's whole range, every card.
+    #[default]
+    Cfr,
+    /// External-sampling Monte Carlo CFR: each traverser hand is dealt one
+    /// opponent hand, and opponent and chance nodes follow one sampled
+    /// action or card. Traverser nodes still explore every action.
+    Mccfr,
+}
+
+impl std::fmt::Display for SolverAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolverAlgorithm::Cfr => write!(f, "cfr"),
+            SolverAlgorithm::Mccfr => write!(f, "mccfr"),
+        }
+    }
+}
+
+impl std::str::FromStr for SolverAlgorithm {
+    type Err = String;
+
+    /// Parse "cfr" or "mccfr".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "cfr" => Ok(SolverAlgorithm::Cfr),
+            "mccfr" => Ok(SolverAlgorithm::Mccfr),
+            _ => Err(format!("Unknown algorithm '{}' (expected cfr or mccfr)", s)),
+        }
+    }
+}
+
+/// Storage type for regrets and strategy sums, chosen at runtime and
+/// mapped onto a `CfrFloat` type parameter by the solvers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Precision {
+    /// Half the memory; sums are still accumulated in f64 and rounded on store.
+    F32,
+    #[default]
+    F64,
+}
+
+impl Precision {
+    /// Bytes per stored regret or strategy-sum value.
+    pub fn bytes(self) -> usize {
+        match self {
+            Precision::F32 => 4,
+            Precision::F64 => 8,
+        }
+    }
+}
+
+impl std::fmt::Display for Precision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Precision::F32 => write!(f, "f32"),
+            Precision::F64 => write!(f, "f64"),
+        }
+    }
+}
+
+impl std::str::FromStr for Precision {
+    type Err = String;
+
+    /// Parse "f32" or "f64".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "f32" | "32" => Ok(Precision::F32),
+            "f64" | "64" => Ok(Precision::F64),
+            _ => Err(format!("Unknown precision '{}' (expected f32 or f64)", s)),
+        }
+    }
+}
+
+/// RNG driving sampled traversals and pruning draws. The same generator as
+/// `StdRng`, but serializable so checkpoints carry its position in the stream.
+pub type SolverRng = ChaCha12Rng;
+
+/// RNG for a solve: reproducible with a seed, from entropy without one.
+pub fn solver_rng(seed: Option<u64>) -> SolverRng {
+    match seed {
+        Some(seed) => SolverRng::seed_from_u64(seed),
+        None => SolverRng::from_entropy(),
+    }
+}
+
+/// Draw an index with probability proportional to `weights`. Falls back to
+/// the last index if rounding leaves the draw past the total.
+pub fn sample_index<R: Rng>(weights: &[f64], rng: &mut R) -> usize {
+    let total: f64 = weights.iter().sum();
+    let mut draw = rng.gen::<f64>() * total;
+    for (i, &w) in weights.iter().enumerate() {
+        if draw < w {
+            return i;
+        }
+        draw -= w;
+    }
+    weights.len() - 1
+}
 
 /// One information set's accumulated data.
-#[derive(Debug, Clone)]
-pub struct InfoSetData {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct InfoSetData<F: CfrFloat = f64> {
     /// Number of actions available at this information set.
     pub num_actions: usize,
-    /// Cumulative regret for each action (floored to 0 in CFR+).
-    pub cumulative_regret: Vec<f64>,
+    /// Cumulative regret for each action (floored to 0 in CFR+, discounted in
+    /// DCFR and linear CFR).
+    pub cumulative_regret: Vec<F>,
     /// Cumulative strategy weight for each action (for computing average strategy).
-    pub cumulative_strategy: Vec<f64>,
+    pub cumulative_strategy: Vec<F>,
+    /// Last iteration at which each action's cumulative regret was positive
+    /// (for regret-based pruning).
+    pub last_positive: Vec<u32>,
 }
 
-impl InfoSetData {
+impl<F: CfrFloat> InfoSetData<F> {
+    /// Heap bytes behind this info set's arrays, at their allocated capacity.
+    pub fn heap_bytes(&self) -> usize {
+        (self.cumulative_regret.capacity() + self.cumulative_strategy.capacity()) * std::mem::size_of::<F>()
+            + self.last_positive.capacity() * std::mem::size_of::<u32>()
+    }
+
     pub fn new(num_actions: usize) -> Self {
         InfoSetData {
             num_actions,
-            cumulative_regret: vec![0.0; num_actions],
-            cumulative_strategy: vec![0.0; num_actions],
+            cumulative_regret: vec![F::ZERO; num_actions],
+            cumulative_strategy: vec![F::ZERO; num_actions],
+            last_positive: vec![0; num_actions],
         }
     }
 
     /// Current strategy via regret matching: proportional to positive regrets.
     /// If all regrets are non-positive, returns uniform distribution.
     pub fn current_strategy(&self) -> Vec<f64> {
-        let positive_sum: f64 = self
-            .cumulative_regret
-            .iter()
-            .map(|&r| r.max(0.0))
-            .sum();
-
-        if positive_sum > 0.0 {
-            self.cumulative_regret
-                .iter()
-                .map(|&r| r.max(0.0) / positive_sum)
-                .collect()
-        } else {
-            vec![1.0 / self.num_actions as f64; self.num_actions]
-        }
+        regret_matching(&self.cumulative_regret)
     }
 
     /// Average strategy over all iterations — this is the actual Nash
     /// equilibrium approximation.
     pub fn average_strategy(&self) -> Vec<f64> {
-        let total: f64 = self.cumulative_strategy.iter().sum();
-        if total > 0.0 {
-            self.cumulative_strategy.iter().map(|&s| s / total).collect()
-        } else {
-            vec![1.0 / self.num_actions as f64; self.num_actions]
-        }
+        normalized_sums(&self.cumulative_strategy)
     }
 
     /// Update regrets and strategy weights after one traversal.
     /// `action_utilities`: the counterfactual value of each action.
     /// `reach_prob`: the probability of reaching this info set (for strategy weighting).
-    pub fn update(&mut self, action_utilities: &[f64], node_utility: f64, reach_prob: f64) {
-        let strategy = self.current_strategy();
+    /// `iteration`: 1-based count of updates to this info set, which sets
+    /// how much `variant` discounts the sums accumulated so far.
+    pub fn update(
+        &mut self,
+        action_utilities: &[f64],
+        node_utility: f64,
+        reach_prob: f64,
+        variant: CfrVariant,
+        iteration: usize,
+    ) {
+        update_sums(
+            &mut self.cumulative_regret,
+            &mut self.cumulative_strategy,
+            action_utilities,
+            node_utility,
+            reach_prob,
+            variant,
+            iteration,
+        );
+    }
 
+    /// Record `iteration` as the last time each positive-regret action was live.
+    pub fn mark_positive(&mut self, iteration: usize) {
         for a in 0..self.num_actions {
-            // Regret = "how much better action a would have been"
-            let regret = action_utilities[a] - node_utility;
+            if self.cumulative_regret[a] > F::ZERO {
+                self.last_positive[a] = iteration as u32;
+            }
+        }
+    }
+
+    /// Whether action `a` is a pruning candidate: it currently has zero
+    /// probability (zero regret while another action is positive) and hasn't
+    /// had positive regret for more than `threshold` iterations.
+    pub fn is_prunable(&self, a: usize, iteration: usize, threshold: usize) -> bool {
+        self.cumulative_regret[a] <= F::ZERO
+            && self.cumulative_regret.iter().any(|&r| r > F::ZERO)
+            && iteration.saturating_sub(self.last_positive[a] as usize) > threshold
+    }
+}
+
+/// Regret matching over one info set's cumulative regrets, in storage
+/// precision: proportional to the positive regrets, uniform if none is.
+pub(crate) fn regret_matching<F: CfrFloat>(regrets: &[F]) -> Vec<f64> {
+    let mut positive_sum = F::ZERO;
+    for &r in regrets {
+        positive_sum += floor_zero(r);
+    }
+
+    if positive_sum > F::ZERO {
+        regrets.iter().map(|&r| (floor_zero(r) / positive_sum).to_f64()).collect()
+    } else {
+        vec![1.0 / regrets.len() as f64; regrets.len()]
+    }
+}
+
+/// Strategy sums scaled to frequencies, uniform while they are all zero.
+pub(crate) fn normalized_sums<F: CfrFloat>(sums: &[F]) -> Vec<f64> {
+    let mut total = F::ZERO;
+    for &s in sums {
+        total += s;
+    }
+    if total > F::ZERO {
+        sums.iter().map(|&s| (s / total).to_f64()).collect()
+    } else {
+        vec![1.0 / sums.len() as f64; sums.len()]
+    }
+}
+
+/// The `InfoSetData::update` rule over bare regret and strategy-sum slices,
+/// so flat storage applies exactly the same arithmetic.
+pub(crate) fn update_sums<F: CfrFloat>(
+    regrets: &mut [F],
+    strategy_sums: &mut [F],
+    action_utilities: &[f64],
+    node_utility: f64,
+    reach_prob: f64,
+    variant: CfrVariant,
+    iteration: usize,
+) {
+    // Sums are carried forward in f64 and only rounded when stored, so
+    // f32 storage loses precision once per update rather than per step.
+    let strategy = regret_matching(regrets);
+    let (pos_discount, neg_discount, strat_discount) = variant.discounts(iteration);
 
+    for a in 0..regrets.len() {
+        // Regret = "how much better action a would have been"
+        let regret = action_utilities[a] - node_utility;
+        let prior = regrets[a].to_f64();
+
+        let cumulative = match variant {
             // CFR+: floor cumulative regret at 0
-            self.cumulative_regret[a] = (self.cumulative_regret[a] + regret).max(0.0);
+            CfrVariant::CfrPlus => (prior + regret).max(0.0),
+            _ => {
+                let discount = if prior > 0.0 { pos_discount } else { neg_discount };
+                prior * discount + regret
+            }
+        };
+        regrets[a] = F::from_f64(cumulative);
 
-            // Accumulate strategy weighted by reach probability
-            self.cumulative_strategy[a] += reach_prob * strategy[a];
-        }
+        // Accumulate strategy weighted by reach probability
+        let weight = strategy_sums[a].to_f64() * strat_discount + reach_prob * strategy[a];
+        strategy_sums[a] = F::from_f64(weight);
+    }
+}
+
+/// Buckets a `HashMap` allocates to hold `capacity` entries: a power of two
+/// kept at most 7/8 full, or 4 or 8 for small maps.
+fn map_buckets(capacity: usize) -> usize {
+    match capacity {
+        0 => 0,
+        1..=3 => 4,
+        4..=7 => 8,
+        _ => (capacity * 8).div_ceil(7).next_power_of_two(),
     }
 }
 
 /// Key for an information set: encodes what the player knows.
 /// For push/fold: the canonical hand index (0-168) + the decision point.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct InfoSetKey {
     /// Canonical hand bucket (0-168 for preflop hands).
     pub hand_bucket: u16,
     /// Which decision point in the game tree (0 = SB push/fold, 1 = BB call/fold).
-    pub node_id: u16,
+    pub node_id: u32,
+    /// Seat acting at this decision, for multiway trees where the node id
+    /// alone doesn't say whose turn it is. Heads-up trees leave it at 0.
+    pub position: u8,
 }
 
 /// The CFR trainer holds all information set data.
-pub struct CfrTrainer {
-    pub info_sets: HashMap<InfoSetKey, InfoSetData>,
+///
+/// Generic over the storage precision; `CfrTrainer` (f64) is the default and
+/// `CfrTrainer<f32>` halves the memory of every info set.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CfrTrainer<F: CfrFloat = f64> {
+    pub info_sets: HashMap<InfoSetKey, InfoSetData<F>>,
+    /// Regret and strategy-sum update rule applied by `update`.
+    pub variant: CfrVariant,
 }
 
 impl CfrTrainer {
     pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<F: CfrFloat> Default for CfrTrainer<F> {
+    fn default() -> Self {
+        Self::with_variant(CfrVariant::CfrPlus)
+    }
+}
+
+impl<F: CfrFloat> CfrTrainer<F> {
+    pub fn with_variant(variant: CfrVariant) -> Self {
         CfrTrainer {
             info_sets: HashMap::new(),
+            variant,
         }
     }
 
+    /// Apply one traversal's update to the info set at `key`, creating it if
+    /// needed, under the trainer's variant. See `InfoSetData::update`.
+    pub fn update(
+        &mut self,
+        key: &InfoSetKey,
+        action_utilities: &[f64],
+        node_utility: f64,
+        reach_prob: f64,
+        iteration: usize,
+    ) -> &mut InfoSetData<F> {
+        let variant = self.variant;
+        let data = self.get_or_create(key, action_utilities.len());
+        data.update(action_utilities, node_utility, reach_prob, variant, iteration);
+        data
+    }
+
     /// Get or create an information set entry.
-    pub fn get_or_create(&mut self, key: &InfoSetKey, num_actions: usize) -> &mut InfoSetData {
+    pub fn get_or_create(&mut self, key: &InfoSetKey, num_actions: usize) -> &mut InfoSetData<F> {
         self.info_sets
             .entry(key.clone())
             .or_insert_with(|| InfoSetData::new(num_actions))
     }
 
+    /// Bytes one map bucket takes: the key and data inline plus the map's
+    /// control byte.
+    const BUCKET_BYTES: usize = std::mem::size_of::<(InfoSetKey, InfoSetData<F>)>() + 1;
+
+    /// Bytes the trainer holds: every bucket the map has allocated, full or
+    /// not, plus each info set's arrays at their allocated capacity.
+    pub fn memory_bytes(&self) -> usize {
+        let heap: usize = self.info_sets.values().map(InfoSetData::heap_bytes).sum();
+        map_buckets(self.info_sets.capacity()) * Self::BUCKET_BYTES + heap
+    }
+
+    /// What `memory_bytes` comes to once `info_sets` info sets holding
+    /// `slots` actions between them have been inserted one by one.
+    pub fn estimated_bytes(info_sets: usize, slots: usize) -> usize {
+        let per_slot = 2 * std::mem::size_of::<F>() + std::mem::size_of::<u32>();
+        map_buckets(info_sets) * Self::BUCKET_BYTES + slots * per_slot
+    }
+
     /// Get the current strategy for an info set (read-only).
     pub fn get_strategy(&self, key: &InfoSetKey, num_actions: usize) -> Vec<f64> {
         match self.info_sets.get(key) {
@@ -112,6 +510,16 @@ impl CfrTrainer {
         }
     }
 
+    /// Write every info set to a checkpoint at `path` behind `header`.
+    pub fn save(&self, path: &std::path::Path, header: CheckpointHeader) -> Result<(), String> {
+        checkpoint::save(path, header, self)
+    }
+
+    /// Read a trainer written by `save`, with the header it was saved under.
+    pub fn load(path: &std::path::Path) -> Result<(CheckpointHeader, Self), String> {
+        checkpoint::load(path)
+    }
+
     /// Get the converged average strategy.
     pub fn get_average_strategy(&self, key: &InfoSetKey, num_actions: usize) -> Vec<f64> {
         match self.info_sets.get(key) {
@@ -119,6 +527,51 @@ impl CfrTrainer {
             None => vec![1.0 / num_actions as f64; num_actions],
         }
     }
+
+    /// What this trainer accumulated since it was cloned from `baseline`:
+    /// the regret and strategy-sum changes of every info set that moved.
+    /// Regret deltas are unfloored, so they can be negative.
+    pub fn delta_from(&self, baseline: &CfrTrainer<F>) -> CfrTrainer<F> {
+        let mut delta = CfrTrainer::with_variant(self.variant);
+        for (key, data) in &self.info_sets {
+            let diff = match baseline.info_sets.get(key) {
+                Some(base) => InfoSetData {
+                    num_actions: data.num_actions,
+                    cumulative_regret: sub(&data.cumulative_regret, &base.cumulative_regret),
+                    cumulative_strategy: sub(&data.cumulative_strategy, &base.cumulative_strategy),
+                    last_positive: data.last_positive.clone(),
+                },
+                None => data.clone(),
+            };
+            let moved = diff.cumulative_regret.iter().chain(&diff.cumulative_strategy).any(|&v| v != F::ZERO);
+            if moved {
+                delta.info_sets.insert(key.clone(), diff);
+            }
+        }
+        delta
+    }
+
+    /// Add `other`'s regrets and strategy sums into this trainer key by key,
+    /// flooring regrets at 0 afterwards under CFR+. Used to fold the
+    /// `delta_from` of each worker's batch back into the shared trainer.
+    pub fn merge(&mut self, other: CfrTrainer<F>) {
+        let floor = self.variant == CfrVariant::CfrPlus;
+        for (key, theirs) in other.info_sets {
+            let ours = self.get_or_create(&key, theirs.num_actions);
+            for a in 0..ours.num_actions {
+                let regret = ours.cumulative_regret[a].to_f64() + theirs.cumulative_regret[a].to_f64();
+                ours.cumulative_regret[a] = F::from_f64(if floor { regret.max(0.0) } else { regret });
+                let weight = ours.cumulative_strategy[a].to_f64() + theirs.cumulative_strategy[a].to_f64();
+                ours.cumulative_strategy[a] = F::from_f64(weight);
+                ours.last_positive[a] = ours.last_positive[a].max(theirs.last_positive[a]);
+            }
+        }
+    }
+}
+
+/// Element-wise `a - b`, computed in f64.
+fn sub<F: CfrFloat>(a: &[F], b: &[F]) -> Vec<F> {
+    a.iter().zip(b).map(|(&x, &y)| F::from_f64(x.to_f64() - y.to_f64())).collect()
 }
 
 #[cfg(test)]
@@ -127,7 +580,7 @@ mod tests {
 
     #[test]
     fn uniform_with_no_regret() {
-        let data = InfoSetData::new(3);
+        let data: InfoSetData = InfoSetData::new(3);
         let strat = data.current_strategy();
         assert_eq!(strat.len(), 3);
         for &p in &strat {
@@ -137,7 +590,7 @@ mod tests {
 
     #[test]
     fn regret_matching_proportional() {
-        let mut data = InfoSetData::new(2);
+        let mut data: InfoSetData = InfoSetData::new(2);
         data.cumulative_regret = vec![3.0, 1.0];
         let strat = data.current_strategy();
         assert!((strat[0] - 0.75).abs() < 1e-9);
@@ -146,7 +599,7 @@ mod tests {
 
     #[test]
     fn negative_regret_floored() {
-        let mut data = InfoSetData::new(2);
+        let mut data: InfoSetData = InfoSetData::new(2);
         data.cumulative_regret = vec![-5.0, 3.0];
         let strat = data.current_strategy();
         // -5 floors to 0, so all weight on action 1
@@ -156,7 +609,7 @@ mod tests {
 
     #[test]
     fn average_strategy_accumulates() {
-        let mut data = InfoSetData::new(2);
+        let mut data: InfoSetData = InfoSetData::new(2);
         // Simulate two updates with different strategies
         data.cumulative_strategy = vec![0.6, 0.4];
         let avg = data.average_strategy();
@@ -166,10 +619,10 @@ mod tests {
 
     #[test]
     fn cfr_plus_floors_regret() {
-        let mut data = InfoSetData::new(2);
+        let mut data: InfoSetData = InfoSetData::new(2);
         data.cumulative_regret = vec![1.0, 1.0];
         // Action 0 had utility -10, action 1 had utility 5, node utility = 0
-        data.update(&[-10.0, 5.0], 0.0, 1.0);
+        data.update(&[-10.0, 5.0], 0.0, 1.0, CfrVariant::CfrPlus, 1);
         // regret[0] = max(1.0 + (-10 - 0), 0) = max(-9, 0) = 0
         // regret[1] = max(1.0 + (5 - 0), 0) = 6.0
         assert!((data.cumulative_regret[0] - 0.0).abs() < 1e-9);
@@ -179,8 +632,176 @@ mod tests {
     #[test]
     fn trainer_get_or_create() {
         let mut trainer = CfrTrainer::new();
-        let key = InfoSetKey { hand_bucket: 0, node_id: 0 };
+        let key = InfoSetKey { hand_bucket: 0, node_id: 0, position: 0 };
         trainer.get_or_create(&key, 2);
         assert!(trainer.info_sets.contains_key(&key));
     }
+
+    #[test]
+    fn memory_bytes_counts_map_buckets_and_arrays() {
+        let mut trainer = CfrTrainer::<f32>::default();
+        assert_eq!(trainer.memory_bytes(), 0);
+        for n in 1..=100usize {
+            trainer.get_or_create(&InfoSetKey { hand_bucket: n as u16, node_id: 0, position: 0 }, 3);
+            // Priced from the capacity the map reports, not from how it grew
+            let buckets = map_buckets(trainer.info_sets.capacity());
+            let arrays = n * 3 * (4 + 4 + 4);
+            assert_eq!(trainer.memory_bytes(), buckets * CfrTrainer::<f32>::BUCKET_BYTES + arrays);
+            assert!(CfrTrainer::<f32>::estimated_bytes(n, 3 * n) <= trainer.memory_bytes());
+        }
+    }
+
+    #[test]
+    fn map_buckets_hold_capacity_under_seven_eighths_load() {
+        assert_eq!(map_buckets(0), 0);
+        assert_eq!(map_buckets(3), 4);
+        assert_eq!(map_buckets(7), 8);
+        assert_eq!(map_buckets(100), 128);
+        assert_eq!(map_buckets(112), 128);
+        assert_eq!(map_buckets(113), 256);
+    }
+
+    #[test]
+    fn f32_storage_matches_f64() {
+        let mut d64: InfoSetData = InfoSetData::new(3);
+        let mut d32: InfoSetData<f32> = InfoSetData::new(3);
+        for &(u, n) in &[([1.0, -2.0, 0.5], 0.1), ([0.3, 0.2, -1.0], -0.2), ([2.0, 0.0, 1.0], 1.0)] {
+            d64.update(&u, n, 1.0, CfrVariant::CfrPlus, 1);
+            d32.update(&u, n, 1.0, CfrVariant::CfrPlus, 1);
+        }
+        for (a, b) in d64.average_strategy().iter().zip(d32.average_strategy()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+        assert_eq!(std::mem::size_of_val(&d32.cumulative_regret[0]), 4);
+    }
+
+    #[test]
+    fn prunable_only_after_threshold_with_zero_probability() {
+        let mut data: InfoSetData = InfoSetData::new(2);
+        // All regrets zero: uniform strategy, nothing prunable
+        assert!(!data.is_prunable(0, 1000, 10));
+
+        data.update(&[1.0, -1.0], 0.0, 1.0, CfrVariant::CfrPlus, 1);
+        data.mark_positive(5);
+        assert!(!data.is_prunable(0, 100, 10), "positive action is never pruned");
+        assert!(data.is_prunable(1, 100, 10));
+        assert!(!data.is_prunable(1, 10, 10), "too recent to prune");
+    }
+
+    #[test]
+    fn dcfr_discounts_prior_sums() {
+        let mut data: InfoSetData = InfoSetData::new(2);
+        data.cumulative_regret = vec![4.0, -4.0];
+        data.cumulative_strategy = vec![1.0, 1.0];
+        // Iteration 3: positive regrets scale by 2^1.5 / (2^1.5 + 1), negative
+        // ones by 1/2 (beta = 0), strategy sums by (2/3)^2
+        data.update(&[0.0, 0.0], 0.0, 1.0, CfrVariant::DCFR, 3);
+        let w = 2f64.powf(1.5);
+        assert!((data.cumulative_regret[0] - 4.0 * w / (w + 1.0)).abs() < 1e-9);
+        assert!((data.cumulative_regret[1] + 2.0).abs() < 1e-9);
+        // Current strategy was all on action 0
+        assert!((data.cumulative_strategy[0] - (4.0 / 9.0 + 1.0)).abs() < 1e-9);
+        assert!((data.cumulative_strategy[1] - 4.0 / 9.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn linear_cfr_weights_iterations_linearly() {
+        let mut data: InfoSetData = InfoSetData::new(2);
+        for t in 1..=3 {
+            data.update(&[1.0, -1.0], 0.0, 1.0, CfrVariant::LinearCfr, t);
+        }
+        // Iteration t weighted by t, all scaled by 1/T: regrets (1 + 2 + 3) / 3
+        assert!((data.cumulative_regret[0] - 2.0).abs() < 1e-9);
+        assert!((data.cumulative_regret[1] + 2.0).abs() < 1e-9);
+        // Uniform on iteration 1, then all on action 0: (0.5 + 2 + 3) / 3
+        assert!((data.cumulative_strategy[0] - 5.5 / 3.0).abs() < 1e-9);
+        assert!((data.cumulative_strategy[1] - 0.5 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn variant_parses_from_cli_names() {
+        assert_eq!("dcfr".parse::<CfrVariant>().unwrap(), CfrVariant::DCFR);
+        assert_eq!("CFR+".parse::<CfrVariant>().unwrap(), CfrVariant::CfrPlus);
+        assert_eq!("linear".parse::<CfrVariant>().unwrap(), CfrVariant::LinearCfr);
+        assert!("mccfr".parse::<CfrVariant>().is_err());
+    }
+
+    #[test]
+    fn precision_parses_and_sizes() {
+        assert_eq!("f32".parse::<Precision>().unwrap(), Precision::F32);
+        assert_eq!("F64".parse::<Precision>().unwrap(), Precision::F64);
+        assert!("f16".parse::<Precision>().is_err());
+        assert_eq!(Precision::F32.bytes() * 2, Precision::F64.bytes());
+    }
+
+    #[test]
+    fn sampled_indices_follow_weights() {
+        let mut rng = solver_rng(Some(3));
+        let mut counts = [0usize; 3];
+        for _ in 0..10_000 {
+            counts[sample_index(&[0.2, 0.0, 0.8], &mut rng)] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        assert!((counts[0] as f64 / 10_000.0 - 0.2).abs() < 0.02, "{:?}", counts);
+    }
+
+    #[test]
+    fn merged_split_sweep_matches_sequential_sweep() {
+        let utilities = |h: u16| [h as f64 - 1.5, 1.0 - h as f64 * 0.5, 0.25];
+        let sweep = |trainer: &mut CfrTrainer, hands: std::ops::Range<u16>, iteration: usize| {
+            for h in hands {
+                for node_id in 0..2 {
+                    let key = InfoSetKey { hand_bucket: h, node_id, position: 0 };
+                    let strategy = trainer.get_strategy(&key, 3);
+                    let u = utilities(h + node_id as u16);
+                    let node = strategy.iter().zip(&u).map(|(s, v)| s * v).sum();
+                    trainer.update(&key, &u, node, 0.5 + h as f64 * 0.1, iteration);
+                }
+            }
+        };
+
+        let mut baseline = CfrTrainer::new();
+        sweep(&mut baseline, 0..4, 1);
+
+        let mut sequential = baseline.clone();
+        sweep(&mut sequential, 0..4, 2);
+
+        // Two workers each sweep half of the combos from the same baseline
+        let mut low = baseline.clone();
+        let mut high = baseline.clone();
+        sweep(&mut low, 0..2, 2);
+        sweep(&mut high, 2..4, 2);
+        let (low, high) = (low.delta_from(&baseline), high.delta_from(&baseline));
+        let mut merged = baseline;
+        merged.merge(low);
+        merged.merge(high);
+
+        assert_eq!(merged.info_sets.len(), sequential.info_sets.len());
+        for (key, want) in &sequential.info_sets {
+            let got = &merged.info_sets[key];
+            for a in 0..3 {
+                assert!((got.cumulative_regret[a] - want.cumulative_regret[a]).abs() < 1e-12, "{:?}", key);
+                assert!((got.cumulative_strategy[a] - want.cumulative_strategy[a]).abs() < 1e-12, "{:?}", key);
+            }
+            assert!(got.cumulative_regret.iter().all(|&r| r >= 0.0));
+        }
+    }
+
+    #[test]
+    fn trainer_save_load_round_trips() {
+        let mut trainer: CfrTrainer<f32> = CfrTrainer::with_variant(CfrVariant::DCFR);
+        let key = InfoSetKey { hand_bucket: 3, node_id: 9, position: 0 };
+        trainer.update(&key, &[2.0, -1.0], 0.5, 1.0, 1);
+
+        let path = std::env::temp_dir().join(format!("gto_trainer_{}.ckpt", std::process::id()));
+        let header = CheckpointHeader { iteration: 1, config_hash: 42 };
+        trainer.save(&path, header).unwrap();
+        let (loaded_header, loaded) = CfrTrainer::<f32>::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded_header, header);
+        assert_eq!(loaded.variant, CfrVariant::DCFR);
+        assert_eq!(loaded.get_strategy(&key, 2), trainer.get_strategy(&key, 2));
+        assert_eq!(loaded.get_average_strategy(&key, 2), trainer.get_average_strategy(&key, 2));
+    }
 }
@@ -0,0 +1,117 @@
+//! On-disk solver checkpoints.
+//!
+//! A checkpoint holds a solver's CFR state together with a header recording
+//! how many iterations produced it and a hash of the config that defines the
+//! spot, so resuming against a different spot fails instead of silently
+//! mixing regrets from two games.
+
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// What a checkpoint was written from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointHeader {
+    /// Iterations completed when the checkpoint was written.
+    pub iteration: usize,
+    /// `config_hash` of the solver config that produced the state.
+    pub config_hash: u64,
+}
+
+/// Root of everything gto-cli keeps on disk: `$GTO_CLI_HOME` when set, so
+/// tests and scripts can keep off the real caches, and `~/.gto-cli` otherwise.
+pub fn gto_home() -> PathBuf {
+    if let Some(dir) = std::env::var_os("GTO_CLI_HOME") {
+        return PathBuf::from(dir);
+    }
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    Path::new(&home).join(".gto-cli")
+}
+
+/// Directory shared with the solution caches, `~/.gto-cli/solver`.
+pub fn solver_dir() -> PathBuf {
+    let dir = gto_home().join("solver");
+    std::fs::create_dir_all(&dir).ok();
+    dir
+}
+
+/// Stable 64-bit FNV-1a hash of `fields` in bincode form. Unlike
+/// `DefaultHasher` it doesn't change between builds, so checkpoints written
+/// by one binary resume under the next.
+pub fn config_hash<T: Serialize>(fields: &T) -> u64 {
+    let bytes = bincode::serialize(fields).unwrap_or_default();
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Extension of the file `save` writes before renaming it into place.
+pub const TMP_EXTENSION: &str = "tmp";
+
+/// Write `state` behind `header`. The file is written beside `path` and
+/// renamed into place, so an interrupted save leaves the previous
+/// checkpoint intact.
+pub fn save<T: Serialize>(path: &Path, header: CheckpointHeader, state: &T) -> Result<(), String> {
+    let data = bincode::serialize(&(header, state)).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension(TMP_EXTENSION);
+    std::fs::write(&tmp, data).map_err(|e| format!("Cannot write {}: {}", tmp.display(), e))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+}
+
+/// Read a checkpoint written by `save`.
+pub fn load<T: DeserializeOwned>(path: &Path) -> Result<(CheckpointHeader, T), String> {
+    let data = std::fs::read(path).map_err(|e| format!("Cannot read checkpoint {}: {}", path.display(), e))?;
+    bincode::deserialize(&data).map_err(|e| format!("Corrupt checkpoint {}: {}", path.display(), e))
+}
+
+/// Read a checkpoint and check it came from a config hashing to
+/// `config_hash`. Returns the iteration to continue from and the state.
+pub fn load_matching<T: DeserializeOwned>(path: &Path, config_hash: u64) -> Result<(usize, T), String> {
+    let (header, state) = load(path)?;
+    if header.config_hash != config_hash {
+        return Err(format!(
+            "Checkpoint {} was written for a different config (hash {:016x}, expected {:016x})",
+            path.display(),
+            header.config_hash,
+            config_hash
+        ));
+    }
+    Ok((header.iteration, state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("gto_checkpoint_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn round_trips_header_and_state() {
+        let path = temp_path("roundtrip");
+        let header = CheckpointHeader { iteration: 40, config_hash: 7 };
+        save(&path, header, &vec![1.5f32, -2.0]).unwrap();
+        let (loaded, state): (CheckpointHeader, Vec<f32>) = load(&path).unwrap();
+        assert_eq!(loaded, header);
+        assert_eq!(state, vec![1.5, -2.0]);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn mismatched_hash_fails() {
+        let path = temp_path("mismatch");
+        save(&path, CheckpointHeader { iteration: 10, config_hash: 1 }, &0u32).unwrap();
+        let err = load_matching::<u32>(&path, 2).unwrap_err();
+        assert!(err.contains("different config"), "{}", err);
+        assert_eq!(load_matching::<u32>(&path, 1).unwrap(), (10, 0));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn hash_is_stable_and_field_sensitive() {
+        assert_eq!(config_hash(&("Ks9d4c", 10.0)), config_hash(&("Ks9d4c", 10.0)));
+        assert_ne!(config_hash(&("Ks9d4c", 10.0)), config_hash(&("Ks9d4c", 12.0)));
+    }
+}
@@ -1,10 +1,13 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use std::io::IsTerminal;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use colored::Colorize;
 use comfy_table::{Cell, CellAlignment, ContentArrangement, Table};
 
 use crate::cards::parse_board;
 use crate::display::{
-    board_display, equity_bar, print_error, range_grid, styled_action,
+    best_hand_display, board_display, equity_bar, print_error, range_grid, styled_action,
+    weighted_range_grid,
 };
 
 const POSITIONS_6MAX: &[&str] = &["UTG", "HJ", "CO", "BTN", "SB", "BB"];
@@ -119,6 +122,104 @@ impl Strength {
     }
 }
 
+#[derive(Clone, ValueEnum)]
+enum Bucketing {
+    /// Equal-frequency bins over mean equity
+    Ehs,
+    /// Potential-aware k-means over the river equity distribution
+    Ehs2,
+    /// Equity bins within flush-draw / straight-draw / pair classes
+    Draws,
+    /// k-means over river equity histograms (earth mover's distance)
+    Hist,
+}
+
+impl Bucketing {
+    fn mode(&self) -> crate::bucketing::BucketingMode {
+        match self {
+            Bucketing::Ehs => crate::bucketing::BucketingMode::Ehs,
+            Bucketing::Ehs2 => crate::bucketing::BucketingMode::Ehs2,
+            Bucketing::Draws => crate::bucketing::BucketingMode::Draws,
+            Bucketing::Hist => crate::bucketing::BucketingMode::Histogram,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Game {
+    Holdem,
+    Omaha,
+    /// Short deck (6+): no 2-5, flushes beat full houses, A-6-7-8-9 straight
+    #[value(name = "shortdeck")]
+    ShortDeck,
+}
+
+#[derive(Clone, ValueEnum)]
+enum EvaluatorArg {
+    /// Precomputed 7-card lookup table (~127MB, built on first use)
+    Table,
+    /// Rank-histogram evaluator
+    Fast,
+}
+
+/// Select the hand evaluator for a solve. Without a choice the table is used
+/// only when it is already cached.
+fn select_evaluator(choice: Option<EvaluatorArg>) {
+    use crate::lookup_table::{set_evaluator, table_path, use_cached_table, Evaluator};
+
+    match choice {
+        None => {
+            use_cached_table();
+        }
+        Some(EvaluatorArg::Fast) => set_evaluator(Evaluator::Fast),
+        Some(EvaluatorArg::Table) => {
+            if !table_path().exists() {
+                println!(
+                    "  {}",
+                    format!("Building hand lookup table (cached at {})...", table_path().display())
+                        .dimmed()
+                );
+            }
+            set_evaluator(Evaluator::Table);
+        }
+    }
+}
+
+#[derive(Clone, ValueEnum)]
+enum Subset {
+    /// 1,755 suit-isomorphic flops weighted by multiplicity
+    Canonical,
+    /// All 22,100 flops
+    Full,
+}
+
+impl Subset {
+    fn subset(&self) -> crate::flop_enumerator::FlopSubset {
+        match self {
+            Subset::Canonical => crate::flop_enumerator::FlopSubset::Canonical,
+            Subset::Full => crate::flop_enumerator::FlopSubset::Full,
+        }
+    }
+}
+
+#[derive(Clone, ValueEnum)]
+enum TreeFormat {
+    /// Indented outline
+    Text,
+    /// Graphviz DOT (pipe into `dot -Tsvg`)
+    Dot,
+}
+
+#[derive(Clone, PartialEq, ValueEnum)]
+enum OutputFormat {
+    /// Colored summary for the terminal
+    Text,
+    /// Full solution as pretty-printed JSON on stdout
+    Json,
+}
+
+// Parsed once per run, so the solver variants' size doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     /// Show preflop opening range for a position
@@ -144,20 +245,78 @@ enum Commands {
         #[arg(long, default_value = "0")]
         rake: f64,
     },
-    /// Calculate equity between two hands or hand vs range
+    /// Calculate equity between two hands, hand vs range, or range vs range
     Equity {
         /// Your hand (e.g., AhAs)
-        hand1: String,
+        #[arg(required_unless_present = "hero")]
+        hand1: Option<String>,
         /// "vs" keyword (optional)
         versus: Option<String>,
         /// Opponent hand or range (e.g., KsKd or KK)
         hand2: Option<String>,
+        /// More opponents for multiway equity (e.g., `gto equity AsKs QhQd
+        /// "JJ-99"`)
+        others: Vec<String>,
         /// Board cards (e.g., AsKd5c)
         #[arg(short, long)]
         board: Option<String>,
         /// Number of simulations
         #[arg(short = 'n', long, default_value = "30000")]
         sims: usize,
+        /// Count each range entry equally instead of weighting hand classes
+        /// by the combos that survive blockers
+        #[arg(long)]
+        unweighted: bool,
+        /// Worker threads for the simulations (0 = all cores, 1 = serial)
+        #[arg(long, default_value = "0")]
+        threads: usize,
+        /// Game: holdem, shortdeck, or omaha (4-card hands; ranges are
+        /// explicit comma-separated combos like AsAdKhQh)
+        #[arg(long, value_enum, default_value = "holdem")]
+        game: Game,
+        /// Show the best five cards your hand makes on the board
+        #[arg(long)]
+        verbose: bool,
+        /// Break wins, ties and losses down by hand category (hold'em)
+        #[arg(long)]
+        breakdown: bool,
+        /// List the villain combos you beat, flip against and lose to (hand
+        /// vs range, hold'em)
+        #[arg(long)]
+        combos: bool,
+        /// Show how your river equity spreads over the runouts of a flop or
+        /// turn board, as percentiles and a histogram (hold'em)
+        #[arg(long, conflicts_with_all = ["breakdown", "combos", "precision"])]
+        runouts: bool,
+        /// Enumerate every runout instead of sampling (hold'em; errors if
+        /// there are too many). Small spots are enumerated automatically
+        #[arg(long, conflicts_with = "mc")]
+        exact: bool,
+        /// Always sample runouts, even where enumerating would be cheaper
+        #[arg(long)]
+        mc: bool,
+        /// Sample until the 95% confidence interval is within this many
+        /// percentage points of the equity (e.g. 0.25), instead of a fixed
+        /// --sims (hold'em)
+        #[arg(long, conflicts_with = "exact")]
+        precision: Option<f64>,
+        /// Most simulations a --precision run may take
+        #[arg(long, default_value_t = crate::equity::ADAPTIVE_MAX, requires = "precision")]
+        max_sims: usize,
+        /// Your range, for range vs range equity with a per-combo table
+        /// (e.g., "22+,ATs+")
+        #[arg(long, requires = "villain", conflicts_with = "hand1")]
+        hero: Option<String>,
+        /// Villain range for --hero (e.g., "55+,AQ+")
+        #[arg(long, requires = "hero")]
+        villain: Option<String>,
+    },
+    /// All-in equity of one preflop hand class against another
+    Matchup {
+        /// Your hand class (e.g., AKs, QQ, T9o)
+        hand1: String,
+        /// Opponent hand class
+        hand2: String,
     },
     /// Calculate pot odds, EV, and implied odds
     Odds {
@@ -233,6 +392,26 @@ enum Commands {
         /// Range expression (e.g., "AA,KK,QQ,AKs" or "TT+")
         range_str: String,
     },
+    /// Show which villain combos a hand blocks (e.g., `gto blockers AhKh "QQ+,AK,KQs"`)
+    Blockers {
+        /// Your combo (e.g., AhKh)
+        hand: String,
+        /// Villain range (e.g., "QQ+,AK,KQs")
+        range: String,
+        /// Also count the combos this range has in common with the villain range
+        #[arg(long)]
+        shared: Option<String>,
+        /// Hand classes to list, most blocked first
+        #[arg(long, default_value = "10")]
+        top: usize,
+    },
+    /// Save a range as ~/.gto-cli/ranges/<name>.txt for use as @name
+    SaveRange {
+        /// Name to save under (letters, digits, '_', '-', '.')
+        name: String,
+        /// Range expression (e.g., "22+,A2s+,KTs+")
+        range_str: String,
+    },
     /// Calculate bluff-to-value ratio and fold equity needed
     Bluff {
         /// Current pot size
@@ -260,14 +439,127 @@ enum Commands {
         /// MCCFR iterations for on-demand solving
         #[arg(short, long, default_value = "500000")]
         iterations: usize,
+        /// Round postflop mixes below this frequency to pure actions (e.g.,
+        /// 0.05); river answers also report the exploitability that costs
+        #[arg(long)]
+        simplify: Option<f64>,
     },
     /// Interactive hand advisor — walk through a poker hand step-by-step
     Play,
     /// Solve GTO strategies using CFR+
     Solve {
+        /// 7-card hand evaluator (default: the lookup table if ~/.gto-cli/eval.bin exists)
+        #[arg(long, value_enum, global = true)]
+        evaluator: Option<EvaluatorArg>,
+        /// After solving, delete the oldest solutions in ~/.gto-cli/solver until
+        /// they fit in this size (e.g., "500M", "2G"; plain numbers are MB).
+        /// Checkpoints are kept
+        #[arg(long, global = true)]
+        clean_cache: Option<String>,
         #[command(subcommand)]
         solver: SolverCommands,
     },
+    /// Solve the river of a PokerStars hand history (pot, stacks and board
+    /// come from the hand; ranges are yours)
+    SolveHh {
+        /// Hand history file (the first hand in it is used)
+        file: String,
+        /// Range of the player out of position on the river (e.g., "AA,AKs,KQs")
+        #[arg(long)]
+        oop: String,
+        /// Range of the player in position on the river
+        #[arg(long)]
+        ip: String,
+        /// Number of CFR+ iterations
+        #[arg(short, long, default_value = "10000")]
+        iterations: usize,
+    },
+    /// List flop boards with their multiplicity weights
+    Flops {
+        /// Which flops to list
+        #[arg(long, default_value = "canonical")]
+        subset: Subset,
+    },
+    /// Show how the strategies changed between two river solutions
+    /// (`.json` from `solve river --format json --out`, or cached `.bin`)
+    Diff {
+        /// The earlier solution
+        old: String,
+        /// The solution to compare against it
+        new: String,
+        /// Smallest frequency change to report (e.g., 0.05 for 5 points)
+        #[arg(long, default_value = "0.05")]
+        threshold: f64,
+    },
+    /// Step through a river solution one action at a time, showing each
+    /// node's strategy (`.json` from `solve river --format json --out`, or cached `.bin`)
+    Walk {
+        /// The solution to walk
+        solution: String,
+    },
+    /// Print a postflop game tree — `gto tree --pot 10 --stack 50 --bet-sizes 0.5,1.0`
+    Tree {
+        /// Starting pot size
+        #[arg(short, long, default_value = "10")]
+        pot: f64,
+        /// Effective stack remaining
+        #[arg(short, long, default_value = "50")]
+        stack: f64,
+        /// Bet sizes for both streets (e.g., "0.33,0.75,geo2,allin")
+        #[arg(long, default_value = "0.5,1.0")]
+        bet_sizes: String,
+        /// Maximum raises per street
+        #[arg(long, default_value = "2")]
+        max_raises: usize,
+        /// 4-card turn board for a turn+river tree (omit for a river tree)
+        #[arg(long)]
+        board: Option<String>,
+        /// Action depth to print in text format
+        #[arg(long, default_value = "100")]
+        depth: usize,
+        /// Output format
+        #[arg(long, default_value = "text")]
+        format: TreeFormat,
+    },
+    /// Solve one spot across many flops and write a CSV of flop tendencies
+    FlopReport {
+        /// File of flops, one per line (default: 50 representative flops)
+        #[arg(long, conflicts_with = "subset")]
+        flops: Option<String>,
+        /// Use a generated flop subset instead of a file
+        #[arg(long)]
+        subset: Option<Subset>,
+        /// OOP player range (e.g., "AA,AKs,KQs")
+        #[arg(long)]
+        oop: String,
+        /// IP player range (e.g., "QQ,JJ,TT")
+        #[arg(long)]
+        ip: String,
+        /// Starting pot size
+        #[arg(short, long, default_value = "10")]
+        pot: f64,
+        /// Effective stack remaining
+        #[arg(short, long, default_value = "50")]
+        stack: f64,
+        /// Number of MCCFR iterations per flop
+        #[arg(short, long, default_value = "100000")]
+        iterations: usize,
+        /// Hand bucketing mode for flop and turn
+        #[arg(long, default_value = "ehs")]
+        bucketing: Bucketing,
+        /// Number of hand buckets per street
+        #[arg(long, default_value = "200")]
+        buckets: usize,
+        /// Flops solved at once (0 = all cores)
+        #[arg(short, long, default_value = "0")]
+        jobs: usize,
+        /// Write the CSV here instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Recompute bucket assignments instead of using ~/.gto-cli/buckets/
+        #[arg(long)]
+        no_bucket_cache: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -283,6 +575,26 @@ enum SolverCommands {
         /// Number of CFR+ iterations (more = more accurate)
         #[arg(short, long, default_value = "10000")]
         iterations: usize,
+        /// Tournament stacks in bb, SB first, BB second, then the rest of the
+        /// table (e.g., "12,8,25,30"); solves in ICM $EV with --payouts
+        #[arg(long, requires = "payouts")]
+        stacks: Option<String>,
+        /// Prize for each finishing place, first place first (e.g., "50,30,20")
+        #[arg(long, requires = "stacks")]
+        payouts: Option<String>,
+    },
+    /// Solve 3-handed push/fold ranges (BTN, SB, BB)
+    Shove {
+        /// Stacks in bb before the blinds are posted, in the order the
+        /// players act: BTN, SB, BB (e.g., "10,8,12"); two stacks solve SB vs BB
+        #[arg(long)]
+        stacks: String,
+        /// Ante every player posts, in bb
+        #[arg(long, default_value = "0")]
+        ante: f64,
+        /// Number of sampled deals (more = more accurate)
+        #[arg(short, long, default_value = "500000")]
+        iterations: usize,
     },
     /// Solve full preflop decision tree (open/3-bet/4-bet)
     Preflop {
@@ -319,6 +631,57 @@ enum SolverCommands {
         /// Number of CFR+ iterations
         #[arg(short, long, default_value = "10000")]
         iterations: usize,
+        /// Regret update rule: cfr+, dcfr (alpha 1.5, beta 0, gamma 2) or linear
+        #[arg(long, default_value = "cfr+")]
+        cfr: crate::cfr::CfrVariant,
+        /// Regret and strategy-sum storage: f32 (half the memory) or f64
+        #[arg(long, default_value = "f64")]
+        precision: crate::cfr::Precision,
+        #[command(flatten)]
+        algorithm: AlgorithmArgs,
+        #[command(flatten)]
+        checkpoint: CheckpointArgs,
+        /// Record exploitability every N iterations and show the series
+        /// (slows the solve; off by default)
+        #[arg(long)]
+        convergence_every: Option<usize>,
+        /// Bet sizes, comma-separated pot fractions, geoN or allin (e.g., "0.33,0.75,1.5,allin")
+        #[arg(long)]
+        bet_sizes: Option<String>,
+        /// Raise sizes, comma-separated fractions of the pot after calling or
+        /// multiples of the bet faced (e.g., "0.5,1.0" or "2.5x,3x"; "none" = all-in only)
+        #[arg(long)]
+        raise_sizes: Option<String>,
+        #[command(flatten)]
+        player_sizing: PlayerSizingArgs,
+        #[command(flatten)]
+        allin: AllInArgs,
+        /// Abort if the estimated solver memory exceeds this (e.g., "512M", "4G"; plain numbers are MB)
+        #[arg(long)]
+        max_memory: Option<String>,
+        /// Print the solver's memory use at the start and end of the solve
+        #[arg(long)]
+        verbose: bool,
+        /// Round mixes below this frequency to pure actions (e.g., 0.05) and
+        /// report the exploitability that costs
+        #[arg(long)]
+        simplify: Option<f64>,
+        /// Skip actions whose regret has stayed at zero for N iterations
+        /// (default 100), exploring them now and then so they can recover.
+        /// Exploitability is still measured on the full tree
+        #[arg(long, num_args = 0..=1, default_missing_value = "100")]
+        prune: Option<usize>,
+        /// Group each range into N equity buckets that share a strategy,
+        /// for a faster, approximate solve of wide ranges
+        #[arg(long)]
+        buckets: Option<usize>,
+        /// Output format (json prints the full solution, combos and
+        /// frequencies indexed alike)
+        #[arg(long, default_value = "text")]
+        format: OutputFormat,
+        /// Write the solution to this file instead of stdout (colors stripped)
+        #[arg(long)]
+        out: Option<String>,
     },
     /// Solve a turn spot using CFR+ (turn + river)
     Turn {
@@ -340,6 +703,39 @@ enum SolverCommands {
         /// Number of CFR+ iterations
         #[arg(short, long, default_value = "5000")]
         iterations: usize,
+        /// Worker threads for river subtrees (0 = all cores, 1 = serial)
+        #[arg(long, default_value = "0")]
+        threads: usize,
+        /// Regret and strategy-sum storage: f32 (half the memory) or f64
+        #[arg(long, default_value = "f32")]
+        precision: crate::cfr::Precision,
+        #[command(flatten)]
+        algorithm: AlgorithmArgs,
+        #[command(flatten)]
+        checkpoint: CheckpointArgs,
+        /// Record exploitability every N iterations and show the series
+        /// (slows the solve; off by default)
+        #[arg(long)]
+        convergence_every: Option<usize>,
+        #[command(flatten)]
+        sizing: StreetSizingArgs,
+        #[command(flatten)]
+        player_sizing: PlayerSizingArgs,
+        #[command(flatten)]
+        allin: AllInArgs,
+        /// Abort if the estimated solver memory exceeds this (e.g., "512M", "4G"; plain numbers are MB)
+        #[arg(long)]
+        max_memory: Option<String>,
+        /// Print the solver's memory use at the start and end of the solve
+        #[arg(long)]
+        verbose: bool,
+        /// Output format (json prints the full solution, combos and
+        /// frequencies indexed alike)
+        #[arg(long, default_value = "text")]
+        format: OutputFormat,
+        /// Write the solution to this file instead of stdout (colors stripped)
+        #[arg(long)]
+        out: Option<String>,
     },
     /// Solve a flop spot using MCCFR (flop + turn + river)
     Flop {
@@ -361,6 +757,26 @@ enum SolverCommands {
         /// Number of MCCFR iterations
         #[arg(short, long, default_value = "500000")]
         iterations: usize,
+        /// Hand bucketing mode for flop and turn
+        #[arg(long, default_value = "ehs")]
+        bucketing: Bucketing,
+        /// Number of hand buckets per street; pass a comma-separated list
+        /// (e.g. 50,100,200) to sweep and compare exploitability
+        #[arg(long, default_value = "200", value_delimiter = ',')]
+        buckets: Vec<usize>,
+        /// Recompute bucket assignments instead of using ~/.gto-cli/buckets/
+        #[arg(long)]
+        no_bucket_cache: bool,
+        /// Solve at raw combo granularity (for small ranges)
+        #[arg(long)]
+        no_buckets: bool,
+        /// Output format (json prints the full solution, combos and
+        /// frequencies indexed alike)
+        #[arg(long, default_value = "text")]
+        format: OutputFormat,
+        /// Write the solution to this file instead of stdout (colors stripped)
+        #[arg(long)]
+        out: Option<String>,
     },
     /// Batch pre-solve flop spots across positions and boards
     Batch {
@@ -382,6 +798,121 @@ enum SolverCommands {
     },
 }
 
+/// Per-street bet sizing overrides for the turn solver.
+#[derive(Args)]
+struct StreetSizingArgs {
+    /// Turn bet sizes, comma-separated pot fractions, geoN or allin (e.g., "0.5,1.5,geo2,allin"; "none" = check only)
+    #[arg(long)]
+    turn_bet_sizes: Option<String>,
+    /// River bet sizes, comma-separated pot fractions or allin (e.g., "0.33,0.67,1.0"; "none" = check only)
+    #[arg(long)]
+    river_bet_sizes: Option<String>,
+    /// Turn raise sizes, fractions of the pot after calling or multiples of the bet faced (e.g., "1.0" or "3x")
+    #[arg(long)]
+    turn_raise_sizes: Option<String>,
+    /// River raise sizes, fractions of the pot after calling or multiples of the bet faced (e.g., "0.5,1.0" or "2.5x")
+    #[arg(long)]
+    river_raise_sizes: Option<String>,
+    /// Maximum raises on the turn
+    #[arg(long)]
+    turn_max_raises: Option<usize>,
+    /// Maximum raises on the river
+    #[arg(long)]
+    river_max_raises: Option<usize>,
+}
+
+#[derive(clap::Args, Debug, Clone, Default)]
+struct PlayerSizingArgs {
+    /// OOP bet sizes, overriding the shared sizes for OOP only (e.g., "0.33,0.75"; "none" = check only)
+    #[arg(long)]
+    oop_bet_sizes: Option<String>,
+    /// IP bet sizes, overriding the shared sizes for IP only (e.g., "0.5,1.5,allin"; "none" = check only)
+    #[arg(long)]
+    ip_bet_sizes: Option<String>,
+    /// OOP may only call or fold when facing a bet
+    #[arg(long)]
+    no_oop_raises: bool,
+    /// IP may only call or fold when facing a bet
+    #[arg(long)]
+    no_ip_raises: bool,
+}
+
+impl PlayerSizingArgs {
+    /// Replace each player's entry in `bet_sizes` when its flag was given.
+    fn apply(&self, bet_sizes: &mut [Vec<crate::postflop_tree::BetSize>; 2]) -> Result<(), String> {
+        for (slot, flag) in bet_sizes.iter_mut().zip([&self.oop_bet_sizes, &self.ip_bet_sizes]) {
+            if let Some(sizes) = flag {
+                *slot = parse_bet_sizes(sizes)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Which players may raise, `[oop, ip]`.
+    fn can_raise(&self) -> [bool; 2] {
+        [!self.no_oop_raises, !self.no_ip_raises]
+    }
+}
+
+#[derive(clap::Args, Debug, Clone, Default)]
+struct AlgorithmArgs {
+    /// Traversal: cfr (whole ranges every iteration) or mccfr (external sampling)
+    #[arg(long, default_value = "cfr")]
+    algo: crate::cfr::SolverAlgorithm,
+    /// Seed for sampling, for reproducible solves
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+#[derive(clap::Args, Debug, Clone, Default)]
+struct CheckpointArgs {
+    /// Save a checkpoint under ~/.gto-cli/solver every N iterations
+    #[arg(long)]
+    checkpoint_every: Option<usize>,
+    /// Continue from this spot's checkpoint instead of starting over
+    #[arg(long)]
+    resume: bool,
+}
+
+#[derive(clap::Args, Debug, Clone, Default)]
+struct AllInArgs {
+    /// Offer an extra all-in only when the stack exceeds this fraction of the pot
+    #[arg(long)]
+    allin_threshold: Option<f64>,
+    /// Turn bets and raises above this percentage of the stack into all-ins (e.g., 90)
+    #[arg(long)]
+    force_allin_above_stack_pct: Option<f64>,
+}
+
+impl AllInArgs {
+    /// Validate the flags and write the given ones into a solver config.
+    fn apply(&self, threshold: &mut f64, force_pct: &mut Option<f64>) -> Result<(), String> {
+        if let Some(t) = self.allin_threshold {
+            if !t.is_finite() || t < 0.0 {
+                return Err(format!("All-in threshold must be a non-negative pot fraction, got {}", t));
+            }
+            *threshold = t;
+        }
+        if let Some(pct) = self.force_allin_above_stack_pct {
+            if !(pct > 0.0 && pct <= 100.0) {
+                return Err(format!("Forced all-in percentage must be in (0, 100], got {}", pct));
+            }
+            *force_pct = Some(pct);
+        }
+        Ok(())
+    }
+}
+
+/// Parse a comma-separated list of bet sizes: pot fractions ("0.5", "150%"),
+/// "geo2"/"geo3" and "allin". An empty list or "none" disables betting.
+fn parse_bet_sizes(input: &str) -> Result<Vec<crate::postflop_tree::BetSize>, String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() || trimmed.eq_ignore_ascii_case("none") {
+        return Ok(Vec::new());
+    }
+    trimmed.split(',').map(str::parse).collect()
+}
+
 fn validate_position(pos: &str, table_size: &str) -> Result<String, String> {
     let pos = pos.to_uppercase();
     let valid = if table_size == "9max" {
@@ -410,7 +941,31 @@ pub fn run_with_args(args: Vec<String>) {
     dispatch(cli);
 }
 
-fn dispatch(cli: Cli) {
+fn dispatch(mut cli: Cli) {
+    // Piped or redirected output gets plain text
+    if !std::io::stdout().is_terminal() {
+        colored::control::set_override(false);
+    }
+
+    if let Err(e) = resolve_named_ranges(&mut cli.command) {
+        print_error(&e);
+        return;
+    }
+
+    let mut cache_limit = None;
+    if let Commands::Solve { evaluator, clean_cache, .. } = &cli.command {
+        if let Some(size) = clean_cache {
+            match parse_memory_size(size) {
+                Ok(bytes) => cache_limit = Some(bytes as u64),
+                Err(e) => {
+                    print_error(&e);
+                    return;
+                }
+            }
+        }
+        select_evaluator(evaluator.clone());
+    }
+
     match cli.command {
         Commands::Range {
             position,
@@ -431,9 +986,62 @@ fn dispatch(cli: Cli) {
             hand1,
             versus,
             hand2,
+            others,
             board,
             sims,
-        } => cmd_equity(hand1, versus, hand2, board, sims),
+            unweighted,
+            threads,
+            game,
+            verbose,
+            breakdown,
+            combos,
+            runouts,
+            exact,
+            mc,
+            precision,
+            max_sims,
+            hero,
+            villain,
+        } => {
+            use crate::equity::{EquityOptions, Precision, Sampling};
+            let sampling = match (exact, mc) {
+                (true, _) => Sampling::Exact,
+                (_, true) => Sampling::MonteCarlo,
+                _ => Sampling::Auto,
+            };
+            let precision = precision.map(|half_width| Precision { half_width, max_simulations: max_sims });
+            let options = EquityOptions { sampling, breakdown, per_combo: combos, precision };
+            match (hand1, hero, villain) {
+                (_, Some(hero), Some(villain)) => {
+                    cmd_equity_ranges(&hero, &villain, board, sims, threads, game, &options)
+                }
+                (Some(hand1), ..)
+                    if [&versus, &hand2].into_iter().flatten().chain(&others).filter(|h| !is_vs(h)).count() > 1 =>
+                {
+                    let opponents: Vec<String> =
+                        versus.into_iter().chain(hand2).chain(others).filter(|h| !is_vs(h)).collect();
+                    if game != Game::Holdem {
+                        print_error("Multiway equity is hold'em only");
+                    } else {
+                        cmd_equity_multiway(&hand1, &opponents, board, sims, threads);
+                    }
+                }
+                (Some(hand1), ..) if runouts => {
+                    match versus.into_iter().chain(hand2).find(|h| !is_vs(h)) {
+                        Some(villain) if game == Game::Holdem => {
+                            cmd_equity_runouts(&hand1, &villain, board, sims, threads)
+                        }
+                        Some(_) => print_error("--runouts is hold'em only"),
+                        None => print_error("Usage: gto equity <hand> vs <hand|range> --board <flop or turn> --runouts"),
+                    }
+                }
+                (Some(hand1), ..) => {
+                    cmd_equity(hand1, versus, hand2, board, sims, unweighted, threads, game, verbose, options)
+                }
+                _ => print_error("Usage: gto equity <hand1> vs <hand2|range>, or --hero <range> --villain <range>"),
+            }
+        }
+        Commands::Matchup { hand1, hand2 } => cmd_matchup(&hand1, &hand2),
         Commands::Odds {
             pot,
             bet,
@@ -484,6 +1092,13 @@ fn dispatch(cli: Cli) {
             pot_size,
         } => cmd_spr(stack_size, pot_size),
         Commands::Combos { range_str } => cmd_combos(range_str),
+        Commands::Blockers {
+            hand,
+            range,
+            shared,
+            top,
+        } => cmd_blockers(&hand, &range, shared.as_deref(), top),
+        Commands::SaveRange { name, range_str } => cmd_save_range(name, range_str),
         Commands::Bluff { pot, bet } => cmd_bluff(pot, bet),
         Commands::Query {
             hand,
@@ -493,14 +1108,25 @@ fn dispatch(cli: Cli) {
             pot,
             stack,
             iterations,
-        } => cmd_query(hand, position, vs, board, pot, stack, iterations),
+            simplify,
+        } => cmd_query(hand, position, vs, board, pot, stack, iterations, simplify),
         Commands::Play => crate::play::play_command(),
-        Commands::Solve { solver } => match solver {
+        Commands::Solve { solver, .. } => match solver {
             SolverCommands::Pushfold {
                 stack,
                 rake,
                 iterations,
-            } => cmd_solve_pushfold(stack, rake, iterations),
+                stacks,
+                payouts,
+            } => match (stacks, payouts) {
+                (Some(stacks), Some(payouts)) => cmd_solve_pushfold_icm(&stacks, &payouts, iterations),
+                _ => cmd_solve_pushfold(stack, rake, iterations),
+            },
+            SolverCommands::Shove {
+                stacks,
+                ante,
+                iterations,
+            } => cmd_solve_shove(&stacks, ante, iterations),
             SolverCommands::Preflop {
                 table_size,
                 stack,
@@ -514,7 +1140,46 @@ fn dispatch(cli: Cli) {
                 pot,
                 stack,
                 iterations,
-            } => cmd_solve_river(board, oop, ip, pot, stack, iterations),
+                cfr,
+                precision,
+                algorithm,
+                checkpoint,
+                convergence_every,
+                bet_sizes,
+                raise_sizes,
+                player_sizing,
+                allin,
+                max_memory,
+                verbose,
+                simplify,
+                prune,
+                buckets,
+                format,
+                out,
+            } => cmd_solve_river(
+                board,
+                oop,
+                ip,
+                pot,
+                stack,
+                iterations,
+                cfr,
+                precision,
+                algorithm,
+                checkpoint,
+                convergence_every,
+                bet_sizes,
+                raise_sizes,
+                player_sizing,
+                allin,
+                max_memory,
+                verbose,
+                simplify,
+                prune,
+                buckets,
+                format,
+                out,
+            ),
             SolverCommands::Turn {
                 board,
                 oop,
@@ -522,7 +1187,38 @@ fn dispatch(cli: Cli) {
                 pot,
                 stack,
                 iterations,
-            } => cmd_solve_turn(board, oop, ip, pot, stack, iterations),
+                threads,
+                precision,
+                algorithm,
+                checkpoint,
+                convergence_every,
+                sizing,
+                player_sizing,
+                allin,
+                max_memory,
+                verbose,
+                format,
+                out,
+            } => cmd_solve_turn(
+                board,
+                oop,
+                ip,
+                pot,
+                stack,
+                iterations,
+                threads,
+                precision,
+                algorithm,
+                checkpoint,
+                convergence_every,
+                sizing,
+                player_sizing,
+                allin,
+                max_memory,
+                verbose,
+                format,
+                out,
+            ),
             SolverCommands::Flop {
                 board,
                 oop,
@@ -530,7 +1226,23 @@ fn dispatch(cli: Cli) {
                 pot,
                 stack,
                 iterations,
-            } => cmd_solve_flop(board, oop, ip, pot, stack, iterations),
+                bucketing,
+                buckets,
+                no_bucket_cache,
+                no_buckets,
+                format,
+                out,
+            } => {
+                crate::bucketing::set_bucket_cache_enabled(!no_bucket_cache);
+                let bucketing = if no_buckets {
+                    crate::bucketing::BucketingMode::Unabstracted
+                } else {
+                    bucketing.mode()
+                };
+                cmd_solve_flop(
+                    board, oop, ip, pot, stack, iterations, bucketing, buckets, format, out,
+                )
+            }
             SolverCommands::Batch {
                 stack,
                 srp_only,
@@ -539,7 +1251,88 @@ fn dispatch(cli: Cli) {
                 all_flops,
             } => crate::batch::run_batch_solve(stack, srp_only, limit, iterations, all_flops),
         },
+        Commands::Flops { subset } => cmd_flops(subset),
+        Commands::SolveHh {
+            file,
+            oop,
+            ip,
+            iterations,
+        } => cmd_solve_hh(&file, &oop, &ip, iterations),
+        Commands::Diff { old, new, threshold } => cmd_diff(&old, &new, threshold),
+        Commands::Walk { solution } => cmd_walk(&solution),
+        Commands::Tree {
+            pot,
+            stack,
+            bet_sizes,
+            max_raises,
+            board,
+            depth,
+            format,
+        } => cmd_tree(pot, stack, &bet_sizes, max_raises, board.as_deref(), depth, format),
+        Commands::FlopReport {
+            flops,
+            subset,
+            oop,
+            ip,
+            pot,
+            stack,
+            iterations,
+            bucketing,
+            buckets,
+            jobs,
+            output,
+            no_bucket_cache,
+        } => {
+            crate::bucketing::set_bucket_cache_enabled(!no_bucket_cache);
+            let config = crate::flop_report::FlopReportConfig {
+                oop_range: oop,
+                ip_range: ip,
+                starting_pot: pot,
+                effective_stack: stack,
+                iterations,
+                bucketing: bucketing.mode(),
+                num_buckets: buckets,
+                jobs,
+            };
+            cmd_flop_report(config, flops, subset, output)
+        }
+    }
+
+    if let Some(max_bytes) = cache_limit {
+        cmd_clean_cache(max_bytes);
+    }
+}
+
+/// Replace `@name` range arguments with the contents of
+/// `~/.gto-cli/ranges/<name>.txt` before any command sees them.
+fn resolve_named_ranges(command: &mut Commands) -> Result<(), String> {
+    let ranges: Vec<&mut String> = match command {
+        Commands::Combos { range_str } => vec![range_str],
+        Commands::Equity {
+            versus,
+            hand2,
+            others,
+            hero,
+            villain,
+            ..
+        } => [versus, hand2, hero, villain].into_iter().flat_map(Option::as_mut).chain(others).collect(),
+        Commands::Blockers { range, shared, .. } => std::iter::once(range).chain(shared.as_mut()).collect(),
+        Commands::FlopReport { oop, ip, .. }
+        | Commands::Solve {
+            solver:
+                SolverCommands::River { oop, ip, .. }
+                | SolverCommands::Turn { oop, ip, .. }
+                | SolverCommands::Flop { oop, ip, .. },
+            ..
+        } => vec![oop, ip],
+        _ => vec![],
+    };
+    for range in ranges {
+        if let Some(name) = range.strip_prefix('@') {
+            *range = crate::ranges::read_named_range(name).map_err(|e| e.to_string())?;
+        }
     }
+    Ok(())
 }
 
 fn cmd_range(position: String, table_size: &str, vs: Option<String>, situation: Situation) {
@@ -878,15 +1671,24 @@ fn cmd_range_solved(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn cmd_equity(
     hand1: String,
     versus: Option<String>,
     hand2: Option<String>,
     board: Option<String>,
     sims: usize,
+    unweighted: bool,
+    threads: usize,
+    game: Game,
+    verbose: bool,
+    options: crate::equity::EquityOptions,
 ) {
     use crate::cards::parse_card;
-    use crate::equity::{equity_vs_hand, equity_vs_range};
+    use crate::equity::{
+        equity_vs_hand_short_deck, equity_vs_hand_with_options, equity_vs_range_short_deck,
+        equity_vs_range_with_options, in_pool,
+    };
     use crate::ranges::parse_range;
 
     // Handle "gto equity AhAs vs KsKd" or "gto equity AhAs KsKd"
@@ -936,6 +1738,15 @@ fn cmd_equity(
         cards
     };
 
+    if game == Game::Omaha {
+        if let Some(flags) = unsupported_equity_flags(&options) {
+            print_error(&format!("{} not supported for Omaha", flags));
+            return;
+        }
+        cmd_equity_omaha(&h1, &hand1, &hand2, board_cards.as_deref(), sims, threads);
+        return;
+    }
+
     // Try parsing hand2 as specific cards first
     let is_range = hand2.len() != 4 || {
         let chars: Vec<char> = hand2.chars().collect();
@@ -969,45 +1780,33 @@ fn cmd_equity(
             hand2.bold(),
             board_str
         );
-        println!("  Running {} simulations...\n", format!("{}", sims).bold());
-
-        match equity_vs_range(
-            &h1,
-            &villain_range,
-            board_cards.as_deref(),
-            sims,
-        ) {
-            Ok(result) => {
-                println!("  Hero:    {}", equity_bar(result.equity(), 30));
-                println!("  Villain: {}", equity_bar(1.0 - result.equity(), 30));
-                println!();
-
-                let mut table = Table::new();
-                table.set_content_arrangement(ContentArrangement::Dynamic);
-                table.set_header(vec![Cell::new(""), Cell::new("")]);
-                table.add_row(vec![
-                    Cell::new("Win".bold().to_string()),
-                    Cell::new(format!("{:.1}%", result.win * 100.0)),
-                ]);
-                table.add_row(vec![
-                    Cell::new("Tie".bold().to_string()),
-                    Cell::new(format!("{:.1}%", result.tie * 100.0)),
-                ]);
-                table.add_row(vec![
-                    Cell::new("Lose".bold().to_string()),
-                    Cell::new(format!("{:.1}%", result.lose * 100.0)),
-                ]);
-                table.add_row(vec![
-                    Cell::new("Equity".bold().to_string()),
-                    Cell::new(format!("{:.1}%", result.equity() * 100.0).bold().to_string()),
-                ]);
-                table.add_row(vec![
-                    Cell::new("Sims".bold().to_string()),
-                    Cell::new(format!("{}", result.simulations)),
-                ]);
-                println!("{}", table);
-                println!();
-            }
+        print_running(sims, &options);
+
+        let result = if game == Game::ShortDeck {
+            in_pool(threads, || {
+                equity_vs_range_short_deck(
+                    &h1,
+                    &villain_range,
+                    board_cards.as_deref(),
+                    sims,
+                    !unweighted,
+                    options,
+                )
+            })
+        } else {
+            in_pool(threads, || {
+                equity_vs_range_with_options(
+                    &h1,
+                    &villain_range,
+                    board_cards.as_deref(),
+                    sims,
+                    !unweighted,
+                    options,
+                )
+            })
+        };
+        match result {
+            Ok(result) => print_equity_result(&result),
             Err(e) => print_error(&e.to_string()),
         }
     } else {
@@ -1027,49 +1826,395 @@ fn cmd_equity(
             hand2.bold(),
             board_str
         );
-        println!("  Running {} simulations...\n", format!("{}", sims).bold());
-
-        match equity_vs_hand(&h1, &h2, board_cards.as_deref(), sims) {
-            Ok(result) => {
-                println!("  Hero:    {}", equity_bar(result.equity(), 30));
-                println!("  Villain: {}", equity_bar(1.0 - result.equity(), 30));
-                println!();
+        print_running(sims, &options);
 
-                let mut table = Table::new();
-                table.set_content_arrangement(ContentArrangement::Dynamic);
-                table.set_header(vec![Cell::new(""), Cell::new("")]);
-                table.add_row(vec![
-                    Cell::new("Win".bold().to_string()),
-                    Cell::new(format!("{:.1}%", result.win * 100.0)),
-                ]);
-                table.add_row(vec![
-                    Cell::new("Tie".bold().to_string()),
-                    Cell::new(format!("{:.1}%", result.tie * 100.0)),
-                ]);
-                table.add_row(vec![
-                    Cell::new("Lose".bold().to_string()),
-                    Cell::new(format!("{:.1}%", result.lose * 100.0)),
-                ]);
-                table.add_row(vec![
-                    Cell::new("Equity".bold().to_string()),
-                    Cell::new(format!("{:.1}%", result.equity() * 100.0).bold().to_string()),
-                ]);
-                table.add_row(vec![
-                    Cell::new("Sims".bold().to_string()),
-                    Cell::new(format!("{}", result.simulations)),
-                ]);
-                println!("{}", table);
-                println!();
-            }
+        let result = if game == Game::ShortDeck {
+            in_pool(threads, || {
+                equity_vs_hand_short_deck(&h1, &h2, board_cards.as_deref(), sims, options)
+            })
+        } else {
+            in_pool(threads, || {
+                equity_vs_hand_with_options(&h1, &h2, board_cards.as_deref(), sims, options)
+            })
+        };
+        match result {
+            Ok(result) => print_equity_result(&result),
             Err(e) => print_error(&e.to_string()),
         }
     }
+
+    // Short-deck categories rank differently; the best five follow hold'em rules
+    if verbose && game == Game::Holdem {
+        if let Some(best) = board_cards.as_deref().and_then(|bc| best_hand_display(&h1, bc)) {
+            println!("  Your best hand: {}", best);
+            println!();
+        }
+    }
 }
 
-fn cmd_odds(pot: f64, bet: f64, equity_val: Option<f64>, future: Option<f64>) {
-    use crate::math_engine::{ev, implied_odds, pot_odds};
+/// Omaha equity: `hand2` is one 4-card hand or comma-separated 4-card combos.
+fn cmd_equity_omaha(
+    h1: &[crate::cards::Card],
+    hand1: &str,
+    hand2: &str,
+    board: Option<&[crate::cards::Card]>,
+    sims: usize,
+    threads: usize,
+) {
+    use crate::equity::{equity_vs_hand_omaha, equity_vs_range_omaha, in_pool};
 
-    let needed = match pot_odds(pot, bet) {
+    let board_str = board.map_or(String::new(), |bc| format!(" on {}", board_display(bc)));
+    println!();
+    println!("  {} vs {}{} (Omaha)", hand1.bold(), hand2.bold(), board_str);
+    println!("  Running {} simulations...\n", format!("{}", sims).bold());
+
+    let result = match parse_board(hand2) {
+        Ok(h2) if !hand2.contains(',') => {
+            in_pool(threads, || equity_vs_hand_omaha(h1, &h2, board, sims))
+        }
+        _ => {
+            let range: Vec<String> = hand2
+                .split(',')
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .collect();
+            in_pool(threads, || equity_vs_range_omaha(h1, &range, board, sims))
+        }
+    };
+    match result {
+        Ok(result) => print_equity_result(&result),
+        Err(e) => print_error(&e.to_string()),
+    }
+}
+
+fn cmd_matchup(hand1: &str, hand2: &str) {
+    use crate::game_tree::{preflop_equity, MATRIX_SAMPLES};
+
+    let equity = match preflop_equity(hand1, hand2) {
+        Ok(eq) => eq,
+        Err(e) => {
+            print_error(&e.to_string());
+            return;
+        }
+    };
+
+    println!();
+    println!("  {} vs {}", hand1.bold(), hand2.bold());
+    println!("  {:<5} {}", hand1, equity_bar(equity, 30));
+    println!("  {:<5} {}", hand2, equity_bar(1.0 - equity, 30));
+    println!(
+        "  {}",
+        format!("All-in preflop, {} runouts per matchup", MATRIX_SAMPLES).dimmed()
+    );
+    println!();
+}
+
+fn cmd_equity_ranges(
+    hero: &str,
+    villain: &str,
+    board: Option<String>,
+    sims: usize,
+    threads: usize,
+    game: Game,
+    options: &crate::equity::EquityOptions,
+) {
+    use crate::equity::{in_pool, equity_range_vs_range};
+    use crate::ranges::parse_range;
+
+    if game != Game::Holdem {
+        print_error("--hero/--villain equity is hold'em only");
+        return;
+    }
+    if let Some(flags) = unsupported_equity_flags(options) {
+        print_error(&format!("{} not supported with --hero/--villain", flags));
+        return;
+    }
+
+    let board_cards = match board.as_deref().map(parse_board).transpose() {
+        Ok(cards) => cards,
+        Err(e) => {
+            print_error(&e.to_string());
+            return;
+        }
+    };
+    let (hero_range, villain_range) = (parse_range(hero), parse_range(villain));
+
+    println!();
+    let board_str = board_cards
+        .as_deref()
+        .map(|bc| format!(" on {}", board_display(bc)))
+        .unwrap_or_default();
+    println!("  {} vs {}{}", hero.bold(), villain.bold(), board_str);
+    if board_cards.as_ref().is_none_or(|bc| bc.len() < 5) {
+        println!("  Running {} simulations...\n", format!("{}", sims).bold());
+    } else {
+        println!("  Enumerating every combo pairing...\n");
+    }
+
+    let result = match in_pool(threads, || {
+        equity_range_vs_range(&hero_range, &villain_range, board_cards.as_deref(), sims)
+    }) {
+        Ok(r) => r,
+        Err(e) => {
+            print_error(&e.to_string());
+            return;
+        }
+    };
+
+    println!("  Hero:    {}", equity_bar(result.equity, 30));
+    println!("  Villain: {}", equity_bar(1.0 - result.equity, 30));
+    println!();
+
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec![
+        Cell::new("Combo".bold().to_string()),
+        Cell::new("Equity").set_alignment(CellAlignment::Right),
+        Cell::new("Villain combos").set_alignment(CellAlignment::Right),
+    ]);
+    for combo in result.sorted_by_equity() {
+        let (c1, c2) = combo.combo;
+        table.add_row(vec![
+            Cell::new(format!("{}{}", c1.pretty(), c2.pretty())),
+            Cell::new(format!("{:.1}%", combo.equity * 100.0)).set_alignment(CellAlignment::Right),
+            Cell::new(format!("{}", combo.villain_combos)).set_alignment(CellAlignment::Right),
+        ]);
+    }
+    println!("{}", table);
+    println!(
+        "  {} hero combos  |  {}",
+        result.combos.len(),
+        if result.exact { "exact" } else { "sampled" }
+    );
+    println!();
+}
+
+/// Percentiles and a histogram of hero's river equity over the runouts.
+fn cmd_equity_runouts(hand: &str, villain: &str, board: Option<String>, sims: usize, threads: usize) {
+    use crate::equity::{in_pool, runout_equity_distribution};
+    use crate::ranges::parse_range;
+
+    const BINS: usize = 10;
+    const BAR_WIDTH: usize = 40;
+
+    let Some(board) = board else {
+        print_error("--runouts needs a flop or turn --board");
+        return;
+    };
+    let (hand_cards, board_cards) = match (parse_board(hand), parse_board(&board)) {
+        (Ok(h), Ok(b)) => (h, b),
+        (Err(e), _) | (_, Err(e)) => {
+            print_error(&e.to_string());
+            return;
+        }
+    };
+    let villain_range = parse_range(villain);
+
+    println!();
+    println!("  {} vs {} on {}", hand.bold(), villain.bold(), board_display(&board_cards));
+    let dist = match in_pool(threads, || runout_equity_distribution(&hand_cards, &villain_range, &board_cards, sims)) {
+        Ok(dist) => dist,
+        Err(e) => {
+            print_error(&e.to_string());
+            return;
+        }
+    };
+    println!(
+        "  {} {} runouts",
+        if dist.exact { "Enumerated" } else { "Sampled" },
+        dist.equities.len()
+    );
+    println!();
+
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec!["Mean", "Min", "25th", "Median", "75th", "Max"]);
+    table.add_row(
+        [dist.mean, dist.min, dist.p25, dist.median, dist.p75, dist.max]
+            .iter()
+            .map(|e| Cell::new(format!("{:.1}%", e * 100.0)).set_alignment(CellAlignment::Right)),
+    );
+    println!("{}", table);
+    println!();
+
+    let histogram = dist.histogram(BINS);
+    let tallest = histogram.iter().cloned().fold(0.0, f64::max);
+    for (i, share) in histogram.iter().enumerate().rev() {
+        let lo = i * 100 / BINS;
+        let filled = if tallest > 0.0 { (share / tallest * BAR_WIDTH as f64).round() as usize } else { 0 };
+        let bar = "\u{2588}".repeat(filled);
+        let bar = match lo {
+            60.. => bar.green(),
+            40.. => bar.yellow(),
+            _ => bar.red(),
+        };
+        let label = format!("{}-{}%", lo, lo + 100 / BINS);
+        println!("  {:>7} {:<width$} {:>5.1}%", label, bar, share * 100.0, width = BAR_WIDTH);
+    }
+    println!();
+}
+
+/// The optional "vs" between equity hands.
+fn is_vs(arg: &str) -> bool {
+    arg.eq_ignore_ascii_case("vs")
+}
+
+fn cmd_equity_multiway(hand1: &str, opponents: &[String], board: Option<String>, sims: usize, threads: usize) {
+    use crate::equity::in_pool;
+    use crate::multiway::{multiway_equity, PlayerSpec};
+
+    let board_cards = match board.as_deref().map(parse_board).transpose() {
+        Ok(cards) => cards,
+        Err(e) => {
+            print_error(&e.to_string());
+            return;
+        }
+    };
+    let names: Vec<&str> = std::iter::once(hand1).chain(opponents.iter().map(String::as_str)).collect();
+    let players: Vec<PlayerSpec> = names.iter().map(|name| PlayerSpec::parse(name)).collect();
+
+    println!();
+    let board_str = board_cards
+        .as_deref()
+        .map(|bc| format!(" on {}", board_display(bc)))
+        .unwrap_or_default();
+    let matchup: Vec<String> = names.iter().map(|name| name.bold().to_string()).collect();
+    println!("  {}{}", matchup.join(" vs "), board_str);
+    println!("  Running {} simulations...\n", format!("{}", sims).bold());
+
+    let result = match in_pool(threads, || multiway_equity(&players, board_cards.as_deref(), sims)) {
+        Ok(r) => r,
+        Err(e) => {
+            print_error(&e.to_string());
+            return;
+        }
+    };
+
+    let width = names.iter().map(|name| name.len()).max().unwrap_or(0);
+    for (name, share) in names.iter().zip(&result.players) {
+        println!("  {:<width$}  {}", name, equity_bar(share.equity, 30), width = width);
+    }
+    println!();
+
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec![
+        Cell::new("Player".bold().to_string()),
+        Cell::new("Win").set_alignment(CellAlignment::Right),
+        Cell::new("Tie").set_alignment(CellAlignment::Right),
+        Cell::new("Equity").set_alignment(CellAlignment::Right),
+    ]);
+    for (name, share) in names.iter().zip(&result.players) {
+        table.add_row(vec![
+            Cell::new(name),
+            Cell::new(format!("{:.1}%", share.win * 100.0)).set_alignment(CellAlignment::Right),
+            Cell::new(format!("{:.1}%", share.tie * 100.0)).set_alignment(CellAlignment::Right),
+            Cell::new(format!("{:.1}%", share.equity * 100.0).bold().to_string())
+                .set_alignment(CellAlignment::Right),
+        ]);
+    }
+    println!("{}", table);
+    println!("  {} simulations  |  ties split among the tied hands", result.simulations);
+    println!();
+}
+
+/// The equity option flags set in `options`, for paths that take none of
+/// them (e.g. "--exact, --combos"). `None` when none are set.
+fn unsupported_equity_flags(options: &crate::equity::EquityOptions) -> Option<String> {
+    use crate::equity::Sampling;
+
+    let flags: Vec<&str> = [
+        (options.sampling == Sampling::Exact, "--exact"),
+        (options.sampling == Sampling::MonteCarlo, "--mc"),
+        (options.breakdown, "--breakdown"),
+        (options.per_combo, "--combos"),
+        (options.precision.is_some(), "--precision"),
+    ]
+    .into_iter()
+    .filter_map(|(set, flag)| set.then_some(flag))
+    .collect();
+    (!flags.is_empty()).then(|| flags.join(", "))
+}
+
+/// What an equity run is about to do: a fixed number of simulations, or
+/// sampling to a precision.
+fn print_running(sims: usize, options: &crate::equity::EquityOptions) {
+    match options.precision {
+        Some(p) => println!(
+            "  Sampling to ±{}% (at most {} simulations)...\n",
+            format!("{}", p.half_width).bold(),
+            p.max_simulations
+        ),
+        None => println!("  Running {} simulations...\n", format!("{}", sims).bold()),
+    }
+}
+
+/// Equity bars plus the win/tie/lose table for an equity result.
+fn print_equity_result(result: &crate::equity::EquityResult) {
+    println!("  Hero:    {}", equity_bar(result.equity(), 30));
+    println!("  Villain: {}", equity_bar(1.0 - result.equity(), 30));
+    println!();
+
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec![Cell::new(""), Cell::new("")]);
+    table.add_row(vec![
+        Cell::new("Win".bold().to_string()),
+        Cell::new(format!("{:.1}%", result.win * 100.0)),
+    ]);
+    table.add_row(vec![
+        Cell::new("Tie".bold().to_string()),
+        Cell::new(format!("{:.1}%", result.tie * 100.0)),
+    ]);
+    table.add_row(vec![
+        Cell::new("Lose".bold().to_string()),
+        Cell::new(format!("{:.1}%", result.lose * 100.0)),
+    ]);
+    table.add_row(vec![
+        Cell::new("Equity".bold().to_string()),
+        Cell::new(format!("{:.1}%", result.equity() * 100.0).bold().to_string()),
+    ]);
+    if !result.exact {
+        let (low, high) = result.ci95();
+        table.add_row(vec![
+            Cell::new("95% CI".bold().to_string()),
+            Cell::new(format!("{:.1}% - {:.1}%", low * 100.0, high * 100.0)),
+        ]);
+    }
+    table.add_row(vec![
+        Cell::new("Sims".bold().to_string()),
+        Cell::new(if result.exact {
+            format!("{} (exact)", result.simulations)
+        } else {
+            format!("{}", result.simulations)
+        }),
+    ]);
+    println!("{}", table);
+    println!();
+
+    if let Some(ref breakdown) = result.category_breakdown {
+        for line in breakdown.to_string().lines() {
+            println!("  {}", line);
+        }
+        println!();
+    }
+
+    if let Some(ref combos) = result.villain_combos {
+        let mut buckets = String::new();
+        let _ = crate::equity::write_combo_buckets(&mut buckets, combos);
+        for line in buckets.lines() {
+            let (label, list) = line.split_once(": ").unwrap_or((line, ""));
+            println!("  {}", label.bold());
+            println!("    {}", list);
+        }
+        println!();
+    }
+}
+
+fn cmd_odds(pot: f64, bet: f64, equity_val: Option<f64>, future: Option<f64>) {
+    use crate::math_engine::{ev, implied_odds, pot_odds};
+
+    let needed = match pot_odds(pot, bet) {
         Ok(v) => v,
         Err(e) => {
             print_error(&e.to_string());
@@ -1144,6 +2289,7 @@ fn cmd_odds(pot: f64, bet: f64, equity_val: Option<f64>, future: Option<f64>) {
 }
 
 fn cmd_board(cards: String) {
+    use crate::board_texture::classify_board;
     use crate::postflop::{analyze_board, cbet_recommendation};
 
     let board_cards = match parse_board(&cards) {
@@ -1207,6 +2353,22 @@ fn cmd_board(cards: String) {
             Cell::new(texture.draws.join(", ")),
         ]);
     }
+    if board_cards.len() <= 5 {
+        let class = classify_board(&crate::card_encoding::cards_to_indices(&board_cards));
+        let yes_no = |b: bool| if b { "Yes" } else { "No" };
+        table.add_row(vec![
+            Cell::new("Class".bold().to_string()),
+            Cell::new(class.summary()),
+        ]);
+        table.add_row(vec![
+            Cell::new("Flush Possible".bold().to_string()),
+            Cell::new(yes_no(class.flush_possible)),
+        ]);
+        table.add_row(vec![
+            Cell::new("Straight Possible".bold().to_string()),
+            Cell::new(yes_no(class.straight_possible)),
+        ]);
+    }
     println!("{}", table);
     println!();
 
@@ -1602,8 +2764,59 @@ fn cmd_spr(stack_size: f64, pot_size: f64) {
     }
 }
 
+fn cmd_blockers(hand: &str, range_str: &str, shared: Option<&str>, top: usize) {
+    use crate::ranges::{combos_in_common, parse_range, range_blocker_report};
+
+    let villain = parse_range(range_str);
+    let report = match range_blocker_report(hand, &villain) {
+        Ok(r) => r,
+        Err(e) => {
+            print_error(&e.to_string());
+            return;
+        }
+    };
+
+    println!();
+    println!(
+        "  {} blocks {} of {} villain combos ({} left)",
+        hand.bold(),
+        report.removed_combos.to_string().bold(),
+        report.total_combos,
+        report.remaining_combos()
+    );
+    for (card, removed) in &report.removed_by_card {
+        println!("    {}  {} combos", card.pretty(), removed);
+    }
+
+    if !report.affected.is_empty() {
+        println!();
+        let mut table = Table::new();
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        table.set_header(vec![
+            Cell::new("Hand".bold().to_string()),
+            Cell::new("Blocked").set_alignment(CellAlignment::Right),
+            Cell::new("Left").set_alignment(CellAlignment::Right),
+        ]);
+        for (class, removed, combos) in report.affected.iter().take(top) {
+            table.add_row(vec![
+                Cell::new(class.bold().to_string()),
+                Cell::new(format!("{}/{}", removed, combos)).set_alignment(CellAlignment::Right),
+                Cell::new(format!("{}", combos - removed)).set_alignment(CellAlignment::Right),
+            ]);
+        }
+        println!("{}", table);
+    }
+
+    if let Some(shared) = shared {
+        let common = combos_in_common(&parse_range(shared), &villain);
+        println!();
+        println!("  Combos in common with {}: {}", shared, common.to_string().bold());
+    }
+    println!();
+}
+
 fn cmd_combos(range_str: String) {
-    use crate::ranges::{combo_count, parse_range, range_pct, total_combos};
+    use crate::ranges::{combo_count, parse_range, parse_weighted_range, range_pct, total_combos};
 
     let hands = parse_range(&range_str);
 
@@ -1637,10 +2850,40 @@ fn cmd_combos(range_str: String) {
 
     println!("{}", table);
     println!();
-    println!("{}", range_grid(&hands, &range_str));
+    // Shade by weight; fall back to a plain grid if a weight does not parse
+    match parse_weighted_range(&range_str) {
+        Ok(weighted) => println!("{}", weighted_range_grid(&weighted, &range_str)),
+        Err(_) => println!("{}", range_grid(&hands, &range_str)),
+    }
     println!();
 }
 
+fn cmd_save_range(name: String, range_str: String) {
+    use crate::ranges::{combo_count, parse_weighted_range, save_named_range};
+
+    let hands = match parse_weighted_range(&range_str) {
+        Ok(hands) => hands,
+        Err(e) => {
+            print_error(&e.to_string());
+            return;
+        }
+    };
+    let combos: f64 = hands.iter().map(|(h, w)| combo_count(h) as f64 * w).sum();
+    if combos <= 0.0 {
+        print_error("Range is empty");
+        return;
+    }
+    match save_named_range(&name, &hands) {
+        Ok(path) => println!(
+            "  Saved @{} ({} combos) to {}",
+            name.bold(),
+            combos,
+            path.display()
+        ),
+        Err(e) => print_error(&e.to_string()),
+    }
+}
+
 fn cmd_bluff(pot: f64, bet: f64) {
     use crate::math_engine::{bluff_to_value_ratio, break_even_pct};
 
@@ -1694,6 +2937,7 @@ fn cmd_bluff(pot: f64, bet: f64) {
     println!();
 }
 
+#[allow(clippy::too_many_arguments)]
 fn cmd_query(
     hand: String,
     position: String,
@@ -1702,6 +2946,7 @@ fn cmd_query(
     pot: Option<f64>,
     stack: f64,
     iterations: usize,
+    simplify: Option<f64>,
 ) {
     use crate::preflop_solver::Position;
     use crate::strategy::{
@@ -1731,7 +2976,12 @@ fn cmd_query(
         None => default_villain(hero),
     };
 
+    if !valid_simplify(simplify) {
+        return;
+    }
+
     let mut engine = StrategyEngine::new(stack);
+    engine.simplify = simplify;
 
     let hero_side = if hero.is_ip_vs(&villain) { "IP" } else { "OOP" };
     let villain_str = villain.as_str();
@@ -1803,6 +3053,9 @@ fn cmd_query(
                     } else {
                         println!();
                         println!("  {}", format_strategy(&result));
+                        if let Some(cost) = result.simplification_cost {
+                            println!("  simplification cost: {:.2}% pot", cost);
+                        }
                     }
                     println!();
                 }
@@ -1856,7 +3109,7 @@ fn rank_value(c: char) -> u8 {
 }
 
 fn cmd_solve_pushfold(stack: f64, rake: f64, iterations: usize) {
-    use crate::game_tree::solve_push_fold;
+    use crate::game_tree::{preflop_equity_table, solve_push_fold};
 
     if stack <= 0.0 {
         print_error("Stack must be positive");
@@ -1873,11 +3126,77 @@ fn cmd_solve_pushfold(stack: f64, rake: f64, iterations: usize) {
         stack, rake, iterations
     );
 
-    let result = solve_push_fold(stack, iterations, rake);
+    let result = solve_push_fold(stack, iterations, rake, preflop_equity_table());
+    result.display();
+}
+
+fn cmd_solve_pushfold_icm(stacks: &str, payouts: &str, iterations: usize) {
+    use crate::game_tree::{preflop_equity_table, solve_push_fold_icm};
+    use crate::icm::IcmSpot;
+
+    let parse_list = |input: &str, what: &str| -> Result<Vec<f64>, String> {
+        input
+            .split(',')
+            .map(|v| v.trim().parse::<f64>().map_err(|_| format!("Invalid {} '{}'", what, v.trim())))
+            .collect()
+    };
+    let spot = match parse_list(stacks, "stack")
+        .and_then(|stacks| Ok((stacks, parse_list(payouts, "payout")?)))
+        .and_then(|(stacks, payouts)| IcmSpot::new(stacks, payouts))
+    {
+        Ok(spot) => spot,
+        Err(ref e) => {
+            print_error(e);
+            return;
+        }
+    };
+
+    println!();
+    println!(
+        "  Solving ICM push/fold: SB {}bb vs BB {}bb, {} players, {} paid, {} iterations...",
+        spot.stacks[0],
+        spot.stacks[1],
+        spot.stacks.len(),
+        spot.payouts.len(),
+        iterations
+    );
+
+    let result = solve_push_fold_icm(&spot, iterations, preflop_equity_table());
+    result.display();
+}
+
+fn cmd_solve_shove(stacks: &str, ante: f64, iterations: usize) {
+    use crate::game_tree::preflop_equity_table;
+    use crate::multiway_pushfold::{solve_multiway_push_fold, MultiwaySpot};
+
+    let spot = match stacks
+        .split(',')
+        .map(|v| v.trim().parse::<f64>().map_err(|_| format!("Invalid stack '{}'", v.trim())))
+        .collect::<Result<Vec<f64>, String>>()
+        .and_then(|stacks| MultiwaySpot::new(stacks, ante))
+    {
+        Ok(spot) => spot,
+        Err(ref e) => {
+            print_error(e);
+            return;
+        }
+    };
+
+    println!();
+    println!("  Loading equity matrix...");
+    let table = preflop_equity_table();
+    println!(
+        "  Solving {}-way push/fold, {} deals...",
+        spot.num_players(),
+        iterations
+    );
+
+    let result = solve_multiway_push_fold(&spot, iterations, table);
     result.display();
 }
 
 fn cmd_solve_preflop(table_size: TableSize, stack: f64, rake: f64, iterations: usize) {
+    use crate::game_tree::preflop_equity_table;
     use crate::preflop_solver::solve_preflop_6max;
 
     if stack <= 0.0 {
@@ -1908,7 +3227,12 @@ fn cmd_solve_preflop(table_size: TableSize, stack: f64, rake: f64, iterations: u
     );
     println!();
 
-    let solution = solve_preflop_6max(stack, iterations, rake);
+    // Built once (slow) and cached under ~/.gto-cli, then loaded instantly
+    println!("  Loading equity matrix...");
+    let table = preflop_equity_table();
+    println!("  Equity matrix ready.\n");
+
+    let solution = solve_preflop_6max(stack, iterations, rake, table);
 
     // Display summary table
     println!();
@@ -1957,8 +3281,37 @@ fn cmd_solve_preflop(table_size: TableSize, stack: f64, rake: f64, iterations: u
     println!();
 }
 
-fn cmd_solve_river(board: String, oop: String, ip: String, pot: f64, stack: f64, iterations: usize) {
-    use crate::river_solver::{RiverSolverConfig, solve_river};
+#[allow(clippy::too_many_arguments)]
+fn cmd_solve_river(
+    board: String,
+    oop: String,
+    ip: String,
+    pot: f64,
+    stack: f64,
+    iterations: usize,
+    cfr: crate::cfr::CfrVariant,
+    precision: crate::cfr::Precision,
+    algorithm: AlgorithmArgs,
+    checkpoint: CheckpointArgs,
+    convergence_every: Option<usize>,
+    bet_sizes: Option<String>,
+    raise_sizes: Option<String>,
+    player_sizing: PlayerSizingArgs,
+    allin: AllInArgs,
+    max_memory: Option<String>,
+    verbose: bool,
+    simplify: Option<f64>,
+    prune: Option<usize>,
+    buckets: Option<usize>,
+    format: OutputFormat,
+    out: Option<String>,
+) {
+    use crate::cfr::Precision;
+    use crate::postflop_tree::{estimate_tree, same_for_both, RaiseSizing};
+    use crate::river_solver::{
+        expand_range_to_combos, resume_river, simplify_solution, solve_river_with_progress,
+        Abstraction, RiverCfr, RiverSolverConfig, ShowdownTable,
+    };
 
     if pot <= 0.0 {
         print_error("Pot must be positive");
@@ -1968,28 +3321,386 @@ fn cmd_solve_river(board: String, oop: String, ip: String, pot: f64, stack: f64,
         print_error("Stack must be positive");
         return;
     }
+    if !valid_simplify(simplify) {
+        return;
+    }
+    if buckets.is_some_and(|b| b == 0 || b > u16::MAX as usize) {
+        print_error("Buckets must be between 1 and 65535");
+        return;
+    }
 
-    let config = match RiverSolverConfig::new(&board, &oop, &ip, pot, stack, iterations) {
+    let mut config = match RiverSolverConfig::new(&board, &oop, &ip, pot, stack, iterations) {
         Ok(c) => c,
         Err(ref e) => {
             print_error(e);
             return;
         }
     };
+    config.cfr_variant = cfr;
+    config.precision = precision;
+    config.algorithm = algorithm.algo;
+    config.seed = algorithm.seed;
+    config.checkpoint_every = checkpoint.checkpoint_every;
+    config.convergence_every = convergence_every;
+    config.prune_threshold = prune;
+    if let Some(n) = buckets {
+        config.abstraction = Abstraction::EquityBuckets(n);
+    }
+    let sized = bet_sizes
+        .as_deref()
+        .map(parse_bet_sizes)
+        .transpose()
+        .and_then(|shared| {
+            if let Some(sizes) = shared {
+                config.bet_sizes = same_for_both(sizes);
+            }
+            config.can_raise = player_sizing.can_raise();
+            player_sizing.apply(&mut config.bet_sizes)
+        })
+        .and_then(|()| match raise_sizes.as_deref() {
+            Some(sizes) => {
+                let sizing: RaiseSizing = sizes.parse()?;
+                sizing.validate()?;
+                config.raise_sizes = same_for_both(sizing);
+                Ok(())
+            }
+            None => Ok(()),
+        })
+        .and_then(|()| {
+            allin.apply(&mut config.allin_threshold, &mut config.force_allin_above_stack_pct)
+        });
+    if let Err(e) = sized {
+        print_error(&e);
+        return;
+    }
+
+    let oop_combos = expand_range_to_combos(&config.oop_range, &config.board);
+    let ip_combos = expand_range_to_combos(&config.ip_range, &config.board);
+    // A bucketed solve keeps a row per bucket rather than per combo
+    let rows = |n: usize| buckets.map_or(n, |b| n.min(b));
+    let combos = [rows(oop_combos.len()), rows(ip_combos.len())];
+    let showdown_bytes = ShowdownTable::new(oop_combos, ip_combos, &config.board).memory_bytes();
+    let estimate = estimate_tree(&config.tree_config());
+    let (nodes, entries) = (estimate.action_nodes, estimate.entries(combos));
+    let pruning = config.prune_threshold.is_some();
+    let trainer_bytes = match config.precision {
+        Precision::F32 => RiverCfr::<f32>::estimated_bytes(nodes, entries, pruning),
+        Precision::F64 => RiverCfr::<f64>::estimated_bytes(nodes, entries, pruning),
+    };
+    let memory = trainer_bytes + showdown_bytes;
+    if !check_tree_size(&estimate, memory, max_memory.as_deref(), &format) {
+        return;
+    }
+
+    if format == OutputFormat::Text {
+        println!();
+        println!(
+            "  Solving river: board={}, pot={}, stack={}, {} iterations...",
+            board, pot, stack, iterations
+        );
+        if verbose {
+            println!(
+                "  Memory (estimated): {}",
+                memory_summary(&[("trainer", trainer_bytes), ("showdown table", showdown_bytes)])
+            );
+        }
+    }
+
+    let mut result = if checkpoint.resume {
+        match resume_river(&config, solve_progress(&format)) {
+            Ok(result) => result,
+            Err(e) => {
+                print_error(&e);
+                return;
+            }
+        }
+    } else {
+        solve_river_with_progress(&config, solve_progress(&format))
+    };
+    if verbose && format == OutputFormat::Text {
+        println!(
+            "  Memory: {}",
+            memory_summary(&[("trainer", result.trainer_bytes), ("showdown table", result.showdown_bytes)])
+        );
+    }
+    // The cache keeps the solved strategies; simplification is for display
+    result.save_cache();
+    if let Some(threshold) = simplify {
+        if let Err(e) = simplify_solution(&mut result, threshold) {
+            print_error(&e);
+            return;
+        }
+    }
+    emit_solution(&result, || result.render(), &format, out.as_deref());
+}
+
+fn cmd_solve_hh(file: &str, oop: &str, ip: &str, iterations: usize) {
+    use crate::hand_history::parse_pokerstars;
+    use crate::river_solver::{solve_river_with_progress, RiverSolverConfig};
+
+    let spot = match std::fs::read_to_string(file)
+        .map_err(|e| format!("{}: {}", file, e))
+        .and_then(|text| parse_pokerstars(&text).map_err(|e| e.to_string()))
+    {
+        Ok(spot) => spot,
+        Err(e) => {
+            print_error(&e);
+            return;
+        }
+    };
+    let [oop_player, ip_player] = &spot.players;
+    // The solver works in big blinds, like the rest of the CLI
+    let pot = spot.pot / spot.big_blind;
+    let stack = spot.effective_stack() / spot.big_blind;
 
     println!();
+    println!("  Board:  {}", spot.board_notation().bold());
     println!(
-        "  Solving river: board={}, pot={}, stack={}, {} iterations...",
-        board, pot, stack, iterations
+        "  OOP:    {} ({}, seat {})  IP: {} ({}, seat {})",
+        oop_player.name, oop_player.position, oop_player.seat, ip_player.name, ip_player.position, ip_player.seat
     );
+    println!("  Pot:    {:.1}bb  Effective stack: {:.1}bb", pot, stack);
+    if stack <= 0.0 {
+        print_error("No chips behind on the river, so there is nothing to solve");
+        return;
+    }
 
-    let result = solve_river(&config);
-    result.display();
+    let config = match RiverSolverConfig::new(&spot.board_notation(), oop, ip, pot, stack, iterations) {
+        Ok(c) => c,
+        Err(ref e) => {
+            print_error(e);
+            return;
+        }
+    };
+    println!("  Solving river, {} iterations...", iterations);
+    let result = solve_river_with_progress(&config, solve_progress(&OutputFormat::Text));
     result.save_cache();
+    print!("{}", result.render());
+}
+
+/// Check a `--simplify` threshold, reporting it if it's out of range.
+fn valid_simplify(simplify: Option<f64>) -> bool {
+    match simplify {
+        Some(t) if !(0.0..1.0).contains(&t) => {
+            print_error(&format!("--simplify must be in [0, 1), got {}", t));
+            false
+        }
+        _ => true,
+    }
+}
+
+/// Apply `--turn-*` / `--river-*` sizing flags to a turn solver config,
+/// then the per-player `--oop-*` / `--ip-*` overrides on both streets.
+fn apply_street_sizing(
+    config: &mut crate::turn_solver::TurnSolverConfig,
+    args: &StreetSizingArgs,
+    player_args: &PlayerSizingArgs,
+) -> Result<(), String> {
+    use crate::postflop_tree::same_for_both;
+
+    if let Some(ref sizes) = args.turn_bet_sizes {
+        config.turn_sizing.bet_sizes = same_for_both(parse_bet_sizes(sizes)?);
+    }
+    if let Some(ref sizes) = args.river_bet_sizes {
+        config.river_sizing.bet_sizes = same_for_both(parse_bet_sizes(sizes)?);
+    }
+    if let Some(ref sizes) = args.turn_raise_sizes {
+        config.turn_sizing.raise_sizes = same_for_both(sizes.parse()?);
+    }
+    if let Some(ref sizes) = args.river_raise_sizes {
+        config.river_sizing.raise_sizes = same_for_both(sizes.parse()?);
+    }
+    player_args.apply(&mut config.turn_sizing.bet_sizes)?;
+    player_args.apply(&mut config.river_sizing.bet_sizes)?;
+    config.can_raise = player_args.can_raise();
+    if let Some(n) = args.turn_max_raises {
+        config.turn_sizing.max_raises = n;
+    }
+    if let Some(n) = args.river_max_raises {
+        config.river_sizing.max_raises = n;
+    }
+    config.turn_sizing.validate().map_err(|e| format!("Turn: {}", e))?;
+    config.river_sizing.validate().map_err(|e| format!("River: {}", e))
+}
+
+/// Tree size above which solve commands ask before building.
+const LARGE_TREE_NODES: usize = 50_000;
+
+/// Progress callback for text-mode solves: a bar on stderr when it is a
+/// terminal, nothing otherwise.
+fn solve_progress(format: &OutputFormat) -> crate::progress::Progress<'static> {
+    let show = *format == OutputFormat::Text && std::io::stderr().is_terminal();
+    show.then_some(&print_solve_progress)
+}
+
+fn print_solve_progress(current: usize, total: usize) {
+    eprint!("\r  {}", crate::display::progress_bar(current, total, 30));
+    if current == total {
+        eprintln!();
+    }
+}
+
+/// Parse a memory size such as "512M", "4G" or "1.5GB". Plain numbers are
+/// megabytes.
+fn parse_memory_size(input: &str) -> Result<usize, String> {
+    let upper = input.trim().to_uppercase();
+    let digits = upper.strip_suffix('B').unwrap_or(&upper);
+    let (number, scale) = match digits.chars().last() {
+        Some('K') => (&digits[..digits.len() - 1], 1u64 << 10),
+        Some('M') => (&digits[..digits.len() - 1], 1 << 20),
+        Some('G') => (&digits[..digits.len() - 1], 1 << 30),
+        Some('T') => (&digits[..digits.len() - 1], 1 << 40),
+        _ => (digits, 1 << 20),
+    };
+    match number.trim().parse::<f64>() {
+        Ok(n) if n.is_finite() && n > 0.0 => Ok((n * scale as f64) as usize),
+        _ => Err(format!("Invalid memory size '{}' (e.g., 512M, 4G)", input)),
+    }
+}
+
+/// `--clean-cache`: evict the oldest cached solves until the solver
+/// directory fits in `max_bytes`.
+fn cmd_clean_cache(max_bytes: u64) {
+    use crate::solver_cache::{cache_stats, clean_cache};
+
+    match clean_cache(max_bytes) {
+        Ok(removed) => {
+            let left = cache_stats();
+            println!(
+                "  {}",
+                format!(
+                    "Solver cache: removed {} files ({}), {} files ({}) remain",
+                    removed.files,
+                    format_bytes(removed.total_bytes as usize),
+                    left.files,
+                    format_bytes(left.total_bytes as usize),
+                )
+                .dimmed()
+            );
+        }
+        Err(e) => print_error(&e),
+    }
+}
+
+/// Human-readable byte count, e.g. "1.5 GB".
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// `--verbose` memory line: each named part, then the total when there is
+/// more than one.
+fn memory_summary(parts: &[(&str, usize)]) -> String {
+    let mut line = parts
+        .iter()
+        .map(|(name, bytes)| format!("{} {}", name, format_bytes(*bytes)))
+        .collect::<Vec<_>>()
+        .join(" + ");
+    if parts.len() > 1 {
+        let total: usize = parts.iter().map(|(_, bytes)| bytes).sum();
+        line += &format!(" = {}", format_bytes(total));
+    }
+    line
+}
+
+/// Print the tree estimate (text output only), then reject trees that go
+/// over `max_memory` or overflow the solver's u32 node ids, and ask before
+/// building large ones.
+fn check_tree_size(
+    estimate: &crate::postflop_tree::TreeEstimate,
+    memory: usize,
+    max_memory: Option<&str>,
+    format: &OutputFormat,
+) -> bool {
+    use std::io::Write;
+
+    let nodes = estimate.action_nodes;
+    if *format == OutputFormat::Text {
+        println!();
+        println!(
+            "  Tree: {} action nodes, {} terminals, {} chance nodes, ~{} solver memory",
+            nodes,
+            estimate.terminal_nodes,
+            estimate.chance_nodes,
+            format_bytes(memory)
+        );
+    }
+
+    if let Some(limit) = max_memory {
+        match parse_memory_size(limit) {
+            Ok(limit) if memory > limit => {
+                print_error(&format!(
+                    "Estimated solver memory ~{} exceeds --max-memory {}. Use fewer bet sizes, a lower raise cap or narrower ranges.",
+                    format_bytes(memory),
+                    format_bytes(limit)
+                ));
+                return false;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                print_error(&e);
+                return false;
+            }
+        }
+    }
+
+    if nodes > u32::MAX as usize {
+        print_error(&format!(
+            "This config produces ~{} nodes, more than the solver supports ({}). Use fewer bet sizes or a lower raise cap.",
+            nodes,
+            u32::MAX
+        ));
+        return false;
+    }
+    if nodes <= LARGE_TREE_NODES {
+        return true;
+    }
+
+    print!(
+        "  {} This config produces ~{} nodes, continue? (y/n) [n]: ",
+        "Warning:".yellow().bold(),
+        nodes
+    );
+    std::io::stdout().flush().ok();
+    let mut line = String::new();
+    if std::io::stdin().read_line(&mut line).is_err() {
+        return false;
+    }
+    matches!(line.trim().to_lowercase().as_str(), "y" | "yes")
 }
 
-fn cmd_solve_turn(board: String, oop: String, ip: String, pot: f64, stack: f64, iterations: usize) {
-    use crate::turn_solver::{TurnSolverConfig, solve_turn};
+#[allow(clippy::too_many_arguments)]
+fn cmd_solve_turn(
+    board: String,
+    oop: String,
+    ip: String,
+    pot: f64,
+    stack: f64,
+    iterations: usize,
+    threads: usize,
+    precision: crate::cfr::Precision,
+    algorithm: AlgorithmArgs,
+    checkpoint: CheckpointArgs,
+    convergence_every: Option<usize>,
+    sizing: StreetSizingArgs,
+    player_sizing: PlayerSizingArgs,
+    allin: AllInArgs,
+    max_memory: Option<String>,
+    verbose: bool,
+    format: OutputFormat,
+    out: Option<String>,
+) {
+    use crate::cfr::Precision;
+    use crate::flat_cfr::FlatCfr;
+    use crate::postflop_tree::estimate_turn_tree;
+    use crate::river_solver::expand_range_to_combos;
+    use crate::turn_solver::{resume_turn, solve_turn_with_progress, TurnSolverConfig};
 
     if pot <= 0.0 {
         print_error("Pot must be positive");
@@ -2000,27 +3711,89 @@ fn cmd_solve_turn(board: String, oop: String, ip: String, pot: f64, stack: f64,
         return;
     }
 
-    let config = match TurnSolverConfig::new(&board, &oop, &ip, pot, stack, iterations) {
+    let mut config = match TurnSolverConfig::new(&board, &oop, &ip, pot, stack, iterations) {
         Ok(c) => c,
         Err(ref e) => {
             print_error(e);
             return;
         }
     };
+    config.threads = threads;
+    config.precision = precision;
+    config.algorithm = algorithm.algo;
+    config.seed = algorithm.seed;
+    config.checkpoint_every = checkpoint.checkpoint_every;
+    config.convergence_every = convergence_every;
+
+    let sized = apply_street_sizing(&mut config, &sizing, &player_sizing).and_then(|()| {
+        allin.apply(&mut config.allin_threshold, &mut config.force_allin_above_stack_pct)
+    });
+    if let Err(e) = sized {
+        print_error(&e);
+        return;
+    }
 
-    println!();
-    println!(
-        "  Solving turn: board={}, pot={}, stack={}, {} iterations...",
-        board, pot, stack, iterations
-    );
+    let combos = [
+        expand_range_to_combos(&config.oop_range, &config.board).len(),
+        expand_range_to_combos(&config.ip_range, &config.board).len(),
+    ];
+    let estimate = estimate_turn_tree(&config.tree_config());
+    // Each player's FlatCfr lays out every node, holding entries only where
+    // that player acts
+    let (nodes, entries) = (2 * estimate.action_nodes, estimate.entries(combos));
+    let memory = match config.precision {
+        Precision::F32 => FlatCfr::<f32>::estimated_bytes(nodes, entries),
+        Precision::F64 => FlatCfr::<f64>::estimated_bytes(nodes, entries),
+    };
+    if !check_tree_size(&estimate, memory, max_memory.as_deref(), &format) {
+        return;
+    }
 
-    let result = solve_turn(&config);
-    result.display();
+    if format == OutputFormat::Text {
+        println!();
+        println!(
+            "  Solving turn: board={}, pot={}, stack={}, {} iterations...",
+            board, pot, stack, iterations
+        );
+        if verbose {
+            println!("  Memory (estimated): {}", memory_summary(&[("trainer", memory)]));
+        }
+    }
+
+    let result = if checkpoint.resume {
+        match resume_turn(&config, solve_progress(&format)) {
+            Ok(result) => result,
+            Err(e) => {
+                print_error(&e);
+                return;
+            }
+        }
+    } else {
+        solve_turn_with_progress(&config, solve_progress(&format))
+    };
+    if verbose && format == OutputFormat::Text {
+        println!("  Memory: {}", memory_summary(&[("trainer", result.trainer_bytes)]));
+    }
+    emit_solution(&result, || result.render(), &format, out.as_deref());
     result.save_cache();
 }
 
-fn cmd_solve_flop(board: String, oop: String, ip: String, pot: f64, stack: f64, iterations: usize) {
-    use crate::flop_solver::{FlopSolverConfig, solve_flop};
+#[allow(clippy::too_many_arguments)]
+fn cmd_solve_flop(
+    board: String,
+    oop: String,
+    ip: String,
+    pot: f64,
+    stack: f64,
+    iterations: usize,
+    bucketing: crate::bucketing::BucketingMode,
+    buckets: Vec<usize>,
+    format: OutputFormat,
+    out: Option<String>,
+) {
+    use crate::bucketing::BucketingMode;
+    use crate::flop_solver::{FlopSolverConfig, UNABSTRACTED_COMBO_THRESHOLD, solve_flop};
+    use crate::river_solver::expand_range_to_combos;
 
     if pot <= 0.0 {
         print_error("Pot must be positive");
@@ -2031,21 +3804,430 @@ fn cmd_solve_flop(board: String, oop: String, ip: String, pot: f64, stack: f64,
         return;
     }
 
-    let config = match FlopSolverConfig::new(&board, &oop, &ip, pot, stack, iterations) {
+    if buckets.is_empty() || buckets.iter().any(|&b| b == 0 || b > u16::MAX as usize) {
+        print_error("Buckets must be between 1 and 65535");
+        return;
+    }
+
+    let mut config = match FlopSolverConfig::new(&board, &oop, &ip, pot, stack, iterations) {
         Ok(c) => c,
         Err(ref e) => {
             print_error(e);
             return;
         }
     };
+    config.bucketing = bucketing;
+    let text = format == OutputFormat::Text;
+
+    if bucketing == BucketingMode::Unabstracted {
+        if text {
+            println!();
+            println!(
+                "  Solving flop: board={}, pot={}, stack={}, {} iterations, unabstracted...",
+                board, pot, stack, iterations
+            );
+        }
+    } else {
+        let oop_combos = expand_range_to_combos(&config.oop_range, &config.board).len();
+        let ip_combos = expand_range_to_combos(&config.ip_range, &config.board).len();
+        let small = oop_combos <= UNABSTRACTED_COMBO_THRESHOLD
+            && ip_combos <= UNABSTRACTED_COMBO_THRESHOLD;
+        if text && small {
+            println!(
+                "  {} Ranges are small ({} vs {} combos); --no-buckets solves without abstraction.",
+                "Tip:".yellow(),
+                oop_combos,
+                ip_combos
+            );
+        }
+
+        if buckets.len() > 1 {
+            if !text {
+                print_error("--format json needs a single --buckets value");
+                return;
+            }
+            sweep_flop_buckets(&mut config, &board, &buckets);
+            return;
+        }
+        config.num_buckets = buckets[0];
+
+        if text {
+            println!();
+            println!(
+                "  Solving flop: board={}, pot={}, stack={}, {} iterations, {} {} buckets...",
+                board,
+                pot,
+                stack,
+                iterations,
+                config.num_buckets,
+                config.bucketing.as_str()
+            );
+        }
+    }
+
+    let result = solve_flop(&config);
+    emit_solution(&result, || result.render(), &format, out.as_deref());
+    result.save_cache();
+}
+
+/// Print a solution as text or pretty JSON, or write it to `out` with ANSI
+/// codes stripped. JSON node strategies keep the solver's
+/// `[combo_idx][action_idx]` layout, so each row of `frequencies` lines up
+/// with the acting player's `oop_combos` / `ip_combos` entry.
+fn emit_solution<T: serde::Serialize>(
+    solution: &T,
+    render_text: impl FnOnce() -> String,
+    format: &OutputFormat,
+    out: Option<&str>,
+) {
+    let rendered = match format {
+        OutputFormat::Text => render_text(),
+        OutputFormat::Json => match serde_json::to_string_pretty(solution) {
+            Ok(json) => json + "\n",
+            Err(e) => {
+                print_error(&format!("Failed to serialize solution: {}", e));
+                return;
+            }
+        },
+    };
+    match out {
+        None => print!("{}", rendered),
+        Some(path) => match std::fs::write(path, crate::display::strip_ansi(&rendered)) {
+            Ok(()) => println!("  Wrote solution to {}", path),
+            Err(e) => print_error(&format!("Failed to write {}: {}", path, e)),
+        },
+    }
+}
+
+/// Solve the same flop once per bucket count and compare exploitability.
+/// Only the real (combo-level) number is comparable across bucket counts.
+fn sweep_flop_buckets(
+    config: &mut crate::flop_solver::FlopSolverConfig,
+    board: &str,
+    buckets: &[usize],
+) {
+    use crate::flop_solver::solve_flop;
 
     println!();
     println!(
-        "  Solving flop: board={}, pot={}, stack={}, {} iterations...",
-        board, pot, stack, iterations
+        "  Sweeping buckets on {}: {} iterations, {} bucketing",
+        board,
+        config.iterations,
+        config.bucketing.as_str()
     );
 
-    let result = solve_flop(&config);
-    result.display();
-    result.save_cache();
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec![
+        Cell::new("Buckets".bold().to_string()),
+        Cell::new("Abstraction").set_alignment(CellAlignment::Right),
+        Cell::new("Real").set_alignment(CellAlignment::Right),
+        Cell::new("Real % pot").set_alignment(CellAlignment::Right),
+        Cell::new("Time").set_alignment(CellAlignment::Right),
+    ]);
+
+    for &b in buckets {
+        config.num_buckets = b;
+        println!("  Solving with {} buckets...", b);
+        let start = std::time::Instant::now();
+        let result = solve_flop(config);
+        let elapsed = start.elapsed().as_secs_f64();
+        let real_pct = result.real_exploitability / config.starting_pot * 100.0;
+        table.add_row(vec![
+            Cell::new(b.to_string()),
+            Cell::new(format!("{:.4}", result.exploitability)).set_alignment(CellAlignment::Right),
+            Cell::new(format!("{:.4}", result.real_exploitability))
+                .set_alignment(CellAlignment::Right),
+            Cell::new(format!("{:.2}", real_pct)).set_alignment(CellAlignment::Right),
+            Cell::new(format!("{:.1}s", elapsed)).set_alignment(CellAlignment::Right),
+        ]);
+    }
+
+    println!();
+    println!("{}", table);
+}
+
+/// Solve the spot on every flop and write the CSV. Progress goes to stderr
+/// so the CSV can be piped from stdout.
+fn cmd_flop_report(
+    config: crate::flop_report::FlopReportConfig,
+    flops: Option<String>,
+    subset: Option<Subset>,
+    output: Option<String>,
+) {
+    use crate::flop_report::{group_by_texture, read_flop_list, run_flop_report, write_csv};
+
+    if config.starting_pot <= 0.0 {
+        print_error("Pot must be positive");
+        return;
+    }
+    if config.effective_stack <= 0.0 {
+        print_error("Stack must be positive");
+        return;
+    }
+    if config.num_buckets == 0 || config.num_buckets > u16::MAX as usize {
+        print_error("Buckets must be between 1 and 65535");
+        return;
+    }
+
+    let weighted: Vec<(String, u32)> = match (flops, subset) {
+        (Some(ref path), _) => match read_flop_list(path) {
+            Ok(b) => b.into_iter().map(|board| (board, 1)).collect(),
+            Err(e) => {
+                print_error(&e);
+                return;
+            }
+        },
+        (None, Some(subset)) => subset.subset().flops(),
+        (None, None) => crate::batch::REPRESENTATIVE_FLOPS
+            .iter()
+            .map(|s| (s.to_string(), 1))
+            .collect(),
+    };
+    let (boards, weights): (Vec<String>, Vec<u32>) = weighted.into_iter().unzip();
+
+    eprintln!(
+        "  Flop report: {} flops, pot={}, stack={}, {} iterations, {} {} buckets",
+        boards.len(),
+        config.starting_pot,
+        config.effective_stack,
+        config.iterations,
+        config.num_buckets,
+        config.bucketing.as_str()
+    );
+
+    let results = run_flop_report(&config, &boards, |done, total, board, result| {
+        match result {
+            Ok(row) => eprintln!(
+                "  [{}/{}] {} done (exploit: {:.4})",
+                done, total, board, row.exploitability
+            ),
+            Err(e) => eprintln!("  [{}/{}] {} {}", done, total, board, format!("skipped: {}", e).red()),
+        }
+    });
+    let rows: Vec<_> = results
+        .into_iter()
+        .zip(weights)
+        .filter_map(|(result, weight)| result.ok().map(|row| row.with_weight(weight)))
+        .collect();
+    if rows.is_empty() {
+        print_error("No flops could be solved");
+        return;
+    }
+
+    let written = match output {
+        Some(ref path) => std::fs::File::create(path)
+            .and_then(|f| write_csv(&rows, &mut std::io::BufWriter::new(f))),
+        None => write_csv(&rows, &mut std::io::stdout().lock()),
+    };
+    match (written, output) {
+        (Err(e), _) => print_error(&format!("Failed to write CSV: {}", e)),
+        (Ok(()), Some(path)) => eprintln!("  Wrote {} rows to {}", rows.len(), path),
+        (Ok(()), None) => {}
+    }
+
+    // The CSV may be on stdout, so the summary goes with the progress lines
+    eprintln!();
+    eprintln!("  {}", "By texture (weighted):".bold());
+    for group in group_by_texture(&rows) {
+        eprintln!(
+            "    {:<40} {:>4} flops  OOP bet {:>3.0}%  IP bet {:>3.0}%  exploit {:.2}% pot",
+            group.texture,
+            group.flops,
+            group.oop_bet * 100.0,
+            group.ip_bet * 100.0,
+            group.exploitability_pct
+        );
+    }
+}
+
+/// Build a river tree (or a turn+river tree when a turn board is given) and
+/// print it as text or DOT.
+fn cmd_tree(
+    pot: f64,
+    stack: f64,
+    bet_sizes: &str,
+    max_raises: usize,
+    board: Option<&str>,
+    depth: usize,
+    format: TreeFormat,
+) {
+    use crate::postflop_tree::{build_tree, build_turn_tree, same_for_both, TreeConfig, TurnTreeConfig};
+
+    if pot <= 0.0 || stack < 0.0 {
+        print_error("Pot must be positive and stack non-negative");
+        return;
+    }
+    let sizes = match parse_bet_sizes(bet_sizes) {
+        Ok(s) => s,
+        Err(e) => {
+            print_error(&e);
+            return;
+        }
+    };
+
+    let (tree, _) = match board {
+        Some(b) => {
+            let cards = match parse_board(b) {
+                Ok(c) if c.len() == 4 => c,
+                Ok(_) => {
+                    print_error("Tree board must be a 4-card turn board (e.g., Ks9d4c7h)");
+                    return;
+                }
+                Err(e) => {
+                    print_error(&e.to_string());
+                    return;
+                }
+            };
+            let mut config = TurnTreeConfig::new(
+                crate::card_encoding::cards_to_indices(&cards),
+                pot,
+                stack,
+            );
+            config.turn.bet_sizes = same_for_both(sizes.clone());
+            config.turn.max_raises = max_raises;
+            config.river_bet_sizes = same_for_both(sizes);
+            config.river_max_raises = max_raises;
+            build_turn_tree(&config)
+        }
+        None => {
+            let mut config = TreeConfig::default_river(pot, stack);
+            config.bet_sizes = same_for_both(sizes);
+            config.max_raises = max_raises;
+            build_tree(&config)
+        }
+    };
+
+    match format {
+        TreeFormat::Text => print!("{}", tree.to_text(depth)),
+        TreeFormat::Dot => print!("{}", tree.to_dot()),
+    }
+}
+
+/// Print every board in a flop subset with its weight, one per line.
+fn cmd_diff(old: &str, new: &str, threshold: f64) {
+    use crate::river_solver::RiverSolution;
+
+    if !(0.0..1.0).contains(&threshold) {
+        print_error(&format!("--threshold must be in [0, 1), got {}", threshold));
+        return;
+    }
+    let (old, new) = match (
+        RiverSolution::read_file(std::path::Path::new(old)),
+        RiverSolution::read_file(std::path::Path::new(new)),
+    ) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(e), _) | (_, Err(e)) => {
+            print_error(&e);
+            return;
+        }
+    };
+    if old.board != new.board {
+        println!("  Note: boards differ ({} vs {})", old.board, new.board);
+    }
+
+    let diffs = old.diff_above(&new, threshold);
+    if diffs.is_empty() {
+        println!("  No strategy changes above {:.0}%", threshold * 100.0);
+        return;
+    }
+    for diff in &diffs {
+        println!("  {}", diff);
+    }
+    println!();
+    println!(
+        "  {} differences  |  exploitability {:.4} -> {:.4}",
+        diffs.len(),
+        old.exploitability,
+        new.exploitability
+    );
+}
+
+fn cmd_walk(path: &str) {
+    use crate::river_solver::RiverSolution;
+    use crate::walk::Walk;
+    use std::io::{BufRead, Write};
+
+    let mut walk = match RiverSolution::read_file(std::path::Path::new(path)).and_then(Walk::new) {
+        Ok(walk) => walk,
+        Err(e) => {
+            print_error(&e);
+            return;
+        }
+    };
+    println!();
+    println!(
+        "  {} {}  |  OOP {} combos vs IP {} combos",
+        "Board".bold(),
+        walk.solution().board,
+        walk.solution().oop_combos.len(),
+        walk.solution().ip_combos.len()
+    );
+    println!("  Pick an action by number or name; {} undoes, {} restarts, {} exits.", "back".bold(), "root".bold(), "quit".bold());
+
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print_walk_node(&walk);
+        print!("  > ");
+        std::io::stdout().flush().ok();
+        let Some(Ok(line)) = lines.next() else {
+            println!();
+            return;
+        };
+        match walk.step(&line) {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(e) => print_error(&e),
+        }
+    }
+}
+
+fn print_walk_node(walk: &crate::walk::Walk) {
+    println!();
+    let line = walk.line();
+    println!("  {} {}", "Line:".bold(), if line.is_empty() { "(root)".to_string() } else { line.join(" - ") });
+
+    if let Some(outcome) = walk.outcome() {
+        println!("  {}", outcome.yellow());
+        return;
+    }
+    let Some(strategy) = walk.strategy() else {
+        println!("  No strategy stored for this node");
+        return;
+    };
+    let actions = walk.actions();
+    println!("  {} to act (node {})", strategy.player.cyan().bold(), strategy.node_id);
+    if let Some(aggregate) = walk.aggregate() {
+        for (i, (label, freq)) in aggregate.iter().enumerate() {
+            println!("    {}. {:<18} {:>5.1}%", i + 1, label, freq * 100.0);
+        }
+    }
+
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    let mut header = vec![Cell::new("Combo"), Cell::new("Reach")];
+    header.extend(actions.iter().map(Cell::new));
+    table.set_header(header);
+    for (combo, reach, freqs) in walk.combo_frequencies() {
+        let mut row = vec![Cell::new(combo), Cell::new(format!("{:.0}%", reach * 100.0))];
+        row.extend(freqs.iter().map(|f| Cell::new(format!("{:.1}%", f * 100.0))));
+        table.add_row(row);
+    }
+    println!("{}", table);
+}
+
+fn cmd_flops(subset: Subset) {
+    use std::io::Write;
+
+    let flops = subset.subset().flops();
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    for (board, weight) in &flops {
+        if writeln!(out, "{} {}", board, weight).is_err() {
+            return;
+        }
+    }
+    let total: u32 = flops.iter().map(|(_, w)| w).sum();
+    let _ = out.flush();
+    eprintln!("  {} flops, total weight {}", flops.len(), total);
 }
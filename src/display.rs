@@ -1,13 +1,62 @@
+use std::collections::HashMap;
+
 use colored::Colorize;
 use comfy_table::{Cell, CellAlignment, ContentArrangement, Table};
 
-use crate::cards::{Card, Suit};
+use crate::card_encoding::{card_to_index, index_to_card};
+use crate::cards::{hand_combos, simplify_hand, Card, Suit};
+use crate::lookup_eval::{best_five, category_from_score};
+use crate::ranges::combo_count;
 
 const RANGE_GRID_RANKS: [char; 13] = ['A', 'K', 'Q', 'J', 'T', '9', '8', '7', '6', '5', '4', '3', '2'];
 
 pub fn range_grid(hands_in_range: &[String], title: &str) -> String {
-    let in_range: std::collections::HashSet<&str> =
-        hands_in_range.iter().map(|s| s.as_str()).collect();
+    let weighted: Vec<(String, f64)> = hands_in_range.iter().map(|h| (h.clone(), 1.0)).collect();
+    weighted_range_grid(&weighted, title)
+}
+
+/// Grid label for row `i`, column `j`: pairs on the diagonal, suited above,
+/// offsuit below.
+fn grid_hand(i: usize, j: usize) -> String {
+    let (r1, r2) = (RANGE_GRID_RANKS[i], RANGE_GRID_RANKS[j]);
+    if i == j {
+        format!("{}{}", r1, r2)
+    } else if i < j {
+        format!("{}{}s", r1, r2)
+    } else {
+        format!("{}{}o", r2, r1)
+    }
+}
+
+/// Fraction of each hand class in the range, indexed row*13 + col like
+/// `strategy_grid`. Suit-specific combos ("AhKh") count toward their class,
+/// so a class holding 2 of its 4 suited combos at full weight gets 0.5.
+pub fn range_grid_weights(range: &[(String, f64)]) -> [f64; 169] {
+    let mut class_weight: HashMap<String, f64> = HashMap::new();
+    for (hand, weight) in range {
+        if hand.len() == 4 {
+            let Ok(combos) = hand_combos(hand) else { continue };
+            let Some(&(c1, c2)) = combos.first() else { continue };
+            let Ok(class) = simplify_hand(&[c1, c2]) else { continue };
+            let share = weight / combo_count(&class).max(1) as f64;
+            *class_weight.entry(class).or_insert(0.0) += share;
+        } else {
+            class_weight.insert(hand.clone(), *weight);
+        }
+    }
+
+    let mut weights = [0.0; 169];
+    for (i, w) in weights.iter_mut().enumerate() {
+        let hand = grid_hand(i / 13, i % 13);
+        *w = class_weight.get(&hand).map_or(0.0, |w| w.clamp(0.0, 1.0));
+    }
+    weights
+}
+
+/// 13x13 range grid shaded by weight: full weight green, at least half
+/// plain green, anything less yellow, and hands not in the range dimmed.
+pub fn weighted_range_grid(range: &[(String, f64)], title: &str) -> String {
+    let weights = range_grid_weights(range);
 
     let mut table = Table::new();
     table.set_content_arrangement(ContentArrangement::Dynamic);
@@ -21,17 +70,16 @@ pub fn range_grid(hands_in_range: &[String], title: &str) -> String {
 
     for (i, &r1) in RANGE_GRID_RANKS.iter().enumerate() {
         let mut row = vec![Cell::new(format!("{}", r1).bold().to_string())];
-        for (j, &r2) in RANGE_GRID_RANKS.iter().enumerate() {
-            let hand = if i == j {
-                format!("{}{}", r1, r2)
-            } else if i < j {
-                format!("{}{}s", r1, r2)
-            } else {
-                format!("{}{}o", r2, r1)
-            };
+        for j in 0..RANGE_GRID_RANKS.len() {
+            let hand = grid_hand(i, j);
+            let weight = weights[i * 13 + j];
 
-            let cell = if in_range.contains(hand.as_str()) {
+            let cell = if weight >= 0.995 {
                 Cell::new(hand.green().bold().to_string())
+            } else if weight >= 0.5 {
+                Cell::new(hand.green().to_string())
+            } else if weight > 0.0 {
+                Cell::new(hand.yellow().to_string())
             } else {
                 Cell::new(hand.dimmed().to_string())
             };
@@ -40,7 +88,11 @@ pub fn range_grid(hands_in_range: &[String], title: &str) -> String {
         table.add_row(row);
     }
 
-    format!("  {}\n{}", title.bold(), table)
+    if weights.iter().all(|&w| w == 0.0) {
+        format!("  {} {}\n{}", title.bold(), "(empty range)".dimmed(), table)
+    } else {
+        format!("  {}\n{}", title.bold(), table)
+    }
 }
 
 pub fn range_grid_strs(hands_in_range: &[&str], title: &str) -> String {
@@ -98,6 +150,53 @@ pub fn equity_bar(equity: f64, width: usize) -> String {
     }
 }
 
+/// Text progress bar for long runs, e.g. `[█████░░░░░]  50% (500/1000)`.
+pub fn progress_bar(current: usize, total: usize, width: usize) -> String {
+    let frac = if total == 0 { 1.0 } else { current.min(total) as f64 / total as f64 };
+    let filled = (frac * width as f64) as usize;
+    format!(
+        "[{}{}] {:>3.0}% ({}/{})",
+        "\u{2588}".repeat(filled).cyan(),
+        "\u{2591}".repeat(width - filled),
+        frac * 100.0,
+        current,
+        total
+    )
+}
+
+/// One-line sparkline of `values`, low to high as `▁` to `█`, scaled
+/// between the smallest and largest value.
+pub fn sparkline(values: &[f64]) -> String {
+    const BARS: [char; 8] = [
+        '\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}',
+        '\u{2585}', '\u{2586}', '\u{2587}', '\u{2588}',
+    ];
+    let lo = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let hi = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    values
+        .iter()
+        .map(|&v| {
+            let frac = if hi > lo { (v - lo) / (hi - lo) } else { 0.0 };
+            BARS[(frac * (BARS.len() - 1) as f64).round() as usize]
+        })
+        .collect()
+}
+
+/// Report line for an `(iterations, exploitability)` series: a sparkline
+/// on a log scale, since exploitability falls by orders of magnitude,
+/// then the last point. `None` for an empty series.
+pub fn convergence_summary(points: &[(usize, f64)]) -> Option<String> {
+    let &(last_iter, last) = points.last()?;
+    let logs: Vec<f64> = points.iter().map(|&(_, e)| e.max(1e-9).log10()).collect();
+    Some(format!(
+        "Convergence: {}  ({} points, {:.4} at {} iterations)",
+        sparkline(&logs),
+        points.len(),
+        last,
+        last_iter
+    ))
+}
+
 pub fn board_display(cards: &[Card]) -> String {
     cards
         .iter()
@@ -116,6 +215,22 @@ pub fn board_display(cards: &[Card]) -> String {
         .join(" ")
 }
 
+/// "Ah Ad As Ks Kd (full house)": the five cards `hole` plays with `board`,
+/// or None before the flop.
+pub fn best_hand_display(hole: &[Card], board: &[Card]) -> Option<String> {
+    let cards: Vec<u8> = hole.iter().chain(board).map(card_to_index).collect();
+    if !(5..=7).contains(&cards.len()) {
+        return None;
+    }
+    let (five, score) = best_five(&cards);
+    let five: Vec<Card> = five.iter().map(|&c| index_to_card(c)).collect();
+    Some(format!(
+        "{} ({})",
+        board_display(&five),
+        category_from_score(score).to_string().to_lowercase()
+    ))
+}
+
 pub fn odds_table(pot: f64, bet: f64, equity_needed: f64, ev_value: Option<f64>) -> String {
     let mut table = Table::new();
     table.set_content_arrangement(ContentArrangement::Dynamic);
@@ -201,6 +316,27 @@ pub fn print_section(title: &str, content: &str) {
     println!("  {}", content);
 }
 
+/// Remove ANSI escape sequences (colors, bold) so text can go to a file.
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // CSI sequence: ESC [ params final-byte
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
 pub fn print_error(msg: &str) {
     eprintln!("{} {}", "Error:".red().bold(), msg);
 }
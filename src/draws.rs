@@ -0,0 +1,315 @@
+//! Draw detection for a combo on a flop or turn board.
+//!
+//! Works on the u8 card encoding (rank = `c >> 2`, suit = `c & 3`) with the
+//! same per-rank counts and per-suit rank masks as `lookup_eval`, so it is
+//! cheap enough to run for every combo while bucketing.
+
+use crate::lookup_eval::straight_high;
+
+/// All 13 rank bits.
+const ALL_RANKS: u16 = 0x1fff;
+
+/// What a combo is drawing to. A draw always needs a hole card to take part
+/// and must beat what the board alone would make; on the river there is
+/// nothing left to draw to and every field is zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DrawInfo {
+    /// Four to a flush, at least one of them a hole card, with no flush made yet.
+    pub flush_draw: bool,
+    /// A flush draw holding the highest card of the suit not on the board.
+    pub nut_flush_draw: bool,
+    /// Four ranks in a row that either end completes.
+    pub open_ended: bool,
+    /// Exactly one rank completes a straight.
+    pub gutshot: bool,
+    /// Two or more ranks complete a straight without four in a row
+    /// (e.g. 9-7 on J-8-5).
+    pub double_gutshot: bool,
+    /// Flop only: three to a flush with a hole card, needing turn and river.
+    pub backdoor_flush: bool,
+    /// Flop only: runner-runner ranks complete a straight no single card does.
+    pub backdoor_straight: bool,
+    /// Unseen cards that lift the hand's category further than they lift the
+    /// board's own: a card pairing the board or completing a straight on it
+    /// isn't an out, because it helps every hand the same.
+    pub outs: u8,
+}
+
+impl DrawInfo {
+    pub fn is_straight_draw(&self) -> bool {
+        self.open_ended || self.gutshot || self.double_gutshot
+    }
+
+    pub fn is_draw(&self) -> bool {
+        self.flush_draw || self.is_straight_draw()
+    }
+}
+
+/// Detect the draws `hole` holds on `board` (3-5 cards).
+pub fn detect_draws(hole: &[u8; 2], board: &[u8]) -> DrawInfo {
+    let mut info = DrawInfo::default();
+    if board.len() >= 5 {
+        return info;
+    }
+    let on_flop = board.len() == 3;
+
+    let board_only = Masks::new(board);
+    let mut hand = board_only;
+    for &c in hole {
+        hand.add(c);
+    }
+
+    // --- Flush draws, in the suits of the hole cards ---
+    for suit in suits_of(hole) {
+        match hand.suit_masks[suit].count_ones() {
+            4 => {
+                info.flush_draw = true;
+                let missing = ALL_RANKS & !board_only.suit_masks[suit];
+                let nut = 1u16 << (15 - missing.leading_zeros());
+                info.nut_flush_draw |= hand.suit_masks[suit] & nut != 0;
+            }
+            3 if on_flop => info.backdoor_flush = true,
+            _ => {}
+        }
+    }
+
+    // --- Straight draws ---
+    let ranks = hand.rank_mask();
+    let board_ranks = board_only.rank_mask();
+    // A rank completes when it makes a straight higher than the board's own
+    let completes = |extra: u16| straight_high(ranks | extra) > straight_high(board_ranks | extra);
+    if straight_high(ranks) == 0 {
+        let completing = (0..13)
+            .map(|r| 1u16 << r)
+            .filter(|&bit| ranks & bit == 0 && completes(bit))
+            .fold(0u16, |m, bit| m | bit);
+
+        // Four in a row with both ends completing, ace playing either end
+        let run = ace_both_ends(ranks);
+        let ends = ace_both_ends(completing);
+        info.open_ended = (0..=8)
+            .any(|low| ends >> low & 0b10_0001 == 0b10_0001 && run >> (low + 1) & 0xf == 0xf);
+        let count = completing.count_ones();
+        info.double_gutshot = !info.open_ended && count >= 2;
+        info.gutshot = count == 1;
+
+        if on_flop {
+            let open: Vec<u16> = (0..13)
+                .map(|r| 1u16 << r)
+                .filter(|&bit| (ranks | completing) & bit == 0)
+                .collect();
+            info.backdoor_straight = open
+                .iter()
+                .enumerate()
+                .any(|(i, &a)| open[i + 1..].iter().any(|&b| completes(a | b)));
+        }
+    }
+
+    // --- Outs ---
+    let hand_category = hand.category();
+    let board_category = board_only.category();
+    info.outs = (0..52u8)
+        .filter(|&c| !hand.holds(c))
+        .filter(|&c| {
+            let (mut with_hand, mut with_board) = (hand, board_only);
+            with_hand.add(c);
+            with_board.add(c);
+            with_hand.category() - hand_category > with_board.category() - board_category
+        })
+        .count() as u8;
+
+    info
+}
+
+/// The distinct suits of the hole cards.
+fn suits_of(hole: &[u8; 2]) -> impl Iterator<Item = usize> {
+    let (s0, s1) = ((hole[0] & 3) as usize, (hole[1] & 3) as usize);
+    std::iter::once(s0).chain((s1 != s0).then_some(s1))
+}
+
+/// Rank mask shifted up one with the ace copied into bit 0, so bit 0 is an
+/// ace playing low and bit 13 an ace playing high.
+fn ace_both_ends(mask: u16) -> u16 {
+    mask << 1 | (mask >> 12 & 1)
+}
+
+/// Rank counts and per-suit rank masks of a set of cards.
+#[derive(Clone, Copy, Default)]
+struct Masks {
+    rank_counts: [u8; 13],
+    suit_masks: [u16; 4],
+}
+
+impl Masks {
+    fn new(cards: &[u8]) -> Self {
+        let mut masks = Masks::default();
+        for &c in cards {
+            masks.add(c);
+        }
+        masks
+    }
+
+    #[inline]
+    fn add(&mut self, card: u8) {
+        let rank = (card >> 2) as usize;
+        self.rank_counts[rank] += 1;
+        self.suit_masks[(card & 3) as usize] |= 1 << rank;
+    }
+
+    #[inline]
+    fn holds(&self, card: u8) -> bool {
+        self.suit_masks[(card & 3) as usize] & 1 << (card >> 2) != 0
+    }
+
+    fn rank_mask(&self) -> u16 {
+        self.suit_masks.iter().fold(0, |m, &s| m | s)
+    }
+
+    /// Category of the best five cards (0 = high card .. 8 = straight flush,
+    /// numbered like `lookup_eval` scores), for any number of cards.
+    fn category(&self) -> u8 {
+        let flush = self.suit_masks.iter().find(|m| m.count_ones() >= 5);
+        if flush.is_some_and(|&m| straight_high(m) > 0) {
+            return 8;
+        }
+        let (mut quads, mut trips, mut pairs) = (0, 0, 0);
+        for &n in &self.rank_counts {
+            match n {
+                4 => quads += 1,
+                3 => trips += 1,
+                2 => pairs += 1,
+                _ => {}
+            }
+        }
+        if quads > 0 {
+            7
+        } else if trips >= 2 || (trips == 1 && pairs > 0) {
+            6
+        } else if flush.is_some() {
+            5
+        } else if straight_high(self.rank_mask()) > 0 {
+            4
+        } else if trips > 0 {
+            3
+        } else {
+            pairs.min(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_encoding::cards_to_indices;
+    use crate::cards::parse_board;
+
+    fn cards(s: &str) -> Vec<u8> {
+        cards_to_indices(&parse_board(s).unwrap())
+    }
+
+    fn draws(hole: &str, board: &str) -> DrawInfo {
+        let hole = cards(hole);
+        detect_draws(&[hole[0], hole[1]], &cards(board))
+    }
+
+    #[test]
+    fn nut_flush_draw_with_overcards() {
+        let d = draws("AhTh", "Kh7h2c");
+        assert!(d.flush_draw && d.nut_flush_draw);
+        assert!(!d.is_straight_draw() && !d.backdoor_flush);
+        // 9 hearts, 3 aces, 3 tens
+        assert_eq!(d.outs, 15);
+    }
+
+    #[test]
+    fn second_nut_flush_draw_is_not_the_nuts() {
+        let d = draws("9h8h", "Ah7h2c");
+        assert!(d.flush_draw && !d.nut_flush_draw);
+        // T-6 runner-runner
+        assert!(d.backdoor_straight);
+        assert!(!d.is_straight_draw());
+    }
+
+    #[test]
+    fn open_ended_gutshot_and_double_gutshot() {
+        let oesd = draws("9s8d", "7c6h2s");
+        assert!(oesd.open_ended && !oesd.gutshot && !oesd.double_gutshot);
+        // 4 tens, 4 fives, 3 nines, 3 eights
+        assert_eq!(oesd.outs, 14);
+
+        let gutshot = draws("9s8d", "Jc7h2s");
+        assert!(gutshot.gutshot && !gutshot.open_ended && !gutshot.double_gutshot);
+
+        let double = draws("9s7d", "Jc8h5s");
+        assert!(double.double_gutshot && !double.open_ended && !double.gutshot);
+    }
+
+    #[test]
+    fn ace_plays_both_ends_of_a_straight_draw() {
+        // A-2-3-4 waits on the five only
+        let wheel = draws("As2d", "4c3h9s");
+        assert!(wheel.gutshot && !wheel.open_ended);
+        // 2-3-4-5 takes the ace or the six
+        assert!(draws("5s4d", "3c2hKs").open_ended);
+        // J-Q-K-A only takes the ten
+        let broadway = draws("KsQd", "AcJh4s");
+        assert!(broadway.gutshot && !broadway.open_ended);
+    }
+
+    #[test]
+    fn paired_board() {
+        let d = draws("AhKh", "7h7d2h");
+        assert!(d.flush_draw && d.nut_flush_draw);
+        // 9 hearts and 6 cards pairing a hole card; a seven or deuce helps
+        // the board just as much
+        assert_eq!(d.outs, 15);
+
+        let straight = draws("9s8s", "7s7d6h");
+        assert!(straight.open_ended);
+        assert!(!straight.flush_draw && straight.backdoor_flush);
+    }
+
+    #[test]
+    fn three_to_a_straight_board() {
+        // A made straight has nothing left to draw to
+        let made = draws("QsJd", "Tc9h8d");
+        assert!(!made.is_straight_draw() && !made.backdoor_straight);
+        // The board's own straight draws don't count for a hand that can't improve on them
+        let board_draws = draws("3s2d", "Tc9h8d");
+        assert!(!board_draws.is_straight_draw() && !board_draws.backdoor_straight);
+        // A-K needs Q-J, which beats the board's Q-high straight
+        let broadway = draws("AsKd", "Tc9h8d");
+        assert!(!broadway.is_straight_draw() && broadway.backdoor_straight);
+        // With four to a straight on the turn, only a higher straight counts
+        let turn = draws("Ts2d", "5c6h7d8s");
+        assert!(turn.gutshot && !turn.open_ended);
+    }
+
+    #[test]
+    fn monotone_board() {
+        let nut = draws("KhQd", "Ah9h2h");
+        assert!(nut.flush_draw && nut.nut_flush_draw);
+        let second = draws("JhQd", "Ah9h2h");
+        assert!(second.flush_draw && !second.nut_flush_draw);
+        // Two hearts is already a flush
+        let made = draws("KhQh", "Ah9h2h");
+        assert!(!made.flush_draw);
+        // No heart: no flush draw, and the flush cards aren't outs
+        let none = draws("KsKd", "Ah9h2h");
+        assert!(!none.flush_draw && !none.backdoor_flush);
+        assert_eq!(none.outs, 2);
+    }
+
+    #[test]
+    fn backdoors_are_flop_only() {
+        let flop = draws("Ah5h", "Kh7c2d");
+        assert!(flop.backdoor_flush && !flop.flush_draw);
+        let turn = draws("Ah5h", "Kh7c2dQs");
+        assert!(!turn.backdoor_flush && !turn.backdoor_straight);
+    }
+
+    #[test]
+    fn nothing_to_draw_on_the_river() {
+        assert_eq!(draws("9h8h", "7h6h2cKsAd"), DrawInfo::default());
+    }
+}
@@ -1,24 +1,352 @@
 use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use rand::seq::SliceRandom;
 use rayon::prelude::*;
 
-use crate::card_encoding::{card_to_index, remaining_deck};
-use crate::cards::{hand_combos, Card};
+use crate::bucketing::{river_equity_vs_hands, runouts};
+use crate::card_encoding::{card_to_index, index_to_card, remaining_deck};
+use crate::cards::{hand_combos, Card, Rank};
 use crate::error::{GtoError, GtoResult};
-use crate::lookup_eval::evaluate_fast;
+use crate::hand_evaluator::HandCategory;
+use crate::lookup_eval::{evaluate_omaha, RuleSet};
+use crate::progress::{report, Progress, PROGRESS_STEPS};
 
 pub struct EquityResult {
     pub win: f64,
     pub tie: f64,
     pub lose: f64,
     pub simulations: usize,
+    /// Every runout (and villain combo) was enumerated rather than sampled,
+    /// so `simulations` is the exact count.
+    pub exact: bool,
+    /// How the runouts were won, tied and lost, when asked for.
+    pub category_breakdown: Option<CategoryBreakdown>,
+    /// Standard error of `equity()`; 0 when `exact`.
+    pub stderr: f64,
+    /// Hero's equity against each villain combo, when asked for (hand vs
+    /// range only).
+    pub villain_combos: Option<Vec<VillainComboEquity>>,
 }
 
 impl EquityResult {
     pub fn equity(&self) -> f64 {
         self.win + self.tie / 2.0
     }
+
+    /// 95% confidence interval for `equity()`, clamped to [0, 1].
+    pub fn ci95(&self) -> (f64, f64) {
+        let half_width = Z_95 * self.stderr;
+        (
+            (self.equity() - half_width).max(0.0),
+            (self.equity() + half_width).min(1.0),
+        )
+    }
+}
+
+/// Enumerate automatically when there are at most this many runout and
+/// villain combo pairings, e.g. any river or turn spot between two hands,
+/// or a flop between two hands (990 runouts).
+pub const EXACT_AUTO_LIMIT: usize = 5_000;
+
+/// Most pairings `Sampling::Exact` will enumerate; enough for two hands
+/// preflop (1,712,304 runouts).
+pub const EXACT_MAX: usize = 20_000_000;
+
+/// Most simulations an adaptive (`Precision`) run takes by default.
+pub const ADAPTIVE_MAX: usize = 10_000_000;
+
+/// Simulations between an adaptive run's convergence checks.
+const ADAPTIVE_BATCH: usize = 10_000;
+
+/// z-score of a two-sided 95% confidence interval.
+const Z_95: f64 = 1.96;
+
+/// Whether an equity calculation enumerates every runout or samples them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Sampling {
+    /// Enumerate up to `EXACT_AUTO_LIMIT` pairings, sample above it.
+    #[default]
+    Auto,
+    /// Always enumerate; errors above `EXACT_MAX` pairings.
+    Exact,
+    /// Always sample, even where enumerating would be cheaper.
+    MonteCarlo,
+}
+
+impl Sampling {
+    /// Whether to enumerate a space of `pairings` runout and villain combo
+    /// pairings.
+    fn enumerate(self, pairings: usize) -> GtoResult<bool> {
+        match self {
+            Sampling::Auto => Ok(pairings <= EXACT_AUTO_LIMIT),
+            Sampling::MonteCarlo => Ok(false),
+            Sampling::Exact if pairings <= EXACT_MAX => Ok(true),
+            Sampling::Exact => Err(GtoError::InvalidValue(format!(
+                "{} runouts are too many to enumerate (limit {})",
+                pairings, EXACT_MAX
+            ))),
+        }
+    }
+}
+
+/// Stop sampling once the 95% confidence interval on equity is narrow
+/// enough, or the cap is reached.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Precision {
+    /// Largest acceptable interval half-width, in percentage points of
+    /// equity (0.25 stops at ±0.25%).
+    pub half_width: f64,
+    /// Hard cap on simulations.
+    pub max_simulations: usize,
+}
+
+impl Precision {
+    /// Stop at `half_width` percentage points, or `ADAPTIVE_MAX`
+    /// simulations.
+    pub fn new(half_width: f64) -> Self {
+        Precision { half_width, max_simulations: ADAPTIVE_MAX }
+    }
+
+    fn validate(&self) -> GtoResult<()> {
+        if !(self.half_width.is_finite() && self.half_width > 0.0) {
+            return Err(GtoError::InvalidValue(format!(
+                "Precision must be a positive number of percentage points, got {}",
+                self.half_width
+            )));
+        }
+        if self.max_simulations == 0 {
+            return Err(GtoError::InvalidValue(
+                "Adaptive sampling needs at least one simulation".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn met(&self, stderr: f64) -> bool {
+        Z_95 * stderr * 100.0 <= self.half_width
+    }
+}
+
+/// Options for the hold'em equity calculations.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EquityOptions {
+    pub sampling: Sampling,
+    /// Fill in `EquityResult::category_breakdown`.
+    pub breakdown: bool,
+    /// Fill in `EquityResult::villain_combos` (hand vs range).
+    pub per_combo: bool,
+    /// Sample in batches until the confidence interval is this narrow;
+    /// `simulations` is then ignored. Has no effect on enumerated spots.
+    pub precision: Option<Precision>,
+}
+
+/// Win, tie and loss counts, with the hand categories behind them when
+/// asked for.
+#[derive(Debug, Clone, Default)]
+struct Tally {
+    wins: u64,
+    ties: u64,
+    losses: u64,
+    categories: Option<CategoryBreakdown>,
+}
+
+impl Tally {
+    fn new(breakdown: bool) -> Self {
+        Tally { categories: breakdown.then(CategoryBreakdown::default), ..Default::default() }
+    }
+
+    fn record(&mut self, rules: RuleSet, hero: u32, villain: u32) {
+        match hero.cmp(&villain) {
+            std::cmp::Ordering::Greater => self.wins += 1,
+            std::cmp::Ordering::Equal => self.ties += 1,
+            std::cmp::Ordering::Less => self.losses += 1,
+        }
+        if let Some(c) = self.categories.as_mut() {
+            c.record(rules, hero, villain, 1.0);
+        }
+    }
+
+    fn merge(mut self, other: Tally) -> Tally {
+        self.wins += other.wins;
+        self.ties += other.ties;
+        self.losses += other.losses;
+        if let (Some(c), Some(o)) = (self.categories.as_mut(), &other.categories) {
+            c.add(o, 1.0);
+        }
+        self
+    }
+
+    fn count(&self) -> u64 {
+        self.wins + self.ties + self.losses
+    }
+
+    fn equity(&self) -> f64 {
+        (self.wins as f64 + self.ties as f64 / 2.0) / self.count() as f64
+    }
+}
+
+/// Tally `(hero, villain)` hand scores in parallel.
+fn tally_scores(
+    rules: RuleSet,
+    breakdown: bool,
+    scores: impl ParallelIterator<Item = (u32, u32)>,
+) -> Tally {
+    scores
+        .fold(
+            || Tally::new(breakdown),
+            |mut tally, (hero, villain)| {
+                tally.record(rules, hero, villain);
+                tally
+            },
+        )
+        .reduce(|| Tally::new(breakdown), Tally::merge)
+}
+
+/// Standard error of the weighted mean equity of `tallies`, each runout
+/// scoring 1 (win), ½ (tie) or 0 (loss) and counting `weight` times. Uses
+/// the pooled variance, which overstates the error of sampling every
+/// villain combo equally rather than understating it.
+fn standard_error<'a>(tallies: impl IntoIterator<Item = (&'a Tally, f64)>) -> f64 {
+    let (mut total, mut sum, mut sum_sq, mut weight_sq) = (0.0, 0.0, 0.0, 0.0);
+    for (tally, weight) in tallies {
+        let (w, t) = (tally.wins as f64, tally.ties as f64);
+        total += weight * tally.count() as f64;
+        sum += weight * (w + t / 2.0);
+        sum_sq += weight * (w + t / 4.0);
+        weight_sq += weight * weight * tally.count() as f64;
+    }
+    if total == 0.0 {
+        return 0.0;
+    }
+    let mean = sum / total;
+    let variance = (sum_sq / total - mean * mean).max(0.0);
+    (variance * weight_sq).sqrt() / total
+}
+
+/// Hero's equity against one villain combo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VillainComboEquity {
+    /// The combo, higher rank first (e.g. "AsKd").
+    pub combo: String,
+    /// Share of the villain range this combo makes up; the weights sum to 1.
+    pub weight: f64,
+    pub equity: f64,
+}
+
+/// Hero equity within this much of 50% counts as a coinflip.
+pub const COINFLIP_MARGIN: f64 = 0.1;
+
+impl VillainComboEquity {
+    /// Bucket label: "You beat", "Coinflip" or "You lose to".
+    pub fn matchup(&self) -> &'static str {
+        if self.equity > 0.5 + COINFLIP_MARGIN {
+            "You beat"
+        } else if self.equity < 0.5 - COINFLIP_MARGIN {
+            "You lose to"
+        } else {
+            "Coinflip"
+        }
+    }
+}
+
+/// Villain combos grouped into "You beat", "Coinflip" and "You lose to",
+/// one line per non-empty bucket with its combo count and share of the
+/// range, best matchups first.
+pub fn write_combo_buckets(out: &mut impl fmt::Write, combos: &[VillainComboEquity]) -> fmt::Result {
+    let mut sorted: Vec<&VillainComboEquity> = combos.iter().collect();
+    sorted.sort_by(|a, b| b.equity.total_cmp(&a.equity));
+    for bucket in ["You beat", "Coinflip", "You lose to"] {
+        let members: Vec<&&VillainComboEquity> =
+            sorted.iter().filter(|c| c.matchup() == bucket).collect();
+        if members.is_empty() {
+            continue;
+        }
+        let share: f64 = members.iter().map(|c| c.weight).sum();
+        let list: Vec<String> = members
+            .iter()
+            .map(|c| format!("{} {:.0}%", c.combo, c.equity * 100.0))
+            .collect();
+        writeln!(
+            out,
+            "{} ({} combos, {:.1}% of range): {}",
+            bucket,
+            members.len(),
+            share * 100.0,
+            list.join(", ")
+        )?;
+    }
+    Ok(())
+}
+
+/// Hand categories behind an equity result, indexed by `HandCategory as
+/// usize`: hero's category on the runouts hero won or tied, villain's on
+/// the ones hero lost. Each entry is a fraction of all runouts, so `wins`
+/// sums to `EquityResult::win`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CategoryBreakdown {
+    pub wins: [f64; 10],
+    pub ties: [f64; 10],
+    pub losses: [f64; 10],
+}
+
+const CATEGORIES: [HandCategory; 10] = [
+    HandCategory::HighCard,
+    HandCategory::OnePair,
+    HandCategory::TwoPair,
+    HandCategory::ThreeOfAKind,
+    HandCategory::Straight,
+    HandCategory::Flush,
+    HandCategory::FullHouse,
+    HandCategory::FourOfAKind,
+    HandCategory::StraightFlush,
+    HandCategory::RoyalFlush,
+];
+
+impl CategoryBreakdown {
+    fn record(&mut self, rules: RuleSet, hero: u32, villain: u32, weight: f64) {
+        match hero.cmp(&villain) {
+            std::cmp::Ordering::Greater => self.wins[rules.category(hero) as usize] += weight,
+            std::cmp::Ordering::Equal => self.ties[rules.category(hero) as usize] += weight,
+            std::cmp::Ordering::Less => self.losses[rules.category(villain) as usize] += weight,
+        }
+    }
+
+    /// Add `other` scaled by `weight`.
+    fn add(&mut self, other: &CategoryBreakdown, weight: f64) {
+        for i in 0..CATEGORIES.len() {
+            self.wins[i] += other.wins[i] * weight;
+            self.ties[i] += other.ties[i] * weight;
+            self.losses[i] += other.losses[i] * weight;
+        }
+    }
+
+    fn scaled(&self, factor: f64) -> CategoryBreakdown {
+        let mut scaled = CategoryBreakdown::default();
+        scaled.add(self, factor);
+        scaled
+    }
+}
+
+impl fmt::Display for CategoryBreakdown {
+    /// One row per category that came up, strongest first.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<16} {:>6} {:>6} {:>8}", "Category", "Win", "Tie", "Lose to")?;
+        for (i, category) in CATEGORIES.iter().enumerate().rev() {
+            let (w, t, l) = (self.wins[i], self.ties[i], self.losses[i]);
+            if w + t + l > 0.0 {
+                writeln!(
+                    f,
+                    "{:<16} {:>5.1}% {:>5.1}% {:>7.1}%",
+                    category.to_string(),
+                    w * 100.0,
+                    t * 100.0,
+                    l * 100.0
+                )?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl fmt::Display for EquityResult {
@@ -30,7 +358,25 @@ impl fmt::Display for EquityResult {
             self.tie * 100.0,
             self.lose * 100.0,
             self.equity() * 100.0,
-        )
+        )?;
+        if !self.exact {
+            let (low, high) = self.ci95();
+            write!(
+                f,
+                " ± {:.2}% (95% CI {:.1}-{:.1}%)",
+                Z_95 * self.stderr * 100.0,
+                low * 100.0,
+                high * 100.0
+            )?;
+        }
+        if let Some(breakdown) = &self.category_breakdown {
+            write!(f, "\n{}", breakdown)?;
+        }
+        if let Some(combos) = &self.villain_combos {
+            writeln!(f)?;
+            write_combo_buckets(f, combos)?;
+        }
+        Ok(())
     }
 }
 
@@ -39,6 +385,55 @@ pub fn equity_vs_hand(
     hand2: &[Card],
     board: Option<&[Card]>,
     simulations: usize,
+) -> GtoResult<EquityResult> {
+    equity_vs_hand_with_progress(hand1, hand2, board, simulations, None)
+}
+
+/// [`equity_vs_hand`], reporting `(simulations done, total)` to `progress`.
+pub fn equity_vs_hand_with_progress(
+    hand1: &[Card],
+    hand2: &[Card],
+    board: Option<&[Card]>,
+    simulations: usize,
+    progress: Progress,
+) -> GtoResult<EquityResult> {
+    hand_vs_hand(RuleSet::Holdem, hand1, hand2, board, simulations, EquityOptions::default(), progress)
+}
+
+/// [`equity_vs_hand`] with explicit sampling and breakdown options.
+/// `simulations` is ignored when the runouts are enumerated.
+pub fn equity_vs_hand_with_options(
+    hand1: &[Card],
+    hand2: &[Card],
+    board: Option<&[Card]>,
+    simulations: usize,
+    options: EquityOptions,
+) -> GtoResult<EquityResult> {
+    hand_vs_hand(RuleSet::Holdem, hand1, hand2, board, simulations, options, None)
+}
+
+/// [`equity_vs_hand_with_options`] under short-deck rules (see
+/// `evaluate_short_deck`). Errors if any card is a 2-5.
+pub fn equity_vs_hand_short_deck(
+    hand1: &[Card],
+    hand2: &[Card],
+    board: Option<&[Card]>,
+    simulations: usize,
+    options: EquityOptions,
+) -> GtoResult<EquityResult> {
+    let board_cards = board.unwrap_or(&[]);
+    check_short_deck(hand1.iter().chain(hand2).chain(board_cards))?;
+    hand_vs_hand(RuleSet::ShortDeck, hand1, hand2, board, simulations, options, None)
+}
+
+fn hand_vs_hand(
+    rules: RuleSet,
+    hand1: &[Card],
+    hand2: &[Card],
+    board: Option<&[Card]>,
+    simulations: usize,
+    options: EquityOptions,
+    progress: Progress,
 ) -> GtoResult<EquityResult> {
     let board = board.unwrap_or(&[]);
 
@@ -51,57 +446,256 @@ pub fn equity_vs_hand(
     dead.extend_from_slice(&h1);
     dead.extend_from_slice(&h2);
     dead.extend_from_slice(&board_idx);
-    let remaining = remaining_deck(&dead);
+    let remaining = rules.remaining_deck(&dead);
     let cards_needed = 5 - board_idx.len();
 
-    let results: Vec<(u64, u64, u64)> = (0..simulations)
-        .into_par_iter()
-        .map(|_| {
-            let mut rng = rand::thread_rng();
-            let mut deck = remaining.clone();
-            deck.shuffle(&mut rng);
-
-            // Build 7-card hands directly as [u8; 7]
-            let mut all1 = [0u8; 7];
-            let mut all2 = [0u8; 7];
-            all1[0] = h1[0]; all1[1] = h1[1];
-            all2[0] = h2[0]; all2[1] = h2[1];
-            for (i, &c) in board_idx.iter().chain(deck[..cards_needed].iter()).enumerate() {
-                all1[2 + i] = c;
-                all2[2 + i] = c;
-            }
+    let score = |runout: &[u8]| {
+        // Build 7-card hands directly as [u8; 7]
+        let mut all1 = [0u8; 7];
+        let mut all2 = [0u8; 7];
+        all1[0] = h1[0]; all1[1] = h1[1];
+        all2[0] = h2[0]; all2[1] = h2[1];
+        for (i, &c) in board_idx.iter().chain(runout).enumerate() {
+            all1[2 + i] = c;
+            all2[2 + i] = c;
+        }
 
-            let r1 = evaluate_fast(&all1);
-            let r2 = evaluate_fast(&all2);
+        (rules.evaluate(&all1), rules.evaluate(&all2))
+    };
+    let simulate = |_| {
+        let mut rng = rand::thread_rng();
+        let mut deck = remaining.clone();
+        deck.shuffle(&mut rng);
+        score(&deck[..cards_needed])
+    };
 
-            match r1.cmp(&r2) {
-                std::cmp::Ordering::Greater => (1, 0, 0),
-                std::cmp::Ordering::Equal => (0, 1, 0),
-                std::cmp::Ordering::Less => (0, 0, 1),
-            }
-        })
-        .collect();
+    let exact = options.sampling.enumerate(choose(remaining.len(), cards_needed))?;
+    let precision = options.precision.filter(|_| !exact);
+    if let Some(p) = &precision {
+        p.validate()?;
+    }
+    let runouts = if exact { all_runouts(&remaining, cards_needed) } else { Vec::new() };
+    let total = match precision {
+        _ if exact => runouts.len(),
+        Some(p) => p.max_simulations,
+        None => simulations,
+    };
 
-    let (wins, ties, losses) = results
-        .iter()
-        .fold((0u64, 0u64, 0u64), |acc, &(w, t, l)| {
-            (acc.0 + w, acc.1 + t, acc.2 + l)
-        });
+    // Run in batches between convergence checks or progress reports;
+    // without either, run in one
+    let chunk = match (precision, progress) {
+        (Some(_), _) => ADAPTIVE_BATCH,
+        (None, Some(_)) => (total / PROGRESS_STEPS).max(1),
+        (None, None) => total.max(1),
+    };
+    let mut tally = Tally::new(options.breakdown);
+    let mut done = 0;
+    while done < total {
+        let n = chunk.min(total - done);
+        let batch = if exact {
+            let scores = runouts[done..done + n].par_iter().map(|r| score(&r[..cards_needed]));
+            tally_scores(rules, options.breakdown, scores)
+        } else {
+            tally_scores(rules, options.breakdown, (0..n).into_par_iter().map(simulate))
+        };
+        tally = tally.merge(batch);
+        done += n;
+        if precision.is_some_and(|p| p.met(standard_error([(&tally, 1.0)]))) {
+            report(progress, total, total);
+            break;
+        }
+        report(progress, done, total);
+    }
 
-    let total = (wins + ties + losses) as f64;
+    let total = tally.count() as f64;
     Ok(EquityResult {
-        win: wins as f64 / total,
-        tie: ties as f64 / total,
-        lose: losses as f64 / total,
+        win: tally.wins as f64 / total,
+        tie: tally.ties as f64 / total,
+        lose: tally.losses as f64 / total,
         simulations: total as usize,
+        exact,
+        stderr: if exact { 0.0 } else { standard_error([(&tally, 1.0)]) },
+        category_breakdown: tally.categories.map(|c| c.scaled(1.0 / total)),
+        villain_combos: None,
     })
 }
 
+/// Every `k`-card runout from `deck`, padded to five cards.
+fn all_runouts(deck: &[u8], k: usize) -> Vec<[u8; 5]> {
+    fn extend(deck: &[u8], k: usize, runout: &mut [u8; 5], depth: usize, out: &mut Vec<[u8; 5]>) {
+        if depth == k {
+            out.push(*runout);
+            return;
+        }
+        for (i, &card) in deck.iter().enumerate() {
+            runout[depth] = card;
+            extend(&deck[i + 1..], k, runout, depth + 1, out);
+        }
+    }
+    let mut out = Vec::with_capacity(choose(deck.len(), k));
+    extend(deck, k, &mut [0u8; 5], 0, &mut out);
+    out
+}
+
+/// Binomial coefficient `n` choose `k`.
+fn choose(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+/// [`equity_vs_hand`] on a dedicated pool of `threads` workers instead of
+/// rayon's global pool (0 = all cores, 1 = serial).
+pub fn equity_vs_hand_threads(
+    hand1: &[Card],
+    hand2: &[Card],
+    board: Option<&[Card]>,
+    simulations: usize,
+    threads: usize,
+) -> GtoResult<EquityResult> {
+    in_pool(threads, || equity_vs_hand(hand1, hand2, board, simulations))
+}
+
+/// [`equity_vs_range`] on a dedicated pool of `threads` workers instead of
+/// rayon's global pool (0 = all cores, 1 = serial).
+pub fn equity_vs_range_threads(
+    hand: &[Card],
+    villain_range: &[String],
+    board: Option<&[Card]>,
+    simulations: usize,
+    weighted: bool,
+    threads: usize,
+) -> GtoResult<EquityResult> {
+    in_pool(threads, || {
+        equity_vs_range(hand, villain_range, board, simulations, weighted)
+    })
+}
+
+pub(crate) fn in_pool<T, F>(threads: usize, run: F) -> GtoResult<T>
+where
+    T: Send,
+    F: FnOnce() -> GtoResult<T> + Send,
+{
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(|e| GtoError::InvalidValue(format!("could not build thread pool: {}", e)))?;
+    pool.install(run)
+}
+
+/// Equity of `hand` against a villain range.
+///
+/// With `weighted`, every villain combo that survives the hero and board
+/// cards counts once, so each hand class is weighted by its remaining combo
+/// count (`AA` with one ace blocked is worth 3 combos, not 6), and combos
+/// listed twice in the range are counted once. Without it, every range
+/// entry counts equally however many of its combos survive.
 pub fn equity_vs_range(
     hand: &[Card],
     villain_range: &[String],
     board: Option<&[Card]>,
     simulations: usize,
+    weighted: bool,
+) -> GtoResult<EquityResult> {
+    equity_vs_range_with_progress(hand, villain_range, board, simulations, weighted, None)
+}
+
+/// [`equity_vs_range`], reporting `(villain combos done, total)` to
+/// `progress`.
+pub fn equity_vs_range_with_progress(
+    hand: &[Card],
+    villain_range: &[String],
+    board: Option<&[Card]>,
+    simulations: usize,
+    weighted: bool,
+    progress: Progress,
+) -> GtoResult<EquityResult> {
+    hand_vs_range(
+        RuleSet::Holdem,
+        hand,
+        villain_range,
+        board,
+        simulations,
+        weighted,
+        EquityOptions::default(),
+        progress,
+    )
+}
+
+/// [`equity_vs_range`] with explicit sampling and breakdown options.
+/// `simulations` is ignored when the runouts are enumerated.
+pub fn equity_vs_range_with_options(
+    hand: &[Card],
+    villain_range: &[String],
+    board: Option<&[Card]>,
+    simulations: usize,
+    weighted: bool,
+    options: EquityOptions,
+) -> GtoResult<EquityResult> {
+    hand_vs_range(
+        RuleSet::Holdem,
+        hand,
+        villain_range,
+        board,
+        simulations,
+        weighted,
+        options,
+        None,
+    )
+}
+
+/// [`equity_vs_range_with_options`] under short-deck rules. Errors if a
+/// hero or board card, or any hand in the range, uses a 2-5 (e.g. "22" or
+/// "A5s").
+pub fn equity_vs_range_short_deck(
+    hand: &[Card],
+    villain_range: &[String],
+    board: Option<&[Card]>,
+    simulations: usize,
+    weighted: bool,
+    options: EquityOptions,
+) -> GtoResult<EquityResult> {
+    check_short_deck(hand.iter().chain(board.unwrap_or(&[])))?;
+    for notation in villain_range {
+        let combos = hand_combos(notation)?;
+        if combos
+            .iter()
+            .any(|(c1, c2)| c1.rank < Rank::Six || c2.rank < Rank::Six)
+        {
+            return Err(GtoError::NotInShortDeck(notation.clone()));
+        }
+    }
+    hand_vs_range(
+        RuleSet::ShortDeck,
+        hand,
+        villain_range,
+        board,
+        simulations,
+        weighted,
+        options,
+        None,
+    )
+}
+
+/// The first card below a Six, as an error.
+fn check_short_deck<'a>(cards: impl IntoIterator<Item = &'a Card>) -> GtoResult<()> {
+    match cards.into_iter().find(|c| c.rank < Rank::Six) {
+        Some(card) => Err(GtoError::NotInShortDeck(card.to_string())),
+        None => Ok(()),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn hand_vs_range(
+    rules: RuleSet,
+    hand: &[Card],
+    villain_range: &[String],
+    board: Option<&[Card]>,
+    simulations: usize,
+    weighted: bool,
+    options: EquityOptions,
+    progress: Progress,
 ) -> GtoResult<EquityResult> {
     let board = board.unwrap_or(&[]);
 
@@ -111,13 +705,23 @@ pub fn equity_vs_range(
     // Dead cards for filtering combos
     let dead_set: std::collections::HashSet<Card> = hand.iter().chain(board.iter()).copied().collect();
 
-    // Convert villain combos to u8 index pairs
-    let mut all_combos: Vec<[u8; 2]> = Vec::new();
+    // Convert villain combos to u8 index pairs, each with its weight
+    let mut all_combos: Vec<([u8; 2], f64)> = Vec::new();
+    let mut seen: std::collections::HashSet<[u8; 2]> = std::collections::HashSet::new();
     for notation in villain_range {
-        for (c1, c2) in hand_combos(notation)? {
-            if !dead_set.contains(&c1) && !dead_set.contains(&c2) {
-                all_combos.push([card_to_index(&c1), card_to_index(&c2)]);
-            }
+        let live: Vec<[u8; 2]> = hand_combos(notation)?
+            .into_iter()
+            .filter(|(c1, c2)| !dead_set.contains(c1) && !dead_set.contains(c2))
+            .map(|(c1, c2)| {
+                let (a, b) = (card_to_index(&c1), card_to_index(&c2));
+                [a.min(b), a.max(b)]
+            })
+            .collect();
+        if weighted {
+            all_combos.extend(live.into_iter().filter(|c| seen.insert(*c)).map(|c| (c, 1.0)));
+        } else {
+            let weight = 1.0 / live.len() as f64;
+            all_combos.extend(live.into_iter().map(|c| (c, weight)));
         }
     }
 
@@ -125,61 +729,543 @@ pub fn equity_vs_range(
         return Err(GtoError::NoValidCombos);
     }
 
-    let sims_per = (simulations / all_combos.len()).max(1);
     let cards_needed = 5 - board_idx.len();
+    // Every villain combo leaves the same number of cards to deal from
+    let mut known = hero.to_vec();
+    known.extend_from_slice(&board_idx);
+    let runouts_per = choose(rules.remaining_deck(&known).len() - 2, cards_needed);
+    let exact = options.sampling.enumerate(runouts_per * all_combos.len())?;
+    let precision = options.precision.filter(|_| !exact);
+    if let Some(p) = &precision {
+        p.validate()?;
+    }
+    let sims_per = if exact { runouts_per } else { (simulations / all_combos.len()).max(1) };
 
-    let results: Vec<(u64, u64, u64)> = all_combos
-        .par_iter()
-        .map(|villain| {
-            let mut dead = Vec::with_capacity(4 + board_idx.len());
-            dead.extend_from_slice(&hero);
-            dead.extend_from_slice(&board_idx);
-            dead.extend_from_slice(villain);
-            let remaining = remaining_deck(&dead);
+    // Deal `sims` runouts (or all of them, when exact) against one villain
+    // combo
+    let run_combo = |villain: &[u8; 2], tally: &mut Tally, sims: usize| {
+        let mut dead = Vec::with_capacity(4 + board_idx.len());
+        dead.extend_from_slice(&hero);
+        dead.extend_from_slice(&board_idx);
+        dead.extend_from_slice(villain);
+        let remaining = rules.remaining_deck(&dead);
 
-            let mut wins = 0u64;
-            let mut ties = 0u64;
-            let mut losses = 0u64;
+        let mut showdown = |runout: &[u8]| {
+            let mut all1 = [0u8; 7];
+            let mut all2 = [0u8; 7];
+            all1[0] = hero[0]; all1[1] = hero[1];
+            all2[0] = villain[0]; all2[1] = villain[1];
+            for (i, &c) in board_idx.iter().chain(runout).enumerate() {
+                all1[2 + i] = c;
+                all2[2 + i] = c;
+            }
+            tally.record(rules, rules.evaluate(&all1), rules.evaluate(&all2));
+        };
 
+        if exact {
+            for runout in all_runouts(&remaining, cards_needed) {
+                showdown(&runout[..cards_needed]);
+            }
+        } else {
             let mut rng = rand::thread_rng();
-            for _ in 0..sims_per {
+            for _ in 0..sims {
                 let mut deck = remaining.clone();
                 deck.shuffle(&mut rng);
+                showdown(&deck[..cards_needed]);
+            }
+        }
+    };
 
-                let mut all1 = [0u8; 7];
-                let mut all2 = [0u8; 7];
-                all1[0] = hero[0]; all1[1] = hero[1];
-                all2[0] = villain[0]; all2[1] = villain[1];
-                for (i, &c) in board_idx.iter().chain(deck[..cards_needed].iter()).enumerate() {
-                    all1[2 + i] = c;
-                    all2[2 + i] = c;
-                }
+    let mut tallies = vec![Tally::new(options.breakdown); all_combos.len()];
+    let weighted_tallies = |tallies: &[Tally]| {
+        standard_error(tallies.iter().zip(&all_combos).map(|(t, &(_, weight))| (t, weight)))
+    };
+    if let Some(p) = precision {
+        // Every combo gets the same number of runouts each round, so the
+        // weights keep meaning the same thing as in a fixed-size run
+        let per_round = (ADAPTIVE_BATCH / all_combos.len()).max(1);
+        let mut done = 0;
+        while done < p.max_simulations {
+            tallies
+                .par_iter_mut()
+                .zip(&all_combos)
+                .for_each(|(tally, (villain, _))| run_combo(villain, tally, per_round));
+            done += per_round * all_combos.len();
+            if p.met(weighted_tallies(&tallies)) {
+                break;
+            }
+            report(progress, done.min(p.max_simulations), p.max_simulations);
+        }
+        report(progress, p.max_simulations, p.max_simulations);
+    } else {
+        let combos_done = AtomicUsize::new(0);
+        tallies.par_iter_mut().zip(&all_combos).for_each(|(tally, (villain, _))| {
+            run_combo(villain, tally, sims_per);
+
+            // Workers report intermediate steps; completion is reported
+            // below, once every combo is in
+            let done = combos_done.fetch_add(1, Ordering::Relaxed) + 1;
+            if done < all_combos.len() {
+                report(progress, done, all_combos.len());
+            }
+        });
+        report(progress, all_combos.len(), all_combos.len());
+    }
+
+    // Every combo ran the same number of runouts, so weighting the raw counts
+    // weights each combo's outcome frequencies.
+    let (wins, ties, losses) = tallies.iter().zip(&all_combos).fold(
+        (0.0, 0.0, 0.0),
+        |acc, (t, &(_, weight))| {
+            (
+                acc.0 + t.wins as f64 * weight,
+                acc.1 + t.ties as f64 * weight,
+                acc.2 + t.losses as f64 * weight,
+            )
+        },
+    );
+
+    let total = wins + ties + losses;
+    let category_breakdown = options.breakdown.then(|| {
+        let mut sum = CategoryBreakdown::default();
+        for (tally, &(_, weight)) in tallies.iter().zip(&all_combos) {
+            if let Some(c) = &tally.categories {
+                sum.add(c, weight / total);
+            }
+        }
+        sum
+    });
+    let villain_combos = options.per_combo.then(|| {
+        let total_weight: f64 = all_combos.iter().map(|&(_, weight)| weight).sum();
+        tallies
+            .iter()
+            .zip(&all_combos)
+            .map(|(tally, &(villain, weight))| VillainComboEquity {
+                combo: format!("{}{}", index_to_card(villain[1]), index_to_card(villain[0])),
+                weight: weight / total_weight,
+                equity: tally.equity(),
+            })
+            .collect()
+    });
+    Ok(EquityResult {
+        win: wins / total,
+        tie: ties / total,
+        lose: losses / total,
+        simulations: tallies.iter().map(|t| t.count() as usize).sum(),
+        exact,
+        stderr: if exact { 0.0 } else { weighted_tallies(&tallies) },
+        category_breakdown,
+        villain_combos,
+    })
+}
 
-                let r1 = evaluate_fast(&all1);
-                let r2 = evaluate_fast(&all2);
+/// One hero combo's equity against what's left of the villain range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComboEquity {
+    pub combo: (Card, Card),
+    pub equity: f64,
+    /// Villain combos that survive this combo's cards and the board.
+    pub villain_combos: usize,
+}
+
+/// Equity of a hero range against a villain range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeEquity {
+    /// Hero's overall equity: every (hero, villain) combo pairing that
+    /// doesn't share a card counts once.
+    pub equity: f64,
+    /// Every live hero combo, in range order.
+    pub combos: Vec<ComboEquity>,
+    /// Whether every runout was enumerated (a complete board) rather than
+    /// sampled.
+    pub exact: bool,
+}
 
-                match r1.cmp(&r2) {
-                    std::cmp::Ordering::Greater => wins += 1,
-                    std::cmp::Ordering::Equal => ties += 1,
-                    std::cmp::Ordering::Less => losses += 1,
+impl RangeEquity {
+    /// Hero combos from best equity to worst.
+    pub fn sorted_by_equity(&self) -> Vec<&ComboEquity> {
+        let mut combos: Vec<&ComboEquity> = self.combos.iter().collect();
+        combos.sort_by(|a, b| b.equity.total_cmp(&a.equity));
+        combos
+    }
+}
+
+/// Equity of `hero_range` against `villain_range`, overall and per hero
+/// combo. Conflicting combos are removed pairing by pairing, so each hero
+/// combo faces only the villain combos its cards leave live, and weighs in
+/// the overall number by how many that is. On a complete board every
+/// pairing is compared exactly; otherwise `simulations` is split evenly
+/// across the hero combos. Hero combos blocked by the board, or that leave
+/// villain no live combos, are skipped.
+pub fn equity_range_vs_range(
+    hero_range: &[String],
+    villain_range: &[String],
+    board: Option<&[Card]>,
+    simulations: usize,
+) -> GtoResult<RangeEquity> {
+    let board_cards = board.unwrap_or(&[]);
+    let board_idx: Vec<u8> = board_cards.iter().map(card_to_index).collect();
+    let hero_combos = live_combos(hero_range, &board_idx)?;
+    let villain_combos = live_combos(villain_range, &board_idx)?;
+    if hero_combos.is_empty() || villain_combos.is_empty() {
+        return Err(GtoError::NoValidCombos);
+    }
+    let disjoint = |a: &[u8; 2], b: &[u8; 2]| !a.contains(&b[0]) && !a.contains(&b[1]);
+
+    let exact = board_idx.len() == 5;
+    let results: Vec<GtoResult<Option<ComboEquity>>> = if exact {
+        let score = |combo: &[u8; 2]| {
+            let mut cards = [0u8; 7];
+            cards[..2].copy_from_slice(combo);
+            cards[2..].copy_from_slice(&board_idx);
+            RuleSet::Holdem.evaluate(&cards)
+        };
+        let villain_scores: Vec<u32> = villain_combos.par_iter().map(score).collect();
+        hero_combos
+            .par_iter()
+            .map(|hero| {
+                let hero_score = score(hero);
+                let (mut won, mut live) = (0.0, 0usize);
+                for (villain, &villain_score) in villain_combos.iter().zip(&villain_scores) {
+                    if disjoint(hero, villain) {
+                        live += 1;
+                        won += match hero_score.cmp(&villain_score) {
+                            std::cmp::Ordering::Greater => 1.0,
+                            std::cmp::Ordering::Equal => 0.5,
+                            std::cmp::Ordering::Less => 0.0,
+                        };
+                    }
+                }
+                Ok((live > 0).then(|| ComboEquity {
+                    combo: (index_to_card(hero[0]), index_to_card(hero[1])),
+                    equity: won / live as f64,
+                    villain_combos: live,
+                }))
+            })
+            .collect()
+    } else {
+        let sims_per = (simulations / hero_combos.len()).max(1);
+        hero_combos
+            .iter()
+            .map(|hero| {
+                let live = villain_combos.iter().filter(|v| disjoint(hero, v)).count();
+                let cards = [index_to_card(hero[0]), index_to_card(hero[1])];
+                match equity_vs_range(&cards, villain_range, board, sims_per, true) {
+                    Ok(result) => Ok(Some(ComboEquity {
+                        combo: (cards[0], cards[1]),
+                        equity: result.equity(),
+                        villain_combos: live,
+                    })),
+                    Err(GtoError::NoValidCombos) => Ok(None),
+                    Err(e) => Err(e),
                 }
+            })
+            .collect()
+    };
+
+    let mut combos = Vec::with_capacity(results.len());
+    for result in results {
+        combos.extend(result?);
+    }
+    let pairings: usize = combos.iter().map(|c| c.villain_combos).sum();
+    if pairings == 0 {
+        return Err(GtoError::NoValidCombos);
+    }
+    let equity = combos
+        .iter()
+        .map(|c| c.equity * c.villain_combos as f64)
+        .sum::<f64>()
+        / pairings as f64;
+    Ok(RangeEquity { equity, combos, exact })
+}
+
+/// Distinct combos of a range that don't touch the board, as sorted index
+/// pairs in range order.
+fn live_combos(range: &[String], board: &[u8]) -> GtoResult<Vec<[u8; 2]>> {
+    let mut combos: Vec<[u8; 2]> = Vec::new();
+    let mut seen: std::collections::HashSet<[u8; 2]> = std::collections::HashSet::new();
+    for notation in range {
+        for (c1, c2) in hand_combos(notation)? {
+            let (a, b) = (card_to_index(&c1), card_to_index(&c2));
+            let combo = [a.max(b), a.min(b)];
+            if !board.contains(&a) && !board.contains(&b) && seen.insert(combo) {
+                combos.push(combo);
             }
+        }
+    }
+    Ok(combos)
+}
 
-            (wins, ties, losses)
+/// Histogram of hero's per-combo equities against `villain_range`:
+/// `num_buckets` equal-width equity buckets as `(equity_midpoint,
+/// fraction_of_combos)`, lowest first. Empty buckets are included, so the
+/// fractions always sum to 1.
+pub fn equity_distribution(
+    hero_range: &[String],
+    villain_range: &[String],
+    board: Option<&[Card]>,
+    simulations: usize,
+    num_buckets: usize,
+) -> GtoResult<Vec<(f64, f64)>> {
+    if num_buckets == 0 {
+        return Err(GtoError::InvalidValue(
+            "Equity distribution needs at least one bucket".to_string(),
+        ));
+    }
+    let equities = equity_range_vs_range(hero_range, villain_range, board, simulations)?.combos;
+
+    let mut counts = vec![0usize; num_buckets];
+    for combo in &equities {
+        let bucket = ((combo.equity * num_buckets as f64) as usize).min(num_buckets - 1);
+        counts[bucket] += 1;
+    }
+    let total = equities.len() as f64;
+    Ok(counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| ((i as f64 + 0.5) / num_buckets as f64, count as f64 / total))
+        .collect())
+}
+
+/// Hero's river equity on each runout of a flop or turn, and its spread.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunoutDistribution {
+    /// Equity against the live villain combos on each runout, lowest first.
+    pub equities: Vec<f64>,
+    pub min: f64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Whether every runout was counted rather than a sample of them.
+    pub exact: bool,
+}
+
+impl RunoutDistribution {
+    fn new(mut equities: Vec<f64>, exact: bool) -> Self {
+        equities.sort_by(f64::total_cmp);
+        let percentile = |p: f64| {
+            // Linear interpolation between the closest ranks
+            let pos = p * (equities.len() - 1) as f64;
+            let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+            equities[lo] + (equities[hi] - equities[lo]) * (pos - lo as f64)
+        };
+        RunoutDistribution {
+            min: equities[0],
+            p25: percentile(0.25),
+            median: percentile(0.5),
+            p75: percentile(0.75),
+            max: equities[equities.len() - 1],
+            mean: equities.iter().sum::<f64>() / equities.len() as f64,
+            equities,
+            exact,
+        }
+    }
+
+    /// Share of runouts in each of `bins` equal-width equity bins, lowest
+    /// first.
+    pub fn histogram(&self, bins: usize) -> Vec<f64> {
+        let mut counts = vec![0.0; bins];
+        if bins == 0 {
+            return counts;
+        }
+        for &equity in &self.equities {
+            counts[((equity * bins as f64) as usize).min(bins - 1)] += 1.0;
+        }
+        let total = self.equities.len() as f64;
+        counts.iter_mut().for_each(|c| *c /= total);
+        counts
+    }
+}
+
+/// Distribution of `hand`'s river equity against `villain_range` over the
+/// runouts of a flop or turn `board`. Each runout counts once, with equity
+/// taken exactly against every villain combo it leaves live. Every runout
+/// is enumerated when there are at most `samples`; otherwise `samples` of
+/// them are drawn at random.
+pub fn runout_equity_distribution(
+    hand: &[Card],
+    villain_range: &[String],
+    board: &[Card],
+    samples: usize,
+) -> GtoResult<RunoutDistribution> {
+    if hand.len() != 2 {
+        return Err(GtoError::InvalidHandSize);
+    }
+    if !(3..=4).contains(&board.len()) {
+        return Err(GtoError::InvalidValue(format!(
+            "Runout equity needs a flop or turn board, got {} cards",
+            board.len()
+        )));
+    }
+    if samples == 0 {
+        return Err(GtoError::InvalidValue("Runout equity needs at least one sample".to_string()));
+    }
+    let hero = [card_to_index(&hand[0]), card_to_index(&hand[1])];
+    let board_idx: Vec<u8> = board.iter().map(card_to_index).collect();
+    if hero[0] == hero[1] || hero.iter().any(|c| board_idx.contains(c)) {
+        return Err(GtoError::InvalidValue("Your hand shares a card with the board".to_string()));
+    }
+    let villains: Vec<[u8; 2]> =
+        live_combos(villain_range, &board_idx)?.into_iter().filter(|v| !v.iter().any(|c| hero.contains(c))).collect();
+    if villains.is_empty() {
+        return Err(GtoError::NoValidCombos);
+    }
+
+    let mut dead = [false; 52];
+    for &c in hero.iter().chain(&board_idx) {
+        dead[c as usize] = true;
+    }
+    let mut runouts = runouts(&board_idx, &dead);
+    let exact = runouts.len() <= samples;
+    if !exact {
+        runouts.partial_shuffle(&mut rand::thread_rng(), samples);
+        runouts.truncate(samples);
+    }
+
+    let equities: Vec<f64> = runouts
+        .par_iter()
+        .filter_map(|runout| {
+            let mut full = [0u8; 5];
+            for (i, &c) in board_idx.iter().chain(runout).enumerate() {
+                full[i] = c;
+            }
+            river_equity_vs_hands(hero, &full, &villains)
         })
         .collect();
+    // Only when every runout blocks the whole range
+    if equities.is_empty() {
+        return Err(GtoError::NoValidCombos);
+    }
+    Ok(RunoutDistribution::new(equities, exact))
+}
+
+/// Omaha equity of a 4-card `hand1` against a 4-card `hand2`. Each player
+/// must use exactly two hole cards (see `evaluate_omaha`).
+pub fn equity_vs_hand_omaha(
+    hand1: &[Card],
+    hand2: &[Card],
+    board: Option<&[Card]>,
+    simulations: usize,
+) -> GtoResult<EquityResult> {
+    let hero = omaha_hand(hand1)?;
+    let villain = omaha_hand(hand2)?;
+    let board_idx = omaha_board(board)?;
+
+    let results: Vec<(u64, u64, u64)> = (0..simulations.max(1))
+        .into_par_iter()
+        .map(|_| omaha_showdowns(&hero, &villain, &board_idx, 1, &mut rand::thread_rng()))
+        .collect();
+    Ok(tally(&results))
+}
+
+/// Omaha equity of a 4-card `hand` against a range of explicit 4-card
+/// combos (e.g. "AsAdKhQh"). Every combo that survives the hero and board
+/// cards counts once.
+pub fn equity_vs_range_omaha(
+    hand: &[Card],
+    villain_range: &[String],
+    board: Option<&[Card]>,
+    simulations: usize,
+) -> GtoResult<EquityResult> {
+    let hero = omaha_hand(hand)?;
+    let board_idx = omaha_board(board)?;
+
+    let mut villains: Vec<[u8; 4]> = Vec::new();
+    for notation in villain_range {
+        let cards = crate::cards::parse_board(notation)
+            .map_err(|_| GtoError::InvalidHandNotation(notation.clone()))?;
+        let mut combo = omaha_hand(&cards)
+            .map_err(|_| GtoError::InvalidHandNotation(notation.clone()))?;
+        combo.sort_unstable();
+        let blocked = combo.iter().any(|c| hero.contains(c) || board_idx.contains(c));
+        if !blocked && !villains.contains(&combo) {
+            villains.push(combo);
+        }
+    }
+    if villains.is_empty() {
+        return Err(GtoError::NoValidCombos);
+    }
 
-    let (wins, ties, losses) = results
+    let sims_per = (simulations / villains.len()).max(1);
+    let results: Vec<(u64, u64, u64)> = villains
+        .par_iter()
+        .map(|villain| omaha_showdowns(&hero, villain, &board_idx, sims_per, &mut rand::thread_rng()))
+        .collect();
+    Ok(tally(&results))
+}
+
+/// Four distinct cards as indices.
+fn omaha_hand(cards: &[Card]) -> GtoResult<[u8; 4]> {
+    let hand: [u8; 4] = cards
         .iter()
-        .fold((0u64, 0u64, 0u64), |acc, &(w, t, l)| {
-            (acc.0 + w, acc.1 + t, acc.2 + l)
-        });
+        .map(card_to_index)
+        .collect::<Vec<_>>()
+        .try_into()
+        .map_err(|_| GtoError::InvalidValue("Omaha hands must be exactly 4 cards".to_string()))?;
+    if (1..4).any(|i| hand[..i].contains(&hand[i])) {
+        return Err(GtoError::InvalidValue("Omaha hand repeats a card".to_string()));
+    }
+    Ok(hand)
+}
 
-    let total = (wins + ties + losses) as f64;
-    Ok(EquityResult {
-        win: wins as f64 / total,
-        tie: ties as f64 / total,
-        lose: losses as f64 / total,
+/// Board cards as indices; at most five.
+fn omaha_board(board: Option<&[Card]>) -> GtoResult<Vec<u8>> {
+    let board = board.unwrap_or(&[]);
+    if board.len() > 5 {
+        return Err(GtoError::InvalidValue("Board can have at most 5 cards".to_string()));
+    }
+    Ok(board.iter().map(card_to_index).collect())
+}
+
+/// Deal `sims` random runouts and count hero (wins, ties, losses).
+fn omaha_showdowns<R: rand::Rng>(
+    hero: &[u8; 4],
+    villain: &[u8; 4],
+    board: &[u8],
+    sims: usize,
+    rng: &mut R,
+) -> (u64, u64, u64) {
+    let mut dead = Vec::with_capacity(8 + board.len());
+    dead.extend_from_slice(hero);
+    dead.extend_from_slice(villain);
+    dead.extend_from_slice(board);
+    let mut deck = remaining_deck(&dead);
+    let cards_needed = 5 - board.len();
+
+    let mut counts = (0, 0, 0);
+    for _ in 0..sims {
+        let (runout, _) = deck.partial_shuffle(rng, cards_needed);
+        let mut full = [0u8; 5];
+        for (i, &c) in board.iter().chain(runout.iter()).enumerate() {
+            full[i] = c;
+        }
+        match evaluate_omaha(hero, &full).cmp(&evaluate_omaha(villain, &full)) {
+            std::cmp::Ordering::Greater => counts.0 += 1,
+            std::cmp::Ordering::Equal => counts.1 += 1,
+            std::cmp::Ordering::Less => counts.2 += 1,
+        }
+    }
+    counts
+}
+
+fn tally(results: &[(u64, u64, u64)]) -> EquityResult {
+    let sum = results.iter().fold(Tally::default(), |mut sum, &(w, t, l)| {
+        sum.wins += w;
+        sum.ties += t;
+        sum.losses += l;
+        sum
+    });
+    let total = sum.count() as f64;
+    EquityResult {
+        win: sum.wins as f64 / total,
+        tie: sum.ties as f64 / total,
+        lose: sum.losses as f64 / total,
         simulations: total as usize,
-    })
+        exact: false,
+        category_breakdown: None,
+        stderr: standard_error([(&sum, 1.0)]),
+        villain_combos: None,
+    }
 }
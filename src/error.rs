@@ -32,6 +32,12 @@ pub enum GtoError {
     #[error("No valid villain combos after removing dead cards")]
     NoValidCombos,
 
+    #[error("{0} is not in the short deck (ranks 2-5 are removed)")]
+    NotInShortDeck(String),
+
+    #[error("Hand history: {0}")]
+    HandHistory(String),
+
     #[error("No range data for: {0}")]
     RangeDataNotFound(String),
 
@@ -1,8 +1,9 @@
 //! Flat-array CFR+ engine for postflop solving.
 //!
-//! Replaces the HashMap-based `CfrTrainer` with contiguous f32 arrays for
-//! ~5x memory reduction and better cache performance. Designed for turn and
-//! flop solvers where info set counts reach millions.
+//! Replaces the HashMap-based `CfrTrainer` with contiguous arrays (f32 by
+//! default, or any `CfrFloat`) for ~5x memory reduction and better cache
+//! performance. Designed for turn and flop solvers where info set counts
+//! reach millions.
 //!
 //! Layout: data is organized by *node*, where each node has a fixed number
 //! of actions and hands. For node `n`, hand `h`, action `a`:
@@ -19,10 +20,13 @@
 /// strategy weights are stored in parallel contiguous arrays.
 use serde::{Serialize, Deserialize};
 
+use crate::cfr::{normalized_sums, regret_matching, update_sums, CfrFloat, CfrVariant};
+
 #[derive(Clone, Serialize, Deserialize)]
-pub struct FlatCfr {
-    regrets: Vec<f32>,
-    cum_strategy: Vec<f32>,
+#[serde(bound = "")]
+pub struct FlatCfr<F: CfrFloat = f32> {
+    regrets: Vec<F>,
+    cum_strategy: Vec<F>,
     /// Number of legal actions at each node.
     num_actions: Vec<u8>,
     /// Number of hand combos at each node.
@@ -32,11 +36,19 @@ pub struct FlatCfr {
 }
 
 impl FlatCfr {
-    /// Create a new FlatCfr from a list of (num_actions, num_hands) per node.
+    /// Create a new f32 FlatCfr from a list of (num_actions, num_hands) per
+    /// node.
     ///
     /// Nodes are indexed 0..nodes.len()-1. The order must match the node_ids
     /// used during CFR traversal.
     pub fn new(nodes: &[(u8, u16)]) -> Self {
+        Self::from_nodes(nodes)
+    }
+}
+
+impl<F: CfrFloat> FlatCfr<F> {
+    /// Like `new`, storing values as `F`.
+    pub fn from_nodes(nodes: &[(u8, u16)]) -> Self {
         let mut offsets = Vec::with_capacity(nodes.len());
         let mut num_actions = Vec::with_capacity(nodes.len());
         let mut num_hands = Vec::with_capacity(nodes.len());
@@ -51,8 +63,8 @@ impl FlatCfr {
 
         let total = offset as usize;
         FlatCfr {
-            regrets: vec![0.0f32; total],
-            cum_strategy: vec![0.0f32; total],
+            regrets: vec![F::ZERO; total],
+            cum_strategy: vec![F::ZERO; total],
             num_actions,
             num_hands,
             offsets,
@@ -71,26 +83,40 @@ impl FlatCfr {
         self.num_actions[node]
     }
 
-    /// Total number of f32 entries (regrets or cum_strategy).
+    /// Number of hand combos at the given node.
+    #[inline]
+    pub fn node_num_hands(&self, node: usize) -> u16 {
+        self.num_hands[node]
+    }
+
+    /// Total number of entries in each of the regret and cum_strategy arrays.
     pub fn total_entries(&self) -> usize {
         self.regrets.len()
     }
 
-    /// Memory usage in bytes (both arrays).
+    /// Memory usage in bytes: both value arrays and the per-node layout, at
+    /// their allocated capacity.
     pub fn memory_bytes(&self) -> usize {
-        self.regrets.len() * 4 * 2
-            + self.num_actions.len()
-            + self.num_hands.len() * 2
-            + self.offsets.len() * 4
+        (self.regrets.capacity() + self.cum_strategy.capacity()) * std::mem::size_of::<F>()
+            + self.num_actions.capacity()
+            + self.num_hands.capacity() * 2
+            + self.offsets.capacity() * 4
+    }
+
+    /// What `memory_bytes` comes to for `nodes` nodes holding `entries`
+    /// (node, hand, action) entries between them.
+    pub fn estimated_bytes(nodes: usize, entries: usize) -> usize {
+        entries * 2 * std::mem::size_of::<F>() + nodes * (1 + 2 + 4)
     }
 
     // -----------------------------------------------------------------------
     // Index helpers
     // -----------------------------------------------------------------------
 
-    /// Base index for (node, hand) in the flat arrays.
+    /// Base index for (node, hand) in the flat arrays: where its first
+    /// action's entry sits.
     #[inline]
-    fn base(&self, node: usize, hand: usize) -> usize {
+    pub fn base(&self, node: usize, hand: usize) -> usize {
         self.offsets[node] as usize + hand * self.num_actions[node] as usize
     }
 
@@ -110,13 +136,13 @@ impl FlatCfr {
 
         let mut positive_sum: f32 = 0.0;
         for &r in regrets {
-            positive_sum += r.max(0.0);
+            positive_sum += (r.to_f64() as f32).max(0.0);
         }
 
         if positive_sum > 0.0 {
             let inv = 1.0 / positive_sum;
             for (i, &r) in regrets.iter().enumerate() {
-                out[i] = r.max(0.0) * inv;
+                out[i] = (r.to_f64() as f32).max(0.0) * inv;
             }
         } else {
             let uniform = 1.0 / na as f32;
@@ -135,11 +161,11 @@ impl FlatCfr {
         let base = self.base(node, hand);
         let cum = &self.cum_strategy[base..base + na];
 
-        let total: f32 = cum.iter().sum();
+        let total: f32 = cum.iter().map(|s| s.to_f64() as f32).sum();
         if total > 0.0 {
             let inv = 1.0 / total;
             for (i, &s) in cum.iter().enumerate() {
-                out[i] = s * inv;
+                out[i] = s.to_f64() as f32 * inv;
             }
         } else {
             let uniform = 1.0 / na as f32;
@@ -149,6 +175,26 @@ impl FlatCfr {
         }
     }
 
+    /// Regrets of one (node, hand), one per action.
+    #[inline]
+    pub fn regrets(&self, node: usize, hand: usize) -> &[F] {
+        let base = self.base(node, hand);
+        &self.regrets[base..base + self.num_actions[node] as usize]
+    }
+
+    /// Current strategy in f64, computed exactly as
+    /// `InfoSetData::current_strategy` computes it.
+    pub fn current_strategy_f64(&self, node: usize, hand: usize) -> Vec<f64> {
+        regret_matching(self.regrets(node, hand))
+    }
+
+    /// Average strategy in f64, computed exactly as
+    /// `InfoSetData::average_strategy` computes it.
+    pub fn average_strategy_f64(&self, node: usize, hand: usize) -> Vec<f64> {
+        let base = self.base(node, hand);
+        normalized_sums(&self.cum_strategy[base..base + self.num_actions[node] as usize])
+    }
+
     // -----------------------------------------------------------------------
     // CFR+ update
     // -----------------------------------------------------------------------
@@ -157,9 +203,11 @@ impl FlatCfr {
     ///
     /// - `action_values`: counterfactual value of each action (len = num_actions)
     /// - `node_value`: weighted value of the node under current strategy
-    /// - `reach_prob`: probability of reaching this info set (for strategy weighting)
+    /// - `reach_prob`: the acting player's own probability of reaching this
+    ///   info set; the average strategy is weighted by it, not by opponent reach
     ///
-    /// Regrets are floored at 0.0 (CFR+).
+    /// Regrets are floored at 0.0 (CFR+). Sums are accumulated in f64 and
+    /// rounded to `F` on store.
     #[inline]
     pub fn update(
         &mut self,
@@ -173,23 +221,84 @@ impl FlatCfr {
         let base = self.base(node, hand);
 
         // Read current strategy for accumulation
-        let mut positive_sum: f32 = 0.0;
+        let mut positive_sum = 0.0f64;
         for i in 0..na {
-            positive_sum += self.regrets[base + i].max(0.0);
+            positive_sum += self.regrets[base + i].to_f64().max(0.0);
         }
 
         for i in 0..na {
             // Update regret (CFR+: floor at 0)
-            let regret = action_values[i] - node_value;
-            self.regrets[base + i] = (self.regrets[base + i] + regret).max(0.0);
+            let regret = (action_values[i] - node_value) as f64;
+            let cumulative = (self.regrets[base + i].to_f64() + regret).max(0.0);
+            self.regrets[base + i] = F::from_f64(cumulative);
 
             // Accumulate strategy weighted by reach probability
             let sigma = if positive_sum > 0.0 {
-                self.regrets[base + i].max(0.0) / positive_sum
+                cumulative / positive_sum
             } else {
-                1.0 / na as f32
+                1.0 / na as f64
             };
-            self.cum_strategy[base + i] += reach_prob * sigma;
+            let weight = self.cum_strategy[base + i].to_f64() + reach_prob as f64 * sigma;
+            self.cum_strategy[base + i] = F::from_f64(weight);
+        }
+    }
+
+    /// Update one info set under `variant` with the `InfoSetData::update`
+    /// rule, in f64: `iteration` is the 1-based count of updates to it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_variant(
+        &mut self,
+        node: usize,
+        hand: usize,
+        action_utilities: &[f64],
+        node_utility: f64,
+        reach_prob: f64,
+        variant: CfrVariant,
+        iteration: usize,
+    ) {
+        let base = self.base(node, hand);
+        let end = base + self.num_actions[node] as usize;
+        update_sums(
+            &mut self.regrets[base..end],
+            &mut self.cum_strategy[base..end],
+            action_utilities,
+            node_utility,
+            reach_prob,
+            variant,
+            iteration,
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // Batch merging
+    // -----------------------------------------------------------------------
+
+    /// What this instance accumulated since it was cloned from `baseline`,
+    /// entry by entry. Regret deltas are unfloored. Panics if the two
+    /// weren't built from the same nodes.
+    pub fn delta_from(&self, baseline: &FlatCfr<F>) -> FlatCfr<F> {
+        assert_eq!(self.offsets, baseline.offsets, "FlatCfr layouts differ");
+        let diff = |a: &[F], b: &[F]| -> Vec<F> {
+            a.iter().zip(b).map(|(&x, &y)| F::from_f64(x.to_f64() - y.to_f64())).collect()
+        };
+        FlatCfr {
+            regrets: diff(&self.regrets, &baseline.regrets),
+            cum_strategy: diff(&self.cum_strategy, &baseline.cum_strategy),
+            num_actions: self.num_actions.clone(),
+            num_hands: self.num_hands.clone(),
+            offsets: self.offsets.clone(),
+        }
+    }
+
+    /// Add `other`'s regrets and strategy sums entry by entry, flooring
+    /// regrets at 0 (CFR+) after summing. Panics if the layouts differ.
+    pub fn merge(&mut self, other: FlatCfr<F>) {
+        assert_eq!(self.offsets, other.offsets, "FlatCfr layouts differ");
+        for (r, d) in self.regrets.iter_mut().zip(&other.regrets) {
+            *r = F::from_f64((r.to_f64() + d.to_f64()).max(0.0));
+        }
+        for (s, d) in self.cum_strategy.iter_mut().zip(&other.cum_strategy) {
+            *s = F::from_f64(s.to_f64() + d.to_f64());
         }
     }
 }
@@ -312,6 +421,77 @@ mod tests {
         assert!(out[0] > out[1]);
     }
 
+    #[test]
+    fn f64_storage_matches_f32_at_twice_the_memory() {
+        let nodes = [(2u8, 4u16)];
+        let mut narrow = FlatCfr::new(&nodes);
+        let mut wide = FlatCfr::<f64>::from_nodes(&nodes);
+        for _ in 0..10 {
+            narrow.update(0, 1, &[5.0, 0.0], 2.5, 0.3);
+            wide.update(0, 1, &[5.0, 0.0], 2.5, 0.3);
+        }
+
+        let (mut a, mut b) = ([0.0f32; 2], [0.0f32; 2]);
+        narrow.average_strategy(0, 1, &mut a);
+        wide.average_strategy(0, 1, &mut b);
+        assert!((a[0] - b[0]).abs() < 1e-6);
+        // 8 entries in each of the two arrays, 4 more bytes apiece
+        assert_eq!(wide.memory_bytes() - narrow.memory_bytes(), 8 * 2 * 4);
+    }
+
+    #[test]
+    fn variant_update_matches_info_set_update() {
+        use crate::cfr::InfoSetData;
+
+        let mut flat = FlatCfr::<f32>::from_nodes(&[(2, 1), (3, 2)]);
+        let mut data: InfoSetData<f32> = InfoSetData::new(3);
+        let steps = [([1.0, -2.0, 0.5], 0.1), ([0.3, 0.2, -1.0], -0.2), ([2.0, 0.0, 1.0], 1.0)];
+        for (t, &(u, n)) in steps.iter().enumerate() {
+            flat.update_variant(1, 1, &u, n, 0.7, CfrVariant::DCFR, t + 1);
+            data.update(&u, n, 0.7, CfrVariant::DCFR, t + 1);
+        }
+        assert_eq!(flat.regrets(1, 1), &data.cumulative_regret[..]);
+        assert_eq!(flat.current_strategy_f64(1, 1), data.current_strategy());
+        assert_eq!(flat.average_strategy_f64(1, 1), data.average_strategy());
+        // Other hands and nodes are untouched
+        assert_eq!(flat.current_strategy_f64(1, 0), vec![1.0 / 3.0; 3]);
+        assert_eq!(flat.regrets(0, 0), &[0.0, 0.0]);
+    }
+
+    #[test]
+    fn merged_split_sweep_matches_sequential_sweep() {
+        let sweep = |cfr: &mut FlatCfr, hands: std::ops::Range<usize>| {
+            for h in hands {
+                let mut strategy = [0.0f32; 3];
+                cfr.current_strategy(0, h, &mut strategy);
+                let values = [h as f32, 2.0 - h as f32, 0.5];
+                let node: f32 = strategy.iter().zip(&values).map(|(s, v)| s * v).sum();
+                cfr.update(0, h, &values, node, 1.0);
+            }
+        };
+
+        let mut baseline = FlatCfr::new(&[(3, 4)]);
+        sweep(&mut baseline, 0..4);
+        let mut sequential = baseline.clone();
+        sweep(&mut sequential, 0..4);
+
+        let mut low = baseline.clone();
+        let mut high = baseline.clone();
+        sweep(&mut low, 0..2);
+        sweep(&mut high, 2..4);
+        let (low, high) = (low.delta_from(&baseline), high.delta_from(&baseline));
+        let mut merged = baseline;
+        merged.merge(low);
+        merged.merge(high);
+
+        for (got, want) in merged.regrets.iter().zip(&sequential.regrets) {
+            assert!((got - want).abs() < 1e-6, "{} vs {}", got, want);
+        }
+        for (got, want) in merged.cum_strategy.iter().zip(&sequential.cum_strategy) {
+            assert!((got - want).abs() < 1e-6, "{} vs {}", got, want);
+        }
+    }
+
     #[test]
     fn memory_bytes_reasonable() {
         // 1000 nodes × 4 actions × 500 hands = 2M entries
@@ -321,5 +501,6 @@ mod tests {
         // 2M entries × 4 bytes × 2 arrays = 16 MB + small overhead
         assert!(mb < 20.0, "Expected <20 MB, got {:.1} MB", mb);
         assert!(mb > 10.0, "Expected >10 MB, got {:.1} MB", mb);
+        assert_eq!(cfr.memory_bytes(), FlatCfr::<f32>::estimated_bytes(1000, 2_000_000));
     }
 }
@@ -3,7 +3,7 @@
 //! suit *pattern* (e.g., rainbow, two-tone, monotone). Canonical form maps suits
 //! to indices 0,1,2,3 in order of first appearance.
 
-use std::collections::BTreeSet;
+use std::collections::BTreeMap;
 
 /// Ranks indexed 0..13 mapping to 2,3,4,5,6,7,8,9,T,J,Q,K,A.
 const RANK_CHARS: [char; 13] = ['2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A'];
@@ -17,21 +17,70 @@ const SUIT_CHARS: [char; 4] = ['s', 'h', 'd', 'c'];
 /// sorted for deterministic output. Each string represents a canonical flop —
 /// the unique representative for its suit isomorphism class.
 pub fn generate_canonical_flops() -> Vec<String> {
-    let mut canonical_set: BTreeSet<String> = BTreeSet::new();
+    generate_weighted_canonical_flops()
+        .into_iter()
+        .map(|(board, _)| board)
+        .collect()
+}
+
+/// Generate the 1,755 canonical flops with their multiplicities.
+///
+/// The weight of a canonical flop is the number of the 22,100 raw flops that
+/// map to it (4 for a monotone board, 24 for an unpaired rainbow board, ...),
+/// so weights sum to 22,100 and weighting per-flop results by them gives the
+/// true average over all flops.
+pub fn generate_weighted_canonical_flops() -> Vec<(String, u32)> {
+    let mut weights: BTreeMap<String, u32> = BTreeMap::new();
 
     // Enumerate all 52C3 = 22,100 three-card combinations.
     // Card index 0..51: card i has rank i/4, suit i%4.
-    for c1 in 0u8..52 {
-        for c2 in (c1 + 1)..52 {
-            for c3 in (c2 + 1)..52 {
-                let cards = [(c1 / 4, c1 % 4), (c2 / 4, c2 % 4), (c3 / 4, c3 % 4)];
-                let canonical = canonicalize(&cards);
-                canonical_set.insert(canonical);
-            }
+    for cards in all_flop_cards() {
+        *weights.entry(canonicalize(&cards)).or_insert(0) += 1;
+    }
+
+    weights.into_iter().collect()
+}
+
+/// Generate all 22,100 flops, each with weight 1, ranks descending.
+pub fn generate_all_flops() -> Vec<(String, u32)> {
+    all_flop_cards()
+        .map(|mut cards| {
+            cards.sort_by_key(|c| std::cmp::Reverse(c.0));
+            let board = cards
+                .iter()
+                .flat_map(|&(rank, suit)| [RANK_CHARS[rank as usize], SUIT_CHARS[suit as usize]])
+                .collect();
+            (board, 1)
+        })
+        .collect()
+}
+
+/// Which flops a multi-flop run covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlopSubset {
+    /// 1,755 suit-isomorphic flops weighted by multiplicity.
+    Canonical,
+    /// All 22,100 flops, unweighted.
+    Full,
+}
+
+impl FlopSubset {
+    /// Boards in this subset with their weights.
+    pub fn flops(self) -> Vec<(String, u32)> {
+        match self {
+            FlopSubset::Canonical => generate_weighted_canonical_flops(),
+            FlopSubset::Full => generate_all_flops(),
         }
     }
+}
 
-    canonical_set.into_iter().collect()
+/// Every 3-card combination as (rank, suit) pairs.
+fn all_flop_cards() -> impl Iterator<Item = [(u8, u8); 3]> {
+    (0u8..52).flat_map(|c1| {
+        ((c1 + 1)..52).flat_map(move |c2| {
+            ((c2 + 1)..52).map(move |c3| [(c1 / 4, c1 % 4), (c2 / 4, c2 % 4), (c3 / 4, c3 % 4)])
+        })
+    })
 }
 
 /// Map a 3-card flop to its canonical string representation.
@@ -118,6 +167,68 @@ fn first_appearance_canonical(cards: &[(u8, u8); 3]) -> String {
     result
 }
 
+/// A suit relabeling in the u8 card encoding (suit = `card % 4`), from a
+/// board's original suits to its canonical ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SuitMap {
+    /// Canonical suit for each original suit.
+    to_canonical: [u8; 4],
+}
+
+impl SuitMap {
+    pub fn identity() -> Self {
+        SuitMap { to_canonical: [0, 1, 2, 3] }
+    }
+
+    /// Relabel a card from the original board's suits to the canonical ones.
+    pub fn to_canonical(self, card: u8) -> u8 {
+        card / 4 * 4 + self.to_canonical[(card % 4) as usize]
+    }
+
+    /// Relabel a card from canonical suits back to the original board's.
+    pub fn to_original(self, card: u8) -> u8 {
+        let suit = self.to_canonical.iter().position(|&s| s == card % 4).unwrap() as u8;
+        card / 4 * 4 + suit
+    }
+}
+
+/// Relabel a board's suits to the canonical representative of its suit
+/// isomorphism class, returning the canonical board (ranks descending, suits
+/// ascending within a rank) and the map from the original suits to it.
+/// Boards that differ only by a suit relabeling, in any card order, give the
+/// same canonical board; solve that one and translate combos through the map.
+pub fn canonicalize_board(board: &[u8]) -> (Vec<u8>, SuitMap) {
+    let sorted = |cards: &mut Vec<u8>| cards.sort_by_key(|&c| (std::cmp::Reverse(c / 4), c % 4));
+
+    let mut best: Option<(Vec<u8>, SuitMap)> = None;
+    for map in suit_permutations() {
+        let mut cards: Vec<u8> = board.iter().map(|&c| map.to_canonical(c)).collect();
+        sorted(&mut cards);
+        let better = match &best {
+            Some((current, _)) => cards < *current,
+            None => true,
+        };
+        if better {
+            best = Some((cards, map));
+        }
+    }
+    best.unwrap_or_else(|| (Vec::new(), SuitMap::identity()))
+}
+
+/// All 24 relabelings of the four suits.
+fn suit_permutations() -> Vec<SuitMap> {
+    let mut maps = Vec::with_capacity(24);
+    for a in 0..4u8 {
+        for b in (0..4).filter(|&b| b != a) {
+            for c in (0..4).filter(|&c| c != a && c != b) {
+                let d = 6 - a - b - c;
+                maps.push(SuitMap { to_canonical: [a, b, c, d] });
+            }
+        }
+    }
+    maps
+}
+
 /// Return the strategic priority score for a canonical flop string.
 /// Higher score = higher priority (should be solved first).
 ///
@@ -207,6 +318,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_canonical_flop_weights_sum_to_all_flops() {
+        let flops = generate_weighted_canonical_flops();
+        assert_eq!(flops.len(), 1755);
+        let total: u32 = flops.iter().map(|(_, w)| w).sum();
+        assert_eq!(total, 22_100);
+
+        let weight = |board: &str| flops.iter().find(|(b, _)| b == board).map(|(_, w)| *w);
+        assert_eq!(weight("AsKsQs"), Some(4), "monotone: one per suit");
+        assert_eq!(weight("AsKhQd"), Some(24), "rainbow: 4 * 3 * 2 suit choices");
+        assert_eq!(weight("AsAhAd"), Some(4), "trips: choose 3 of 4 suits");
+    }
+
+    #[test]
+    fn test_full_flop_subset() {
+        let flops = FlopSubset::Full.flops();
+        assert_eq!(flops.len(), 22_100);
+        assert!(flops.iter().all(|(_, w)| *w == 1));
+        let distinct: std::collections::HashSet<&String> = flops.iter().map(|(b, _)| b).collect();
+        assert_eq!(distinct.len(), 22_100);
+        assert!(distinct.contains(&"Kd9c4h".to_string()));
+    }
+
     #[test]
     fn test_canonical_flops_are_valid() {
         let flops = generate_canonical_flops();
@@ -260,6 +394,68 @@ mod tests {
         assert_eq!(b, c, "Kh9s4c and Kd9c4s should canonicalize the same");
     }
 
+    fn board(s: &str) -> Vec<u8> {
+        crate::card_encoding::cards_to_indices(&crate::cards::parse_board(s).unwrap())
+    }
+
+    #[test]
+    fn test_isomorphic_boards_canonicalize_identically() {
+        let (a, _) = canonicalize_board(&board("AhKhQh"));
+        let (b, _) = canonicalize_board(&board("AsKsQs"));
+        let (c, _) = canonicalize_board(&board("QdAdKd"));
+        assert_eq!(a, b);
+        assert_eq!(a, c);
+        assert_eq!(a, board("AsKsQs"));
+
+        let (two_tone, _) = canonicalize_board(&board("Kh9h4c2d"));
+        assert_eq!(two_tone, canonicalize_board(&board("Kc9c4s2h")).0);
+        assert_ne!(two_tone, canonicalize_board(&board("Kh9c4h2d")).0);
+
+        // Paired boards: swapping the paired cards' suits is a relabeling
+        assert_eq!(canonicalize_board(&board("8h8d3h")).0, canonicalize_board(&board("8d8h3d")).0);
+    }
+
+    #[test]
+    fn test_suit_map_round_trips_a_range() {
+        use crate::river_solver::expand_range_to_combos;
+
+        let original = board("Td7c2c");
+        let (canonical, map) = canonicalize_board(&original);
+        let mut mapped: Vec<u8> = original.iter().map(|&c| map.to_canonical(c)).collect();
+        mapped.sort_unstable();
+        let mut expected = canonical.clone();
+        expected.sort_unstable();
+        assert_eq!(mapped, expected);
+
+        let range: Vec<String> = ["AA", "AKs", "T9s", "76o"].iter().map(|s| s.to_string()).collect();
+        let mut on_original: Vec<(u8, u8)> = expand_range_to_combos(&range, &original)
+            .into_iter()
+            .map(|c| (c.0, c.1))
+            .collect();
+        let mut translated: Vec<(u8, u8)> = on_original
+            .iter()
+            .map(|&(a, b)| (map.to_canonical(a), map.to_canonical(b)))
+            .collect();
+
+        // The translated combos are exactly the range on the canonical board
+        let mut on_canonical: Vec<(u8, u8)> = expand_range_to_combos(&range, &canonical)
+            .into_iter()
+            .map(|c| (c.0.min(c.1), c.0.max(c.1)))
+            .collect();
+        let mut normalized: Vec<(u8, u8)> = translated.iter().map(|&(a, b)| (a.min(b), a.max(b))).collect();
+        normalized.sort();
+        on_canonical.sort();
+        assert_eq!(normalized, on_canonical);
+
+        // And map back unchanged
+        for combo in translated.iter_mut() {
+            *combo = (map.to_original(combo.0), map.to_original(combo.1));
+        }
+        on_original.sort();
+        translated.sort();
+        assert_eq!(translated, on_original);
+    }
+
     #[test]
     fn test_monotone_not_same_as_rainbow() {
         // Monotone: all same suit
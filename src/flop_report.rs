@@ -0,0 +1,319 @@
+//! Aggregate flop report: solve one spot across many flops and summarize each
+//! solution's flop-level tendencies as a CSV row.
+//!
+//! Solves run in parallel across flops, but each worker reduces its solution
+//! to a `FlopReportRow` before taking the next board, so at most `jobs`
+//! solutions are resident at once.
+
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::board_texture::{classify_board, BoardTexture};
+use crate::bucketing::BucketingMode;
+use crate::card_encoding::cards_to_indices;
+use crate::cards::parse_board;
+use crate::flop_solver::{solve_flop, FlopNodeStrategy, FlopSolution, FlopSolverConfig};
+
+// ---------------------------------------------------------------------------
+// Config & rows
+// ---------------------------------------------------------------------------
+
+/// Spot shared by every flop in the report.
+#[derive(Debug, Clone)]
+pub struct FlopReportConfig {
+    pub oop_range: String,
+    pub ip_range: String,
+    pub starting_pot: f64,
+    pub effective_stack: f64,
+    pub iterations: usize,
+    pub bucketing: BucketingMode,
+    pub num_buckets: usize,
+    /// Flops solved at once (0 = all cores).
+    pub jobs: usize,
+}
+
+/// Range-average action frequencies at one flop node.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeFrequencies {
+    pub actions: Vec<String>,
+    pub frequencies: Vec<f64>,
+}
+
+impl NodeFrequencies {
+    /// Average a node strategy over the acting player's combos.
+    pub fn from_strategy(strat: &FlopNodeStrategy) -> Self {
+        let n = strat.frequencies.len().max(1) as f64;
+        let frequencies = (0..strat.actions.len())
+            .map(|a| strat.frequencies.iter().map(|f| f[a]).sum::<f64>() / n)
+            .collect();
+        NodeFrequencies {
+            actions: strat.actions.clone(),
+            frequencies,
+        }
+    }
+
+    /// Frequency of the named action, 0 if it is not available here.
+    pub fn get(&self, action: &str) -> f64 {
+        self.actions
+            .iter()
+            .position(|a| a == action)
+            .map_or(0.0, |i| self.frequencies[i])
+    }
+
+    /// Total frequency of every bet size.
+    pub fn bet(&self) -> f64 {
+        self.actions
+            .iter()
+            .zip(&self.frequencies)
+            .filter(|(a, _)| a.starts_with("Bet"))
+            .map(|(_, f)| f)
+            .sum()
+    }
+}
+
+/// Summary of one flop solve.
+#[derive(Debug, Clone)]
+pub struct FlopReportRow {
+    pub board: String,
+    /// Raw flops this board stands for (1 unless it came from a weighted subset).
+    pub weight: u32,
+    /// OOP at the root.
+    pub oop: NodeFrequencies,
+    /// IP facing a check.
+    pub ip: NodeFrequencies,
+    /// Exploitability against a combo-level best response (chips per hand).
+    pub exploitability: f64,
+    pub exploitability_pct: f64,
+}
+
+impl FlopReportRow {
+    /// Reduce a solution to its report row.
+    pub fn from_solution(solution: &FlopSolution) -> Self {
+        let node = |id: u32| {
+            solution
+                .strategies
+                .iter()
+                .find(|s| s.node_id == id)
+                .map(NodeFrequencies::from_strategy)
+                .unwrap_or_default()
+        };
+        let ip_after_check = solution
+            .flop_tree_edges
+            .iter()
+            .find(|e| e.from == 0 && e.action == "Check")
+            .map_or_else(NodeFrequencies::default, |e| node(e.to));
+
+        FlopReportRow {
+            board: solution.board.clone(),
+            weight: 1,
+            oop: node(0),
+            ip: ip_after_check,
+            exploitability: solution.real_exploitability,
+            exploitability_pct: solution.real_exploitability / solution.starting_pot * 100.0,
+        }
+    }
+
+    /// Set the board's multiplicity weight.
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Flop lists
+// ---------------------------------------------------------------------------
+
+/// Parse a flop list: one board per line, blank lines and `#` comments skipped.
+pub fn parse_flop_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|l| l.split('#').next().unwrap_or("").trim())
+        .filter(|l| !l.is_empty())
+        .map(|l| l.chars().filter(|c| !c.is_whitespace()).collect())
+        .collect()
+}
+
+/// Read a flop list file (see `parse_flop_list`).
+pub fn read_flop_list(path: &str) -> Result<Vec<String>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let flops = parse_flop_list(&text);
+    if flops.is_empty() {
+        return Err(format!("{}: no flops listed", path));
+    }
+    Ok(flops)
+}
+
+// ---------------------------------------------------------------------------
+// Running the report
+// ---------------------------------------------------------------------------
+
+/// Solve every flop and return one result per board, in input order.
+///
+/// `on_done(done, total, board, result)` is called as each flop finishes.
+pub fn run_flop_report<F>(
+    config: &FlopReportConfig,
+    boards: &[String],
+    on_done: F,
+) -> Vec<Result<FlopReportRow, String>>
+where
+    F: Fn(usize, usize, &str, &Result<FlopReportRow, String>) + Sync,
+{
+    use rayon::prelude::*;
+
+    let done = AtomicUsize::new(0);
+    let solve = |board: &String| {
+        let result = solve_report_row(config, board);
+        let n = done.fetch_add(1, Ordering::Relaxed) + 1;
+        on_done(n, boards.len(), board, &result);
+        result
+    };
+
+    match rayon::ThreadPoolBuilder::new()
+        .num_threads(config.jobs)
+        .build()
+    {
+        Ok(pool) => pool.install(|| boards.par_iter().map(solve).collect()),
+        Err(_) => boards.iter().map(solve).collect(),
+    }
+}
+
+/// Solve one flop; the full solution is dropped once its row is built.
+fn solve_report_row(config: &FlopReportConfig, board: &str) -> Result<FlopReportRow, String> {
+    let mut solver_config = FlopSolverConfig::new(
+        board,
+        &config.oop_range,
+        &config.ip_range,
+        config.starting_pot,
+        config.effective_stack,
+        config.iterations,
+    )?;
+    solver_config.bucketing = config.bucketing;
+    solver_config.num_buckets = config.num_buckets;
+    Ok(FlopReportRow::from_solution(&solve_flop(&solver_config)))
+}
+
+// ---------------------------------------------------------------------------
+// Grouping by texture
+// ---------------------------------------------------------------------------
+
+/// Weight-averaged report rows for the flops sharing one texture class.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureGroup {
+    /// High cards, suits and pairing, e.g. "ace-high, two-tone, unpaired".
+    pub texture: String,
+    pub flops: usize,
+    pub weight: u32,
+    /// OOP's total bet frequency at the root.
+    pub oop_bet: f64,
+    /// IP's total bet frequency facing a check.
+    pub ip_bet: f64,
+    pub exploitability_pct: f64,
+}
+
+/// The texture class a report groups a board under.
+pub fn texture_class(texture: &BoardTexture) -> String {
+    format!(
+        "{}, {}, {}",
+        texture.high_card_class.as_str(),
+        texture.suit_pattern.as_str(),
+        texture.pairing.as_str()
+    )
+}
+
+/// Group rows by `texture_class`, heaviest group first. Rows whose board
+/// doesn't parse are left out.
+pub fn group_by_texture(rows: &[FlopReportRow]) -> Vec<TextureGroup> {
+    let mut groups: Vec<TextureGroup> = Vec::new();
+    for row in rows {
+        let Ok(cards) = parse_board(&row.board) else {
+            continue;
+        };
+        let texture = texture_class(&classify_board(&cards_to_indices(&cards)));
+        let index = match groups.iter().position(|g| g.texture == texture) {
+            Some(i) => i,
+            None => {
+                groups.push(TextureGroup {
+                    texture,
+                    flops: 0,
+                    weight: 0,
+                    oop_bet: 0.0,
+                    ip_bet: 0.0,
+                    exploitability_pct: 0.0,
+                });
+                groups.len() - 1
+            }
+        };
+        // Sums for now; divided by the weight below
+        let group = &mut groups[index];
+        let w = row.weight as f64;
+        group.flops += 1;
+        group.weight += row.weight;
+        group.oop_bet += row.oop.bet() * w;
+        group.ip_bet += row.ip.bet() * w;
+        group.exploitability_pct += row.exploitability_pct * w;
+    }
+
+    for group in &mut groups {
+        let total = group.weight.max(1) as f64;
+        group.oop_bet /= total;
+        group.ip_bet /= total;
+        group.exploitability_pct /= total;
+    }
+    groups.sort_by(|a, b| b.weight.cmp(&a.weight).then_with(|| a.texture.cmp(&b.texture)));
+    groups
+}
+
+// ---------------------------------------------------------------------------
+// CSV
+// ---------------------------------------------------------------------------
+
+/// Every action label seen for one player, in first-seen order.
+fn action_columns<'a>(rows: impl Iterator<Item = &'a NodeFrequencies>) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+    for node in rows {
+        for action in &node.actions {
+            if !columns.contains(action) {
+                columns.push(action.clone());
+            }
+        }
+    }
+    columns
+}
+
+/// CSV column name for an action label: "Bet 3.3" -> "bet_3.3".
+fn column_name(prefix: &str, action: &str) -> String {
+    format!("{}_{}", prefix, action.to_lowercase().replace(' ', "_"))
+}
+
+/// Write rows as CSV: board and weight, OOP check/bet totals and per-size frequencies,
+/// the same for IP facing a check, then exploitability.
+pub fn write_csv<W: Write>(rows: &[FlopReportRow], out: &mut W) -> std::io::Result<()> {
+    let sizes = |cols: Vec<String>| -> Vec<String> {
+        cols.into_iter().filter(|a| a != "Check").collect()
+    };
+    let oop_sizes = sizes(action_columns(rows.iter().map(|r| &r.oop)));
+    let ip_sizes = sizes(action_columns(rows.iter().map(|r| &r.ip)));
+
+    let mut header = vec!["board".to_string(), "weight".to_string()];
+    for (prefix, cols) in [("oop", &oop_sizes), ("ip", &ip_sizes)] {
+        header.push(format!("{}_check", prefix));
+        header.push(format!("{}_bet", prefix));
+        header.extend(cols.iter().map(|a| column_name(prefix, a)));
+    }
+    header.push("exploitability".to_string());
+    header.push("exploitability_pct_pot".to_string());
+    writeln!(out, "{}", header.join(","))?;
+
+    for row in rows {
+        let mut fields = vec![row.board.clone(), row.weight.to_string()];
+        for (node, cols) in [(&row.oop, &oop_sizes), (&row.ip, &ip_sizes)] {
+            fields.push(format!("{:.4}", node.get("Check")));
+            fields.push(format!("{:.4}", node.bet()));
+            fields.extend(cols.iter().map(|a| format!("{:.4}", node.get(a))));
+        }
+        fields.push(format!("{:.4}", row.exploitability));
+        fields.push(format!("{:.2}", row.exploitability_pct));
+        writeln!(out, "{}", fields.join(","))?;
+    }
+    Ok(())
+}
@@ -16,35 +16,56 @@
 //!
 //! Hand combos are grouped into equity buckets (~200 per street) to further
 //! reduce the info set space.
+//!
+//! For small ranges `BucketingMode::Unabstracted` skips bucketing: each combo
+//! gets its own info set per turn and river card, and every iteration deals
+//! all turn cards instead of sampling one.
+
+use std::collections::HashMap;
 
 use rand::Rng;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::bucketing::assign_buckets;
-use crate::card_encoding::{index_to_card, remaining_deck};
-use crate::cards::parse_board;
+use crate::bucketing::{
+    bucket_combos, bucket_combos_with_equity, bucket_members, BucketingMode,
+};
+use crate::card_encoding::{card_to_index, index_to_card, remaining_deck};
+use crate::cards::{parse_board, parse_card};
+use crate::cfr::solver_rng;
 use crate::flat_cfr::FlatCfr;
-use crate::lookup_eval::evaluate_fast;
+use crate::lookup_table::evaluate_seven;
 use crate::postflop_tree::{
-    build_tree, collect_node_metadata, Player, TerminalType, TreeConfig, TreeNode,
+    build_tree, collect_node_metadata, same_for_both, BetSize, Player, RaiseSizing, TerminalType,
+    TreeConfig, TreeNode,
 };
 use crate::ranges::parse_range;
 use crate::river_solver::{expand_range_to_combos, Combo};
+use crate::turn_solver::write_combo_evs;
 
 // ---------------------------------------------------------------------------
 // Config & result
 // ---------------------------------------------------------------------------
 
+#[derive(Clone)]
 pub struct FlopSolverConfig {
     /// 3-card flop board as u8 indices.
     pub board: Vec<u8>,
     pub oop_range: Vec<String>,
     pub ip_range: Vec<String>,
     pub starting_pot: f64,
+    /// Chips in the pot that neither player invested (antes, folded
+    /// players' bets). Added to the pot when the tree is built, so it's won
+    /// at showdown or on a fold but never refunded.
+    pub dead_money: f64,
     pub effective_stack: f64,
     pub iterations: usize,
     pub num_buckets: usize,
+    /// How flop and turn combos are bucketed.
+    pub bucketing: BucketingMode,
+    /// Seed for bucketing equities and sampled runouts; `None` seeds from
+    /// entropy.
+    pub seed: Option<u64>,
 }
 
 impl FlopSolverConfig {
@@ -79,9 +100,12 @@ impl FlopSolverConfig {
             oop_range,
             ip_range,
             starting_pot,
+            dead_money: 0.0,
             effective_stack,
             iterations,
             num_buckets: 200,
+            bucketing: BucketingMode::Ehs,
+            seed: None,
         })
     }
 }
@@ -89,7 +113,7 @@ impl FlopSolverConfig {
 /// Per-node strategy for the flop solution.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FlopNodeStrategy {
-    pub node_id: u16,
+    pub node_id: u32,
     pub player: String,
     pub actions: Vec<String>,
     /// Average strategy frequencies: [combo_idx][action_idx].
@@ -100,7 +124,7 @@ pub struct FlopNodeStrategy {
 /// Same shape as FlopNodeStrategy but indexed by bucket instead of combo.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemplateBucketStrategy {
-    pub node_id: u16,
+    pub node_id: u32,
     pub player: String,
     pub actions: Vec<String>,
     /// Average strategy frequencies: [bucket_idx][action_idx].
@@ -111,11 +135,28 @@ pub struct TemplateBucketStrategy {
 /// Only action→action edges are stored (terminals have no decisions).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TreeEdge {
-    pub from: u16,
+    pub from: u32,
     pub action: String,
-    pub to: u16,
+    pub to: u32,
+}
+
+/// A flop bucket and the combos it contains.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlopBucketInfo {
+    pub bucket: u16,
+    /// Mean equity vs a random hand across member combos.
+    pub mean_equity: f64,
+    /// Member combos, strongest first.
+    pub combos: Vec<String>,
 }
 
+/// Below this many combos per player, bucketing saves little and
+/// `BucketingMode::Unabstracted` is worth using.
+pub const UNABSTRACTED_COMBO_THRESHOLD: usize = 80;
+
+/// Cards left after the flop; unabstracted turn/river buckets are per card.
+const CARDS_AFTER_FLOP: usize = 49;
+
 /// Full solution from the flop solver.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FlopSolution {
@@ -125,6 +166,7 @@ pub struct FlopSolution {
     pub starting_pot: f64,
     pub effective_stack: f64,
     pub iterations: usize,
+    /// Exploitability against a bucket-level best response (chips per hand).
     pub exploitability: f64,
     pub oop_combos: Vec<String>,
     pub ip_combos: Vec<String>,
@@ -145,6 +187,9 @@ pub struct FlopSolution {
     /// Number of buckets used for turn/river template strategies.
     #[serde(default)]
     pub num_buckets: usize,
+    /// Bucketing mode used for flop and turn buckets.
+    #[serde(default)]
+    pub bucketing: BucketingMode,
     /// Tree edges for flop action navigation.
     #[serde(default)]
     pub flop_tree_edges: Vec<TreeEdge>,
@@ -154,6 +199,26 @@ pub struct FlopSolution {
     /// Tree edges for river template navigation.
     #[serde(default)]
     pub river_tree_edges: Vec<TreeEdge>,
+    /// Exploitability against a combo-level best response (chips per hand).
+    #[serde(default)]
+    pub real_exploitability: f64,
+    /// OOP flop buckets with their member combos.
+    #[serde(default)]
+    pub oop_buckets: Vec<FlopBucketInfo>,
+    /// IP flop buckets with their member combos.
+    #[serde(default)]
+    pub ip_buckets: Vec<FlopBucketInfo>,
+    /// Average-strategy EV of each OOP combo at the root (same order as
+    /// `oop_combos`), over the sampled runouts. `None` when the combo has no
+    /// valid opponent combos.
+    #[serde(default)]
+    pub oop_combo_evs: Vec<Option<f64>>,
+    /// Average-strategy EV of each IP combo at the root (same order as `ip_combos`).
+    #[serde(default)]
+    pub ip_combo_evs: Vec<Option<f64>>,
+    /// Dead money the pot held on top of `starting_pot`.
+    #[serde(default)]
+    pub dead_money: f64,
 }
 
 // ---------------------------------------------------------------------------
@@ -162,34 +227,69 @@ pub struct FlopSolution {
 
 /// Solve a flop spot using External Sampling MCCFR with template trees.
 pub fn solve_flop(config: &FlopSolverConfig) -> FlopSolution {
+    // Unabstracted: one bucket per (combo, turn or river card), so size the
+    // templates for the larger range
+    if config.bucketing == BucketingMode::Unabstracted {
+        let max_combos = expand_range_to_combos(&config.oop_range, &config.board)
+            .len()
+            .max(expand_range_to_combos(&config.ip_range, &config.board).len());
+        let num_buckets = max_combos * CARDS_AFTER_FLOP;
+        if max_combos > 0 && config.num_buckets != num_buckets {
+            let mut config = config.clone();
+            config.num_buckets = num_buckets;
+            return solve_flop(&config);
+        }
+    }
+
     // 1. Build three separate trees
     let flop_tree_config = TreeConfig {
-        bet_sizes: vec![0.33, 0.75],
-        raise_sizes: vec![1.0],
+        bet_sizes: same_for_both(vec![BetSize::Pot(0.33), BetSize::Pot(0.75)]),
+        raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![1.0])),
         max_raises: 2,
-        starting_pot: config.starting_pot,
-        effective_stack: config.effective_stack,
+        starting_pot: config.starting_pot + config.dead_money,
+        stacks: [config.effective_stack; 2],
         add_allin: true,
+        min_bet: 0.0,
+        bet_round_to: None,
+        merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+        allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+        force_allin_above_stack_pct: None,
+        can_raise: [true; 2],
+        max_check_raises: None,
     };
     let (flop_tree, _flop_nodes) = build_tree(&flop_tree_config);
 
     let turn_template_config = TreeConfig {
-        bet_sizes: vec![0.66],
-        raise_sizes: vec![1.0],
+        bet_sizes: same_for_both(vec![BetSize::Pot(0.66)]),
+        raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![1.0])),
         max_raises: 1,
         starting_pot: 1.0,
-        effective_stack: 100.0,
+        stacks: [100.0; 2],
         add_allin: false,
+        min_bet: 0.0,
+        bet_round_to: None,
+        merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+        allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+        force_allin_above_stack_pct: None,
+        can_raise: [true; 2],
+        max_check_raises: None,
     };
     let (turn_template, _turn_nodes) = build_tree(&turn_template_config);
 
     let river_template_config = TreeConfig {
-        bet_sizes: vec![0.5, 1.0],
-        raise_sizes: vec![1.0],
+        bet_sizes: same_for_both(vec![BetSize::Pot(0.5), BetSize::Pot(1.0)]),
+        raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![1.0])),
         max_raises: 1,
         starting_pot: 1.0,
-        effective_stack: 100.0,
+        stacks: [100.0; 2],
         add_allin: false,
+        min_bet: 0.0,
+        bet_round_to: None,
+        merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+        allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+        force_allin_above_stack_pct: None,
+        can_raise: [true; 2],
+        max_check_raises: None,
     };
     let (river_template, _river_nodes) = build_tree(&river_template_config);
 
@@ -205,10 +305,22 @@ pub fn solve_flop(config: &FlopSolverConfig) -> FlopSolution {
     let oop_combo_pairs: Vec<(u8, u8)> = oop_combos.iter().map(|c| (c.0, c.1)).collect();
     let ip_combo_pairs: Vec<(u8, u8)> = ip_combos.iter().map(|c| (c.0, c.1)).collect();
 
-    let flop_oop_buckets =
-        assign_buckets(&oop_combo_pairs, &config.board, config.num_buckets, 500);
-    let flop_ip_buckets =
-        assign_buckets(&ip_combo_pairs, &config.board, config.num_buckets, 500);
+    let (flop_oop_buckets, flop_oop_equities) = bucket_combos_with_equity(
+        config.bucketing,
+        &oop_combo_pairs,
+        &config.board,
+        config.num_buckets,
+        500,
+        config.seed,
+    );
+    let (flop_ip_buckets, flop_ip_equities) = bucket_combos_with_equity(
+        config.bucketing,
+        &ip_combo_pairs,
+        &config.board,
+        config.num_buckets,
+        500,
+        config.seed,
+    );
 
     let num_oop_buckets = (*flop_oop_buckets.iter().max().unwrap_or(&0) + 1) as u16;
     let num_ip_buckets = (*flop_ip_buckets.iter().max().unwrap_or(&0) + 1) as u16;
@@ -373,8 +485,22 @@ pub fn solve_flop(config: &FlopSolverConfig) -> FlopSolution {
         .par_iter()
         .map(|&turn_card| {
             let turn_board = [config.board[0], config.board[1], config.board[2], turn_card];
-            let turn_oop = assign_buckets(&oop_combo_pairs, &turn_board, config.num_buckets, 200);
-            let turn_ip = assign_buckets(&ip_combo_pairs, &turn_board, config.num_buckets, 200);
+            let turn_oop = bucket_combos(
+                config.bucketing,
+                &oop_combo_pairs,
+                &turn_board,
+                config.num_buckets,
+                200,
+                config.seed,
+            );
+            let turn_ip = bucket_combos(
+                config.bucketing,
+                &ip_combo_pairs,
+                &turn_board,
+                config.num_buckets,
+                200,
+                config.seed,
+            );
             (turn_oop, turn_ip)
         })
         .collect();
@@ -405,14 +531,26 @@ pub fn solve_flop(config: &FlopSolverConfig) -> FlopSolution {
                     turn_card,
                     river_card,
                 ];
-                let r_oop =
-                    assign_buckets(&oop_combo_pairs, &river_board, config.num_buckets, 0);
-                let r_ip =
-                    assign_buckets(&ip_combo_pairs, &river_board, config.num_buckets, 0);
+                let r_oop = bucket_combos(
+                    config.bucketing,
+                    &oop_combo_pairs,
+                    &river_board,
+                    config.num_buckets,
+                    0,
+                    config.seed,
+                );
+                let r_ip = bucket_combos(
+                    config.bucketing,
+                    &ip_combo_pairs,
+                    &river_board,
+                    config.num_buckets,
+                    0,
+                    config.seed,
+                );
                 let s_oop: Vec<u32> = oop_combo_pairs
                     .iter()
                     .map(|&(c0, c1)| {
-                        evaluate_fast(&[
+                        evaluate_seven(&[
                             c0,
                             c1,
                             river_board[0],
@@ -426,7 +564,7 @@ pub fn solve_flop(config: &FlopSolverConfig) -> FlopSolution {
                 let s_ip: Vec<u32> = ip_combo_pairs
                     .iter()
                     .map(|&(c0, c1)| {
-                        evaluate_fast(&[
+                        evaluate_seven(&[
                             c0,
                             c1,
                             river_board[0],
@@ -446,7 +584,8 @@ pub fn solve_flop(config: &FlopSolverConfig) -> FlopSolution {
         score_table = st;
     }
 
-    let mut rng = rand::thread_rng();
+    let mut rng = solver_rng(config.seed);
+    let deal_every_turn = config.bucketing == BucketingMode::Unabstracted;
 
     // 7. Run MCCFR iterations
     for iter in 0..config.iterations {
@@ -456,214 +595,221 @@ pub fn solve_flop(config: &FlopSolverConfig) -> FlopSolution {
             Player::IP
         };
 
-        // Sample a turn card
-        let turn_raw_idx = rng.gen_range(0..num_remaining);
-        let turn_card = remaining_after_flop[turn_raw_idx];
-
-        // Sample a river card (not the turn card)
-        let river_raw_idx = {
-            let mut ri;
-            loop {
-                ri = rng.gen_range(0..num_remaining);
-                if ri != turn_raw_idx {
-                    break;
-                }
-            }
-            ri
+        // Unabstracted mode deals every turn card (a chance node) with one
+        // sampled river each; bucketed mode samples a single runout.
+        let turns: Vec<usize> = if deal_every_turn {
+            (0..num_remaining).collect()
+        } else {
+            vec![rng.gen_range(0..num_remaining)]
         };
-        let river_card = remaining_after_flop[river_raw_idx];
+        for turn_raw_idx in turns {
+            let turn_card = remaining_after_flop[turn_raw_idx];
+
+            // Sample a river card (not the turn card)
+            let river_raw_idx = {
+                let mut ri;
+                loop {
+                    ri = rng.gen_range(0..num_remaining);
+                    if ri != turn_raw_idx {
+                        break;
+                    }
+                }
+                ri
+            };
+            let river_card = remaining_after_flop[river_raw_idx];
 
-        // Lookup precomputed buckets and scores
-        let (turn_oop_buckets, turn_ip_buckets) = &turn_bucket_table[turn_raw_idx];
-        let runout_idx = turn_raw_idx * (num_remaining - 1)
-            + if river_raw_idx > turn_raw_idx {
-                river_raw_idx - 1
-            } else {
-                river_raw_idx
+            // Lookup precomputed buckets and scores
+            let (turn_oop_buckets, turn_ip_buckets) = &turn_bucket_table[turn_raw_idx];
+            let runout_idx = turn_raw_idx * (num_remaining - 1)
+                + if river_raw_idx > turn_raw_idx {
+                    river_raw_idx - 1
+                } else {
+                    river_raw_idx
+                };
+            let (river_oop_buckets, river_ip_buckets) = &river_bucket_table[runout_idx];
+            let (oop_scores, ip_scores) = &score_table[runout_idx];
+
+            let num_combos = match traverser {
+                Player::OOP => oop_combos.len(),
+                Player::IP => ip_combos.len(),
             };
-        let (river_oop_buckets, river_ip_buckets) = &river_bucket_table[runout_idx];
-        let (oop_scores, ip_scores) = &score_table[runout_idx];
 
-        let num_combos = match traverser {
-            Player::OOP => oop_combos.len(),
-            Player::IP => ip_combos.len(),
-        };
+            // Sequential path for small ranges (< 20 combos)
+            if num_combos < 20 {
+                for h in 0..num_combos {
+                    let blocked = match traverser {
+                        Player::OOP => {
+                            oop_blockers[h][turn_card as usize]
+                                || oop_blockers[h][river_card as usize]
+                        }
+                        Player::IP => {
+                            ip_blockers[h][turn_card as usize]
+                                || ip_blockers[h][river_card as usize]
+                        }
+                    };
+                    if blocked { continue; }
 
-        // Sequential path for small ranges (< 20 combos)
-        if num_combos < 20 {
-            for h in 0..num_combos {
-                let blocked = match traverser {
-                    Player::OOP => {
-                        oop_blockers[h][turn_card as usize]
-                            || oop_blockers[h][river_card as usize]
-                    }
-                    Player::IP => {
-                        ip_blockers[h][turn_card as usize]
-                            || ip_blockers[h][river_card as usize]
-                    }
-                };
-                if blocked { continue; }
-
-                let opp_reach = match traverser {
-                    Player::OOP => {
-                        let valid = &valid_ip_for_oop[h];
-                        let mut reach = vec![0.0f64; ip_combos.len()];
-                        for &j in valid {
-                            let j = j as usize;
-                            if !ip_blockers[j][turn_card as usize]
-                                && !ip_blockers[j][river_card as usize]
-                            {
-                                reach[j] = 1.0;
+                    let opp_reach = match traverser {
+                        Player::OOP => {
+                            let valid = &valid_ip_for_oop[h];
+                            let mut reach = vec![0.0f64; ip_combos.len()];
+                            for &j in valid {
+                                let j = j as usize;
+                                if !ip_blockers[j][turn_card as usize]
+                                    && !ip_blockers[j][river_card as usize]
+                                {
+                                    reach[j] = 1.0;
+                                }
                             }
+                            reach
                         }
-                        reach
-                    }
-                    Player::IP => {
-                        let valid = &valid_oop_for_ip[h];
-                        let mut reach = vec![0.0f64; oop_combos.len()];
-                        for &i in valid {
-                            let i = i as usize;
-                            if !oop_blockers[i][turn_card as usize]
-                                && !oop_blockers[i][river_card as usize]
-                            {
-                                reach[i] = 1.0;
+                        Player::IP => {
+                            let valid = &valid_oop_for_ip[h];
+                            let mut reach = vec![0.0f64; oop_combos.len()];
+                            for &i in valid {
+                                let i = i as usize;
+                                if !oop_blockers[i][turn_card as usize]
+                                    && !oop_blockers[i][river_card as usize]
+                                {
+                                    reach[i] = 1.0;
+                                }
                             }
+                            reach
                         }
-                        reach
-                    }
-                };
+                    };
 
-                let flop_bucket = match traverser {
-                    Player::OOP => flop_oop_buckets[h] as usize,
-                    Player::IP => flop_ip_buckets[h] as usize,
-                };
-                let turn_bucket = match traverser {
-                    Player::OOP => turn_oop_buckets[h] as usize,
-                    Player::IP => turn_ip_buckets[h] as usize,
-                };
-                let river_bucket = match traverser {
-                    Player::OOP => river_oop_buckets[h] as usize,
-                    Player::IP => river_ip_buckets[h] as usize,
-                };
+                    let flop_bucket = match traverser {
+                        Player::OOP => flop_oop_buckets[h] as usize,
+                        Player::IP => flop_ip_buckets[h] as usize,
+                    };
+                    let turn_bucket = match traverser {
+                        Player::OOP => turn_oop_buckets[h] as usize,
+                        Player::IP => turn_ip_buckets[h] as usize,
+                    };
+                    let river_bucket = match traverser {
+                        Player::OOP => river_oop_buckets[h] as usize,
+                        Player::IP => river_ip_buckets[h] as usize,
+                    };
 
-                cfr_traverse_flop(
-                    &flop_tree, traverser, h, flop_bucket, turn_bucket, river_bucket,
-                    &opp_reach, &oop_combos, &ip_combos,
-                    &oop_blockers, &ip_blockers,
-                    &flop_oop_buckets, &flop_ip_buckets,
-                    turn_oop_buckets, turn_ip_buckets,
-                    river_oop_buckets, river_ip_buckets,
-                    oop_scores, ip_scores,
-                    &valid_ip_for_oop, &valid_oop_for_ip,
-                    config.starting_pot, &turn_template, &river_template,
-                    &mut flop_oop_cfr, &mut flop_ip_cfr,
-                    &mut turn_oop_cfr, &mut turn_ip_cfr,
-                    &mut river_oop_cfr, &mut river_ip_cfr,
-                    &mut strategy_buf, &mut action_values,
-                    iter,
-                );
+                    cfr_traverse_flop(
+                        &flop_tree, traverser, h, 1.0, flop_bucket, turn_bucket, river_bucket,
+                        &opp_reach, &oop_combos, &ip_combos,
+                        &oop_blockers, &ip_blockers,
+                        &flop_oop_buckets, &flop_ip_buckets,
+                        turn_oop_buckets, turn_ip_buckets,
+                        river_oop_buckets, river_ip_buckets,
+                        oop_scores, ip_scores,
+                        &valid_ip_for_oop, &valid_oop_for_ip,
+                        config.starting_pot + config.dead_money, &turn_template, &river_template,
+                        &mut flop_oop_cfr, &mut flop_ip_cfr,
+                        &mut turn_oop_cfr, &mut turn_ip_cfr,
+                        &mut river_oop_cfr, &mut river_ip_cfr,
+                        &mut strategy_buf, &mut action_values,
+                        iter,
+                    );
+                }
+                continue;
             }
-            continue;
-        }
 
-        // Parallel path for large ranges (>= 20 combos)
-        // Snapshot CFR instances for parallel readonly traversal
-        let snap_flop_oop = flop_oop_cfr.clone();
-        let snap_flop_ip = flop_ip_cfr.clone();
-        let snap_turn_oop = turn_oop_cfr.clone();
-        let snap_turn_ip = turn_ip_cfr.clone();
-        let snap_river_oop = river_oop_cfr.clone();
-        let snap_river_ip = river_ip_cfr.clone();
+            // Parallel path for large ranges (>= 20 combos)
+            // Snapshot CFR instances for parallel readonly traversal
+            let snap_flop_oop = flop_oop_cfr.clone();
+            let snap_flop_ip = flop_ip_cfr.clone();
+            let snap_turn_oop = turn_oop_cfr.clone();
+            let snap_turn_ip = turn_ip_cfr.clone();
+            let snap_river_oop = river_oop_cfr.clone();
+            let snap_river_ip = river_ip_cfr.clone();
+
+            let all_updates: Vec<Vec<RegretUpdate>> = (0..num_combos)
+                .into_par_iter()
+                .filter_map(|h| {
+                    let blocked = match traverser {
+                        Player::OOP => {
+                            oop_blockers[h][turn_card as usize]
+                                || oop_blockers[h][river_card as usize]
+                        }
+                        Player::IP => {
+                            ip_blockers[h][turn_card as usize]
+                                || ip_blockers[h][river_card as usize]
+                        }
+                    };
+                    if blocked { return None; }
 
-        let all_updates: Vec<Vec<RegretUpdate>> = (0..num_combos)
-            .into_par_iter()
-            .filter_map(|h| {
-                let blocked = match traverser {
-                    Player::OOP => {
-                        oop_blockers[h][turn_card as usize]
-                            || oop_blockers[h][river_card as usize]
-                    }
-                    Player::IP => {
-                        ip_blockers[h][turn_card as usize]
-                            || ip_blockers[h][river_card as usize]
-                    }
-                };
-                if blocked { return None; }
-
-                let opp_reach = match traverser {
-                    Player::OOP => {
-                        let valid = &valid_ip_for_oop[h];
-                        let mut reach = vec![0.0f64; ip_combos.len()];
-                        for &j in valid {
-                            let j = j as usize;
-                            if !ip_blockers[j][turn_card as usize]
-                                && !ip_blockers[j][river_card as usize]
-                            {
-                                reach[j] = 1.0;
+                    let opp_reach = match traverser {
+                        Player::OOP => {
+                            let valid = &valid_ip_for_oop[h];
+                            let mut reach = vec![0.0f64; ip_combos.len()];
+                            for &j in valid {
+                                let j = j as usize;
+                                if !ip_blockers[j][turn_card as usize]
+                                    && !ip_blockers[j][river_card as usize]
+                                {
+                                    reach[j] = 1.0;
+                                }
                             }
+                            reach
                         }
-                        reach
-                    }
-                    Player::IP => {
-                        let valid = &valid_oop_for_ip[h];
-                        let mut reach = vec![0.0f64; oop_combos.len()];
-                        for &i in valid {
-                            let i = i as usize;
-                            if !oop_blockers[i][turn_card as usize]
-                                && !oop_blockers[i][river_card as usize]
-                            {
-                                reach[i] = 1.0;
+                        Player::IP => {
+                            let valid = &valid_oop_for_ip[h];
+                            let mut reach = vec![0.0f64; oop_combos.len()];
+                            for &i in valid {
+                                let i = i as usize;
+                                if !oop_blockers[i][turn_card as usize]
+                                    && !oop_blockers[i][river_card as usize]
+                                {
+                                    reach[i] = 1.0;
+                                }
                             }
+                            reach
                         }
-                        reach
-                    }
-                };
+                    };
 
-                let flop_bucket = match traverser {
-                    Player::OOP => flop_oop_buckets[h] as usize,
-                    Player::IP => flop_ip_buckets[h] as usize,
-                };
-                let turn_bucket = match traverser {
-                    Player::OOP => turn_oop_buckets[h] as usize,
-                    Player::IP => turn_ip_buckets[h] as usize,
-                };
-                let river_bucket = match traverser {
-                    Player::OOP => river_oop_buckets[h] as usize,
-                    Player::IP => river_ip_buckets[h] as usize,
-                };
+                    let flop_bucket = match traverser {
+                        Player::OOP => flop_oop_buckets[h] as usize,
+                        Player::IP => flop_ip_buckets[h] as usize,
+                    };
+                    let turn_bucket = match traverser {
+                        Player::OOP => turn_oop_buckets[h] as usize,
+                        Player::IP => turn_ip_buckets[h] as usize,
+                    };
+                    let river_bucket = match traverser {
+                        Player::OOP => river_oop_buckets[h] as usize,
+                        Player::IP => river_ip_buckets[h] as usize,
+                    };
 
-                let mut updates = Vec::new();
-                cfr_traverse_flop_ro(
-                    &flop_tree, traverser, h, flop_bucket, turn_bucket, river_bucket,
-                    &opp_reach, &oop_combos, &ip_combos,
-                    &oop_blockers, &ip_blockers,
-                    &flop_oop_buckets, &flop_ip_buckets,
-                    turn_oop_buckets, turn_ip_buckets,
-                    river_oop_buckets, river_ip_buckets,
-                    oop_scores, ip_scores,
-                    &valid_ip_for_oop, &valid_oop_for_ip,
-                    config.starting_pot, &turn_template, &river_template,
-                    &snap_flop_oop, &snap_flop_ip,
-                    &snap_turn_oop, &snap_turn_ip,
-                    &snap_river_oop, &snap_river_ip,
-                    &mut updates, iter,
-                );
-                Some(updates)
-            })
-            .collect();
+                    let mut updates = Vec::new();
+                    cfr_traverse_flop_ro(
+                        &flop_tree, traverser, h, 1.0, flop_bucket, turn_bucket, river_bucket,
+                        &opp_reach, &oop_combos, &ip_combos,
+                        &oop_blockers, &ip_blockers,
+                        &flop_oop_buckets, &flop_ip_buckets,
+                        turn_oop_buckets, turn_ip_buckets,
+                        river_oop_buckets, river_ip_buckets,
+                        oop_scores, ip_scores,
+                        &valid_ip_for_oop, &valid_oop_for_ip,
+                        config.starting_pot + config.dead_money, &turn_template, &river_template,
+                        &snap_flop_oop, &snap_flop_ip,
+                        &snap_turn_oop, &snap_turn_ip,
+                        &snap_river_oop, &snap_river_ip,
+                        &mut updates, iter,
+                    );
+                    Some(updates)
+                })
+                .collect();
 
-        for hand_updates in all_updates {
-            for upd in hand_updates {
-                let cfr = match (traverser, upd.street) {
-                    (Player::OOP, 0) => &mut flop_oop_cfr,
-                    (Player::IP, 0) => &mut flop_ip_cfr,
-                    (Player::OOP, 1) => &mut turn_oop_cfr,
-                    (Player::IP, 1) => &mut turn_ip_cfr,
-                    (Player::OOP, 2) => &mut river_oop_cfr,
-                    (Player::IP, 2) => &mut river_ip_cfr,
-                    _ => unreachable!(),
-                };
-                cfr.update(upd.node_id, upd.bucket, &upd.action_values, upd.node_value, upd.reach_prob);
+            for hand_updates in all_updates {
+                for upd in hand_updates {
+                    let cfr = match (traverser, upd.street) {
+                        (Player::OOP, 0) => &mut flop_oop_cfr,
+                        (Player::IP, 0) => &mut flop_ip_cfr,
+                        (Player::OOP, 1) => &mut turn_oop_cfr,
+                        (Player::IP, 1) => &mut turn_ip_cfr,
+                        (Player::OOP, 2) => &mut river_oop_cfr,
+                        (Player::IP, 2) => &mut river_ip_cfr,
+                        _ => unreachable!(),
+                    };
+                    cfr.update(upd.node_id, upd.bucket, &upd.action_values, upd.node_value, upd.reach_prob);
+                }
             }
         }
     }
@@ -678,6 +824,7 @@ pub fn solve_flop(config: &FlopSolverConfig) -> FlopSolution {
         &ip_combos,
         &flop_oop_buckets,
         &flop_ip_buckets,
+        [&flop_oop_equities, &flop_ip_equities],
         &flop_metas,
         &turn_template,
         &river_template,
@@ -702,6 +849,7 @@ fn cfr_traverse_flop(
     node: &TreeNode,
     traverser: Player,
     hand_idx: usize,
+    my_reach: f64,
     flop_bucket: usize,
     turn_bucket: usize,
     river_bucket: usize,
@@ -761,6 +909,7 @@ fn cfr_traverse_flop(
                         turn_template,
                         traverser,
                         hand_idx,
+                        my_reach,
                         turn_bucket,
                         river_bucket,
                         opp_reach,
@@ -819,6 +968,7 @@ fn cfr_traverse_flop(
                         &children[a],
                         traverser,
                         hand_idx,
+                        my_reach * strategy_buf[a] as f64,
                         flop_bucket,
                         turn_bucket,
                         river_bucket,
@@ -854,8 +1004,7 @@ fn cfr_traverse_flop(
                     node_value += strategy_buf[a] as f64 * av;
                 }
 
-                let reach_sum: f64 = opp_reach.iter().sum();
-                let reach_prob = if reach_sum > 0.0 { 1.0f32 } else { 0.0f32 };
+                let reach_prob = my_reach as f32;
 
                 let cfr_mut = match traverser {
                     Player::OOP => &mut *flop_oop_cfr,
@@ -910,6 +1059,7 @@ fn cfr_traverse_flop(
                         &children[a],
                         traverser,
                         hand_idx,
+                        my_reach,
                         flop_bucket,
                         turn_bucket,
                         river_bucket,
@@ -963,6 +1113,7 @@ fn cfr_traverse_turn_template(
     node: &TreeNode,
     traverser: Player,
     hand_idx: usize,
+    my_reach: f64,
     turn_bucket: usize,
     river_bucket: usize,
     opp_reach: &[f64],
@@ -1009,12 +1160,14 @@ fn cfr_traverse_turn_template(
                     }
                 }
                 TerminalType::Showdown => {
-                    // Chain to river template
+                    // Chain to river template, charging this street's investment
                     let river_scale = *pot * scale;
-                    cfr_traverse_river_template(
+                    let my_invested = invested[traverser.index()] * scale;
+                    let river_value = cfr_traverse_river_template(
                         river_template,
                         traverser,
                         hand_idx,
+                        my_reach,
                         river_bucket,
                         opp_reach,
                         oop_combos,
@@ -1031,7 +1184,8 @@ fn cfr_traverse_turn_template(
                         strategy_buf,
                         action_values_buf,
                         iter,
-                    )
+                    );
+                    river_value - my_invested * opp_reach_sum
                 }
             }
         }
@@ -1063,6 +1217,7 @@ fn cfr_traverse_turn_template(
                         &children[a],
                         traverser,
                         hand_idx,
+                        my_reach * strategy_buf[a] as f64,
                         turn_bucket,
                         river_bucket,
                         opp_reach,
@@ -1090,8 +1245,7 @@ fn cfr_traverse_turn_template(
                     node_value += strategy_buf[a] as f64 * av;
                 }
 
-                let reach_sum: f64 = opp_reach.iter().sum();
-                let reach_prob = if reach_sum > 0.0 { 1.0f32 } else { 0.0f32 };
+                let reach_prob = my_reach as f32;
 
                 let cfr_mut = match traverser {
                     Player::OOP => &mut *turn_oop_cfr,
@@ -1144,6 +1298,7 @@ fn cfr_traverse_turn_template(
                         &children[a],
                         traverser,
                         hand_idx,
+                        my_reach,
                         turn_bucket,
                         river_bucket,
                         &new_opp_reach,
@@ -1189,6 +1344,7 @@ fn cfr_traverse_river_template(
     node: &TreeNode,
     traverser: Player,
     hand_idx: usize,
+    my_reach: f64,
     river_bucket: usize,
     opp_reach: &[f64],
     oop_combos: &[Combo],
@@ -1308,6 +1464,7 @@ fn cfr_traverse_river_template(
                         &children[a],
                         traverser,
                         hand_idx,
+                        my_reach * strategy_buf[a] as f64,
                         river_bucket,
                         opp_reach,
                         oop_combos,
@@ -1329,8 +1486,7 @@ fn cfr_traverse_river_template(
                     node_value += strategy_buf[a] as f64 * av;
                 }
 
-                let reach_sum: f64 = opp_reach.iter().sum();
-                let reach_prob = if reach_sum > 0.0 { 1.0f32 } else { 0.0f32 };
+                let reach_prob = my_reach as f32;
 
                 let cfr_mut = match traverser {
                     Player::OOP => &mut *river_oop_cfr,
@@ -1383,6 +1539,7 @@ fn cfr_traverse_river_template(
                         &children[a],
                         traverser,
                         hand_idx,
+                        my_reach,
                         river_bucket,
                         &new_opp_reach,
                         oop_combos,
@@ -1429,7 +1586,7 @@ struct RegretUpdate {
 /// Readonly flop traversal that collects RegretUpdates instead of mutating CFR.
 #[allow(clippy::too_many_arguments)]
 fn cfr_traverse_flop_ro(
-    node: &TreeNode, traverser: Player, hand_idx: usize,
+    node: &TreeNode, traverser: Player, hand_idx: usize, my_reach: f64,
     flop_bucket: usize, turn_bucket: usize, river_bucket: usize,
     opp_reach: &[f64], oop_combos: &[Combo], ip_combos: &[Combo],
     oop_blockers: &[[bool; 52]], ip_blockers: &[[bool; 52]],
@@ -1457,7 +1614,7 @@ fn cfr_traverse_flop_ro(
                 TerminalType::Showdown => {
                     let turn_scale = *pot;
                     let turn_value = cfr_traverse_turn_template_ro(
-                        turn_template, traverser, hand_idx, turn_bucket, river_bucket,
+                        turn_template, traverser, hand_idx, my_reach, turn_bucket, river_bucket,
                         opp_reach, oop_combos, ip_combos,
                         turn_oop_buckets, turn_ip_buckets,
                         river_oop_buckets, river_ip_buckets,
@@ -1485,7 +1642,7 @@ fn cfr_traverse_flop_ro(
                 for a in 0..num_actions {
                     if strategy[a] < 0.001 && iter > 1000 && iter % 1000 != 0 { continue; }
                     let av = cfr_traverse_flop_ro(
-                        &children[a], traverser, hand_idx,
+                        &children[a], traverser, hand_idx, my_reach * strategy[a] as f64,
                         flop_bucket, turn_bucket, river_bucket,
                         opp_reach, oop_combos, ip_combos,
                         oop_blockers, ip_blockers,
@@ -1503,8 +1660,7 @@ fn cfr_traverse_flop_ro(
                     action_vals[a] = av as f32;
                     node_value += strategy[a] as f64 * av;
                 }
-                let reach_sum: f64 = opp_reach.iter().sum();
-                let reach_prob = if reach_sum > 0.0 { 1.0f32 } else { 0.0f32 };
+                let reach_prob = my_reach as f32;
                 updates.push(RegretUpdate {
                     street: 0, node_id: nid, bucket: flop_bucket,
                     action_values: action_vals, node_value: node_value as f32, reach_prob,
@@ -1537,7 +1693,7 @@ fn cfr_traverse_flop_ro(
                         }
                     }
                     node_value += cfr_traverse_flop_ro(
-                        &children[a], traverser, hand_idx,
+                        &children[a], traverser, hand_idx, my_reach,
                         flop_bucket, turn_bucket, river_bucket,
                         &new_opp_reach, oop_combos, ip_combos,
                         oop_blockers, ip_blockers,
@@ -1563,7 +1719,7 @@ fn cfr_traverse_flop_ro(
 /// Readonly turn template traversal.
 #[allow(clippy::too_many_arguments)]
 fn cfr_traverse_turn_template_ro(
-    node: &TreeNode, traverser: Player, hand_idx: usize,
+    node: &TreeNode, traverser: Player, hand_idx: usize, my_reach: f64,
     turn_bucket: usize, river_bucket: usize,
     opp_reach: &[f64], oop_combos: &[Combo], ip_combos: &[Combo],
     turn_oop_buckets: &[u16], turn_ip_buckets: &[u16],
@@ -1587,13 +1743,15 @@ fn cfr_traverse_turn_template_ro(
                 }
                 TerminalType::Showdown => {
                     let river_scale = *pot * scale;
-                    cfr_traverse_river_template_ro(
-                        river_template, traverser, hand_idx, river_bucket,
+                    let my_invested = invested[traverser.index()] * scale;
+                    let river_value = cfr_traverse_river_template_ro(
+                        river_template, traverser, hand_idx, my_reach, river_bucket,
                         opp_reach, oop_combos, ip_combos,
                         river_oop_buckets, river_ip_buckets,
                         oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
                         river_scale, river_oop_cfr, river_ip_cfr, updates, iter,
-                    )
+                    );
+                    river_value - my_invested * opp_reach_sum
                 }
             }
         }
@@ -1612,7 +1770,7 @@ fn cfr_traverse_turn_template_ro(
                 for a in 0..num_actions {
                     if strategy[a] < 0.001 && iter > 1000 && iter % 1000 != 0 { continue; }
                     let av = cfr_traverse_turn_template_ro(
-                        &children[a], traverser, hand_idx, turn_bucket, river_bucket,
+                        &children[a], traverser, hand_idx, my_reach * strategy[a] as f64, turn_bucket, river_bucket,
                         opp_reach, oop_combos, ip_combos,
                         turn_oop_buckets, turn_ip_buckets,
                         river_oop_buckets, river_ip_buckets,
@@ -1624,8 +1782,7 @@ fn cfr_traverse_turn_template_ro(
                     action_vals[a] = av as f32;
                     node_value += strategy[a] as f64 * av;
                 }
-                let reach_sum: f64 = opp_reach.iter().sum();
-                let reach_prob = if reach_sum > 0.0 { 1.0f32 } else { 0.0f32 };
+                let reach_prob = my_reach as f32;
                 updates.push(RegretUpdate {
                     street: 1, node_id: nid, bucket: turn_bucket,
                     action_values: action_vals, node_value: node_value as f32, reach_prob,
@@ -1658,7 +1815,7 @@ fn cfr_traverse_turn_template_ro(
                         }
                     }
                     node_value += cfr_traverse_turn_template_ro(
-                        &children[a], traverser, hand_idx, turn_bucket, river_bucket,
+                        &children[a], traverser, hand_idx, my_reach, turn_bucket, river_bucket,
                         &new_opp_reach, oop_combos, ip_combos,
                         turn_oop_buckets, turn_ip_buckets,
                         river_oop_buckets, river_ip_buckets,
@@ -1678,7 +1835,7 @@ fn cfr_traverse_turn_template_ro(
 /// Readonly river template traversal.
 #[allow(clippy::too_many_arguments)]
 fn cfr_traverse_river_template_ro(
-    node: &TreeNode, traverser: Player, hand_idx: usize,
+    node: &TreeNode, traverser: Player, hand_idx: usize, my_reach: f64,
     river_bucket: usize, opp_reach: &[f64],
     oop_combos: &[Combo], ip_combos: &[Combo],
     river_oop_buckets: &[u16], river_ip_buckets: &[u16],
@@ -1743,7 +1900,7 @@ fn cfr_traverse_river_template_ro(
                 for a in 0..num_actions {
                     if strategy[a] < 0.001 && iter > 1000 && iter % 1000 != 0 { continue; }
                     let av = cfr_traverse_river_template_ro(
-                        &children[a], traverser, hand_idx, river_bucket,
+                        &children[a], traverser, hand_idx, my_reach * strategy[a] as f64, river_bucket,
                         opp_reach, oop_combos, ip_combos,
                         river_oop_buckets, river_ip_buckets,
                         oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
@@ -1752,8 +1909,7 @@ fn cfr_traverse_river_template_ro(
                     action_vals[a] = av as f32;
                     node_value += strategy[a] as f64 * av;
                 }
-                let reach_sum: f64 = opp_reach.iter().sum();
-                let reach_prob = if reach_sum > 0.0 { 1.0f32 } else { 0.0f32 };
+                let reach_prob = my_reach as f32;
                 updates.push(RegretUpdate {
                     street: 2, node_id: nid, bucket: river_bucket,
                     action_values: action_vals, node_value: node_value as f32, reach_prob,
@@ -1786,7 +1942,7 @@ fn cfr_traverse_river_template_ro(
                         }
                     }
                     node_value += cfr_traverse_river_template_ro(
-                        &children[a], traverser, hand_idx, river_bucket,
+                        &children[a], traverser, hand_idx, my_reach, river_bucket,
                         &new_opp_reach, oop_combos, ip_combos,
                         river_oop_buckets, river_ip_buckets,
                         oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
@@ -1801,248 +1957,244 @@ fn cfr_traverse_river_template_ro(
 }
 
 // ---------------------------------------------------------------------------
-// Exploitability (Monte Carlo estimate)
+// Exploitability (combo space)
 // ---------------------------------------------------------------------------
 
-/// Estimate exploitability via Monte Carlo best-response sampling.
+/// Number of sampled (turn, river) runouts used by the exploitability pass.
+const EXPLOIT_RUNOUTS: usize = 100;
+
+/// A sampled runout with the bucket and hand-strength tables for its turn
+/// and river. Tables are indexed `[player][combo]`.
+struct EvalRunout {
+    turn_card: u8,
+    river_card: u8,
+    turn_buckets: [Vec<u16>; 2],
+    river_buckets: [Vec<u16>; 2],
+    scores: [Vec<u32>; 2],
+}
+
+/// Read-only state for the exploitability pass. Per-player arrays are indexed
+/// by `Player::index()`; `cfrs` is `[street][player]`.
+struct EvalCtx<'a> {
+    turn_template: &'a TreeNode,
+    river_template: &'a TreeNode,
+    cfrs: [[&'a FlatCfr; 2]; 3],
+    flop_buckets: [&'a [u16]; 2],
+    blockers: [&'a [[bool; 52]]; 2],
+    /// `valid[p][h]`: opponent combos that don't share a card with `p`'s combo `h`.
+    valid: [&'a [Vec<u16>]; 2],
+    runouts: Vec<EvalRunout>,
+}
+
+impl EvalCtx<'_> {
+    fn bucket(&self, street: usize, player: usize, h: usize, runout: Option<&EvalRunout>) -> usize {
+        match (street, runout) {
+            (0, _) => self.flop_buckets[player][h] as usize,
+            (1, Some(r)) => r.turn_buckets[player][h] as usize,
+            (2, Some(r)) => r.river_buckets[player][h] as usize,
+            _ => unreachable!("turn and river nodes need a runout"),
+        }
+    }
+
+    fn blocked(&self, player: usize, h: usize, runout: Option<&EvalRunout>) -> bool {
+        runout.is_some_and(|r| {
+            self.blockers[player][h][r.turn_card as usize]
+                || self.blockers[player][h][r.river_card as usize]
+        })
+    }
+}
+
+/// Per-combo values for the best-responder, each summed over opponent reach:
+/// playing the average strategy, best-responding per combo, and
+/// best-responding per bucket (one action for every combo in a bucket).
+struct BrValues {
+    avg: Vec<f64>,
+    combo: Vec<f64>,
+    bucket: Vec<f64>,
+}
+
+impl BrValues {
+    fn zeros(n: usize) -> Self {
+        BrValues {
+            avg: vec![0.0; n],
+            combo: vec![0.0; n],
+            bucket: vec![0.0; n],
+        }
+    }
+
+    fn uniform(values: Vec<f64>) -> Self {
+        BrValues {
+            avg: values.clone(),
+            combo: values.clone(),
+            bucket: values,
+        }
+    }
+
+    fn add(&mut self, other: &BrValues) {
+        for h in 0..self.avg.len() {
+            self.avg[h] += other.avg[h];
+            self.combo[h] += other.combo[h];
+            self.bucket[h] += other.bucket[h];
+        }
+    }
+
+    /// Charge an investment of `amount` against each combo's opponent reach.
+    fn charge(&mut self, amount: f64, reach_sums: &[f64]) {
+        for (h, &sum) in reach_sums.iter().enumerate() {
+            self.avg[h] -= amount * sum;
+            self.combo[h] -= amount * sum;
+            self.bucket[h] -= amount * sum;
+        }
+    }
+}
+
+/// Exploitability of a solved flop strategy and each combo's root EV under it.
+struct Evaluation {
+    /// Against a bucket-level best response (chips per hand).
+    exploitability: f64,
+    /// Against a combo-level best response (chips per hand).
+    real_exploitability: f64,
+    /// Average-strategy EV of every combo, `[oop, ip]`, per valid matchup.
+    combo_evs: [Vec<Option<f64>>; 2],
+}
+
+/// Evaluate exploitability of the solved strategy at two granularities.
+///
+/// Exploitability is in chips per hand averaged over the two players. The abstraction number lets the best-responder pick one
+/// action per bucket, which is all the bucketed game can see. The real number
+/// lets it pick per combo, mapping each bucket's average strategy back onto
+/// its member combos. Flop decisions are made against the expectation over
+/// sampled runouts, never a single known runout.
 #[allow(clippy::too_many_arguments)]
-fn estimate_exploitability(
+fn evaluate_exploitability(
     flop_tree: &TreeNode,
     turn_template: &TreeNode,
     river_template: &TreeNode,
-    flop_oop_cfr: &FlatCfr,
-    flop_ip_cfr: &FlatCfr,
-    turn_oop_cfr: &FlatCfr,
-    turn_ip_cfr: &FlatCfr,
-    river_oop_cfr: &FlatCfr,
-    river_ip_cfr: &FlatCfr,
-    oop_combos: &[Combo],
-    ip_combos: &[Combo],
-    oop_blockers: &[[bool; 52]],
-    ip_blockers: &[[bool; 52]],
-    flop_oop_buckets: &[u16],
-    flop_ip_buckets: &[u16],
-    valid_ip_for_oop: &[Vec<u16>],
-    valid_oop_for_ip: &[Vec<u16>],
+    cfrs: [[&FlatCfr; 2]; 3],
+    combos: [&[Combo]; 2],
+    blockers: [&[[bool; 52]]; 2],
+    flop_buckets: [&[u16]; 2],
+    valid: [&[Vec<u16>]; 2],
     board: &[u8],
-    starting_pot: f64,
     num_buckets: usize,
-) -> f64 {
+    bucketing: BucketingMode,
+    seed: Option<u64>,
+) -> Evaluation {
     let remaining = remaining_deck(board);
-    let num_remaining = remaining.len();
-    let num_samples = 100;
-    let mut rng = rand::thread_rng();
-
-    let oop_pairs: Vec<(u8, u8)> = oop_combos.iter().map(|c| (c.0, c.1)).collect();
-    let ip_pairs: Vec<(u8, u8)> = ip_combos.iter().map(|c| (c.0, c.1)).collect();
+    let mut rng = solver_rng(seed);
+    let pairs: [Vec<(u8, u8)>; 2] = [
+        combos[0].iter().map(|c| (c.0, c.1)).collect(),
+        combos[1].iter().map(|c| (c.0, c.1)).collect(),
+    ];
+
+    let mut sampled: Vec<(u8, u8)> = Vec::with_capacity(EXPLOIT_RUNOUTS);
+    for _ in 0..EXPLOIT_RUNOUTS {
+        let t = rng.gen_range(0..remaining.len());
+        let r = loop {
+            let r = rng.gen_range(0..remaining.len());
+            if r != t {
+                break r;
+            }
+        };
+        sampled.push((remaining[t], remaining[r]));
+    }
 
-    // Precompute turn buckets for exploitability estimation
-    let turn_bucket_table: Vec<(Vec<u16>, Vec<u16>)> = remaining
+    let runouts: Vec<EvalRunout> = sampled
         .par_iter()
-        .map(|&turn_card| {
+        .map(|&(turn_card, river_card)| {
             let turn_board = [board[0], board[1], board[2], turn_card];
-            let t_oop = assign_buckets(&oop_pairs, &turn_board, num_buckets, 200);
-            let t_ip = assign_buckets(&ip_pairs, &turn_board, num_buckets, 200);
-            (t_oop, t_ip)
-        })
-        .collect();
-
-    // Precompute river data for exploitability estimation
-    let num_runouts = num_remaining * (num_remaining - 1);
-    let river_data: Vec<((Vec<u16>, Vec<u16>), (Vec<u32>, Vec<u32>))> = (0..num_runouts)
-        .into_par_iter()
-        .map(|runout_idx| {
-            let turn_idx = runout_idx / (num_remaining - 1);
-            let river_adj = runout_idx % (num_remaining - 1);
-            let river_idx = if river_adj >= turn_idx {
-                river_adj + 1
-            } else {
-                river_adj
-            };
-            let turn_card = remaining[turn_idx];
-            let river_card = remaining[river_idx];
             let river_board = [board[0], board[1], board[2], turn_card, river_card];
-            let r_oop = assign_buckets(&oop_pairs, &river_board, num_buckets, 0);
-            let r_ip = assign_buckets(&ip_pairs, &river_board, num_buckets, 0);
-            let s_oop: Vec<u32> = oop_pairs
-                .iter()
-                .map(|&(c0, c1)| {
-                    evaluate_fast(&[
-                        c0, c1, river_board[0], river_board[1], river_board[2],
-                        river_board[3], river_board[4],
-                    ])
-                })
-                .collect();
-            let s_ip: Vec<u32> = ip_pairs
-                .iter()
-                .map(|&(c0, c1)| {
-                    evaluate_fast(&[
-                        c0, c1, river_board[0], river_board[1], river_board[2],
-                        river_board[3], river_board[4],
-                    ])
-                })
-                .collect();
-            ((r_oop, r_ip), (s_oop, s_ip))
+            let turn = |p: usize| bucket_combos(bucketing, &pairs[p], &turn_board, num_buckets, 200, seed);
+            let river = |p: usize| bucket_combos(bucketing, &pairs[p], &river_board, num_buckets, 0, seed);
+            let scores = |p: usize| -> Vec<u32> {
+                pairs[p]
+                    .iter()
+                    .map(|&(c0, c1)| {
+                        evaluate_seven(&[
+                            c0, c1, river_board[0], river_board[1], river_board[2],
+                            river_board[3], river_board[4],
+                        ])
+                    })
+                    .collect()
+            };
+            EvalRunout {
+                turn_card,
+                river_card,
+                turn_buckets: [turn(0), turn(1)],
+                river_buckets: [river(0), river(1)],
+                scores: [scores(0), scores(1)],
+            }
         })
         .collect();
 
-    let mut oop_total_gain = 0.0;
-    let mut ip_total_gain = 0.0;
-    let mut sample_count = 0;
-
-    for _ in 0..num_samples {
-        let turn_raw_idx = rng.gen_range(0..num_remaining);
-        let turn_card = remaining[turn_raw_idx];
-        let river_raw_idx = loop {
-            let ri = rng.gen_range(0..num_remaining);
-            if ri != turn_raw_idx {
-                break ri;
-            }
-        };
-        let river_card = remaining[river_raw_idx];
-
-        let (turn_oop_buckets, turn_ip_buckets) = &turn_bucket_table[turn_raw_idx];
-        let runout_idx = turn_raw_idx * (num_remaining - 1)
-            + if river_raw_idx > turn_raw_idx {
-                river_raw_idx - 1
-            } else {
-                river_raw_idx
-            };
-        let ((river_oop_buckets, river_ip_buckets), (oop_scores, ip_scores)) =
-            &river_data[runout_idx];
-
-        let mut strat_buf = vec![0.0f32; 16];
-
-        // Compute BR and avg value for OOP
-        for h in 0..oop_combos.len() {
-            if oop_blockers[h][turn_card as usize] || oop_blockers[h][river_card as usize] {
-                continue;
-            }
-            let mut opp_reach = vec![0.0f64; ip_combos.len()];
-            for &j in &valid_ip_for_oop[h] {
-                let j = j as usize;
-                if !ip_blockers[j][turn_card as usize] && !ip_blockers[j][river_card as usize] {
-                    opp_reach[j] = 1.0;
-                }
-            }
+    let ctx = EvalCtx {
+        turn_template,
+        river_template,
+        cfrs,
+        flop_buckets,
+        blockers,
+        valid,
+        runouts,
+    };
 
-            let flop_bucket = flop_oop_buckets[h] as usize;
-            let turn_bucket = turn_oop_buckets[h] as usize;
-            let river_bucket = river_oop_buckets[h] as usize;
-
-            let br_val = br_traverse_flop(
-                flop_tree, Player::OOP, h, flop_bucket, turn_bucket, river_bucket,
-                &opp_reach, oop_combos, ip_combos,
-                flop_oop_buckets, flop_ip_buckets,
-                turn_oop_buckets, turn_ip_buckets,
-                river_oop_buckets, river_ip_buckets,
-                oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
-                starting_pot, turn_template, river_template,
-                flop_oop_cfr, flop_ip_cfr, turn_oop_cfr, turn_ip_cfr,
-                river_oop_cfr, river_ip_cfr, &mut strat_buf, true,
-            );
-            let avg_val = br_traverse_flop(
-                flop_tree, Player::OOP, h, flop_bucket, turn_bucket, river_bucket,
-                &opp_reach, oop_combos, ip_combos,
-                flop_oop_buckets, flop_ip_buckets,
-                turn_oop_buckets, turn_ip_buckets,
-                river_oop_buckets, river_ip_buckets,
-                oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
-                starting_pot, turn_template, river_template,
-                flop_oop_cfr, flop_ip_cfr, turn_oop_cfr, turn_ip_cfr,
-                river_oop_cfr, river_ip_cfr, &mut strat_buf, false,
-            );
-            oop_total_gain += br_val - avg_val;
-            sample_count += 1;
+    let mut abstraction = 0.0;
+    let mut real = 0.0;
+    let mut combo_evs: [Vec<Option<f64>>; 2] = Default::default();
+    for br in [Player::OOP, Player::IP] {
+        let p = br.index();
+        let opp_reach = vec![1.0f64; combos[1 - p].len()];
+        let values = eval_traverse(&ctx, flop_tree, 0, 1.0, br, None, &opp_reach);
+        combo_evs[p] = valid[p]
+            .iter()
+            .zip(&values.avg)
+            .map(|(v, &avg)| (!v.is_empty()).then(|| avg / v.len() as f64))
+            .collect();
+        let matchups: usize = valid[p].iter().map(|v| v.len()).sum();
+        if matchups == 0 {
+            continue;
         }
-
-        // Compute BR and avg value for IP
-        for h in 0..ip_combos.len() {
-            if ip_blockers[h][turn_card as usize] || ip_blockers[h][river_card as usize] {
-                continue;
-            }
-            let mut opp_reach = vec![0.0f64; oop_combos.len()];
-            for &i in &valid_oop_for_ip[h] {
-                let i = i as usize;
-                if !oop_blockers[i][turn_card as usize] && !oop_blockers[i][river_card as usize] {
-                    opp_reach[i] = 1.0;
-                }
-            }
-
-            let flop_bucket = flop_ip_buckets[h] as usize;
-            let turn_bucket = turn_ip_buckets[h] as usize;
-            let river_bucket = river_ip_buckets[h] as usize;
-
-            let br_val = br_traverse_flop(
-                flop_tree, Player::IP, h, flop_bucket, turn_bucket, river_bucket,
-                &opp_reach, oop_combos, ip_combos,
-                flop_oop_buckets, flop_ip_buckets,
-                turn_oop_buckets, turn_ip_buckets,
-                river_oop_buckets, river_ip_buckets,
-                oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
-                starting_pot, turn_template, river_template,
-                flop_oop_cfr, flop_ip_cfr, turn_oop_cfr, turn_ip_cfr,
-                river_oop_cfr, river_ip_cfr, &mut strat_buf, true,
-            );
-            let avg_val = br_traverse_flop(
-                flop_tree, Player::IP, h, flop_bucket, turn_bucket, river_bucket,
-                &opp_reach, oop_combos, ip_combos,
-                flop_oop_buckets, flop_ip_buckets,
-                turn_oop_buckets, turn_ip_buckets,
-                river_oop_buckets, river_ip_buckets,
-                oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
-                starting_pot, turn_template, river_template,
-                flop_oop_cfr, flop_ip_cfr, turn_oop_cfr, turn_ip_cfr,
-                river_oop_cfr, river_ip_cfr, &mut strat_buf, false,
-            );
-            ip_total_gain += br_val - avg_val;
+        for h in 0..combos[p].len() {
+            abstraction += (values.bucket[h] - values.avg[h]) / matchups as f64;
+            real += (values.combo[h] - values.avg[h]) / matchups as f64;
         }
     }
-
-    if sample_count > 0 {
-        (oop_total_gain + ip_total_gain) / (2.0 * sample_count as f64)
-    } else {
-        0.0
+    Evaluation {
+        exploitability: abstraction / 2.0,
+        real_exploitability: real / 2.0,
+        combo_evs,
     }
 }
 
-// ---------------------------------------------------------------------------
-// Best-response traversal for exploitability
-// ---------------------------------------------------------------------------
-
-#[allow(clippy::too_many_arguments)]
-fn br_traverse_flop(
+/// Vectorized best-response traversal for every combo of `br` at once.
+///
+/// `street` is 0/1/2 for the flop tree, turn template and river template;
+/// template values are multiplied by `scale`. Every street's investment is
+/// charged when play moves on to the next street.
+fn eval_traverse(
+    ctx: &EvalCtx,
     node: &TreeNode,
-    br_player: Player,
-    hand_idx: usize,
-    flop_bucket: usize,
-    turn_bucket: usize,
-    river_bucket: usize,
+    street: usize,
+    scale: f64,
+    br: Player,
+    runout: Option<&EvalRunout>,
     opp_reach: &[f64],
-    oop_combos: &[Combo],
-    ip_combos: &[Combo],
-    flop_oop_buckets: &[u16],
-    flop_ip_buckets: &[u16],
-    turn_oop_buckets: &[u16],
-    turn_ip_buckets: &[u16],
-    river_oop_buckets: &[u16],
-    river_ip_buckets: &[u16],
-    oop_scores: &[u32],
-    ip_scores: &[u32],
-    valid_ip_for_oop: &[Vec<u16>],
-    valid_oop_for_ip: &[Vec<u16>],
-    flop_pot: f64,
-    turn_template: &TreeNode,
-    river_template: &TreeNode,
-    flop_oop_cfr: &FlatCfr,
-    flop_ip_cfr: &FlatCfr,
-    turn_oop_cfr: &FlatCfr,
-    turn_ip_cfr: &FlatCfr,
-    river_oop_cfr: &FlatCfr,
-    river_ip_cfr: &FlatCfr,
-    strat_buf: &mut [f32],
-    is_br: bool,
-) -> f64 {
+) -> BrValues {
+    let p = br.index();
+    let n = ctx.valid[p].len();
+
+    // Opponent reach summed over the combos each of our combos can face
+    let reach_sums = |reach: &[f64], runout: Option<&EvalRunout>| -> Vec<f64> {
+        (0..n)
+            .map(|h| {
+                if ctx.blocked(p, h, runout) {
+                    0.0
+                } else {
+                    ctx.valid[p][h].iter().map(|&j| reach[j as usize]).sum()
+                }
+            })
+            .collect()
+    };
+
     match node {
         TreeNode::Terminal {
             terminal_type,
@@ -2050,354 +2202,92 @@ fn br_traverse_flop(
             invested,
             ..
         } => {
-            let opp_reach_sum: f64 = opp_reach.iter().sum();
-            if opp_reach_sum < 1e-10 {
-                return 0.0;
-            }
-            let my_invested = invested[br_player.index()];
+            let my_invested = invested[p] * scale;
             match terminal_type {
                 TerminalType::Fold { folder } => {
-                    if *folder == br_player {
-                        -my_invested * opp_reach_sum
+                    let payoff = if *folder == br {
+                        -my_invested
                     } else {
-                        (*pot - my_invested) * opp_reach_sum
-                    }
+                        *pot * scale - my_invested
+                    };
+                    let sums = reach_sums(opp_reach, runout);
+                    BrValues::uniform(sums.iter().map(|s| s * payoff).collect())
                 }
-                TerminalType::Showdown => {
-                    let turn_scale = *pot;
-                    let turn_val = br_traverse_turn_template(
-                        turn_template,
-                        br_player,
-                        hand_idx,
-                        turn_bucket,
-                        river_bucket,
-                        opp_reach,
-                        oop_combos,
-                        ip_combos,
-                        turn_oop_buckets,
-                        turn_ip_buckets,
-                        river_oop_buckets,
-                        river_ip_buckets,
-                        oop_scores,
-                        ip_scores,
-                        valid_ip_for_oop,
-                        valid_oop_for_ip,
-                        turn_scale,
-                        river_template,
-                        turn_oop_cfr,
-                        turn_ip_cfr,
-                        river_oop_cfr,
-                        river_ip_cfr,
-                        strat_buf,
-                        is_br,
-                    );
-                    turn_val - my_invested * opp_reach_sum
-                }
-            }
-        }
-        TreeNode::Action {
-            node_id,
-            player,
-            children,
-            actions,
-            ..
-        } => {
-            let num_actions = actions.len();
-            let nid = *node_id as usize;
-
-            if *player == br_player {
-                if is_br {
-                    let mut best = f64::NEG_INFINITY;
-                    for a in 0..num_actions {
-                        let v = br_traverse_flop(
-                            &children[a], br_player, hand_idx, flop_bucket, turn_bucket, river_bucket,
-                            opp_reach, oop_combos, ip_combos,
-                            flop_oop_buckets, flop_ip_buckets,
-                            turn_oop_buckets, turn_ip_buckets,
-                            river_oop_buckets, river_ip_buckets,
-                            oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
-                            flop_pot, turn_template, river_template,
-                            flop_oop_cfr, flop_ip_cfr, turn_oop_cfr, turn_ip_cfr,
-                            river_oop_cfr, river_ip_cfr, strat_buf, is_br,
-                        );
-                        if v > best { best = v; }
-                    }
-                    best
-                } else {
-                    let cfr = match br_player {
-                        Player::OOP => flop_oop_cfr,
-                        Player::IP => flop_ip_cfr,
-                    };
-                    cfr.average_strategy(nid, flop_bucket, strat_buf);
-                    let mut node_value = 0.0;
-                    for a in 0..num_actions {
-                        let v = br_traverse_flop(
-                            &children[a], br_player, hand_idx, flop_bucket, turn_bucket, river_bucket,
-                            opp_reach, oop_combos, ip_combos,
-                            flop_oop_buckets, flop_ip_buckets,
-                            turn_oop_buckets, turn_ip_buckets,
-                            river_oop_buckets, river_ip_buckets,
-                            oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
-                            flop_pot, turn_template, river_template,
-                            flop_oop_cfr, flop_ip_cfr, turn_oop_cfr, turn_ip_cfr,
-                            river_oop_cfr, river_ip_cfr, strat_buf, is_br,
+                TerminalType::Showdown if street == 0 => {
+                    let next_scale = *pot * scale;
+                    let (mut total, counts) = ctx
+                        .runouts
+                        .par_iter()
+                        .map(|r| {
+                            let mut reach = opp_reach.to_vec();
+                            for (j, w) in reach.iter_mut().enumerate() {
+                                if ctx.blocked(1 - p, j, Some(r)) {
+                                    *w = 0.0;
+                                }
+                            }
+                            let mut v = eval_traverse(
+                                ctx, ctx.turn_template, 1, next_scale, br, Some(r), &reach,
+                            );
+                            v.charge(my_invested, &reach_sums(&reach, Some(r)));
+                            let counts: Vec<usize> = (0..n)
+                                .map(|h| usize::from(!ctx.blocked(p, h, Some(r))))
+                                .collect();
+                            (v, counts)
+                        })
+                        .reduce(
+                            || (BrValues::zeros(n), vec![0usize; n]),
+                            |(mut a, mut ca), (b, cb)| {
+                                a.add(&b);
+                                for (x, y) in ca.iter_mut().zip(cb) {
+                                    *x += y;
+                                }
+                                (a, ca)
+                            },
                         );
-                        node_value += strat_buf[a] as f64 * v;
+                    for (h, &count) in counts.iter().enumerate() {
+                        let c = count.max(1) as f64;
+                        total.avg[h] /= c;
+                        total.combo[h] /= c;
+                        total.bucket[h] /= c;
                     }
-                    node_value
+                    total
                 }
-            } else {
-                let opp_cfr = match br_player {
-                    Player::OOP => flop_ip_cfr,
-                    Player::IP => flop_oop_cfr,
-                };
-                let opp_buckets = match br_player {
-                    Player::OOP => flop_ip_buckets,
-                    Player::IP => flop_oop_buckets,
-                };
-                let num_opp = opp_reach.len();
-                let mut node_value = 0.0;
-
-                for a in 0..num_actions {
-                    let mut new_opp_reach = vec![0.0f64; num_opp];
-                    for j in 0..num_opp {
-                        if opp_reach[j] > 0.0 {
-                            let bucket = opp_buckets[j] as usize;
-                            opp_cfr.average_strategy(nid, bucket, strat_buf);
-                            new_opp_reach[j] = opp_reach[j] * strat_buf[a] as f64;
-                        }
-                    }
-                    node_value += br_traverse_flop(
-                        &children[a], br_player, hand_idx, flop_bucket, turn_bucket, river_bucket,
-                        &new_opp_reach, oop_combos, ip_combos,
-                        flop_oop_buckets, flop_ip_buckets,
-                        turn_oop_buckets, turn_ip_buckets,
-                        river_oop_buckets, river_ip_buckets,
-                        oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
-                        flop_pot, turn_template, river_template,
-                        flop_oop_cfr, flop_ip_cfr, turn_oop_cfr, turn_ip_cfr,
-                        river_oop_cfr, river_ip_cfr, strat_buf, is_br,
+                TerminalType::Showdown if street == 1 => {
+                    let mut v = eval_traverse(
+                        ctx, ctx.river_template, 2, *pot * scale, br, runout, opp_reach,
                     );
-                }
-                node_value
-            }
-        }
-        TreeNode::Chance { .. } => unreachable!("Flop tree should not contain chance nodes"),
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-fn br_traverse_turn_template(
-    node: &TreeNode,
-    br_player: Player,
-    hand_idx: usize,
-    turn_bucket: usize,
-    river_bucket: usize,
-    opp_reach: &[f64],
-    oop_combos: &[Combo],
-    ip_combos: &[Combo],
-    turn_oop_buckets: &[u16],
-    turn_ip_buckets: &[u16],
-    river_oop_buckets: &[u16],
-    river_ip_buckets: &[u16],
-    oop_scores: &[u32],
-    ip_scores: &[u32],
-    valid_ip_for_oop: &[Vec<u16>],
-    valid_oop_for_ip: &[Vec<u16>],
-    scale: f64,
-    river_template: &TreeNode,
-    turn_oop_cfr: &FlatCfr,
-    turn_ip_cfr: &FlatCfr,
-    river_oop_cfr: &FlatCfr,
-    river_ip_cfr: &FlatCfr,
-    strat_buf: &mut [f32],
-    is_br: bool,
-) -> f64 {
-    match node {
-        TreeNode::Terminal {
-            terminal_type,
-            pot,
-            invested,
-            ..
-        } => {
-            let opp_reach_sum: f64 = opp_reach.iter().sum();
-            if opp_reach_sum < 1e-10 {
-                return 0.0;
-            }
-            match terminal_type {
-                TerminalType::Fold { folder } => {
-                    let my_invested = invested[br_player.index()] * scale;
-                    if *folder == br_player {
-                        -my_invested * opp_reach_sum
-                    } else {
-                        (*pot * scale - my_invested) * opp_reach_sum
-                    }
-                }
-                TerminalType::Showdown => {
-                    let river_scale = *pot * scale;
-                    br_traverse_river_template(
-                        river_template, br_player, hand_idx, river_bucket,
-                        opp_reach, oop_combos, ip_combos,
-                        river_oop_buckets, river_ip_buckets,
-                        oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
-                        river_scale, river_oop_cfr, river_ip_cfr, strat_buf, is_br,
-                    )
-                }
-            }
-        }
-        TreeNode::Action {
-            node_id,
-            player,
-            children,
-            actions,
-            ..
-        } => {
-            let num_actions = actions.len();
-            let nid = *node_id as usize;
-
-            if *player == br_player {
-                if is_br {
-                    let mut best = f64::NEG_INFINITY;
-                    for a in 0..num_actions {
-                        let v = br_traverse_turn_template(
-                            &children[a], br_player, hand_idx, turn_bucket, river_bucket,
-                            opp_reach, oop_combos, ip_combos,
-                            turn_oop_buckets, turn_ip_buckets,
-                            river_oop_buckets, river_ip_buckets,
-                            oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
-                            scale, river_template, turn_oop_cfr, turn_ip_cfr,
-                            river_oop_cfr, river_ip_cfr, strat_buf, is_br,
-                        );
-                        if v > best { best = v; }
-                    }
-                    best
-                } else {
-                    let cfr = match br_player {
-                        Player::OOP => turn_oop_cfr,
-                        Player::IP => turn_ip_cfr,
-                    };
-                    cfr.average_strategy(nid, turn_bucket, strat_buf);
-                    let mut nv = 0.0;
-                    for a in 0..num_actions {
-                        let v = br_traverse_turn_template(
-                            &children[a], br_player, hand_idx, turn_bucket, river_bucket,
-                            opp_reach, oop_combos, ip_combos,
-                            turn_oop_buckets, turn_ip_buckets,
-                            river_oop_buckets, river_ip_buckets,
-                            oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
-                            scale, river_template, turn_oop_cfr, turn_ip_cfr,
-                            river_oop_cfr, river_ip_cfr, strat_buf, is_br,
-                        );
-                        nv += strat_buf[a] as f64 * v;
-                    }
-                    nv
-                }
-            } else {
-                let opp_cfr = match br_player {
-                    Player::OOP => turn_ip_cfr,
-                    Player::IP => turn_oop_cfr,
-                };
-                let opp_buckets = match br_player {
-                    Player::OOP => turn_ip_buckets,
-                    Player::IP => turn_oop_buckets,
-                };
-                let num_opp = opp_reach.len();
-                let mut nv = 0.0;
-                for a in 0..num_actions {
-                    let mut new_opp_reach = vec![0.0f64; num_opp];
-                    for j in 0..num_opp {
-                        if opp_reach[j] > 0.0 {
-                            let b = opp_buckets[j] as usize;
-                            opp_cfr.average_strategy(nid, b, strat_buf);
-                            new_opp_reach[j] = opp_reach[j] * strat_buf[a] as f64;
-                        }
-                    }
-                    nv += br_traverse_turn_template(
-                        &children[a], br_player, hand_idx, turn_bucket, river_bucket,
-                        &new_opp_reach, oop_combos, ip_combos,
-                        turn_oop_buckets, turn_ip_buckets,
-                        river_oop_buckets, river_ip_buckets,
-                        oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
-                        scale, river_template, turn_oop_cfr, turn_ip_cfr,
-                        river_oop_cfr, river_ip_cfr, strat_buf, is_br,
-                    );
-                }
-                nv
-            }
-        }
-        TreeNode::Chance { .. } => unreachable!(),
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-fn br_traverse_river_template(
-    node: &TreeNode,
-    br_player: Player,
-    hand_idx: usize,
-    river_bucket: usize,
-    opp_reach: &[f64],
-    oop_combos: &[Combo],
-    ip_combos: &[Combo],
-    river_oop_buckets: &[u16],
-    river_ip_buckets: &[u16],
-    oop_scores: &[u32],
-    ip_scores: &[u32],
-    valid_ip_for_oop: &[Vec<u16>],
-    valid_oop_for_ip: &[Vec<u16>],
-    scale: f64,
-    river_oop_cfr: &FlatCfr,
-    river_ip_cfr: &FlatCfr,
-    strat_buf: &mut [f32],
-    is_br: bool,
-) -> f64 {
-    match node {
-        TreeNode::Terminal {
-            terminal_type,
-            pot,
-            invested,
-            ..
-        } => {
-            let opp_reach_sum: f64 = opp_reach.iter().sum();
-            if opp_reach_sum < 1e-10 {
-                return 0.0;
-            }
-            match terminal_type {
-                TerminalType::Fold { folder } => {
-                    let my_invested = invested[br_player.index()] * scale;
-                    if *folder == br_player {
-                        -my_invested * opp_reach_sum
-                    } else {
-                        (*pot * scale - my_invested) * opp_reach_sum
-                    }
+                    v.charge(my_invested, &reach_sums(opp_reach, runout));
+                    v
                 }
                 TerminalType::Showdown => {
+                    let r = runout.expect("river showdown needs a runout");
                     let pot_s = *pot * scale;
-                    let my_inv = invested[br_player.index()] * scale;
-                    let win = pot_s - my_inv;
-                    let lose = -my_inv;
-                    let tie = pot_s / 2.0 - my_inv;
-                    let mut value = 0.0;
-                    match br_player {
-                        Player::OOP => {
-                            let ms = oop_scores[hand_idx];
-                            for &j in &valid_ip_for_oop[hand_idx] {
-                                let j = j as usize;
-                                if opp_reach[j] < 1e-10 { continue; }
-                                let os = ip_scores[j];
-                                value += opp_reach[j] * if ms > os { win } else if ms < os { lose } else { tie };
+                    let (win, lose, tie) = (pot_s - my_invested, -my_invested, pot_s / 2.0 - my_invested);
+                    let values = (0..n)
+                        .map(|h| {
+                            if ctx.blocked(p, h, runout) {
+                                return 0.0;
                             }
-                        }
-                        Player::IP => {
-                            let ms = ip_scores[hand_idx];
-                            for &i in &valid_oop_for_ip[hand_idx] {
-                                let i = i as usize;
-                                if opp_reach[i] < 1e-10 { continue; }
-                                let os = oop_scores[i];
-                                value += opp_reach[i] * if ms > os { win } else if ms < os { lose } else { tie };
+                            let ms = r.scores[p][h];
+                            let mut value = 0.0;
+                            for &j in &ctx.valid[p][h] {
+                                let w = opp_reach[j as usize];
+                                if w < 1e-10 {
+                                    continue;
+                                }
+                                let os = r.scores[1 - p][j as usize];
+                                value += w * if ms > os {
+                                    win
+                                } else if ms < os {
+                                    lose
+                                } else {
+                                    tie
+                                };
                             }
-                        }
-                    }
-                    value
+                            value
+                        })
+                        .collect();
+                    BrValues::uniform(values)
                 }
             }
         }
@@ -2410,72 +2300,78 @@ fn br_traverse_river_template(
         } => {
             let num_actions = actions.len();
             let nid = *node_id as usize;
+            let mut strat_buf = vec![0.0f32; num_actions];
+
+            if *player == br {
+                let child_values: Vec<BrValues> = children
+                    .iter()
+                    .map(|c| eval_traverse(ctx, c, street, scale, br, runout, opp_reach))
+                    .collect();
+                let cfr = ctx.cfrs[street][p];
+                let mut out = BrValues::zeros(n);
 
-            if *player == br_player {
-                if is_br {
+                // Average strategy and per-combo best response
+                for h in 0..n {
+                    cfr.average_strategy(nid, ctx.bucket(street, p, h, runout), &mut strat_buf);
                     let mut best = f64::NEG_INFINITY;
-                    for a in 0..num_actions {
-                        let v = br_traverse_river_template(
-                            &children[a], br_player, hand_idx, river_bucket,
-                            opp_reach, oop_combos, ip_combos,
-                            river_oop_buckets, river_ip_buckets,
-                            oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
-                            scale, river_oop_cfr, river_ip_cfr, strat_buf, is_br,
-                        );
-                        if v > best { best = v; }
+                    for (a, child) in child_values.iter().enumerate() {
+                        out.avg[h] += strat_buf[a] as f64 * child.avg[h];
+                        best = best.max(child.combo[h]);
                     }
-                    best
-                } else {
-                    let cfr = match br_player {
-                        Player::OOP => river_oop_cfr,
-                        Player::IP => river_ip_cfr,
-                    };
-                    cfr.average_strategy(nid, river_bucket, strat_buf);
-                    let mut nv = 0.0;
-                    for a in 0..num_actions {
-                        let v = br_traverse_river_template(
-                            &children[a], br_player, hand_idx, river_bucket,
-                            opp_reach, oop_combos, ip_combos,
-                            river_oop_buckets, river_ip_buckets,
-                            oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
-                            scale, river_oop_cfr, river_ip_cfr, strat_buf, is_br,
-                        );
-                        nv += strat_buf[a] as f64 * v;
+                    out.combo[h] = best;
+                }
+
+                // Per-bucket best response: one action for the whole bucket
+                let bucket_of: Vec<usize> =
+                    (0..n).map(|h| ctx.bucket(street, p, h, runout)).collect();
+                let num_b = bucket_of.iter().max().map_or(0, |&b| b + 1);
+                let mut totals = vec![vec![0.0f64; num_actions]; num_b];
+                for h in 0..n {
+                    for (a, child) in child_values.iter().enumerate() {
+                        totals[bucket_of[h]][a] += child.bucket[h];
                     }
-                    nv
                 }
+                let best_action: Vec<usize> = totals
+                    .iter()
+                    .map(|t| {
+                        (0..num_actions)
+                            .max_by(|&a, &b| t[a].partial_cmp(&t[b]).unwrap())
+                            .unwrap_or(0)
+                    })
+                    .collect();
+                for h in 0..n {
+                    out.bucket[h] = child_values[best_action[bucket_of[h]]].bucket[h];
+                }
+                out
             } else {
-                let opp_cfr = match br_player {
-                    Player::OOP => river_ip_cfr,
-                    Player::IP => river_oop_cfr,
-                };
-                let opp_buckets = match br_player {
-                    Player::OOP => river_ip_buckets,
-                    Player::IP => river_oop_buckets,
-                };
-                let num_opp = opp_reach.len();
-                let mut nv = 0.0;
-                for a in 0..num_actions {
-                    let mut new_opp_reach = vec![0.0f64; num_opp];
-                    for j in 0..num_opp {
+                let opp = 1 - p;
+                let cfr = ctx.cfrs[street][opp];
+                let opp_strats: Vec<Vec<f32>> = (0..opp_reach.len())
+                    .map(|j| {
                         if opp_reach[j] > 0.0 {
-                            let b = opp_buckets[j] as usize;
-                            opp_cfr.average_strategy(nid, b, strat_buf);
-                            new_opp_reach[j] = opp_reach[j] * strat_buf[a] as f64;
+                            cfr.average_strategy(
+                                nid,
+                                ctx.bucket(street, opp, j, runout),
+                                &mut strat_buf,
+                            );
                         }
-                    }
-                    nv += br_traverse_river_template(
-                        &children[a], br_player, hand_idx, river_bucket,
-                        &new_opp_reach, oop_combos, ip_combos,
-                        river_oop_buckets, river_ip_buckets,
-                        oop_scores, ip_scores, valid_ip_for_oop, valid_oop_for_ip,
-                        scale, river_oop_cfr, river_ip_cfr, strat_buf, is_br,
-                    );
+                        strat_buf.clone()
+                    })
+                    .collect();
+
+                let mut out = BrValues::zeros(n);
+                for (a, child) in children.iter().enumerate() {
+                    let new_reach: Vec<f64> = opp_reach
+                        .iter()
+                        .zip(&opp_strats)
+                        .map(|(&w, s)| if w > 0.0 { w * s[a] as f64 } else { 0.0 })
+                        .collect();
+                    out.add(&eval_traverse(ctx, child, street, scale, br, runout, &new_reach));
                 }
-                nv
+                out
             }
         }
-        TreeNode::Chance { .. } => unreachable!(),
+        TreeNode::Chance { .. } => unreachable!("Flop solver trees have no chance nodes"),
     }
 }
 
@@ -2493,6 +2389,7 @@ fn extract_solution(
     ip_combos: &[Combo],
     flop_oop_buckets: &[u16],
     flop_ip_buckets: &[u16],
+    flop_equities: [&[f64]; 2],
     _metas: &[crate::postflop_tree::NodeMeta],
     turn_template: &TreeNode,
     river_template: &TreeNode,
@@ -2505,28 +2402,28 @@ fn extract_solution(
     valid_ip_for_oop: &[Vec<u16>],
     valid_oop_for_ip: &[Vec<u16>],
 ) -> FlopSolution {
-    // Compute exploitability
-    let exploitability = estimate_exploitability(
+    // Compute exploitability at bucket and combo granularity
+    let Evaluation {
+        exploitability,
+        real_exploitability,
+        combo_evs: [oop_combo_evs, ip_combo_evs],
+    } = evaluate_exploitability(
         flop_tree,
         turn_template,
         river_template,
-        flop_oop_cfr,
-        flop_ip_cfr,
-        turn_oop_cfr,
-        turn_ip_cfr,
-        river_oop_cfr,
-        river_ip_cfr,
-        oop_combos,
-        ip_combos,
-        oop_blockers,
-        ip_blockers,
-        flop_oop_buckets,
-        flop_ip_buckets,
-        valid_ip_for_oop,
-        valid_oop_for_ip,
+        [
+            [flop_oop_cfr, flop_ip_cfr],
+            [turn_oop_cfr, turn_ip_cfr],
+            [river_oop_cfr, river_ip_cfr],
+        ],
+        [oop_combos, ip_combos],
+        [oop_blockers, ip_blockers],
+        [flop_oop_buckets, flop_ip_buckets],
+        [valid_ip_for_oop, valid_oop_for_ip],
         &config.board,
-        config.starting_pot,
         config.num_buckets,
+        config.bucketing,
+        config.seed,
     );
 
     // Extract flop-level strategies (combo-level from bucket-level)
@@ -2583,6 +2480,9 @@ fn extract_solution(
         .map(|c| format!("{}{}", index_to_card(c.0), index_to_card(c.1)))
         .collect();
 
+    let oop_buckets = bucket_infos(flop_oop_buckets, flop_equities[0], &oop_combo_strs);
+    let ip_buckets = bucket_infos(flop_ip_buckets, flop_equities[1], &ip_combo_strs);
+
     FlopSolution {
         board: board_str,
         oop_range: config.oop_range.clone(),
@@ -2591,6 +2491,7 @@ fn extract_solution(
         effective_stack: config.effective_stack,
         iterations: config.iterations,
         exploitability,
+        real_exploitability,
         oop_combos: oop_combo_strs,
         ip_combos: ip_combo_strs,
         strategies,
@@ -2599,12 +2500,32 @@ fn extract_solution(
         turn_strategies,
         river_strategies,
         num_buckets: config.num_buckets,
+        bucketing: config.bucketing,
         flop_tree_edges,
         turn_tree_edges,
         river_tree_edges,
+        oop_buckets,
+        ip_buckets,
+        oop_combo_evs,
+        ip_combo_evs,
+        dead_money: config.dead_money,
     }
 }
 
+/// Group combo strings by flop bucket.
+fn bucket_infos(buckets: &[u16], equities: &[f64], combos: &[String]) -> Vec<FlopBucketInfo> {
+    bucket_members(buckets, equities)
+        .into_iter()
+        .enumerate()
+        .filter(|(_, (members, _))| !members.is_empty())
+        .map(|(b, (members, mean_equity))| FlopBucketInfo {
+            bucket: b as u16,
+            mean_equity,
+            combos: members.iter().map(|&i| combos[i].clone()).collect(),
+        })
+        .collect()
+}
+
 fn extract_flop_strategies(
     node: &TreeNode,
     flop_oop_cfr: &FlatCfr,
@@ -2761,6 +2682,7 @@ fn empty_solution(config: &FlopSolverConfig) -> FlopSolution {
         effective_stack: config.effective_stack,
         iterations: config.iterations,
         exploitability: 0.0,
+        real_exploitability: 0.0,
         oop_combos: vec![],
         ip_combos: vec![],
         strategies: vec![],
@@ -2769,9 +2691,15 @@ fn empty_solution(config: &FlopSolverConfig) -> FlopSolution {
         turn_strategies: vec![],
         river_strategies: vec![],
         num_buckets: 0,
+        bucketing: config.bucketing,
         flop_tree_edges: vec![],
         turn_tree_edges: vec![],
         river_tree_edges: vec![],
+        oop_buckets: vec![],
+        ip_buckets: vec![],
+        oop_combo_evs: vec![],
+        ip_combo_evs: vec![],
+        dead_money: config.dead_money,
     }
 }
 
@@ -2781,82 +2709,212 @@ fn empty_solution(config: &FlopSolverConfig) -> FlopSolution {
 
 impl FlopSolution {
     pub fn display(&self) {
+        print!("{}", self.render());
+    }
+
+    /// The human-readable report `display` prints.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = self.write_report(&mut out);
+        out
+    }
+
+    fn write_report(&self, out: &mut impl std::fmt::Write) -> std::fmt::Result {
         use colored::Colorize;
 
-        println!();
-        println!(
+        writeln!(out)?;
+        writeln!(
+            out,
             "  {} Flop Solution  |  Board: {}  |  Pot: {:.0}  |  Stack: {:.0}  |  {} iterations",
             "GTO".bold(),
             self.board,
             self.starting_pot,
             self.effective_stack,
             self.iterations,
-        );
-        println!("  Exploitability: {:.4}", self.exploitability);
-        println!(
+        )?;
+        writeln!(
+            out,
+            "  Exploitability: {:.4} abstraction | {:.4} real ({:.2}% of pot)",
+            self.exploitability,
+            self.real_exploitability,
+            self.real_exploitability / self.starting_pot * 100.0,
+        )?;
+        writeln!(
+            out,
             "  OOP range: {} ({} combos)  |  IP range: {} ({} combos)",
             self.oop_range.join(","),
             self.oop_combos.len(),
             self.ip_range.join(","),
             self.ip_combos.len(),
-        );
+        )?;
 
         if let Some(root_strat) = self.strategies.first() {
-            println!();
-            println!(
+            writeln!(out)?;
+            writeln!(
+                out,
                 "  {} at root (node {}):",
                 root_strat.player.bold(),
                 root_strat.node_id
-            );
-            println!("  Actions: {}", root_strat.actions.join(" | "));
+            )?;
+            writeln!(out, "  Actions: {}", root_strat.actions.join(" | "))?;
 
-            let num_to_show = root_strat.frequencies.len().min(20);
-            let combos = if root_strat.player == "OOP" {
-                &self.oop_combos
+            let (combos, buckets) = if root_strat.player == "OOP" {
+                (&self.oop_combos, &self.oop_buckets)
             } else {
-                &self.ip_combos
+                (&self.ip_combos, &self.ip_buckets)
             };
 
-            for i in 0..num_to_show {
-                let freq_str: String = root_strat.frequencies[i]
-                    .iter()
-                    .zip(&root_strat.actions)
-                    .map(|(f, a)| {
-                        let pct = (f * 100.0).round() as u32;
-                        if pct > 70 {
-                            format!("{}:{}", a, format!("{}%", pct).green())
-                        } else if pct > 30 {
-                            format!("{}:{}", a, format!("{}%", pct).yellow())
-                        } else {
-                            format!("{}:{}%", a, pct)
-                        }
-                    })
-                    .collect::<Vec<_>>()
-                    .join("  ");
-                println!("    {}  {}", combos[i].bold(), freq_str);
-            }
-            if root_strat.frequencies.len() > num_to_show {
-                println!(
-                    "    ... and {} more combos",
-                    root_strat.frequencies.len() - num_to_show
-                );
+            if buckets.is_empty() {
+                // Solutions cached before bucket info was recorded
+                let num_to_show = root_strat.frequencies.len().min(20);
+                for (combo, freqs) in combos.iter().zip(&root_strat.frequencies).take(num_to_show) {
+                    writeln!(
+                        out,
+                        "    {}  {}",
+                        combo.bold(),
+                        format_frequencies(&root_strat.actions, freqs)
+                    )?;
+                }
+                if root_strat.frequencies.len() > num_to_show {
+                    writeln!(
+                        out,
+                        "    ... and {} more combos",
+                        root_strat.frequencies.len() - num_to_show
+                    )?;
+                }
+            } else {
+                let mut by_equity: Vec<&FlopBucketInfo> = buckets.iter().collect();
+                by_equity.sort_by(|a, b| b.mean_equity.partial_cmp(&a.mean_equity).unwrap());
+
+                let num_to_show = by_equity.len().min(20);
+                for info in &by_equity[..num_to_show] {
+                    let Some(idx) = combos.iter().position(|c| *c == info.combos[0]) else {
+                        continue;
+                    };
+                    let mut members = info.combos[..info.combos.len().min(3)].join(", ");
+                    if info.combos.len() > 3 {
+                        members.push_str(" …");
+                    }
+                    writeln!(
+                        out,
+                        "    {} (eq {:.2}): {}  {}",
+                        format!("Bucket {}", info.bucket).bold(),
+                        info.mean_equity,
+                        members,
+                        format_frequencies(&root_strat.actions, &root_strat.frequencies[idx])
+                    )?;
+                }
+                if by_equity.len() > num_to_show {
+                    writeln!(out, "    ... and {} more buckets", by_equity.len() - num_to_show)?;
+                }
             }
         }
 
-        println!();
+        write_combo_evs(out, "OOP", &self.oop_combos, &self.oop_combo_evs)?;
+        write_combo_evs(out, "IP", &self.ip_combos, &self.ip_combo_evs)?;
+
+        writeln!(out)
+    }
+
+    /// Root strategy for a combo (e.g. "AhKh"), resolved through its flop
+    /// bucket. Returns (action, frequency) pairs, or None if the combo is not
+    /// in the acting player's range.
+    pub fn strategy_for_combo(&self, combo: &str) -> Option<Vec<(String, f64)>> {
+        let root = self.strategies.first()?;
+        let freqs = self.combo_strategy(combo, root.node_id)?;
+        Some(root.actions.iter().cloned().zip(freqs).collect())
+    }
+
+    /// Strategy of a combo (e.g. "AhKh", either card order) at flop node
+    /// `node_id`, in the node's action order. Strategies are trained per
+    /// bucket, so this is the strategy of the combo's flop bucket. None if
+    /// the node doesn't exist or the combo isn't in the acting player's
+    /// range (including combos blocked by the board).
+    pub fn combo_strategy(&self, combo: &str, node_id: u32) -> Option<Vec<f64>> {
+        let node = self.strategies.iter().find(|s| s.node_id == node_id)?;
+        let player = if node.player == "OOP" { Player::OOP } else { Player::IP };
+        let (combos, buckets) = self.player_buckets(player);
+
+        let key = combo_key(combo)?;
+        let info = buckets
+            .iter()
+            .find(|info| info.combos.iter().any(|c| combo_key(c) == Some(key)))?;
+        let idx = combos.iter().position(|c| *c == info.combos[0])?;
+        node.frequencies.get(idx).cloned()
+    }
+
+    /// Flop bucket of every combo in `player`'s range, in `oop_combos` /
+    /// `ip_combos` order. Strategies are per bucket, so combos sharing a
+    /// bucket always play the same way.
+    pub fn bucket_map(&self, player: Player) -> Vec<(String, u16)> {
+        let (combos, buckets) = self.player_buckets(player);
+        let bucket_of: HashMap<&str, u16> = buckets
+            .iter()
+            .flat_map(|info| info.combos.iter().map(move |c| (c.as_str(), info.bucket)))
+            .collect();
+        combos
+            .iter()
+            .filter_map(|c| Some((c.clone(), *bucket_of.get(c.as_str())?)))
+            .collect()
+    }
+
+    /// Mean equity vs a random hand of each of `player`'s flop buckets,
+    /// indexed by bucket id. A bucket with no members reads 0.
+    pub fn bucket_equities(&self, player: Player) -> Vec<f64> {
+        let (_, buckets) = self.player_buckets(player);
+        let len = buckets.iter().map(|info| info.bucket as usize + 1).max().unwrap_or(0);
+        let mut equities = vec![0.0; len];
+        for info in buckets {
+            equities[info.bucket as usize] = info.mean_equity;
+        }
+        equities
+    }
+
+    fn player_buckets(&self, player: Player) -> (&[String], &[FlopBucketInfo]) {
+        match player {
+            Player::OOP => (&self.oop_combos, &self.oop_buckets),
+            Player::IP => (&self.ip_combos, &self.ip_buckets),
+        }
     }
 }
 
+/// Order-independent card indices for a four-character combo string.
+fn combo_key(combo: &str) -> Option<(u8, u8)> {
+    if combo.len() != 4 || !combo.is_ascii() {
+        return None;
+    }
+    let a = card_to_index(&parse_card(&combo[..2]).ok()?);
+    let b = card_to_index(&parse_card(&combo[2..]).ok()?);
+    Some((a.min(b), a.max(b)))
+}
+
+fn format_frequencies(actions: &[String], frequencies: &[f64]) -> String {
+    use colored::Colorize;
+
+    frequencies
+        .iter()
+        .zip(actions)
+        .map(|(f, a)| {
+            let pct = (f * 100.0).round() as u32;
+            if pct > 70 {
+                format!("{}:{}", a, format!("{}%", pct).green())
+            } else if pct > 30 {
+                format!("{}:{}", a, format!("{}%", pct).yellow())
+            } else {
+                format!("{}:{}%", a, pct)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
 // ---------------------------------------------------------------------------
 // Cache
 // ---------------------------------------------------------------------------
 
 impl FlopSolution {
     pub fn cache_path(&self) -> std::path::PathBuf {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let dir = std::path::Path::new(&home).join(".gto-cli").join("solver");
-        std::fs::create_dir_all(&dir).ok();
-        dir.join(format!(
+        crate::checkpoint::solver_dir().join(format!(
             "flop_{}_{}_{}_{:.0}_{:.0}.bin",
             self.board, self.oop_pos, self.ip_pos, self.starting_pot, self.effective_stack,
         ))
@@ -2870,9 +2928,7 @@ impl FlopSolution {
     }
 
     pub fn load_cache(board: &str, oop_pos: &str, ip_pos: &str, pot: f64, stack: f64) -> Option<FlopSolution> {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let path = std::path::Path::new(&home)
-            .join(".gto-cli")
+        let path = crate::checkpoint::gto_home()
             .join("solver")
             .join(format!("flop_{}_{}_{}_{:.0}_{:.0}.bin", board, oop_pos, ip_pos, pot, stack));
         let data = std::fs::read(path).ok()?;
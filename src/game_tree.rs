@@ -4,6 +4,7 @@
 //! SB shoves all-in or folds, BB calls or folds. Produces Nash
 //! equilibrium push/call ranges for any stack depth.
 
+use once_cell::sync::OnceCell;
 use rand::Rng;
 use rand::SeedableRng;
 use rand::rngs::StdRng;
@@ -12,6 +13,8 @@ use rayon::prelude::*;
 use crate::card_encoding::{card_to_index, remaining_deck};
 use crate::cards::hand_combos;
 use crate::cfr::{CfrTrainer, InfoSetKey};
+use crate::error::{GtoError, GtoResult};
+use crate::icm::{IcmOutcomes, IcmSpot};
 use crate::lookup_eval::evaluate_fast;
 use crate::ranges::combo_count;
 
@@ -24,8 +27,8 @@ pub const GRID_RANKS: [char; 13] = [
 pub const NUM_HANDS: usize = 169;
 
 /// Node IDs in the push/fold game tree.
-const SB_NODE: u16 = 0;
-const BB_NODE: u16 = 1;
+const SB_NODE: u32 = 0;
+const BB_NODE: u32 = 1;
 
 // ---------------------------------------------------------------------------
 // Hand bucket mapping
@@ -81,6 +84,18 @@ pub fn bucket_to_hand(bucket: usize) -> String {
     }
 }
 
+/// Bucket index (0-168) of a hole-card pair in u8 card encoding.
+pub fn combo_to_bucket(c0: u8, c1: u8) -> usize {
+    // Grid rows run from the ace down
+    let (g0, g1) = (12 - (c0 >> 2) as usize, 12 - (c1 >> 2) as usize);
+    let (high, low) = (g0.min(g1), g0.max(g1));
+    if c0 & 3 == c1 & 3 {
+        high * 13 + low
+    } else {
+        low * 13 + high
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Equity and combo weight precomputation
 // ---------------------------------------------------------------------------
@@ -110,8 +125,41 @@ impl EquityTable {
 /// For each pair of canonical hands, enumerates non-conflicting combo pairs
 /// and runs `mc_samples` random board runouts to estimate showdown equity.
 pub fn precompute_equity_table(mc_samples: usize) -> EquityTable {
-    // Generate all combos for each canonical hand as u8 card indices.
-    let hand_combos_list: Vec<Vec<[u8; 2]>> = (0..NUM_HANDS)
+    let hand_combos_list = canonical_combos();
+
+    // Compute each row in parallel using rayon.
+    let rows: Vec<(Vec<f64>, Vec<f64>)> = (0..NUM_HANDS)
+        .into_par_iter()
+        .map(|i| {
+            let mut eq_row = vec![0.5f64; NUM_HANDS];
+            let mut combo_row = vec![0.0f64; NUM_HANDS];
+            let mut rng = StdRng::seed_from_u64(i as u64);
+
+            for j in 0..NUM_HANDS {
+                let valid_pairs = disjoint_pairs(&hand_combos_list[i], &hand_combos_list[j]);
+                combo_row[j] = valid_pairs.len() as f64;
+                eq_row[j] = sample_equity(&valid_pairs, mc_samples, &mut rng);
+            }
+
+            (eq_row, combo_row)
+        })
+        .collect();
+
+    // Flatten into contiguous arrays.
+    let mut equity = vec![0.0f64; NUM_HANDS * NUM_HANDS];
+    let mut combos = vec![0.0f64; NUM_HANDS * NUM_HANDS];
+
+    for (i, (eq_row, combo_row)) in rows.into_iter().enumerate() {
+        equity[i * NUM_HANDS..(i + 1) * NUM_HANDS].copy_from_slice(&eq_row);
+        combos[i * NUM_HANDS..(i + 1) * NUM_HANDS].copy_from_slice(&combo_row);
+    }
+
+    EquityTable { equity, combos }
+}
+
+/// Every combo of each canonical hand as u8 card indices, by bucket.
+fn canonical_combos() -> Vec<Vec<[u8; 2]>> {
+    (0..NUM_HANDS)
         .map(|bucket| {
             let notation = bucket_to_hand(bucket);
             hand_combos(&notation)
@@ -120,91 +168,179 @@ pub fn precompute_equity_table(mc_samples: usize) -> EquityTable {
                 .map(|(c1, c2)| [card_to_index(c1), card_to_index(c2)])
                 .collect()
         })
-        .collect();
+        .collect()
+}
 
-    // Compute each row in parallel using rayon.
-    let rows: Vec<(Vec<f64>, Vec<f64>)> = (0..NUM_HANDS)
-        .into_par_iter()
-        .map(|i| {
-            let mut eq_row = vec![0.5f64; NUM_HANDS];
-            let mut combo_row = vec![0.0f64; NUM_HANDS];
-            let mut rng = StdRng::seed_from_u64(i as u64);
+/// All combo pairs of two canonical hands that share no card.
+fn disjoint_pairs(combos_i: &[[u8; 2]], combos_j: &[[u8; 2]]) -> Vec<[u8; 4]> {
+    let mut valid_pairs = Vec::new();
+    for ci in combos_i {
+        for cj in combos_j {
+            if ci[0] != cj[0] && ci[0] != cj[1] && ci[1] != cj[0] && ci[1] != cj[1] {
+                valid_pairs.push([ci[0], ci[1], cj[0], cj[1]]);
+            }
+        }
+    }
+    valid_pairs
+}
 
-            let combos_i = &hand_combos_list[i];
+/// Monte Carlo equity of the first hand of each pair over `samples` random
+/// runouts, ties split. 0.5 when no pair is possible.
+fn sample_equity(valid_pairs: &[[u8; 4]], samples: usize, rng: &mut StdRng) -> f64 {
+    if valid_pairs.is_empty() {
+        return 0.5;
+    }
 
-            for j in 0..NUM_HANDS {
-                let combos_j = &hand_combos_list[j];
-
-                // Find all non-conflicting combo pairs.
-                let mut valid_pairs: Vec<[u8; 4]> = Vec::new();
-                for ci in combos_i {
-                    for cj in combos_j {
-                        if ci[0] != cj[0]
-                            && ci[0] != cj[1]
-                            && ci[1] != cj[0]
-                            && ci[1] != cj[1]
-                        {
-                            valid_pairs.push([ci[0], ci[1], cj[0], cj[1]]);
-                        }
-                    }
-                }
+    let mut wins = 0u32;
+    let mut ties = 0u32;
 
-                combo_row[j] = valid_pairs.len() as f64;
+    for _ in 0..samples {
+        let pair = valid_pairs[rng.gen_range(0..valid_pairs.len())];
 
-                if valid_pairs.is_empty() {
-                    eq_row[j] = 0.5;
-                    continue;
-                }
+        let mut deck = remaining_deck(&pair);
+        // Shuffle first 5 elements (partial Fisher-Yates).
+        for k in 0..5 {
+            let swap = rng.gen_range(k..deck.len());
+            deck.swap(k, swap);
+        }
 
-                // Monte Carlo equity estimation.
-                let mut wins = 0u32;
-                let mut ties = 0u32;
-                let total = mc_samples as u32;
+        let h1 = [pair[0], pair[1], deck[0], deck[1], deck[2], deck[3], deck[4]];
+        let h2 = [pair[2], pair[3], deck[0], deck[1], deck[2], deck[3], deck[4]];
 
-                for _ in 0..mc_samples {
-                    let pair = valid_pairs[rng.gen_range(0..valid_pairs.len())];
+        let s1 = evaluate_fast(&h1);
+        let s2 = evaluate_fast(&h2);
 
-                    let mut deck = remaining_deck(&pair);
-                    // Shuffle first 5 elements (partial Fisher-Yates).
-                    for k in 0..5 {
-                        let swap = rng.gen_range(k..deck.len());
-                        deck.swap(k, swap);
-                    }
+        if s1 > s2 {
+            wins += 1;
+        } else if s1 == s2 {
+            ties += 1;
+        }
+    }
 
-                    let h1 = [
-                        pair[0], pair[1], deck[0], deck[1], deck[2], deck[3], deck[4],
-                    ];
-                    let h2 = [
-                        pair[2], pair[3], deck[0], deck[1], deck[2], deck[3], deck[4],
-                    ];
+    (wins as f64 + 0.5 * ties as f64) / samples as f64
+}
 
-                    let s1 = evaluate_fast(&h1);
-                    let s2 = evaluate_fast(&h2);
+// ---------------------------------------------------------------------------
+// Cached all-in equity matrix
+// ---------------------------------------------------------------------------
 
-                    if s1 > s2 {
-                        wins += 1;
-                    } else if s1 == s2 {
-                        ties += 1;
-                    }
-                }
+/// All-in equity of every canonical hand against every other,
+/// `m[i][j]` = equity of bucket `i` vs bucket `j` with ties split.
+pub type EquityMatrix = [[f64; NUM_HANDS]; NUM_HANDS];
+
+/// Runouts sampled per matchup for the cached matrix (about ±0.4% error).
+pub const MATRIX_SAMPLES: usize = 10_000;
+
+/// Format of `~/.gto-cli/preflop_equity.bin`. Bump it whenever the
+/// generator changes so stale caches are rebuilt rather than trusted.
+const MATRIX_VERSION: u32 = 1;
+
+static MATRIX: OnceCell<Box<EquityMatrix>> = OnceCell::new();
+
+/// The 169x169 all-in equity matrix, loaded from
+/// `~/.gto-cli/preflop_equity.bin` or built with `MATRIX_SAMPLES` runouts
+/// per matchup and cached there on first use. Loaded once per process.
+pub fn preflop_equity_matrix() -> &'static EquityMatrix {
+    MATRIX.get_or_init(|| {
+        let path = matrix_cache_path();
+        let cached = std::fs::read(&path)
+            .ok()
+            .and_then(|data| bincode::deserialize::<(u32, usize, Vec<f64>)>(&data).ok());
+        if let Some((version, samples, flat)) = cached {
+            if version == MATRIX_VERSION && samples == MATRIX_SAMPLES && flat.len() == NUM_HANDS * NUM_HANDS {
+                return unflatten_matrix(&flat);
+            }
+        }
 
-                eq_row[j] = (wins as f64 + 0.5 * ties as f64) / total as f64;
+        let matrix = Box::new(build_preflop_equity_matrix(MATRIX_SAMPLES));
+        let flat: Vec<f64> = matrix.iter().flatten().copied().collect();
+        if let Ok(data) = bincode::serialize(&(MATRIX_VERSION, MATRIX_SAMPLES, flat)) {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).ok();
             }
+            if std::fs::write(&path, data).is_ok() {
+                // Superseded by this file; nothing reads it anymore
+                std::fs::remove_file(crate::checkpoint::gto_home().join("preflop_matrix.bin")).ok();
+            }
+        }
+        matrix
+    })
+}
 
-            (eq_row, combo_row)
+/// All-in equity of one canonical hand against another ("AKs", "QQ",
+/// "72o") from the cached matrix, ties split.
+pub fn preflop_equity(hand: &str, villain: &str) -> GtoResult<f64> {
+    let bucket = |h: &str| hand_to_bucket(h).ok_or_else(|| GtoError::InvalidHandNotation(h.to_string()));
+    let (i, j) = (bucket(hand)?, bucket(villain)?);
+    Ok(preflop_equity_matrix()[i][j])
+}
+
+/// Equity table over the cached matrix, what the CLI hands every preflop
+/// solve. Tests build a cheaper table with `build_preflop_equity_matrix`.
+pub fn preflop_equity_table() -> &'static EquityTable {
+    static TABLE: OnceCell<EquityTable> = OnceCell::new();
+    TABLE.get_or_init(|| EquityTable::from_matrix(preflop_equity_matrix()))
+}
+
+/// Build the equity matrix with `samples` runouts per matchup, drawing the
+/// combo pair of each runout from those the two hands can hold together.
+/// Each matchup is sampled once and mirrored, so `m[i][j] + m[j][i] == 1`.
+pub fn build_preflop_equity_matrix(samples: usize) -> EquityMatrix {
+    let hand_combos_list = canonical_combos();
+
+    let rows: Vec<Vec<f64>> = (0..NUM_HANDS)
+        .into_par_iter()
+        .map(|i| {
+            let mut rng = StdRng::seed_from_u64(i as u64);
+            (0..NUM_HANDS)
+                .map(|j| {
+                    if j <= i {
+                        return 0.5;
+                    }
+                    let valid_pairs = disjoint_pairs(&hand_combos_list[i], &hand_combos_list[j]);
+                    sample_equity(&valid_pairs, samples, &mut rng)
+                })
+                .collect()
         })
         .collect();
 
-    // Flatten into contiguous arrays.
-    let mut equity = vec![0.0f64; NUM_HANDS * NUM_HANDS];
-    let mut combos = vec![0.0f64; NUM_HANDS * NUM_HANDS];
+    let mut matrix = [[0.5f64; NUM_HANDS]; NUM_HANDS];
+    for (i, row) in rows.iter().enumerate() {
+        for j in i + 1..NUM_HANDS {
+            matrix[i][j] = row[j];
+            matrix[j][i] = 1.0 - row[j];
+        }
+    }
+    matrix
+}
 
-    for (i, (eq_row, combo_row)) in rows.into_iter().enumerate() {
-        equity[i * NUM_HANDS..(i + 1) * NUM_HANDS].copy_from_slice(&eq_row);
-        combos[i * NUM_HANDS..(i + 1) * NUM_HANDS].copy_from_slice(&combo_row);
+impl EquityTable {
+    /// Equity table backed by a precomputed matrix, with exact combo weights.
+    pub fn from_matrix(matrix: &EquityMatrix) -> Self {
+        let hand_combos_list = canonical_combos();
+        let mut combos = Vec::with_capacity(NUM_HANDS * NUM_HANDS);
+        for combos_i in &hand_combos_list {
+            for combos_j in &hand_combos_list {
+                combos.push(disjoint_pairs(combos_i, combos_j).len() as f64);
+            }
+        }
+        EquityTable {
+            equity: matrix.iter().flatten().copied().collect(),
+            combos,
+        }
     }
+}
 
-    EquityTable { equity, combos }
+fn unflatten_matrix(flat: &[f64]) -> Box<EquityMatrix> {
+    let mut matrix = Box::new([[0.0f64; NUM_HANDS]; NUM_HANDS]);
+    for (row, chunk) in matrix.iter_mut().zip(flat.chunks(NUM_HANDS)) {
+        row.copy_from_slice(chunk);
+    }
+    matrix
+}
+
+fn matrix_cache_path() -> std::path::PathBuf {
+    crate::checkpoint::gto_home().join("preflop_equity.bin")
 }
 
 // ---------------------------------------------------------------------------
@@ -218,9 +354,12 @@ pub fn precompute_equity_table(mc_samples: usize) -> EquityTable {
 /// - SB folds: SB = -0.5, BB = +0.5
 /// - SB pushes, BB folds: SB = +1.0, BB = -1.0
 /// - SB pushes, BB calls: showdown for 2*stack pot (minus rake)
+///
+/// With `icm` set, every terminal pays the change in ICM equity instead.
 pub struct PushFoldPayoffs {
     pub stack_bb: f64,
     pub rake: f64, // as fraction (0.0 - 1.0)
+    pub icm: Option<IcmOutcomes>,
 }
 
 impl PushFoldPayoffs {
@@ -228,39 +367,55 @@ impl PushFoldPayoffs {
         PushFoldPayoffs {
             stack_bb,
             rake: rake_pct / 100.0,
+            icm: None,
+        }
+    }
+
+    /// $EV payoffs for a tournament spot. Tournaments take no rake.
+    pub fn icm(spot: &IcmSpot) -> Self {
+        PushFoldPayoffs {
+            stack_bb: spot.effective_stack(),
+            rake: 0.0,
+            icm: Some(spot.push_fold_outcomes()),
         }
     }
 
     /// SB folds: loses small blind.
     #[inline]
     pub fn sb_fold(&self) -> f64 {
-        -0.5
+        self.icm.map_or(-0.5, |o| o.sb_folds[0])
     }
 
     /// SB pushes, BB folds: SB wins BB's blind.
     #[inline]
     pub fn sb_push_bb_fold(&self) -> f64 {
-        1.0
+        self.icm.map_or(1.0, |o| o.bb_folds[0])
     }
 
     /// BB folds vs push: loses big blind.
     #[inline]
     pub fn bb_fold(&self) -> f64 {
-        -1.0
+        self.icm.map_or(-1.0, |o| o.bb_folds[1])
     }
 
     /// SB's payoff at showdown given SB's equity.
     /// payoff = stack * (2 * equity * (1 - rake) - 1)
     #[inline]
     pub fn sb_showdown(&self, sb_equity: f64) -> f64 {
-        self.stack_bb * (2.0 * sb_equity * (1.0 - self.rake) - 1.0)
+        match self.icm {
+            Some(o) => sb_equity * o.sb_wins[0] + (1.0 - sb_equity) * o.sb_loses[0],
+            None => self.stack_bb * (2.0 * sb_equity * (1.0 - self.rake) - 1.0),
+        }
     }
 
     /// BB's payoff at showdown given SB's equity.
     /// payoff = stack * (2 * (1 - sb_equity) * (1 - rake) - 1)
     #[inline]
     pub fn bb_showdown(&self, sb_equity: f64) -> f64 {
-        self.stack_bb * (2.0 * (1.0 - sb_equity) * (1.0 - self.rake) - 1.0)
+        match self.icm {
+            Some(o) => sb_equity * o.sb_wins[1] + (1.0 - sb_equity) * o.sb_loses[1],
+            None => self.stack_bb * (2.0 * (1.0 - sb_equity) * (1.0 - self.rake) - 1.0),
+        }
     }
 }
 
@@ -275,12 +430,15 @@ pub struct PushFoldResult {
     pub push_strategy: Vec<f64>,
     /// Call probability for each BB hand bucket (0-168).
     pub call_strategy: Vec<f64>,
-    /// Exploitability in bb per hand (0 = Nash equilibrium).
+    /// Exploitability in bb per hand (0 = Nash equilibrium), or in payout
+    /// units when `icm` is set.
     pub exploitability: f64,
     /// Number of CFR iterations run.
     pub iterations: usize,
     /// Effective stack in bb.
     pub stack_bb: f64,
+    /// Solved in tournament $EV, so exploitability is in payout units.
+    pub icm: bool,
 }
 
 impl PushFoldResult {
@@ -325,11 +483,12 @@ impl PushFoldResult {
 
         println!();
         println!(
-            "  {} Push/Fold Solution  |  Stack: {}bb  |  {} iterations  |  Exploitability: {:.4} bb",
+            "  {} Push/Fold Solution  |  Stack: {}bb  |  {} iterations  |  Exploitability: {:.4} {}",
             "GTO".bold(),
             self.stack_bb,
             self.iterations,
             self.exploitability,
+            if self.icm { "$EV (ICM)" } else { "bb" },
         );
 
         // SB push range
@@ -367,52 +526,57 @@ impl PushFoldResult {
 /// Solve the push/fold game for a given stack depth using CFR+.
 ///
 /// Returns Nash equilibrium push/call ranges.
-pub fn solve_push_fold(stack_bb: f64, iterations: usize, rake_pct: f64) -> PushFoldResult {
-    let payoffs = PushFoldPayoffs::new(stack_bb, rake_pct);
+pub fn solve_push_fold(stack_bb: f64, iterations: usize, rake_pct: f64, table: &EquityTable) -> PushFoldResult {
+    solve_with_payoffs(&PushFoldPayoffs::new(stack_bb, rake_pct), iterations, table)
+}
 
-    // Step 1: Precompute equity table (the expensive part).
-    let table = precompute_equity_table(2000);
+/// Solve a tournament push/fold spot, with terminals valued by the change
+/// in each blind's ICM equity rather than chips.
+pub fn solve_push_fold_icm(spot: &IcmSpot, iterations: usize, table: &EquityTable) -> PushFoldResult {
+    solve_with_payoffs(&PushFoldPayoffs::icm(spot), iterations, table)
+}
 
-    // Step 2: Run CFR+ iterations.
+fn solve_with_payoffs(payoffs: &PushFoldPayoffs, iterations: usize, table: &EquityTable) -> PushFoldResult {
+    // Step 1: Run CFR+ iterations.
     let mut trainer = CfrTrainer::new();
 
     // Pre-create all info sets.
     for h in 0..NUM_HANDS {
         trainer.get_or_create(
-            &InfoSetKey { hand_bucket: h as u16, node_id: SB_NODE },
+            &InfoSetKey { hand_bucket: h as u16, node_id: SB_NODE, position: 0 },
             2,
         );
         trainer.get_or_create(
-            &InfoSetKey { hand_bucket: h as u16, node_id: BB_NODE },
+            &InfoSetKey { hand_bucket: h as u16, node_id: BB_NODE, position: 0 },
             2,
         );
     }
 
-    for _ in 0..iterations {
-        cfr_iteration(&mut trainer, &table, &payoffs);
+    for iter in 0..iterations {
+        cfr_iteration(&mut trainer, table, payoffs, iter + 1);
     }
 
-    // Step 3: Extract average strategies.
+    // Step 2: Extract average strategies.
     let push_strategy: Vec<f64> = (0..NUM_HANDS)
         .map(|h| {
-            let key = InfoSetKey { hand_bucket: h as u16, node_id: SB_NODE };
+            let key = InfoSetKey { hand_bucket: h as u16, node_id: SB_NODE, position: 0 };
             trainer.get_average_strategy(&key, 2)[0]
         })
         .collect();
 
     let call_strategy: Vec<f64> = (0..NUM_HANDS)
         .map(|h| {
-            let key = InfoSetKey { hand_bucket: h as u16, node_id: BB_NODE };
+            let key = InfoSetKey { hand_bucket: h as u16, node_id: BB_NODE, position: 0 };
             trainer.get_average_strategy(&key, 2)[0]
         })
         .collect();
 
-    // Step 4: Compute exploitability.
+    // Step 3: Compute exploitability.
     let exploitability = compute_exploitability(
         &push_strategy,
         &call_strategy,
-        &table,
-        &payoffs,
+        table,
+        payoffs,
     );
 
     PushFoldResult {
@@ -420,17 +584,24 @@ pub fn solve_push_fold(stack_bb: f64, iterations: usize, rake_pct: f64) -> PushF
         call_strategy,
         exploitability,
         iterations,
-        stack_bb,
+        stack_bb: payoffs.stack_bb,
+        icm: payoffs.icm.is_some(),
     }
 }
 
-/// Run one CFR+ iteration: update all SB and BB info sets.
-fn cfr_iteration(trainer: &mut CfrTrainer, table: &EquityTable, payoffs: &PushFoldPayoffs) {
+/// Run one CFR+ iteration (1-based `iteration`): update all SB and BB info
+/// sets.
+fn cfr_iteration(
+    trainer: &mut CfrTrainer,
+    table: &EquityTable,
+    payoffs: &PushFoldPayoffs,
+    iteration: usize,
+) {
     // Snapshot current strategies to avoid borrow conflicts.
     let bb_strats: Vec<[f64; 2]> = (0..NUM_HANDS)
         .map(|h| {
             let s = trainer.get_strategy(
-                &InfoSetKey { hand_bucket: h as u16, node_id: BB_NODE },
+                &InfoSetKey { hand_bucket: h as u16, node_id: BB_NODE, position: 0 },
                 2,
             );
             [s[0], s[1]]
@@ -439,7 +610,7 @@ fn cfr_iteration(trainer: &mut CfrTrainer, table: &EquityTable, payoffs: &PushFo
 
     // --- Update SB info sets ---
     for sb in 0..NUM_HANDS {
-        let sb_key = InfoSetKey { hand_bucket: sb as u16, node_id: SB_NODE };
+        let sb_key = InfoSetKey { hand_bucket: sb as u16, node_id: SB_NODE, position: 0 };
         let sb_strat = trainer.get_strategy(&sb_key, 2);
 
         let mut push_value = 0.0;
@@ -469,15 +640,14 @@ fn cfr_iteration(trainer: &mut CfrTrainer, table: &EquityTable, payoffs: &PushFo
 
         let node_value = sb_strat[0] * push_value + sb_strat[1] * fold_value;
 
-        let data = trainer.get_or_create(&sb_key, 2);
-        data.update(&[push_value, fold_value], node_value, 1.0);
+        trainer.update(&sb_key, &[push_value, fold_value], node_value, 1.0, iteration);
     }
 
     // Snapshot SB strategies for BB update.
     let sb_strats: Vec<[f64; 2]> = (0..NUM_HANDS)
         .map(|h| {
             let s = trainer.get_strategy(
-                &InfoSetKey { hand_bucket: h as u16, node_id: SB_NODE },
+                &InfoSetKey { hand_bucket: h as u16, node_id: SB_NODE, position: 0 },
                 2,
             );
             [s[0], s[1]]
@@ -486,7 +656,7 @@ fn cfr_iteration(trainer: &mut CfrTrainer, table: &EquityTable, payoffs: &PushFo
 
     // --- Update BB info sets ---
     for bb in 0..NUM_HANDS {
-        let bb_key = InfoSetKey { hand_bucket: bb as u16, node_id: BB_NODE };
+        let bb_key = InfoSetKey { hand_bucket: bb as u16, node_id: BB_NODE, position: 0 };
         let bb_strat = trainer.get_strategy(&bb_key, 2);
 
         let mut call_value = 0.0;
@@ -516,8 +686,7 @@ fn cfr_iteration(trainer: &mut CfrTrainer, table: &EquityTable, payoffs: &PushFo
 
         let node_value = bb_strat[0] * call_value + bb_strat[1] * fold_value;
 
-        let data = trainer.get_or_create(&bb_key, 2);
-        data.update(&[call_value, fold_value], node_value, 1.0);
+        trainer.update(&bb_key, &[call_value, fold_value], node_value, 1.0, iteration);
     }
 }
 
@@ -612,6 +781,12 @@ fn compute_exploitability(
 mod tests {
     use super::*;
 
+    /// A coarse matrix, so the solver tests never build or read the cached one.
+    fn test_table() -> &'static EquityTable {
+        static TABLE: OnceCell<EquityTable> = OnceCell::new();
+        TABLE.get_or_init(|| EquityTable::from_matrix(&build_preflop_equity_matrix(500)))
+    }
+
     #[test]
     fn bucket_roundtrip() {
         for i in 0..169 {
@@ -632,6 +807,16 @@ mod tests {
         assert_eq!(hand_to_bucket("32s"), Some(155));
     }
 
+    #[test]
+    fn combo_buckets_match_notation() {
+        use crate::cards::parse_board;
+        use crate::card_encoding::cards_to_indices;
+        for (cards, hand) in [("AsAh", "AA"), ("KdAd", "AKs"), ("Ah7c", "A7o"), ("2c3d", "32o"), ("2h2d", "22")] {
+            let c = cards_to_indices(&parse_board(cards).unwrap());
+            assert_eq!(bucket_to_hand(combo_to_bucket(c[0], c[1])), hand);
+        }
+    }
+
     #[test]
     fn bucket_to_hand_diagonal() {
         assert_eq!(bucket_to_hand(0), "AA");
@@ -701,6 +886,20 @@ mod tests {
         assert!(avg > 0.7, "AA average equity {} should be > 0.7", avg);
     }
 
+    #[test]
+    fn matrix_is_complementary() {
+        let m = build_preflop_equity_matrix(20);
+        let (aa, kk) = (hand_to_bucket("AA").unwrap(), hand_to_bucket("KK").unwrap());
+        assert!((m[aa][kk] + m[kk][aa] - 1.0).abs() < 1e-12);
+        assert_eq!(m[aa][aa], 0.5);
+    }
+
+    #[test]
+    fn preflop_equity_rejects_bad_notation() {
+        assert!(preflop_equity("AKx", "QQ").is_err());
+        assert!(preflop_equity("QQ", "").is_err());
+    }
+
     #[test]
     fn payoffs_zero_sum_no_rake() {
         let p = PushFoldPayoffs::new(10.0, 0.0);
@@ -722,7 +921,7 @@ mod tests {
     #[test]
     fn solver_converges() {
         // Run solver at 10bb with low iterations to verify convergence direction.
-        let result = solve_push_fold(10.0, 1000, 0.0);
+        let result = solve_push_fold(10.0, 1000, 0.0, test_table());
 
         // Exploitability should be small after 1000 iterations.
         assert!(
@@ -755,7 +954,7 @@ mod tests {
 
     #[test]
     fn solver_strategies_valid() {
-        let result = solve_push_fold(10.0, 500, 0.0);
+        let result = solve_push_fold(10.0, 500, 0.0, test_table());
 
         // All strategies should be valid probabilities.
         for i in 0..NUM_HANDS {
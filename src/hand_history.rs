@@ -0,0 +1,502 @@
+//! PokerStars hand history parsing, to set up a river solve from a real hand.
+//!
+//! Reads the common cash-game format line by line, tracking each player's
+//! stack and the pot through the blinds, bets and uncalled-bet refunds, and
+//! snapshots both when the river is dealt. Parsing is strict: a line it
+//! doesn't recognize is an error naming the line, rather than a guess that
+//! silently skews the pot.
+
+use crate::cards::{parse_board, Card};
+use crate::error::{GtoError, GtoResult};
+
+/// A player who saw the river.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandPlayer {
+    pub name: String,
+    pub seat: u32,
+    /// Table position ("SB", "BB", "UTG", ..., "CO", "BTN").
+    pub position: &'static str,
+    /// Chips behind when the river was dealt.
+    pub stack: f64,
+}
+
+/// The river spot of a hand: board, pot and the two players left.
+/// Amounts are in the hand's currency; divide by `big_blind` for bb.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandSpot {
+    /// All five board cards.
+    pub board: Vec<Card>,
+    /// Pot when the river was dealt, the starting pot of a river solve.
+    pub pot: f64,
+    /// Final pot from the summary, after river action and before rake.
+    pub total_pot: Option<f64>,
+    pub big_blind: f64,
+    /// The two players who saw the river, out of position first.
+    pub players: [HandPlayer; 2],
+    /// The player whose hole cards were dealt face up to the history's owner.
+    pub hero: Option<String>,
+}
+
+impl HandSpot {
+    /// Smaller of the two stacks behind on the river.
+    pub fn effective_stack(&self) -> f64 {
+        self.players[0].stack.min(self.players[1].stack)
+    }
+
+    /// Board in solver notation, e.g. "Ks9d4c7hQc".
+    pub fn board_notation(&self) -> String {
+        self.board.iter().map(|c| c.to_string()).collect()
+    }
+}
+
+struct Seat {
+    seat: u32,
+    name: String,
+    stack: f64,
+    in_hand: bool,
+    folded: bool,
+    /// Chips put in on the current street, for "raises X to Y".
+    street: f64,
+}
+
+/// The pot and stacks at the moment the river was dealt.
+struct RiverSnapshot {
+    pot: f64,
+    stacks: Vec<f64>,
+    live: Vec<bool>,
+}
+
+/// Lines about players that carry no chips: chat and table traffic.
+const INFO_SUFFIXES: &[&str] = &[
+    " is sitting out",
+    " has timed out",
+    " is disconnected",
+    " is connected",
+    " has returned",
+    " joins the table",
+    " leaves the table",
+    " will be allowed to play after the button",
+    " was removed from the table for failing to post",
+];
+
+/// Parse the first hand of a PokerStars Hold'em hand history into its
+/// river spot. Errors if the text isn't a PokerStars Hold'em hand, if a
+/// line isn't recognized, or if the hand didn't reach the river with
+/// exactly two players.
+pub fn parse_pokerstars(text: &str) -> GtoResult<HandSpot> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .skip_while(|(_, l)| l.is_empty());
+
+    let (header_line, header) = lines
+        .next()
+        .ok_or_else(|| hh_error(1, "empty hand history"))?;
+    if !header.starts_with("PokerStars") || !header.contains("Hold'em") {
+        return Err(hh_error(header_line, "not a PokerStars Hold'em hand"));
+    }
+    let big_blind = parse_stakes(header)
+        .ok_or_else(|| hh_error(header_line, &format!("no stakes in header: {}", header)))?;
+
+    let mut seats: Vec<Seat> = Vec::new();
+    let mut button = None;
+    let mut board: Vec<Card> = Vec::new();
+    let mut pot = 0.0;
+    let mut river: Option<RiverSnapshot> = None;
+    let mut total_pot = None;
+    let mut hero = None;
+    let mut dealt = false;
+    let mut in_summary = false;
+
+    for (n, line) in lines {
+        if line.is_empty() {
+            // A blank line after the summary ends the hand
+            if in_summary {
+                break;
+            }
+            continue;
+        }
+        if in_summary {
+            if let Some(rest) = line.strip_prefix("Total pot ") {
+                let amount = rest.split(['|', ' ']).next().unwrap_or("");
+                total_pot = Some(amount_at(n, amount)?);
+            }
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("*** ") {
+            let (street, cards) = rest.split_once(" ***").unwrap_or((rest, ""));
+            match street {
+                "HOLE CARDS" => dealt = true,
+                "FLOP" | "TURN" | "RIVER" => {
+                    board = parse_board(&cards.replace(['[', ']'], ""))
+                        .map_err(|e| hh_error(n, &e.to_string()))?;
+                    for s in &mut seats {
+                        s.street = 0.0;
+                    }
+                    if street == "RIVER" {
+                        river = Some(RiverSnapshot {
+                            pot,
+                            stacks: seats.iter().map(|s| s.stack).collect(),
+                            live: seats.iter().map(|s| s.in_hand && !s.folded).collect(),
+                        });
+                    }
+                }
+                "SHOW DOWN" => {}
+                "SUMMARY" => in_summary = true,
+                _ => return Err(hh_error(n, &format!("unrecognized street: {}", line))),
+            }
+            continue;
+        }
+
+        if line.starts_with("Table '") {
+            let seat = line
+                .split_once("Seat #")
+                .and_then(|(_, rest)| rest.split_whitespace().next())
+                .and_then(|s| s.parse::<u32>().ok());
+            button = Some(seat.ok_or_else(|| hh_error(n, "no button seat on table line"))?);
+            continue;
+        }
+
+        if !dealt {
+            if let Some(seat) = parse_seat_line(line) {
+                let (seat, name, stack, sitting_out) = seat.map_err(|m| hh_error(n, &m))?;
+                seats.push(Seat { seat, name, stack, in_hand: !sitting_out, folded: false, street: 0.0 });
+                continue;
+            }
+        }
+
+        if let Some(rest) = line.strip_prefix("Dealt to ") {
+            let name = rest.split_once(" [").map_or(rest, |(name, _)| name);
+            hero = Some(name.to_string());
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("Uncalled bet (") {
+            let (amount, name) = rest
+                .split_once(") returned to ")
+                .ok_or_else(|| hh_error(n, &format!("unrecognized line: {}", line)))?;
+            let amount = amount_at(n, amount)?;
+            let i = seat_named(&seats, name).ok_or_else(|| hh_error(n, &format!("unknown player: {}", name)))?;
+            seats[i].stack += amount;
+            seats[i].street -= amount;
+            pot -= amount;
+            continue;
+        }
+
+        if line.contains(" said, \"") || INFO_SUFFIXES.iter().any(|s| line.contains(s)) {
+            continue;
+        }
+
+        // "<name> collected $x from pot": the winner, not needed for the spot
+        if line.contains(" collected ") && line.contains(" from ") && player_prefix(&seats, line, " ").is_some() {
+            continue;
+        }
+
+        let (i, action) = player_prefix(&seats, line, ": ")
+            .ok_or_else(|| hh_error(n, &format!("unrecognized line: {}", line)))?;
+        let action = action.trim_end_matches(" and is all-in");
+        let seat = &mut seats[i];
+        let paid = if let Some(amount) = action.strip_prefix("posts small blind ") {
+            let amount = amount_at(n, amount)?;
+            seat.street += amount;
+            amount
+        } else if let Some(amount) = action.strip_prefix("posts big blind ") {
+            let amount = amount_at(n, amount)?;
+            seat.street += amount;
+            amount
+        } else if let Some(amount) = action.strip_prefix("posts small & big blinds ") {
+            // The small blind part is dead
+            seat.street += big_blind;
+            amount_at(n, amount)?
+        } else if let Some(amount) = action.strip_prefix("posts the ante ") {
+            amount_at(n, amount)?
+        } else if let Some(amount) = action.strip_prefix("calls ").or_else(|| action.strip_prefix("bets ")) {
+            let amount = amount_at(n, amount)?;
+            seat.street += amount;
+            amount
+        } else if let Some(rest) = action.strip_prefix("raises ") {
+            let to = rest
+                .split_once(" to ")
+                .ok_or_else(|| hh_error(n, &format!("unrecognized raise: {}", line)))?
+                .1;
+            let to = amount_at(n, to)?;
+            let paid = to - seat.street;
+            seat.street = to;
+            paid
+        } else if action.starts_with("folds") {
+            seat.folded = true;
+            0.0
+        } else if action == "sits out" {
+            seat.in_hand = false;
+            0.0
+        } else if action == "checks"
+            || action.starts_with("shows ")
+            || action.starts_with("mucks")
+            || action == "doesn't show hand"
+        {
+            0.0
+        } else {
+            return Err(hh_error(n, &format!("unrecognized action: {}", line)));
+        };
+        seat.stack -= paid;
+        pot += paid;
+    }
+
+    let river = river.ok_or_else(|| GtoError::HandHistory("the hand ended before the river".to_string()))?;
+    let button = button.ok_or_else(|| GtoError::HandHistory("no table line with the button seat".to_string()))?;
+    if board.len() != 5 {
+        return Err(GtoError::HandHistory(format!("expected 5 board cards, got {}", board.len())));
+    }
+
+    // Postflop order: the first seat after the button acts first
+    let mut order: Vec<usize> = (0..seats.len()).filter(|&i| seats[i].in_hand).collect();
+    order.sort_by_key(|&i| (seats[i].seat <= button, seats[i].seat));
+    let positions = position_names(order.len());
+    let players: Vec<HandPlayer> = order
+        .iter()
+        .zip(&positions)
+        .filter(|&(&i, _)| river.live[i])
+        .map(|(&i, &position)| HandPlayer {
+            name: seats[i].name.clone(),
+            seat: seats[i].seat,
+            position,
+            stack: river.stacks[i],
+        })
+        .collect();
+    let players: [HandPlayer; 2] = players.try_into().map_err(|p: Vec<HandPlayer>| {
+        GtoError::HandHistory(format!(
+            "{} players saw the river; a river solve needs exactly two",
+            p.len()
+        ))
+    })?;
+
+    Ok(HandSpot {
+        board,
+        pot: river.pot,
+        total_pot,
+        big_blind,
+        players,
+        hero,
+    })
+}
+
+/// Position names for `n` players in postflop order, from the small blind
+/// round to the button. Heads-up the button posts the small blind and acts
+/// last, so its opponent is the big blind.
+fn position_names(n: usize) -> Vec<&'static str> {
+    const MIDDLE: [&str; 6] = ["UTG", "UTG+1", "UTG+2", "LJ", "HJ", "CO"];
+    match n {
+        0 | 1 => vec!["BTN"; n],
+        2 => vec!["BB", "BTN"],
+        _ => {
+            let middle = n - 3;
+            let mut names = vec!["SB", "BB"];
+            if middle > 0 {
+                names.push(MIDDLE[0]);
+                names.extend(&MIDDLE[MIDDLE.len() + 1 - middle..]);
+            }
+            names.push("BTN");
+            names
+        }
+    }
+}
+
+/// Big blind from a header like "... Hold'em No Limit ($0.50/$1.00 USD) - ...".
+fn parse_stakes(header: &str) -> Option<f64> {
+    let (_, rest) = header.split_once('(')?;
+    let (stakes, _) = rest.split_once(')')?;
+    let (_, bb) = stakes.split_once('/')?;
+    parse_amount(bb.split_whitespace().next()?)
+}
+
+/// A "Seat 3: name ($100 in chips)" line: seat, name, stack and whether the
+/// player is sitting out. `None` if the line isn't a seat line at all.
+fn parse_seat_line(line: &str) -> Option<Result<(u32, String, f64, bool), String>> {
+    let rest = line.strip_prefix("Seat ")?;
+    let (seat, rest) = rest.split_once(": ")?;
+    let seat = seat.parse::<u32>().ok()?;
+    let chips = rest.find(" in chips")?;
+    let open = rest[..chips].rfind(" (")?;
+    let name = rest[..open].to_string();
+    let stack = parse_amount(&rest[open + 2..chips]);
+    let sitting_out = rest[chips..].contains("is sitting out");
+    Some(stack.map(|s| (seat, name, s, sitting_out)).ok_or_else(|| format!("bad stack: {}", line)))
+}
+
+/// The seat whose name followed by `sep` starts the line, and the rest of
+/// the line. Takes the longest such name, so "Bob" can't shadow "Bob 2".
+fn player_prefix<'a>(seats: &[Seat], line: &'a str, sep: &str) -> Option<(usize, &'a str)> {
+    seats
+        .iter()
+        .enumerate()
+        .filter_map(|(i, s)| {
+            let rest = line.strip_prefix(s.name.as_str())?.strip_prefix(sep)?;
+            Some((i, s.name.len(), rest))
+        })
+        .max_by_key(|&(_, len, _)| len)
+        .map(|(i, _, rest)| (i, rest))
+}
+
+fn seat_named(seats: &[Seat], name: &str) -> Option<usize> {
+    seats.iter().position(|s| s.name == name)
+}
+
+/// "$1,234.50", "€2" or play-money "1500" as a number.
+fn parse_amount(s: &str) -> Option<f64> {
+    s.trim()
+        .trim_start_matches(['$', '€', '£'])
+        .replace(',', "")
+        .parse()
+        .ok()
+}
+
+fn amount_at(line: usize, s: &str) -> GtoResult<f64> {
+    parse_amount(s).ok_or_else(|| hh_error(line, &format!("bad amount: {}", s)))
+}
+
+fn hh_error(line: usize, message: &str) -> GtoError {
+    GtoError::HandHistory(format!("line {}: {}", line, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIX_MAX: &str = "\
+PokerStars Hand #245131842457:  Hold'em No Limit ($0.50/$1.00 USD) - 2023/05/14 21:03:11 ET
+Table 'Aludra IV' 6-max Seat #4 is the button
+Seat 1: fishy22 ($87.25 in chips)
+Seat 2: Hero ($100 in chips)
+Seat 3: grinder_x ($112.40 in chips)
+Seat 4: Nick Sly ($100 in chips)
+Seat 5: tightpassive ($64 in chips)
+Seat 6: LAGtastic ($230.15 in chips)
+tightpassive: posts small blind $0.50
+LAGtastic: posts big blind $1
+*** HOLE CARDS ***
+Dealt to Hero [Ah Kh]
+fishy22: folds
+Hero: raises $2 to $3
+grinder_x: folds
+Nick Sly: folds
+tightpassive: folds
+LAGtastic: calls $2
+*** FLOP *** [Ks 9d 4c]
+LAGtastic: checks
+Hero: bets $4
+LAGtastic: calls $4
+*** TURN *** [Ks 9d 4c] [7h]
+LAGtastic: checks
+Hero: bets $10
+LAGtastic said, \"nh\"
+LAGtastic: calls $10
+*** RIVER *** [Ks 9d 4c 7h] [Qc]
+LAGtastic: checks
+Hero: bets $25
+LAGtastic: folds
+Uncalled bet ($25) returned to Hero
+Hero collected $33.30 from pot
+Hero: doesn't show hand
+*** SUMMARY ***
+Total pot $34.50 | Rake $1.20
+Board [Ks 9d 4c 7h Qc]
+Seat 2: Hero collected ($33.30)
+Seat 6: LAGtastic (big blind) folded on the River
+
+PokerStars Hand #245131842458:  Hold'em No Limit ($0.50/$1.00 USD) - 2023/05/14 21:04:02 ET
+";
+
+    const BLIND_VS_BLIND: &str = "\
+PokerStars Hand #245131900001:  Hold'em No Limit ($1/$2 USD) - 2023/05/15 10:00:00 ET
+Table 'Zeta' 6-max Seat #1 is the button
+Seat 1: button_guy ($200 in chips)
+Seat 2: sb_player ($150 in chips)
+Seat 3: bb_player ($300 in chips)
+Seat 4: away ($200 in chips) is sitting out
+sb_player: posts small blind $1
+bb_player: posts big blind $2
+*** HOLE CARDS ***
+button_guy: folds
+sb_player: raises $4 to $6
+bb_player: raises $14 to $20
+sb_player: calls $14
+*** FLOP *** [Jh 8h 2c]
+sb_player: bets $30
+bb_player: calls $30
+*** TURN *** [Jh 8h 2c] [3s]
+sb_player: checks
+bb_player: checks
+*** RIVER *** [Jh 8h 2c 3s] [Th]
+sb_player: bets $100 and is all-in
+bb_player: calls $100
+*** SHOW DOWN ***
+sb_player: shows [Ah Kh] (a flush, Ace high)
+bb_player: mucks hand
+sb_player collected $297 from pot
+*** SUMMARY ***
+Total pot $300 | Rake $3
+";
+
+    #[test]
+    fn six_max_single_raised_pot() {
+        let spot = parse_pokerstars(SIX_MAX).unwrap();
+        assert_eq!(spot.board_notation(), "Ks9d4c7hQc");
+        // 3 + 3 + 0.5 dead, then 4 and 10 from each
+        assert!((spot.pot - 34.5).abs() < 1e-9);
+        assert_eq!(spot.total_pot, Some(34.5));
+        assert_eq!(spot.big_blind, 1.0);
+        assert_eq!(spot.hero.as_deref(), Some("Hero"));
+
+        let [oop, ip] = &spot.players;
+        assert_eq!((oop.name.as_str(), oop.position), ("LAGtastic", "BB"));
+        assert_eq!((ip.name.as_str(), ip.position), ("Hero", "HJ"));
+        assert!((oop.stack - 213.15).abs() < 1e-9);
+        assert!((ip.stack - 83.0).abs() < 1e-9);
+        assert!((spot.effective_stack() - 83.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn blind_vs_blind_three_bet_pot_skips_sitting_out_seat() {
+        let spot = parse_pokerstars(BLIND_VS_BLIND).unwrap();
+        assert_eq!(spot.board_notation(), "Jh8h2c3sTh");
+        assert!((spot.pot - 100.0).abs() < 1e-9);
+        assert_eq!(spot.hero, None);
+
+        let [oop, ip] = &spot.players;
+        assert_eq!((oop.name.as_str(), oop.position, oop.seat), ("sb_player", "SB", 2));
+        assert_eq!((ip.name.as_str(), ip.position, ip.seat), ("bb_player", "BB", 3));
+        assert!((spot.effective_stack() - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn unrecognized_line_names_its_line_number() {
+        let text = SIX_MAX.replace("grinder_x: folds", "grinder_x: dances");
+        match parse_pokerstars(&text) {
+            Err(GtoError::HandHistory(message)) => {
+                assert!(message.starts_with("line 15:"), "{}", message);
+                assert!(message.contains("dances"), "{}", message);
+            }
+            other => panic!("expected a hand history error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_hands_that_end_before_the_river() {
+        let end = BLIND_VS_BLIND.find("*** RIVER ***").unwrap();
+        let text = format!("{}sb_player: bets $30\nbb_player: folds\n", &BLIND_VS_BLIND[..end]);
+        assert!(parse_pokerstars(&text).is_err());
+        assert!(parse_pokerstars("Full Tilt Poker Game #1").is_err());
+    }
+
+    #[test]
+    fn position_names_by_table_size() {
+        assert_eq!(position_names(2), ["BB", "BTN"]);
+        assert_eq!(position_names(6), ["SB", "BB", "UTG", "HJ", "CO", "BTN"]);
+        assert_eq!(
+            position_names(9),
+            ["SB", "BB", "UTG", "UTG+1", "UTG+2", "LJ", "HJ", "CO", "BTN"]
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Independent Chip Model (Malmuth-Harville) for tournament spots.
+//!
+//! Converts chip stacks into shares of a prize pool, so push/fold spots near
+//! the money can be solved in $EV instead of chip EV.
+
+/// Each player's expected prize under Malmuth-Harville: the chance of
+/// finishing first is proportional to stack size, and each lower place is
+/// filled the same way from the players left. Players with no chips finish
+/// below everyone else, splitting the places they share evenly.
+pub fn icm_equity(stacks: &[f64], payouts: &[f64]) -> Vec<f64> {
+    let mut equity = vec![0.0; stacks.len()];
+    let remaining: Vec<usize> = (0..stacks.len()).collect();
+    finish_places(stacks, payouts, &remaining, 0, 1.0, &mut equity);
+    equity
+}
+
+/// Credit `prob` times the expected prize of every finishing order of the
+/// `remaining` players from `place` down.
+fn finish_places(
+    stacks: &[f64],
+    payouts: &[f64],
+    remaining: &[usize],
+    place: usize,
+    prob: f64,
+    equity: &mut [f64],
+) {
+    if place >= payouts.len() || remaining.is_empty() {
+        return;
+    }
+
+    let total: f64 = remaining.iter().map(|&i| stacks[i]).sum();
+    if total <= 0.0 {
+        let end = (place + remaining.len()).min(payouts.len());
+        let share = payouts[place..end].iter().sum::<f64>() / remaining.len() as f64;
+        for &i in remaining {
+            equity[i] += prob * share;
+        }
+        return;
+    }
+
+    for &i in remaining {
+        if stacks[i] <= 0.0 {
+            continue;
+        }
+        let p = prob * stacks[i] / total;
+        equity[i] += p * payouts[place];
+        let rest: Vec<usize> = remaining.iter().copied().filter(|&j| j != i).collect();
+        finish_places(stacks, payouts, &rest, place + 1, p, equity);
+    }
+}
+
+/// A tournament push/fold spot: every stack at the table and the prizes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcmSpot {
+    /// Stacks in big blinds before the blinds are posted, small blind
+    /// first, big blind second, then the rest of the table.
+    pub stacks: Vec<f64>,
+    /// Prize for each finishing place, first place first.
+    pub payouts: Vec<f64>,
+}
+
+/// Change in the blinds' ICM equity for each push/fold outcome, `[sb, bb]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IcmOutcomes {
+    /// SB folds and BB collects the small blind.
+    pub sb_folds: [f64; 2],
+    /// SB shoves and BB folds the big blind.
+    pub bb_folds: [f64; 2],
+    /// SB shoves, BB calls and SB wins the showdown.
+    pub sb_wins: [f64; 2],
+    /// SB shoves, BB calls and SB loses the showdown.
+    pub sb_loses: [f64; 2],
+}
+
+impl IcmSpot {
+    pub fn new(stacks: Vec<f64>, payouts: Vec<f64>) -> Result<Self, String> {
+        if stacks.len() < 2 {
+            return Err("ICM needs at least the small and big blind stacks".to_string());
+        }
+        if let Some(s) = stacks.iter().find(|s| !s.is_finite() || **s < 0.0) {
+            return Err(format!("Stacks must be non-negative, got {}", s));
+        }
+        if stacks[0] < 1.0 || stacks[1] < 1.0 {
+            return Err("The blinds need at least 1bb each".to_string());
+        }
+        if payouts.is_empty() {
+            return Err("Payouts are empty".to_string());
+        }
+        if let Some(p) = payouts.iter().find(|p| !p.is_finite() || **p < 0.0) {
+            return Err(format!("Payouts must be non-negative, got {}", p));
+        }
+        Ok(IcmSpot { stacks, payouts })
+    }
+
+    /// Chips at stake when SB shoves and BB calls.
+    pub fn effective_stack(&self) -> f64 {
+        self.stacks[0].min(self.stacks[1])
+    }
+
+    /// Each outcome's ICM equity change for the blinds, relative to the
+    /// stacks before the hand.
+    pub fn push_fold_outcomes(&self) -> IcmOutcomes {
+        let before = icm_equity(&self.stacks, &self.payouts);
+        let after = |sb_gain: f64| {
+            let mut stacks = self.stacks.clone();
+            stacks[0] += sb_gain;
+            stacks[1] -= sb_gain;
+            let equity = icm_equity(&stacks, &self.payouts);
+            [equity[0] - before[0], equity[1] - before[1]]
+        };
+        let stake = self.effective_stack();
+        IcmOutcomes {
+            sb_folds: after(-0.5),
+            bb_folds: after(1.0),
+            sb_wins: after(stake),
+            sb_loses: after(-stake),
+        }
+    }
+}
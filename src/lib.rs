@@ -1,25 +1,36 @@
 pub mod batch;
 pub mod card_encoding;
+pub mod checkpoint;
 pub mod cards;
 pub mod cfr;
 pub mod flat_cfr;
 pub mod display;
+pub mod draws;
 pub mod equity;
 pub mod error;
 pub mod flop_enumerator;
+pub mod flop_report;
 pub mod game_tree;
+pub mod hand_history;
 pub mod hand_evaluator;
+pub mod icm;
 pub mod lookup_eval;
+pub mod lookup_table;
 pub mod math_engine;
 pub mod multiway;
+pub mod multiway_pushfold;
 pub mod play;
 pub mod postflop;
 pub mod postflop_tree;
 pub mod preflop;
 pub mod preflop_solver;
+pub mod progress;
 pub mod ranges;
+pub mod board_texture;
 pub mod bucketing;
 pub mod flop_solver;
 pub mod river_solver;
+pub mod solver_cache;
 pub mod strategy;
 pub mod turn_solver;
+pub mod walk;
@@ -16,6 +16,7 @@
 
 use once_cell::sync::Lazy;
 
+use crate::card_encoding::{remaining_deck, remaining_short_deck};
 use crate::hand_evaluator::HandCategory;
 
 // -------------------------------------------------------------------------
@@ -51,6 +52,12 @@ static STRAIGHT_TABLE: Lazy<[u8; 8192]> = Lazy::new(|| {
     table
 });
 
+/// High card (5-14) of the best straight in a 13-bit rank mask, or 0.
+#[inline]
+pub(crate) fn straight_high(mask: u16) -> u8 {
+    STRAIGHT_TABLE[mask as usize]
+}
+
 // -------------------------------------------------------------------------
 // Score packing
 // -------------------------------------------------------------------------
@@ -69,15 +76,13 @@ fn hand_score(category: u8, v: &[u8]) -> u32 {
 
 /// Extract the top `n` set bits from a 13-bit mask as rank values (high→low).
 #[inline]
-fn top_n_from_mask(mask: u16, n: usize) -> [u8; 5] {
+fn top_n_from_mask(mut mask: u16, n: usize) -> [u8; 5] {
     let mut result = [0u8; 5];
-    let mut count = 0;
-    for bit in (0..13u8).rev() {
-        if mask & (1 << bit) != 0 {
-            result[count] = bit + 2; // bit index → rank value
-            count += 1;
-            if count == n { break; }
-        }
+    for r in result.iter_mut().take(n) {
+        if mask == 0 { break; }
+        let bit = 15 - mask.leading_zeros() as u8;
+        *r = bit + 2; // bit index → rank value
+        mask &= !(1 << bit);
     }
     result
 }
@@ -194,6 +199,337 @@ fn evaluate_non_flush(rc: &[u8; 13]) -> u32 {
     hand_score(0, &[sing[0], sing[1], sing[2], sing[3], sing[4]])
 }
 
+// -------------------------------------------------------------------------
+// Best five cards (for display)
+// -------------------------------------------------------------------------
+
+/// The five cards that make the best hand out of 5-7 cards, with the score
+/// `evaluate_fast` gives the whole set.
+///
+/// Only same-rank cards can be swapped without changing the score; such
+/// ties go to the card later in `cards`. With hole cards passed first, a
+/// hole card only plays when no board card of its rank could take its
+/// place.
+///
+/// The five come back grouped the way the hand reads: the largest set of a
+/// rank first, then higher ranks first, with the ace last in a five-high
+/// straight (e.g. `Ah Ad As Ks Kd`, `5c 4d 3s 2h Ah`).
+pub fn best_five(cards: &[u8]) -> ([u8; 5], u32) {
+    debug_assert!(cards.len() >= 5 && cards.len() <= 7);
+    let n = cards.len();
+
+    // Key: score, then the chosen positions from last to first, so among
+    // equal scores every swap to a later card of the same rank wins
+    let mut best: Option<(u32, [usize; 5])> = None;
+    for a in 0..n {
+        for b in (a + 1)..n {
+            for c in (b + 1)..n {
+                for d in (c + 1)..n {
+                    for e in (d + 1)..n {
+                        let hand = [cards[a], cards[b], cards[c], cards[d], cards[e]];
+                        let key = (evaluate_fast(&hand), [e, d, c, b, a]);
+                        if best.is_none_or(|b| key > b) {
+                            best = Some(key);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    let (score, picked) = best.unwrap_or_default();
+
+    let mut five = picked.map(|i| cards[i]);
+    five.reverse();
+    let wheel = matches!(score >> 20, 4 | 8) && (score >> 16) & 0xF == 5;
+    let play_rank = |c: u8| match c >> 2 {
+        12 if wheel => 0,
+        r => r + 1,
+    };
+    let count = |c: u8| five.iter().filter(|&&o| o >> 2 == c >> 2).count();
+    let mut ordered = five;
+    // Stable sort keeps same-rank cards in their input order
+    ordered.sort_by_key(|&c| std::cmp::Reverse((count(c), play_rank(c))));
+
+    debug_assert_eq!(score, evaluate_fast(cards));
+    (ordered, score)
+}
+
+// -------------------------------------------------------------------------
+// Batch evaluation
+// -------------------------------------------------------------------------
+
+/// Incremental rank/suit masks for a partial hand.
+///
+/// `at_least[k]` holds the ranks seen at least `k + 1` times, so exact
+/// multiplicities fall out of two mask operations instead of a histogram
+/// scan. Copying the masks of a fixed board and folding in two hole cards
+/// is much cheaper than rebuilding them from seven cards.
+#[derive(Clone, Copy, Default)]
+struct HandMasks {
+    at_least: [u16; 4],
+    suit_masks: [u16; 4],
+    suit_counts: [u8; 4],
+}
+
+impl HandMasks {
+    fn from_cards(cards: &[u8]) -> Self {
+        let mut masks = Self::default();
+        for &c in cards {
+            masks.add(c);
+        }
+        masks
+    }
+
+    #[inline]
+    fn add(&mut self, c: u8) {
+        let bit = 1u16 << (c >> 2);
+        let suit = (c & 0x3) as usize;
+        // A rank reaches level k+1 only if it already sits at every lower level
+        let [one, two, three, _] = self.at_least;
+        self.at_least[3] |= bit & one & two & three;
+        self.at_least[2] |= bit & one & two;
+        self.at_least[1] |= bit & one;
+        self.at_least[0] |= bit;
+        self.suit_masks[suit] |= bit;
+        self.suit_counts[suit] += 1;
+    }
+
+    /// Score the hand. Identical to `evaluate_fast` on the same cards.
+    #[inline]
+    fn score(&self) -> u32 {
+        if let Some(suit) = self.suit_counts.iter().position(|&c| c >= 5) {
+            let fmask = self.suit_masks[suit];
+            let sf_high = STRAIGHT_TABLE[fmask as usize];
+            if sf_high > 0 {
+                if sf_high == 14 {
+                    return hand_score(9, &[14]);
+                }
+                return hand_score(8, &[sf_high]);
+            }
+            return hand_score(5, &top_n_from_mask(fmask, 5));
+        }
+
+        let [any, two, three, four] = self.at_least;
+        let trips = three & !four;
+        let pairs = two & !three;
+        let singles = any & !two;
+
+        if four != 0 {
+            let quad = top_n_from_mask(four, 1)[0];
+            // Same kicker preference as `evaluate_non_flush`.
+            let rest = if trips != 0 { trips } else if pairs != 0 { pairs } else { singles };
+            let kick = top_n_from_mask(rest, 1)[0];
+            return hand_score(7, &[quad, kick]);
+        }
+
+        let nt = trips.count_ones();
+        let np = pairs.count_ones();
+        if nt >= 1 && (np >= 1 || nt >= 2) {
+            let t = top_n_from_mask(trips, 2);
+            let pr = if nt >= 2 { t[1] } else { top_n_from_mask(pairs, 1)[0] };
+            return hand_score(6, &[t[0], pr]);
+        }
+
+        let sh = STRAIGHT_TABLE[any as usize];
+        if sh > 0 {
+            return hand_score(4, &[sh]);
+        }
+
+        let sing = top_n_from_mask(singles, 5);
+        if nt >= 1 {
+            let t = top_n_from_mask(trips, 1)[0];
+            return hand_score(3, &[t, sing[0], sing[1]]);
+        }
+        if np >= 2 {
+            let p = top_n_from_mask(pairs, 3);
+            let kick = if np >= 3 && p[2] > sing[0] { p[2] } else { sing[0] };
+            return hand_score(2, &[p[0], p[1], kick]);
+        }
+        if np == 1 {
+            let p = top_n_from_mask(pairs, 1)[0];
+            return hand_score(1, &[p, sing[0], sing[1], sing[2]]);
+        }
+        hand_score(0, &sing)
+    }
+}
+
+/// Score a 7-card hand given its rank counts (index 0 = Two) and the rank
+/// masks of every suit that could still hold a flush. Suits that can't may
+/// be left as 0. Used to fill the `lookup_table` state machine.
+pub(crate) fn score_from_counts(rank_counts: &[u8; 13], suit_masks: &[u16; 4]) -> u32 {
+    let mut masks = HandMasks { suit_masks: *suit_masks, ..HandMasks::default() };
+    for (rank, &count) in rank_counts.iter().enumerate() {
+        for level in &mut masks.at_least[..count as usize] {
+            *level |= 1 << rank;
+        }
+    }
+    for (count, mask) in masks.suit_counts.iter_mut().zip(suit_masks) {
+        *count = mask.count_ones() as u8;
+    }
+    masks.score()
+}
+
+/// Evaluate a batch of 7-card hands. Scores match `evaluate_fast`.
+pub fn evaluate_many(hands: &[[u8; 7]]) -> Vec<u32> {
+    let mut out = vec![0; hands.len()];
+    evaluate_many_into(hands, &mut out);
+    out
+}
+
+/// `evaluate_many` writing into a caller-provided buffer of the same length.
+pub fn evaluate_many_into(hands: &[[u8; 7]], out: &mut [u32]) {
+    assert_eq!(hands.len(), out.len(), "output buffer must match hand count");
+    for (hand, score) in hands.iter().zip(out.iter_mut()) {
+        *score = HandMasks::from_cards(hand).score();
+    }
+}
+
+/// Evaluate many hole-card pairs on one 5-card board.
+///
+/// The board's rank and suit masks are built once; each hand only folds
+/// in its two hole cards. Unless the hand makes a flush its score depends
+/// only on the two hole ranks, so those scores are memoized per rank pair.
+/// Scores match `evaluate_fast` on the 7 cards.
+pub fn evaluate_board_plus(board: &[u8; 5], holes: &[[u8; 2]]) -> Vec<u32> {
+    let base = HandMasks::from_cards(board);
+    // Every score is nonzero (even high card has kickers), so 0 = unseen
+    let mut by_ranks = [0u32; 13 * 13];
+    holes
+        .iter()
+        .map(|&[c0, c1]| {
+            let mut suit_counts = base.suit_counts;
+            suit_counts[(c0 & 0x3) as usize] += 1;
+            suit_counts[(c1 & 0x3) as usize] += 1;
+            let plus_hole = || {
+                let mut masks = base;
+                masks.add(c0);
+                masks.add(c1);
+                masks.score()
+            };
+            if suit_counts.iter().any(|&c| c >= 5) {
+                return plus_hole();
+            }
+            let (r0, r1) = ((c0 >> 2) as usize, (c1 >> 2) as usize);
+            let slot = &mut by_ranks[r0.max(r1) * 13 + r0.min(r1)];
+            if *slot == 0 {
+                *slot = plus_hole();
+            }
+            *slot
+        })
+        .collect()
+}
+
+// -------------------------------------------------------------------------
+// Short deck (6+)
+// -------------------------------------------------------------------------
+
+/// A-6-7-8-9: the lowest straight once 2-5 are removed.
+const SHORT_DECK_WHEEL: u16 = (1 << 12) | (0b1111 << 4);
+
+/// Evaluate a 5-7 card short-deck hand. Same packing as `evaluate_fast`,
+/// except that a flush outranks a full house and A-6-7-8-9 counts as a
+/// 9-high straight. Use `short_deck_category` to read the category back.
+pub fn evaluate_short_deck(cards: &[u8]) -> u32 {
+    let score = evaluate_fast(cards);
+    let category = score >> 20;
+
+    // The short-deck wheel only matters where `evaluate_fast` found no straight
+    if category == 5 || category < 4 {
+        let mut suit_masks = [0u16; 4];
+        let mut rank_mask = 0u16;
+        for &c in cards {
+            suit_masks[(c & 0x3) as usize] |= 1 << (c >> 2);
+            rank_mask |= 1 << (c >> 2);
+        }
+        if category == 5
+            && suit_masks
+                .iter()
+                .any(|&m| m & SHORT_DECK_WHEEL == SHORT_DECK_WHEEL)
+        {
+            return hand_score(8, &[9]);
+        }
+        if category < 4 && rank_mask & SHORT_DECK_WHEEL == SHORT_DECK_WHEEL {
+            return hand_score(4, &[9]);
+        }
+    }
+
+    // Flushes are rarer than full houses with 36 cards: swap the two
+    match category {
+        5 => (score & 0xF_FFFF) | 6 << 20,
+        6 => (score & 0xF_FFFF) | 5 << 20,
+        _ => score,
+    }
+}
+
+/// HandCategory of an `evaluate_short_deck` score.
+pub fn short_deck_category(score: u32) -> HandCategory {
+    match category_from_score(score) {
+        HandCategory::Flush => HandCategory::FullHouse,
+        HandCategory::FullHouse => HandCategory::Flush,
+        other => other,
+    }
+}
+
+/// Hand-ranking rules for equity calculations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleSet {
+    /// Standard 52-card hold'em.
+    Holdem,
+    /// 36-card short deck: see `evaluate_short_deck`.
+    ShortDeck,
+}
+
+impl RuleSet {
+    /// Score a 5-7 card hand under these rules.
+    #[inline]
+    pub fn evaluate(&self, cards: &[u8]) -> u32 {
+        match self {
+            RuleSet::Holdem => evaluate_fast(cards),
+            RuleSet::ShortDeck => evaluate_short_deck(cards),
+        }
+    }
+
+    /// Category of a score from `evaluate` under these rules.
+    pub fn category(&self, score: u32) -> HandCategory {
+        match self {
+            RuleSet::Holdem => category_from_score(score),
+            RuleSet::ShortDeck => short_deck_category(score),
+        }
+    }
+
+    /// The deck for these rules minus `dead`.
+    pub fn remaining_deck(&self, dead: &[u8]) -> Vec<u8> {
+        match self {
+            RuleSet::Holdem => remaining_deck(dead),
+            RuleSet::ShortDeck => remaining_short_deck(dead),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------
+// Omaha
+// -------------------------------------------------------------------------
+
+/// Best Omaha score: exactly two hole cards plus exactly three board cards,
+/// maximized over the 6 hole pairs × 10 board triples. Comparable with
+/// other `evaluate_omaha` scores (and with `evaluate_fast` on 5 cards).
+pub fn evaluate_omaha(hole: &[u8; 4], board: &[u8; 5]) -> u32 {
+    let mut best = 0;
+    for i in 0..4 {
+        for j in (i + 1)..4 {
+            for a in 0..5 {
+                for b in (a + 1)..5 {
+                    for c in (b + 1)..5 {
+                        let hand = [hole[i], hole[j], board[a], board[b], board[c]];
+                        best = best.max(evaluate_fast(&hand));
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
 // -------------------------------------------------------------------------
 // Score → HandCategory (for display code)
 // -------------------------------------------------------------------------
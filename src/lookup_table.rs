@@ -0,0 +1,269 @@
+//! Precomputed 7-card evaluator (2+2-style state machine).
+//!
+//! Every partial hand of 0-6 cards is a state; `table[state + card]` is the
+//! next state, already multiplied by 52, or after the seventh card the final
+//! score. Evaluating a hand is seven array loads.
+//!
+//! A state keeps each rank's count plus the exact rank mask of every suit
+//! that can still make a flush with the cards left to come. Once a suit
+//! falls short it is dropped, which folds the ~133M ordered 7-card deals
+//! into about 613K states (a ~127MB table).
+//!
+//! Scores are identical to `lookup_eval::evaluate_fast`, so the two are
+//! interchangeable. Building the table takes a few seconds in release; it
+//! is cached under `~/.gto-cli/eval.bin`. Solvers pick it up through
+//! `evaluate_seven` / `evaluate_board` when it is active (see
+//! `set_evaluator` and `use_cached_table`).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use once_cell::sync::OnceCell;
+
+use crate::lookup_eval::{evaluate_board_plus, evaluate_fast, score_from_counts};
+
+/// A partial hand as the state machine sees it.
+#[derive(Clone, Copy, Default)]
+struct State {
+    rank_counts: [u8; 13],
+    /// Rank masks of suits that can still reach five cards; 0 otherwise.
+    suit_masks: [u16; 4],
+    len: u8,
+}
+
+impl State {
+    /// The state after drawing `card`, or None when the card can't be added
+    /// (fifth card of a rank, or already held in a tracked suit).
+    fn add(&self, card: u8) -> Option<State> {
+        let rank = (card >> 2) as usize;
+        let suit = (card & 0x3) as usize;
+        let bit = 1u16 << rank;
+        if self.rank_counts[rank] == 4 || self.suit_masks[suit] & bit != 0 {
+            return None;
+        }
+
+        let mut next = *self;
+        next.rank_counts[rank] += 1;
+        next.suit_masks[suit] |= bit;
+        next.len += 1;
+
+        let to_come = 7 - next.len as u32;
+        for mask in next.suit_masks.iter_mut() {
+            if mask.count_ones() + to_come < 5 {
+                *mask = 0;
+            }
+        }
+        Some(next)
+    }
+
+    fn key(&self) -> u128 {
+        let counts = self
+            .rank_counts
+            .iter()
+            .enumerate()
+            .fold(0u128, |k, (r, &c)| k | (c as u128) << (3 * r));
+        let masks = self
+            .suit_masks
+            .iter()
+            .enumerate()
+            .fold(0u128, |k, (s, &m)| k | (m as u128) << (13 * s));
+        counts | masks << 64
+    }
+}
+
+/// The generated transition table.
+pub struct LookupTable {
+    table: Vec<u32>,
+}
+
+/// Leading bytes of a saved table file.
+const MAGIC: &[u8; 8] = b"GTOEVAL1";
+
+impl LookupTable {
+    /// Build the table by walking every reachable state breadth-first.
+    pub fn generate() -> Self {
+        let mut states = vec![State::default()];
+        let mut ids: HashMap<u128, u32> = HashMap::new();
+        ids.insert(State::default().key(), 0);
+        let mut table = Vec::new();
+
+        let mut id = 0;
+        while id < states.len() {
+            let state = states[id];
+            for card in 0..52u8 {
+                let entry = match state.add(card) {
+                    None => 0,
+                    Some(next) if next.len == 7 => {
+                        score_from_counts(&next.rank_counts, &next.suit_masks)
+                    }
+                    Some(next) => {
+                        let next_id = *ids.entry(next.key()).or_insert_with(|| {
+                            states.push(next);
+                            (states.len() - 1) as u32
+                        });
+                        next_id * 52
+                    }
+                };
+                table.push(entry);
+            }
+            id += 1;
+        }
+        LookupTable { table }
+    }
+
+    /// Number of partial-hand states (the table holds 52 entries per state).
+    pub fn num_states(&self) -> usize {
+        self.table.len() / 52
+    }
+
+    /// Score of a 7-card hand; identical to `evaluate_fast`.
+    #[inline]
+    pub fn evaluate(&self, cards: &[u8; 7]) -> u32 {
+        let state = self.walk(0, &cards[..6]);
+        self.table[(state + cards[6] as u32) as usize]
+    }
+
+    /// `evaluate_board_plus` through the table: the board is walked once,
+    /// then each hand costs two loads.
+    pub fn evaluate_board_plus(&self, board: &[u8; 5], holes: &[[u8; 2]]) -> Vec<u32> {
+        let base = self.walk(0, board);
+        holes
+            .iter()
+            .map(|&[c0, c1]| {
+                let state = self.table[(base + c0 as u32) as usize];
+                self.table[(state + c1 as u32) as usize]
+            })
+            .collect()
+    }
+
+    #[inline]
+    fn walk(&self, mut state: u32, cards: &[u8]) -> u32 {
+        for &c in cards {
+            state = self.table[(state + c as u32) as usize];
+        }
+        state
+    }
+
+    /// Write the table to `path`, creating parent directories.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let mut data = Vec::with_capacity(MAGIC.len() + 8 + self.table.len() * 4);
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&(self.table.len() as u64).to_le_bytes());
+        for &entry in &self.table {
+            data.extend_from_slice(&entry.to_le_bytes());
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        std::fs::write(path, data).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Read a table written by `save`.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let bad = || format!("{}: not an evaluator table", path.display());
+        let body = data.strip_prefix(MAGIC.as_slice()).ok_or_else(bad)?;
+        if body.len() < 8 {
+            return Err(bad());
+        }
+        let (len, entries) = body.split_at(8);
+        let len = u64::from_le_bytes(len.try_into().unwrap()) as usize;
+        if entries.len() != len * 4 || !len.is_multiple_of(52) {
+            return Err(bad());
+        }
+        let table = entries
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        Ok(LookupTable { table })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Process-wide evaluator selection
+// ---------------------------------------------------------------------------
+
+/// Which 7-card evaluator the solvers use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Evaluator {
+    /// Rank-histogram evaluator (`evaluate_fast`); no setup cost.
+    Fast,
+    /// The precomputed table, loaded from or saved to `table_path()`.
+    Table,
+}
+
+static TABLE: OnceCell<LookupTable> = OnceCell::new();
+static TABLE_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// `~/.gto-cli/eval.bin`.
+pub fn table_path() -> PathBuf {
+    crate::checkpoint::gto_home().join("eval.bin")
+}
+
+/// The process-wide table: loaded from `table_path()`, or generated and
+/// saved there on first use. A failed save only costs the next run a rebuild.
+fn global_table() -> &'static LookupTable {
+    TABLE.get_or_init(|| {
+        let path = table_path();
+        LookupTable::load(&path).unwrap_or_else(|_| {
+            let table = LookupTable::generate();
+            table.save(&path).ok();
+            table
+        })
+    })
+}
+
+/// Select the evaluator for `evaluate_seven` / `evaluate_board`.
+/// Choosing `Table` loads (or builds) the table up front.
+pub fn set_evaluator(evaluator: Evaluator) {
+    if evaluator == Evaluator::Table {
+        global_table();
+    }
+    TABLE_ACTIVE.store(evaluator == Evaluator::Table, Ordering::Relaxed);
+}
+
+/// Activate the table if it is already cached on disk. Returns whether it is
+/// now active. Never builds the table.
+pub fn use_cached_table() -> bool {
+    if TABLE.get().is_none() {
+        match LookupTable::load(&table_path()) {
+            Ok(table) => {
+                TABLE.set(table).ok();
+            }
+            Err(_) => return false,
+        }
+    }
+    TABLE_ACTIVE.store(true, Ordering::Relaxed);
+    true
+}
+
+/// Score of a 7-card hand through the table, building it on first use.
+pub fn evaluate_table(cards: &[u8; 7]) -> u32 {
+    global_table().evaluate(cards)
+}
+
+fn active_table() -> Option<&'static LookupTable> {
+    if TABLE_ACTIVE.load(Ordering::Relaxed) {
+        TABLE.get()
+    } else {
+        None
+    }
+}
+
+/// Score a 7-card hand with the selected evaluator.
+#[inline]
+pub fn evaluate_seven(cards: &[u8; 7]) -> u32 {
+    match active_table() {
+        Some(table) => table.evaluate(cards),
+        None => evaluate_fast(cards),
+    }
+}
+
+/// `evaluate_board_plus` with the selected evaluator.
+pub fn evaluate_board(board: &[u8; 5], holes: &[[u8; 2]]) -> Vec<u32> {
+    match active_table() {
+        Some(table) => table.evaluate_board_plus(board, holes),
+        None => evaluate_board_plus(board, holes),
+    }
+}
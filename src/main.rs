@@ -1,29 +1,40 @@
 mod batch;
+mod board_texture;
 mod bucketing;
 mod card_encoding;
 mod cards;
 mod cfr;
+mod checkpoint;
 mod cli;
+mod draws;
 mod display;
 mod equity;
 mod error;
 mod flat_cfr;
 mod flop_enumerator;
+mod flop_report;
 mod flop_solver;
 mod game_tree;
+mod hand_history;
 mod hand_evaluator;
+mod icm;
 mod lookup_eval;
+mod lookup_table;
 mod math_engine;
 mod multiway;
+mod multiway_pushfold;
 mod play;
 mod postflop;
 mod postflop_tree;
 mod preflop;
 mod preflop_solver;
+mod progress;
 mod ranges;
 mod river_solver;
+mod solver_cache;
 mod strategy;
 mod turn_solver;
+mod walk;
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
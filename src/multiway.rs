@@ -1,4 +1,12 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rayon::prelude::*;
+
+use crate::card_encoding::card_to_index;
+use crate::cards::{hand_combos, parse_board, Card};
 use crate::error::{GtoError, GtoResult};
+use crate::lookup_eval::RuleSet;
+use crate::ranges::parse_range;
 
 pub struct MultiwayBetAdvice {
     pub frequency: f64,
@@ -108,3 +116,223 @@ pub fn multiway_range_adjustment(num_players: usize) -> &'static str {
         "Standard heads-up ranges apply."
     }
 }
+
+/// Split an all-in showdown into main and side pots and return what each
+/// player collects. `invested` is each player's total contribution and
+/// `scores` their final hand score (higher wins). Each pot layer, up to the
+/// next distinct all-in amount, goes to the best hand among players who
+/// invested at least that much; tied hands split it evenly.
+///
+/// Panics if `invested` and `scores` differ in length.
+pub fn distribute_pots(invested: &[f64], scores: &[u32]) -> Vec<f64> {
+    assert_eq!(invested.len(), scores.len(), "one score per player");
+
+    let mut levels: Vec<f64> = invested.iter().copied().filter(|&x| x > 0.0).collect();
+    levels.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    levels.dedup();
+
+    let mut winnings = vec![0.0; invested.len()];
+    let mut prev = 0.0;
+    for level in levels {
+        let pot: f64 = invested.iter().map(|&x| x.min(level) - x.min(prev)).sum();
+        let eligible: Vec<usize> = (0..invested.len()).filter(|&i| invested[i] >= level).collect();
+        let best = eligible.iter().map(|&i| scores[i]).max().unwrap_or(0);
+        let winners: Vec<usize> = eligible.into_iter().filter(|&i| scores[i] == best).collect();
+        let share = pot / winners.len() as f64;
+        for i in winners {
+            winnings[i] += share;
+        }
+        prev = level;
+    }
+    winnings
+}
+
+/// One player's holding in a multiway equity calculation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlayerSpec {
+    /// Known hole cards.
+    Hand(Card, Card),
+    /// Hand notations (e.g. from `parse_range("JJ-99")`). Every simulation
+    /// deals the player one of the range's live combos at random.
+    Range(Vec<String>),
+}
+
+impl PlayerSpec {
+    /// A hand if `notation` is two cards ("AsKs"), otherwise a range
+    /// ("JJ-99,AQs+").
+    pub fn parse(notation: &str) -> PlayerSpec {
+        match parse_board(notation).as_deref() {
+            Ok(&[c1, c2]) => PlayerSpec::Hand(c1, c2),
+            _ => PlayerSpec::Range(parse_range(notation)),
+        }
+    }
+}
+
+/// One player's share of a multiway pot, as fractions of all simulations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MultiwayShare {
+    /// Split-adjusted equity: each pot is divided evenly among the hands
+    /// that tie for best, so the players' equities sum to 1.
+    pub equity: f64,
+    /// Pots won outright.
+    pub win: f64,
+    /// Pots split with at least one other player.
+    pub tie: f64,
+}
+
+/// Result of `multiway_equity`: one share per player, in the order given.
+#[derive(Debug, Clone)]
+pub struct MultiwayEquity {
+    pub players: Vec<MultiwayShare>,
+    pub simulations: usize,
+}
+
+/// Deals retried before giving up on ranges whose combos keep colliding.
+const MAX_DEAL_ATTEMPTS: usize = 1_000;
+
+/// Hold'em all-in equity of every player at once. Each simulation deals a
+/// combo to every range player (no card twice; combos within a range are
+/// equally likely), completes the board and splits the pot among the best
+/// hands.
+pub fn multiway_equity(
+    players: &[PlayerSpec],
+    board: Option<&[Card]>,
+    simulations: usize,
+) -> GtoResult<MultiwayEquity> {
+    if players.len() < 2 {
+        return Err(GtoError::InvalidValue(
+            "Multiway equity needs at least two players".to_string(),
+        ));
+    }
+    let board: Vec<u8> = board.unwrap_or(&[]).iter().map(card_to_index).collect();
+    if board.len() > 5 {
+        return Err(GtoError::InvalidValue("Board can have at most 5 cards".to_string()));
+    }
+
+    // Known cards may each appear once; range combos must avoid them all
+    let mut known = 0u64;
+    for &c in &board {
+        if known & (1 << c) != 0 {
+            return Err(GtoError::InvalidValue("Board repeats a card".to_string()));
+        }
+        known |= 1 << c;
+    }
+    for player in players {
+        if let PlayerSpec::Hand(c1, c2) = player {
+            let mask = (1u64 << card_to_index(c1)) | (1u64 << card_to_index(c2));
+            if known & mask != 0 || c1 == c2 {
+                return Err(GtoError::InvalidValue(format!("{}{} reuses a dealt card", c1, c2)));
+            }
+            known |= mask;
+        }
+    }
+    if 2 * players.len() + 5 > 52 {
+        return Err(GtoError::InvalidValue("Too many players for one deck".to_string()));
+    }
+
+    let holdings: Vec<Vec<[u8; 2]>> = players
+        .iter()
+        .map(|player| match player {
+            PlayerSpec::Hand(c1, c2) => Ok(vec![[card_to_index(c1), card_to_index(c2)]]),
+            PlayerSpec::Range(range) => live_combos(range, known),
+        })
+        .collect::<GtoResult<_>>()?;
+    if holdings.iter().any(Vec::is_empty) {
+        return Err(GtoError::NoValidCombos);
+    }
+
+    let n = players.len();
+    let simulations = simulations.max(1);
+    let totals = (0..simulations)
+        .into_par_iter()
+        .map_init(rand::thread_rng, |rng, _| deal_scores(&holdings, &board, rng))
+        .try_fold(
+            || vec![[0.0; 3]; n],
+            |mut acc, scores| {
+                let scores = scores?;
+                let best = scores.iter().copied().max().unwrap_or(0);
+                let winners = scores.iter().filter(|&&s| s == best).count();
+                for (tally, &score) in acc.iter_mut().zip(&scores) {
+                    if score == best {
+                        tally[0] += 1.0 / winners as f64;
+                        tally[if winners == 1 { 1 } else { 2 }] += 1.0;
+                    }
+                }
+                Ok::<_, GtoError>(acc)
+            },
+        )
+        .try_reduce(
+            || vec![[0.0; 3]; n],
+            |mut a, b| {
+                for (x, y) in a.iter_mut().zip(&b) {
+                    for i in 0..3 {
+                        x[i] += y[i];
+                    }
+                }
+                Ok(a)
+            },
+        )?;
+
+    let total = simulations as f64;
+    Ok(MultiwayEquity {
+        players: totals
+            .iter()
+            .map(|t| MultiwayShare { equity: t[0] / total, win: t[1] / total, tie: t[2] / total })
+            .collect(),
+        simulations,
+    })
+}
+
+/// Distinct combos of `range` that use none of the `known` cards (a bit
+/// per card index).
+fn live_combos(range: &[String], known: u64) -> GtoResult<Vec<[u8; 2]>> {
+    let mut combos: Vec<[u8; 2]> = Vec::new();
+    for notation in range {
+        for (c1, c2) in hand_combos(notation)? {
+            let combo = [card_to_index(&c1), card_to_index(&c2)];
+            let mask = (1u64 << combo[0]) | (1u64 << combo[1]);
+            if known & mask == 0 && !combos.iter().any(|c| c.contains(&combo[0]) && c.contains(&combo[1])) {
+                combos.push(combo);
+            }
+        }
+    }
+    Ok(combos)
+}
+
+/// Deal every player a combo from their holdings with no card used twice,
+/// run out the board and score each hand. Whole deals are redrawn on a
+/// collision, so the combos come out jointly uniform.
+fn deal_scores<R: Rng>(holdings: &[Vec<[u8; 2]>], board: &[u8], rng: &mut R) -> GtoResult<Vec<u32>> {
+    let board_mask = board.iter().fold(0u64, |mask, &c| mask | (1 << c));
+    for _ in 0..MAX_DEAL_ATTEMPTS {
+        let mut used = board_mask;
+        let mut hands = Vec::with_capacity(holdings.len());
+        for combos in holdings {
+            let combo = combos[rng.gen_range(0..combos.len())];
+            let mask = (1u64 << combo[0]) | (1u64 << combo[1]);
+            if used & mask != 0 {
+                break;
+            }
+            used |= mask;
+            hands.push(combo);
+        }
+        if hands.len() < holdings.len() {
+            continue;
+        }
+
+        let mut deck: Vec<u8> = (0..52).filter(|&c| used & (1 << c) == 0).collect();
+        let (runout, _) = deck.partial_shuffle(rng, 5 - board.len());
+        let mut cards = [0u8; 7];
+        for (slot, &c) in cards[2..].iter_mut().zip(board.iter().chain(runout.iter())) {
+            *slot = c;
+        }
+        return Ok(hands
+            .iter()
+            .map(|hand| {
+                cards[..2].copy_from_slice(hand);
+                RuleSet::Holdem.evaluate(&cards)
+            })
+            .collect());
+    }
+    Err(GtoError::NoValidCombos)
+}
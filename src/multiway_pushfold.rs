@@ -0,0 +1,346 @@
+//! N-way push/fold solver for short-stacked tournament spots.
+//!
+//! Players act in turn and either go all in or fold; once someone is all in,
+//! everyone after calls or folds. An info set is a hand bucket, the seat
+//! acting (`InfoSetKey::position`) and which earlier seats are already all
+//! in (`node_id`, one bit per seat). Each iteration deals real cards, so
+//! blockers come out exact, and runs CFR+ over the whole shove/fold tree
+//! for that deal.
+//!
+//! All-in showdowns are valued from the heads-up preflop equity matrix.
+//! With three or more hands in, each hand's share of a pot is the product
+//! of its head-to-head equities, normalized over the hands contesting it:
+//! exact for two hands, an approximation beyond. Side pots follow the
+//! stacks. Solved 3-handed (BTN, SB, BB) or heads-up for now.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::cfr::{CfrTrainer, InfoSetKey};
+use crate::game_tree::{bucket_to_hand, combo_to_bucket, EquityTable, NUM_HANDS};
+use crate::ranges::combo_count;
+
+/// Most players the solver takes.
+pub const MAX_PLAYERS: usize = 3;
+
+/// Seat names for a full `MAX_PLAYERS` table; smaller tables drop the
+/// early seats.
+const POSITION_NAMES: [&str; MAX_PLAYERS] = ["BTN", "SB", "BB"];
+
+/// Per-seat values at a node, seat order.
+type SeatValues = [f64; MAX_PLAYERS];
+
+/// A push/fold spot: every stack in big blinds before anything is posted,
+/// in the order the players act. The last two seats post the small and big
+/// blind and every seat posts `ante`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiwaySpot {
+    pub stacks: Vec<f64>,
+    pub ante: f64,
+}
+
+impl MultiwaySpot {
+    pub fn new(stacks: Vec<f64>, ante: f64) -> Result<Self, String> {
+        if !(2..=MAX_PLAYERS).contains(&stacks.len()) {
+            return Err(format!(
+                "Push/fold needs 2 to {} players, got {}",
+                MAX_PLAYERS,
+                stacks.len()
+            ));
+        }
+        if !ante.is_finite() || ante < 0.0 {
+            return Err(format!("Ante must be non-negative, got {}", ante));
+        }
+        let spot = MultiwaySpot { stacks, ante };
+        for (seat, &stack) in spot.stacks.iter().enumerate() {
+            if !stack.is_finite() || stack <= spot.posted(seat) {
+                return Err(format!(
+                    "{} needs more than the {}bb they post, got {}bb",
+                    spot.position_name(seat),
+                    spot.posted(seat),
+                    stack
+                ));
+            }
+        }
+        Ok(spot)
+    }
+
+    pub fn num_players(&self) -> usize {
+        self.stacks.len()
+    }
+
+    /// "BTN", "SB" or "BB".
+    pub fn position_name(&self, seat: usize) -> &'static str {
+        POSITION_NAMES[MAX_PLAYERS - self.num_players() + seat]
+    }
+
+    /// Blind plus ante `seat` puts in before the deal.
+    pub fn posted(&self, seat: usize) -> f64 {
+        let blind = match self.num_players() - seat {
+            1 => 1.0,
+            2 => 0.5,
+            _ => 0.0,
+        };
+        blind + self.ante
+    }
+
+    /// Chips each seat wins or loses, in bb, once the hand ends with the
+    /// seats in `live` (a bit per seat) still in and `all_in` of them all
+    /// in; everyone else has folded what they posted. `hands` are the
+    /// seats' hand buckets.
+    pub fn payoffs(&self, live: u32, all_in: u32, hands: &[usize], table: &EquityTable) -> SeatValues {
+        let n = self.num_players();
+        let seats = 0..n;
+        let has = |mask: u32, seat: usize| mask >> seat & 1 == 1;
+        let put_in: Vec<f64> = seats
+            .clone()
+            .map(|s| if has(all_in, s) { self.stacks[s] } else { self.posted(s) })
+            .collect();
+
+        // One pot per distinct amount the live seats put in, smallest first
+        let mut levels: Vec<f64> = seats.clone().filter(|&s| has(live, s)).map(|s| put_in[s]).collect();
+        levels.sort_by(f64::total_cmp);
+        levels.dedup();
+
+        let mut won = [0.0; MAX_PLAYERS];
+        let mut prev = 0.0;
+        for &level in &levels {
+            let pot: f64 = put_in.iter().map(|&p| p.min(level) - p.min(prev)).sum();
+            let contesting: Vec<usize> = seats.clone().filter(|&s| has(live, s) && put_in[s] >= level).collect();
+            for (&s, share) in contesting.iter().zip(pot_shares(&contesting, hands, table)) {
+                won[s] += pot * share;
+            }
+            prev = level;
+        }
+
+        // Anything a folded seat put in above the biggest pot goes back to it
+        let mut values = [0.0; MAX_PLAYERS];
+        for s in seats {
+            values[s] = won[s] - put_in[s].min(prev);
+        }
+        values
+    }
+}
+
+/// Each contesting seat's share of a pot: head-to-head equities multiplied
+/// together and normalized.
+fn pot_shares(contesting: &[usize], hands: &[usize], table: &EquityTable) -> Vec<f64> {
+    let weights: Vec<f64> = contesting
+        .iter()
+        .map(|&s| {
+            contesting
+                .iter()
+                .filter(|&&o| o != s)
+                .map(|&o| table.eq(hands[s], hands[o]))
+                .product()
+        })
+        .collect();
+    let total: f64 = weights.iter().sum();
+    if total > 0.0 {
+        weights.iter().map(|w| w / total).collect()
+    } else {
+        vec![1.0 / contesting.len() as f64; contesting.len()]
+    }
+}
+
+/// One decision point: `seat` to act with the seats in `all_in` (a bit per
+/// seat) already all in and every other earlier seat folded.
+#[derive(Debug, Clone)]
+pub struct MultiwayNode {
+    pub seat: usize,
+    pub all_in: u32,
+    /// All-in frequency (shove, or call once someone is in) per hand bucket.
+    pub strategy: Vec<f64>,
+}
+
+/// Solved strategies for every decision point of a spot.
+pub struct MultiwayPushFoldResult {
+    pub spot: MultiwaySpot,
+    /// Number of deals sampled.
+    pub iterations: usize,
+    /// Every decision point, seat by seat.
+    pub nodes: Vec<MultiwayNode>,
+}
+
+impl MultiwayPushFoldResult {
+    pub fn node(&self, seat: usize, all_in: u32) -> Option<&MultiwayNode> {
+        self.nodes.iter().find(|n| n.seat == seat && n.all_in == all_in)
+    }
+
+    /// `seat`'s shove frequencies when everyone before it folded. None for
+    /// the big blind, which wins the blinds when folded to.
+    pub fn shove_strategy(&self, seat: usize) -> Option<&[f64]> {
+        self.node(seat, 0).map(|n| n.strategy.as_slice())
+    }
+
+    /// Share of all combos that go all in at `node`, in percent, counting
+    /// mixed hands by their frequency.
+    pub fn all_in_pct(&self, node: &MultiwayNode) -> f64 {
+        let combos: f64 = (0..NUM_HANDS)
+            .map(|h| node.strategy[h] * combo_count(&bucket_to_hand(h)) as f64)
+            .sum();
+        combos / 1326.0 * 100.0
+    }
+
+    /// "SB first in", "BB vs BTN shove, SB call".
+    pub fn describe(&self, node: &MultiwayNode) -> String {
+        let name = self.spot.position_name(node.seat);
+        if node.all_in == 0 {
+            return format!("{} first in", name);
+        }
+        let actions: Vec<String> = (0..node.seat)
+            .filter(|&s| node.all_in >> s & 1 == 1)
+            .enumerate()
+            .map(|(i, s)| {
+                let verb = if i == 0 { "shove" } else { "call" };
+                format!("{} {}", self.spot.position_name(s), verb)
+            })
+            .collect();
+        format!("{} vs {}", name, actions.join(", "))
+    }
+
+    /// Display the all-in range of every decision point.
+    pub fn display(&self) {
+        use crate::display::range_grid;
+        use colored::Colorize;
+
+        let stacks: Vec<String> = (0..self.spot.num_players())
+            .map(|s| format!("{} {}bb", self.spot.position_name(s), self.spot.stacks[s]))
+            .collect();
+        println!();
+        println!(
+            "  {} {}-way Push/Fold  |  {}  |  Ante: {}bb  |  {} deals",
+            "GTO".bold(),
+            self.spot.num_players(),
+            stacks.join(", "),
+            self.spot.ante,
+            self.iterations,
+        );
+
+        for node in &self.nodes {
+            let range: Vec<String> = (0..NUM_HANDS)
+                .filter(|&h| node.strategy[h] > 0.5)
+                .map(bucket_to_hand)
+                .collect();
+            let verb = if node.all_in == 0 { "Shove" } else { "Call" };
+            println!();
+            println!(
+                "{}",
+                range_grid(
+                    &range,
+                    &format!("{}: {} ({:.1}% of hands)", self.describe(node), verb, self.all_in_pct(node))
+                )
+            );
+        }
+        println!();
+    }
+}
+
+/// Solve a push/fold spot with `iterations` sampled deals of CFR+.
+pub fn solve_multiway_push_fold(
+    spot: &MultiwaySpot,
+    iterations: usize,
+    table: &EquityTable,
+) -> MultiwayPushFoldResult {
+    let n = spot.num_players();
+    let mut trainer = CfrTrainer::new();
+    let mut rng = StdRng::seed_from_u64(n as u64);
+    let mut deck: Vec<u8> = (0..52).collect();
+    let mut hands = [0usize; MAX_PLAYERS];
+
+    for iter in 0..iterations {
+        // Partial Fisher-Yates: two cards per seat
+        for k in 0..2 * n {
+            let swap = rng.gen_range(k..deck.len());
+            deck.swap(k, swap);
+        }
+        for (seat, hand) in hands.iter_mut().take(n).enumerate() {
+            *hand = combo_to_bucket(deck[2 * seat], deck[2 * seat + 1]);
+        }
+        let reach = [1.0; MAX_PLAYERS];
+        traverse(&mut trainer, spot, table, &hands[..n], 0, 0, &reach, iter + 1);
+    }
+
+    let nodes = decision_points(n)
+        .map(|(seat, all_in)| MultiwayNode {
+            seat,
+            all_in,
+            strategy: (0..NUM_HANDS)
+                .map(|h| trainer.get_average_strategy(&info_set(h, seat, all_in), 2)[0])
+                .collect(),
+        })
+        .collect();
+
+    MultiwayPushFoldResult {
+        spot: spot.clone(),
+        iterations,
+        nodes,
+    }
+}
+
+/// Every (seat, all-in seats before it) that is a decision, seat by seat.
+/// The big blind folded to has none.
+fn decision_points(n: usize) -> impl Iterator<Item = (usize, u32)> {
+    (0..n).flat_map(move |seat| {
+        (0..1u32 << seat)
+            .filter(move |&all_in| !(seat == n - 1 && all_in == 0))
+            .map(move |all_in| (seat, all_in))
+    })
+}
+
+fn info_set(hand: usize, seat: usize, all_in: u32) -> InfoSetKey {
+    InfoSetKey {
+        hand_bucket: hand as u16,
+        node_id: all_in,
+        position: seat as u8,
+    }
+}
+
+/// CFR+ for every seat at once from `seat` on, returning each seat's value.
+/// `reach` is each seat's probability of playing to here.
+#[allow(clippy::too_many_arguments)]
+fn traverse(
+    trainer: &mut CfrTrainer,
+    spot: &MultiwaySpot,
+    table: &EquityTable,
+    hands: &[usize],
+    seat: usize,
+    all_in: u32,
+    reach: &SeatValues,
+    iteration: usize,
+) -> SeatValues {
+    let n = hands.len();
+    if seat == n {
+        return spot.payoffs(all_in, all_in, hands, table);
+    }
+    if seat == n - 1 && all_in == 0 {
+        // Folded to the big blind
+        return spot.payoffs(1 << seat, 0, hands, table);
+    }
+
+    let key = info_set(hands[seat], seat, all_in);
+    let strategy = trainer.get_strategy(&key, 2);
+
+    let mut children = [[0.0; MAX_PLAYERS]; 2];
+    for (a, child) in children.iter_mut().enumerate() {
+        let mut next_reach = *reach;
+        next_reach[seat] *= strategy[a];
+        let next_all_in = if a == 0 { all_in | 1 << seat } else { all_in };
+        *child = traverse(trainer, spot, table, hands, seat + 1, next_all_in, &next_reach, iteration);
+    }
+
+    let mut value = [0.0; MAX_PLAYERS];
+    for (v, (&allin, &fold)) in value.iter_mut().zip(children[0].iter().zip(&children[1])) {
+        *v = strategy[0] * allin + strategy[1] * fold;
+    }
+
+    let others: f64 = (0..n).filter(|&s| s != seat).map(|s| reach[s]).product();
+    trainer.update(
+        &key,
+        &[children[0][seat] * others, children[1][seat] * others],
+        value[seat] * others,
+        reach[seat],
+        iteration,
+    );
+    value
+}
@@ -4,7 +4,7 @@ use std::io::{self, BufRead, Write};
 use colored::Colorize;
 
 use crate::cards::{parse_board, parse_card, simplify_hand, Card};
-use crate::display::{board_display, equity_bar, styled_action};
+use crate::display::{best_hand_display, board_display, equity_bar, styled_action};
 use crate::equity::equity_vs_range;
 use crate::hand_evaluator::{evaluate_hand, HandCategory, HandResult};
 use crate::math_engine::{break_even_pct, spr as calc_spr, SprZone};
@@ -658,6 +658,10 @@ fn show_street_advice(
     table_size: &str,
     writer: &mut dyn Write,
 ) {
+    if let Some(best) = best_hand_display(hole_cards, board) {
+        writeln!(writer, "  Your best hand: {}", best).ok();
+    }
+
     // Try solver-backed advice
     let iterations = match street {
         "flop" => 500000,
@@ -722,7 +726,7 @@ fn show_heuristic_analysis(
 
     // Equity
     let villain_range = estimate_villain_range(situation, hero_pos, villain_pos, hole_cards, table_size);
-    let equity = match equity_vs_range(hole_cards, &villain_range, Some(board), 10000) {
+    let equity = match equity_vs_range(hole_cards, &villain_range, Some(board), 10000, true) {
         Ok(result) => {
             let eq = result.equity();
             writeln!(writer, "  Equity: {}", equity_bar(eq, 30)).ok();
@@ -8,10 +8,12 @@
 //! earlier street are replaced with Chance nodes that branch into the
 //! next street's action subtrees.
 
-use crate::card_encoding::remaining_deck;
+use serde::{Deserialize, Serialize};
+
+use crate::card_encoding::{index_to_card, remaining_deck};
 
 /// Which player is acting.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Player {
     OOP,
     IP,
@@ -34,7 +36,7 @@ impl Player {
 }
 
 /// An action a player can take at an action node.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Action {
     Check,
     Bet(f64),
@@ -53,20 +55,228 @@ impl Action {
             Action::Fold => "Fold".to_string(),
         }
     }
+
+    /// Chips the actor puts into the pot with this action.
+    pub fn chips(&self) -> f64 {
+        match self {
+            Action::Bet(amt) | Action::Call(amt) | Action::Raise(amt) => *amt,
+            Action::Check | Action::Fold => 0.0,
+        }
+    }
+
+    /// Label in pot terms for display: "Bet 67% (6.7)", "Raise 2.5x (25.0)",
+    /// or "All-in (50.0)" when a bet or raise uses the actor's whole
+    /// `remaining` stack. `pot_before` is the pot when the action is taken
+    /// and `street_in` holds the actor's, then the opponent's, chips already
+    /// in on this street, so a raise reads as a multiple of the bet it
+    /// raises. Check, call and fold keep their plain labels.
+    pub fn label_with_pot(&self, pot_before: f64, street_in: [f64; 2], remaining: f64) -> String {
+        match self {
+            Action::Bet(amt) | Action::Raise(amt) if *amt >= remaining - 0.005 => {
+                format!("All-in ({:.1})", amt)
+            }
+            Action::Bet(amt) if pot_before > 0.0 => {
+                format!("Bet {:.0}% ({:.1})", amt / pot_before * 100.0, amt)
+            }
+            Action::Raise(amt) if street_in[1] > 0.0 => {
+                format!("Raise {:.1}x ({:.1})", (street_in[0] + amt) / street_in[1], amt)
+            }
+            _ => self.label(),
+        }
+    }
+}
+
+/// An opening bet size, resolved to chips when the node is built.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum BetSize {
+    /// Fraction of the current pot (1.5 = 150% overbet).
+    Pot(f64),
+    /// The same pot fraction bet on each of this many streets (this one
+    /// included) gets the remaining stack in by the last one.
+    Geometric(u32),
+    /// The bettor's whole remaining stack.
+    AllIn,
+}
+
+impl BetSize {
+    /// Chips to bet into `pot` with `remaining` behind, before rounding.
+    pub fn amount(self, pot: f64, remaining: f64) -> f64 {
+        match self {
+            BetSize::Pot(frac) => pot * frac,
+            BetSize::Geometric(streets) => pot * geometric_fraction(pot, remaining, streets),
+            BetSize::AllIn => remaining,
+        }
+    }
+}
+
+/// Pot fraction `f` that, bet and called on each of `streets` streets,
+/// commits exactly `stack`: each bet-call multiplies the pot by (1 + 2f), so
+/// `pot * ((1 + 2f)^streets - 1) / 2 = stack`.
+pub fn geometric_fraction(pot: f64, stack: f64, streets: u32) -> f64 {
+    if pot <= 0.0 || streets == 0 {
+        return 0.0;
+    }
+    ((1.0 + 2.0 * stack / pot).powf(1.0 / streets as f64) - 1.0) / 2.0
+}
+
+impl From<f64> for BetSize {
+    fn from(frac: f64) -> Self {
+        BetSize::Pot(frac)
+    }
+}
+
+impl std::fmt::Display for BetSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BetSize::Pot(frac) => write!(f, "{}", frac),
+            BetSize::Geometric(streets) => write!(f, "geo{}", streets),
+            BetSize::AllIn => write!(f, "allin"),
+        }
+    }
+}
+
+impl std::str::FromStr for BetSize {
+    type Err = String;
+
+    /// Parse "0.75", "75%", "geo2" or "allin".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let lower = s.to_ascii_lowercase();
+        if lower == "allin" || lower == "all-in" {
+            return Ok(BetSize::AllIn);
+        }
+        if let Some(n) = lower.strip_prefix("geo") {
+            return match n.parse::<u32>() {
+                Ok(streets) if streets > 0 => Ok(BetSize::Geometric(streets)),
+                _ => Err(format!("Invalid bet size '{}'", s)),
+            };
+        }
+        let (num, scale) = match s.strip_suffix('%') {
+            Some(pct) => (pct, 0.01),
+            None => (s, 1.0),
+        };
+        num.parse::<f64>()
+            .map(|v| BetSize::Pot(v * scale))
+            .map_err(|_| format!("Invalid bet size '{}'", s))
+    }
+}
+
+impl From<BetSize> for String {
+    fn from(size: BetSize) -> String {
+        size.to_string()
+    }
+}
+
+impl TryFrom<String> for BetSize {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// How raise sizes are expressed when facing a bet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RaiseSizing {
+    /// Raise increment as a fraction of the pot after calling.
+    PotFraction(Vec<f64>),
+    /// Street total raised to as a multiple of the opponent's street total
+    /// (3.0 facing a bet of 10 = raise to 30; facing that, 3.0 = raise to 90).
+    BetMultiple(Vec<f64>),
+}
+
+impl Default for RaiseSizing {
+    fn default() -> Self {
+        RaiseSizing::PotFraction(Vec::new())
+    }
+}
+
+impl RaiseSizing {
+    /// Raise increments on top of the call, before rounding and clamping.
+    /// `facing_total` is what the opponent has put in this street.
+    fn increments(&self, pot_after_call: f64, facing_total: f64) -> Vec<f64> {
+        match self {
+            RaiseSizing::PotFraction(fracs) => fracs.iter().map(|f| pot_after_call * f).collect(),
+            RaiseSizing::BetMultiple(multiples) => {
+                multiples.iter().map(|m| facing_total * (m - 1.0)).collect()
+            }
+        }
+    }
+
+    /// Reject non-positive or non-finite pot fractions and multiples that
+    /// don't raise.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            RaiseSizing::PotFraction(fracs) => match fracs.iter().find(|f| !f.is_finite() || **f <= 0.0) {
+                Some(f) => Err(format!("Raise sizes must be positive pot fractions, got {}", f)),
+                None => Ok(()),
+            },
+            RaiseSizing::BetMultiple(multiples) => match multiples.iter().find(|m| !m.is_finite() || **m <= 1.0) {
+                Some(m) => Err(format!("Raise multiples must be above 1x, got {}x", m)),
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+impl std::str::FromStr for RaiseSizing {
+    type Err = String;
+
+    /// Parse comma-separated pot fractions ("0.5,1.0", "100%") or bet
+    /// multiples ("2.5x,3x"); "none" is no raise sizes. One list can't mix
+    /// the two.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        if trimmed.is_empty() || trimmed.eq_ignore_ascii_case("none") {
+            return Ok(RaiseSizing::default());
+        }
+        let lower = trimmed.to_ascii_lowercase();
+        let entries: Vec<&str> = lower.split(',').map(str::trim).collect();
+        if entries.iter().all(|e| e.ends_with('x')) {
+            let multiples = entries
+                .iter()
+                .map(|e| e[..e.len() - 1].parse::<f64>().map_err(|_| format!("Invalid raise size '{}'", e)))
+                .collect::<Result<_, _>>()?;
+            return Ok(RaiseSizing::BetMultiple(multiples));
+        }
+        if entries.iter().any(|e| e.ends_with('x')) {
+            return Err(format!("Raise sizes '{}' mix multiples and pot fractions", trimmed));
+        }
+        let fracs = entries
+            .iter()
+            .map(|e| match e.parse::<BetSize>() {
+                Ok(BetSize::Pot(frac)) => Ok(frac),
+                _ => Err(format!("Invalid raise size '{}'", e)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(RaiseSizing::PotFraction(fracs))
+    }
 }
 
 /// How a terminal node was reached.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TerminalType {
     Showdown,
     Fold { folder: Player },
 }
 
 /// A node in the postflop game tree.
-#[derive(Debug)]
+///
+/// `invested` is what each player has put in since the tree's investment
+/// baseline, and `pot` includes it. Payoffs are measured from that baseline:
+/// winning a terminal is worth `pot - invested[me]` and losing it costs
+/// `invested[me]`. Anything in the pot beyond both players' `invested` is
+/// dead money from before the baseline. `build_tree` puts the baseline at the
+/// root; `build_tree_with_invested` and turn trees keep it at an earlier
+/// point, so river subtrees carry the turn's investments forward.
+///
+/// Serializes through `FlatTree`, so saved trees don't nest one level per
+/// node.
+#[derive(Debug, Clone, PartialEq)]
 pub enum TreeNode {
     Action {
-        node_id: u16,
+        node_id: u32,
         player: Player,
         pot: f64,
         stacks: [f64; 2],
@@ -86,6 +296,10 @@ pub enum TreeNode {
         invested: [f64; 2],
         /// Possible cards to deal (u8 indices, 0-51).
         cards: Vec<u8>,
+        /// How many deck cards each entry of `cards` stands for (same
+        /// order): 1, or the size of a class of strategically identical
+        /// cards that share one subtree.
+        weights: Vec<u32>,
         /// One child subtree per card (same order as `cards`).
         children: Vec<TreeNode>,
     },
@@ -115,88 +329,334 @@ impl TreeNode {
             TreeNode::Terminal { .. } => 1,
         }
     }
+
+    /// Pot-relative labels for an action node's actions (empty for other
+    /// nodes), given each player's chips already in on this street indexed
+    /// by `Player::index()`. See `Action::label_with_pot`.
+    pub fn pot_labels(&self, street_in: [f64; 2]) -> Vec<String> {
+        match self {
+            TreeNode::Action { player, pot, stacks, actions, .. } => {
+                let (pi, oi) = (player.index(), player.opponent().index());
+                let own_first = [street_in[pi], street_in[oi]];
+                // All-in means all the opponent can still call
+                let to_call = (street_in[oi] - street_in[pi]).max(0.0);
+                let remaining = stacks[pi].min(stacks[oi] + to_call);
+                actions
+                    .iter()
+                    .map(|a| a.label_with_pot(*pot, own_first, remaining))
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Indented outline of the tree: node id, acting player, pot and stacks,
+    /// then each action with its amount and the subtree it leads to. Action
+    /// nodes deeper than `max_depth` are elided. Chance nodes show a single
+    /// representative card rather than every river.
+    pub fn to_text(&self, max_depth: usize) -> String {
+        let mut out = String::new();
+        self.write_text(&mut out, 0, max_depth, "");
+        out
+    }
+
+    fn write_text(&self, out: &mut String, depth: usize, max_depth: usize, indent: &str) {
+        use std::fmt::Write;
+
+        match self {
+            TreeNode::Action { node_id, player, pot, stacks, actions, children } => {
+                if depth > max_depth {
+                    let _ = writeln!(out, "{}...", indent);
+                    return;
+                }
+                let _ = writeln!(
+                    out,
+                    "{}#{} {:?}  pot {:.1}  stacks {:.1}/{:.1}",
+                    indent, node_id, player, pot, stacks[0], stacks[1]
+                );
+                let child_indent = format!("{}    ", indent);
+                for (action, child) in actions.iter().zip(children) {
+                    match child {
+                        TreeNode::Terminal { .. } => {
+                            let _ = writeln!(out, "{}  {} -> {}", indent, action.label(), child.summary());
+                        }
+                        _ => {
+                            let _ = writeln!(out, "{}  {}", indent, action.label());
+                            child.write_text(out, depth + 1, max_depth, &child_indent);
+                        }
+                    }
+                }
+            }
+            TreeNode::Terminal { .. } => {
+                let _ = writeln!(out, "{}{}", indent, self.summary());
+            }
+            TreeNode::Chance { cards, children, .. } => {
+                let _ = writeln!(out, "{}{}", indent, self.summary());
+                if let (Some(&card), Some(child)) = (cards.first(), children.first()) {
+                    let _ = writeln!(out, "{}  river {} (1 of {} shown)", indent, index_to_card(card), cards.len());
+                    child.write_text(out, depth, max_depth, &format!("{}    ", indent));
+                }
+            }
+        }
+    }
+
+    /// Graphviz DOT for the tree. Action nodes are boxes labeled with id,
+    /// player and pot; terminals are ellipses labeled with type and pot;
+    /// chance nodes show one representative card subtree.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph tree {\n    node [fontname=\"monospace\"];\n");
+        let mut next_name = 0;
+        self.write_dot(&mut out, &mut next_name);
+        out.push_str("}\n");
+        out
+    }
+
+    /// Write this subtree's DOT statements and return its node name.
+    fn write_dot(&self, out: &mut String, next_name: &mut usize) -> String {
+        use std::fmt::Write;
+
+        let name = format!("n{}", *next_name);
+        *next_name += 1;
+        match self {
+            TreeNode::Action { node_id, player, pot, actions, children, .. } => {
+                let _ = writeln!(
+                    out,
+                    "    {} [shape=box, label=\"#{} {:?}\\npot {:.1}\"];",
+                    name, node_id, player, pot
+                );
+                for (action, child) in actions.iter().zip(children) {
+                    let child_name = child.write_dot(out, next_name);
+                    let _ = writeln!(out, "    {} -> {} [label=\"{}\"];", name, child_name, action.label());
+                }
+            }
+            TreeNode::Terminal { .. } => {
+                let _ = writeln!(out, "    {} [shape=ellipse, label=\"{}\"];", name, self.summary());
+            }
+            TreeNode::Chance { cards, children, .. } => {
+                let _ = writeln!(out, "    {} [shape=diamond, label=\"{}\"];", name, self.summary());
+                if let (Some(&card), Some(child)) = (cards.first(), children.first()) {
+                    let child_name = child.write_dot(out, next_name);
+                    let _ = writeln!(
+                        out,
+                        "    {} -> {} [label=\"{} (+{} more)\"];",
+                        name,
+                        child_name,
+                        index_to_card(card),
+                        cards.len() - 1
+                    );
+                }
+            }
+        }
+        name
+    }
+
+    /// One-line description of a terminal or chance node.
+    fn summary(&self) -> String {
+        match self {
+            TreeNode::Terminal { terminal_type: TerminalType::Showdown, pot, .. } => {
+                format!("showdown, pot {:.1}", pot)
+            }
+            TreeNode::Terminal { terminal_type: TerminalType::Fold { folder }, pot, .. } => {
+                format!("{:?} folds, pot {:.1}", folder, pot)
+            }
+            TreeNode::Chance { pot, cards, .. } => {
+                format!("chance, pot {:.1}: {} river cards", pot, cards.len())
+            }
+            TreeNode::Action { node_id, player, .. } => format!("#{} {:?}", node_id, player),
+        }
+    }
 }
 
 /// Configuration for building a postflop game tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TreeConfig {
-    /// Bet sizes as fractions of pot (e.g., [0.33, 0.67, 1.0]).
-    pub bet_sizes: Vec<f64>,
-    /// Raise sizes as fractions of pot when facing a bet.
-    pub raise_sizes: Vec<f64>,
+    /// Opening bet sizes per player, indexed by `Player::index()`: pot
+    /// fractions (e.g., 0.33, 1.5), geometric or all-in.
+    pub bet_sizes: [Vec<BetSize>; 2],
+    /// Raise sizes per player when facing a bet, as pot fractions or
+    /// multiples of the bet faced.
+    pub raise_sizes: [RaiseSizing; 2],
     /// Maximum number of raises per street (typically 3).
     pub max_raises: usize,
     /// Starting pot size.
     pub starting_pot: f64,
-    /// Effective stack remaining (beyond what's already in the pot).
-    pub effective_stack: f64,
+    /// Each player's stack behind (beyond what's already in the pot),
+    /// indexed by `Player::index()`. Bets are capped at what the opponent
+    /// can call, so only the shorter stack can go all-in.
+    pub stacks: [f64; 2],
     /// Whether to add all-in as an option when it's not already covered.
     pub add_allin: bool,
+    /// Smallest bet or raise increment allowed; smaller computed sizes are
+    /// dropped (all-ins below this are still allowed).
+    pub min_bet: f64,
+    /// Round every bet and raise increment to the nearest multiple of this
+    /// amount (e.g., 0.5 for half-bb chips) before clamping to the stack.
+    pub bet_round_to: Option<f64>,
+    /// Relative tolerance for merging bet and raise amounts at a node: an
+    /// amount within this fraction of one already generated, or of the
+    /// all-in, is treated as the same action.
+    pub merge_tolerance: f64,
+    /// With `add_allin`, an extra all-in is only offered when the stack is
+    /// more than this fraction of the pot.
+    pub allin_threshold: f64,
+    /// Convert any bet or raise that would put in more than this percentage
+    /// of the acting player's remaining stack into an all-in (e.g., 90.0).
+    pub force_allin_above_stack_pct: Option<f64>,
+    /// Whether each player may raise when facing a bet, indexed by
+    /// `Player::index()`. A player who can't raise only folds or calls.
+    pub can_raise: [bool; 2],
+    /// Most raises OOP may make in a line that began with a check-raise
+    /// (OOP checks, IP bets), on top of `max_raises`. `Some(1)` allows the
+    /// check-raise and IP's re-raise but no re-raise from OOP after it;
+    /// `None` leaves check-raise lines capped by `max_raises` alone.
+    pub max_check_raises: Option<usize>,
 }
 
 impl TreeConfig {
+    /// Amounts within 2% of each other (or of the stack) merge.
+    pub const DEFAULT_MERGE_TOLERANCE: f64 = 0.02;
+
+    /// Stack-to-pot fraction above which the extra all-in is offered.
+    pub const DEFAULT_ALLIN_THRESHOLD: f64 = 0.2;
+
+    /// The shorter of the two stacks: the most either player can win or lose.
+    pub fn effective_stack(&self) -> f64 {
+        self.stacks[0].min(self.stacks[1])
+    }
+
+    /// Whether `amount` out of `remaining` crosses the forced all-in line.
+    fn forces_allin(&self, amount: f64, remaining: f64) -> bool {
+        self.force_allin_above_stack_pct
+            .is_some_and(|pct| amount > remaining * pct / 100.0)
+    }
+
+    /// Whether two bet or raise amounts are close enough to be one action.
+    /// Never finer than the 0.01 chip resolution used elsewhere in the tree.
+    fn amounts_merge(&self, a: f64, b: f64) -> bool {
+        (a - b).abs() < (self.merge_tolerance * a.max(b)).max(0.01)
+    }
+
+    /// Apply rounding and the minimum-bet filter to a raw bet or raise
+    /// increment. Returns `None` if the size should be dropped.
+    fn size_amount(&self, raw: f64) -> Option<f64> {
+        let amount = match self.bet_round_to {
+            Some(step) if step > 0.0 => (raw / step).round() * step,
+            _ => raw,
+        };
+        if amount < 0.01 || amount < self.min_bet {
+            None
+        } else {
+            Some(amount)
+        }
+    }
+
     pub fn default_river(starting_pot: f64, effective_stack: f64) -> Self {
         TreeConfig {
-            bet_sizes: vec![0.33, 0.67, 1.0],
-            raise_sizes: vec![1.0],
+            bet_sizes: same_for_both(vec![
+                BetSize::Pot(0.33),
+                BetSize::Pot(0.67),
+                BetSize::Pot(1.0),
+            ]),
+            raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![1.0])),
             max_raises: 3,
             starting_pot,
-            effective_stack,
+            stacks: [effective_stack; 2],
             add_allin: true,
+            min_bet: 0.0,
+            bet_round_to: None,
+            merge_tolerance: Self::DEFAULT_MERGE_TOLERANCE,
+            allin_threshold: Self::DEFAULT_ALLIN_THRESHOLD,
+            force_allin_above_stack_pct: None,
+            can_raise: [true; 2],
+            max_check_raises: None,
         }
     }
 
     pub fn default_turn(starting_pot: f64, effective_stack: f64) -> Self {
         TreeConfig {
-            bet_sizes: vec![0.5, 1.0],
-            raise_sizes: vec![1.0],
+            bet_sizes: same_for_both(vec![BetSize::Pot(0.5), BetSize::Pot(1.0)]),
+            raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![1.0])),
             max_raises: 2,
             starting_pot,
-            effective_stack,
+            stacks: [effective_stack; 2],
             add_allin: true,
+            min_bet: 0.0,
+            bet_round_to: None,
+            merge_tolerance: Self::DEFAULT_MERGE_TOLERANCE,
+            allin_threshold: Self::DEFAULT_ALLIN_THRESHOLD,
+            force_allin_above_stack_pct: None,
+            can_raise: [true; 2],
+            max_check_raises: None,
         }
     }
 }
 
+/// One size list used for both players, as `[oop, ip]`.
+pub fn same_for_both<T: Clone>(sizes: T) -> [T; 2] {
+    [sizes.clone(), sizes]
+}
+
 /// Configuration for a turn+river tree.
 pub struct TurnTreeConfig {
     pub turn: TreeConfig,
-    pub river_bet_sizes: Vec<f64>,
-    pub river_raise_sizes: Vec<f64>,
+    pub river_bet_sizes: [Vec<BetSize>; 2],
+    pub river_raise_sizes: [RaiseSizing; 2],
     pub river_max_raises: usize,
     /// 4-card turn board as u8 indices (used to enumerate river cards).
     pub board: Vec<u8>,
+    /// River cards to deal, each with how many deck cards it stands for.
+    /// `None` deals every card off the board once.
+    pub river_cards: Option<Vec<(u8, u32)>>,
 }
 
 impl TurnTreeConfig {
     pub fn new(board: Vec<u8>, starting_pot: f64, effective_stack: f64) -> Self {
         TurnTreeConfig {
             turn: TreeConfig::default_turn(starting_pot, effective_stack),
-            river_bet_sizes: vec![0.33, 0.67, 1.0],
-            river_raise_sizes: vec![1.0],
+            river_bet_sizes: same_for_both(vec![
+                BetSize::Pot(0.33),
+                BetSize::Pot(0.67),
+                BetSize::Pot(1.0),
+            ]),
+            river_raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![1.0])),
             river_max_raises: 3,
             board,
+            river_cards: None,
         }
     }
 }
 
 /// Build a postflop game tree from the given config.
 /// Returns the root node and the total number of action nodes.
-pub fn build_tree(config: &TreeConfig) -> (TreeNode, u16) {
-    let mut next_id: u16 = 0;
-    let invested = [0.0, 0.0]; // how much each player has put in beyond starting pot
-    let root = build_node(
+pub fn build_tree(config: &TreeConfig) -> (TreeNode, u32) {
+    build_tree_with_invested(config, [0.0, 0.0])
+}
+
+/// Build a tree whose root already has `invested` chips from each player in
+/// the pot, e.g. from earlier streets. `config.starting_pot` must include
+/// them. Payoffs stay measured from the point where `invested` was zero (see
+/// `TreeNode`), so prior chips count as lost on a fold.
+pub fn build_tree_with_invested(config: &TreeConfig, invested: [f64; 2]) -> (TreeNode, u32) {
+    let mut next_id: u32 = 0;
+    let root = build_street(config, invested, &mut next_id);
+    (root, next_id)
+}
+
+/// One street of betting from `config.starting_pot` and `config.stacks`,
+/// with OOP first to act and `invested` carried in from before the street.
+fn build_street(config: &TreeConfig, invested: [f64; 2], next_id: &mut u32) -> TreeNode {
+    build_node(
         config,
         Player::OOP,
         config.starting_pot,
-        [config.effective_stack; 2],
+        config.stacks,
         invested,
         0,      // raises this street
         false,  // facing_bet
         0.0,    // amount_to_call
         false,  // check_back (IP checked after OOP check?)
-        &mut next_id,
-    );
-    (root, next_id)
+        next_id,
+    )
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -210,13 +670,14 @@ fn build_node(
     facing_bet: bool,
     amount_to_call: f64,
     oop_checked: bool,
-    next_id: &mut u16,
+    next_id: &mut u32,
 ) -> TreeNode {
     let pi = player.index();
     let remaining = stacks[pi];
 
-    // If player has no stack left, they can't act
-    if remaining < 0.01 {
+    // If player has no stack left, they can't act; nor can they open the
+    // betting once the opponent is all-in
+    if remaining < 0.01 || (!facing_bet && stacks[player.opponent().index()] < 0.01) {
         return TreeNode::Terminal {
             terminal_type: TerminalType::Showdown,
             pot,
@@ -229,7 +690,7 @@ fn build_node(
         // Facing a bet/raise: Fold / Call / Raise(sizes)
         build_facing_bet(
             config, player, pot, stacks, invested, raises,
-            amount_to_call, next_id,
+            amount_to_call, oop_checked, next_id,
         )
     } else if player == Player::IP && oop_checked {
         // IP acts after OOP check: Check (back) / Bet(sizes)
@@ -248,6 +709,14 @@ fn build_node(
     }
 }
 
+/// Hand out the next action node id. Panics rather than wrapping, since a
+/// reused id would silently merge two nodes' CFR state.
+fn take_node_id(next_id: &mut u32) -> u32 {
+    let node_id = *next_id;
+    *next_id = node_id.checked_add(1).expect("action node ids overflowed u32");
+    node_id
+}
+
 #[allow(clippy::too_many_arguments)]
 fn build_open_action(
     config: &TreeConfig,
@@ -257,13 +726,13 @@ fn build_open_action(
     invested: [f64; 2],
     raises: usize,
     is_check_back: bool,
-    next_id: &mut u16,
+    next_id: &mut u32,
 ) -> TreeNode {
     let pi = player.index();
-    let remaining = stacks[pi];
+    // Betting more than the opponent has behind can't be called
+    let remaining = stacks[pi].min(stacks[player.opponent().index()]);
 
-    let node_id = *next_id;
-    *next_id += 1;
+    let node_id = take_node_id(next_id);
 
     let mut actions = Vec::new();
     let mut children = Vec::new();
@@ -286,24 +755,8 @@ fn build_open_action(
         ));
     }
 
-    // Bet sizes
-    let mut added_allin = false;
-    for &frac in &config.bet_sizes {
-        let raw_bet = pot * frac;
-        let bet = raw_bet.min(remaining);
-
-        if bet < 0.01 {
-            continue;
-        }
-
-        // Skip if this is effectively the same as all-in and we already added it
-        if (bet - remaining).abs() < 0.01 {
-            if added_allin {
-                continue;
-            }
-            added_allin = true;
-        }
-
+    // Bet sizes (plus all-in when configured)
+    for bet in open_bet_amounts(config, player, pot, remaining) {
         actions.push(Action::Bet(bet));
 
         let mut new_stacks = stacks;
@@ -313,31 +766,13 @@ fn build_open_action(
         new_invested[pi] += bet;
 
         // Opponent faces this bet
+        // A bet after OOP's check opens a check-raise line
         children.push(build_node(
             config, player.opponent(), new_pot, new_stacks, new_invested,
-            raises, true, bet, false, next_id,
+            raises, true, bet, is_check_back, next_id,
         ));
     }
 
-    // All-in option (only if bet sizes are configured — empty bet_sizes means check-only)
-    if config.add_allin && !added_allin && remaining > 0.01 && !config.bet_sizes.is_empty() {
-        let min_bet_threshold = pot * 0.2;
-        if remaining > min_bet_threshold {
-            actions.push(Action::Bet(remaining));
-
-            let mut new_stacks = stacks;
-            new_stacks[pi] -= remaining;
-            let new_pot = pot + remaining;
-            let mut new_invested = invested;
-            new_invested[pi] += remaining;
-
-            children.push(build_node(
-                config, player.opponent(), new_pot, new_stacks, new_invested,
-                raises, true, remaining, false, next_id,
-            ));
-        }
-    }
-
     TreeNode::Action {
         node_id,
         player,
@@ -357,13 +792,13 @@ fn build_facing_bet(
     invested: [f64; 2],
     raises: usize,
     amount_to_call: f64,
-    next_id: &mut u16,
+    oop_checked: bool,
+    next_id: &mut u32,
 ) -> TreeNode {
     let pi = player.index();
     let remaining = stacks[pi];
 
-    let node_id = *next_id;
-    *next_id += 1;
+    let node_id = take_node_id(next_id);
 
     let mut actions = Vec::new();
     let mut children = Vec::new();
@@ -377,124 +812,222 @@ fn build_facing_bet(
         invested,
     });
 
-    // Call
-    let call_amount = amount_to_call.min(remaining);
+    // Call closes the action -> showdown, all-in or not
+    let (call_amount, new_pot, new_stacks, new_invested) =
+        call_outcome(player, pot, stacks, invested, amount_to_call);
     actions.push(Action::Call(call_amount));
-    {
+    children.push(TreeNode::Terminal {
+        terminal_type: TerminalType::Showdown,
+        pot: new_pot,
+        stacks: new_stacks,
+        invested: new_invested,
+    });
+
+    // Raise options (if under the cap and has remaining stack after calling).
+    // Heads-up, the amount to call is always the last bet/raise increment,
+    // which is the smallest legal raise. Nobody raises past what the
+    // opponent has left to call.
+    let min_raise = amount_to_call;
+    let raise_cap = remaining.min(call_amount + stacks[player.opponent().index()]);
+    let raises_to = raise_amounts(
+        config, player, pot, call_amount, min_raise, raise_cap, raises, oop_checked,
+    );
+    for total_put_in in raises_to {
+        actions.push(Action::Raise(total_put_in));
+
         let mut new_stacks = stacks;
-        new_stacks[pi] -= call_amount;
-        let new_pot = pot + call_amount;
+        new_stacks[pi] -= total_put_in;
+        let new_pot = pot + total_put_in;
         let mut new_invested = invested;
-        new_invested[pi] += call_amount;
-
-        // Check if both players are all-in or if remaining stack is gone
-        let opp_remaining = new_stacks[player.opponent().index()];
-        if new_stacks[pi] < 0.01 || opp_remaining < 0.01 {
-            // All-in: go to showdown
-            children.push(TreeNode::Terminal {
-                terminal_type: TerminalType::Showdown,
-                pot: new_pot,
-                stacks: new_stacks,
-                invested: new_invested,
-            });
-        } else {
-            // Call closes the action -> showdown
-            children.push(TreeNode::Terminal {
-                terminal_type: TerminalType::Showdown,
-                pot: new_pot,
-                stacks: new_stacks,
-                invested: new_invested,
-            });
-        }
+        new_invested[pi] += total_put_in;
+
+        // Opponent now faces this raise
+        children.push(build_node(
+            config, player.opponent(), new_pot, new_stacks, new_invested,
+            raises + 1, true, total_put_in - call_amount, oop_checked, next_id,
+        ));
     }
 
-    // Raise options (if under the cap and has remaining stack after calling)
-    if raises < config.max_raises {
-        let remaining_after_call = remaining - call_amount;
-        if remaining_after_call > 0.01 {
-            let pot_after_call = pot + call_amount;
-            let mut added_allin = false;
+    TreeNode::Action {
+        node_id,
+        player,
+        pot,
+        stacks,
+        actions,
+        children,
+    }
+}
 
-            for &frac in &config.raise_sizes {
-                let raise_amount = (pot_after_call * frac).min(remaining_after_call);
+/// Pot, stacks and investments after `player` calls a bet of
+/// `amount_to_call`, plus the amount called. A caller who is short matches
+/// only what they have and the uncalled rest goes back to the bettor, so
+/// both players have the same amount at risk at showdown.
+fn call_outcome(
+    player: Player,
+    pot: f64,
+    stacks: [f64; 2],
+    invested: [f64; 2],
+    amount_to_call: f64,
+) -> (f64, f64, [f64; 2], [f64; 2]) {
+    let (pi, oi) = (player.index(), player.opponent().index());
+    let call_amount = amount_to_call.min(stacks[pi]);
+    let uncalled = amount_to_call - call_amount;
+
+    let mut new_stacks = stacks;
+    new_stacks[pi] -= call_amount;
+    new_stacks[oi] += uncalled;
+    let mut new_invested = invested;
+    new_invested[pi] += call_amount;
+    new_invested[oi] -= uncalled;
+    (call_amount, pot + call_amount - uncalled, new_stacks, new_invested)
+}
 
-                if raise_amount < 0.01 {
-                    continue;
-                }
+/// Bet amounts available to `player` when opening the action: their
+/// configured sizes resolved against this pot and stack, after rounding,
+/// min-bet filtering, stack clamping and dedup, plus an all-in when
+/// `add_allin` is set and it isn't already covered.
+fn open_bet_amounts(config: &TreeConfig, player: Player, pot: f64, remaining: f64) -> Vec<f64> {
+    let sizes = &config.bet_sizes[player.index()];
+    let mut bets: Vec<f64> = Vec::new();
+    let mut added_allin = false;
+    for &size in sizes {
+        let raw = size.amount(pot, remaining);
+        let sized = if size == BetSize::AllIn {
+            // All-in is exempt from rounding and the minimum bet
+            Some(raw).filter(|&b| b > 0.01)
+        } else {
+            config.size_amount(raw)
+        };
+        let mut bet = match sized {
+            Some(b) => b.min(remaining),
+            None => continue,
+        };
 
-                let total_put_in = call_amount + raise_amount;
+        // Sizes close to the stack, or past the forced all-in line, are the
+        // all-in; add it only once
+        if config.amounts_merge(bet, remaining) || config.forces_allin(bet, remaining) {
+            if added_allin {
+                continue;
+            }
+            bet = remaining;
+            added_allin = true;
+        }
 
-                if (total_put_in - remaining).abs() < 0.01 {
-                    if added_allin {
-                        continue;
-                    }
-                    added_allin = true;
-                }
+        // Skip sizes that collapsed onto one already added (e.g., by rounding)
+        if bets.iter().any(|&b| config.amounts_merge(b, bet)) {
+            continue;
+        }
+        bets.push(bet);
+    }
 
-                actions.push(Action::Raise(total_put_in));
+    // All-in option (only if bet sizes are configured — empty bet_sizes means
+    // check-only — and the stack is above `allin_threshold` of the pot)
+    if config.add_allin
+        && !added_allin
+        && remaining > 0.01
+        && !sizes.is_empty()
+        && remaining > pot * config.allin_threshold
+    {
+        bets.push(remaining);
+    }
+    bets
+}
 
-                let mut new_stacks = stacks;
-                new_stacks[pi] -= total_put_in;
-                let new_pot = pot + total_put_in;
-                let mut new_invested = invested;
-                new_invested[pi] += total_put_in;
+/// Total amounts put in (call + raise increment) for each of `player`'s raise
+/// options when facing a bet, including the all-in raise. Empty once the
+/// raise cap is hit or nothing is left behind after calling.
+///
+/// `min_raise` is the increment of the last bet or raise: configured sizes
+/// below it are raised to it, and only an all-in may fall short of it.
+///
+/// `oop_checked` marks a check-raise line. Raises there alternate starting
+/// with OOP's check-raise, so OOP facing a bet has already made `raises / 2`
+/// of them, which is what `max_check_raises` caps.
+#[allow(clippy::too_many_arguments)]
+fn raise_amounts(
+    config: &TreeConfig,
+    player: Player,
+    pot: f64,
+    call_amount: f64,
+    min_raise: f64,
+    remaining: f64,
+    raises: usize,
+    oop_checked: bool,
+) -> Vec<f64> {
+    let mut totals: Vec<f64> = Vec::new();
+    if raises >= config.max_raises || !config.can_raise[player.index()] {
+        return totals;
+    }
+    let check_raise_capped = config
+        .max_check_raises
+        .is_some_and(|max| player == Player::OOP && oop_checked && raises / 2 >= max);
+    if check_raise_capped {
+        return totals;
+    }
+    let remaining_after_call = remaining - call_amount;
+    if remaining_after_call <= 0.01 {
+        return totals;
+    }
 
-                // Opponent now faces this raise
-                children.push(build_node(
-                    config, player.opponent(), new_pot, new_stacks, new_invested,
-                    raises + 1, true, raise_amount, false, next_id,
-                ));
-            }
+    let pot_after_call = pot + call_amount;
+    // The street began at `starting_pot`; the opponent has put in `call_amount`
+    // more of what went in since
+    let facing_total = (pot - config.starting_pot + call_amount) / 2.0;
+    let mut added_allin = false;
 
-            // All-in raise
-            if config.add_allin && !added_allin && remaining_after_call > 0.01 {
-                let total_put_in = remaining;
-                actions.push(Action::Raise(total_put_in));
+    for raw in config.raise_sizes[player.index()].increments(pot_after_call, facing_total) {
+        let raise_amount = match config.size_amount(raw) {
+            Some(r) => r.max(min_raise).min(remaining_after_call),
+            None => continue,
+        };
 
-                let mut new_stacks = stacks;
-                new_stacks[pi] = 0.0;
-                let new_pot = pot + total_put_in;
-                let mut new_invested = invested;
-                new_invested[pi] += total_put_in;
+        let mut total_put_in = call_amount + raise_amount;
 
-                children.push(build_node(
-                    config, player.opponent(), new_pot, new_stacks, new_invested,
-                    raises + 1, true, total_put_in - call_amount, false, next_id,
-                ));
+        if config.amounts_merge(total_put_in, remaining)
+            || config.forces_allin(total_put_in, remaining)
+        {
+            if added_allin {
+                continue;
             }
+            total_put_in = remaining;
+            added_allin = true;
+        }
+
+        if totals.iter().any(|&r| config.amounts_merge(r, total_put_in)) {
+            continue;
         }
+        totals.push(total_put_in);
     }
 
-    TreeNode::Action {
-        node_id,
-        player,
-        pot,
-        stacks,
-        actions,
-        children,
+    // All-in raise
+    if config.add_allin && !added_allin {
+        totals.push(remaining);
     }
+    totals
 }
 
 /// Build a turn+river game tree.
 ///
 /// Constructs the turn action tree, then replaces every Showdown terminal
 /// with a Chance node that branches into river subtrees (one per possible
-/// river card). Fold terminals are left as-is.
+/// river card, or per entry of `config.river_cards`). Fold terminals are
+/// left as-is.
 ///
 /// Returns (root, total_action_nodes).
-pub fn build_turn_tree(config: &TurnTreeConfig) -> (TreeNode, u16) {
+pub fn build_turn_tree(config: &TurnTreeConfig) -> (TreeNode, u32) {
     // Build single-street turn action tree
     let (turn_tree, mut next_id) = build_tree(&config.turn);
 
-    // Possible river cards = 52 minus board cards
-    let river_cards = remaining_deck(&config.board);
+    // Possible river cards = 52 minus board cards, unless classes were given
+    let river_cards = config
+        .river_cards
+        .clone()
+        .unwrap_or_else(|| remaining_deck(&config.board).into_iter().map(|c| (c, 1)).collect());
 
     // Transform: replace Showdown terminals with Chance → river subtrees
     let root = attach_river_streets(
         turn_tree,
-        &config.river_bet_sizes,
-        &config.river_raise_sizes,
-        config.river_max_raises,
+        &river_template(config),
         &river_cards,
         &mut next_id,
     );
@@ -502,15 +1035,34 @@ pub fn build_turn_tree(config: &TurnTreeConfig) -> (TreeNode, u16) {
     (root, next_id)
 }
 
+/// River street config shared by every chance branch. River subtrees inherit
+/// the turn's sizing constraints; pot and stack are filled in per showdown
+/// terminal.
+fn river_template(config: &TurnTreeConfig) -> TreeConfig {
+    TreeConfig {
+        bet_sizes: config.river_bet_sizes.clone(),
+        raise_sizes: config.river_raise_sizes.clone(),
+        max_raises: config.river_max_raises,
+        starting_pot: 0.0,
+        stacks: [0.0; 2],
+        add_allin: true,
+        min_bet: config.turn.min_bet,
+        bet_round_to: config.turn.bet_round_to,
+        merge_tolerance: config.turn.merge_tolerance,
+        allin_threshold: config.turn.allin_threshold,
+        force_allin_above_stack_pct: config.turn.force_allin_above_stack_pct,
+        can_raise: config.turn.can_raise,
+        max_check_raises: config.turn.max_check_raises,
+    }
+}
+
 /// Recursively walk the tree and replace Showdown terminals with
 /// Chance nodes leading to river action subtrees.
 fn attach_river_streets(
     node: TreeNode,
-    river_bet_sizes: &[f64],
-    river_raise_sizes: &[f64],
-    river_max_raises: usize,
-    river_cards: &[u8],
-    next_id: &mut u16,
+    river_template: &TreeConfig,
+    river_cards: &[(u8, u32)],
+    next_id: &mut u32,
 ) -> TreeNode {
     match node {
         TreeNode::Terminal {
@@ -519,39 +1071,25 @@ fn attach_river_streets(
             stacks,
             invested,
         } => {
-            // Replace with Chance node → river subtrees
-            let eff_stack = stacks[0].min(stacks[1]);
-            let mut children = Vec::with_capacity(river_cards.len());
-
-            for &_card in river_cards {
-                let river_config = TreeConfig {
-                    bet_sizes: river_bet_sizes.to_vec(),
-                    raise_sizes: river_raise_sizes.to_vec(),
-                    max_raises: river_max_raises,
-                    starting_pot: pot,
-                    effective_stack: eff_stack,
-                    add_allin: true,
-                };
-                let river_root = build_node(
-                    &river_config,
-                    Player::OOP,
-                    pot,
-                    [eff_stack; 2],
-                    invested,
-                    0,
-                    false,
-                    0.0,
-                    false,
-                    next_id,
-                );
-                children.push(river_root);
-            }
+            // Replace with Chance node → river subtrees. The river pot
+            // already holds the turn investments, which carry on so payoffs
+            // stay measured from the turn root.
+            let river_config = TreeConfig {
+                starting_pot: pot,
+                stacks,
+                ..river_template.clone()
+            };
+            let children = river_cards
+                .iter()
+                .map(|_| build_street(&river_config, invested, next_id))
+                .collect();
 
             TreeNode::Chance {
                 pot,
                 stacks,
                 invested,
-                cards: river_cards.to_vec(),
+                cards: river_cards.iter().map(|&(c, _)| c).collect(),
+                weights: river_cards.iter().map(|&(_, w)| w).collect(),
                 children,
             }
         }
@@ -567,14 +1105,7 @@ fn attach_river_streets(
             let new_children = children
                 .into_iter()
                 .map(|c| {
-                    attach_river_streets(
-                        c,
-                        river_bet_sizes,
-                        river_raise_sizes,
-                        river_max_raises,
-                        river_cards,
-                        next_id,
-                    )
+                    attach_river_streets(c, river_template, river_cards, next_id)
                 })
                 .collect();
             TreeNode::Action {
@@ -590,28 +1121,406 @@ fn attach_river_streets(
     }
 }
 
-/// Metadata about an action node, used to initialize FlatCfr.
-#[derive(Debug, Clone, Copy)]
-pub struct NodeMeta {
-    pub node_id: u16,
-    pub player: Player,
-    pub num_actions: u8,
+// ---------------------------------------------------------------------------
+// Tree size estimation
+// ---------------------------------------------------------------------------
+
+/// Size of a game tree, counted without building it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TreeEstimate {
+    pub action_nodes: usize,
+    pub terminal_nodes: usize,
+    pub chance_nodes: usize,
+    /// Legal actions summed over each player's action nodes, `[oop, ip]`:
+    /// the regret entries CFR keeps per combo of that player.
+    pub action_slots: [usize; 2],
+    /// Action nodes where each player acts, `[oop, ip]`.
+    pub player_nodes: [usize; 2],
 }
 
-/// Collect metadata for all action nodes in the tree, sorted by node_id.
-pub fn collect_node_metadata(tree: &TreeNode) -> Vec<NodeMeta> {
-    let mut metas = Vec::new();
-    collect_meta_recursive(tree, &mut metas);
-    metas.sort_by_key(|m| m.node_id);
-    metas
-}
+impl TreeEstimate {
+    fn action(player: Player, num_actions: usize) -> Self {
+        let mut action_slots = [0; 2];
+        action_slots[player.index()] = num_actions;
+        let mut player_nodes = [0; 2];
+        player_nodes[player.index()] = 1;
+        TreeEstimate { action_nodes: 1, action_slots, player_nodes, ..Default::default() }
+    }
 
-fn collect_meta_recursive(node: &TreeNode, metas: &mut Vec<NodeMeta>) {
-    match node {
-        TreeNode::Action {
-            node_id,
-            player,
-            actions,
+    fn terminal() -> Self {
+        TreeEstimate { terminal_nodes: 1, ..Default::default() }
+    }
+
+    /// Approximate CFR memory: a regret and a strategy sum per (node, combo,
+    /// action), at `bytes_per_value` each (4 for f32 storage, 8 for f64).
+    /// Ignores per-node bookkeeping, so it is a lower bound.
+    pub fn cfr_memory_bytes(&self, combos: [usize; 2], bytes_per_value: usize) -> usize {
+        self.entries(combos) * 2 * bytes_per_value
+    }
+
+    /// (node, combo, action) entries CFR keeps with `combos` per player.
+    pub fn entries(&self, combos: [usize; 2]) -> usize {
+        self.action_slots.iter().zip(combos).map(|(&s, c)| s * c).sum()
+    }
+
+    /// Information sets, one per (node, combo) of the acting player, with
+    /// `combos` per player.
+    pub fn info_sets(&self, combos: [usize; 2]) -> usize {
+        self.player_nodes.iter().zip(combos).map(|(&n, c)| n * c).sum()
+    }
+}
+
+impl std::ops::Add for TreeEstimate {
+    type Output = TreeEstimate;
+
+    fn add(self, other: TreeEstimate) -> TreeEstimate {
+        TreeEstimate {
+            action_nodes: self.action_nodes + other.action_nodes,
+            terminal_nodes: self.terminal_nodes + other.terminal_nodes,
+            chance_nodes: self.chance_nodes + other.chance_nodes,
+            action_slots: [
+                self.action_slots[0] + other.action_slots[0],
+                self.action_slots[1] + other.action_slots[1],
+            ],
+            player_nodes: [
+                self.player_nodes[0] + other.player_nodes[0],
+                self.player_nodes[1] + other.player_nodes[1],
+            ],
+        }
+    }
+}
+
+impl std::ops::AddAssign for TreeEstimate {
+    fn add_assign(&mut self, other: TreeEstimate) {
+        *self = *self + other;
+    }
+}
+
+impl std::ops::Mul<usize> for TreeEstimate {
+    type Output = TreeEstimate;
+
+    fn mul(self, n: usize) -> TreeEstimate {
+        TreeEstimate {
+            action_nodes: self.action_nodes * n,
+            terminal_nodes: self.terminal_nodes * n,
+            chance_nodes: self.chance_nodes * n,
+            action_slots: [self.action_slots[0] * n, self.action_slots[1] * n],
+            player_nodes: [self.player_nodes[0] * n, self.player_nodes[1] * n],
+        }
+    }
+}
+
+/// Count the nodes `build_tree` would create for `config` without allocating
+/// the tree. Uses the same sizing, dedup and stack-clamp rules, so the action
+/// node count matches the returned node total.
+pub fn estimate_tree(config: &TreeConfig) -> TreeEstimate {
+    count_nodes(
+        config,
+        Player::OOP,
+        config.starting_pot,
+        config.stacks,
+        0,
+        false,
+        0.0,
+        false,
+        &|_, _| TreeEstimate::terminal(),
+    )
+}
+
+/// Count the nodes `build_turn_tree` would create, including one river
+/// subtree per possible river card (or per entry of `config.river_cards`)
+/// under the chance node at every turn showdown.
+pub fn estimate_turn_tree(config: &TurnTreeConfig) -> TreeEstimate {
+    let num_rivers = config
+        .river_cards
+        .as_ref()
+        .map_or_else(|| remaining_deck(&config.board).len(), |cards| cards.len());
+    let template = river_template(config);
+    let river_subtrees = |pot: f64, stacks: [f64; 2]| {
+        let river_config = TreeConfig {
+            starting_pot: pot,
+            stacks,
+            ..template.clone()
+        };
+        let chance = TreeEstimate { chance_nodes: 1, ..Default::default() };
+        chance + estimate_tree(&river_config) * num_rivers
+    };
+    count_nodes(
+        &config.turn,
+        Player::OOP,
+        config.turn.starting_pot,
+        config.turn.stacks,
+        0,
+        false,
+        0.0,
+        false,
+        &river_subtrees,
+    )
+}
+
+/// Count the action nodes `build_tree` would create for `config`.
+pub fn estimate_tree_nodes(config: &TreeConfig) -> usize {
+    estimate_tree(config).action_nodes
+}
+
+/// Count the action nodes `build_turn_tree` would create.
+pub fn estimate_turn_tree_nodes(config: &TurnTreeConfig) -> usize {
+    estimate_turn_tree(config).action_nodes
+}
+
+/// Mirror of `build_node` that only counts nodes. `showdown` returns what
+/// hangs at a showdown (a terminal for a single street, a chance node and
+/// its river subtrees for a turn tree).
+#[allow(clippy::too_many_arguments)]
+fn count_nodes(
+    config: &TreeConfig,
+    player: Player,
+    pot: f64,
+    stacks: [f64; 2],
+    raises: usize,
+    facing_bet: bool,
+    amount_to_call: f64,
+    oop_checked: bool,
+    showdown: &dyn Fn(f64, [f64; 2]) -> TreeEstimate,
+) -> TreeEstimate {
+    let pi = player.index();
+    let remaining = stacks[pi];
+    let opp_remaining = stacks[player.opponent().index()];
+
+    if remaining < 0.01 || (!facing_bet && opp_remaining < 0.01) {
+        return showdown(pot, stacks);
+    }
+
+    if facing_bet {
+        let (call_amount, call_pot, call_stacks, _) =
+            call_outcome(player, pot, stacks, [0.0; 2], amount_to_call);
+        let raise_cap = remaining.min(call_amount + opp_remaining);
+        let raises_to = raise_amounts(
+            config, player, pot, call_amount, amount_to_call, raise_cap, raises, oop_checked,
+        );
+
+        // Fold, call, then each raise
+        let mut count = TreeEstimate::action(player, 2 + raises_to.len())
+            + TreeEstimate::terminal()
+            + showdown(call_pot, call_stacks);
+        for total_put_in in raises_to {
+            let mut new_stacks = stacks;
+            new_stacks[pi] -= total_put_in;
+            count += count_nodes(
+                config, player.opponent(), pot + total_put_in, new_stacks,
+                raises + 1, true, total_put_in - call_amount, oop_checked, showdown,
+            );
+        }
+        count
+    } else if player == Player::OOP || oop_checked {
+        let bets = open_bet_amounts(config, player, pot, remaining.min(opp_remaining));
+        let mut count = TreeEstimate::action(player, 1 + bets.len());
+        if player == Player::IP {
+            // IP checks back -> showdown
+            count += showdown(pot, stacks);
+        } else {
+            count += count_nodes(config, Player::IP, pot, stacks, raises, false, 0.0, true, showdown);
+        }
+
+        for bet in bets {
+            let mut new_stacks = stacks;
+            new_stacks[pi] -= bet;
+            count += count_nodes(
+                config, player.opponent(), pot + bet, new_stacks,
+                raises, true, bet, player == Player::IP, showdown,
+            );
+        }
+        count
+    } else {
+        showdown(pot, stacks)
+    }
+}
+
+/// A tree stored as a node list, root first, with children referenced by
+/// index. This is how `TreeNode` serializes: a turn tree nests dozens of
+/// levels deep, which recursive formats like JSON reject, while the flat list
+/// stays one level deep and drops the per-level enum framing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlatTree {
+    pub nodes: Vec<FlatNode>,
+}
+
+/// One node of a `FlatTree`. `children` index into `FlatTree::nodes` and
+/// always point past the node itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FlatNode {
+    Action {
+        node_id: u32,
+        player: Player,
+        pot: f64,
+        stacks: [f64; 2],
+        actions: Vec<Action>,
+        children: Vec<u32>,
+    },
+    Terminal {
+        terminal_type: TerminalType,
+        pot: f64,
+        stacks: [f64; 2],
+        invested: [f64; 2],
+    },
+    Chance {
+        pot: f64,
+        stacks: [f64; 2],
+        invested: [f64; 2],
+        cards: Vec<u8>,
+        /// Missing from trees written before river classes; read as all 1s.
+        #[serde(default)]
+        weights: Vec<u32>,
+        children: Vec<u32>,
+    },
+}
+
+impl From<&TreeNode> for FlatTree {
+    fn from(tree: &TreeNode) -> Self {
+        let mut nodes = Vec::new();
+        flatten(tree, &mut nodes);
+        FlatTree { nodes }
+    }
+}
+
+/// Append `node` and its subtree in pre-order, returning `node`'s index.
+fn flatten(node: &TreeNode, nodes: &mut Vec<FlatNode>) -> u32 {
+    let index = nodes.len();
+    match node {
+        TreeNode::Action { node_id, player, pot, stacks, actions, children } => {
+            nodes.push(FlatNode::Action {
+                node_id: *node_id,
+                player: *player,
+                pot: *pot,
+                stacks: *stacks,
+                actions: actions.clone(),
+                children: Vec::new(),
+            });
+            let child_indices: Vec<u32> = children.iter().map(|c| flatten(c, nodes)).collect();
+            if let FlatNode::Action { children, .. } = &mut nodes[index] {
+                *children = child_indices;
+            }
+        }
+        TreeNode::Terminal { terminal_type, pot, stacks, invested } => {
+            nodes.push(FlatNode::Terminal {
+                terminal_type: *terminal_type,
+                pot: *pot,
+                stacks: *stacks,
+                invested: *invested,
+            });
+        }
+        TreeNode::Chance { pot, stacks, invested, cards, weights, children } => {
+            nodes.push(FlatNode::Chance {
+                pot: *pot,
+                stacks: *stacks,
+                invested: *invested,
+                cards: cards.clone(),
+                weights: weights.clone(),
+                children: Vec::new(),
+            });
+            let child_indices: Vec<u32> = children.iter().map(|c| flatten(c, nodes)).collect();
+            if let FlatNode::Chance { children, .. } = &mut nodes[index] {
+                *children = child_indices;
+            }
+        }
+    }
+    index as u32
+}
+
+impl TryFrom<FlatTree> for TreeNode {
+    type Error = String;
+
+    fn try_from(flat: FlatTree) -> Result<Self, Self::Error> {
+        if flat.nodes.is_empty() {
+            return Err("Empty tree".to_string());
+        }
+        unflatten(&flat.nodes, 0)
+    }
+}
+
+/// Rebuild the subtree rooted at `nodes[index]`. Children must point forward
+/// and match their node's actions or cards, so a corrupt list can't loop.
+fn unflatten(nodes: &[FlatNode], index: usize) -> Result<TreeNode, String> {
+    let build_children = |children: &[u32], expected: usize| -> Result<Vec<TreeNode>, String> {
+        if children.len() != expected {
+            return Err(format!("Node {} has {} children for {} branches", index, children.len(), expected));
+        }
+        children
+            .iter()
+            .map(|&c| {
+                let c = c as usize;
+                if c <= index || c >= nodes.len() {
+                    return Err(format!("Node {} has invalid child index {}", index, c));
+                }
+                unflatten(nodes, c)
+            })
+            .collect()
+    };
+
+    Ok(match &nodes[index] {
+        FlatNode::Action { node_id, player, pot, stacks, actions, children } => TreeNode::Action {
+            node_id: *node_id,
+            player: *player,
+            pot: *pot,
+            stacks: *stacks,
+            actions: actions.clone(),
+            children: build_children(children, actions.len())?,
+        },
+        FlatNode::Terminal { terminal_type, pot, stacks, invested } => TreeNode::Terminal {
+            terminal_type: *terminal_type,
+            pot: *pot,
+            stacks: *stacks,
+            invested: *invested,
+        },
+        FlatNode::Chance { pot, stacks, invested, cards, weights, children } => TreeNode::Chance {
+            pot: *pot,
+            stacks: *stacks,
+            invested: *invested,
+            cards: cards.clone(),
+            weights: match weights.len() {
+                0 => vec![1; cards.len()],
+                n if n == cards.len() => weights.clone(),
+                n => return Err(format!("Node {} has {} weights for {} cards", index, n, cards.len())),
+            },
+            children: build_children(children, cards.len())?,
+        },
+    })
+}
+
+impl Serialize for TreeNode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        FlatTree::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TreeNode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        TreeNode::try_from(FlatTree::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Metadata about an action node, used to initialize FlatCfr.
+#[derive(Debug, Clone, Copy)]
+pub struct NodeMeta {
+    pub node_id: u32,
+    pub player: Player,
+    pub num_actions: u8,
+}
+
+/// Collect metadata for all action nodes in the tree, sorted by node_id.
+pub fn collect_node_metadata(tree: &TreeNode) -> Vec<NodeMeta> {
+    let mut metas = Vec::new();
+    collect_meta_recursive(tree, &mut metas);
+    metas.sort_by_key(|m| m.node_id);
+    metas
+}
+
+fn collect_meta_recursive(node: &TreeNode, metas: &mut Vec<NodeMeta>) {
+    match node {
+        TreeNode::Action {
+            node_id,
+            player,
+            actions,
             children,
             ..
         } => {
@@ -640,12 +1549,19 @@ mod tests {
     #[test]
     fn basic_tree_structure() {
         let config = TreeConfig {
-            bet_sizes: vec![1.0],
-            raise_sizes: vec![],
+            bet_sizes: same_for_both(vec![BetSize::Pot(1.0)]),
+            raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![])),
             max_raises: 0,
             starting_pot: 10.0,
-            effective_stack: 20.0,
+            stacks: [20.0; 2],
             add_allin: false,
+            min_bet: 0.0,
+            bet_round_to: None,
+            merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+            allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+            force_allin_above_stack_pct: None,
+            can_raise: [true; 2],
+            max_check_raises: None,
         };
         let (root, num_nodes) = build_tree(&config);
         assert!(num_nodes > 0);
@@ -653,15 +1569,81 @@ mod tests {
         assert!(root.count_terminal_nodes() > 0);
     }
 
+    #[test]
+    fn ip_only_sizes_widen_ip_nodes() {
+        // OOP bets half pot only; IP also gets a pot-size bet and an overbet
+        let mut config = TreeConfig::default_river(10.0, 100.0);
+        config.bet_sizes = [
+            vec![BetSize::Pot(0.5)],
+            vec![BetSize::Pot(0.5), BetSize::Pot(1.0), BetSize::Pot(2.0)],
+        ];
+        config.raise_sizes = same_for_both(RaiseSizing::PotFraction(vec![]));
+        config.max_raises = 0;
+        let (root, _) = build_tree(&config);
+
+        // Opening the action: OOP at the root, IP once checked to
+        let TreeNode::Action { player: Player::OOP, children: oop_children, .. } = &root else {
+            panic!("OOP acts first");
+        };
+        let TreeNode::Action { player: Player::IP, children: ip_children, .. } = &oop_children[0] else {
+            panic!("IP acts after a check");
+        };
+        // Check plus each bet, plus the all-in both get
+        assert_eq!(oop_children.len(), 3);
+        assert_eq!(ip_children.len(), 5);
+    }
+
+    #[test]
+    fn check_raise_cap_stops_oop_reraising() {
+        let mut config = TreeConfig::default_river(10.0, 200.0);
+        config.add_allin = false;
+        config.max_check_raises = Some(1);
+        let (root, _) = build_tree(&config);
+
+        // The first child of an action node matching `action`
+        fn after(node: &TreeNode, action: fn(&Action) -> bool) -> &TreeNode {
+            let TreeNode::Action { actions, children, .. } = node else {
+                panic!("expected an action node");
+            };
+            let i = actions.iter().position(action).expect("action available");
+            &children[i]
+        }
+        let is_raise = |a: &Action| matches!(a, Action::Raise(_));
+        let has_raise = |node: &TreeNode| match node {
+            TreeNode::Action { actions, .. } => actions.iter().any(is_raise),
+            _ => false,
+        };
+
+        // Check, IP bets, OOP check-raises, IP re-raises
+        let ip_bet = after(after(&root, |a| *a == Action::Check), |a| matches!(a, Action::Bet(_)));
+        let ip_facing = after(ip_bet, is_raise);
+        assert!(has_raise(ip_facing), "IP may still re-raise a check-raise");
+        let oop_facing = after(ip_facing, is_raise);
+        assert!(matches!(oop_facing, TreeNode::Action { player: Player::OOP, .. }));
+        assert!(!has_raise(oop_facing), "OOP can't raise again after a check-raise");
+
+        // Lines OOP opens with a bet keep the full raise cap
+        let oop_bet = after(&root, |a| matches!(a, Action::Bet(_)));
+        let oop_facing = after(after(oop_bet, is_raise), is_raise);
+        assert!(has_raise(oop_facing));
+    }
+
     #[test]
     fn check_check_leads_to_showdown() {
         let config = TreeConfig {
-            bet_sizes: vec![1.0],
-            raise_sizes: vec![],
+            bet_sizes: same_for_both(vec![BetSize::Pot(1.0)]),
+            raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![])),
             max_raises: 0,
             starting_pot: 10.0,
-            effective_stack: 20.0,
+            stacks: [20.0; 2],
             add_allin: false,
+            min_bet: 0.0,
+            bet_round_to: None,
+            merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+            allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+            force_allin_above_stack_pct: None,
+            can_raise: [true; 2],
+            max_check_raises: None,
         };
         let (root, _) = build_tree(&config);
 
@@ -697,11 +1679,11 @@ mod tests {
         assert_eq!(ids.len(), num_nodes as usize);
         // IDs should be 0..num_nodes
         for (i, &id) in ids.iter().enumerate() {
-            assert_eq!(id, i as u16);
+            assert_eq!(id, i as u32);
         }
     }
 
-    fn collect_node_ids(node: &TreeNode, ids: &mut Vec<u16>) {
+    fn collect_node_ids(node: &TreeNode, ids: &mut Vec<u32>) {
         match node {
             TreeNode::Action { node_id, children, .. } => {
                 ids.push(*node_id);
@@ -721,12 +1703,19 @@ mod tests {
     #[test]
     fn allin_clamped_to_stack() {
         let config = TreeConfig {
-            bet_sizes: vec![2.0], // 200% pot bet = 20.0, but stack is only 5
-            raise_sizes: vec![],
+            bet_sizes: same_for_both(vec![BetSize::Pot(2.0)]), // 200% pot bet = 20.0, but stack is only 5
+            raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![])),
             max_raises: 0,
             starting_pot: 10.0,
-            effective_stack: 5.0,
+            stacks: [5.0; 2],
             add_allin: false,
+            min_bet: 0.0,
+            bet_round_to: None,
+            merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+            allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+            force_allin_above_stack_pct: None,
+            can_raise: [true; 2],
+            max_check_raises: None,
         };
         let (root, _) = build_tree(&config);
 
@@ -742,12 +1731,19 @@ mod tests {
     #[test]
     fn no_bets_means_only_check() {
         let config = TreeConfig {
-            bet_sizes: vec![],
-            raise_sizes: vec![],
+            bet_sizes: same_for_both(vec![]),
+            raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![])),
             max_raises: 0,
             starting_pot: 10.0,
-            effective_stack: 20.0,
+            stacks: [20.0; 2],
             add_allin: false,
+            min_bet: 0.0,
+            bet_round_to: None,
+            merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+            allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+            force_allin_above_stack_pct: None,
+            can_raise: [true; 2],
+            max_check_raises: None,
         };
         let (root, _) = build_tree(&config);
 
@@ -757,6 +1753,235 @@ mod tests {
         }
     }
 
+    #[test]
+    fn bet_below_min_bet_dropped() {
+        let config = TreeConfig {
+            bet_sizes: same_for_both(vec![BetSize::Pot(0.1), BetSize::Pot(1.0)]), // 1.0 and 10.0 into a 10 pot
+            raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![])),
+            max_raises: 0,
+            starting_pot: 10.0,
+            stacks: [20.0; 2],
+            add_allin: false,
+            min_bet: 2.0,
+            bet_round_to: None,
+            merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+            allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+            force_allin_above_stack_pct: None,
+            can_raise: [true; 2],
+            max_check_raises: None,
+        };
+        let (root, _) = build_tree(&config);
+
+        if let TreeNode::Action { actions, .. } = &root {
+            assert_eq!(actions, &vec![Action::Check, Action::Bet(10.0)]);
+        } else {
+            panic!("Root should be action node");
+        }
+    }
+
+    #[test]
+    fn rounding_collapses_duplicate_sizes() {
+        let config = TreeConfig {
+            bet_sizes: same_for_both(vec![BetSize::Pot(0.3), BetSize::Pot(0.33)]), // 3.0 and 3.3 both round to 3.0
+            raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![])),
+            max_raises: 0,
+            starting_pot: 10.0,
+            stacks: [20.0; 2],
+            add_allin: false,
+            min_bet: 0.0,
+            bet_round_to: Some(1.0),
+            merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+            allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+            force_allin_above_stack_pct: None,
+            can_raise: [true; 2],
+            max_check_raises: None,
+        };
+        let (root, _) = build_tree(&config);
+
+        if let TreeNode::Action { actions, children, .. } = &root {
+            assert_eq!(actions, &vec![Action::Check, Action::Bet(3.0)]);
+            assert_eq!(children.len(), actions.len());
+        } else {
+            panic!("Root should be action node");
+        }
+    }
+
+    #[test]
+    fn estimate_matches_built_tree() {
+        let mut configs = vec![
+            TreeConfig::default_river(10.0, 20.0),
+            TreeConfig::default_river(10.0, 200.0),
+            TreeConfig::default_turn(6.0, 3.0),
+            TreeConfig {
+                bet_sizes: same_for_both(vec![
+                    BetSize::Pot(0.1),
+                    BetSize::Pot(0.3),
+                    BetSize::Pot(0.33),
+                    BetSize::Pot(2.0),
+                ]),
+                raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![0.5, 1.0])),
+                max_raises: 4,
+                starting_pot: 10.0,
+                stacks: [100.0; 2],
+                add_allin: true,
+                min_bet: 2.0,
+                bet_round_to: Some(1.0),
+                merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+                allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+                force_allin_above_stack_pct: None,
+                can_raise: [true; 2],
+                max_check_raises: None,
+            },
+        ];
+        configs[2].add_allin = false;
+        let mut capped = TreeConfig::default_river(10.0, 200.0);
+        capped.max_check_raises = Some(1);
+        configs.push(capped);
+
+        for config in &configs {
+            let (root, num_nodes) = build_tree(config);
+            assert_eq!(estimate_tree_nodes(config), num_nodes as usize);
+            assert_eq!(root.count_action_nodes(), num_nodes as usize);
+            assert_eq!(estimate_tree(config), measure_tree(&root));
+        }
+    }
+
+    /// The `TreeEstimate` of an already-built tree.
+    fn measure_tree(node: &TreeNode) -> TreeEstimate {
+        match node {
+            TreeNode::Action { player, actions, children, .. } => children
+                .iter()
+                .fold(TreeEstimate::action(*player, actions.len()), |acc, c| acc + measure_tree(c)),
+            TreeNode::Chance { children, .. } => children.iter().fold(
+                TreeEstimate { chance_nodes: 1, ..Default::default() },
+                |acc, c| acc + measure_tree(c),
+            ),
+            TreeNode::Terminal { .. } => TreeEstimate::terminal(),
+        }
+    }
+
+    #[test]
+    fn estimate_memory_scales_with_combos() {
+        let config = TreeConfig::default_river(10.0, 20.0);
+        let estimate = estimate_tree(&config);
+        let (root, _) = build_tree(&config);
+        assert_eq!(estimate.terminal_nodes, root.count_terminal_nodes());
+
+        let slots = estimate.action_slots[0] + estimate.action_slots[1];
+        assert_eq!(estimate.cfr_memory_bytes([10, 10], 4), slots * 10 * 2 * 4);
+        assert_eq!(
+            estimate.cfr_memory_bytes([10, 0], 8),
+            estimate.action_slots[0] * 10 * 2 * 8
+        );
+    }
+
+    #[test]
+    fn bet_size_parse_and_display() {
+        let parsed: Vec<BetSize> = ["0.5", "150%", "geo2", "GEO3", "allin"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        assert_eq!(
+            parsed,
+            vec![
+                BetSize::Pot(0.5),
+                BetSize::Pot(1.5),
+                BetSize::Geometric(2),
+                BetSize::Geometric(3),
+                BetSize::AllIn,
+            ]
+        );
+        let shown: Vec<String> = parsed.iter().map(|s| s.to_string()).collect();
+        assert_eq!(shown, vec!["0.5", "1.5", "geo2", "geo3", "allin"]);
+        assert!("geo0".parse::<BetSize>().is_err());
+        assert!("geo".parse::<BetSize>().is_err());
+        assert!("big".parse::<BetSize>().is_err());
+    }
+
+    #[test]
+    fn raise_sizing_parse() {
+        assert_eq!("0.5, 100%".parse(), Ok(RaiseSizing::PotFraction(vec![0.5, 1.0])));
+        assert_eq!("2.5x,3X".parse(), Ok(RaiseSizing::BetMultiple(vec![2.5, 3.0])));
+        assert_eq!("none".parse(), Ok(RaiseSizing::PotFraction(vec![])));
+        assert!("0.5,3x".parse::<RaiseSizing>().unwrap_err().contains("mix"));
+        assert!("allin".parse::<RaiseSizing>().is_err());
+        assert!("abcx".parse::<RaiseSizing>().is_err());
+        assert!(RaiseSizing::BetMultiple(vec![1.0]).validate().is_err());
+        assert!(RaiseSizing::PotFraction(vec![-0.5]).validate().is_err());
+        assert!(RaiseSizing::BetMultiple(vec![2.5]).validate().is_ok());
+    }
+
+    #[test]
+    fn geometric_sizing_gets_stacks_in() {
+        // Pot 10, stack 40: pot-sized twice (bet 10 into 10, then 30 into 30)
+        let f = geometric_fraction(10.0, 40.0, 2);
+        assert!((f - 1.0).abs() < 1e-9, "expected pot-sized bets, got {}", f);
+
+        for &(pot, stack, streets) in &[(10.0, 40.0, 2), (6.0, 97.0, 3), (20.0, 5.0, 2)] {
+            let f = geometric_fraction(pot, stack, streets);
+            let mut p = pot;
+            let mut committed = 0.0;
+            for _ in 0..streets {
+                committed += p * f;
+                p += 2.0 * p * f;
+            }
+            assert!((committed - stack).abs() < 1e-6, "{} streets commit {}", streets, committed);
+        }
+        // One street is just a pot-sized-to-stack shove
+        assert!((BetSize::Geometric(1).amount(10.0, 25.0) - 25.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn symbolic_sizes_resolve_at_build_time() {
+        let config = TreeConfig {
+            bet_sizes: same_for_both(vec![BetSize::Pot(1.5), BetSize::Geometric(2), BetSize::AllIn]),
+            raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![])),
+            max_raises: 0,
+            starting_pot: 10.0,
+            stacks: [40.0; 2],
+            add_allin: false,
+            min_bet: 0.0,
+            bet_round_to: None,
+            merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+            allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+            force_allin_above_stack_pct: None,
+            can_raise: [true; 2],
+            max_check_raises: None,
+        };
+        let (root, _) = build_tree(&config);
+        let TreeNode::Action { actions, .. } = &root else { panic!("root is an action node") };
+        // 150% overbet = 15, geo2 into 10 with 40 behind = 10, all-in = 40
+        assert_eq!(
+            actions,
+            &vec![Action::Check, Action::Bet(15.0), Action::Bet(10.0), Action::Bet(40.0)]
+        );
+        assert_eq!(actions[1].label(), "Bet 15.0");
+        assert_eq!(actions[3].label(), "Bet 40.0");
+        assert_eq!(estimate_tree_nodes(&config), root.count_action_nodes());
+    }
+
+    #[test]
+    fn allin_size_is_not_duplicated() {
+        let config = TreeConfig {
+            bet_sizes: same_for_both(vec![BetSize::Pot(5.0), BetSize::AllIn]),
+            raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![])),
+            max_raises: 0,
+            starting_pot: 10.0,
+            stacks: [20.0; 2],
+            add_allin: true,
+            min_bet: 0.0,
+            bet_round_to: Some(3.0),
+            merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+            allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+            force_allin_above_stack_pct: None,
+            can_raise: [true; 2],
+            max_check_raises: None,
+        };
+        let (root, _) = build_tree(&config);
+        let TreeNode::Action { actions, .. } = &root else { panic!("root is an action node") };
+        assert_eq!(actions, &vec![Action::Check, Action::Bet(20.0)]);
+    }
+
     // -----------------------------------------------------------------------
     // Turn tree tests
     // -----------------------------------------------------------------------
@@ -877,8 +2102,500 @@ mod tests {
 
         // Verify sorted by node_id and sequential
         for (i, m) in metas.iter().enumerate() {
-            assert_eq!(m.node_id, i as u16);
+            assert_eq!(m.node_id, i as u32);
             assert!(m.num_actions >= 1);
         }
     }
+
+    #[test]
+    fn turn_estimate_matches_built_tree() {
+        for &(pot, stack) in &[(10.0, 20.0), (10.0, 5.0), (4.0, 40.0)] {
+            let config = TurnTreeConfig::new(vec![0, 1, 2, 3], pot, stack);
+            let (root, num_nodes) = build_turn_tree(&config);
+            assert_eq!(estimate_turn_tree_nodes(&config), num_nodes as usize);
+            assert_eq!(estimate_turn_tree(&config), measure_tree(&root));
+        }
+
+        // Dealing river classes instead of every card
+        let mut config = TurnTreeConfig::new(vec![0, 1, 2, 3], 10.0, 20.0);
+        config.river_cards = Some(vec![(4, 40), (5, 4), (6, 4)]);
+        let (root, num_nodes) = build_turn_tree(&config);
+        assert_eq!(estimate_turn_tree_nodes(&config), num_nodes as usize);
+        assert_eq!(estimate_turn_tree(&config), measure_tree(&root));
+    }
+
+    /// Raise actions at the node reached by OOP's first bet.
+    fn raises_facing_first_bet(config: &TreeConfig) -> Vec<f64> {
+        let TreeNode::Action { children, .. } = build_tree(config).0 else {
+            panic!("Root should be action node");
+        };
+        let TreeNode::Action { actions, .. } = &children[1] else {
+            panic!("Expected IP action node facing the bet");
+        };
+        actions
+            .iter()
+            .filter_map(|a| match a {
+                Action::Raise(total) => Some(*total),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Assert every raise is at least the last increment, unless all-in.
+    fn assert_raises_legal(node: &TreeNode) {
+        let TreeNode::Action { player, stacks, actions, children, .. } = node else {
+            return;
+        };
+        let call = actions.iter().find_map(|a| match a {
+            Action::Call(c) => Some(*c),
+            _ => None,
+        });
+        for action in actions {
+            if let (Action::Raise(total), Some(call)) = (action, call) {
+                let all_in = (total - stacks[player.index()]).abs() < 0.01;
+                assert!(
+                    all_in || total - call >= call - 0.01,
+                    "raise to {} facing {} is below the min-raise",
+                    total,
+                    call
+                );
+            }
+        }
+        children.iter().for_each(assert_raises_legal);
+    }
+
+    fn min_raise_config(raise_sizes: Vec<f64>, effective_stack: f64) -> TreeConfig {
+        TreeConfig {
+            bet_sizes: same_for_both(vec![BetSize::Pot(1.0)]),
+            raise_sizes: same_for_both(RaiseSizing::PotFraction(raise_sizes)),
+            max_raises: 3,
+            starting_pot: 10.0,
+            stacks: [effective_stack; 2],
+            add_allin: false,
+            min_bet: 0.0,
+            bet_round_to: None,
+            merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+            allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+            force_allin_above_stack_pct: None,
+            can_raise: [true; 2],
+            max_check_raises: None,
+        }
+    }
+
+    #[test]
+    fn small_raise_sizes_clamped_to_min_raise() {
+        // Facing a 10 bet into 10, 0.25x of the 30 pot-after-call is a 7.5
+        // raise; the legal minimum is 10, so both sizes become raise-to-20.
+        let config = min_raise_config(vec![0.1, 0.25], 100.0);
+        assert_eq!(raises_facing_first_bet(&config), vec![20.0]);
+        assert_raises_legal(&build_tree(&config).0);
+    }
+
+    #[test]
+    fn short_stack_raise_is_all_in_below_min_raise() {
+        // 4 behind after calling 10: a full 1.0x raise is impossible, but
+        // the all-in for less than a min-raise stays available.
+        let config = min_raise_config(vec![1.0], 14.0);
+        assert_eq!(raises_facing_first_bet(&config), vec![14.0]);
+        assert_raises_legal(&build_tree(&config).0);
+    }
+
+    #[test]
+    fn bet_multiple_raises_scale_the_bet_faced() {
+        // Facing a pot-size 10 bet, 3x puts in 30 and 2.5x puts in 25;
+        // 1.5x is below a min-raise and becomes raise-to-20.
+        let mut config = min_raise_config(vec![], 100.0);
+        config.raise_sizes = same_for_both(RaiseSizing::BetMultiple(vec![1.5, 2.5, 3.0]));
+        assert_eq!(raises_facing_first_bet(&config), vec![20.0, 25.0, 30.0]);
+        assert_raises_legal(&build_tree(&config).0);
+
+        // With 28 behind, the 3x raise is clamped to the stack
+        let mut config = min_raise_config(vec![], 28.0);
+        config.raise_sizes = same_for_both(RaiseSizing::BetMultiple(vec![3.0]));
+        assert_eq!(raises_facing_first_bet(&config), vec![28.0]);
+    }
+
+    #[test]
+    fn bet_multiple_reraises_scale_the_street_total_faced() {
+        // Bet 10, raise to 30, then 3x of 30 is a re-raise to 90: the bettor
+        // adds 80 on top of their 10
+        let mut config = min_raise_config(vec![], 100.0);
+        config.raise_sizes = same_for_both(RaiseSizing::BetMultiple(vec![3.0]));
+        let TreeNode::Action { children, .. } = build_tree(&config).0 else {
+            panic!("Root should be action node");
+        };
+        let TreeNode::Action { actions, children, .. } = &children[1] else {
+            panic!("Expected IP action node facing the bet");
+        };
+        let raise = actions.iter().position(|a| *a == Action::Raise(30.0)).expect("IP raises to 30");
+        let TreeNode::Action { player, actions, .. } = &children[raise] else {
+            panic!("Expected OOP facing the raise");
+        };
+        assert_eq!(*player, Player::OOP);
+        assert!(actions.contains(&Action::Raise(80.0)), "{:?}", actions);
+        assert_raises_legal(&build_tree(&config).0);
+    }
+
+    #[test]
+    fn default_trees_only_raise_legally() {
+        for &(pot, stack) in &[(10.0, 20.0), (10.0, 7.0), (4.0, 40.0)] {
+            let mut config = TreeConfig::default_river(pot, stack);
+            config.raise_sizes = same_for_both(RaiseSizing::PotFraction(vec![0.2, 1.0]));
+            assert_raises_legal(&build_tree(&config).0);
+        }
+    }
+
+    /// Bet and raise amounts at every action node, checked for near
+    /// duplicates and for an all-in option wherever betting is possible.
+    fn assert_no_merged_duplicates(config: &TreeConfig, node: &TreeNode) {
+        let TreeNode::Action { player, stacks, actions, children, .. } = node else {
+            return;
+        };
+        let amounts: Vec<f64> = actions
+            .iter()
+            .filter_map(|a| match a {
+                Action::Bet(x) | Action::Raise(x) => Some(*x),
+                _ => None,
+            })
+            .collect();
+        for (i, &a) in amounts.iter().enumerate() {
+            for &b in &amounts[i + 1..] {
+                assert!(!config.amounts_merge(a, b), "duplicate actions {} and {} in {:?}", a, b, actions);
+            }
+        }
+        if !amounts.is_empty() {
+            let stack = stacks[player.index()];
+            assert!(
+                amounts.iter().any(|&x| (x - stack).abs() < 1e-9),
+                "all-in for {} missing from {:?}",
+                stack,
+                actions
+            );
+        }
+        for child in children {
+            assert_no_merged_duplicates(config, child);
+        }
+    }
+
+    fn merge_config(starting_pot: f64, effective_stack: f64) -> TreeConfig {
+        TreeConfig {
+            bet_sizes: same_for_both(vec![
+                BetSize::Pot(0.33),
+                BetSize::Pot(0.335),
+                BetSize::Pot(0.99),
+                BetSize::Pot(1.0),
+            ]),
+            raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![0.99, 1.0])),
+            max_raises: 3,
+            starting_pot,
+            stacks: [effective_stack; 2],
+            add_allin: true,
+            min_bet: 0.0,
+            bet_round_to: None,
+            merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+            allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+            force_allin_above_stack_pct: None,
+            can_raise: [true; 2],
+            max_check_raises: None,
+        }
+    }
+
+    #[test]
+    fn near_pot_bet_merges_into_allin() {
+        // 99% and 100% of 2000 are 1980 and 1990; both are the 1990 all-in
+        let mut config = merge_config(2000.0, 1990.0);
+        config.bet_sizes = same_for_both(vec![BetSize::Pot(0.99), BetSize::Pot(1.0)]);
+        let (root, _) = build_tree(&config);
+        if let TreeNode::Action { actions, .. } = &root {
+            assert_eq!(actions, &vec![Action::Check, Action::Bet(1990.0)]);
+        } else {
+            panic!("Root should be action node");
+        }
+    }
+
+    #[test]
+    fn merging_scales_with_pot_size() {
+        for &(pot, stack) in &[(1.5, 1.5), (1.5, 9.75), (5000.0, 4990.0), (5000.0, 32500.0)] {
+            let config = merge_config(pot, stack);
+            let (root, num_nodes) = build_tree(&config);
+            assert_no_merged_duplicates(&config, &root);
+            assert_eq!(estimate_tree_nodes(&config), num_nodes as usize);
+        }
+    }
+
+    fn root_bets(config: &TreeConfig) -> Vec<Action> {
+        match build_tree(config).0 {
+            TreeNode::Action { actions, .. } => actions[1..].to_vec(),
+            _ => panic!("Root should be action node"),
+        }
+    }
+
+    #[test]
+    fn allin_threshold_is_configurable() {
+        // A 1.5 stack is 15% of the 10 pot: under the default 20% threshold
+        // no extra all-in is offered, under a 10% threshold it is
+        let mut config = TreeConfig::default_river(10.0, 1.5);
+        config.bet_sizes = same_for_both(vec![BetSize::Pot(0.1)]);
+        assert_eq!(root_bets(&config), vec![Action::Bet(1.0)]);
+
+        config.allin_threshold = 0.1;
+        assert_eq!(root_bets(&config), vec![Action::Bet(1.0), Action::Bet(1.5)]);
+    }
+
+    #[test]
+    fn pot_bet_forced_allin_above_stack_pct() {
+        // A 10 pot bet with 10.87 behind is 92% of the stack
+        let mut config = TreeConfig::default_river(10.0, 10.87);
+        config.bet_sizes = same_for_both(vec![BetSize::Pot(0.5), BetSize::Pot(1.0)]);
+        config.add_allin = false;
+
+        config.force_allin_above_stack_pct = Some(95.0);
+        assert_eq!(root_bets(&config), vec![Action::Bet(5.0), Action::Bet(10.0)]);
+
+        config.force_allin_above_stack_pct = Some(90.0);
+        assert_eq!(root_bets(&config), vec![Action::Bet(5.0), Action::Bet(10.87)]);
+        assert_eq!(estimate_tree_nodes(&config), build_tree(&config).1 as usize);
+    }
+
+    #[test]
+    fn large_turn_tree_ids_unique_past_u16() {
+        // 48 rivers under a deep turn tree: well past 65,535 action nodes,
+        // where u16 ids used to wrap and collide
+        let mut config = TurnTreeConfig::new(vec![0, 1, 2, 3], 10.0, 200.0);
+        let sizes = vec![BetSize::Pot(0.25), BetSize::Pot(0.5), BetSize::Pot(1.0), BetSize::Pot(2.0)];
+        config.turn.bet_sizes = same_for_both(sizes.clone());
+        config.river_bet_sizes = same_for_both(sizes);
+        config.turn.raise_sizes = same_for_both(RaiseSizing::PotFraction(vec![0.5, 1.0]));
+        config.river_raise_sizes = same_for_both(RaiseSizing::PotFraction(vec![0.5, 1.0]));
+        let (root, num_nodes) = build_turn_tree(&config);
+        assert!(num_nodes > u16::MAX as u32, "only {} nodes", num_nodes);
+
+        let mut ids = Vec::new();
+        collect_node_ids(&root, &mut ids);
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), num_nodes as usize);
+        assert_eq!(ids.last(), Some(&(num_nodes - 1)));
+    }
+
+    #[test]
+    fn tree_text_lists_nodes_and_respects_depth() {
+        let mut config = TreeConfig::default_river(10.0, 50.0);
+        config.bet_sizes = same_for_both(vec![BetSize::Pot(0.5), BetSize::Pot(1.0)]);
+        let (root, num_nodes) = build_tree(&config);
+
+        let text = root.to_text(usize::MAX);
+        assert!(text.starts_with("#0 OOP  pot 10.0"));
+        assert!(text.contains("Bet 5.0"));
+        assert!(text.contains("Check -> showdown, pot 10.0"));
+        assert!(text.contains(&format!("#{} ", num_nodes - 1)));
+
+        let shallow = root.to_text(1);
+        assert!(shallow.contains("#1 IP"));
+        // #2 is OOP facing a bet after checking: two levels below the root
+        assert!(text.contains("#2 OOP"));
+        assert!(!shallow.contains("#2 OOP"));
+        assert!(shallow.contains("..."));
+    }
+
+    #[test]
+    fn tree_dot_has_edges_and_summarizes_chance() {
+        let mut config = TurnTreeConfig::new(vec![0, 5, 10, 15], 10.0, 20.0);
+        config.turn.bet_sizes = same_for_both(vec![BetSize::Pot(1.0)]);
+        config.river_bet_sizes = same_for_both(vec![BetSize::Pot(1.0)]);
+        let (root, _) = build_turn_tree(&config);
+
+        let dot = root.to_dot();
+        assert!(dot.starts_with("digraph tree {"));
+        assert!(dot.trim_end().ends_with('}'));
+        assert!(dot.contains("n0 -> n1 [label=\"Check\"]"));
+        assert!(dot.contains("48 river cards"));
+        assert!(dot.contains("(+47 more)"));
+        // Only one river subtree is drawn per chance node
+        let boxes = dot.matches("shape=box").count();
+        assert!(boxes < root.count_action_nodes() / 10, "{} boxes drawn", boxes);
+    }
+
+    #[test]
+    fn labels_in_pot_terms() {
+        assert_eq!(Action::Bet(6.7).label_with_pot(10.0, [0.0; 2], 50.0), "Bet 67% (6.7)");
+        assert_eq!(Action::Raise(25.0).label_with_pot(20.0, [0.0, 10.0], 50.0), "Raise 2.5x (25.0)");
+        // 3-bet: 10 in, facing a raise to 30, raising to 90
+        assert_eq!(Action::Raise(80.0).label_with_pot(50.0, [10.0, 30.0], 100.0), "Raise 3.0x (80.0)");
+        assert_eq!(Action::Bet(50.0).label_with_pot(10.0, [0.0; 2], 50.0), "All-in (50.0)");
+        assert_eq!(Action::Call(10.0).label_with_pot(20.0, [0.0, 10.0], 50.0), "Call 10.0");
+        assert_eq!(Action::Check.label_with_pot(10.0, [0.0; 2], 50.0), "Check");
+    }
+
+    #[test]
+    fn pot_labels_follow_node_pot() {
+        let mut config = TreeConfig::default_river(10.0, 30.0);
+        config.bet_sizes = same_for_both(vec![BetSize::Pot(0.5)]);
+        let (root, _) = build_tree(&config);
+        assert_eq!(root.pot_labels([0.0; 2]), vec!["Check", "Bet 50% (5.0)", "All-in (30.0)"]);
+
+        // IP facing the half-pot bet: a pot-sized raise is to 25
+        let TreeNode::Action { children, .. } = &root else { panic!("root is an action node") };
+        assert_eq!(
+            children[1].pot_labels([5.0, 0.0]),
+            vec!["Fold", "Call 5.0", "Raise 5.0x (25.0)", "All-in (30.0)"]
+        );
+    }
+
+    /// Actions at every IP node facing a bet.
+    fn ip_facing_bet_actions(node: &TreeNode, out: &mut Vec<Vec<Action>>) {
+        match node {
+            TreeNode::Action { player, actions, children, .. } => {
+                if *player == Player::IP && actions[0] == Action::Fold {
+                    out.push(actions.clone());
+                }
+                for child in children {
+                    ip_facing_bet_actions(child, out);
+                }
+            }
+            TreeNode::Chance { children, .. } => {
+                for child in children {
+                    ip_facing_bet_actions(child, out);
+                }
+            }
+            TreeNode::Terminal { .. } => {}
+        }
+    }
+
+    #[test]
+    fn ip_raises_disabled_leaves_fold_and_call() {
+        let mut config = TreeConfig::default_river(10.0, 100.0);
+        config.can_raise = [true, false];
+        let (root, _) = build_tree(&config);
+
+        let mut facing = Vec::new();
+        ip_facing_bet_actions(&root, &mut facing);
+        assert!(!facing.is_empty());
+        for actions in &facing {
+            assert_eq!(actions.len(), 2, "{:?}", actions);
+            assert!(matches!(actions[1], Action::Call(_)));
+        }
+        assert_eq!(estimate_tree_nodes(&config), build_tree(&config).1 as usize);
+
+        // OOP can still raise IP's bets
+        let TreeNode::Action { children, .. } = &root else { panic!("root is an action node") };
+        let TreeNode::Action { children: ip_bets, .. } = &children[0] else { panic!("IP acts after a check") };
+        let TreeNode::Action { actions, .. } = &ip_bets[1] else { panic!("OOP faces IP's bet") };
+        assert!(actions.iter().any(|a| matches!(a, Action::Raise(_))));
+
+        // The river streets of a turn tree inherit the setting
+        let mut turn = TurnTreeConfig::new(vec![0, 5, 10, 15], 10.0, 50.0);
+        turn.turn.can_raise = [true, false];
+        let (root, _) = build_turn_tree(&turn);
+        let mut facing = Vec::new();
+        ip_facing_bet_actions(&root, &mut facing);
+        assert!(facing.iter().all(|a| a.len() == 2));
+    }
+
+    /// Each player's payoff, `[oop, ip]`, for every way a terminal can end:
+    /// the one fold result, or either player winning a showdown.
+    fn terminal_payoffs(node: &TreeNode, out: &mut Vec<[f64; 2]>) {
+        match node {
+            TreeNode::Terminal { terminal_type, pot, invested, .. } => {
+                let win = |p: usize| {
+                    let mut payoff = [-invested[0], -invested[1]];
+                    payoff[p] = pot - invested[p];
+                    payoff
+                };
+                match terminal_type {
+                    TerminalType::Fold { folder } => out.push(win(folder.opponent().index())),
+                    TerminalType::Showdown => {
+                        out.push(win(0));
+                        out.push(win(1));
+                    }
+                }
+            }
+            TreeNode::Action { children, .. } | TreeNode::Chance { children, .. } => {
+                for child in children {
+                    terminal_payoffs(child, out);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn prior_investment_shifts_payoffs_by_a_constant() {
+        // 10 dead + 5 each from earlier streets vs the same pot all dead
+        let config = TreeConfig::default_river(20.0, 40.0);
+        let (fresh, fresh_nodes) = build_tree(&config);
+        let (carried, carried_nodes) = build_tree_with_invested(&config, [5.0, 5.0]);
+        assert_eq!(fresh_nodes, carried_nodes);
+
+        let (mut fresh_payoffs, mut carried_payoffs) = (Vec::new(), Vec::new());
+        terminal_payoffs(&fresh, &mut fresh_payoffs);
+        terminal_payoffs(&carried, &mut carried_payoffs);
+        assert_eq!(fresh_payoffs.len(), carried_payoffs.len());
+        // Sunk chips lower every outcome equally, so strategies can't change
+        for (f, c) in fresh_payoffs.iter().zip(&carried_payoffs) {
+            assert!((f[0] - 5.0 - c[0]).abs() < 1e-9 && (f[1] - 5.0 - c[1]).abs() < 1e-9);
+            // Payoffs always sum to the 10 chips of dead money
+            assert!((c[0] + c[1] - 10.0).abs() < 1e-9, "{:?}", c);
+        }
+    }
+
+    #[test]
+    fn river_subtrees_keep_the_turn_baseline() {
+        let mut config = TurnTreeConfig::new(vec![0, 5, 10, 15], 10.0, 30.0);
+        config.turn.bet_sizes = same_for_both(vec![BetSize::Pot(0.5)]);
+        config.river_bet_sizes = same_for_both(vec![BetSize::Pot(0.5)]);
+        let (root, _) = build_turn_tree(&config);
+
+        // Turn and river outcomes alike sum to the turn's starting pot: river
+        // payoffs are still measured from the turn root
+        let mut payoffs = Vec::new();
+        terminal_payoffs(&root, &mut payoffs);
+        assert!(payoffs.len() > 48);
+        for p in payoffs {
+            assert!((p[0] + p[1] - 10.0).abs() < 1e-9, "{:?}", p);
+        }
+    }
+
+    #[test]
+    fn turn_tree_round_trips_through_json_and_bincode() {
+        let config = TurnTreeConfig::new(vec![0, 1, 2, 3], 10.0, 20.0);
+        let (tree, _) = build_turn_tree(&config);
+
+        let json = serde_json::to_string(&tree).unwrap();
+        let from_json: TreeNode = serde_json::from_str(&json).unwrap();
+        // JSON may be off by an ulp on amounts like 3.3, so compare structure
+        assert_eq!(from_json.to_text(usize::MAX), tree.to_text(usize::MAX));
+        assert_eq!(from_json.count_terminal_nodes(), tree.count_terminal_nodes());
+
+        let bytes = bincode::serialize(&tree).unwrap();
+        let from_bincode: TreeNode = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(from_bincode, tree);
+
+        let flat = FlatTree::from(&tree);
+        assert!(matches!(flat.nodes[0], FlatNode::Action { node_id: 0, .. }));
+        assert!(flat.nodes.len() > tree.count_action_nodes() + tree.count_terminal_nodes());
+    }
+
+    #[test]
+    fn tree_config_round_trips() {
+        let mut config = TreeConfig::default_river(10.0, 20.0);
+        config.stacks = [20.0, 35.0];
+        config.can_raise = [true, false];
+        let json = serde_json::to_string(&config).unwrap();
+        let back: TreeConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.bet_sizes, config.bet_sizes);
+        assert_eq!(back.stacks, config.stacks);
+        assert_eq!(back.can_raise, config.can_raise);
+        assert_eq!(build_tree(&back).0, build_tree(&config).0);
+    }
+
+    #[test]
+    fn flat_tree_rejects_backward_child_index() {
+        let (tree, _) = build_tree(&TreeConfig::default_river(10.0, 20.0));
+        let mut flat = FlatTree::from(&tree);
+        if let FlatNode::Action { children, .. } = &mut flat.nodes[0] {
+            children[0] = 0;
+        }
+        assert!(TreeNode::try_from(flat).is_err());
+        assert!(TreeNode::try_from(FlatTree { nodes: vec![] }).is_err());
+    }
 }
@@ -16,20 +16,18 @@
 use serde::{Deserialize, Serialize};
 
 use crate::cfr::{CfrTrainer, InfoSetKey};
-use crate::game_tree::{
-    bucket_to_hand, precompute_equity_table, EquityTable, NUM_HANDS,
-};
+use crate::game_tree::{bucket_to_hand, EquityTable, NUM_HANDS};
 use crate::ranges::combo_count;
 
 // ---------------------------------------------------------------------------
 // Node IDs for the preflop game tree
 // ---------------------------------------------------------------------------
 
-const NODE_OPEN: u16 = 100;       // Opener: Open / Fold
-const NODE_VS_OPEN: u16 = 101;    // Responder: 3-Bet / Call / Fold
-const NODE_VS_3BET: u16 = 102;    // Opener: 4-Bet / Call / Fold
-const NODE_VS_4BET: u16 = 103;    // Responder: All-In / Call / Fold
-const NODE_VS_5BET: u16 = 104;    // Opener: Call / Fold
+const NODE_OPEN: u32 = 100;       // Opener: Open / Fold
+const NODE_VS_OPEN: u32 = 101;    // Responder: 3-Bet / Call / Fold
+const NODE_VS_3BET: u32 = 102;    // Opener: 4-Bet / Call / Fold
+const NODE_VS_4BET: u32 = 103;    // Responder: All-In / Call / Fold
+const NODE_VS_5BET: u32 = 104;    // Opener: Call / Fold
 
 // Action counts per node
 const ACTIONS_OPEN: usize = 2;     // Open, Fold
@@ -340,16 +338,16 @@ pub fn solve_preflop_spot(
     // Pre-create all info sets.
     for h in 0..NUM_HANDS {
         let hb = h as u16;
-        trainer.get_or_create(&InfoSetKey { hand_bucket: hb, node_id: NODE_OPEN }, ACTIONS_OPEN);
-        trainer.get_or_create(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_OPEN }, ACTIONS_VS_OPEN);
-        trainer.get_or_create(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_3BET }, ACTIONS_VS_3BET);
-        trainer.get_or_create(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_4BET }, ACTIONS_VS_4BET);
-        trainer.get_or_create(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_5BET }, ACTIONS_VS_5BET);
+        trainer.get_or_create(&InfoSetKey { hand_bucket: hb, node_id: NODE_OPEN, position: 0 }, ACTIONS_OPEN);
+        trainer.get_or_create(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_OPEN, position: 0 }, ACTIONS_VS_OPEN);
+        trainer.get_or_create(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_3BET, position: 0 }, ACTIONS_VS_3BET);
+        trainer.get_or_create(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_4BET, position: 0 }, ACTIONS_VS_4BET);
+        trainer.get_or_create(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_5BET, position: 0 }, ACTIONS_VS_5BET);
     }
 
     // Run CFR+ iterations.
-    for _ in 0..iterations {
-        preflop_cfr_iteration(&mut trainer, table, &payoffs);
+    for iter in 0..iterations {
+        preflop_cfr_iteration(&mut trainer, table, &payoffs, iter + 1);
     }
 
     // Extract average strategies.
@@ -365,22 +363,22 @@ pub fn solve_preflop_spot(
     for h in 0..NUM_HANDS {
         let hb = h as u16;
 
-        let s = trainer.get_average_strategy(&InfoSetKey { hand_bucket: hb, node_id: NODE_OPEN }, ACTIONS_OPEN);
+        let s = trainer.get_average_strategy(&InfoSetKey { hand_bucket: hb, node_id: NODE_OPEN, position: 0 }, ACTIONS_OPEN);
         open_strategy[h] = s[0];
 
-        let s = trainer.get_average_strategy(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_OPEN }, ACTIONS_VS_OPEN);
+        let s = trainer.get_average_strategy(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_OPEN, position: 0 }, ACTIONS_VS_OPEN);
         vs_open_3bet[h] = s[0];
         vs_open_call[h] = s[1];
 
-        let s = trainer.get_average_strategy(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_3BET }, ACTIONS_VS_3BET);
+        let s = trainer.get_average_strategy(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_3BET, position: 0 }, ACTIONS_VS_3BET);
         vs_3bet_4bet[h] = s[0];
         vs_3bet_call[h] = s[1];
 
-        let s = trainer.get_average_strategy(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_4BET }, ACTIONS_VS_4BET);
+        let s = trainer.get_average_strategy(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_4BET, position: 0 }, ACTIONS_VS_4BET);
         vs_4bet_allin[h] = s[0];
         vs_4bet_call[h] = s[1];
 
-        let s = trainer.get_average_strategy(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_5BET }, ACTIONS_VS_5BET);
+        let s = trainer.get_average_strategy(&InfoSetKey { hand_bucket: hb, node_id: NODE_VS_5BET, position: 0 }, ACTIONS_VS_5BET);
         vs_5bet_call[h] = s[0];
     }
 
@@ -408,23 +406,25 @@ pub fn solve_preflop_spot(
     }
 }
 
-/// One CFR+ iteration: alternating updates for opener and responder.
+/// One CFR+ iteration (1-based `iteration`): alternating updates for opener
+/// and responder.
 fn preflop_cfr_iteration(
     trainer: &mut CfrTrainer,
     table: &EquityTable,
     payoffs: &PreflopPayoffs,
+    iteration: usize,
 ) {
     // --- Snapshot responder strategies (nodes 101, 103) ---
     let resp_101: Vec<[f64; 3]> = (0..NUM_HANDS)
         .map(|h| {
-            let s = trainer.get_strategy(&InfoSetKey { hand_bucket: h as u16, node_id: NODE_VS_OPEN }, ACTIONS_VS_OPEN);
+            let s = trainer.get_strategy(&InfoSetKey { hand_bucket: h as u16, node_id: NODE_VS_OPEN, position: 0 }, ACTIONS_VS_OPEN);
             [s[0], s[1], s[2]]
         })
         .collect();
 
     let resp_103: Vec<[f64; 3]> = (0..NUM_HANDS)
         .map(|h| {
-            let s = trainer.get_strategy(&InfoSetKey { hand_bucket: h as u16, node_id: NODE_VS_4BET }, ACTIONS_VS_4BET);
+            let s = trainer.get_strategy(&InfoSetKey { hand_bucket: h as u16, node_id: NODE_VS_4BET, position: 0 }, ACTIONS_VS_4BET);
             [s[0], s[1], s[2]]
         })
         .collect();
@@ -433,20 +433,20 @@ fn preflop_cfr_iteration(
     // Snapshot opener strategies for self-reference
     let opener_102: Vec<[f64; 3]> = (0..NUM_HANDS)
         .map(|h| {
-            let s = trainer.get_strategy(&InfoSetKey { hand_bucket: h as u16, node_id: NODE_VS_3BET }, ACTIONS_VS_3BET);
+            let s = trainer.get_strategy(&InfoSetKey { hand_bucket: h as u16, node_id: NODE_VS_3BET, position: 0 }, ACTIONS_VS_3BET);
             [s[0], s[1], s[2]]
         })
         .collect();
 
     let opener_104: Vec<[f64; 2]> = (0..NUM_HANDS)
         .map(|h| {
-            let s = trainer.get_strategy(&InfoSetKey { hand_bucket: h as u16, node_id: NODE_VS_5BET }, ACTIONS_VS_5BET);
+            let s = trainer.get_strategy(&InfoSetKey { hand_bucket: h as u16, node_id: NODE_VS_5BET, position: 0 }, ACTIONS_VS_5BET);
             [s[0], s[1]]
         })
         .collect();
 
     for op in 0..NUM_HANDS {
-        let op_key_100 = InfoSetKey { hand_bucket: op as u16, node_id: NODE_OPEN };
+        let op_key_100 = InfoSetKey { hand_bucket: op as u16, node_id: NODE_OPEN, position: 0 };
         let op_strat_100 = trainer.get_strategy(&op_key_100, ACTIONS_OPEN);
 
         // Compute EV of opening vs folding at node 100
@@ -486,12 +486,11 @@ fn preflop_cfr_iteration(
         }
 
         let node_value_100 = op_strat_100[0] * open_ev + op_strat_100[1] * fold_ev_100;
-        let data = trainer.get_or_create(&op_key_100, ACTIONS_OPEN);
-        data.update(&[open_ev, fold_ev_100], node_value_100, 1.0);
+        trainer.update(&op_key_100, &[open_ev, fold_ev_100], node_value_100, 1.0, iteration);
 
         // --- Update node 102 (opener vs 3-bet) ---
         // EV is conditional on reaching node 102 (responder 3-bet)
-        let op_key_102 = InfoSetKey { hand_bucket: op as u16, node_id: NODE_VS_3BET };
+        let op_key_102 = InfoSetKey { hand_bucket: op as u16, node_id: NODE_VS_3BET, position: 0 };
         let op_strat_102 = opener_102[op];
 
         let mut fourbet_ev = 0.0;
@@ -523,11 +522,10 @@ fn preflop_cfr_iteration(
         }
 
         let node_value_102 = op_strat_102[0] * fourbet_ev + op_strat_102[1] * call3bet_ev + op_strat_102[2] * fold3bet_ev;
-        let data = trainer.get_or_create(&op_key_102, ACTIONS_VS_3BET);
-        data.update(&[fourbet_ev, call3bet_ev, fold3bet_ev], node_value_102, 1.0);
+        trainer.update(&op_key_102, &[fourbet_ev, call3bet_ev, fold3bet_ev], node_value_102, 1.0, iteration);
 
         // --- Update node 104 (opener vs 5-bet/all-in) ---
-        let op_key_104 = InfoSetKey { hand_bucket: op as u16, node_id: NODE_VS_5BET };
+        let op_key_104 = InfoSetKey { hand_bucket: op as u16, node_id: NODE_VS_5BET, position: 0 };
         let op_strat_104 = opener_104[op];
 
         let mut call5bet_ev = 0.0;
@@ -557,28 +555,27 @@ fn preflop_cfr_iteration(
         }
 
         let node_value_104 = op_strat_104[0] * call5bet_ev + op_strat_104[1] * fold5bet_ev;
-        let data = trainer.get_or_create(&op_key_104, ACTIONS_VS_5BET);
-        data.update(&[call5bet_ev, fold5bet_ev], node_value_104, 1.0);
+        trainer.update(&op_key_104, &[call5bet_ev, fold5bet_ev], node_value_104, 1.0, iteration);
     }
 
     // --- Now snapshot opener strategies for responder update ---
     let opener_100: Vec<[f64; 2]> = (0..NUM_HANDS)
         .map(|h| {
-            let s = trainer.get_strategy(&InfoSetKey { hand_bucket: h as u16, node_id: NODE_OPEN }, ACTIONS_OPEN);
+            let s = trainer.get_strategy(&InfoSetKey { hand_bucket: h as u16, node_id: NODE_OPEN, position: 0 }, ACTIONS_OPEN);
             [s[0], s[1]]
         })
         .collect();
 
     let opener_102_new: Vec<[f64; 3]> = (0..NUM_HANDS)
         .map(|h| {
-            let s = trainer.get_strategy(&InfoSetKey { hand_bucket: h as u16, node_id: NODE_VS_3BET }, ACTIONS_VS_3BET);
+            let s = trainer.get_strategy(&InfoSetKey { hand_bucket: h as u16, node_id: NODE_VS_3BET, position: 0 }, ACTIONS_VS_3BET);
             [s[0], s[1], s[2]]
         })
         .collect();
 
     let opener_104_new: Vec<[f64; 2]> = (0..NUM_HANDS)
         .map(|h| {
-            let s = trainer.get_strategy(&InfoSetKey { hand_bucket: h as u16, node_id: NODE_VS_5BET }, ACTIONS_VS_5BET);
+            let s = trainer.get_strategy(&InfoSetKey { hand_bucket: h as u16, node_id: NODE_VS_5BET, position: 0 }, ACTIONS_VS_5BET);
             [s[0], s[1]]
         })
         .collect();
@@ -586,7 +583,7 @@ fn preflop_cfr_iteration(
     // --- Update responder nodes (101, 103) ---
     for resp in 0..NUM_HANDS {
         // --- Node 101: Responder vs open ---
-        let resp_key_101 = InfoSetKey { hand_bucket: resp as u16, node_id: NODE_VS_OPEN };
+        let resp_key_101 = InfoSetKey { hand_bucket: resp as u16, node_id: NODE_VS_OPEN, position: 0 };
         let resp_strat_101 = resp_101[resp];
 
         let mut threebet_ev = 0.0;
@@ -629,11 +626,10 @@ fn preflop_cfr_iteration(
         }
 
         let node_value_101 = resp_strat_101[0] * threebet_ev + resp_strat_101[1] * call_ev + resp_strat_101[2] * resp_fold_ev;
-        let data = trainer.get_or_create(&resp_key_101, ACTIONS_VS_OPEN);
-        data.update(&[threebet_ev, call_ev, resp_fold_ev], node_value_101, 1.0);
+        trainer.update(&resp_key_101, &[threebet_ev, call_ev, resp_fold_ev], node_value_101, 1.0, iteration);
 
         // --- Node 103: Responder vs 4-bet ---
-        let resp_key_103 = InfoSetKey { hand_bucket: resp as u16, node_id: NODE_VS_4BET };
+        let resp_key_103 = InfoSetKey { hand_bucket: resp as u16, node_id: NODE_VS_4BET, position: 0 };
         let resp_strat_103 = resp_103[resp];
 
         let mut allin_ev = 0.0;
@@ -689,8 +685,7 @@ fn preflop_cfr_iteration(
         }
 
         let node_value_103 = resp_strat_103[0] * allin_ev + resp_strat_103[1] * call4bet_ev + resp_strat_103[2] * resp_fold_4bet_ev;
-        let data = trainer.get_or_create(&resp_key_103, ACTIONS_VS_4BET);
-        data.update(&[allin_ev, call4bet_ev, resp_fold_4bet_ev], node_value_103, 1.0);
+        trainer.update(&resp_key_103, &[allin_ev, call4bet_ev, resp_fold_4bet_ev], node_value_103, 1.0, iteration);
     }
 }
 
@@ -920,18 +915,15 @@ pub struct PreflopSolution {
     pub spots: Vec<PreflopSpotResult>,
 }
 
-/// Solve all 15 6-max preflop spots.
+/// Solve all 15 6-max preflop spots over `table`.
 pub fn solve_preflop_6max(
     stack_bb: f64,
     iterations: usize,
     rake_pct: f64,
+    table: &EquityTable,
 ) -> PreflopSolution {
     use colored::Colorize;
 
-    println!("  Computing equity table...");
-    let table = precompute_equity_table(2000);
-    println!("  Equity table ready.\n");
-
     let spots_config = all_6max_spots();
     let mut spots = Vec::with_capacity(spots_config.len());
 
@@ -943,7 +935,7 @@ pub fn solve_preflop_6max(
             opener.as_str().bold(),
             responder.as_str().bold(),
         );
-        let result = solve_preflop_spot(*opener, *responder, stack_bb, iterations, rake_pct, &table);
+        let result = solve_preflop_spot(*opener, *responder, stack_bb, iterations, rake_pct, table);
         println!(
             " done (exploit: {:.4} bb, open: {:.1}%, 3bet: {:.1}%)",
             result.exploitability,
@@ -1006,8 +998,7 @@ impl PreflopSolution {
 }
 
 fn dirs_cache_dir() -> std::path::PathBuf {
-    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    std::path::PathBuf::from(home).join(".gto-cli").join("solver")
+    crate::checkpoint::gto_home().join("solver")
 }
 
 // ---------------------------------------------------------------------------
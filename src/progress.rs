@@ -0,0 +1,38 @@
+//! Progress reporting for long solves and equity runs.
+
+/// Optional progress callback, called with `(current, total)` at coarse
+/// intervals and once with `(total, total)` when the run completes.
+pub type Progress<'a> = Option<&'a (dyn Fn(usize, usize) + Sync)>;
+
+/// Number of intermediate reports over a run (one every 2%).
+pub const PROGRESS_STEPS: usize = 50;
+
+/// Whether `current` of `total` is worth reporting: a step boundary or the
+/// end of the run.
+pub fn is_progress_step(current: usize, total: usize) -> bool {
+    current == total || current.is_multiple_of((total / PROGRESS_STEPS).max(1))
+}
+
+/// Invoke the callback if `current` of `total` is a progress step.
+pub fn report(progress: Progress, current: usize, total: usize) {
+    if let Some(callback) = progress {
+        if is_progress_step(current, total) {
+            callback(current, total);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_are_coarse_and_end_on_total() {
+        let steps: Vec<usize> = (1..=1000).filter(|&i| is_progress_step(i, 1000)).collect();
+        assert_eq!(steps.len(), PROGRESS_STEPS);
+        assert_eq!(steps.last(), Some(&1000));
+
+        // Short runs report every step
+        assert_eq!((1..=7).filter(|&i| is_progress_step(i, 7)).count(), 7);
+    }
+}
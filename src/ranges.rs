@@ -1,3 +1,7 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::card_encoding::{card_to_index, index_to_card};
 use crate::cards::{hand_combos, Card, RANKS_STR};
 use crate::error::{GtoError, GtoResult};
 
@@ -6,22 +10,27 @@ pub const HAND_RANKING: &[&str] = &[
     "AKo", "ATs", "QJs", "KTs", "QTs", "JTs", "99", "AQo", "A9s", "KQo",
     "K9s", "T9s", "J9s", "Q9s", "A8s", "88", "A5s", "A7s", "A4s", "A6s",
     "A3s", "K8s", "T8s", "A2s", "98s", "J8s", "77", "Q8s", "K7s", "AJo",
-    "87s", "66", "K6s", "ATo", "97s", "76s", "T7s", "K5s", "ATo", "55",
-    "J7s", "86s", "KJo", "65s", "Q7s", "K4s", "K3s", "K2s", "96s", "44",
-    "QJo", "75s", "54s", "A9o", "T6s", "KTo", "J6s", "Q6s", "33", "85s",
-    "64s", "QTo", "22", "53s", "JTo", "K9o", "J9o", "T9o", "Q9o", "74s",
-    "43s", "A8o", "A5o", "A7o", "A4o", "A6o", "A3o", "95s", "63s", "A2o",
-    "52s", "84s", "42s", "T8o", "98o", "J8o", "Q8o", "73s", "87o", "32s",
-    "62s", "97o", "76o", "K8o", "86o", "65o", "94s", "93s", "92s", "T7o",
-    "54o", "83s", "75o", "82s", "K7o", "K6o", "72s", "96o", "J7o", "K5o",
-    "T6o", "K4o", "K3o", "K2o", "85o", "Q7o", "64o", "53o", "J6o", "Q6o",
-    "Q5o", "Q4o", "Q3o", "Q2o", "74o", "43o", "95o", "63o", "84o", "42o",
-    "T5o", "T4o", "T3o", "T2o", "52o", "J5o", "J4o", "J3o", "J2o", "73o",
-    "32o", "62o", "94o", "93o", "92o", "83o", "82o", "72o",
+    "87s", "66", "K6s", "ATo", "97s", "76s", "T7s", "K5s", "55", "J7s",
+    "86s", "KJo", "65s", "Q7s", "K4s", "K3s", "K2s", "96s", "44", "QJo",
+    "75s", "54s", "A9o", "T6s", "KTo", "J6s", "Q6s", "33", "85s", "Q5s",
+    "64s", "QTo", "22", "53s", "JTo", "K9o", "J9o", "T9o", "Q9o", "Q4s",
+    "74s", "43s", "A8o", "A5o", "A7o", "A4o", "A6o", "Q3s", "A3o", "95s",
+    "63s", "A2o", "Q2s", "J5s", "52s", "84s", "42s", "T8o", "98o", "J8o",
+    "Q8o", "J4s", "73s", "87o", "32s", "62s", "97o", "76o", "K8o", "J3s",
+    "86o", "65o", "94s", "93s", "92s", "T7o", "J2s", "T5s", "54o", "83s",
+    "75o", "82s", "K7o", "T4s", "K6o", "72s", "96o", "J7o", "T3s", "K5o",
+    "T6o", "K4o", "T2s", "K3o", "K2o", "85o", "Q7o", "64o", "53o", "J6o",
+    "Q6o", "Q5o", "Q4o", "Q3o", "Q2o", "74o", "43o", "95o", "63o", "84o",
+    "42o", "T5o", "T4o", "T3o", "T2o", "52o", "J5o", "J4o", "J3o", "J2o",
+    "73o", "32o", "62o", "94o", "93o", "92o", "83o", "82o", "72o",
 ];
 
 pub fn combo_count(notation: &str) -> u32 {
     let chars: Vec<char> = notation.chars().collect();
+    if chars.len() == 4 {
+        // Suit-specific combo: "AhKh"
+        return hand_combos(notation).map_or(0, |c| c.len() as u32);
+    }
     if chars.len() == 2 && chars[0] == chars[1] {
         return 6;
     }
@@ -36,6 +45,27 @@ pub fn combo_count(notation: &str) -> u32 {
     0
 }
 
+/// Parse a range into hand strings. Any `:weight` suffixes are ignored; use
+/// `parse_weighted_range` to keep them.
+///
+/// Accepts PokerStove / GTO+ syntax. A range is comma-separated entries,
+/// spaces ignored; each entry is one of:
+///
+/// - `AA`, `AKs`, `AKo` — a pair, suited or offsuit class
+/// - `AK` — both `AKs` and `AKo`
+/// - `AhKh` — one suit-specific combo
+/// - `QQ+` — that pair and every higher pair (QQ, KK, AA)
+/// - `ATs+`, `KTo+` — raise the kicker up to just below the high card
+///   (ATs, AJs, AQs, AKs); `AT+` does both suited and offsuit. The kicker of
+///   `KQo+` or `54s+` can't rise, so each is that one class
+/// - `99-55` — pairs between the two, either order
+/// - `A2s-A5s`, `K9o-KQo` — kickers between the two, same high card and suit
+///   suffix (`A2-A5` does both)
+/// - `54s-T9s`, `T8o-53o` — connectors or gappers stepping both ranks between
+///   the two, same gap and suit suffix
+///
+/// Ranks may be written low-first (`KAs`). Entries that match none of these
+/// are kept as-is and contribute no combos.
 pub fn parse_range(range_str: &str) -> Vec<String> {
     let mut hands = std::collections::HashSet::new();
     for part in range_str.replace(' ', "").split(',') {
@@ -43,30 +73,98 @@ pub fn parse_range(range_str: &str) -> Vec<String> {
         if part.is_empty() {
             continue;
         }
-        if part.ends_with('+') {
-            for h in expand_plus(&part[..part.len() - 1]) {
-                hands.insert(h);
-            }
-        } else if part.contains('-') && part.len() > 3 {
-            for h in expand_dash(part) {
-                hands.insert(h);
-            }
-        } else {
-            hands.insert(part.to_string());
-        }
+        let notation = part.split_once(':').map_or(part, |(n, _)| n);
+        hands.extend(expand_entry(notation));
     }
     let mut result: Vec<String> = hands.into_iter().collect();
     result.sort_by_key(|h| hand_strength_index(h));
     result
 }
 
+/// Parse a range with optional per-entry weights, e.g. `"AA:0.75,AKs,QQ+:0.5"`.
+///
+/// Entries without a weight get 1.0 and weights are clamped to [0, 1]. When a
+/// hand appears more than once, the later entry wins.
+pub fn parse_weighted_range(range_str: &str) -> GtoResult<Vec<(String, f64)>> {
+    let mut weights = std::collections::HashMap::new();
+    for part in range_str.replace(' ', "").split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+        let (notation, weight) = match part.split_once(':') {
+            Some((notation, w)) => {
+                let weight: f64 = w.parse().map_err(|_| {
+                    GtoError::InvalidValue(format!("range weight '{}' in '{}'", w, part))
+                })?;
+                if weight.is_nan() {
+                    return Err(GtoError::InvalidValue(format!(
+                        "range weight '{}' in '{}'",
+                        w, part
+                    )));
+                }
+                (notation, weight.clamp(0.0, 1.0))
+            }
+            None => (part, 1.0),
+        };
+        for hand in expand_entry(notation) {
+            weights.insert(hand, weight);
+        }
+    }
+    let mut result: Vec<(String, f64)> = weights.into_iter().collect();
+    result.sort_by_key(|(h, _)| hand_strength_index(h));
+    Ok(result)
+}
+
+/// Expand one range entry ("TT+", "A2s-A5s", "AKo") into hand strings.
+fn expand_entry(part: &str) -> Vec<String> {
+    if let Some(base) = part.strip_suffix('+') {
+        suit_variants(base).iter().flat_map(|b| expand_plus(b)).collect()
+    } else if let Some((start, end)) = part.split_once('-').filter(|_| part.len() > 3) {
+        let starts = suit_variants(start);
+        let ends = suit_variants(end);
+        if starts.len() != ends.len() {
+            return vec![part.to_string()];
+        }
+        starts
+            .iter()
+            .zip(&ends)
+            .flat_map(|(s, e)| expand_dash(s, e))
+            .collect()
+    } else {
+        suit_variants(part)
+    }
+}
+
+/// Normalize a hand class to high rank first ("KAs" -> "AKs") and split a
+/// bare non-pair ("AK") into its suited and offsuit classes. Anything that
+/// isn't a hand class (e.g. "AhKh") passes through unchanged.
+fn suit_variants(hand: &str) -> Vec<String> {
+    let chars: Vec<char> = hand.chars().collect();
+    if chars.len() < 2 || chars.len() > 3 {
+        return vec![hand.to_string()];
+    }
+    let (Some(r1), Some(r2)) = (RANKS_STR.find(chars[0]), RANKS_STR.find(chars[1])) else {
+        return vec![hand.to_string()];
+    };
+    let (high, low) = if r1 >= r2 { (chars[0], chars[1]) } else { (chars[1], chars[0]) };
+    match chars.get(2) {
+        None if r1 == r2 => vec![format!("{}{}", high, low)],
+        None => vec![format!("{}{}s", high, low), format!("{}{}o", high, low)],
+        Some(&kind) if r1 != r2 && (kind == 's' || kind == 'o') => {
+            vec![format!("{}{}{}", high, low, kind)]
+        }
+        Some(_) => vec![hand.to_string()],
+    }
+}
+
 fn expand_plus(base: &str) -> Vec<String> {
     let chars: Vec<char> = base.chars().collect();
+    let ranks: Vec<char> = RANKS_STR.chars().collect();
 
     // Pair: "TT+"
     if chars.len() == 2 && chars[0] == chars[1] {
         if let Some(rank_idx) = RANKS_STR.find(chars[0]) {
-            let ranks: Vec<char> = RANKS_STR.chars().collect();
             return (rank_idx..ranks.len())
                 .map(|i| format!("{}{}", ranks[i], ranks[i]))
                 .collect();
@@ -74,13 +172,10 @@ fn expand_plus(base: &str) -> Vec<String> {
         return vec![base.to_string()];
     }
 
-    // Suited/offsuit: "ATs+"
     if chars.len() == 3 {
-        let high = chars[0];
-        let low = chars[1];
-        let kind = chars[2];
+        let (high, low, kind) = (chars[0], chars[1], chars[2]);
         if let (Some(low_idx), Some(high_idx)) = (RANKS_STR.find(low), RANKS_STR.find(high)) {
-            let ranks: Vec<char> = RANKS_STR.chars().collect();
+            // Suited/offsuit: "ATs+" raises the kicker up to just below the high card
             return (low_idx..high_idx)
                 .map(|i| format!("{}{}{}", high, ranks[i], kind))
                 .collect();
@@ -90,13 +185,7 @@ fn expand_plus(base: &str) -> Vec<String> {
     vec![base.to_string()]
 }
 
-fn expand_dash(range_str: &str) -> Vec<String> {
-    let parts: Vec<&str> = range_str.split('-').collect();
-    if parts.len() != 2 {
-        return vec![range_str.to_string()];
-    }
-
-    let (start, end) = (parts[0], parts[1]);
+fn expand_dash(start: &str, end: &str) -> Vec<String> {
     let start_chars: Vec<char> = start.chars().collect();
     let end_chars: Vec<char> = end.chars().collect();
     let ranks: Vec<char> = RANKS_STR.chars().collect();
@@ -133,7 +222,21 @@ fn expand_dash(range_str: &str) -> Vec<String> {
         }
     }
 
-    vec![range_str.to_string()]
+    // Connector/gapper run: "54s-T9s" steps both ranks, keeping the gap
+    if start_chars.len() == 3 && end_chars.len() == 3 && start_chars[2] == end_chars[2] {
+        let kind = start_chars[2];
+        let idx = |c: &[char]| Some((RANKS_STR.find(c[0])?, RANKS_STR.find(c[1])?));
+        if let (Some((sh, sl)), Some((eh, el))) = (idx(&start_chars), idx(&end_chars)) {
+            if sh > sl && sh - sl == eh - el {
+                let gap = sh - sl;
+                return (sl.min(el)..=sl.max(el))
+                    .map(|i| format!("{}{}{}", ranks[i + gap], ranks[i], kind))
+                    .collect();
+            }
+        }
+    }
+
+    vec![format!("{}-{}", start, end)]
 }
 
 fn hand_strength_index(hand: &str) -> usize {
@@ -143,26 +246,24 @@ fn hand_strength_index(hand: &str) -> usize {
         .unwrap_or(HAND_RANKING.len())
 }
 
+/// The strongest hands (by `HAND_RANKING`) covering at least `pct` percent of
+/// the 1326 starting combos. A hand class straddling the cutoff is included
+/// whole, so the result can slightly exceed `pct`; 100 returns all 169 hands.
 pub fn range_from_top_pct(pct: f64) -> GtoResult<Vec<String>> {
-    if pct <= 0.0 || pct > 100.0 {
+    if !(pct > 0.0 && pct <= 100.0) {
         return Err(GtoError::InvalidValue(
             "Percentage must be between 0 and 100".to_string(),
         ));
     }
-    let total_combos = 1326.0;
-    let target = total_combos * (pct / 100.0);
+    let target = 1326.0 * (pct / 100.0);
     let mut result = Vec::new();
     let mut running = 0u32;
     for &hand in HAND_RANKING {
-        let count = combo_count(hand);
-        if running + count > target as u32 && running > 0 {
-            break;
-        }
-        result.push(hand.to_string());
-        running += count;
         if running as f64 >= target {
             break;
         }
+        result.push(hand.to_string());
+        running += combo_count(hand);
     }
     Ok(result)
 }
@@ -183,6 +284,54 @@ pub fn range_pct_strs(hands: &[&str]) -> f64 {
     total_combos_strs(hands) as f64 / 1326.0 * 100.0
 }
 
+/// Combos in `a` that are not in `b`, collapsed back to range notation.
+pub fn range_subtract(a: &[String], b: &[String]) -> Vec<String> {
+    let b = range_combo_set(b);
+    collapse_combos(range_combo_set(a).difference(&b).copied().collect())
+}
+
+/// Combos in both `a` and `b`, collapsed back to range notation.
+pub fn range_intersect(a: &[String], b: &[String]) -> Vec<String> {
+    let b = range_combo_set(b);
+    collapse_combos(range_combo_set(a).intersection(&b).copied().collect())
+}
+
+/// Every combo in a range as (low, high) card indices.
+fn range_combo_set(hands: &[String]) -> HashSet<(u8, u8)> {
+    hands
+        .iter()
+        .filter_map(|h| hand_combos(h).ok())
+        .flatten()
+        .map(|(c1, c2)| {
+            let (i1, i2) = (card_to_index(&c1), card_to_index(&c2));
+            (i1.min(i2), i1.max(i2))
+        })
+        .collect()
+}
+
+/// Emit whole hand classes where every combo is present, and suit-specific
+/// combos ("AhKh") for the rest, strongest first.
+fn collapse_combos(combos: HashSet<(u8, u8)>) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut partial = Vec::new();
+    for &hand in HAND_RANKING {
+        let mut class: Vec<(u8, u8)> = range_combo_set(&[hand.to_string()]).into_iter().collect();
+        if class.iter().all(|c| combos.contains(c)) {
+            result.push(hand.to_string());
+        } else {
+            class.retain(|c| combos.contains(c));
+            class.sort_by(|x, y| y.cmp(x));
+            partial.extend(class);
+        }
+    }
+    result.extend(
+        partial
+            .into_iter()
+            .map(|(lo, hi)| format!("{}{}", index_to_card(hi), index_to_card(lo))),
+    );
+    result
+}
+
 pub fn blockers_remove(villain_range: &[String], hero_cards: &[Card]) -> Vec<String> {
     let mut result = Vec::new();
     for hand in villain_range {
@@ -207,3 +356,144 @@ pub fn blocked_combos(hand_notation: &str, hero_cards: &[Card]) -> GtoResult<u32
         .count();
     Ok(combo_count(hand_notation) - remaining as u32)
 }
+
+/// Number of distinct combos in both `a` and `b`.
+pub fn combos_in_common(a: &[String], b: &[String]) -> usize {
+    let b = range_combo_set(b);
+    range_combo_set(a).intersection(&b).count()
+}
+
+/// What holding one specific combo takes out of a villain range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockerReport {
+    /// Distinct villain combos before removing the hero's cards.
+    pub total_combos: u32,
+    /// Villain combos removed by either hero card.
+    pub removed_combos: u32,
+    /// Villain combos holding each hero card, in the order given. A combo
+    /// holding both cards counts for both.
+    pub removed_by_card: Vec<(Card, u32)>,
+    /// Hand classes that lost combos as `(hand, removed, combos)`, most
+    /// removed first.
+    pub affected: Vec<(String, u32, u32)>,
+}
+
+impl BlockerReport {
+    /// Villain combos still live with the hero's cards out of the deck.
+    pub fn remaining_combos(&self) -> u32 {
+        self.total_combos - self.removed_combos
+    }
+}
+
+/// Blocker report for `hero_combo` (e.g. "AhKh") against `villain_range`.
+/// Villain combos listed twice count once, under the first hand that lists
+/// them.
+pub fn range_blocker_report(hero_combo: &str, villain_range: &[String]) -> GtoResult<BlockerReport> {
+    let hero: Vec<Card> = match hand_combos(hero_combo)?.as_slice() {
+        [(c1, c2)] => vec![*c1, *c2],
+        _ => return Err(GtoError::InvalidHandNotation(hero_combo.to_string())),
+    };
+
+    let mut seen: HashSet<(u8, u8)> = HashSet::new();
+    let mut report = BlockerReport {
+        total_combos: 0,
+        removed_combos: 0,
+        removed_by_card: hero.iter().map(|&c| (c, 0)).collect(),
+        affected: Vec::new(),
+    };
+    for hand in villain_range {
+        let mut combos = 0;
+        let mut removed = 0;
+        for (c1, c2) in hand_combos(hand)? {
+            let (i1, i2) = (card_to_index(&c1), card_to_index(&c2));
+            if !seen.insert((i1.min(i2), i1.max(i2))) {
+                continue;
+            }
+            combos += 1;
+            let mut blocked = false;
+            for (card, count) in &mut report.removed_by_card {
+                if *card == c1 || *card == c2 {
+                    *count += 1;
+                    blocked = true;
+                }
+            }
+            removed += blocked as u32;
+        }
+        report.total_combos += combos;
+        report.removed_combos += removed;
+        if removed > 0 {
+            report.affected.push((hand.clone(), removed, combos));
+        }
+    }
+    report.affected.sort_by_key(|a| std::cmp::Reverse(a.1));
+    Ok(report)
+}
+
+// ---------------------------------------------------------------------------
+// Named ranges
+// ---------------------------------------------------------------------------
+
+/// Directory holding named ranges: `~/.gto-cli/ranges/`.
+pub fn named_range_dir() -> PathBuf {
+    crate::checkpoint::gto_home().join("ranges")
+}
+
+/// Path of a named range file, rejecting names that would escape the directory.
+fn named_range_path(dir: &Path, name: &str) -> GtoResult<PathBuf> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+        && !name.starts_with('.');
+    if !valid {
+        return Err(GtoError::InvalidValue(format!("range name '{}'", name)));
+    }
+    Ok(dir.join(format!("{}.txt", name)))
+}
+
+/// Raw range string stored as `~/.gto-cli/ranges/<name>.txt`, weights and all.
+pub fn read_named_range(name: &str) -> GtoResult<String> {
+    read_named_range_in(&named_range_dir(), name)
+}
+
+pub fn read_named_range_in(dir: &Path, name: &str) -> GtoResult<String> {
+    let path = named_range_path(dir, name)?;
+    let text = std::fs::read_to_string(&path).map_err(|_| {
+        GtoError::RangeDataNotFound(format!("named range '{}' ({})", name, path.display()))
+    })?;
+    // One range per file; allow it to be wrapped over several lines
+    Ok(text.lines().map(str::trim).collect::<Vec<_>>().join(","))
+}
+
+/// Load `~/.gto-cli/ranges/<name>.txt` as hand strings.
+pub fn load_named_range(name: &str) -> GtoResult<Vec<String>> {
+    load_named_range_in(&named_range_dir(), name)
+}
+
+pub fn load_named_range_in(dir: &Path, name: &str) -> GtoResult<Vec<String>> {
+    let hands = parse_range(&read_named_range_in(dir, name)?);
+    if hands.is_empty() {
+        return Err(GtoError::InvalidValue(format!("named range '{}' is empty", name)));
+    }
+    Ok(hands)
+}
+
+/// Save weighted hands (as from `parse_weighted_range`) as
+/// `~/.gto-cli/ranges/<name>.txt`, replacing any existing file. Hands below
+/// full weight keep a `:weight` suffix; zero-weight hands are left out.
+pub fn save_named_range(name: &str, hands: &[(String, f64)]) -> GtoResult<PathBuf> {
+    save_named_range_in(&named_range_dir(), name, hands)
+}
+
+pub fn save_named_range_in(dir: &Path, name: &str, hands: &[(String, f64)]) -> GtoResult<PathBuf> {
+    let path = named_range_path(dir, name)?;
+    let entries: Vec<String> = hands
+        .iter()
+        .filter(|(_, w)| *w > 0.0)
+        .map(|(h, w)| if *w >= 1.0 { h.clone() } else { format!("{}:{}", h, w) })
+        .collect();
+    std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(&path, format!("{}\n", entries.join(","))))
+        .map_err(|e| GtoError::InvalidValue(format!("{}: {}", path.display(), e)))?;
+    Ok(path)
+}
@@ -2,17 +2,36 @@
 //!
 //! Solves heads-up river spots using CFR+ with exact showdown evaluation.
 //! Works at the individual combo level (not canonical 169 buckets) because
-//! board interactions depend on exact suits.
+//! board interactions depend on exact suits. Regrets and strategy sums live
+//! in flat per-player arrays (`RiverCfr`), indexed by node id and combo.
+//!
+//! For quick approximations of big ranges, `Abstraction::EquityBuckets`
+//! trains on equity buckets instead: every combo in a bucket plays one
+//! strategy, and showdowns between buckets are counted over their
+//! non-conflicting combo pairs. Exploitability is still measured per combo.
 
+use std::cell::Cell;
 use std::collections::HashMap;
+use std::path::PathBuf;
 
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
-use crate::card_encoding::card_to_index;
+use crate::bucketing::assign_buckets;
+use crate::card_encoding::{card_to_index, index_to_card};
 use crate::cards::{hand_combos, parse_board};
-use crate::cfr::{CfrTrainer, InfoSetKey};
-use crate::lookup_eval::evaluate_fast;
-use crate::postflop_tree::{build_tree, Player, TerminalType, TreeConfig, TreeNode};
+use crate::cfr::{
+    sample_index, solver_rng, CfrFloat, SolverRng, CfrTrainer, CfrVariant, InfoSetKey, Precision,
+    SolverAlgorithm,
+};
+use crate::checkpoint::{self, CheckpointHeader};
+use crate::flat_cfr::FlatCfr;
+use crate::lookup_table::evaluate_board;
+use crate::postflop_tree::{
+    build_tree, collect_node_metadata, same_for_both, BetSize, Player, RaiseSizing, TerminalType,
+    TreeConfig, TreeNode,
+};
+use crate::progress::{report, Progress};
 use crate::ranges::parse_range;
 
 // ---------------------------------------------------------------------------
@@ -34,13 +53,17 @@ pub fn expand_range_to_combos(range: &[String], board: &[u8]) -> Vec<Combo> {
         s
     };
 
+    // Suit-specific entries ("AhKh") can overlap a class ("AKs") in the same range
+    let mut seen = [[false; 52]; 52];
     let mut combos = Vec::new();
     for hand in range {
         if let Ok(pairs) = hand_combos(hand) {
             for (c1, c2) in pairs {
                 let i1 = card_to_index(&c1);
                 let i2 = card_to_index(&c2);
-                if !board_set[i1 as usize] && !board_set[i2 as usize] {
+                let (lo, hi) = (i1.min(i2) as usize, i1.max(i2) as usize);
+                if !board_set[i1 as usize] && !board_set[i2 as usize] && !seen[lo][hi] {
+                    seen[lo][hi] = true;
                     combos.push(Combo(i1, i2));
                 }
             }
@@ -65,6 +88,11 @@ pub struct ShowdownTable {
     pub oop_scores: Vec<u32>,
     /// 7-card eval score for each IP combo against the board.
     pub ip_scores: Vec<u32>,
+    /// Set on a table from [`ShowdownTable::bucketed`], whose rows are equity
+    /// buckets: `[oop_bucket * num_ip + ip_bucket]` counts the
+    /// non-conflicting combo pairs OOP wins, ties and loses. The combo and
+    /// score fields are then empty.
+    pub bucket_outcomes: Option<Vec<[u32; 3]>>,
 }
 
 impl ShowdownTable {
@@ -73,21 +101,10 @@ impl ShowdownTable {
         assert_eq!(board.len(), 5, "River board must have exactly 5 cards");
 
         // Precompute 7-card scores for each combo
-        let oop_scores: Vec<u32> = oop_combos
-            .iter()
-            .map(|c| {
-                let hand = [c.0, c.1, board[0], board[1], board[2], board[3], board[4]];
-                evaluate_fast(&hand)
-            })
-            .collect();
-
-        let ip_scores: Vec<u32> = ip_combos
-            .iter()
-            .map(|c| {
-                let hand = [c.0, c.1, board[0], board[1], board[2], board[3], board[4]];
-                evaluate_fast(&hand)
-            })
-            .collect();
+        let board_cards = [board[0], board[1], board[2], board[3], board[4]];
+        let holes = |combos: &[Combo]| combos.iter().map(|c| [c.0, c.1]).collect::<Vec<_>>();
+        let oop_scores = evaluate_board(&board_cards, &holes(&oop_combos));
+        let ip_scores = evaluate_board(&board_cards, &holes(&ip_combos));
 
         // Build blocker-aware validity tables
         let valid_ip_for_oop: Vec<Vec<u16>> = oop_combos
@@ -125,15 +142,164 @@ impl ShowdownTable {
             valid_oop_for_ip,
             oop_scores,
             ip_scores,
+            bucket_outcomes: None,
+        }
+    }
+
+    /// This table with combos grouped into buckets (`oop_buckets[i]` is
+    /// the bucket of OOP combo `i`). A pair of buckets is valid when any of
+    /// their combo pairs is.
+    pub fn bucketed(&self, oop_buckets: &[u16], ip_buckets: &[u16]) -> ShowdownTable {
+        let count = |buckets: &[u16]| buckets.iter().map(|&b| b as usize + 1).max().unwrap_or(0);
+        let (num_oop, num_ip) = (count(oop_buckets), count(ip_buckets));
+
+        let mut outcomes = vec![[0u32; 3]; num_oop * num_ip];
+        for (i, valid) in self.valid_ip_for_oop.iter().enumerate() {
+            let row = oop_buckets[i] as usize * num_ip;
+            for &j in valid {
+                let j = j as usize;
+                let outcome = match self.oop_scores[i].cmp(&self.ip_scores[j]) {
+                    std::cmp::Ordering::Greater => 0,
+                    std::cmp::Ordering::Equal => 1,
+                    std::cmp::Ordering::Less => 2,
+                };
+                outcomes[row + ip_buckets[j] as usize][outcome] += 1;
+            }
+        }
+
+        let dealt = |b: usize, c: usize| outcomes[b * num_ip + c] != [0; 3];
+        let valid_ip_for_oop = (0..num_oop)
+            .map(|b| (0..num_ip).filter(|&c| dealt(b, c)).map(|c| c as u16).collect())
+            .collect();
+        let valid_oop_for_ip = (0..num_ip)
+            .map(|c| (0..num_oop).filter(|&b| dealt(b, c)).map(|b| b as u16).collect())
+            .collect();
+
+        ShowdownTable {
+            oop_combos: Vec::new(),
+            ip_combos: Vec::new(),
+            valid_ip_for_oop,
+            valid_oop_for_ip,
+            oop_scores: Vec::new(),
+            ip_scores: Vec::new(),
+            bucket_outcomes: Some(outcomes),
         }
     }
 
+    /// OOP rows: combos, or buckets in a bucketed table.
     pub fn num_oop(&self) -> usize {
-        self.oop_combos.len()
+        self.valid_ip_for_oop.len()
     }
 
+    /// IP rows: combos, or buckets in a bucketed table.
     pub fn num_ip(&self) -> usize {
-        self.ip_combos.len()
+        self.valid_oop_for_ip.len()
+    }
+
+    /// Showdowns the traverser's row `hand` wins, ties and loses against
+    /// the opponent's row `opp` of a bucketed table.
+    fn bucket_outcome(&self, outcomes: &[[u32; 3]], traverser: Player, hand: usize, opp: usize) -> [u32; 3] {
+        match traverser {
+            Player::OOP => outcomes[hand * self.num_ip() + opp],
+            Player::IP => {
+                let [won, tied, lost] = outcomes[opp * self.num_ip() + hand];
+                [lost, tied, won]
+            }
+        }
+    }
+
+    /// Bytes behind the table's arrays, validity lists included, at their
+    /// allocated capacity.
+    pub fn memory_bytes(&self) -> usize {
+        fn valid_bytes(lists: &Vec<Vec<u16>>) -> usize {
+            lists.capacity() * std::mem::size_of::<Vec<u16>>()
+                + lists.iter().map(|l| l.capacity() * 2).sum::<usize>()
+        }
+        (self.oop_combos.capacity() + self.ip_combos.capacity()) * std::mem::size_of::<Combo>()
+            + valid_bytes(&self.valid_ip_for_oop)
+            + valid_bytes(&self.valid_oop_for_ip)
+            + (self.oop_scores.capacity() + self.ip_scores.capacity()) * 4
+            + self.bucket_outcomes.as_ref().map_or(0, |o| o.capacity() * std::mem::size_of::<[u32; 3]>())
+    }
+}
+
+/// Fraction of the combined ranges, by showdown score, that counts as the nuts
+/// for `range_advantage`.
+pub const NUT_FRACTION: f64 = 0.1;
+
+/// Who the board favors before any betting: raw showdown equity and how much
+/// of each range sits in the nuts, at uniform reach.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RangeAdvantage {
+    /// OOP's showdown equity against IP's whole range (ties split).
+    pub oop_equity: f64,
+    /// Fraction of each range among the top combos of both ranges pooled,
+    /// `[oop, ip]`.
+    pub nut_share: [f64; 2],
+}
+
+impl RangeAdvantage {
+    /// The player holding more of their range in the nuts, if either does.
+    pub fn nut_advantage(&self) -> Option<Player> {
+        let [oop, ip] = self.nut_share;
+        if (oop - ip).abs() < 1e-9 {
+            None
+        } else if oop > ip {
+            Some(Player::OOP)
+        } else {
+            Some(Player::IP)
+        }
+    }
+
+    /// One-line summary, e.g. "OOP equity 55%, nut advantage OOP".
+    pub fn summary(&self) -> String {
+        let nuts = match self.nut_advantage() {
+            Some(p) => format!("nut advantage {:?}", p),
+            None => "no nut advantage".to_string(),
+        };
+        format!("OOP equity {:.0}%, {}", self.oop_equity * 100.0, nuts)
+    }
+}
+
+/// Equity and nut advantage over a showdown table with every combo equally
+/// likely. The top `nut_fraction` of both ranges pooled by score are the nuts
+/// (every combo tied with the cutoff score included).
+pub fn range_advantage(showdown: &ShowdownTable, nut_fraction: f64) -> RangeAdvantage {
+    let mut equity = 0.0;
+    let mut pairs = 0usize;
+    for (i, valid) in showdown.valid_ip_for_oop.iter().enumerate() {
+        let oop_score = showdown.oop_scores[i];
+        for &j in valid {
+            let ip_score = showdown.ip_scores[j as usize];
+            equity += match oop_score.cmp(&ip_score) {
+                std::cmp::Ordering::Greater => 1.0,
+                std::cmp::Ordering::Equal => 0.5,
+                std::cmp::Ordering::Less => 0.0,
+            };
+        }
+        pairs += valid.len();
+    }
+
+    let mut pooled: Vec<u32> = showdown
+        .oop_scores
+        .iter()
+        .chain(&showdown.ip_scores)
+        .copied()
+        .collect();
+    pooled.sort_unstable_by(|a, b| b.cmp(a));
+    let nut_count = ((pooled.len() as f64 * nut_fraction).ceil() as usize).clamp(1, pooled.len().max(1));
+    let cutoff = pooled.get(nut_count - 1).copied().unwrap_or(u32::MAX);
+    let share = |scores: &[u32]| {
+        if scores.is_empty() {
+            0.0
+        } else {
+            scores.iter().filter(|&&s| s >= cutoff).count() as f64 / scores.len() as f64
+        }
+    };
+
+    RangeAdvantage {
+        oop_equity: if pairs > 0 { equity / pairs as f64 } else { 0.5 },
+        nut_share: [share(&showdown.oop_scores), share(&showdown.ip_scores)],
     }
 }
 
@@ -141,16 +307,75 @@ impl ShowdownTable {
 // Solver config & result
 // ---------------------------------------------------------------------------
 
+/// What the river solver trains a strategy for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Abstraction {
+    /// Every combo on its own: exact, and the default.
+    #[default]
+    None,
+    /// Each range grouped into this many equal-frequency buckets by equity
+    /// against a random hand (see `assign_buckets`). Much faster for big
+    /// ranges; blockers are only counted on average within a bucket.
+    EquityBuckets(usize),
+}
+
+impl std::fmt::Display for Abstraction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Abstraction::None => write!(f, "exact combos"),
+            Abstraction::EquityBuckets(n) => write!(f, "{} equity buckets", n),
+        }
+    }
+}
+
 pub struct RiverSolverConfig {
     pub board: Vec<u8>,
     pub oop_range: Vec<String>,
     pub ip_range: Vec<String>,
     pub starting_pot: f64,
+    /// Chips in the pot that neither player invested (antes, folded
+    /// players' bets). Added to the pot when the tree is built, so it's won
+    /// at showdown or on a fold but never refunded.
+    pub dead_money: f64,
     pub effective_stack: f64,
     pub iterations: usize,
-    pub bet_sizes: Vec<f64>,
-    pub raise_sizes: Vec<f64>,
+    /// Opening bet sizes per player, `[oop, ip]`.
+    pub bet_sizes: [Vec<BetSize>; 2],
+    /// Raise sizes per player, `[oop, ip]`.
+    pub raise_sizes: [RaiseSizing; 2],
     pub max_raises: usize,
+    /// Storage for regrets and strategy sums; f32 halves info-set memory.
+    pub precision: Precision,
+    /// Regret and strategy-sum update rule.
+    pub cfr_variant: CfrVariant,
+    /// Full or sampled traversal each iteration.
+    pub algorithm: SolverAlgorithm,
+    /// Seed for sampling and pruning draws; `None` seeds from entropy.
+    pub seed: Option<u64>,
+    /// Skip subtrees of actions whose regret has been zero for this many
+    /// iterations (regret-based pruning). `None` disables pruning. Only
+    /// the training traversal prunes: exploitability is always measured on
+    /// the full tree, so a pruned solve reports an honest number.
+    pub prune_threshold: Option<usize>,
+    /// Stack-to-pot fraction above which an extra all-in is offered.
+    pub allin_threshold: f64,
+    /// Bets or raises above this percentage of the stack become all-ins.
+    pub force_allin_above_stack_pct: Option<f64>,
+    /// Whether each player may raise, `[oop, ip]`.
+    pub can_raise: [bool; 2],
+    /// Per-player stacks behind, `[oop, ip]`, when they differ. `None`
+    /// gives both players `effective_stack`.
+    pub stacks: Option<[f64; 2]>,
+    /// Write a checkpoint to `checkpoint_path()` every this many
+    /// iterations. `None` disables checkpointing.
+    pub checkpoint_every: Option<usize>,
+    /// Measure exploitability every this many iterations and keep the
+    /// series in the solution. `None` skips it, since each point costs a
+    /// best-response pass.
+    pub convergence_every: Option<usize>,
+    /// Combos, or equity buckets of them, as the unit a strategy is
+    /// trained for.
+    pub abstraction: Abstraction,
 }
 
 impl RiverSolverConfig {
@@ -182,22 +407,132 @@ impl RiverSolverConfig {
             oop_range,
             ip_range,
             starting_pot,
+            dead_money: 0.0,
             effective_stack,
             iterations,
-            bet_sizes: vec![0.33, 0.67, 1.0],
-            raise_sizes: vec![1.0],
+            bet_sizes: same_for_both(vec![
+                BetSize::Pot(0.33),
+                BetSize::Pot(0.67),
+                BetSize::Pot(1.0),
+            ]),
+            raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![1.0])),
             max_raises: 3,
+            precision: Precision::F64,
+            cfr_variant: CfrVariant::CfrPlus,
+            algorithm: SolverAlgorithm::Cfr,
+            seed: None,
+            prune_threshold: None,
+            allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+            force_allin_above_stack_pct: None,
+            can_raise: [true; 2],
+            stacks: None,
+            checkpoint_every: None,
+            convergence_every: None,
+            abstraction: Abstraction::None,
         })
     }
+
+    /// Hash of every field that shapes the game or the stored CFR state.
+    /// Iterations, seed and pruning are left out so a resumed solve may run
+    /// longer or sample differently than the one that wrote the checkpoint.
+    pub fn config_hash(&self) -> u64 {
+        checkpoint::config_hash(&(
+            (&self.board, &self.oop_range, &self.ip_range),
+            (self.starting_pot, self.dead_money, self.effective_stack, self.stacks),
+            (&self.bet_sizes, &self.raise_sizes, self.max_raises, self.can_raise),
+            (self.allin_threshold, self.force_allin_above_stack_pct),
+            (self.precision, self.cfr_variant, self.algorithm, self.abstraction),
+        ))
+    }
+
+    /// `config_hash` plus the iteration count. Keys the solution cache, so
+    /// a cached solution is only reused for the exact solve that wrote it.
+    pub fn cache_hash(&self) -> u64 {
+        checkpoint::config_hash(&(self.config_hash(), self.iterations))
+    }
+
+    /// Where checkpoints for this config are written and resumed from.
+    pub fn checkpoint_path(&self) -> PathBuf {
+        let board: String = self.board.iter().map(|&c| index_to_card(c).to_string()).collect();
+        checkpoint::solver_dir().join(format!("river_{}_{:016x}.ckpt", board, self.config_hash()))
+    }
+
+    /// River tree config with this config's sizing applied.
+    pub fn tree_config(&self) -> TreeConfig {
+        TreeConfig {
+            bet_sizes: self.bet_sizes.clone(),
+            raise_sizes: self.raise_sizes.clone(),
+            max_raises: self.max_raises,
+            starting_pot: self.starting_pot + self.dead_money,
+            stacks: self.stacks.unwrap_or([self.effective_stack; 2]),
+            add_allin: true,
+            min_bet: 0.0,
+            bet_round_to: None,
+            merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+            allin_threshold: self.allin_threshold,
+            force_allin_above_stack_pct: self.force_allin_above_stack_pct,
+            can_raise: self.can_raise,
+            max_check_raises: None,
+        }
+    }
 }
 
 /// Per-node strategy: action frequencies for each combo.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeStrategy {
-    pub node_id: u16,
+    pub node_id: u32,
     pub player: String,
     pub actions: Vec<String>,
+    /// Pot-relative labels parallel to `actions` ("Bet 67% (6.7)") for
+    /// display. Empty in older solutions.
+    #[serde(default)]
+    pub labels: Vec<String>,
     pub frequencies: Vec<Vec<f64>>, // [combo_idx][action_idx]
+    /// Probability each combo of the acting player plays to this node under
+    /// the average strategy. Empty in older solutions (treated as 1.0).
+    #[serde(default)]
+    pub reach: Vec<f64>,
+}
+
+impl NodeStrategy {
+    /// Labels to show a reader: the pot-relative ones when recorded, else
+    /// the plain action labels.
+    pub fn display_labels(&self) -> &[String] {
+        if self.labels.len() == self.actions.len() {
+            &self.labels
+        } else {
+            &self.actions
+        }
+    }
+
+    /// Zero every action a combo takes less than `threshold` of the time and
+    /// renormalize the rest to sum to 1. A combo with every action below the
+    /// threshold keeps only its most frequent action. Cosmetic cleanup of the
+    /// average strategy for charts; it is not re-solved.
+    pub fn purify(&mut self, threshold: f64) {
+        for freqs in &mut self.frequencies {
+            purify_frequencies(freqs, threshold);
+        }
+    }
+}
+
+/// [`NodeStrategy::purify`] for one combo's action frequencies.
+pub fn purify_frequencies(freqs: &mut [f64], threshold: f64) {
+    let kept: f64 = freqs.iter().filter(|&&f| f >= threshold).sum();
+    if kept > 0.0 {
+        for f in freqs.iter_mut() {
+            *f = if *f >= threshold { *f / kept } else { 0.0 };
+        }
+    } else if let Some(max) = freqs
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(i, _)| i)
+    {
+        for (i, f) in freqs.iter_mut().enumerate() {
+            *f = if i == max { 1.0 } else { 0.0 };
+        }
+    }
 }
 
 /// Full solution from the river solver.
@@ -219,6 +554,242 @@ pub struct RiverSolution {
     /// IP position label (e.g. "BTN") — used in cache key.
     #[serde(default)]
     pub ip_pos: String,
+    /// `RiverSolverConfig::cache_hash` of the solve, part of the cache key.
+    #[serde(default)]
+    pub config_hash: u64,
+    /// Opening bet sizes the tree was built with, `[oop, ip]`.
+    #[serde(default)]
+    pub bet_sizes: [Vec<BetSize>; 2],
+    /// Raise sizes the tree was built with, `[oop, ip]`.
+    #[serde(default)]
+    pub raise_sizes: [RaiseSizing; 2],
+    /// All-in threshold the tree was built with (fraction of pot).
+    #[serde(default = "default_allin_threshold")]
+    pub allin_threshold: f64,
+    /// Forced all-in line the tree was built with (percent of stack).
+    #[serde(default)]
+    pub force_allin_above_stack_pct: Option<f64>,
+    /// Which players could raise in the tree, `[oop, ip]`.
+    #[serde(default = "default_can_raise")]
+    pub can_raise: [bool; 2],
+    /// Dead money the pot held on top of `starting_pot`.
+    #[serde(default)]
+    pub dead_money: f64,
+    /// Per-player stacks the tree was built with, when they differed.
+    #[serde(default)]
+    pub stacks: Option<[f64; 2]>,
+    /// Equity and nut advantage before any betting. `None` for an empty spot
+    /// or an older solution.
+    #[serde(default)]
+    pub range_advantage: Option<RangeAdvantage>,
+    /// Update rule the solve ran with.
+    #[serde(default)]
+    pub cfr_variant: CfrVariant,
+    /// Traversal the solve ran with.
+    #[serde(default)]
+    pub algorithm: SolverAlgorithm,
+    /// Regret and strategy-sum storage the solve ran with.
+    #[serde(default)]
+    pub precision: Precision,
+    /// Bytes the trainer held at the end of the solve (`RiverCfr::memory_bytes`).
+    #[serde(default)]
+    pub trainer_bytes: usize,
+    /// Bytes the showdown table held (`ShowdownTable::memory_bytes`).
+    #[serde(default)]
+    pub showdown_bytes: usize,
+    /// Traverser actions the training traversal skipped by pruning, summed
+    /// over every visit. 0 without `prune_threshold`.
+    #[serde(default)]
+    pub skipped_subtrees: u64,
+    /// The game tree the strategies were solved on, so node ids and action
+    /// amounts can be read back without rebuilding it. `None` for an empty
+    /// spot or an older solution.
+    #[serde(default)]
+    pub tree: Option<TreeNode>,
+    /// Set by [`simplify_solution`]: `strategies` then hold the simplified
+    /// frequencies and this keeps the solved ones.
+    #[serde(default)]
+    pub simplification: Option<Simplification>,
+    /// `(iterations, exploitability)` sampled every
+    /// `RiverSolverConfig::convergence_every` iterations. Empty when
+    /// sampling was off.
+    #[serde(default)]
+    pub convergence: Vec<(usize, f64)>,
+    /// What the strategies were trained for. Under equity buckets every
+    /// combo of a bucket carries the bucket's frequencies.
+    #[serde(default)]
+    pub abstraction: Abstraction,
+}
+
+/// What a [`simplify_solution`] pass changed and what it cost.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Simplification {
+    /// Frequencies below this were zeroed.
+    pub threshold: f64,
+    /// Exploitability of the simplified strategies, in chips.
+    pub exploitability: f64,
+    /// The strategies as solved, before simplification.
+    pub original_strategies: Vec<NodeStrategy>,
+}
+
+pub(crate) fn default_allin_threshold() -> f64 {
+    TreeConfig::DEFAULT_ALLIN_THRESHOLD
+}
+
+pub(crate) fn default_can_raise() -> [bool; 2] {
+    [true; 2]
+}
+
+// ---------------------------------------------------------------------------
+// Flat CFR storage
+// ---------------------------------------------------------------------------
+
+/// Regrets and strategy sums of a river solve: one `FlatCfr` per player
+/// with a node for every action node id (nodes where the other player acts
+/// hold no combos), so the traversal indexes contiguous arrays by
+/// `(node, combo, action)` instead of hashing an `InfoSetKey` per visit.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct RiverCfr<F: CfrFloat = f64> {
+    cfr: [FlatCfr<F>; 2],
+    /// Last iteration each entry's regret was positive, laid out like the
+    /// player's `FlatCfr`. Empty unless the solve prunes.
+    last_positive: [Vec<u32>; 2],
+    variant: CfrVariant,
+}
+
+impl<F: CfrFloat> RiverCfr<F> {
+    /// Zeroed storage for every combo at every action node of `tree`.
+    fn new(tree: &TreeNode, showdown: &ShowdownTable, variant: CfrVariant) -> Self {
+        let metas = collect_node_metadata(tree);
+        let combos = [showdown.num_oop() as u16, showdown.num_ip() as u16];
+        let cfr = [Player::OOP, Player::IP].map(|player| {
+            let nodes: Vec<(u8, u16)> = metas
+                .iter()
+                .map(|m| {
+                    let hands = if m.player == player { combos[player.index()] } else { 0 };
+                    (m.num_actions, hands)
+                })
+                .collect();
+            FlatCfr::from_nodes(&nodes)
+        });
+        RiverCfr { cfr, last_positive: [Vec::new(), Vec::new()], variant }
+    }
+
+    /// Allocate the pruning bookkeeping if `pruning` needs it and a
+    /// checkpoint from an unpruned solve didn't carry any.
+    fn with_pruning(mut self, pruning: bool) -> Self {
+        if pruning {
+            for (last, cfr) in self.last_positive.iter_mut().zip(&self.cfr) {
+                if last.is_empty() {
+                    *last = vec![0; cfr.total_entries()];
+                }
+            }
+        }
+        self
+    }
+
+    /// Index into `cfr` of the player acting at `node_id`.
+    #[inline]
+    fn actor(&self, node_id: u32) -> usize {
+        if self.cfr[0].node_num_hands(node_id as usize) > 0 {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Bytes held: both players' `FlatCfr` arrays plus the pruning
+    /// bookkeeping, at their allocated capacity.
+    pub fn memory_bytes(&self) -> usize {
+        let last_positive: usize = self.last_positive.iter().map(Vec::capacity).sum();
+        self.cfr.iter().map(FlatCfr::memory_bytes).sum::<usize>() + last_positive * std::mem::size_of::<u32>()
+    }
+
+    /// What `memory_bytes` comes to for a tree of `nodes` action nodes
+    /// holding `entries` (node, combo, action) entries between both players.
+    pub fn estimated_bytes(nodes: usize, entries: usize, pruning: bool) -> usize {
+        let last_positive = if pruning { entries * std::mem::size_of::<u32>() } else { 0 };
+        FlatCfr::<F>::estimated_bytes(2 * nodes, entries) + last_positive
+    }
+}
+
+/// What a river solve reads and writes its CFR state through. `RiverCfr`
+/// backs every solve; the trait keeps the traversal independent of the
+/// layout so it can be checked against the map-backed `CfrTrainer`.
+trait RiverStore: AverageStrategy + Serialize {
+    /// Current regret-matching strategy of combo `hand` at `node_id`.
+    fn strategy(&self, node_id: u32, hand: usize, num_actions: usize) -> Vec<f64>;
+
+    /// Apply one traversal's update to combo `hand` at `node_id`; see
+    /// `InfoSetData::update`.
+    fn update(
+        &mut self,
+        node_id: u32,
+        hand: usize,
+        action_values: &[f64],
+        node_value: f64,
+        reach_prob: f64,
+        iteration: usize,
+    );
+
+    /// Record `iteration` as the last time each positive-regret action was
+    /// live; see `InfoSetData::mark_positive`.
+    fn mark_positive(&mut self, node_id: u32, hand: usize, iteration: usize);
+
+    /// Whether action `a` is a pruning candidate; see
+    /// `InfoSetData::is_prunable`.
+    fn is_prunable(&self, node_id: u32, hand: usize, a: usize, iteration: usize, threshold: usize) -> bool;
+
+    /// Bytes the state holds.
+    fn memory_bytes(&self) -> usize;
+}
+
+impl<F: CfrFloat> RiverStore for RiverCfr<F> {
+    #[inline]
+    fn strategy(&self, node_id: u32, hand: usize, _num_actions: usize) -> Vec<f64> {
+        self.cfr[self.actor(node_id)].current_strategy_f64(node_id as usize, hand)
+    }
+
+    #[inline]
+    fn update(
+        &mut self,
+        node_id: u32,
+        hand: usize,
+        action_values: &[f64],
+        node_value: f64,
+        reach_prob: f64,
+        iteration: usize,
+    ) {
+        let (p, variant) = (self.actor(node_id), self.variant);
+        self.cfr[p].update_variant(node_id as usize, hand, action_values, node_value, reach_prob, variant, iteration);
+    }
+
+    fn mark_positive(&mut self, node_id: u32, hand: usize, iteration: usize) {
+        let p = self.actor(node_id);
+        if self.last_positive[p].is_empty() {
+            return;
+        }
+        let base = self.cfr[p].base(node_id as usize, hand);
+        for (a, &r) in self.cfr[p].regrets(node_id as usize, hand).iter().enumerate() {
+            if r > F::ZERO {
+                self.last_positive[p][base + a] = iteration as u32;
+            }
+        }
+    }
+
+    fn is_prunable(&self, node_id: u32, hand: usize, a: usize, iteration: usize, threshold: usize) -> bool {
+        let p = self.actor(node_id);
+        let regrets = self.cfr[p].regrets(node_id as usize, hand);
+        let last = self.last_positive[p][self.cfr[p].base(node_id as usize, hand) + a];
+        regrets[a] <= F::ZERO
+            && regrets.iter().any(|&r| r > F::ZERO)
+            && iteration.saturating_sub(last as usize) > threshold
+    }
+
+    fn memory_bytes(&self) -> usize {
+        RiverCfr::memory_bytes(self)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -227,15 +798,40 @@ pub struct RiverSolution {
 
 /// Solve a river spot.
 pub fn solve_river(config: &RiverSolverConfig) -> RiverSolution {
-    let tree_config = TreeConfig {
-        bet_sizes: config.bet_sizes.clone(),
-        raise_sizes: config.raise_sizes.clone(),
-        max_raises: config.max_raises,
-        starting_pot: config.starting_pot,
-        effective_stack: config.effective_stack,
-        add_allin: true,
-    };
+    solve_river_with_progress(config, None)
+}
+
+/// Solve a river spot, reporting `(iterations done, total)` to `progress`.
+pub fn solve_river_with_progress(config: &RiverSolverConfig, progress: Progress) -> RiverSolution {
+    match config.precision {
+        Precision::F32 => solve_from::<f32>(config, None, progress),
+        Precision::F64 => solve_from::<f64>(config, None, progress),
+    }
+}
+
+/// Continue a river solve from the checkpoint at `config.checkpoint_path()`
+/// up to `config.iterations`. Fails if the checkpoint is missing, unreadable
+/// or was written by a config with a different `config_hash`.
+pub fn resume_river(config: &RiverSolverConfig, progress: Progress) -> Result<RiverSolution, String> {
+    let path = config.checkpoint_path();
+    let hash = config.config_hash();
+    Ok(match config.precision {
+        Precision::F32 => solve_from::<f32>(config, Some(checkpoint::load_matching(&path, hash)?), progress),
+        Precision::F64 => solve_from::<f64>(config, Some(checkpoint::load_matching(&path, hash)?), progress),
+    })
+}
+
+/// Iterations done, the CFR state and the sampling RNG, as a checkpoint
+/// holds them.
+type ResumeState<F> = (usize, (RiverCfr<F>, SolverRng));
 
+/// Run the solve, starting from `resumed` when given.
+fn solve_from<F: CfrFloat>(
+    config: &RiverSolverConfig,
+    resumed: Option<ResumeState<F>>,
+    progress: Progress,
+) -> RiverSolution {
+    let tree_config = config.tree_config();
     let (tree, _num_nodes) = build_tree(&tree_config);
 
     let oop_combos = expand_range_to_combos(&config.oop_range, &config.board);
@@ -246,14 +842,137 @@ pub fn solve_river(config: &RiverSolverConfig) -> RiverSolution {
     }
 
     let showdown = ShowdownTable::new(oop_combos, ip_combos, &config.board);
-    let mut trainer = CfrTrainer::new();
+    let abstraction = BucketAbstraction::new(config.abstraction, &showdown, &config.board);
+    let train = abstraction.as_ref().map_or(&showdown, |a| &a.showdown);
+    let (start_iter, (cfr, rng)) = resumed.unwrap_or_else(|| {
+        (0, (RiverCfr::new(&tree, train, config.cfr_variant), solver_rng(config.seed)))
+    });
+    let cfr = cfr.with_pruning(config.prune_threshold.is_some());
+    run_cfr(config, &tree, &showdown, abstraction.as_ref(), cfr, rng, start_iter, progress)
+}
+
+/// Combos grouped into equity buckets for an abstracted solve.
+struct BucketAbstraction {
+    /// Bucket of each combo, `[oop, ip]`.
+    buckets: [Vec<u16>; 2],
+    /// Showdowns between buckets, which the traversal trains on.
+    showdown: ShowdownTable,
+}
+
+impl BucketAbstraction {
+    /// `None` when `abstraction` keeps every combo.
+    fn new(abstraction: Abstraction, showdown: &ShowdownTable, board: &[u8]) -> Option<Self> {
+        let Abstraction::EquityBuckets(num_buckets) = abstraction else {
+            return None;
+        };
+        // Equity on the river is enumerated, so no samples are drawn
+        let bucket = |combos: &[Combo]| {
+            let pairs: Vec<(u8, u8)> = combos.iter().map(|c| (c.0, c.1)).collect();
+            assign_buckets(&pairs, board, num_buckets.max(1), 0)
+        };
+        let buckets = [bucket(&showdown.oop_combos), bucket(&showdown.ip_combos)];
+        let bucketed = showdown.bucketed(&buckets[0], &buckets[1]);
+        Some(BucketAbstraction { buckets, showdown: bucketed })
+    }
+}
+
+/// A solve's average strategies read per combo. An abstracted solve stores
+/// a row per bucket, so each combo reads its bucket's row.
+struct ComboView<'a, S> {
+    store: &'a S,
+    buckets: Option<&'a [Vec<u16>; 2]>,
+    /// Player acting at each node id; empty without buckets.
+    actors: Vec<Player>,
+}
+
+impl<'a, S: AverageStrategy> ComboView<'a, S> {
+    fn new(store: &'a S, abstraction: Option<&'a BucketAbstraction>, tree: &TreeNode) -> Self {
+        let actors = match abstraction {
+            Some(_) => collect_node_metadata(tree).iter().map(|m| m.player).collect(),
+            None => Vec::new(),
+        };
+        ComboView { store, buckets: abstraction.map(|a| &a.buckets), actors }
+    }
+}
+
+impl<S: AverageStrategy> AverageStrategy for ComboView<'_, S> {
+    fn average(&self, node_id: u32, hand_idx: usize, num_actions: usize) -> Vec<f64> {
+        let row = match self.buckets {
+            Some(buckets) => buckets[self.actors[node_id as usize].index()][hand_idx] as usize,
+            None => hand_idx,
+        };
+        self.store.average(node_id, row, num_actions)
+    }
+}
+
+/// Save a checkpoint if `done` iterations lands on the configured interval.
+/// Best effort, like the solution caches: a failed write doesn't stop the solve.
+fn maybe_checkpoint<S: RiverStore>(config: &RiverSolverConfig, store: &S, rng: &SolverRng, done: usize) {
+    let Some(every) = config.checkpoint_every else {
+        return;
+    };
+    if every > 0 && done.is_multiple_of(every) {
+        let header = CheckpointHeader { iteration: done, config_hash: config.config_hash() };
+        checkpoint::save(&config.checkpoint_path(), header, &(store, rng)).ok();
+    }
+}
+
+/// Record the exploitability after `done` iterations if it lands on the
+/// configured convergence interval.
+fn maybe_record_convergence<S: AverageStrategy>(
+    config: &RiverSolverConfig,
+    tree: &TreeNode,
+    strategy: &S,
+    abstraction: Option<&BucketAbstraction>,
+    showdown: &ShowdownTable,
+    done: usize,
+    convergence: &mut Vec<(usize, f64)>,
+) {
+    let Some(every) = config.convergence_every else {
+        return;
+    };
+    if every > 0 && done.is_multiple_of(every) {
+        let view = ComboView::new(strategy, abstraction, tree);
+        convergence.push((done, strategy_exploitability(tree, &view, showdown)));
+    }
+}
+
+/// Run the CFR+ iterations on `store`, over the buckets of `abstraction`
+/// when given and the combos of `showdown` otherwise.
+#[allow(clippy::too_many_arguments)]
+fn run_cfr<S: RiverStore>(
+    config: &RiverSolverConfig,
+    tree: &TreeNode,
+    exact: &ShowdownTable,
+    abstraction: Option<&BucketAbstraction>,
+    mut store: S,
+    mut rng: SolverRng,
+    start_iter: usize,
+    progress: Progress,
+) -> RiverSolution {
+    let showdown = abstraction.map_or(exact, |a| &a.showdown);
+    let mut convergence = Vec::new();
+    let mut skipped_subtrees = 0;
 
     // Run alternating CFR+ iterations
-    for iter in 0..config.iterations {
+    for iter in start_iter..config.iterations {
         let traverser = if iter % 2 == 0 { Player::OOP } else { Player::IP };
 
+        if config.algorithm == SolverAlgorithm::Mccfr {
+            mccfr_iteration(tree, traverser, showdown, &mut store, iter, &mut rng);
+            maybe_checkpoint(config, &store, &rng, iter + 1);
+            maybe_record_convergence(config, tree, &store, abstraction, exact, iter + 1, &mut convergence);
+            report(progress, iter + 1, config.iterations);
+            continue;
+        }
+
         // Snapshot opponent strategies
-        let opp_snapshot = snapshot_strategies(&trainer, &tree, traverser.opponent(), &showdown);
+        let opp_snapshot = snapshot_strategies(&store, tree, traverser.opponent(), showdown);
+        let ctx = TraverseCtx {
+            iteration: iter,
+            prune_threshold: config.prune_threshold,
+            skipped: Cell::new(0),
+        };
 
         let num_combos = match traverser {
             Player::OOP => showdown.num_oop(),
@@ -282,83 +1001,106 @@ pub fn solve_river(config: &RiverSolverConfig) -> RiverSolution {
             };
 
             cfr_traverse(
-                &tree,
+                tree,
                 traverser,
                 h,
+                1.0,
                 &opp_reach,
-                &showdown,
+                showdown,
                 &opp_snapshot,
-                &mut trainer,
+                &mut store,
+                &ctx,
+                &mut rng,
             );
         }
+        skipped_subtrees += ctx.skipped.get();
+        maybe_checkpoint(config, &store, &rng, iter + 1);
+        maybe_record_convergence(config, tree, &store, abstraction, exact, iter + 1, &mut convergence);
+        report(progress, iter + 1, config.iterations);
     }
 
     // Extract solution
-    extract_solution(config, &tree, &trainer, &showdown)
+    let mut solution = extract_solution(config, tree, &store, abstraction, exact, convergence);
+    solution.skipped_subtrees = skipped_subtrees;
+    solution
+}
+
+/// Probability of skipping a prunable action on a given visit. The rest of the
+/// time it is explored so its regret can recover.
+const PRUNE_PROBABILITY: f64 = 0.95;
+
+/// Per-iteration settings threaded through `cfr_traverse`.
+struct TraverseCtx {
+    iteration: usize,
+    prune_threshold: Option<usize>,
+    /// Actions pruned so far this iteration.
+    skipped: Cell<u64>,
+}
+
+/// One player's current strategies, frozen for an iteration and laid out
+/// like `FlatCfr`: node, then combo, then action.
+struct StrategySnapshot {
+    /// Start of each node id's block in `probs`. Nodes where the other
+    /// player acts have empty blocks.
+    offsets: Vec<usize>,
+    num_actions: Vec<usize>,
+    probs: Vec<f64>,
+}
+
+impl StrategySnapshot {
+    /// Probability that combo `hand` takes action `a` at `node_id`.
+    #[inline]
+    fn get(&self, node_id: u32, hand: usize, a: usize) -> f64 {
+        let node = node_id as usize;
+        self.probs[self.offsets[node] + hand * self.num_actions[node] + a]
+    }
 }
 
 /// Snapshot all opponent strategies for the given player to avoid borrow conflicts.
-fn snapshot_strategies(
-    trainer: &CfrTrainer,
+fn snapshot_strategies<S: RiverStore>(
+    store: &S,
     tree: &TreeNode,
     player: Player,
     showdown: &ShowdownTable,
-) -> HashMap<u16, Vec<Vec<f64>>> {
-    let mut snapshot = HashMap::new();
+) -> StrategySnapshot {
     let num_combos = match player {
         Player::OOP => showdown.num_oop(),
         Player::IP => showdown.num_ip(),
     };
-    collect_strategies(tree, player, num_combos, trainer, &mut snapshot);
-    snapshot
-}
-
-fn collect_strategies(
-    node: &TreeNode,
-    player: Player,
-    num_combos: usize,
-    trainer: &CfrTrainer,
-    snapshot: &mut HashMap<u16, Vec<Vec<f64>>>,
-) {
-    match node {
-        TreeNode::Action {
-            node_id,
-            player: node_player,
-            children,
-            actions,
-            ..
-        } => {
-            if *node_player == player {
-                let num_actions = actions.len();
-                let strats: Vec<Vec<f64>> = (0..num_combos)
-                    .map(|h| {
-                        let key = InfoSetKey {
-                            hand_bucket: h as u16,
-                            node_id: *node_id,
-                        };
-                        trainer.get_strategy(&key, num_actions)
-                    })
-                    .collect();
-                snapshot.insert(*node_id, strats);
-            }
-            for child in children {
-                collect_strategies(child, player, num_combos, trainer, snapshot);
+    // Action node ids run 0..n, so the sorted metadata lines up with them
+    let metas = collect_node_metadata(tree);
+    let mut snapshot = StrategySnapshot {
+        offsets: Vec::with_capacity(metas.len()),
+        num_actions: Vec::with_capacity(metas.len()),
+        probs: Vec::new(),
+    };
+    for meta in &metas {
+        let num_actions = meta.num_actions as usize;
+        snapshot.offsets.push(snapshot.probs.len());
+        snapshot.num_actions.push(num_actions);
+        if meta.player == player {
+            for h in 0..num_combos {
+                snapshot.probs.extend(store.strategy(meta.node_id, h, num_actions));
             }
         }
-        TreeNode::Terminal { .. } | TreeNode::Chance { .. } => {}
     }
+    snapshot
 }
 
 /// Recursive CFR+ traversal for one traverser hand.
 /// Returns the counterfactual value of this node for the traverser.
-fn cfr_traverse(
+#[allow(clippy::too_many_arguments)]
+fn cfr_traverse<S: RiverStore, R: Rng>(
     node: &TreeNode,
     traverser: Player,
     hand_idx: usize,
+    my_reach: f64,
     opp_reach: &[f64],
     showdown: &ShowdownTable,
-    opp_snapshot: &HashMap<u16, Vec<Vec<f64>>>,
-    trainer: &mut CfrTrainer,
+    opp_snapshot: &StrategySnapshot,
+    store: &mut S,
+    ctx: &TraverseCtx,
+    rng: &mut R,
 ) -> f64 {
     match node {
         TreeNode::Terminal {
@@ -379,54 +1121,74 @@ fn cfr_traverse(
 
             if *player == traverser {
                 // Traverser node: compute per-action values, update regrets
-                let key = InfoSetKey {
-                    hand_bucket: hand_idx as u16,
-                    node_id: *node_id,
+                let strategy = store.strategy(*node_id, hand_idx, num_actions);
+                let pruned: Vec<bool> = match ctx.prune_threshold {
+                    Some(threshold) => (0..num_actions)
+                        .map(|a| {
+                            store.is_prunable(*node_id, hand_idx, a, ctx.iteration, threshold)
+                                && rng.gen::<f64>() < PRUNE_PROBABILITY
+                        })
+                        .collect(),
+                    None => vec![false; num_actions],
                 };
-                let strategy = trainer.get_strategy(&key, num_actions);
 
                 let mut action_values = vec![0.0f64; num_actions];
                 let mut node_value = 0.0;
 
                 for a in 0..num_actions {
+                    if pruned[a] {
+                        ctx.skipped.set(ctx.skipped.get() + 1);
+                        continue;
+                    }
                     action_values[a] = cfr_traverse(
-                        &children[a], traverser, hand_idx, opp_reach,
-                        showdown, opp_snapshot, trainer,
+                        &children[a], traverser, hand_idx, my_reach * strategy[a], opp_reach,
+                        showdown, opp_snapshot, store, ctx, rng,
                     );
                     node_value += strategy[a] * action_values[a];
                 }
 
-                // Compute reach probability (sum of opponent reach)
-                let reach_sum: f64 = opp_reach.iter().sum();
-                let reach_prob = if reach_sum > 0.0 { 1.0 } else { 0.0 };
+                // Pruned actions have zero probability; leaving them at the node
+                // value keeps their (floored) regret unchanged.
+                for a in 0..num_actions {
+                    if pruned[a] {
+                        action_values[a] = node_value;
+                    }
+                }
 
-                let data = trainer.get_or_create(&key, num_actions);
-                data.update(&action_values, node_value, reach_prob);
+                // The average strategy is weighted by the traverser's own
+                // probability of playing to this node
+                let reach_prob = my_reach;
+
+                // Each player updates on every other iteration
+                store.update(
+                    *node_id,
+                    hand_idx,
+                    &action_values,
+                    node_value,
+                    reach_prob,
+                    ctx.iteration / 2 + 1,
+                );
+                store.mark_positive(*node_id, hand_idx, ctx.iteration);
 
                 node_value
             } else {
                 // Opponent node: weight by opponent strategy, propagate modified reach
                 let num_opp_combos = opp_reach.len();
-                let opp_strats = opp_snapshot.get(node_id);
 
                 let mut node_value = 0.0;
 
-                for a in 0..num_actions {
+                for (a, child) in children.iter().enumerate() {
                     // Build new opp_reach weighted by opponent's strategy for this action
                     let mut new_opp_reach = vec![0.0f64; num_opp_combos];
                     for j in 0..num_opp_combos {
                         if opp_reach[j] > 0.0 {
-                            let sigma_j_a = match opp_strats {
-                                Some(strats) => strats[j][a],
-                                None => 1.0 / num_actions as f64,
-                            };
-                            new_opp_reach[j] = opp_reach[j] * sigma_j_a;
+                            new_opp_reach[j] = opp_reach[j] * opp_snapshot.get(*node_id, j, a);
                         }
                     }
 
                     node_value += cfr_traverse(
-                        &children[a], traverser, hand_idx, &new_opp_reach,
-                        showdown, opp_snapshot, trainer,
+                        child, traverser, hand_idx, my_reach, &new_opp_reach,
+                        showdown, opp_snapshot, store, ctx, rng,
                     );
                 }
 
@@ -437,6 +1199,132 @@ fn cfr_traverse(
     }
 }
 
+/// One external-sampling iteration: each traverser hand is dealt a random
+/// non-conflicting opponent hand and traversed once.
+fn mccfr_iteration<S: RiverStore, R: Rng>(
+    tree: &TreeNode,
+    traverser: Player,
+    showdown: &ShowdownTable,
+    store: &mut S,
+    iteration: usize,
+    rng: &mut R,
+) {
+    let num_combos = match traverser {
+        Player::OOP => showdown.num_oop(),
+        Player::IP => showdown.num_ip(),
+    };
+    for h in 0..num_combos {
+        let valid = match traverser {
+            Player::OOP => &showdown.valid_ip_for_oop[h],
+            Player::IP => &showdown.valid_oop_for_ip[h],
+        };
+        if valid.is_empty() {
+            continue;
+        }
+        let opp_idx = match &showdown.bucket_outcomes {
+            // Opponent buckets are dealt as often as the combo pairs in them
+            Some(outcomes) => {
+                let pairs: Vec<f64> = valid
+                    .iter()
+                    .map(|&c| showdown.bucket_outcome(outcomes, traverser, h, c as usize).iter().sum::<u32>() as f64)
+                    .collect();
+                valid[sample_index(&pairs, rng)] as usize
+            }
+            None => valid[rng.gen_range(0..valid.len())] as usize,
+        };
+        mccfr_traverse(tree, traverser, h, opp_idx, 1.0, showdown, store, iteration, rng);
+    }
+}
+
+/// External-sampling traversal of one dealt pair, `hand_idx` for the
+/// traverser against `opp_idx`. Every traverser action is explored and its
+/// regret updated from the sampled values; the opponent plays one action
+/// drawn from its current strategy. `my_reach` is the traverser's own
+/// probability of playing to `node`, which weights its average strategy.
+/// Returns the sampled value.
+#[allow(clippy::too_many_arguments)]
+fn mccfr_traverse<S: RiverStore, R: Rng>(
+    node: &TreeNode,
+    traverser: Player,
+    hand_idx: usize,
+    opp_idx: usize,
+    my_reach: f64,
+    showdown: &ShowdownTable,
+    store: &mut S,
+    iteration: usize,
+    rng: &mut R,
+) -> f64 {
+    match node {
+        TreeNode::Terminal { terminal_type, pot, invested, .. } => {
+            if let Some(outcomes) = &showdown.bucket_outcomes {
+                // Expected payoff over the combo pairs of the dealt buckets
+                let counts = showdown.bucket_outcome(outcomes, traverser, hand_idx, opp_idx);
+                let payoffs = outcome_payoffs(*terminal_type, *pot, invested[traverser.index()], traverser);
+                let total: u32 = counts.iter().sum();
+                return counts.iter().zip(&payoffs).map(|(&n, p)| n as f64 * p).sum::<f64>() / total as f64;
+            }
+            let (my_score, opp_score) = match traverser {
+                Player::OOP => (showdown.oop_scores[hand_idx], showdown.ip_scores[opp_idx]),
+                Player::IP => (showdown.ip_scores[hand_idx], showdown.oop_scores[opp_idx]),
+            };
+            pair_payoff(*terminal_type, *pot, invested, traverser, my_score, opp_score)
+        }
+        TreeNode::Action { node_id, player, children, actions, .. } => {
+            let num_actions = actions.len();
+            if *player == traverser {
+                let strategy = store.strategy(*node_id, hand_idx, num_actions);
+                let action_values: Vec<f64> = children
+                    .iter()
+                    .zip(&strategy)
+                    .map(|(child, &p)| {
+                        let reach = my_reach * p;
+                        mccfr_traverse(child, traverser, hand_idx, opp_idx, reach, showdown, store, iteration, rng)
+                    })
+                    .collect();
+                let node_value: f64 = strategy.iter().zip(&action_values).map(|(s, v)| s * v).sum();
+                store.update(*node_id, hand_idx, &action_values, node_value, my_reach, iteration / 2 + 1);
+                node_value
+            } else {
+                let a = sample_index(&store.strategy(*node_id, opp_idx, num_actions), rng);
+                mccfr_traverse(&children[a], traverser, hand_idx, opp_idx, my_reach, showdown, store, iteration, rng)
+            }
+        }
+        TreeNode::Chance { .. } => unreachable!("River solver does not use chance nodes"),
+    }
+}
+
+/// The traverser's payoff at a terminal against one opponent hand, from hand
+/// scores (higher wins).
+pub(crate) fn pair_payoff(
+    terminal_type: TerminalType,
+    pot: f64,
+    invested: &[f64; 2],
+    traverser: Player,
+    my_score: u32,
+    opp_score: u32,
+) -> f64 {
+    let my_invested = invested[traverser.index()];
+    match terminal_type {
+        TerminalType::Fold { folder } if folder == traverser => -my_invested,
+        TerminalType::Fold { .. } => pot - my_invested,
+        TerminalType::Showdown => match my_score.cmp(&opp_score) {
+            std::cmp::Ordering::Greater => pot - my_invested,
+            std::cmp::Ordering::Less => -my_invested,
+            std::cmp::Ordering::Equal => pot / 2.0 - my_invested,
+        },
+    }
+}
+
+/// The traverser's payoffs at a terminal when its showdown would be won,
+/// tied and lost.
+fn outcome_payoffs(terminal_type: TerminalType, pot: f64, my_invested: f64, traverser: Player) -> [f64; 3] {
+    match terminal_type {
+        TerminalType::Fold { folder } if folder == traverser => [-my_invested; 3],
+        TerminalType::Fold { .. } => [pot - my_invested; 3],
+        TerminalType::Showdown => [pot - my_invested, pot / 2.0 - my_invested, -my_invested],
+    }
+}
+
 /// Compute the terminal payoff for the traverser at a terminal node.
 fn compute_terminal_value(
     terminal_type: TerminalType,
@@ -458,6 +1346,26 @@ fn compute_terminal_value(
     // Tie: pot/2 - invested[traverser]
     let my_invested = invested[traverser.index()];
 
+    // A bucketed table weighs each opponent bucket by the combo pairs dealt
+    // against it, split by how their showdowns go.
+    if let Some(outcomes) = &showdown.bucket_outcomes {
+        let payoffs = outcome_payoffs(terminal_type, pot, my_invested, traverser);
+        let valid = match traverser {
+            Player::OOP => &showdown.valid_ip_for_oop[hand_idx],
+            Player::IP => &showdown.valid_oop_for_ip[hand_idx],
+        };
+        return valid
+            .iter()
+            .map(|&c| c as usize)
+            .filter(|&c| opp_reach[c] >= 1e-10)
+            .map(|c| {
+                let counts = showdown.bucket_outcome(outcomes, traverser, hand_idx, c);
+                let value: f64 = counts.iter().zip(&payoffs).map(|(&n, p)| n as f64 * p).sum();
+                opp_reach[c] * value
+            })
+            .sum();
+    }
+
     match terminal_type {
         TerminalType::Fold { folder } => {
             if folder == traverser {
@@ -523,22 +1431,98 @@ fn compute_terminal_value(
 // ---------------------------------------------------------------------------
 
 /// Compute exploitability via best-response traversal.
-pub fn compute_exploitability(
+pub fn compute_exploitability<F: CfrFloat>(
     tree: &TreeNode,
-    trainer: &CfrTrainer,
+    trainer: &CfrTrainer<F>,
     showdown: &ShowdownTable,
 ) -> f64 {
-    let oop_gain = best_response_value(tree, Player::OOP, trainer, showdown);
-    let ip_gain = best_response_value(tree, Player::IP, trainer, showdown);
+    strategy_exploitability(tree, trainer, showdown)
+}
+
+/// Where the best-response traversals read each player's average strategy.
+trait AverageStrategy {
+    fn average(&self, node_id: u32, hand_idx: usize, num_actions: usize) -> Vec<f64>;
+}
+
+impl<F: CfrFloat> AverageStrategy for CfrTrainer<F> {
+    fn average(&self, node_id: u32, hand_idx: usize, num_actions: usize) -> Vec<f64> {
+        let key = InfoSetKey {
+            hand_bucket: hand_idx as u16,
+            node_id,
+            position: 0,
+        };
+        self.get_average_strategy(&key, num_actions)
+    }
+}
+
+impl<F: CfrFloat> AverageStrategy for RiverCfr<F> {
+    fn average(&self, node_id: u32, hand_idx: usize, _num_actions: usize) -> Vec<f64> {
+        self.cfr[self.actor(node_id)].average_strategy_f64(node_id as usize, hand_idx)
+    }
+}
+
+/// A solution's stored strategies, by node id. Nodes or combos missing from
+/// the solution play uniformly.
+struct StoredStrategies<'a>(HashMap<u32, &'a NodeStrategy>);
+
+impl<'a> StoredStrategies<'a> {
+    fn new(strategies: &'a [NodeStrategy]) -> Self {
+        StoredStrategies(strategies.iter().map(|s| (s.node_id, s)).collect())
+    }
+}
+
+impl AverageStrategy for StoredStrategies<'_> {
+    fn average(&self, node_id: u32, hand_idx: usize, num_actions: usize) -> Vec<f64> {
+        self.0
+            .get(&node_id)
+            .and_then(|node| node.frequencies.get(hand_idx))
+            .filter(|freqs| freqs.len() == num_actions)
+            .cloned()
+            .unwrap_or_else(|| vec![1.0 / num_actions as f64; num_actions])
+    }
+}
+
+/// Fixed per-combo strategies keyed `(combo index, node id)`, the
+/// `InfoSetKey` order. Anything missing plays uniformly.
+impl AverageStrategy for HashMap<(u16, u32), Vec<f64>> {
+    fn average(&self, node_id: u32, hand_idx: usize, num_actions: usize) -> Vec<f64> {
+        self.get(&(hand_idx as u16, node_id))
+            .filter(|freqs| freqs.len() == num_actions)
+            .cloned()
+            .unwrap_or_else(|| vec![1.0 / num_actions as f64; num_actions])
+    }
+}
+
+/// Exploitability of a fixed strategy, without running CFR — for grading a
+/// chart or a hand-built line. `strategies` maps `(combo index, node id)` to
+/// the action frequencies that combo plays at that node. Combo indices are
+/// into the acting player's `expand_range_to_combos` list (the order of
+/// `RiverSolution::oop_combos` / `ip_combos`) and node ids are those of
+/// `build_tree(&config.tree_config())`. Combos or nodes left out play
+/// uniformly. A spot where either range is empty has nothing to exploit.
+pub fn exploitability_of(config: &RiverSolverConfig, strategies: &HashMap<(u16, u32), Vec<f64>>) -> f64 {
+    let (tree, _num_nodes) = build_tree(&config.tree_config());
+    let oop_combos = expand_range_to_combos(&config.oop_range, &config.board);
+    let ip_combos = expand_range_to_combos(&config.ip_range, &config.board);
+    if oop_combos.is_empty() || ip_combos.is_empty() {
+        return 0.0;
+    }
+    let showdown = ShowdownTable::new(oop_combos, ip_combos, &config.board);
+    strategy_exploitability(&tree, strategies, &showdown)
+}
+
+fn strategy_exploitability<S: AverageStrategy>(tree: &TreeNode, strategy: &S, showdown: &ShowdownTable) -> f64 {
+    let oop_gain = best_response_value(tree, Player::OOP, strategy, showdown);
+    let ip_gain = best_response_value(tree, Player::IP, strategy, showdown);
     (oop_gain + ip_gain) / 2.0
 }
 
 /// Compute the expected gain from best-response play for one player,
 /// given the opponent's average strategy.
-fn best_response_value(
+fn best_response_value<S: AverageStrategy>(
     tree: &TreeNode,
     br_player: Player,
-    trainer: &CfrTrainer,
+    strategy: &S,
     showdown: &ShowdownTable,
 ) -> f64 {
     let num_br = match br_player {
@@ -573,10 +1557,10 @@ fn best_response_value(
             }
         };
 
-        let br_value = br_traverse(tree, br_player, h, &opp_reach, showdown, trainer);
+        let br_value = br_traverse(tree, br_player, h, &opp_reach, showdown, strategy);
 
         // Also compute the value using the actual average strategy
-        let avg_value = avg_strategy_traverse(tree, br_player, h, &opp_reach, showdown, trainer);
+        let avg_value = avg_strategy_traverse(tree, br_player, h, &opp_reach, showdown, strategy);
 
         total_gain += br_value - avg_value;
     }
@@ -585,13 +1569,13 @@ fn best_response_value(
 }
 
 /// Best-response traversal: for the BR player, pick the best action at each node.
-fn br_traverse(
+fn br_traverse<S: AverageStrategy>(
     node: &TreeNode,
     br_player: Player,
     hand_idx: usize,
     opp_reach: &[f64],
     showdown: &ShowdownTable,
-    trainer: &CfrTrainer,
+    strategy: &S,
 ) -> f64 {
     match node {
         TreeNode::Terminal { terminal_type, pot, invested, .. } => {
@@ -607,7 +1591,7 @@ fn br_traverse(
                 let mut best = f64::NEG_INFINITY;
                 for a in 0..num_actions {
                     let v = br_traverse(
-                        &children[a], br_player, hand_idx, opp_reach, showdown, trainer,
+                        &children[a], br_player, hand_idx, opp_reach, showdown, strategy,
                     );
                     if v > best {
                         best = v;
@@ -623,16 +1607,12 @@ fn br_traverse(
                     let mut new_opp_reach = vec![0.0f64; num_opp];
                     for j in 0..num_opp {
                         if opp_reach[j] > 0.0 {
-                            let key = InfoSetKey {
-                                hand_bucket: j as u16,
-                                node_id: *node_id,
-                            };
-                            let avg = trainer.get_average_strategy(&key, num_actions);
+                            let avg = strategy.average(*node_id, j, num_actions);
                             new_opp_reach[j] = opp_reach[j] * avg[a];
                         }
                     }
                     node_value += br_traverse(
-                        &children[a], br_player, hand_idx, &new_opp_reach, showdown, trainer,
+                        &children[a], br_player, hand_idx, &new_opp_reach, showdown, strategy,
                     );
                 }
                 node_value
@@ -643,13 +1623,13 @@ fn br_traverse(
 }
 
 /// Traverse with both players using average strategies.
-fn avg_strategy_traverse(
+fn avg_strategy_traverse<S: AverageStrategy>(
     node: &TreeNode,
     perspective: Player,
     hand_idx: usize,
     opp_reach: &[f64],
     showdown: &ShowdownTable,
-    trainer: &CfrTrainer,
+    strategy: &S,
 ) -> f64 {
     match node {
         TreeNode::Terminal { terminal_type, pot, invested, .. } => {
@@ -662,16 +1642,12 @@ fn avg_strategy_traverse(
 
             if *player == perspective {
                 // Use average strategy
-                let key = InfoSetKey {
-                    hand_bucket: hand_idx as u16,
-                    node_id: *node_id,
-                };
-                let avg = trainer.get_average_strategy(&key, num_actions);
+                let avg = strategy.average(*node_id, hand_idx, num_actions);
 
                 let mut node_value = 0.0;
                 for a in 0..num_actions {
                     let v = avg_strategy_traverse(
-                        &children[a], perspective, hand_idx, opp_reach, showdown, trainer,
+                        &children[a], perspective, hand_idx, opp_reach, showdown, strategy,
                     );
                     node_value += avg[a] * v;
                 }
@@ -685,16 +1661,12 @@ fn avg_strategy_traverse(
                     let mut new_opp_reach = vec![0.0f64; num_opp];
                     for j in 0..num_opp {
                         if opp_reach[j] > 0.0 {
-                            let key = InfoSetKey {
-                                hand_bucket: j as u16,
-                                node_id: *node_id,
-                            };
-                            let avg = trainer.get_average_strategy(&key, num_actions);
+                            let avg = strategy.average(*node_id, j, num_actions);
                             new_opp_reach[j] = opp_reach[j] * avg[a];
                         }
                     }
                     node_value += avg_strategy_traverse(
-                        &children[a], perspective, hand_idx, &new_opp_reach, showdown, trainer,
+                        &children[a], perspective, hand_idx, &new_opp_reach, showdown, strategy,
                     );
                 }
                 node_value
@@ -708,16 +1680,20 @@ fn avg_strategy_traverse(
 // Strategy extraction
 // ---------------------------------------------------------------------------
 
-fn extract_solution(
+fn extract_solution<S: RiverStore>(
     config: &RiverSolverConfig,
     tree: &TreeNode,
-    trainer: &CfrTrainer,
+    store: &S,
+    abstraction: Option<&BucketAbstraction>,
     showdown: &ShowdownTable,
+    convergence: Vec<(usize, f64)>,
 ) -> RiverSolution {
-    let exploitability = compute_exploitability(tree, trainer, showdown);
+    let view = ComboView::new(store, abstraction, tree);
+    let exploitability = strategy_exploitability(tree, &view, showdown);
 
     let mut strategies = Vec::new();
-    extract_node_strategies(tree, trainer, showdown, &mut strategies);
+    let root_reach = [vec![1.0; showdown.num_oop()], vec![1.0; showdown.num_ip()]];
+    extract_node_strategies(tree, &view, showdown, &root_reach, [0.0; 2], &mut strategies);
 
     let board_str = config
         .board
@@ -761,13 +1737,36 @@ fn extract_solution(
         strategies,
         oop_pos: String::new(),
         ip_pos: String::new(),
+        config_hash: config.cache_hash(),
+        bet_sizes: config.bet_sizes.clone(),
+        raise_sizes: config.raise_sizes.clone(),
+        allin_threshold: config.allin_threshold,
+        force_allin_above_stack_pct: config.force_allin_above_stack_pct,
+        can_raise: config.can_raise,
+        dead_money: config.dead_money,
+        stacks: config.stacks,
+        range_advantage: Some(range_advantage(showdown, NUT_FRACTION)),
+        cfr_variant: config.cfr_variant,
+        algorithm: config.algorithm,
+        precision: config.precision,
+        trainer_bytes: store.memory_bytes(),
+        showdown_bytes: showdown.memory_bytes() + abstraction.map_or(0, |a| a.showdown.memory_bytes()),
+        skipped_subtrees: 0,
+        tree: Some(tree.clone()),
+        simplification: None,
+        convergence,
+        abstraction: config.abstraction,
     }
 }
 
-fn extract_node_strategies(
+/// `reach` is each player's own probability, per combo, of playing to `node`
+/// under the average strategy.
+fn extract_node_strategies<S: AverageStrategy>(
     node: &TreeNode,
-    trainer: &CfrTrainer,
+    store: &S,
     showdown: &ShowdownTable,
+    reach: &[Vec<f64>; 2],
+    street_in: [f64; 2],
     strategies: &mut Vec<NodeStrategy>,
 ) {
     match node {
@@ -784,17 +1783,21 @@ fn extract_node_strategies(
                 Player::IP => showdown.num_ip(),
             };
 
-            let frequencies: Vec<Vec<f64>> = (0..num_combos)
-                .map(|h| {
-                    let key = InfoSetKey {
-                        hand_bucket: h as u16,
-                        node_id: *node_id,
-                    };
-                    trainer.get_average_strategy(&key, num_actions)
-                })
-                .collect();
+            let frequencies: Vec<Vec<f64>> =
+                (0..num_combos).map(|h| store.average(*node_id, h, num_actions)).collect();
 
             let action_labels: Vec<String> = actions.iter().map(|a| a.label()).collect();
+            let pi = player.index();
+
+            let child_reaches: Vec<[Vec<f64>; 2]> = (0..num_actions)
+                .map(|a| {
+                    let mut child_reach = reach.clone();
+                    for (r, freqs) in child_reach[pi].iter_mut().zip(&frequencies) {
+                        *r *= freqs[a];
+                    }
+                    child_reach
+                })
+                .collect();
 
             strategies.push(NodeStrategy {
                 node_id: *node_id,
@@ -803,11 +1806,15 @@ fn extract_node_strategies(
                     Player::IP => "IP".to_string(),
                 },
                 actions: action_labels,
+                labels: node.pot_labels(street_in),
                 frequencies,
+                reach: reach[pi].clone(),
             });
 
-            for child in children {
-                extract_node_strategies(child, trainer, showdown, strategies);
+            for ((child, child_reach), action) in children.iter().zip(&child_reaches).zip(actions) {
+                let mut child_in = street_in;
+                child_in[pi] += action.chips();
+                extract_node_strategies(child, store, showdown, child_reach, child_in, strategies);
             }
         }
         TreeNode::Terminal { .. } | TreeNode::Chance { .. } => {}
@@ -837,6 +1844,227 @@ fn empty_solution(config: &RiverSolverConfig) -> RiverSolution {
         strategies: vec![],
         oop_pos: String::new(),
         ip_pos: String::new(),
+        config_hash: config.cache_hash(),
+        bet_sizes: config.bet_sizes.clone(),
+        raise_sizes: config.raise_sizes.clone(),
+        allin_threshold: config.allin_threshold,
+        force_allin_above_stack_pct: config.force_allin_above_stack_pct,
+        can_raise: config.can_raise,
+        dead_money: config.dead_money,
+        stacks: config.stacks,
+        range_advantage: None,
+        cfr_variant: config.cfr_variant,
+        algorithm: config.algorithm,
+        precision: config.precision,
+        trainer_bytes: 0,
+        showdown_bytes: 0,
+        skipped_subtrees: 0,
+        tree: None,
+        simplification: None,
+        convergence: Vec::new(),
+        abstraction: config.abstraction,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Simplification
+// ---------------------------------------------------------------------------
+
+/// Purify every node of `solution` at `threshold` (see
+/// [`NodeStrategy::purify`]) and re-run the best response against the
+/// simplified strategies to measure what the simpler play gives up. The
+/// solved strategies stay in `solution.simplification`, and simplifying
+/// again starts over from them. Needs the solution's saved tree.
+pub fn simplify_solution(solution: &mut RiverSolution, threshold: f64) -> Result<(), String> {
+    if !(0.0..1.0).contains(&threshold) {
+        return Err(format!("Simplify threshold must be in [0, 1), got {}", threshold));
+    }
+    let original = match &solution.simplification {
+        Some(simplification) => simplification.original_strategies.clone(),
+        None => solution.strategies.clone(),
+    };
+    let mut simplified = original.clone();
+    for node in &mut simplified {
+        node.purify(threshold);
+    }
+
+    let exploitability = if simplified.is_empty() {
+        solution.exploitability
+    } else {
+        let tree = solution
+            .tree
+            .as_ref()
+            .ok_or("Solution has no saved tree to evaluate; re-solve it to simplify")?;
+        let showdown = ShowdownTable::new(
+            parse_combos(&solution.oop_combos)?,
+            parse_combos(&solution.ip_combos)?,
+            &card_indices(&solution.board)?,
+        );
+        strategy_exploitability(tree, &StoredStrategies::new(&simplified), &showdown)
+    };
+
+    solution.strategies = simplified;
+    solution.simplification = Some(Simplification {
+        threshold,
+        exploitability,
+        original_strategies: original,
+    });
+    Ok(())
+}
+
+/// Card indices of a string of cards such as a board or "AhKs".
+fn card_indices(cards: &str) -> Result<Vec<u8>, String> {
+    let cards = parse_board(cards).map_err(|e| e.to_string())?;
+    Ok(cards.iter().map(card_to_index).collect())
+}
+
+fn parse_combos(combos: &[String]) -> Result<Vec<Combo>, String> {
+    combos
+        .iter()
+        .map(|combo| match card_indices(combo)?[..] {
+            [c1, c2] => Ok(Combo(c1, c2)),
+            _ => Err(format!("Invalid combo '{}'", combo)),
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Diffing
+// ---------------------------------------------------------------------------
+
+/// One difference between two river solutions, from [`RiverSolution::diff`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StrategyDiff {
+    /// A node only the new solution has.
+    NodeAdded { node_id: u32, player: String },
+    /// A node only the old solution has.
+    NodeRemoved { node_id: u32, player: String },
+    /// An action only the new solution offers at a shared node.
+    ActionAdded { node_id: u32, action: String },
+    /// An action only the old solution offers at a shared node.
+    ActionRemoved { node_id: u32, action: String },
+    /// A combo's frequency for a shared action moved by more than the
+    /// threshold.
+    Changed {
+        node_id: u32,
+        combo: String,
+        action: String,
+        old: f64,
+        new: f64,
+    },
+}
+
+impl std::fmt::Display for StrategyDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            StrategyDiff::NodeAdded { node_id, player } => write!(f, "+ node {} ({})", node_id, player),
+            StrategyDiff::NodeRemoved { node_id, player } => write!(f, "- node {} ({})", node_id, player),
+            StrategyDiff::ActionAdded { node_id, action } => write!(f, "+ node {}: {}", node_id, action),
+            StrategyDiff::ActionRemoved { node_id, action } => write!(f, "- node {}: {}", node_id, action),
+            StrategyDiff::Changed { node_id, combo, action, old, new } => write!(
+                f,
+                "~ node {}: {} {} {:.0}% -> {:.0}%",
+                node_id,
+                combo,
+                action,
+                old * 100.0,
+                new * 100.0
+            ),
+        }
+    }
+}
+
+impl RiverSolution {
+    /// Frequency change below which [`diff`](Self::diff) ignores a combo.
+    pub const DIFF_THRESHOLD: f64 = 0.05;
+
+    /// What changed from `self` to `other`: nodes and actions only one
+    /// side has, and combos whose frequency on a shared action moved by
+    /// more than [`DIFF_THRESHOLD`](Self::DIFF_THRESHOLD).
+    pub fn diff(&self, other: &RiverSolution) -> Vec<StrategyDiff> {
+        self.diff_above(other, Self::DIFF_THRESHOLD)
+    }
+
+    /// [`diff`](Self::diff) with a custom frequency threshold. Nodes match
+    /// by node id and acting player, actions by label and combos by name,
+    /// so a combo only one solution's range holds is skipped.
+    pub fn diff_above(&self, other: &RiverSolution, threshold: f64) -> Vec<StrategyDiff> {
+        let key = |node: &NodeStrategy| (node.node_id, node.player.clone());
+        let theirs: HashMap<_, &NodeStrategy> = other.strategies.iter().map(|n| (key(n), n)).collect();
+        let ours: HashMap<_, &NodeStrategy> = self.strategies.iter().map(|n| (key(n), n)).collect();
+
+        let mut diffs = Vec::new();
+        for old in &self.strategies {
+            let Some(new) = theirs.get(&key(old)) else {
+                diffs.push(StrategyDiff::NodeRemoved { node_id: old.node_id, player: old.player.clone() });
+                continue;
+            };
+            diffs.extend(self.diff_node(old, other, new, threshold));
+        }
+        for new in &other.strategies {
+            if !ours.contains_key(&key(new)) {
+                diffs.push(StrategyDiff::NodeAdded { node_id: new.node_id, player: new.player.clone() });
+            }
+        }
+        diffs
+    }
+
+    /// Differences at one node both solutions have.
+    fn diff_node(
+        &self,
+        old: &NodeStrategy,
+        other: &RiverSolution,
+        new: &NodeStrategy,
+        threshold: f64,
+    ) -> Vec<StrategyDiff> {
+        let node_id = old.node_id;
+        let mut diffs = Vec::new();
+        for action in &old.actions {
+            if !new.actions.contains(action) {
+                diffs.push(StrategyDiff::ActionRemoved { node_id, action: action.clone() });
+            }
+        }
+        for action in &new.actions {
+            if !old.actions.contains(action) {
+                diffs.push(StrategyDiff::ActionAdded { node_id, action: action.clone() });
+            }
+        }
+
+        let shared: Vec<(usize, usize)> = old
+            .actions
+            .iter()
+            .enumerate()
+            .filter_map(|(a, action)| Some((a, new.actions.iter().position(|b| b == action)?)))
+            .collect();
+        let (old_combos, new_combos) = if old.player == "OOP" {
+            (&self.oop_combos, &other.oop_combos)
+        } else {
+            (&self.ip_combos, &other.ip_combos)
+        };
+        let new_index: HashMap<&str, usize> =
+            new_combos.iter().enumerate().map(|(i, c)| (c.as_str(), i)).collect();
+
+        for (i, combo) in old_combos.iter().enumerate() {
+            let Some(&j) = new_index.get(combo.as_str()) else {
+                continue;
+            };
+            let (Some(old_freqs), Some(new_freqs)) = (old.frequencies.get(i), new.frequencies.get(j)) else {
+                continue;
+            };
+            for &(a, b) in &shared {
+                let (before, after) = (old_freqs[a], new_freqs[b]);
+                if (after - before).abs() > threshold {
+                    diffs.push(StrategyDiff::Changed {
+                        node_id,
+                        combo: combo.clone(),
+                        action: old.actions[a].clone(),
+                        old: before,
+                        new: after,
+                    });
+                }
+            }
+        }
+        diffs
     }
 }
 
@@ -845,39 +2073,219 @@ fn empty_solution(config: &RiverSolverConfig) -> RiverSolution {
 // ---------------------------------------------------------------------------
 
 impl RiverSolution {
+    /// The stored strategies in the form [`exploitability_of`] takes.
+    pub fn strategy_map(&self) -> HashMap<(u16, u32), Vec<f64>> {
+        self.strategies
+            .iter()
+            .flat_map(|node| {
+                node.frequencies
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, freqs)| ((i as u16, node.node_id), freqs.clone()))
+            })
+            .collect()
+    }
+
+    /// Range-wide frequency of each action at `node_id`: every combo's
+    /// frequencies averaged, weighted by how often the combo reaches the
+    /// node. Combos with zero reach are left out. `None` if the node does not
+    /// exist or no combo reaches it.
+    pub fn aggregate_node(&self, node_id: u32) -> Option<Vec<(String, f64)>> {
+        self.aggregate_node_weighted(node_id, None)
+    }
+
+    /// [`aggregate_node`](Self::aggregate_node) with an extra per-combo
+    /// weight (e.g., from a weighted range), indexed like the acting
+    /// player's combos.
+    pub fn aggregate_node_weighted(
+        &self,
+        node_id: u32,
+        combo_weights: Option<&[f64]>,
+    ) -> Option<Vec<(String, f64)>> {
+        let node = self.strategies.iter().find(|s| s.node_id == node_id)?;
+        let mut totals = vec![0.0; node.actions.len()];
+        let mut total_weight = 0.0;
+        for (h, freqs) in node.frequencies.iter().enumerate() {
+            let reach = node.reach.get(h).copied().unwrap_or(1.0);
+            let weight = reach * combo_weights.map_or(1.0, |w| w.get(h).copied().unwrap_or(0.0));
+            if weight <= 0.0 {
+                continue;
+            }
+            for (total, &f) in totals.iter_mut().zip(freqs) {
+                *total += weight * f;
+            }
+            total_weight += weight;
+        }
+        if total_weight <= 0.0 {
+            return None;
+        }
+        Some(
+            node.actions
+                .iter()
+                .cloned()
+                .zip(totals.into_iter().map(|t| t / total_weight))
+                .collect(),
+        )
+    }
+
+    /// Apply [`NodeStrategy::purify`] to every node.
+    pub fn purify(&mut self, threshold: f64) {
+        for node in &mut self.strategies {
+            node.purify(threshold);
+        }
+    }
+
+    /// The strategies as solved, whether or not `strategies` has since been
+    /// simplified.
+    pub fn original_strategies(&self) -> &[NodeStrategy] {
+        match &self.simplification {
+            Some(simplification) => &simplification.original_strategies,
+            None => &self.strategies,
+        }
+    }
+
+    /// Exploitability the simplification added, as a percentage of the pot.
+    /// Negative when rounding only removed noise an unconverged solve left.
+    pub fn simplification_cost_pct(&self) -> Option<f64> {
+        let simplification = self.simplification.as_ref()?;
+        let pot = self.starting_pot + self.dead_money;
+        Some((simplification.exploitability - self.exploitability) / pot * 100.0)
+    }
+
+    /// Each player's expected value, `(oop, ip)`, when both play the stored
+    /// strategies from the root and every pair of combos that shares no card
+    /// is dealt equally often. Values are chips won back from the river pot
+    /// (what each player put in before the river is sunk), so they sum to
+    /// the starting pot: OOP's edge over half the pot is IP's shortfall.
+    /// Errors if the solution has no stored tree or no live combo pair.
+    pub fn game_value(&self) -> Result<(f64, f64), String> {
+        let tree = self
+            .tree
+            .as_ref()
+            .ok_or("Solution has no saved tree to evaluate; re-solve it for the game value")?;
+        let showdown = ShowdownTable::new(
+            parse_combos(&self.oop_combos)?,
+            parse_combos(&self.ip_combos)?,
+            &card_indices(&self.board)?,
+        );
+        let pairs: usize = showdown.valid_ip_for_oop.iter().map(Vec::len).sum();
+        if pairs == 0 {
+            return Err("No pair of OOP and IP combos can be dealt together".to_string());
+        }
+
+        let strategy = StoredStrategies::new(&self.strategies);
+        let value = |player: Player, valid: &[Vec<u16>], num_opp: usize| {
+            valid
+                .iter()
+                .enumerate()
+                .map(|(h, valid)| {
+                    let mut opp_reach = vec![0.0f64; num_opp];
+                    for &j in valid {
+                        opp_reach[j as usize] = 1.0;
+                    }
+                    avg_strategy_traverse(tree, player, h, &opp_reach, &showdown, &strategy)
+                })
+                .sum::<f64>()
+                / pairs as f64
+        };
+        Ok((
+            value(Player::OOP, &showdown.valid_ip_for_oop, showdown.num_ip()),
+            value(Player::IP, &showdown.valid_oop_for_ip, showdown.num_oop()),
+        ))
+    }
+
     pub fn display(&self) {
+        print!("{}", self.render());
+    }
+
+    /// The human-readable report `display` prints.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = self.write_report(&mut out);
+        out
+    }
+
+    fn write_report(&self, out: &mut impl std::fmt::Write) -> std::fmt::Result {
         use colored::Colorize;
 
-        println!();
-        println!(
-            "  {} River Solution  |  Board: {}  |  Pot: {:.0}  |  Stack: {:.0}  |  {} iterations",
+        writeln!(out)?;
+        let stack = match self.stacks {
+            Some([oop, ip]) => format!("Stacks: {:.0}/{:.0}", oop, ip),
+            None => format!("Stack: {:.0}", self.effective_stack),
+        };
+        writeln!(
+            out,
+            "  {} River Solution  |  Board: {}  |  Pot: {:.0}  |  {}  |  {} iterations",
             "GTO".bold(),
             self.board,
             self.starting_pot,
-            self.effective_stack,
+            stack,
             self.iterations,
-        );
-        println!(
-            "  Exploitability: {:.4}",
-            self.exploitability,
-        );
-        println!(
+        )?;
+        write!(out, "  Exploitability: {:.4}", self.exploitability)?;
+        if self.algorithm != SolverAlgorithm::Cfr {
+            write!(out, "  |  {}", self.algorithm)?;
+        }
+        if self.cfr_variant != CfrVariant::CfrPlus {
+            write!(out, "  |  {}", self.cfr_variant)?;
+        }
+        if self.abstraction != Abstraction::None {
+            write!(out, "  |  {}", self.abstraction)?;
+        }
+        writeln!(out)?;
+        if let (Some(simplification), Some(cost)) = (&self.simplification, self.simplification_cost_pct()) {
+            writeln!(
+                out,
+                "  Simplified at {:.0}%  |  simplification cost: {:.2}% pot",
+                simplification.threshold * 100.0,
+                cost
+            )?;
+        }
+        if let Some(line) = crate::display::convergence_summary(&self.convergence) {
+            writeln!(out, "  {}", line)?;
+        }
+        writeln!(
+            out,
             "  OOP range: {} ({} combos)  |  IP range: {} ({} combos)",
             self.oop_range.join(","),
             self.oop_combos.len(),
             self.ip_range.join(","),
             self.ip_combos.len(),
-        );
+        )?;
+        if let Some(advantage) = &self.range_advantage {
+            writeln!(out, "  {}", advantage.summary())?;
+        }
+        if let Ok((oop, ip)) = self.game_value() {
+            let pot = self.starting_pot + self.dead_money;
+            writeln!(
+                out,
+                "  Game value: OOP {:.2} ({:.0}% pot)  |  IP {:.2} ({:.0}% pot)",
+                oop,
+                oop / pot * 100.0,
+                ip,
+                ip / pot * 100.0
+            )?;
+        }
 
         // Display root node strategy (OOP's first decision)
         if let Some(root_strat) = self.strategies.first() {
-            println!();
-            println!(
+            writeln!(out)?;
+            writeln!(
+                out,
                 "  {} at root (node {}):",
                 root_strat.player.bold(),
                 root_strat.node_id
-            );
-            println!("  Actions: {}", root_strat.actions.join(" | "));
+            )?;
+            writeln!(out, "  Actions: {}", root_strat.display_labels().join(" | "))?;
+            if let Some(overall) = self.aggregate_node(root_strat.node_id) {
+                let overall = overall
+                    .iter()
+                    .zip(root_strat.display_labels())
+                    .map(|((_, f), a)| format!("{} {:.0}%", a, f * 100.0))
+                    .collect::<Vec<_>>()
+                    .join("  ");
+                writeln!(out, "  Overall: {}", overall)?;
+            }
 
             let num_to_show = root_strat.frequencies.len().min(20);
             let combos = if root_strat.player == "OOP" {
@@ -889,7 +2297,7 @@ impl RiverSolution {
             for i in 0..num_to_show {
                 let freq_str: String = root_strat.frequencies[i]
                     .iter()
-                    .zip(&root_strat.actions)
+                    .zip(root_strat.display_labels())
                     .map(|(f, a)| {
                         let pct = (f * 100.0).round() as u32;
                         if pct > 70 {
@@ -902,14 +2310,14 @@ impl RiverSolution {
                     })
                     .collect::<Vec<_>>()
                     .join("  ");
-                println!("    {}  {}", combos[i].bold(), freq_str);
+                writeln!(out, "    {}  {}", combos[i].bold(), freq_str)?;
             }
             if root_strat.frequencies.len() > num_to_show {
-                println!("    ... and {} more combos", root_strat.frequencies.len() - num_to_show);
+                writeln!(out, "    ... and {} more combos", root_strat.frequencies.len() - num_to_show)?;
             }
         }
 
-        println!();
+        writeln!(out)
     }
 }
 
@@ -919,13 +2327,14 @@ impl RiverSolution {
 
 impl RiverSolution {
     pub fn cache_path(&self) -> std::path::PathBuf {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let dir = std::path::Path::new(&home).join(".gto-cli").join("solver");
-        std::fs::create_dir_all(&dir).ok();
-        dir.join(format!(
-            "river_{}_{}_{}_{:.0}_{:.0}.bin",
-            self.board, self.oop_pos, self.ip_pos, self.starting_pot, self.effective_stack,
-        ))
+        cache_file(
+            &self.board,
+            &self.oop_pos,
+            &self.ip_pos,
+            self.starting_pot,
+            self.effective_stack,
+            self.config_hash,
+        )
     }
 
     pub fn save_cache(&self) {
@@ -935,13 +2344,199 @@ impl RiverSolution {
         }
     }
 
-    pub fn load_cache(board: &str, oop_pos: &str, ip_pos: &str, pot: f64, stack: f64) -> Option<RiverSolution> {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let path = std::path::Path::new(&home)
-            .join(".gto-cli")
-            .join("solver")
-            .join(format!("river_{}_{}_{}_{:.0}_{:.0}.bin", board, oop_pos, ip_pos, pot, stack));
+    /// The cached solution of exactly `config` (ranges, sizing, iterations
+    /// and all) at these positions, if one was saved.
+    pub fn load_cache(config: &RiverSolverConfig, oop_pos: &str, ip_pos: &str) -> Option<RiverSolution> {
+        let board: String = config.board.iter().map(|&c| index_to_card(c).to_string()).collect();
+        let hash = config.cache_hash();
+        let path = cache_file(&board, oop_pos, ip_pos, config.starting_pot, config.effective_stack, hash);
         let data = std::fs::read(path).ok()?;
-        bincode::deserialize(&data).ok()
+        let solution: RiverSolution = bincode::deserialize(&data).ok()?;
+        (solution.config_hash == hash).then_some(solution)
+    }
+    /// Read a solution written by `solve river --format json --out` (a
+    /// `.json` path) or a cached `.bin` solution.
+    pub fn read_file(path: &std::path::Path) -> Result<RiverSolution, String> {
+        let data = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let parsed = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_slice(&data).map_err(|e| e.to_string())
+        } else {
+            bincode::deserialize(&data).map_err(|e| e.to_string())
+        };
+        parsed.map_err(|e| format!("{} is not a river solution: {}", path.display(), e))
+    }
+}
+
+fn cache_file(board: &str, oop_pos: &str, ip_pos: &str, pot: f64, stack: f64, hash: u64) -> PathBuf {
+    checkpoint::solver_dir().join(format!(
+        "river_{}_{}_{}_{:.0}_{:.0}_{:016x}.bin",
+        board, oop_pos, ip_pos, pot, stack, hash
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(node_id: u32, hand: usize) -> InfoSetKey {
+        InfoSetKey { hand_bucket: hand as u16, node_id, position: 0 }
+    }
+
+    /// The map-backed storage river solves ran on before `RiverCfr`.
+    impl<F: CfrFloat> RiverStore for CfrTrainer<F> {
+        fn strategy(&self, node_id: u32, hand: usize, num_actions: usize) -> Vec<f64> {
+            self.get_strategy(&key(node_id, hand), num_actions)
+        }
+
+        fn update(
+            &mut self,
+            node_id: u32,
+            hand: usize,
+            action_values: &[f64],
+            node_value: f64,
+            reach_prob: f64,
+            iteration: usize,
+        ) {
+            CfrTrainer::update(self, &key(node_id, hand), action_values, node_value, reach_prob, iteration);
+        }
+
+        fn mark_positive(&mut self, node_id: u32, hand: usize, iteration: usize) {
+            if let Some(data) = self.info_sets.get_mut(&key(node_id, hand)) {
+                data.mark_positive(iteration);
+            }
+        }
+
+        fn is_prunable(&self, node_id: u32, hand: usize, a: usize, iteration: usize, threshold: usize) -> bool {
+            self.info_sets.get(&key(node_id, hand)).is_some_and(|data| data.is_prunable(a, iteration, threshold))
+        }
+
+        fn memory_bytes(&self) -> usize {
+            CfrTrainer::memory_bytes(self)
+        }
+    }
+
+    fn solve_both<F: CfrFloat>(config: &RiverSolverConfig) -> (RiverSolution, RiverSolution) {
+        let (tree, _) = build_tree(&config.tree_config());
+        let showdown = ShowdownTable::new(
+            expand_range_to_combos(&config.oop_range, &config.board),
+            expand_range_to_combos(&config.ip_range, &config.board),
+            &config.board,
+        );
+        let flat = RiverCfr::<F>::new(&tree, &showdown, config.cfr_variant)
+            .with_pruning(config.prune_threshold.is_some());
+        let map = CfrTrainer::<F>::with_variant(config.cfr_variant);
+        (
+            run_cfr(config, &tree, &showdown, None, flat, solver_rng(config.seed), 0, None),
+            run_cfr(config, &tree, &showdown, None, map, solver_rng(config.seed), 0, None),
+        )
+    }
+
+    #[test]
+    fn flat_solve_matches_hashmap_solve() {
+        let make_config = |variant, algorithm, prune_threshold| {
+            let mut config =
+                RiverSolverConfig::new("Ks9h5c3d2s", "AA,KQ,98s,76s", "KJ,QQ,76s,A5s", 10.0, 20.0, 300).unwrap();
+            config.cfr_variant = variant;
+            config.algorithm = algorithm;
+            config.prune_threshold = prune_threshold;
+            config.seed = Some(11);
+            config
+        };
+        let spots = [
+            make_config(CfrVariant::CfrPlus, SolverAlgorithm::Cfr, None),
+            make_config(CfrVariant::DCFR, SolverAlgorithm::Cfr, Some(20)),
+            make_config(CfrVariant::LinearCfr, SolverAlgorithm::Mccfr, None),
+        ];
+        for config in &spots {
+            let (flat, map) = solve_both::<f64>(config);
+            assert_eq!(flat.exploitability, map.exploitability, "{}", config.cfr_variant);
+            assert_eq!(flat.strategies.len(), map.strategies.len());
+            for (ours, theirs) in flat.strategies.iter().zip(&map.strategies) {
+                assert_eq!(ours.node_id, theirs.node_id);
+                assert_eq!(ours.frequencies, theirs.frequencies, "node {}", ours.node_id);
+            }
+        }
+
+        // f32 storage rounds the same way in both layouts
+        let (flat, map) = solve_both::<f32>(&spots[0]);
+        assert_eq!(flat.exploitability, map.exploitability);
+    }
+
+    /// Plays every action uniformly and records the reach each update gets.
+    #[derive(Default, Serialize)]
+    struct UniformStore {
+        updates: Vec<(u32, f64)>,
+    }
+
+    impl AverageStrategy for UniformStore {
+        fn average(&self, _node_id: u32, _hand: usize, num_actions: usize) -> Vec<f64> {
+            vec![1.0 / num_actions as f64; num_actions]
+        }
+    }
+
+    impl RiverStore for UniformStore {
+        fn strategy(&self, _node_id: u32, _hand: usize, num_actions: usize) -> Vec<f64> {
+            vec![1.0 / num_actions as f64; num_actions]
+        }
+
+        fn update(&mut self, node_id: u32, _hand: usize, _: &[f64], _: f64, reach_prob: f64, _: usize) {
+            self.updates.push((node_id, reach_prob));
+        }
+
+        fn mark_positive(&mut self, _node_id: u32, _hand: usize, _iteration: usize) {}
+
+        fn is_prunable(&self, _: u32, _: usize, _: usize, _: usize, _: usize) -> bool {
+            false
+        }
+
+        fn memory_bytes(&self) -> usize {
+            0
+        }
+    }
+
+    /// OOP's own probability of reaching each of its nodes under a uniform
+    /// strategy.
+    fn uniform_oop_reach(node: &TreeNode, reach: f64, out: &mut HashMap<u32, f64>) {
+        if let TreeNode::Action { node_id, player, children, .. } = node {
+            let own = if *player == Player::OOP {
+                out.insert(*node_id, reach);
+                reach / children.len() as f64
+            } else {
+                reach
+            };
+            for child in children {
+                uniform_oop_reach(child, own, out);
+            }
+        }
+    }
+
+    #[test]
+    fn mccfr_weights_updates_by_the_traversers_reach() {
+        let config = RiverSolverConfig::new("Ks9h5c3d2s", "AA", "QQ", 10.0, 20.0, 1).unwrap();
+        let (tree, _) = build_tree(&config.tree_config());
+        let showdown = ShowdownTable::new(
+            expand_range_to_combos(&config.oop_range, &config.board),
+            expand_range_to_combos(&config.ip_range, &config.board),
+            &config.board,
+        );
+        let mut expected = HashMap::new();
+        uniform_oop_reach(&tree, 1.0, &mut expected);
+
+        let mut store = UniformStore::default();
+        let mut rng = solver_rng(Some(5));
+        for _ in 0..20 {
+            mccfr_iteration(&tree, Player::OOP, &showdown, &mut store, 0, &mut rng);
+        }
+        assert!(store.updates.iter().any(|&(_, reach)| reach < 1.0), "no update below the root");
+        for (node_id, reach) in store.updates {
+            assert!((reach - expected[&node_id]).abs() < 1e-12, "node {}: {} vs {}", node_id, reach, expected[&node_id]);
+        }
+    }
+
+    #[test]
+    fn flat_storage_is_smaller_than_the_map() {
+        let config = RiverSolverConfig::new("Ks9h5c3d2s", "AA,KQ,98s", "KJ,QQ,76s", 10.0, 20.0, 50).unwrap();
+        let (flat, map) = solve_both::<f64>(&config);
+        assert!(flat.trainer_bytes * 2 < map.trainer_bytes, "{} vs {}", flat.trainer_bytes, map.trainer_bytes);
     }
 }
@@ -0,0 +1,85 @@
+//! Size management for `~/.gto-cli/solver`.
+//!
+//! Every solve saves its solution there and checkpoints land beside them,
+//! but nothing ever deletes them. `clean_cache` evicts the least recently
+//! written solutions until the directory fits a byte budget. Checkpoints
+//! (`*.ckpt`) and their in-progress writes are never counted or evicted, so
+//! a long solve can always resume.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::checkpoint::{solver_dir, TMP_EXTENSION};
+
+/// Total size and count of a set of cache files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub total_bytes: u64,
+    pub files: usize,
+}
+
+impl CacheStats {
+    fn add(&mut self, bytes: u64) {
+        self.total_bytes += bytes;
+        self.files += 1;
+    }
+}
+
+/// Size of the cached solutions in the solver directory.
+pub fn cache_stats() -> CacheStats {
+    cache_stats_in(&solver_dir())
+}
+
+/// `cache_stats` for an arbitrary directory.
+pub fn cache_stats_in(dir: &Path) -> CacheStats {
+    let mut stats = CacheStats::default();
+    for (_, bytes, _) in cache_files(dir) {
+        stats.add(bytes);
+    }
+    stats
+}
+
+/// Delete the oldest cached solutions until they total at most
+/// `max_bytes`. Returns what was removed.
+pub fn clean_cache(max_bytes: u64) -> Result<CacheStats, String> {
+    clean_cache_in(&solver_dir(), max_bytes)
+}
+
+/// `clean_cache` for an arbitrary directory.
+pub fn clean_cache_in(dir: &Path, max_bytes: u64) -> Result<CacheStats, String> {
+    let mut files = cache_files(dir);
+    // Oldest first; the path breaks ties so equal mtimes evict in a fixed order
+    files.sort_by(|a, b| a.2.cmp(&b.2).then_with(|| a.0.cmp(&b.0)));
+
+    let mut total: u64 = files.iter().map(|(_, bytes, _)| bytes).sum();
+    let mut removed = CacheStats::default();
+    for (path, bytes, _) in files {
+        if total <= max_bytes {
+            break;
+        }
+        std::fs::remove_file(&path).map_err(|e| format!("Cannot remove {}: {}", path.display(), e))?;
+        total -= bytes;
+        removed.add(bytes);
+    }
+    Ok(removed)
+}
+
+/// Regular files directly under `dir`, checkpoints and their temporary
+/// files excluded, with their size and mtime. A missing directory is an empty cache.
+fn cache_files(dir: &Path) -> Vec<(PathBuf, u64, SystemTime)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let meta = entry.metadata().ok()?;
+            let checkpoint = entry.path().extension().is_some_and(|ext| ext == "ckpt" || ext == TMP_EXTENSION);
+            if !meta.is_file() || checkpoint {
+                return None;
+            }
+            let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            Some((entry.path(), meta.len(), modified))
+        })
+        .collect()
+}
@@ -1,12 +1,14 @@
 //! Strategy lookup engine — queries solver output to answer:
 //! "Given this hand + position + board, what are the GTO action frequencies?"
 
-use crate::bucketing::assign_buckets;
+use crate::bucketing::{bucket_combos, unabstracted_bucket, BucketingMode};
 use crate::card_encoding::card_to_index;
 use crate::cards::parse_board;
 use crate::flop_solver::{FlopSolverConfig, FlopSolution, TemplateBucketStrategy, TreeEdge, solve_flop};
 use crate::preflop_solver::{Position, PreflopSolution, PreflopSpotResult};
-use crate::river_solver::{RiverSolverConfig, RiverSolution, solve_river};
+use crate::river_solver::{
+    purify_frequencies, simplify_solution, solve_river, RiverSolution, RiverSolverConfig,
+};
 use crate::turn_solver::{TurnSolverConfig, TurnSolution, solve_turn};
 
 // ---------------------------------------------------------------------------
@@ -16,12 +18,17 @@ use crate::turn_solver::{TurnSolverConfig, TurnSolution, solve_turn};
 pub struct StrategyEngine {
     preflop: Option<PreflopSolution>,
     pub stack_bb: f64,
+    /// Round postflop mixes below this frequency to pure actions.
+    pub simplify: Option<f64>,
 }
 
 pub struct StrategyResult {
     pub actions: Vec<String>,
     pub frequencies: Vec<f64>,
     pub source: StrategySource,
+    /// Exploitability the simplification added, in percent of the pot,
+    /// when it could be measured (river solutions only).
+    pub simplification_cost: Option<f64>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -137,6 +144,7 @@ impl StrategyEngine {
         StrategyEngine {
             preflop,
             stack_bb,
+            simplify: None,
         }
     }
 
@@ -165,6 +173,7 @@ impl StrategyEngine {
                     actions: vec!["RAISE 2.5bb".to_string(), "FOLD".to_string()],
                     frequencies: vec![open_freq, fold_freq],
                     source: StrategySource::Cached,
+                    simplification_cost: None,
                 })
             }
             Some(villain_pos) => {
@@ -185,6 +194,7 @@ impl StrategyEngine {
                         ],
                         frequencies: vec![threebet, call, fold],
                         source: StrategySource::Cached,
+                        simplification_cost: None,
                     })
                 } else {
                     // Hero opened, villain 3-bet
@@ -200,6 +210,7 @@ impl StrategyEngine {
                         ],
                         frequencies: vec![fourbet, call, fold],
                         source: StrategySource::Cached,
+                        simplification_cost: None,
                     })
                 }
             }
@@ -236,11 +247,26 @@ impl StrategyEngine {
         let oop_str = oop_range.join(",");
         let ip_str = ip_range.join(",");
 
-        match board_len {
+        let mut result = match board_len {
             6 => self.query_flop(hand, hero_side, board, &oop_str, &ip_str, pot, stack, iterations, oop_pos.as_str(), ip_pos.as_str(), action_path),
             8 => self.query_turn(hand, hero_side, board, &oop_str, &ip_str, pot, stack, iterations, oop_pos.as_str(), ip_pos.as_str(), action_path),
             10 => self.query_river(hand, hero_side, board, &oop_str, &ip_str, pot, stack, iterations, oop_pos.as_str(), ip_pos.as_str(), action_path),
             _ => Err(format!("Invalid board length: {} chars (expected 6, 8, or 10)", board_len)),
+        }?;
+        // River solutions were simplified whole; elsewhere only the answer is
+        if let (Some(threshold), None) = (self.simplify, result.simplification_cost) {
+            purify_frequencies(&mut result.frequencies, threshold);
+        }
+        Ok(result)
+    }
+
+    /// Apply `simplify` to a river solution about to be queried. Older
+    /// solutions without a saved tree are purified without measuring the cost.
+    fn simplify_river(&self, solution: &mut RiverSolution) {
+        if let Some(threshold) = self.simplify {
+            if simplify_solution(solution, threshold).is_err() {
+                solution.purify(threshold);
+            }
         }
     }
 
@@ -328,8 +354,10 @@ impl StrategyEngine {
         ip_pos: &str,
         action_path: &[String],
     ) -> Result<StrategyResult, String> {
+        let config = TurnSolverConfig::new(board, oop_range, ip_range, pot, stack, iterations)?;
+
         // 1. Check dedicated turn cache
-        if let Some(solution) = TurnSolution::load_cache(board, oop_pos, ip_pos, pot, stack) {
+        if let Some(solution) = TurnSolution::load_cache(&config, oop_pos, ip_pos) {
             return lookup_in_turn_solution(&solution, hand, hero_side, action_path);
         }
 
@@ -348,7 +376,6 @@ impl StrategyEngine {
 
         // 3. Solve on-demand
         eprintln!("  Solving turn {} (this may take 15-45s)...", board);
-        let config = TurnSolverConfig::new(board, oop_range, ip_range, pot, stack, iterations)?;
         let mut solution = solve_turn(&config);
         solution.oop_pos = oop_pos.to_string();
         solution.ip_pos = ip_pos.to_string();
@@ -371,8 +398,11 @@ impl StrategyEngine {
         ip_pos: &str,
         action_path: &[String],
     ) -> Result<StrategyResult, String> {
+        let config = RiverSolverConfig::new(board, oop_range, ip_range, pot, stack, iterations)?;
+
         // 1. Check dedicated river cache
-        if let Some(solution) = RiverSolution::load_cache(board, oop_pos, ip_pos, pot, stack) {
+        if let Some(mut solution) = RiverSolution::load_cache(&config, oop_pos, ip_pos) {
+            self.simplify_river(&mut solution);
             return lookup_in_river_solution(&solution, hand, hero_side, action_path);
         }
 
@@ -391,11 +421,11 @@ impl StrategyEngine {
 
         // 3. Solve on-demand
         eprintln!("  Solving river {} (this may take 1-5s)...", board);
-        let config = RiverSolverConfig::new(board, oop_range, ip_range, pot, stack, iterations)?;
         let mut solution = solve_river(&config);
         solution.oop_pos = oop_pos.to_string();
         solution.ip_pos = ip_pos.to_string();
         solution.save_cache();
+        self.simplify_river(&mut solution);
 
         lookup_in_river_solution(&solution, hand, hero_side, action_path)
     }
@@ -410,13 +440,13 @@ impl StrategyEngine {
 /// Action codes: "x"/"check" → Check, "b"/"bet" → Bet, "c"/"call" → Call,
 /// "r"/"raise" → Raise, "f"/"fold" → Fold.
 /// Can also match exact action labels like "Bet 2.0" or "Check".
-fn navigate_to_node(edges: &[TreeEdge], action_path: &[String]) -> Option<u16> {
+fn navigate_to_node(edges: &[TreeEdge], action_path: &[String]) -> Option<u32> {
     if action_path.is_empty() {
         return None; // no navigation needed, use root
     }
 
     // Find root node (smallest from_id that doesn't appear as any to_id)
-    let to_ids: std::collections::HashSet<u16> = edges.iter().map(|e| e.to).collect();
+    let to_ids: std::collections::HashSet<u32> = edges.iter().map(|e| e.to).collect();
     let root_id = edges
         .iter()
         .map(|e| e.from)
@@ -482,6 +512,7 @@ fn lookup_in_flop_solution(
                 actions: vec![],
                 frequencies: vec![],
                 source: StrategySource::NotInRange,
+                simplification_cost: None,
             });
         }
     };
@@ -503,6 +534,7 @@ fn lookup_in_flop_solution(
                 actions: strat.actions.clone(),
                 frequencies: strat.frequencies[combo_idx].clone(),
                 source: StrategySource::Cached,
+                simplification_cost: None,
             });
         }
     }
@@ -533,6 +565,7 @@ fn lookup_in_turn_solution(
                 actions: vec![],
                 frequencies: vec![],
                 source: StrategySource::NotInRange,
+                simplification_cost: None,
             });
         }
     };
@@ -544,6 +577,7 @@ fn lookup_in_turn_solution(
                 actions: strat.actions.clone(),
                 frequencies: strat.frequencies[combo_idx].clone(),
                 source: StrategySource::Cached,
+                simplification_cost: None,
             });
         }
     }
@@ -570,6 +604,7 @@ fn lookup_in_river_solution(
                 actions: vec![],
                 frequencies: vec![],
                 source: StrategySource::NotInRange,
+                simplification_cost: None,
             });
         }
     };
@@ -581,6 +616,7 @@ fn lookup_in_river_solution(
                 actions: strat.actions.clone(),
                 frequencies: strat.frequencies[combo_idx].clone(),
                 source: StrategySource::Cached,
+                simplification_cost: solution.simplification_cost_pct(),
             });
         }
     }
@@ -621,13 +657,14 @@ fn lookup_in_template_strategy(
     } else {
         &flop_sol.ip_combos
     };
-    if find_combo_index(hand, combos).is_none() {
+    let Some(combo_idx) = find_combo_index(hand, combos) else {
         return Ok(StrategyResult {
             actions: vec![],
             frequencies: vec![],
             source: StrategySource::NotInRange,
+            simplification_cost: None,
         });
-    }
+    };
 
     // Parse board to card indices
     let board_cards = parse_board(board).map_err(|e| format!("{}", e))?;
@@ -636,8 +673,19 @@ fn lookup_in_template_strategy(
     // Compute bucket for this hand on this board
     // num_samples: 200 for turn (4 cards), 0 for river (5 cards — exact equity)
     let num_samples = if board_indices.len() == 4 { 200 } else { 0 };
-    let buckets = assign_buckets(&[(h0, h1)], &board_indices, flop_sol.num_buckets, num_samples);
-    let bucket = buckets[0] as usize;
+    let bucket = if flop_sol.bucketing == BucketingMode::Unabstracted {
+        unabstracted_bucket(combo_idx, combos.len(), &board_indices) as usize
+    } else {
+        let buckets = bucket_combos(
+            flop_sol.bucketing,
+            &[(h0, h1)],
+            &board_indices,
+            flop_sol.num_buckets,
+            num_samples,
+            None,
+        );
+        buckets[0] as usize
+    };
 
     // Navigate to target node if action path provided
     let target_node = if action_path.is_empty() {
@@ -656,6 +704,7 @@ fn lookup_in_template_strategy(
                 actions: strat.actions.clone(),
                 frequencies: strat.frequencies[bucket].clone(),
                 source: StrategySource::Cached,
+                simplification_cost: None,
             });
         }
     }
@@ -820,6 +869,7 @@ mod tests {
             actions: vec!["CHECK".to_string(), "BET 33%".to_string(), "BET 75%".to_string()],
             frequencies: vec![0.45, 0.30, 0.25],
             source: StrategySource::Cached,
+            simplification_cost: None,
         };
         let formatted = format_strategy(&result);
         assert!(formatted.contains("CHECK"));
@@ -833,6 +883,7 @@ mod tests {
             actions: vec![],
             frequencies: vec![],
             source: StrategySource::NotInRange,
+            simplification_cost: None,
         };
         assert!(format_strategy(&result).contains("not in range"));
     }
@@ -846,6 +897,7 @@ mod tests {
             effective_stack: 97.0,
             iterations: 100,
             exploitability: 0.0,
+            real_exploitability: 0.0,
             oop_combos: vec!["AhKh".to_string(), "AdKd".to_string()],
             ip_combos: vec!["QhQc".to_string()],
             strategies: vec![],
@@ -859,9 +911,15 @@ mod tests {
             }],
             river_strategies: vec![],
             num_buckets: 200,
+            bucketing: crate::bucketing::BucketingMode::Ehs,
             flop_tree_edges: vec![],
             turn_tree_edges: vec![],
             river_tree_edges: vec![],
+            oop_buckets: vec![],
+            ip_buckets: vec![],
+            oop_combo_evs: vec![],
+            ip_combo_evs: vec![],
+            dead_money: 0.0,
         }
     }
 
@@ -2,22 +2,34 @@
 //!
 //! Solves heads-up turn spots using CFR+ over a turn+river game tree.
 //! At chance nodes (river card dealt), hand strengths are re-evaluated
-//! and blocker-aware reach probabilities are updated.
+//! and blocker-aware reach probabilities are updated. Each river card is
+//! weighted by its probability given both players' hole cards. River cards
+//! that play identically for both ranges share one subtree, weighted by the
+//! number of cards it stands for (see `river_classes`).
 //!
 //! Uses `FlatCfr` for memory-efficient storage (~5x vs HashMap-based)
 //! and two separate instances (one per player) to avoid borrow conflicts.
 
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use rand::Rng;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::card_encoding::{card_to_index, index_to_card};
+use crate::card_encoding::{card_to_index, index_to_card, remaining_deck};
 use crate::cards::parse_board;
+use crate::cfr::{sample_index, solver_rng, CfrFloat, Precision, SolverAlgorithm, SolverRng};
+use crate::checkpoint::{self, CheckpointHeader};
 use crate::flat_cfr::FlatCfr;
-use crate::lookup_eval::evaluate_fast;
+use crate::lookup_table::evaluate_seven;
 use crate::postflop_tree::{
-    build_turn_tree, collect_node_metadata, Player, TerminalType, TreeNode, TurnTreeConfig,
+    build_turn_tree, collect_node_metadata, same_for_both, BetSize, Player, RaiseSizing, TerminalType,
+    TreeConfig, TreeNode, TurnTreeConfig,
 };
+use crate::progress::{report, Progress};
 use crate::ranges::parse_range;
-use crate::river_solver::{expand_range_to_combos, Combo};
+use crate::river_solver::{expand_range_to_combos, pair_payoff, Combo};
 
 // ---------------------------------------------------------------------------
 // Config & result
@@ -29,8 +41,111 @@ pub struct TurnSolverConfig {
     pub oop_range: Vec<String>,
     pub ip_range: Vec<String>,
     pub starting_pot: f64,
+    /// Chips in the pot that neither player invested (antes, folded
+    /// players' bets). Added to the pot when the tree is built, so it's won
+    /// at showdown or on a fold but never refunded.
+    pub dead_money: f64,
     pub effective_stack: f64,
     pub iterations: usize,
+    /// Worker threads for chance-node traversal (0 = all cores, 1 = serial).
+    pub threads: usize,
+    /// Turn betting sizes and raise cap.
+    pub turn_sizing: StreetSizing,
+    /// River betting sizes and raise cap.
+    pub river_sizing: StreetSizing,
+    /// Stack-to-pot fraction above which an extra all-in is offered.
+    pub allin_threshold: f64,
+    /// Bets or raises above this percentage of the stack become all-ins.
+    pub force_allin_above_stack_pct: Option<f64>,
+    /// Whether each player may raise on either street, `[oop, ip]`.
+    pub can_raise: [bool; 2],
+    /// Full or sampled traversal each iteration. Sampled iterations run on
+    /// one thread.
+    pub algorithm: SolverAlgorithm,
+    /// Seed for MCCFR sampling; `None` seeds from entropy.
+    pub seed: Option<u64>,
+    /// Write a checkpoint to `checkpoint_path()` every this many
+    /// iterations. `None` disables checkpointing.
+    pub checkpoint_every: Option<usize>,
+    /// Storage for regrets and strategy sums.
+    pub precision: Precision,
+    /// Measure exploitability every this many iterations and keep the
+    /// series in the solution. `None` skips it; each point is a full
+    /// best-response pass over every river card.
+    pub convergence_every: Option<usize>,
+    /// Solve one river subtree per class of strategically identical river
+    /// cards (see `river_classes`) instead of one per card.
+    pub isomorphic_rivers: bool,
+}
+
+/// Bet/raise sizing for one street of the turn+river tree.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StreetSizing {
+    /// Opening bet sizes per player, `[oop, ip]` (empty = check only).
+    pub bet_sizes: [Vec<BetSize>; 2],
+    /// Raise sizes per player when facing a bet.
+    pub raise_sizes: [RaiseSizing; 2],
+    /// Maximum number of raises on this street.
+    pub max_raises: usize,
+}
+
+impl StreetSizing {
+    /// Largest raise cap accepted per street.
+    pub const MAX_RAISE_CAP: usize = 6;
+
+    /// Reject non-positive or non-finite sizes and oversized raise caps.
+    pub fn validate(&self) -> Result<(), String> {
+        let bet_sizes = self.bet_sizes.iter().flatten();
+        let fractions = bet_sizes.clone().filter_map(|size| match size {
+            BetSize::Pot(frac) => Some(frac),
+            _ => None,
+        });
+        for &size in fractions {
+            if !size.is_finite() || size <= 0.0 {
+                return Err(format!("Bet sizes must be positive pot fractions, got {}", size));
+            }
+        }
+        for sizing in &self.raise_sizes {
+            sizing.validate()?;
+        }
+        if bet_sizes.clone().any(|&size| size == BetSize::Geometric(0)) {
+            return Err("Geometric sizing needs at least one street".to_string());
+        }
+        if self.max_raises > Self::MAX_RAISE_CAP {
+            return Err(format!(
+                "Max raises must be at most {}, got {}",
+                Self::MAX_RAISE_CAP,
+                self.max_raises
+            ));
+        }
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        let describe_sizes = |sizes: &[BetSize]| {
+            if sizes.is_empty() {
+                return "check only".to_string();
+            }
+            sizes
+                .iter()
+                .map(|s| match s {
+                    BetSize::Pot(frac) => format!("{:.0}%", frac * 100.0),
+                    other => other.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let [oop, ip] = &self.bet_sizes;
+        let sizes = if oop == ip {
+            describe_sizes(oop)
+        } else {
+            format!("OOP {} / IP {}", describe_sizes(oop), describe_sizes(ip))
+        };
+        if self.bet_sizes.iter().all(|s| s.is_empty()) {
+            return sizes;
+        }
+        format!("{} (max {} raises)", sizes, self.max_raises)
+    }
 }
 
 impl TurnSolverConfig {
@@ -57,27 +172,116 @@ impl TurnSolverConfig {
             return Err("IP range is empty".to_string());
         }
 
+        let defaults = TurnTreeConfig::new(board.clone(), starting_pot, effective_stack);
+        let turn_sizing = StreetSizing {
+            bet_sizes: defaults.turn.bet_sizes.clone(),
+            raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![1.0])),
+            max_raises: defaults.turn.max_raises,
+        };
+        let river_sizing = StreetSizing {
+            bet_sizes: defaults.river_bet_sizes,
+            raise_sizes: defaults.river_raise_sizes,
+            max_raises: defaults.river_max_raises,
+        };
+
         Ok(TurnSolverConfig {
             board,
             oop_range,
             ip_range,
             starting_pot,
+            dead_money: 0.0,
             effective_stack,
             iterations,
+            threads: 0,
+            turn_sizing,
+            river_sizing,
+            allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+            force_allin_above_stack_pct: None,
+            can_raise: [true; 2],
+            algorithm: SolverAlgorithm::Cfr,
+            seed: None,
+            checkpoint_every: None,
+            precision: Precision::F32,
+            convergence_every: None,
+            isomorphic_rivers: true,
         })
     }
+
+    /// Hash of every field that shapes the game or the stored CFR state.
+    /// Iterations, threads and seed are left out so a resumed solve may
+    /// run longer or on a different machine.
+    pub fn config_hash(&self) -> u64 {
+        checkpoint::config_hash(&(
+            (&self.board, &self.oop_range, &self.ip_range),
+            (self.starting_pot, self.dead_money, self.effective_stack),
+            (&self.turn_sizing, &self.river_sizing, self.can_raise),
+            (self.allin_threshold, self.force_allin_above_stack_pct, self.algorithm),
+            (self.precision, self.isomorphic_rivers),
+        ))
+    }
+
+    /// `config_hash` plus the iteration count. Keys the solution cache, so
+    /// a cached solution is only reused for the exact solve that wrote it.
+    pub fn cache_hash(&self) -> u64 {
+        checkpoint::config_hash(&(self.config_hash(), self.iterations))
+    }
+
+    /// Where checkpoints for this config are written and resumed from.
+    pub fn checkpoint_path(&self) -> PathBuf {
+        let board: String = self.board.iter().map(|&c| index_to_card(c).to_string()).collect();
+        checkpoint::solver_dir().join(format!("turn_{}_{:016x}.ckpt", board, self.config_hash()))
+    }
+
+    /// Turn+river tree config with this config's per-street sizing applied,
+    /// dealing one river per class of `river_classes` when
+    /// `isomorphic_rivers` is set. This is the tree the solve builds.
+    pub fn tree_config(&self) -> TurnTreeConfig {
+        let mut tree_config =
+            TurnTreeConfig::new(self.board.clone(), self.starting_pot + self.dead_money, self.effective_stack);
+        tree_config.turn.bet_sizes = self.turn_sizing.bet_sizes.clone();
+        tree_config.turn.raise_sizes = self.turn_sizing.raise_sizes.clone();
+        tree_config.turn.max_raises = self.turn_sizing.max_raises;
+        tree_config.river_bet_sizes = self.river_sizing.bet_sizes.clone();
+        tree_config.river_raise_sizes = self.river_sizing.raise_sizes.clone();
+        tree_config.river_max_raises = self.river_sizing.max_raises;
+        tree_config.turn.allin_threshold = self.allin_threshold;
+        tree_config.turn.force_allin_above_stack_pct = self.force_allin_above_stack_pct;
+        tree_config.turn.can_raise = self.can_raise;
+        if self.isomorphic_rivers {
+            let oop_combos = expand_range_to_combos(&self.oop_range, &self.board);
+            let ip_combos = expand_range_to_combos(&self.ip_range, &self.board);
+            tree_config.river_cards = Some(river_classes(&self.board, &oop_combos, &ip_combos));
+        }
+        tree_config
+    }
 }
 
 /// Per-node strategy for the turn solution.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TurnNodeStrategy {
-    pub node_id: u16,
+    pub node_id: u32,
     pub player: String,
     pub actions: Vec<String>,
+    /// Pot-relative labels parallel to `actions` ("Bet 67% (6.7)") for
+    /// display. Empty in older solutions.
+    #[serde(default)]
+    pub labels: Vec<String>,
     /// Average strategy frequencies: [combo_idx][action_idx].
     pub frequencies: Vec<Vec<f64>>,
 }
 
+impl TurnNodeStrategy {
+    /// Labels to show a reader: the pot-relative ones when recorded, else
+    /// the plain action labels.
+    pub fn display_labels(&self) -> &[String] {
+        if self.labels.len() == self.actions.len() {
+            &self.labels
+        } else {
+            &self.actions
+        }
+    }
+}
+
 /// Full solution from the turn solver.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TurnSolution {
@@ -98,6 +302,56 @@ pub struct TurnSolution {
     /// IP position label (e.g. "BTN") — used in cache key.
     #[serde(default)]
     pub ip_pos: String,
+    /// `TurnSolverConfig::cache_hash` of the solve, part of the cache key.
+    #[serde(default)]
+    pub config_hash: u64,
+    /// Turn sizing the tree was built with.
+    #[serde(default)]
+    pub turn_sizing: StreetSizing,
+    /// River sizing the tree was built with.
+    #[serde(default)]
+    pub river_sizing: StreetSizing,
+    /// All-in threshold the tree was built with (fraction of pot).
+    #[serde(default = "crate::river_solver::default_allin_threshold")]
+    pub allin_threshold: f64,
+    /// Forced all-in line the tree was built with (percent of stack).
+    #[serde(default)]
+    pub force_allin_above_stack_pct: Option<f64>,
+    /// Which players could raise in the tree, `[oop, ip]`.
+    #[serde(default = "crate::river_solver::default_can_raise")]
+    pub can_raise: [bool; 2],
+    /// Dead money the pot held on top of `starting_pot`.
+    #[serde(default)]
+    pub dead_money: f64,
+    /// Average-strategy EV of each OOP combo (same order as `oop_combos`).
+    /// `None` when the combo has no valid opponent combos.
+    #[serde(default)]
+    pub oop_combo_evs: Vec<Option<f64>>,
+    /// Average-strategy EV of each IP combo (same order as `ip_combos`).
+    #[serde(default)]
+    pub ip_combo_evs: Vec<Option<f64>>,
+    /// Traversal the solve ran with.
+    #[serde(default)]
+    pub algorithm: SolverAlgorithm,
+    /// Bytes both players' `FlatCfr` arrays held (`FlatCfr::memory_bytes`).
+    #[serde(default)]
+    pub trainer_bytes: usize,
+    /// The game tree the strategies were solved on, so node ids and action
+    /// amounts can be read back without rebuilding it. `None` for an empty
+    /// spot or an older solution.
+    #[serde(default)]
+    pub tree: Option<TreeNode>,
+    /// `(iterations, exploitability)` sampled every
+    /// `TurnSolverConfig::convergence_every` iterations. Empty when
+    /// sampling was off.
+    #[serde(default)]
+    pub convergence: Vec<(usize, f64)>,
+    /// Each player's per-combo probability of playing to every turn action
+    /// node under the average strategies, `[oop, ip]` and indexed like
+    /// `oop_combos` / `ip_combos`. 1.0 for every combo at the root. Empty
+    /// for an empty spot or an older solution.
+    #[serde(default)]
+    pub node_reach: BTreeMap<u32, [Vec<f64>; 2]>,
 }
 
 // ---------------------------------------------------------------------------
@@ -106,13 +360,81 @@ pub struct TurnSolution {
 
 /// Solve a turn spot. Returns the full solution including exploitability.
 pub fn solve_turn(config: &TurnSolverConfig) -> TurnSolution {
-    let tree_config = TurnTreeConfig::new(
-        config.board.clone(),
-        config.starting_pot,
-        config.effective_stack,
-    );
-    let (tree, _num_nodes) = build_turn_tree(&tree_config);
+    solve_turn_with_progress(config, None)
+}
+
+/// Solve a turn spot, reporting `(iterations done, total)` to `progress`.
+pub fn solve_turn_with_progress(config: &TurnSolverConfig, progress: Progress) -> TurnSolution {
+    match config.precision {
+        Precision::F32 => solve_from::<f32>(config, None, progress),
+        Precision::F64 => solve_from::<f64>(config, None, progress),
+    }
+}
+
+/// Continue a turn solve from the checkpoint at `config.checkpoint_path()`
+/// up to `config.iterations`. Fails if the checkpoint is missing, unreadable
+/// or was written by a config with a different `config_hash`.
+pub fn resume_turn(config: &TurnSolverConfig, progress: Progress) -> Result<TurnSolution, String> {
+    let path = config.checkpoint_path();
+    let hash = config.config_hash();
+    Ok(match config.precision {
+        Precision::F32 => solve_from::<f32>(config, Some(checkpoint::load_matching(&path, hash)?), progress),
+        Precision::F64 => solve_from::<f64>(config, Some(checkpoint::load_matching(&path, hash)?), progress),
+    })
+}
+
+/// Save a checkpoint if `done` iterations lands on the configured interval.
+/// Best effort, like the solution caches: a failed write doesn't stop the solve.
+fn maybe_checkpoint<F: CfrFloat>(
+    config: &TurnSolverConfig,
+    oop_cfr: &FlatCfr<F>,
+    ip_cfr: &FlatCfr<F>,
+    rng: &SolverRng,
+    done: usize,
+) {
+    let Some(every) = config.checkpoint_every else {
+        return;
+    };
+    if every > 0 && done.is_multiple_of(every) {
+        let header = CheckpointHeader { iteration: done, config_hash: config.config_hash() };
+        checkpoint::save(&config.checkpoint_path(), header, &(oop_cfr, ip_cfr, rng)).ok();
+    }
+}
+
+/// Record the exploitability after `done` iterations if it lands on the
+/// configured convergence interval.
+#[allow(clippy::too_many_arguments)]
+fn maybe_record_convergence<F: CfrFloat>(
+    config: &TurnSolverConfig,
+    tree: &TreeNode,
+    oop_cfr: &FlatCfr<F>,
+    ip_cfr: &FlatCfr<F>,
+    combos: [&[Combo]; 2],
+    blockers: [&[[bool; 52]]; 2],
+    done: usize,
+    convergence: &mut Vec<(usize, f64)>,
+) {
+    let Some(every) = config.convergence_every else {
+        return;
+    };
+    if every > 0 && done.is_multiple_of(every) {
+        let exploitability = compute_exploitability(
+            tree, oop_cfr, ip_cfr, combos[0], combos[1], blockers[0], blockers[1], &config.board,
+        );
+        convergence.push((done, exploitability));
+    }
+}
+
+/// Iterations done, the per-player CFR state and the sampling RNG, as a
+/// checkpoint holds them.
+type ResumeState<F> = (usize, (FlatCfr<F>, FlatCfr<F>, SolverRng));
 
+/// Run the solve, starting from `resumed` when given.
+fn solve_from<F: CfrFloat>(
+    config: &TurnSolverConfig,
+    resumed: Option<ResumeState<F>>,
+    progress: Progress,
+) -> TurnSolution {
     let oop_combos = expand_range_to_combos(&config.oop_range, &config.board);
     let ip_combos = expand_range_to_combos(&config.ip_range, &config.board);
 
@@ -120,6 +442,8 @@ pub fn solve_turn(config: &TurnSolverConfig) -> TurnSolution {
         return empty_solution(config);
     }
 
+    let (tree, _num_nodes) = build_turn_tree(&config.tree_config());
+
     // Collect node metadata and build FlatCfr instances per player
     let metas = collect_node_metadata(&tree);
     let num_oop = oop_combos.len() as u16;
@@ -140,8 +464,10 @@ pub fn solve_turn(config: &TurnSolverConfig) -> TurnSolution {
         })
         .collect();
 
-    let mut oop_cfr = FlatCfr::new(&oop_nodes);
-    let mut ip_cfr = FlatCfr::new(&ip_nodes);
+    let (start_iter, (mut oop_cfr, mut ip_cfr, mut rng)) = resumed.unwrap_or_else(|| {
+        let rng = solver_rng(config.seed);
+        (0, (FlatCfr::from_nodes(&oop_nodes), FlatCfr::from_nodes(&ip_nodes), rng))
+    });
 
     // Precompute: blocker sets for each combo
     let oop_blockers: Vec<[bool; 52]> = oop_combos
@@ -191,83 +517,253 @@ pub fn solve_turn(config: &TurnSolverConfig) -> TurnSolution {
         })
         .collect();
 
-    // Reusable buffers
+    // Reusable strategy buffer
     let max_actions = metas.iter().map(|m| m.num_actions).max().unwrap_or(1) as usize;
     let mut strategy_buf = vec![0.0f32; max_actions];
-    let mut action_values = vec![0.0f32; max_actions];
 
-    // Run alternating CFR+ iterations
-    for iter in 0..config.iterations {
-        let traverser = if iter % 2 == 0 { Player::OOP } else { Player::IP };
+    // With a single thread the traversal stays fully serial; otherwise the
+    // river subtrees under each chance node fan out on a rayon pool.
+    let parallel = config.threads != 1;
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(config.threads)
+        .build()
+        .expect("failed to build solver thread pool");
 
-        let num_combos = match traverser {
-            Player::OOP => oop_combos.len(),
-            Player::IP => ip_combos.len(),
-        };
+    let mut convergence = Vec::new();
+    let combos = [oop_combos.as_slice(), ip_combos.as_slice()];
+    let blockers = [oop_blockers.as_slice(), ip_blockers.as_slice()];
 
-        for h in 0..num_combos {
-            // Initialize opponent reach: 1.0 for non-conflicting, 0.0 for blocked
-            let opp_reach = match traverser {
-                Player::OOP => {
-                    let valid = &valid_ip_for_oop[h];
-                    let mut reach = vec![0.0f64; ip_combos.len()];
-                    for &j in valid {
-                        reach[j as usize] = 1.0;
-                    }
-                    reach
-                }
-                Player::IP => {
-                    let valid = &valid_oop_for_ip[h];
-                    let mut reach = vec![0.0f64; oop_combos.len()];
-                    for &i in valid {
-                        reach[i as usize] = 1.0;
+    // Run alternating CFR+ iterations
+    pool.install(|| {
+        for iter in start_iter..config.iterations {
+            let traverser = if iter % 2 == 0 { Player::OOP } else { Player::IP };
+
+            if config.algorithm == SolverAlgorithm::Mccfr {
+                let (hands, opps, valid) = match traverser {
+                    Player::OOP => (&oop_combos, &ip_combos, &valid_ip_for_oop),
+                    Player::IP => (&ip_combos, &oop_combos, &valid_oop_for_ip),
+                };
+                for (h, valid) in valid.iter().enumerate() {
+                    if valid.is_empty() {
+                        continue;
                     }
-                    reach
+                    let opp_idx = valid[rng.gen_range(0..valid.len())] as usize;
+                    let deal = SampledDeal {
+                        traverser,
+                        hand_idx: h,
+                        opp_idx,
+                        hands: [hands[h], opps[opp_idx]],
+                    };
+                    mccfr_traverse_turn(
+                        &tree, &deal, &config.board, None, 1.0, &mut oop_cfr, &mut ip_cfr, &mut rng,
+                    );
                 }
+                maybe_checkpoint(config, &oop_cfr, &ip_cfr, &rng, iter + 1);
+                maybe_record_convergence(
+                    config, &tree, &oop_cfr, &ip_cfr, combos, blockers, iter + 1, &mut convergence,
+                );
+                report(progress, iter + 1, config.iterations);
+                continue;
+            }
+
+            let num_combos = match traverser {
+                Player::OOP => oop_combos.len(),
+                Player::IP => ip_combos.len(),
             };
 
-            cfr_traverse_turn(
-                &tree,
-                traverser,
-                h,
-                &opp_reach,
-                &oop_combos,
-                &ip_combos,
-                &oop_blockers,
-                &ip_blockers,
-                &config.board,
-                &mut oop_cfr,
-                &mut ip_cfr,
-                &mut strategy_buf,
-                &mut action_values,
-                iter,
+            for h in 0..num_combos {
+                // Initialize opponent reach: 1.0 for non-conflicting, 0.0 for blocked
+                let opp_reach = match traverser {
+                    Player::OOP => {
+                        let valid = &valid_ip_for_oop[h];
+                        let mut reach = vec![0.0f64; ip_combos.len()];
+                        for &j in valid {
+                            reach[j as usize] = 1.0;
+                        }
+                        reach
+                    }
+                    Player::IP => {
+                        let valid = &valid_oop_for_ip[h];
+                        let mut reach = vec![0.0f64; oop_combos.len()];
+                        for &i in valid {
+                            reach[i as usize] = 1.0;
+                        }
+                        reach
+                    }
+                };
+
+                cfr_traverse_turn(
+                    &tree,
+                    traverser,
+                    h,
+                    1.0,
+                    &opp_reach,
+                    &oop_combos,
+                    &ip_combos,
+                    &oop_blockers,
+                    &ip_blockers,
+                    &config.board,
+                    &mut oop_cfr,
+                    &mut ip_cfr,
+                    &mut strategy_buf,
+                    iter,
+                    parallel,
+                );
+            }
+            maybe_checkpoint(config, &oop_cfr, &ip_cfr, &rng, iter + 1);
+            maybe_record_convergence(
+                config, &tree, &oop_cfr, &ip_cfr, combos, blockers, iter + 1, &mut convergence,
             );
+            report(progress, iter + 1, config.iterations);
         }
-    }
-
-    // Extract solution
-    extract_solution(
-        config,
-        &tree,
-        &oop_cfr,
-        &ip_cfr,
-        &oop_combos,
-        &ip_combos,
-        &metas,
-    )
+    });
+
+    // Extract solution; its best-response passes fan out on the same pool
+    pool.install(|| {
+        extract_solution(
+            config,
+            &tree,
+            &oop_cfr,
+            &ip_cfr,
+            &oop_combos,
+            &ip_combos,
+            convergence,
+        )
+    })
 }
 
 // ---------------------------------------------------------------------------
 // CFR+ traversal
 // ---------------------------------------------------------------------------
 
+/// A regret update collected during a read-only river traversal, applied to
+/// the traverser's `FlatCfr` once every river card has been visited.
+struct RegretUpdate {
+    node_id: usize,
+    action_values: Vec<f32>,
+    node_value: f32,
+    reach_prob: f32,
+}
+
+/// Evaluate one dealt river card: score both ranges on the 5-card board and
+/// run the read-only river traversal, buffering the traverser's updates.
+#[allow(clippy::too_many_arguments)]
+fn cfr_traverse_river_card<F: CfrFloat>(
+    node: &TreeNode,
+    traverser: Player,
+    hand_idx: usize,
+    my_reach: f64,
+    opp_reach: &[f64],
+    oop_combos: &[Combo],
+    ip_combos: &[Combo],
+    board: &[u8],
+    river_card: u8,
+    oop_cfr: &FlatCfr<F>,
+    ip_cfr: &FlatCfr<F>,
+    strategy_buf: &mut [f32],
+    updates: &mut Vec<RegretUpdate>,
+    iter: usize,
+) -> f64 {
+    // Build 5-card river board
+    let river_board: [u8; 5] = [board[0], board[1], board[2], board[3], river_card];
+
+    // Evaluate hand strengths for this river card
+    let oop_scores: Vec<u32> = oop_combos
+        .iter()
+        .map(|c| {
+            evaluate_seven(&[
+                c.0,
+                c.1,
+                river_board[0],
+                river_board[1],
+                river_board[2],
+                river_board[3],
+                river_board[4],
+            ])
+        })
+        .collect();
+    let ip_scores: Vec<u32> = ip_combos
+        .iter()
+        .map(|c| {
+            evaluate_seven(&[
+                c.0,
+                c.1,
+                river_board[0],
+                river_board[1],
+                river_board[2],
+                river_board[3],
+                river_board[4],
+            ])
+        })
+        .collect();
+
+    // Validity tables for this hand against opponents (blocker-aware)
+    let (valid_ip_h, valid_oop_h) = match traverser {
+        Player::OOP => {
+            let valid_ip: Vec<u16> = ip_combos
+                .iter()
+                .enumerate()
+                .filter(|(_, ip)| {
+                    let oop = &oop_combos[hand_idx];
+                    oop.0 != ip.0
+                        && oop.0 != ip.1
+                        && oop.1 != ip.0
+                        && oop.1 != ip.1
+                        && ip.0 != river_card
+                        && ip.1 != river_card
+                })
+                .map(|(j, _)| j as u16)
+                .collect();
+            (valid_ip, Vec::new())
+        }
+        Player::IP => {
+            let valid_oop: Vec<u16> = oop_combos
+                .iter()
+                .enumerate()
+                .filter(|(_, oop)| {
+                    let ip = &ip_combos[hand_idx];
+                    ip.0 != oop.0
+                        && ip.0 != oop.1
+                        && ip.1 != oop.0
+                        && ip.1 != oop.1
+                        && oop.0 != river_card
+                        && oop.1 != river_card
+                })
+                .map(|(i, _)| i as u16)
+                .collect();
+            (Vec::new(), valid_oop)
+        }
+    };
+
+    cfr_traverse_river(
+        node,
+        traverser,
+        hand_idx,
+        my_reach,
+        opp_reach,
+        oop_combos,
+        ip_combos,
+        &river_board,
+        &oop_scores,
+        &ip_scores,
+        &valid_ip_h,
+        &valid_oop_h,
+        oop_cfr,
+        ip_cfr,
+        strategy_buf,
+        updates,
+        iter,
+    )
+}
+
 /// Recursive CFR+ traversal for river subtrees (inside chance nodes).
 /// `river_board` is the full 5-card board (turn board + dealt river card).
 #[allow(clippy::too_many_arguments)]
-fn cfr_traverse_river(
+fn cfr_traverse_river<F: CfrFloat>(
     node: &TreeNode,
     traverser: Player,
     hand_idx: usize,
+    my_reach: f64,
     opp_reach: &[f64],
     oop_combos: &[Combo],
     ip_combos: &[Combo],
@@ -276,10 +772,10 @@ fn cfr_traverse_river(
     ip_scores: &[u32],
     valid_ip_for_oop_h: &[u16],
     valid_oop_for_ip_h: &[u16],
-    oop_cfr: &mut FlatCfr,
-    ip_cfr: &mut FlatCfr,
+    oop_cfr: &FlatCfr<F>,
+    ip_cfr: &FlatCfr<F>,
     strategy_buf: &mut [f32],
-    action_values_buf: &mut [f32],
+    updates: &mut Vec<RegretUpdate>,
     iter: usize,
 ) -> f64 {
     match node {
@@ -364,22 +860,25 @@ fn cfr_traverse_river(
 
             if *player == traverser {
                 let cfr = match traverser {
-                    Player::OOP => &*oop_cfr,
-                    Player::IP => &*ip_cfr,
+                    Player::OOP => oop_cfr,
+                    Player::IP => ip_cfr,
                 };
                 cfr.current_strategy(nid, hand_idx, strategy_buf);
+                // Child traversals reuse the shared buffers, so keep local copies
+                let strategy = strategy_buf[..num_actions].to_vec();
+                let mut action_values = vec![0.0f32; num_actions];
 
                 let mut node_value = 0.0f64;
                 for a in 0..num_actions {
                     // Regret pruning: skip near-zero-probability actions after warmup
-                    if strategy_buf[a] < 0.001 && iter > 1000 && iter % 1000 != 0 {
-                        action_values_buf[a] = 0.0;
+                    if strategy[a] < 0.001 && iter > 1000 && iter % 1000 != 0 {
                         continue;
                     }
                     let av = cfr_traverse_river(
                         &children[a],
                         traverser,
                         hand_idx,
+                        my_reach * strategy[a] as f64,
                         opp_reach,
                         oop_combos,
                         ip_combos,
@@ -391,33 +890,27 @@ fn cfr_traverse_river(
                         oop_cfr,
                         ip_cfr,
                         strategy_buf,
-                        action_values_buf,
+                        updates,
                         iter,
                     );
-                    action_values_buf[a] = av as f32;
-                    node_value += strategy_buf[a] as f64 * av;
+                    action_values[a] = av as f32;
+                    node_value += strategy[a] as f64 * av;
                 }
 
-                let reach_sum: f64 = opp_reach.iter().sum();
-                let reach_prob = if reach_sum > 0.0 { 1.0f32 } else { 0.0f32 };
+                let reach_prob = my_reach as f32;
 
-                let cfr_mut = match traverser {
-                    Player::OOP => &mut *oop_cfr,
-                    Player::IP => &mut *ip_cfr,
-                };
-                cfr_mut.update(
-                    nid,
-                    hand_idx,
-                    &action_values_buf[..num_actions],
-                    node_value as f32,
+                updates.push(RegretUpdate {
+                    node_id: nid,
+                    action_values,
+                    node_value: node_value as f32,
                     reach_prob,
-                );
+                });
 
                 node_value
             } else {
                 let opp_cfr = match traverser {
-                    Player::OOP => &*ip_cfr,
-                    Player::IP => &*oop_cfr,
+                    Player::OOP => ip_cfr,
+                    Player::IP => oop_cfr,
                 };
                 let num_opp = opp_reach.len();
                 let opp_num_actions = opp_cfr.node_num_actions(nid) as usize;
@@ -447,6 +940,7 @@ fn cfr_traverse_river(
                         &children[a],
                         traverser,
                         hand_idx,
+                        my_reach,
                         &new_opp_reach,
                         oop_combos,
                         ip_combos,
@@ -458,7 +952,7 @@ fn cfr_traverse_river(
                         oop_cfr,
                         ip_cfr,
                         strategy_buf,
-                        action_values_buf,
+                        updates,
                         iter,
                     );
                 }
@@ -479,21 +973,22 @@ fn cfr_traverse_river(
 /// Top-level CFR+ traversal for the turn tree.
 /// Handles turn action nodes and chance nodes (delegates to river traversal).
 #[allow(clippy::too_many_arguments)]
-fn cfr_traverse_turn(
+fn cfr_traverse_turn<F: CfrFloat>(
     node: &TreeNode,
     traverser: Player,
     hand_idx: usize,
+    my_reach: f64,
     opp_reach: &[f64],
     oop_combos: &[Combo],
     ip_combos: &[Combo],
     oop_blockers: &[[bool; 52]],
     ip_blockers: &[[bool; 52]],
     board: &[u8],
-    oop_cfr: &mut FlatCfr,
-    ip_cfr: &mut FlatCfr,
+    oop_cfr: &mut FlatCfr<F>,
+    ip_cfr: &mut FlatCfr<F>,
     strategy_buf: &mut [f32],
-    action_values_buf: &mut [f32],
     iter: usize,
+    parallel: bool,
 ) -> f64 {
     match node {
         TreeNode::Terminal {
@@ -523,145 +1018,90 @@ fn cfr_traverse_turn(
             }
         }
         TreeNode::Chance {
-            cards, children, ..
+            cards, weights, children, ..
         } => {
-            let mut total_value = 0.0;
-            let mut valid_count = 0usize;
-
-            for (ci, &river_card) in cards.iter().enumerate() {
-                // Skip if traverser's hand blocks this river card
-                let traverser_blocked = match traverser {
-                    Player::OOP => oop_blockers[hand_idx][river_card as usize],
-                    Player::IP => ip_blockers[hand_idx][river_card as usize],
-                };
-                if traverser_blocked {
-                    continue;
-                }
-                valid_count += 1;
-
-                // Build new opp_reach: zero out opponents blocked by river card
-                let new_opp_reach: Vec<f64> = match traverser {
-                    Player::OOP => opp_reach
-                        .iter()
-                        .enumerate()
-                        .map(|(j, &r)| {
-                            if r > 0.0 && !ip_blockers[j][river_card as usize] {
-                                r
-                            } else {
-                                0.0
-                            }
-                        })
-                        .collect(),
-                    Player::IP => opp_reach
-                        .iter()
-                        .enumerate()
-                        .map(|(i, &r)| {
-                            if r > 0.0 && !oop_blockers[i][river_card as usize] {
-                                r
-                            } else {
-                                0.0
-                            }
-                        })
-                        .collect(),
-                };
+            let (hero_blockers, opp_blockers) = match traverser {
+                Player::OOP => (&oop_blockers[hand_idx], ip_blockers),
+                Player::IP => (&ip_blockers[hand_idx], oop_blockers),
+            };
+            let opp_live = live_river_counts(cards, weights, hero_blockers, opp_blockers, opp_reach);
 
-                // Build 5-card river board
-                let river_board: [u8; 5] = [board[0], board[1], board[2], board[3], river_card];
+            // Skip river cards the traverser's hand blocks
+            let live_cards: Vec<(usize, u8)> = cards
+                .iter()
+                .copied()
+                .enumerate()
+                .filter(|&(_, c)| !hero_blockers[c as usize])
+                .collect();
 
-                // Evaluate hand strengths for this river card
-                let oop_scores: Vec<u32> = oop_combos
-                    .iter()
-                    .map(|c| {
-                        evaluate_fast(&[
-                            c.0,
-                            c.1,
-                            river_board[0],
-                            river_board[1],
-                            river_board[2],
-                            river_board[3],
-                            river_board[4],
-                        ])
-                    })
-                    .collect();
-                let ip_scores: Vec<u32> = ip_combos
-                    .iter()
-                    .map(|c| {
-                        evaluate_fast(&[
-                            c.0,
-                            c.1,
-                            river_board[0],
-                            river_board[1],
-                            river_board[2],
-                            river_board[3],
-                            river_board[4],
-                        ])
-                    })
-                    .collect();
+            // River subtrees are independent given the current strategies, so
+            // they are traversed read-only with regret updates buffered and
+            // applied after all children return.
+            let (total_value, updates) = {
+                let (oop_ref, ip_ref) = (&*oop_cfr, &*ip_cfr);
+                let traverse_card = |ci: usize,
+                                     river_card: u8,
+                                     buf: &mut [f32],
+                                     updates: &mut Vec<RegretUpdate>| {
+                    // Zero out opponents blocked by the river card and weight the
+                    // rest by the probability of this card given both hands
+                    let new_opp_reach =
+                        chance_opp_reach(opp_reach, opp_blockers, &opp_live, river_card, weights[ci]);
+                    cfr_traverse_river_card(
+                        &children[ci],
+                        traverser,
+                        hand_idx,
+                        my_reach,
+                        &new_opp_reach,
+                        oop_combos,
+                        ip_combos,
+                        board,
+                        river_card,
+                        oop_ref,
+                        ip_ref,
+                        buf,
+                        updates,
+                        iter,
+                    )
+                };
 
-                // Validity tables for this hand against opponents (blocker-aware)
-                let (valid_ip_h, valid_oop_h) = match traverser {
-                    Player::OOP => {
-                        let valid_ip: Vec<u16> = ip_combos
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, ip)| {
-                                let oop = &oop_combos[hand_idx];
-                                oop.0 != ip.0
-                                    && oop.0 != ip.1
-                                    && oop.1 != ip.0
-                                    && oop.1 != ip.1
-                                    && ip.0 != river_card
-                                    && ip.1 != river_card
-                            })
-                            .map(|(j, _)| j as u16)
-                            .collect();
-                        (valid_ip, Vec::new())
-                    }
-                    Player::IP => {
-                        let valid_oop: Vec<u16> = oop_combos
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, oop)| {
-                                let ip = &ip_combos[hand_idx];
-                                ip.0 != oop.0
-                                    && ip.0 != oop.1
-                                    && ip.1 != oop.0
-                                    && ip.1 != oop.1
-                                    && oop.0 != river_card
-                                    && oop.1 != river_card
-                            })
-                            .map(|(i, _)| i as u16)
-                            .collect();
-                        (Vec::new(), valid_oop)
+                if parallel {
+                    let buf_len = strategy_buf.len();
+                    live_cards
+                        .par_iter()
+                        .map(|&(ci, river_card)| {
+                            let mut buf = vec![0.0f32; buf_len];
+                            let mut updates = Vec::new();
+                            let value = traverse_card(ci, river_card, &mut buf, &mut updates);
+                            (value, updates)
+                        })
+                        .reduce(
+                            || (0.0, Vec::new()),
+                            |mut acc, (value, updates)| {
+                                acc.0 += value;
+                                acc.1.extend(updates);
+                                acc
+                            },
+                        )
+                } else {
+                    let mut updates = Vec::new();
+                    let mut total = 0.0;
+                    for &(ci, river_card) in &live_cards {
+                        total += traverse_card(ci, river_card, strategy_buf, &mut updates);
                     }
-                };
+                    (total, updates)
+                }
+            };
 
-                let child_value = cfr_traverse_river(
-                    &children[ci],
-                    traverser,
-                    hand_idx,
-                    &new_opp_reach,
-                    oop_combos,
-                    ip_combos,
-                    &river_board,
-                    &oop_scores,
-                    &ip_scores,
-                    &valid_ip_h,
-                    &valid_oop_h,
-                    oop_cfr,
-                    ip_cfr,
-                    strategy_buf,
-                    action_values_buf,
-                    iter,
-                );
-                total_value += child_value;
+            let cfr_mut = match traverser {
+                Player::OOP => &mut *oop_cfr,
+                Player::IP => &mut *ip_cfr,
+            };
+            for u in &updates {
+                cfr_mut.update(u.node_id, hand_idx, &u.action_values, u.node_value, u.reach_prob);
             }
 
-            if valid_count > 0 {
-                total_value / valid_count as f64
-            } else {
-                0.0
-            }
+            total_value
         }
         TreeNode::Action {
             node_id,
@@ -679,18 +1119,21 @@ fn cfr_traverse_turn(
                     Player::IP => &*ip_cfr,
                 };
                 cfr.current_strategy(nid, hand_idx, strategy_buf);
+                // Child traversals reuse the shared buffers, so keep local copies
+                let strategy = strategy_buf[..num_actions].to_vec();
+                let mut action_values = vec![0.0f32; num_actions];
 
                 let mut node_value = 0.0f64;
                 for a in 0..num_actions {
                     // Regret pruning: skip near-zero-probability actions after warmup
-                    if strategy_buf[a] < 0.001 && iter > 1000 && iter % 1000 != 0 {
-                        action_values_buf[a] = 0.0;
+                    if strategy[a] < 0.001 && iter > 1000 && iter % 1000 != 0 {
                         continue;
                     }
                     let av = cfr_traverse_turn(
                         &children[a],
                         traverser,
                         hand_idx,
+                        my_reach * strategy[a] as f64,
                         opp_reach,
                         oop_combos,
                         ip_combos,
@@ -700,15 +1143,14 @@ fn cfr_traverse_turn(
                         oop_cfr,
                         ip_cfr,
                         strategy_buf,
-                        action_values_buf,
                         iter,
+                        parallel,
                     );
-                    action_values_buf[a] = av as f32;
-                    node_value += strategy_buf[a] as f64 * av;
+                    action_values[a] = av as f32;
+                    node_value += strategy[a] as f64 * av;
                 }
 
-                let reach_sum: f64 = opp_reach.iter().sum();
-                let reach_prob = if reach_sum > 0.0 { 1.0f32 } else { 0.0f32 };
+                let reach_prob = my_reach as f32;
 
                 let cfr_mut = match traverser {
                     Player::OOP => &mut *oop_cfr,
@@ -717,7 +1159,7 @@ fn cfr_traverse_turn(
                 cfr_mut.update(
                     nid,
                     hand_idx,
-                    &action_values_buf[..num_actions],
+                    &action_values,
                     node_value as f32,
                     reach_prob,
                 );
@@ -756,6 +1198,7 @@ fn cfr_traverse_turn(
                         &children[a],
                         traverser,
                         hand_idx,
+                        my_reach,
                         &new_opp_reach,
                         oop_combos,
                         ip_combos,
@@ -765,8 +1208,8 @@ fn cfr_traverse_turn(
                         oop_cfr,
                         ip_cfr,
                         strategy_buf,
-                        action_values_buf,
                         iter,
+                        parallel,
                     );
                 }
 
@@ -776,15 +1219,218 @@ fn cfr_traverse_turn(
     }
 }
 
+/// One dealt pair for an external-sampling traversal.
+struct SampledDeal {
+    traverser: Player,
+    hand_idx: usize,
+    opp_idx: usize,
+    /// The traverser's hand, then the opponent's.
+    hands: [Combo; 2],
+}
+
+impl SampledDeal {
+    fn blocks(&self, card: u8) -> bool {
+        self.hands.iter().any(|h| h.0 == card || h.1 == card)
+    }
+
+    /// Hand scores `(traverser, opponent)` on the turn board plus `river`.
+    fn scores(&self, board: &[u8], river: u8) -> (u32, u32) {
+        let score = |h: Combo| evaluate_seven(&[h.0, h.1, board[0], board[1], board[2], board[3], river]);
+        (score(self.hands[0]), score(self.hands[1]))
+    }
+}
+
+/// External-sampling traversal of one dealt pair. Every traverser action is
+/// explored and its regret updated from the sampled values; the opponent
+/// plays one action drawn from its current strategy and chance nodes deal one
+/// river card neither hand blocks. `river` is the card dealt so far, if any;
+/// `my_reach` is the traverser's own probability of playing to `node`.
+#[allow(clippy::too_many_arguments)]
+fn mccfr_traverse_turn<R: Rng, F: CfrFloat>(
+    node: &TreeNode,
+    deal: &SampledDeal,
+    board: &[u8],
+    river: Option<u8>,
+    my_reach: f64,
+    oop_cfr: &mut FlatCfr<F>,
+    ip_cfr: &mut FlatCfr<F>,
+    rng: &mut R,
+) -> f64 {
+    match node {
+        TreeNode::Terminal { terminal_type, pot, invested, .. } => {
+            let (my_score, opp_score) = match terminal_type {
+                TerminalType::Fold { .. } => (0, 0),
+                TerminalType::Showdown => {
+                    let river = river.unwrap_or_else(|| {
+                        let live: Vec<u8> = remaining_deck(board)
+                            .into_iter()
+                            .filter(|&c| !deal.blocks(c))
+                            .collect();
+                        live[rng.gen_range(0..live.len())]
+                    });
+                    deal.scores(board, river)
+                }
+            };
+            pair_payoff(*terminal_type, *pot, invested, deal.traverser, my_score, opp_score)
+        }
+        TreeNode::Chance { cards, weights, children, .. } => {
+            // Each live entry is as likely as the number of cards it stands for
+            let live: Vec<f64> = cards
+                .iter()
+                .zip(weights)
+                .map(|(&c, &w)| if deal.blocks(c) { 0.0 } else { w as f64 })
+                .collect();
+            let ci = sample_index(&live, rng);
+            mccfr_traverse_turn(&children[ci], deal, board, Some(cards[ci]), my_reach, oop_cfr, ip_cfr, rng)
+        }
+        TreeNode::Action { node_id, player, children, actions, .. } => {
+            let num_actions = actions.len();
+            let nid = *node_id as usize;
+            let mut strategy = vec![0.0f32; num_actions];
+
+            if *player == deal.traverser {
+                let cfr = match deal.traverser {
+                    Player::OOP => &*oop_cfr,
+                    Player::IP => &*ip_cfr,
+                };
+                cfr.current_strategy(nid, deal.hand_idx, &mut strategy);
+                let mut action_values = vec![0.0f32; num_actions];
+                let mut node_value = 0.0f64;
+                for a in 0..num_actions {
+                    let reach = my_reach * strategy[a] as f64;
+                    let av = mccfr_traverse_turn(&children[a], deal, board, river, reach, oop_cfr, ip_cfr, rng);
+                    action_values[a] = av as f32;
+                    node_value += strategy[a] as f64 * av;
+                }
+                let cfr_mut = match deal.traverser {
+                    Player::OOP => &mut *oop_cfr,
+                    Player::IP => &mut *ip_cfr,
+                };
+                cfr_mut.update(nid, deal.hand_idx, &action_values, node_value as f32, my_reach as f32);
+                node_value
+            } else {
+                let opp_cfr = match deal.traverser {
+                    Player::OOP => &*ip_cfr,
+                    Player::IP => &*oop_cfr,
+                };
+                opp_cfr.current_strategy(nid, deal.opp_idx, &mut strategy);
+                let weights: Vec<f64> = strategy.iter().map(|&p| p as f64).collect();
+                let a = sample_index(&weights, rng);
+                mccfr_traverse_turn(&children[a], deal, board, river, my_reach, oop_cfr, ip_cfr, rng)
+            }
+        }
+    }
+}
+
+/// Group the river cards left by `board` into classes that play identically
+/// for these ranges, as `(representative, class size)` in deck order.
+///
+/// Cards that share a class are held by no combo of either range and leave
+/// every combo ranked the same against every other, so their river
+/// subtrees, showdowns and probabilities all match and one subtree can stand
+/// in for the class. A card some combo holds is always a class of its own.
+pub fn river_classes(board: &[u8], oop_combos: &[Combo], ip_combos: &[Combo]) -> Vec<(u8, u32)> {
+    let mut held = [false; 52];
+    for c in oop_combos.iter().chain(ip_combos) {
+        held[c.0 as usize] = true;
+        held[c.1 as usize] = true;
+    }
+
+    let mut classes: Vec<(u8, u32)> = Vec::new();
+    let mut by_ranking: HashMap<Vec<u32>, usize> = HashMap::new();
+    for river in remaining_deck(board) {
+        if held[river as usize] {
+            classes.push((river, 1));
+            continue;
+        }
+        let scores: Vec<u32> = oop_combos
+            .iter()
+            .chain(ip_combos)
+            .map(|c| evaluate_seven(&[c.0, c.1, board[0], board[1], board[2], board[3], river]))
+            .collect();
+        // Only the ordering matters, so compare dense ranks
+        let mut levels = scores.clone();
+        levels.sort_unstable();
+        levels.dedup();
+        let ranking: Vec<u32> = scores
+            .iter()
+            .map(|s| levels.partition_point(|l| l < s) as u32)
+            .collect();
+        match by_ranking.get(&ranking) {
+            Some(&i) => classes[i].1 += 1,
+            None => {
+                by_ranking.insert(ranking, classes.len());
+                classes.push((river, 1));
+            }
+        }
+    }
+    classes
+}
+
+/// Number of live river cards for each opponent combo given the hero's hand,
+/// counting each entry of `cards` as the `weights` cards it stands for.
+/// Entries for combos with zero reach are left at 0.
+fn live_river_counts(
+    cards: &[u8],
+    weights: &[u32],
+    hero_blockers: &[bool; 52],
+    opp_blockers: &[[bool; 52]],
+    opp_reach: &[f64],
+) -> Vec<usize> {
+    opp_reach
+        .iter()
+        .enumerate()
+        .map(|(j, &r)| {
+            if r <= 0.0 {
+                return 0;
+            }
+            cards
+                .iter()
+                .zip(weights)
+                .filter(|&(&c, _)| !hero_blockers[c as usize] && !opp_blockers[j][c as usize])
+                .map(|(_, &w)| w as usize)
+                .sum()
+        })
+        .collect()
+}
+
+/// Opponent reach below a chance node for one river card: combos holding the
+/// card are zeroed, the rest are scaled by `weight / live rivers` for that
+/// pair so that summing child values yields a properly weighted expectation.
+fn chance_opp_reach(
+    opp_reach: &[f64],
+    opp_blockers: &[[bool; 52]],
+    opp_live: &[usize],
+    river_card: u8,
+    weight: u32,
+) -> Vec<f64> {
+    opp_reach
+        .iter()
+        .enumerate()
+        .map(|(j, &r)| {
+            if r > 0.0 && opp_live[j] > 0 && !opp_blockers[j][river_card as usize] {
+                r * weight as f64 / opp_live[j] as f64
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 // Exploitability
 // ---------------------------------------------------------------------------
 
+/// Most actions at any node of `tree`, which sizes the strategy buffers.
+fn max_actions(tree: &TreeNode) -> usize {
+    collect_node_metadata(tree).iter().map(|m| m.num_actions as usize).max().unwrap_or(1)
+}
+
 /// Compute exploitability via best-response traversal.
-pub fn compute_exploitability(
+pub fn compute_exploitability<F: CfrFloat>(
     tree: &TreeNode,
-    oop_cfr: &FlatCfr,
-    ip_cfr: &FlatCfr,
+    oop_cfr: &FlatCfr<F>,
+    ip_cfr: &FlatCfr<F>,
     oop_combos: &[Combo],
     ip_combos: &[Combo],
     oop_blockers: &[[bool; 52]],
@@ -817,11 +1463,11 @@ pub fn compute_exploitability(
 }
 
 #[allow(clippy::too_many_arguments)]
-fn best_response_value(
+fn best_response_value<F: CfrFloat>(
     tree: &TreeNode,
     br_player: Player,
-    oop_cfr: &FlatCfr,
-    ip_cfr: &FlatCfr,
+    oop_cfr: &FlatCfr<F>,
+    ip_cfr: &FlatCfr<F>,
     oop_combos: &[Combo],
     ip_combos: &[Combo],
     oop_blockers: &[[bool; 52]],
@@ -861,7 +1507,7 @@ fn best_response_value(
         .collect();
 
     let mut total_gain = 0.0;
-    let mut strat_buf = vec![0.0f32; 16]; // max actions at any node
+    let mut strat_buf = vec![0.0f32; max_actions(tree)];
 
     for h in 0..num_br {
         let mut opp_reach = vec![0.0f64; num_opp];
@@ -907,9 +1553,69 @@ fn best_response_value(
     total_gain / num_br as f64
 }
 
+/// Average-strategy EV of every combo of `player`, in chips won from the
+/// starting pot, averaged over the opponent combos it doesn't conflict with.
+/// Combos with no valid opponents get `None`.
+#[allow(clippy::too_many_arguments)]
+pub fn compute_combo_evs<F: CfrFloat>(
+    tree: &TreeNode,
+    player: Player,
+    oop_cfr: &FlatCfr<F>,
+    ip_cfr: &FlatCfr<F>,
+    oop_combos: &[Combo],
+    ip_combos: &[Combo],
+    oop_blockers: &[[bool; 52]],
+    ip_blockers: &[[bool; 52]],
+    board: &[u8],
+) -> Vec<Option<f64>> {
+    let (hero_combos, opp_combos) = match player {
+        Player::OOP => (oop_combos, ip_combos),
+        Player::IP => (ip_combos, oop_combos),
+    };
+    let mut strat_buf = vec![0.0f32; max_actions(tree)];
+
+    hero_combos
+        .iter()
+        .enumerate()
+        .map(|(h, hero)| {
+            let opp_reach: Vec<f64> = opp_combos
+                .iter()
+                .map(|opp| {
+                    let conflict = hero.0 == opp.0
+                        || hero.0 == opp.1
+                        || hero.1 == opp.0
+                        || hero.1 == opp.1;
+                    if conflict { 0.0 } else { 1.0 }
+                })
+                .collect();
+            let num_valid: f64 = opp_reach.iter().sum();
+            if num_valid == 0.0 {
+                return None;
+            }
+
+            let value = br_traverse_turn(
+                tree,
+                player,
+                h,
+                &opp_reach,
+                oop_combos,
+                ip_combos,
+                oop_blockers,
+                ip_blockers,
+                board,
+                oop_cfr,
+                ip_cfr,
+                &mut strat_buf,
+                false, // average strategy
+            );
+            Some(value / num_valid)
+        })
+        .collect()
+}
+
 /// Best-response / average-strategy traversal for exploitability.
 #[allow(clippy::too_many_arguments)]
-fn br_traverse_turn(
+fn br_traverse_turn<F: CfrFloat>(
     node: &TreeNode,
     br_player: Player,
     hand_idx: usize,
@@ -919,8 +1625,8 @@ fn br_traverse_turn(
     oop_blockers: &[[bool; 52]],
     ip_blockers: &[[bool; 52]],
     board: &[u8],
-    oop_cfr: &FlatCfr,
-    ip_cfr: &FlatCfr,
+    oop_cfr: &FlatCfr<F>,
+    ip_cfr: &FlatCfr<F>,
     strat_buf: &mut [f32],
     is_br: bool,
 ) -> f64 {
@@ -948,108 +1654,87 @@ fn br_traverse_turn(
             }
         }
         TreeNode::Chance {
-            cards, children, ..
+            cards, weights, children, ..
         } => {
-            let mut total_value = 0.0;
-            let mut valid_count = 0usize;
+            let (hero_blockers, opp_blockers) = match br_player {
+                Player::OOP => (&oop_blockers[hand_idx], ip_blockers),
+                Player::IP => (&ip_blockers[hand_idx], oop_blockers),
+            };
+            let opp_live = live_river_counts(cards, weights, hero_blockers, opp_blockers, opp_reach);
+            let buf_len = strat_buf.len();
+
+            // Nothing is written here, so the river subtrees fan out on the
+            // current rayon pool; values are summed in card order so the
+            // result doesn't depend on the thread count.
+            let values: Vec<f64> = cards
+                .par_iter()
+                .enumerate()
+                .filter(|&(_, &c)| !hero_blockers[c as usize])
+                .map(|(ci, &river_card)| {
+                    let mut strat_buf = vec![0.0f32; buf_len];
 
-            for (ci, &river_card) in cards.iter().enumerate() {
-                let blocked = match br_player {
-                    Player::OOP => oop_blockers[hand_idx][river_card as usize],
-                    Player::IP => ip_blockers[hand_idx][river_card as usize],
-                };
-                if blocked {
-                    continue;
-                }
-                valid_count += 1;
+                    let new_opp_reach =
+                        chance_opp_reach(opp_reach, opp_blockers, &opp_live, river_card, weights[ci]);
 
-                let new_opp_reach: Vec<f64> = match br_player {
-                    Player::OOP => opp_reach
+                    let river_board = [board[0], board[1], board[2], board[3], river_card];
+                    let oop_scores: Vec<u32> = oop_combos
                         .iter()
-                        .enumerate()
-                        .map(|(j, &r)| {
-                            if r > 0.0 && !ip_blockers[j][river_card as usize] {
-                                r
-                            } else {
-                                0.0
-                            }
-                        })
-                        .collect(),
-                    Player::IP => opp_reach
+                        .map(|c| evaluate_seven(&[c.0, c.1, river_board[0], river_board[1], river_board[2], river_board[3], river_board[4]]))
+                        .collect();
+                    let ip_scores: Vec<u32> = ip_combos
                         .iter()
-                        .enumerate()
-                        .map(|(i, &r)| {
-                            if r > 0.0 && !oop_blockers[i][river_card as usize] {
-                                r
-                            } else {
-                                0.0
-                            }
-                        })
-                        .collect(),
-                };
-
-                let river_board = [board[0], board[1], board[2], board[3], river_card];
-                let oop_scores: Vec<u32> = oop_combos
-                    .iter()
-                    .map(|c| evaluate_fast(&[c.0, c.1, river_board[0], river_board[1], river_board[2], river_board[3], river_board[4]]))
-                    .collect();
-                let ip_scores: Vec<u32> = ip_combos
-                    .iter()
-                    .map(|c| evaluate_fast(&[c.0, c.1, river_board[0], river_board[1], river_board[2], river_board[3], river_board[4]]))
-                    .collect();
+                        .map(|c| evaluate_seven(&[c.0, c.1, river_board[0], river_board[1], river_board[2], river_board[3], river_board[4]]))
+                        .collect();
 
-                let (valid_ip_h, valid_oop_h) = match br_player {
-                    Player::OOP => {
-                        let v: Vec<u16> = ip_combos
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, ip)| {
-                                let oop = &oop_combos[hand_idx];
-                                oop.0 != ip.0 && oop.0 != ip.1 && oop.1 != ip.0 && oop.1 != ip.1
-                                    && ip.0 != river_card && ip.1 != river_card
-                            })
-                            .map(|(j, _)| j as u16)
-                            .collect();
-                        (v, Vec::new())
-                    }
-                    Player::IP => {
-                        let v: Vec<u16> = oop_combos
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, oop)| {
-                                let ip = &ip_combos[hand_idx];
-                                ip.0 != oop.0 && ip.0 != oop.1 && ip.1 != oop.0 && ip.1 != oop.1
-                                    && oop.0 != river_card && oop.1 != river_card
-                            })
-                            .map(|(i, _)| i as u16)
-                            .collect();
-                        (Vec::new(), v)
-                    }
-                };
+                    let (valid_ip_h, valid_oop_h) = match br_player {
+                        Player::OOP => {
+                            let v: Vec<u16> = ip_combos
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, ip)| {
+                                    let oop = &oop_combos[hand_idx];
+                                    oop.0 != ip.0 && oop.0 != ip.1 && oop.1 != ip.0 && oop.1 != ip.1
+                                        && ip.0 != river_card && ip.1 != river_card
+                                })
+                                .map(|(j, _)| j as u16)
+                                .collect();
+                            (v, Vec::new())
+                        }
+                        Player::IP => {
+                            let v: Vec<u16> = oop_combos
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, oop)| {
+                                    let ip = &ip_combos[hand_idx];
+                                    ip.0 != oop.0 && ip.0 != oop.1 && ip.1 != oop.0 && ip.1 != oop.1
+                                        && oop.0 != river_card && oop.1 != river_card
+                                })
+                                .map(|(i, _)| i as u16)
+                                .collect();
+                            (Vec::new(), v)
+                        }
+                    };
 
-                total_value += br_traverse_river(
-                    &children[ci],
-                    br_player,
-                    hand_idx,
-                    &new_opp_reach,
-                    oop_combos,
-                    ip_combos,
-                    &oop_scores,
-                    &ip_scores,
-                    &valid_ip_h,
-                    &valid_oop_h,
-                    oop_cfr,
-                    ip_cfr,
-                    strat_buf,
-                    is_br,
-                );
-            }
+                    br_traverse_river(
+                        &children[ci],
+                        br_player,
+                        hand_idx,
+                        &new_opp_reach,
+                        oop_combos,
+                        ip_combos,
+                        &oop_scores,
+                        &ip_scores,
+                        &valid_ip_h,
+                        &valid_oop_h,
+                        oop_cfr,
+                        ip_cfr,
+                        &mut strat_buf,
+                        is_br,
+                    )
+                })
+                .collect();
 
-            if valid_count > 0 {
-                total_value / valid_count as f64
-            } else {
-                0.0
-            }
+            values.iter().sum()
         }
         TreeNode::Action {
             node_id,
@@ -1083,6 +1768,7 @@ fn br_traverse_turn(
                         Player::IP => ip_cfr,
                     };
                     cfr.average_strategy(nid, hand_idx, strat_buf);
+                    let strategy = strat_buf[..num_actions].to_vec();
                     let mut node_value = 0.0;
                     for a in 0..num_actions {
                         let v = br_traverse_turn(
@@ -1090,7 +1776,7 @@ fn br_traverse_turn(
                             oop_combos, ip_combos, oop_blockers, ip_blockers,
                             board, oop_cfr, ip_cfr, strat_buf, is_br,
                         );
-                        node_value += strat_buf[a] as f64 * v;
+                        node_value += strategy[a] as f64 * v;
                     }
                     node_value
                 }
@@ -1125,7 +1811,7 @@ fn br_traverse_turn(
 
 /// Best-response / avg-strategy traversal for river subtrees.
 #[allow(clippy::too_many_arguments)]
-fn br_traverse_river(
+fn br_traverse_river<F: CfrFloat>(
     node: &TreeNode,
     br_player: Player,
     hand_idx: usize,
@@ -1136,8 +1822,8 @@ fn br_traverse_river(
     ip_scores: &[u32],
     valid_ip_for_oop_h: &[u16],
     valid_oop_for_ip_h: &[u16],
-    oop_cfr: &FlatCfr,
-    ip_cfr: &FlatCfr,
+    oop_cfr: &FlatCfr<F>,
+    ip_cfr: &FlatCfr<F>,
     strat_buf: &mut [f32],
     is_br: bool,
 ) -> f64 {
@@ -1226,6 +1912,7 @@ fn br_traverse_river(
                         Player::IP => ip_cfr,
                     };
                     cfr.average_strategy(nid, hand_idx, strat_buf);
+                    let strategy = strat_buf[..num_actions].to_vec();
                     let mut node_value = 0.0;
                     for a in 0..num_actions {
                         let v = br_traverse_river(
@@ -1234,7 +1921,7 @@ fn br_traverse_river(
                             valid_ip_for_oop_h, valid_oop_for_ip_h,
                             oop_cfr, ip_cfr, strat_buf, is_br,
                         );
-                        node_value += strat_buf[a] as f64 * v;
+                        node_value += strategy[a] as f64 * v;
                     }
                     node_value
                 }
@@ -1272,14 +1959,14 @@ fn br_traverse_river(
 // Solution extraction
 // ---------------------------------------------------------------------------
 
-fn extract_solution(
+fn extract_solution<F: CfrFloat>(
     config: &TurnSolverConfig,
     tree: &TreeNode,
-    oop_cfr: &FlatCfr,
-    ip_cfr: &FlatCfr,
+    oop_cfr: &FlatCfr<F>,
+    ip_cfr: &FlatCfr<F>,
     oop_combos: &[Combo],
     ip_combos: &[Combo],
-    _metas: &[crate::postflop_tree::NodeMeta],
+    convergence: Vec<(usize, f64)>,
 ) -> TurnSolution {
     // Compute exploitability
     let oop_blockers: Vec<[bool; 52]> = oop_combos
@@ -1312,9 +1999,28 @@ fn extract_solution(
         &config.board,
     );
 
+    let combo_evs = |player| {
+        compute_combo_evs(
+            tree,
+            player,
+            oop_cfr,
+            ip_cfr,
+            oop_combos,
+            ip_combos,
+            &oop_blockers,
+            &ip_blockers,
+            &config.board,
+        )
+    };
+    let oop_combo_evs = combo_evs(Player::OOP);
+    let ip_combo_evs = combo_evs(Player::IP);
+
     // Extract turn-level strategies (first few action nodes before chance)
     let mut strategies = Vec::new();
-    extract_turn_strategies(tree, oop_cfr, ip_cfr, oop_combos, ip_combos, &mut strategies);
+    extract_turn_strategies(tree, oop_cfr, ip_cfr, oop_combos, ip_combos, [0.0; 2], &mut strategies);
+    let mut node_reach = BTreeMap::new();
+    let root_reach = [vec![1.0; oop_combos.len()], vec![1.0; ip_combos.len()]];
+    turn_node_reach(tree, oop_cfr, ip_cfr, root_reach, &mut node_reach);
 
     let board_str = config
         .board
@@ -1344,15 +2050,66 @@ fn extract_solution(
         strategies,
         oop_pos: String::new(),
         ip_pos: String::new(),
+        config_hash: config.cache_hash(),
+        turn_sizing: config.turn_sizing.clone(),
+        river_sizing: config.river_sizing.clone(),
+        allin_threshold: config.allin_threshold,
+        force_allin_above_stack_pct: config.force_allin_above_stack_pct,
+        can_raise: config.can_raise,
+        dead_money: config.dead_money,
+        oop_combo_evs,
+        ip_combo_evs,
+        algorithm: config.algorithm,
+        trainer_bytes: oop_cfr.memory_bytes() + ip_cfr.memory_bytes(),
+        tree: Some(tree.clone()),
+        convergence,
+        node_reach,
+    }
+}
+
+/// Record both players' reach at `node` and every turn action node below it,
+/// scaling the acting player's reach by its average strategy on the way
+/// down. Stops at chance nodes, like `extract_turn_strategies`.
+fn turn_node_reach<F: CfrFloat>(
+    node: &TreeNode,
+    oop_cfr: &FlatCfr<F>,
+    ip_cfr: &FlatCfr<F>,
+    reach: [Vec<f64>; 2],
+    node_reach: &mut BTreeMap<u32, [Vec<f64>; 2]>,
+) {
+    let TreeNode::Action { node_id, player, children, actions, .. } = node else {
+        return;
+    };
+    let cfr = match player {
+        Player::OOP => oop_cfr,
+        Player::IP => ip_cfr,
+    };
+    let pi = player.index();
+    let mut avg_buf = vec![0.0f32; actions.len()];
+    let frequencies: Vec<Vec<f32>> = (0..reach[pi].len())
+        .map(|h| {
+            cfr.average_strategy(*node_id as usize, h, &mut avg_buf);
+            avg_buf.clone()
+        })
+        .collect();
+
+    for (a, child) in children.iter().enumerate() {
+        let mut child_reach = reach.clone();
+        for (r, freqs) in child_reach[pi].iter_mut().zip(&frequencies) {
+            *r *= freqs[a] as f64;
+        }
+        turn_node_reach(child, oop_cfr, ip_cfr, child_reach, node_reach);
     }
+    node_reach.insert(*node_id, reach);
 }
 
-fn extract_turn_strategies(
+fn extract_turn_strategies<F: CfrFloat>(
     node: &TreeNode,
-    oop_cfr: &FlatCfr,
-    ip_cfr: &FlatCfr,
+    oop_cfr: &FlatCfr<F>,
+    ip_cfr: &FlatCfr<F>,
     oop_combos: &[Combo],
     ip_combos: &[Combo],
+    street_in: [f64; 2],
     strategies: &mut Vec<TurnNodeStrategy>,
 ) {
     match node {
@@ -1385,11 +2142,14 @@ fn extract_turn_strategies(
                     Player::IP => "IP".to_string(),
                 },
                 actions: actions.iter().map(|a| a.label()).collect(),
+                labels: node.pot_labels(street_in),
                 frequencies,
             });
 
-            for child in children {
-                extract_turn_strategies(child, oop_cfr, ip_cfr, oop_combos, ip_combos, strategies);
+            for (child, action) in children.iter().zip(actions) {
+                let mut child_in = street_in;
+                child_in[player.index()] += action.chips();
+                extract_turn_strategies(child, oop_cfr, ip_cfr, oop_combos, ip_combos, child_in, strategies);
             }
         }
         TreeNode::Chance { .. } => {
@@ -1419,6 +2179,20 @@ fn empty_solution(config: &TurnSolverConfig) -> TurnSolution {
         strategies: vec![],
         oop_pos: String::new(),
         ip_pos: String::new(),
+        config_hash: config.cache_hash(),
+        turn_sizing: config.turn_sizing.clone(),
+        river_sizing: config.river_sizing.clone(),
+        allin_threshold: config.allin_threshold,
+        force_allin_above_stack_pct: config.force_allin_above_stack_pct,
+        can_raise: config.can_raise,
+        dead_money: config.dead_money,
+        oop_combo_evs: vec![],
+        ip_combo_evs: vec![],
+        algorithm: config.algorithm,
+        trainer_bytes: 0,
+        tree: None,
+        convergence: Vec::new(),
+        node_reach: BTreeMap::new(),
     }
 }
 
@@ -1427,35 +2201,69 @@ fn empty_solution(config: &TurnSolverConfig) -> TurnSolution {
 // ---------------------------------------------------------------------------
 
 impl TurnSolution {
+    /// Fraction of `player`'s range that plays to `node_id`: the mean of
+    /// its combos' reach. `None` when the node has no recorded reach.
+    pub fn range_reach(&self, node_id: u32, player: Player) -> Option<f64> {
+        let reach = &self.node_reach.get(&node_id)?[player.index()];
+        (!reach.is_empty()).then(|| reach.iter().sum::<f64>() / reach.len() as f64)
+    }
+
     pub fn display(&self) {
+        print!("{}", self.render());
+    }
+
+    /// The human-readable report `display` prints.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = self.write_report(&mut out);
+        out
+    }
+
+    fn write_report(&self, out: &mut impl std::fmt::Write) -> std::fmt::Result {
         use colored::Colorize;
 
-        println!();
-        println!(
+        writeln!(out)?;
+        writeln!(
+            out,
             "  {} Turn Solution  |  Board: {}  |  Pot: {:.0}  |  Stack: {:.0}  |  {} iterations",
             "GTO".bold(),
             self.board,
             self.starting_pot,
             self.effective_stack,
             self.iterations,
-        );
-        println!("  Exploitability: {:.4}", self.exploitability);
-        println!(
+        )?;
+        write!(out, "  Exploitability: {:.4}", self.exploitability)?;
+        if self.algorithm != SolverAlgorithm::Cfr {
+            write!(out, "  |  {}", self.algorithm)?;
+        }
+        writeln!(out)?;
+        if let Some(line) = crate::display::convergence_summary(&self.convergence) {
+            writeln!(out, "  {}", line)?;
+        }
+        writeln!(
+            out,
+            "  Turn sizes: {}  |  River sizes: {}",
+            self.turn_sizing.describe(),
+            self.river_sizing.describe(),
+        )?;
+        writeln!(
+            out,
             "  OOP range: {} ({} combos)  |  IP range: {} ({} combos)",
             self.oop_range.join(","),
             self.oop_combos.len(),
             self.ip_range.join(","),
             self.ip_combos.len(),
-        );
+        )?;
 
         if let Some(root_strat) = self.strategies.first() {
-            println!();
-            println!(
+            writeln!(out)?;
+            writeln!(
+                out,
                 "  {} at root (node {}):",
                 root_strat.player.bold(),
                 root_strat.node_id
-            );
-            println!("  Actions: {}", root_strat.actions.join(" | "));
+            )?;
+            writeln!(out, "  Actions: {}", root_strat.display_labels().join(" | "))?;
 
             let num_to_show = root_strat.frequencies.len().min(20);
             let combos = if root_strat.player == "OOP" {
@@ -1467,7 +2275,7 @@ impl TurnSolution {
             for i in 0..num_to_show {
                 let freq_str: String = root_strat.frequencies[i]
                     .iter()
-                    .zip(&root_strat.actions)
+                    .zip(root_strat.display_labels())
                     .map(|(f, a)| {
                         let pct = (f * 100.0).round() as u32;
                         if pct > 70 {
@@ -1480,18 +2288,66 @@ impl TurnSolution {
                     })
                     .collect::<Vec<_>>()
                     .join("  ");
-                println!("    {}  {}", combos[i].bold(), freq_str);
+                writeln!(out, "    {}  {}", combos[i].bold(), freq_str)?;
             }
             if root_strat.frequencies.len() > num_to_show {
-                println!(
+                writeln!(
+                    out,
                     "    ... and {} more combos",
                     root_strat.frequencies.len() - num_to_show
-                );
+                )?;
             }
         }
 
-        println!();
+        write_combo_evs(out, "OOP", &self.oop_combos, &self.oop_combo_evs)?;
+        write_combo_evs(out, "IP", &self.ip_combos, &self.ip_combo_evs)?;
+
+        writeln!(out)
+    }
+}
+
+/// Write the top and bottom 10 combos by EV for one player.
+pub(crate) fn write_combo_evs(
+    out: &mut impl std::fmt::Write,
+    player: &str,
+    combos: &[String],
+    evs: &[Option<f64>],
+) -> std::fmt::Result {
+    use colored::Colorize;
+
+    let mut ranked: Vec<(&str, f64)> = combos
+        .iter()
+        .zip(evs)
+        .filter_map(|(c, ev)| ev.map(|v| (c.as_str(), v)))
+        .collect();
+    if ranked.is_empty() {
+        return Ok(());
+    }
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    let fmt = |entries: &[(&str, f64)]| {
+        entries
+            .iter()
+            .map(|(c, v)| format!("{} {:.2}", c, v))
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+
+    writeln!(out)?;
+    writeln!(out, "  {} combo EVs:", player.bold())?;
+    let n = ranked.len().min(10);
+    writeln!(out, "    Top:    {}", fmt(&ranked[..n]))?;
+    if ranked.len() > n {
+        let bottom_n = (ranked.len() - n).min(10);
+        let bottom: Vec<(&str, f64)> =
+            ranked[ranked.len() - bottom_n..].iter().rev().copied().collect();
+        writeln!(out, "    Bottom: {}", fmt(&bottom))?;
     }
+    let blocked = evs.iter().filter(|ev| ev.is_none()).count();
+    if blocked > 0 {
+        writeln!(out, "    {} combos fully blocked (no EV)", blocked)?;
+    }
+    Ok(())
 }
 
 // ---------------------------------------------------------------------------
@@ -1500,13 +2356,14 @@ impl TurnSolution {
 
 impl TurnSolution {
     pub fn cache_path(&self) -> std::path::PathBuf {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let dir = std::path::Path::new(&home).join(".gto-cli").join("solver");
-        std::fs::create_dir_all(&dir).ok();
-        dir.join(format!(
-            "turn_{}_{}_{}_{:.0}_{:.0}.bin",
-            self.board, self.oop_pos, self.ip_pos, self.starting_pot, self.effective_stack,
-        ))
+        cache_file(
+            &self.board,
+            &self.oop_pos,
+            &self.ip_pos,
+            self.starting_pot,
+            self.effective_stack,
+            self.config_hash,
+        )
     }
 
     pub fn save_cache(&self) {
@@ -1516,13 +2373,21 @@ impl TurnSolution {
         }
     }
 
-    pub fn load_cache(board: &str, oop_pos: &str, ip_pos: &str, pot: f64, stack: f64) -> Option<TurnSolution> {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let path = std::path::Path::new(&home)
-            .join(".gto-cli")
-            .join("solver")
-            .join(format!("turn_{}_{}_{}_{:.0}_{:.0}.bin", board, oop_pos, ip_pos, pot, stack));
+    /// The cached solution of exactly `config` (ranges, sizing, iterations
+    /// and all) at these positions, if one was saved.
+    pub fn load_cache(config: &TurnSolverConfig, oop_pos: &str, ip_pos: &str) -> Option<TurnSolution> {
+        let board: String = config.board.iter().map(|&c| index_to_card(c).to_string()).collect();
+        let hash = config.cache_hash();
+        let path = cache_file(&board, oop_pos, ip_pos, config.starting_pot, config.effective_stack, hash);
         let data = std::fs::read(path).ok()?;
-        bincode::deserialize(&data).ok()
+        let solution: TurnSolution = bincode::deserialize(&data).ok()?;
+        (solution.config_hash == hash).then_some(solution)
     }
 }
+
+fn cache_file(board: &str, oop_pos: &str, ip_pos: &str, pot: f64, stack: f64, hash: u64) -> PathBuf {
+    checkpoint::solver_dir().join(format!(
+        "turn_{}_{}_{}_{:.0}_{:.0}_{:016x}.bin",
+        board, oop_pos, ip_pos, pot, stack, hash
+    ))
+}
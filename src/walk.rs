@@ -0,0 +1,215 @@
+//! Step through a solved river tree one action at a time.
+//!
+//! A [`Walk`] holds a solution with its game tree and the line taken from the
+//! root. Input lines pick an action by number or name, or move back; the
+//! `walk` command reads them from stdin.
+
+use crate::postflop_tree::{TerminalType, TreeNode};
+use crate::river_solver::{NodeStrategy, RiverSolution};
+
+/// What one line of input asks the walk to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalkCommand {
+    /// Take the action at this index of the current node.
+    Take(usize),
+    /// Undo the last action.
+    Back,
+    /// Return to the root.
+    Root,
+    Quit,
+}
+
+/// A position in a solved tree and the actions that led there.
+pub struct Walk {
+    solution: RiverSolution,
+    tree: TreeNode,
+    /// Child index taken at each step from the root.
+    path: Vec<usize>,
+}
+
+impl Walk {
+    /// Start at the root of `solution`'s stored tree.
+    pub fn new(mut solution: RiverSolution) -> Result<Self, String> {
+        let tree = solution.tree.take().ok_or_else(|| {
+            "This solution has no stored tree (an empty spot or an older solution); solve it again to walk it"
+                .to_string()
+        })?;
+        Ok(Walk { solution, tree, path: Vec::new() })
+    }
+
+    pub fn solution(&self) -> &RiverSolution {
+        &self.solution
+    }
+
+    /// The node the walk is at.
+    pub fn node(&self) -> &TreeNode {
+        self.path.iter().fold(&self.tree, |node, &i| match node {
+            TreeNode::Action { children, .. } | TreeNode::Chance { children, .. } => &children[i],
+            TreeNode::Terminal { .. } => node,
+        })
+    }
+
+    /// Id of the current node, if a player acts there.
+    pub fn node_id(&self) -> Option<u32> {
+        match self.node() {
+            TreeNode::Action { node_id, .. } => Some(*node_id),
+            _ => None,
+        }
+    }
+
+    /// The solved strategy at the current node, if a player acts there.
+    pub fn strategy(&self) -> Option<&NodeStrategy> {
+        let node_id = self.node_id()?;
+        self.solution.strategies.iter().find(|s| s.node_id == node_id)
+    }
+
+    /// Labels of the actions available here: the solution's pot-relative
+    /// ones when recorded, else the tree's. Empty at a terminal.
+    pub fn actions(&self) -> Vec<String> {
+        match self.node() {
+            TreeNode::Action { actions, .. } => match self.strategy() {
+                Some(strategy) if strategy.display_labels().len() == actions.len() => {
+                    strategy.display_labels().to_vec()
+                }
+                _ => actions.iter().map(|a| a.label()).collect(),
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// The actions taken from the root, as labels.
+    pub fn line(&self) -> Vec<String> {
+        let mut node = &self.tree;
+        let mut line = Vec::with_capacity(self.path.len());
+        for &i in &self.path {
+            if let TreeNode::Action { actions, children, .. } = node {
+                line.push(actions[i].label());
+                node = &children[i];
+            }
+        }
+        line
+    }
+
+    /// Range-wide frequency of each action here, weighted by how often each
+    /// combo reaches the node.
+    pub fn aggregate(&self) -> Option<Vec<(String, f64)>> {
+        let frequencies = self.solution.aggregate_node(self.node_id()?)?;
+        Some(self.actions().into_iter().zip(frequencies.into_iter().map(|(_, f)| f)).collect())
+    }
+
+    /// Every combo of the player to act that reaches this node, with its
+    /// reach probability and action frequencies.
+    pub fn combo_frequencies(&self) -> Vec<(&str, f64, &[f64])> {
+        let Some(strategy) = self.strategy() else {
+            return Vec::new();
+        };
+        let combos = if strategy.player == "OOP" {
+            &self.solution.oop_combos
+        } else {
+            &self.solution.ip_combos
+        };
+        combos
+            .iter()
+            .zip(&strategy.frequencies)
+            .enumerate()
+            .map(|(h, (combo, freqs))| (combo.as_str(), strategy.reach.get(h).copied().unwrap_or(1.0), freqs.as_slice()))
+            .filter(|&(_, reach, _)| reach > 0.0)
+            .collect()
+    }
+
+    /// How the hand ended, at a terminal.
+    pub fn outcome(&self) -> Option<String> {
+        match self.node() {
+            TreeNode::Terminal { terminal_type: TerminalType::Showdown, pot, .. } => {
+                Some(format!("Showdown for a pot of {:.1}", pot))
+            }
+            TreeNode::Terminal { terminal_type: TerminalType::Fold { folder }, pot, .. } => {
+                Some(format!("{:?} folds; {:?} wins {:.1}", folder, folder.opponent(), pot))
+            }
+            _ => None,
+        }
+    }
+
+    /// Read one line of input: an action's number (from 1) or name
+    /// (case-insensitive, any unambiguous prefix), `back`, `root` or `quit`.
+    pub fn parse(&self, input: &str) -> Result<WalkCommand, String> {
+        let input = input.trim().to_lowercase();
+        match input.as_str() {
+            "b" | "back" | "u" | "undo" => return Ok(WalkCommand::Back),
+            "r" | "root" => return Ok(WalkCommand::Root),
+            "q" | "quit" | "exit" => return Ok(WalkCommand::Quit),
+            "" => return Err("Enter an action, back, root or quit".to_string()),
+            _ => {}
+        }
+
+        let actions = self.actions();
+        if actions.is_empty() {
+            return Err("The hand is over here; go back or return to the root".to_string());
+        }
+        if let Ok(n) = input.parse::<usize>() {
+            return match n {
+                1.. if n <= actions.len() => Ok(WalkCommand::Take(n - 1)),
+                _ => Err(format!("Pick an action from 1 to {}", actions.len())),
+            };
+        }
+
+        // The plain tree labels ("Bet 6.7") match as well as the shown ones
+        let plain: Vec<String> = match self.node() {
+            TreeNode::Action { actions, .. } => actions.iter().map(|a| a.label()).collect(),
+            _ => Vec::new(),
+        };
+        let names = |i: usize| [actions[i].to_lowercase(), plain.get(i).map(|p| p.to_lowercase()).unwrap_or_default()];
+        if let Some(i) = (0..actions.len()).find(|&i| names(i).contains(&input)) {
+            return Ok(WalkCommand::Take(i));
+        }
+        let matches: Vec<usize> =
+            (0..actions.len()).filter(|&i| names(i).iter().any(|n| n.starts_with(&input))).collect();
+        match matches.as_slice() {
+            [i] => Ok(WalkCommand::Take(*i)),
+            [] => Err(format!("No action matches \"{}\" (choose from: {})", input, actions.join(", "))),
+            _ => Err(format!(
+                "\"{}\" could be {}",
+                input,
+                matches.iter().map(|&i| actions[i].as_str()).collect::<Vec<_>>().join(" or ")
+            )),
+        }
+    }
+
+    /// Take the action at `index` of the current node.
+    pub fn take(&mut self, index: usize) -> Result<(), String> {
+        match self.node() {
+            TreeNode::Action { children, .. } if index < children.len() => {
+                self.path.push(index);
+                Ok(())
+            }
+            TreeNode::Action { children, .. } => {
+                Err(format!("Action {} is out of range (1-{})", index + 1, children.len()))
+            }
+            _ => Err("No player acts here".to_string()),
+        }
+    }
+
+    /// Undo the last action; false at the root.
+    pub fn back(&mut self) -> bool {
+        self.path.pop().is_some()
+    }
+
+    pub fn root(&mut self) {
+        self.path.clear();
+    }
+
+    /// Apply one line of input. Returns false once asked to quit.
+    pub fn step(&mut self, input: &str) -> Result<bool, String> {
+        match self.parse(input)? {
+            WalkCommand::Take(i) => self.take(i)?,
+            WalkCommand::Back => {
+                if !self.back() {
+                    return Err("Already at the root".to_string());
+                }
+            }
+            WalkCommand::Root => self.root(),
+            WalkCommand::Quit => return Ok(false),
+        }
+        Ok(true)
+    }
+}
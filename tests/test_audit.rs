@@ -473,6 +473,7 @@ fn audit_equity_vs_top_5pct_range() {
         &range,
         None,
         10000,
+        true,
     ).unwrap();
     let eq = result.equity();
     // AA dominates most of top 5% except mirror
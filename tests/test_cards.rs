@@ -214,3 +214,9 @@ fn test_hand_combos_specific() {
     assert_eq!(combos[0].0, Card::new(Rank::Ace, Suit::Spades));
     assert_eq!(combos[0].1, Card::new(Rank::King, Suit::Hearts));
 }
+
+#[test]
+fn test_hand_combos_specific_same_card() {
+    assert!(hand_combos("AhAh").is_err());
+    assert_eq!(hand_combos("7s7d").unwrap().len(), 1);
+}
@@ -78,6 +78,7 @@ fn test_aa_vs_premium_range() {
         &["KK".to_string(), "QQ".to_string(), "JJ".to_string()],
         None,
         5000,
+        true,
     )
     .unwrap();
     assert!(result.equity() > 0.70);
@@ -90,6 +91,468 @@ fn test_no_valid_combos() {
         &["AsAh".to_string()], // exact combo blocked
         None,
         100,
+        true,
     );
     assert!(result.is_err());
 }
+
+#[test]
+fn test_weighted_range_counts_blocked_combos() {
+    // Hero AsAh leaves one AA combo against six KK combos. Weighted, KK
+    // dominates (~77%); counting AA and KK equally drags it toward ~66%.
+    let range = ["AA".to_string(), "KK".to_string()];
+    let weighted = equity_vs_range(&[c("As"), c("Ah")], &range, None, 28000, true).unwrap();
+    let unweighted = equity_vs_range(&[c("As"), c("Ah")], &range, None, 28000, false).unwrap();
+    assert!(
+        weighted.equity() > 0.73 && weighted.equity() < 0.81,
+        "weighted AA vs AA,KK should be ~77%, got {:.1}%",
+        weighted.equity() * 100.0
+    );
+    assert!(
+        weighted.equity() - unweighted.equity() > 0.05,
+        "blockers should shift equity: weighted {:.3}, unweighted {:.3}",
+        weighted.equity(),
+        unweighted.equity()
+    );
+}
+
+#[test]
+fn test_weighted_range_dedups_overlapping_entries() {
+    // KsKh listed on its own and inside KK counts once when weighted
+    let hero = [c("As"), c("Ah")];
+    let board = [c("Kd"), c("2c"), c("7h")];
+    let overlap = ["KsKh".to_string(), "KK".to_string(), "QQ".to_string()];
+    let result = equity_vs_range(&hero, &overlap, Some(&board), 9000, true).unwrap();
+    assert_eq!(result.simulations, 1000 * 9);
+}
+
+#[test]
+fn test_nuts_range_in_top_bucket() {
+    // Every Ts combo makes a royal flush on this board
+    let board = [c("As"), c("Ks"), c("Qs"), c("Js"), c("2d")];
+    let hero = ["Ts9h".to_string(), "Ts8d".to_string(), "TsTh".to_string()];
+    let villain = ["AA".to_string(), "KK".to_string(), "QJs".to_string()];
+    let dist = equity_distribution(&hero, &villain, Some(&board), 300, 10).unwrap();
+
+    assert_eq!(dist.len(), 10);
+    assert!((dist[9].0 - 0.95).abs() < 1e-9);
+    assert!((dist[9].1 - 1.0).abs() < 1e-9, "all combos should be in the top bucket: {:?}", dist);
+}
+
+#[test]
+fn test_equity_distribution_sums_to_one() {
+    let hero = ["AA".to_string(), "72o".to_string(), "JTs".to_string()];
+    let villain = ["KK".to_string(), "QQ".to_string()];
+    let dist = equity_distribution(&hero, &villain, None, 3000, 5).unwrap();
+    let total: f64 = dist.iter().map(|&(_, frac)| frac).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+    // AA sits near 80%, 72o near 13%: the range is spread out, not one bucket
+    assert!(dist.iter().filter(|&&(_, frac)| frac > 0.0).count() >= 2);
+    assert!(equity_distribution(&hero, &villain, None, 100, 0).is_err());
+}
+
+#[test]
+fn test_runout_distribution_mean_matches_exact_equity() {
+    // Against one hand, each river is a win, tie or loss
+    let hero = [c("Ah"), c("Kh")];
+    let board = [c("Qh"), c("7h"), c("2c"), c("8d")];
+    let dist = runout_equity_distribution(&hero, &["KsKd".to_string()], &board, 1000).unwrap();
+    let exact = equity_vs_hand(&hero, &[c("Ks"), c("Kd")], Some(&board), 1000).unwrap();
+
+    assert!(dist.exact);
+    assert_eq!(dist.equities.len(), 44);
+    assert!(dist.equities.iter().all(|&e| e == 0.0 || e == 0.5 || e == 1.0));
+    assert!((dist.mean - exact.equity()).abs() < 1e-9, "{} vs {}", dist.mean, exact.equity());
+    assert_eq!((dist.min, dist.max), (0.0, 1.0));
+}
+
+#[test]
+fn test_runout_distribution_percentiles_and_histogram() {
+    let hero = [c("Ah"), c("Kh")];
+    let board = [c("Qh"), c("7h"), c("2c")];
+    let villain = vec!["QQ".to_string(), "JJ".to_string(), "AQs".to_string()];
+    let dist = runout_equity_distribution(&hero, &villain, &board, 5000).unwrap();
+
+    assert!(dist.exact);
+    assert_eq!(dist.equities.len(), 47 * 46 / 2);
+    assert!(dist.equities.windows(2).all(|w| w[0] <= w[1]));
+    assert!(dist.min <= dist.p25 && dist.p25 <= dist.median);
+    assert!(dist.median <= dist.p75 && dist.p75 <= dist.max);
+    // A flush draw against made hands: mostly drawing dead or way ahead
+    assert_eq!(dist.min, 0.0);
+    assert_eq!(dist.max, 1.0);
+
+    let histogram = dist.histogram(10);
+    assert_eq!(histogram.len(), 10);
+    assert!((histogram.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    assert!(histogram[0] > 0.3 && histogram[9] > 0.1, "{:?}", histogram);
+
+    let sampled = runout_equity_distribution(&hero, &villain, &board, 200).unwrap();
+    assert!(!sampled.exact);
+    assert_eq!(sampled.equities.len(), 200);
+}
+
+#[test]
+fn test_runout_distribution_rejects_bad_spots() {
+    let hero = [c("Ah"), c("Kh")];
+    let villain = vec!["QQ".to_string()];
+    let flop = [c("Qh"), c("7h"), c("2c")];
+    let river = [c("Qh"), c("7h"), c("2c"), c("8d"), c("3s")];
+    assert!(runout_equity_distribution(&hero, &villain, &[], 100).is_err());
+    assert!(runout_equity_distribution(&hero, &villain, &river, 100).is_err());
+    assert!(runout_equity_distribution(&hero, &villain, &flop, 0).is_err());
+    assert!(runout_equity_distribution(&[c("Qh"), c("Kh")], &villain, &flop, 100).is_err());
+    assert!(matches!(
+        runout_equity_distribution(&hero, &["AhAs".to_string()], &flop, 100),
+        Err(gto_cli::error::GtoError::NoValidCombos)
+    ));
+}
+
+#[test]
+fn test_equity_threads_match() {
+    // AA vs KK,QQ is ~81%; serial and 4-thread runs agree within noise
+    let range = ["KK".to_string(), "QQ".to_string()];
+    let serial = equity_vs_range_threads(&[c("As"), c("Ah")], &range, None, 12000, true, 1).unwrap();
+    let parallel = equity_vs_range_threads(&[c("As"), c("Ah")], &range, None, 12000, true, 4).unwrap();
+    assert!(
+        serial.equity() > 0.77 && serial.equity() < 0.86,
+        "1-thread AA vs KK,QQ should be ~81%, got {:.1}%",
+        serial.equity() * 100.0
+    );
+    assert!(
+        (serial.equity() - parallel.equity()).abs() < 0.03,
+        "1 thread {:.3} vs 4 threads {:.3}",
+        serial.equity(),
+        parallel.equity()
+    );
+
+    let hand = equity_vs_hand_threads(&[c("As"), c("Ah")], &[c("Ks"), c("Kh")], None, 5000, 1).unwrap();
+    assert!(hand.equity() > 0.75 && hand.equity() < 0.88);
+}
+
+#[test]
+fn test_equity_progress_ends_on_completion() {
+    let calls = std::sync::Mutex::new(Vec::new());
+    let record = |current: usize, total: usize| calls.lock().unwrap().push((current, total));
+
+    equity_vs_hand_with_progress(&[c("As"), c("Ah")], &[c("Ks"), c("Kh")], None, 5000, Some(&record))
+        .unwrap();
+    let hand_calls = std::mem::take(&mut *calls.lock().unwrap());
+    assert_eq!(hand_calls.len(), 50);
+    assert_eq!(hand_calls.last(), Some(&(5000, 5000)));
+
+    let range = ["KK".to_string(), "QQ".to_string()];
+    equity_vs_range_with_progress(&[c("As"), c("Ah")], &range, None, 1200, true, Some(&record))
+        .unwrap();
+    let range_calls = calls.into_inner().unwrap();
+    assert!(range_calls.len() <= 12);
+    assert_eq!(range_calls.last(), Some(&(12, 12)));
+}
+
+// -------------------------------------------------------------------------
+// Omaha
+// -------------------------------------------------------------------------
+
+fn cards(notation: &str) -> Vec<Card> {
+    parse_board(notation).unwrap()
+}
+
+#[test]
+fn test_omaha_single_suited_card_cannot_use_board_flush() {
+    // Villain's lone spade can't play the four-spade board, so hero's
+    // aces stay ahead on most rivers
+    let board = cards("Ks9s5s2s");
+    let result = equity_vs_hand_omaha(
+        &cards("AhAdQc3c"),
+        &cards("AsJh8d7h"),
+        Some(&board),
+        4000,
+    )
+    .unwrap();
+    assert!(result.equity() > 0.5, "{}", result);
+
+    // On a complete board: villain's single spade still makes no flush
+    let board = cards("Ks9s5s2s4s");
+    let result =
+        equity_vs_hand_omaha(&cards("AhAdQc3c"), &cards("AsJh8d7h"), Some(&board), 10).unwrap();
+    assert_eq!(result.win, 1.0);
+}
+
+#[test]
+fn test_omaha_range_skips_blocked_combos_and_rejects_bad_entries() {
+    let board = cards("KsKhKd7c2s");
+    let hero = cards("AhAd9c8c");
+    let range = vec!["QsQdJcTc".to_string(), "AhKc3d4d".to_string()];
+    let result = equity_vs_range_omaha(&hero, &range, Some(&board), 200).unwrap();
+    // The AhKc combo is blocked by hero's Ah, leaving only QQ (which hero beats)
+    assert_eq!(result.simulations, 200);
+    assert!(result.equity() > 0.9, "{}", result);
+
+    let blocked = vec!["AhKc3d4d".to_string()];
+    assert!(equity_vs_range_omaha(&hero, &blocked, Some(&board), 200).is_err());
+    let bad = vec!["AhKc3d".to_string()];
+    assert!(equity_vs_range_omaha(&hero, &bad, Some(&board), 200).is_err());
+}
+
+#[test]
+fn test_omaha_rejects_holdem_sized_hands() {
+    assert!(equity_vs_hand_omaha(&cards("AhAd"), &cards("KsKdQsQd"), None, 10).is_err());
+}
+
+// -------------------------------------------------------------------------
+// Short deck
+// -------------------------------------------------------------------------
+
+#[test]
+fn test_short_deck_rejects_removed_ranks() {
+    use gto_cli::error::GtoError;
+
+    // 22 doesn't exist in a 36-card deck
+    let options = EquityOptions::default();
+    let err = match equity_vs_range_short_deck(&cards("AsKs"), &["22".to_string()], None, 100, true, options) {
+        Err(e) => e,
+        Ok(_) => panic!("22 should be rejected"),
+    };
+    assert!(
+        matches!(err, GtoError::NotInShortDeck(ref h) if h == "22"),
+        "{}",
+        err
+    );
+    assert!(err.to_string().contains("short deck"));
+
+    assert!(equity_vs_hand_short_deck(&cards("As5s"), &cards("KhKd"), None, 100, options).is_err());
+    let board = cards("9s8s2c");
+    assert!(equity_vs_hand_short_deck(&cards("AsKs"), &cards("QhQd"), Some(&board), 100, options).is_err());
+}
+
+#[test]
+fn test_short_deck_equity_on_complete_board() {
+    // Hero's flush beats villain's full house only under short-deck rules
+    let board = cards("Js9s7hTsTh");
+    let hero = cards("As6s");
+    let villain = cards("JdJc");
+    let holdem = equity_vs_hand(&hero, &villain, Some(&board), 10).unwrap();
+    let short =
+        equity_vs_hand_short_deck(&hero, &villain, Some(&board), 10, EquityOptions::default()).unwrap();
+    assert_eq!(holdem.lose, 1.0);
+    assert_eq!(short.win, 1.0);
+}
+
+#[test]
+fn test_short_deck_honors_equity_options() {
+    use gto_cli::hand_evaluator::HandCategory;
+
+    let board = cards("Js9s7hTsTh");
+    let options = EquityOptions { breakdown: true, sampling: Sampling::Exact, ..Default::default() };
+    let result = equity_vs_hand_short_deck(&cards("As6s"), &cards("JdJc"), Some(&board), 10, options).unwrap();
+    assert!(result.exact);
+    let breakdown = result.category_breakdown.as_ref().unwrap();
+    assert_eq!(breakdown.wins[HandCategory::Flush as usize], 1.0);
+
+    let range = vec!["QQ".to_string(), "A6s".to_string()];
+    let options = EquityOptions { per_combo: true, ..Default::default() };
+    let result =
+        equity_vs_range_short_deck(&cards("KsKd"), &range, Some(&board), 1000, true, options).unwrap();
+    assert!(result.villain_combos.is_some_and(|combos| !combos.is_empty()));
+}
+
+// -------------------------------------------------------------------------
+// Category breakdown
+// -------------------------------------------------------------------------
+
+#[test]
+fn test_breakdown_only_when_asked() {
+    let result = equity_vs_hand(&cards("AsAh"), &cards("KsKh"), None, 100).unwrap();
+    assert!(result.category_breakdown.is_none());
+    assert!(!result.to_string().contains("Lose to"));
+}
+
+#[test]
+fn test_breakdown_on_complete_board() {
+    use gto_cli::hand_evaluator::HandCategory;
+
+    // Hero's flush beats villain's set every time
+    let board = cards("Js9s7h2s3d");
+    let options = EquityOptions { breakdown: true, ..Default::default() };
+    let result = equity_vs_hand_with_options(&cards("AsKs"), &cards("JdJc"), Some(&board), 50, options).unwrap();
+    let breakdown = result.category_breakdown.as_ref().unwrap();
+    assert_eq!(breakdown.wins[HandCategory::Flush as usize], 1.0);
+    assert_eq!(breakdown.wins.iter().sum::<f64>(), result.win);
+    assert!(result.to_string().contains("Flush"));
+}
+
+#[test]
+fn test_range_breakdown_sums_to_outcomes() {
+    let board = cards("Kh8d3c");
+    let range = vec!["AA".to_string(), "88".to_string(), "AQs".to_string()];
+    let options = EquityOptions { breakdown: true, ..Default::default() };
+    let result = equity_vs_range_with_options(&cards("KsQs"), &range, Some(&board), 3000, true, options).unwrap();
+    let breakdown = result.category_breakdown.unwrap();
+    assert!((breakdown.wins.iter().sum::<f64>() - result.win).abs() < 1e-9);
+    assert!((breakdown.ties.iter().sum::<f64>() - result.tie).abs() < 1e-9);
+    assert!((breakdown.losses.iter().sum::<f64>() - result.lose).abs() < 1e-9);
+}
+
+// -------------------------------------------------------------------------
+// Range vs range
+// -------------------------------------------------------------------------
+
+fn range(hands: &[&str]) -> Vec<String> {
+    hands.iter().map(|h| h.to_string()).collect()
+}
+
+#[test]
+fn test_range_vs_range_river_is_exact_and_weighted_by_live_combos() {
+    let board = cards("2c7d9h3sQd");
+    let result = equity_range_vs_range(&range(&["AA", "KK"]), &range(&["AA"]), Some(&board), 0).unwrap();
+    assert!(result.exact);
+    assert_eq!(result.combos.len(), 12);
+
+    // Each AA combo leaves one villain AA to chop with; each KK faces all six
+    for combo in &result.combos {
+        let aces = combo.combo.0.rank == Rank::Ace;
+        assert_eq!(combo.villain_combos, if aces { 1 } else { 6 });
+        assert_eq!(combo.equity, if aces { 0.5 } else { 0.0 });
+    }
+    assert!((result.equity - 3.0 / 42.0).abs() < 1e-12);
+
+    let sorted = result.sorted_by_equity();
+    assert_eq!(sorted[0].combo.0.rank, Rank::Ace);
+    assert_eq!(sorted[11].combo.0.rank, Rank::King);
+}
+
+#[test]
+fn test_range_vs_range_preflop_samples() {
+    let result = equity_range_vs_range(&range(&["AA"]), &range(&["KK"]), None, 6000).unwrap();
+    assert!(!result.exact);
+    assert_eq!(result.combos.len(), 6);
+    assert!(result.equity > 0.75 && result.equity < 0.88, "{}", result.equity);
+}
+
+#[test]
+fn test_range_vs_range_no_live_combos() {
+    let board = cards("AsAhAdAc2c");
+    assert!(equity_range_vs_range(&range(&["AA"]), &range(&["KK"]), Some(&board), 100).is_err());
+}
+
+// -------------------------------------------------------------------------
+// Exact enumeration
+// -------------------------------------------------------------------------
+
+#[test]
+fn test_small_spots_enumerate_automatically() {
+    // 44 river cards on the turn
+    let board = cards("Kh8d3c2s");
+    let result = equity_vs_hand(&cards("AsAh"), &cards("KdKc"), Some(&board), 30000).unwrap();
+    assert!(result.exact);
+    assert_eq!(result.simulations, 44);
+    // Kings already hold a set; only the two remaining aces get there: 2/44
+    assert!((result.win - 2.0 / 44.0).abs() < 1e-12);
+
+    // 990 flop runouts for each of 6 AA combos is past the automatic limit
+    let range = vec!["AA".to_string(), "QQ".to_string()];
+    let sampled = equity_vs_range(&cards("KsKd"), &range, Some(&board[..3]), 6000, true).unwrap();
+    assert!(!sampled.exact);
+    let turn = equity_vs_range(&cards("KsKd"), &range, Some(&board), 6000, true).unwrap();
+    assert!(turn.exact);
+    assert_eq!(turn.simulations, 44 * 12);
+}
+
+#[test]
+fn test_sampling_can_be_forced() {
+    let board = cards("Kh8d3c2s");
+    let mc = EquityOptions { sampling: Sampling::MonteCarlo, ..Default::default() };
+    let result = equity_vs_hand_with_options(&cards("AsAh"), &cards("KdKc"), Some(&board), 500, mc).unwrap();
+    assert!(!result.exact);
+    assert_eq!(result.simulations, 500);
+
+    // Hand vs range preflop is far past the enumeration limit
+    let exact = EquityOptions { sampling: Sampling::Exact, ..Default::default() };
+    let range = vec!["KK".to_string(), "QQ".to_string(), "JJ".to_string()];
+    let too_big = equity_vs_range_with_options(&cards("AsAh"), &range, None, 500, true, exact);
+    assert!(too_big.is_err());
+}
+
+#[test]
+fn test_exact_preflop_aa_vs_kk() {
+    let exact = EquityOptions { sampling: Sampling::Exact, ..Default::default() };
+    let result = equity_vs_hand_with_options(&cards("AsAh"), &cards("KdKc"), None, 0, exact).unwrap();
+    assert!(result.exact);
+    assert_eq!(result.simulations, 1_712_304);
+    // Disjoint suits: 81.07% / 0.38% / 18.55%
+    assert!((result.win - 0.8107).abs() < 5e-4);
+    assert!((result.tie - 0.0038).abs() < 5e-4);
+    assert!((result.lose - 0.1855).abs() < 5e-4);
+}
+
+#[test]
+fn test_adaptive_river_ci_covers_exact() {
+    let board = cards("AhKd8c5s2h");
+    let range: Vec<String> = ["AA", "KK", "QQ", "JJ", "AKs", "AKo", "KQs", "T9s"].iter().map(|s| s.to_string()).collect();
+    let exact = EquityOptions { sampling: Sampling::Exact, ..Default::default() };
+    let truth = equity_vs_range_with_options(&cards("QdQh"), &range, Some(&board), 0, true, exact).unwrap();
+    assert_eq!(truth.stderr, 0.0);
+
+    let adaptive = EquityOptions {
+        sampling: Sampling::MonteCarlo,
+        precision: Some(Precision { half_width: 0.25, max_simulations: 2_000_000 }),
+        ..Default::default()
+    };
+    let result = equity_vs_range_with_options(&cards("QdQh"), &range, Some(&board), 0, true, adaptive).unwrap();
+    assert!(!result.exact);
+    assert!(result.simulations < 2_000_000, "ran to the cap: {}", result.simulations);
+    assert!(1.96 * result.stderr * 100.0 <= 0.25, "stderr {}", result.stderr);
+    let (low, high) = result.ci95();
+    assert!(low <= truth.equity() && truth.equity() <= high, "{:?} vs {}", (low, high), truth.equity());
+}
+
+#[test]
+fn test_adaptive_stops_within_tolerance_of_exact() {
+    let board = cards("Kh8d3c");
+    let truth = equity_vs_hand(&cards("AsAh"), &cards("QdJd"), Some(&board), 0).unwrap();
+    assert!(truth.exact);
+
+    let adaptive = EquityOptions {
+        sampling: Sampling::MonteCarlo,
+        precision: Some(Precision::new(1.0)),
+        ..Default::default()
+    };
+    let result = equity_vs_hand_with_options(&cards("AsAh"), &cards("QdJd"), Some(&board), 0, adaptive).unwrap();
+    assert!(result.simulations < ADAPTIVE_MAX);
+    assert!(1.96 * result.stderr * 100.0 <= 1.0);
+    assert!(
+        (result.equity() - truth.equity()).abs() < 4.0 * result.stderr,
+        "{} vs exact {}",
+        result.equity(),
+        truth.equity()
+    );
+    assert!(format!("{}", result).contains("95% CI"));
+
+    let bad = EquityOptions { precision: Some(Precision::new(0.0)), ..adaptive };
+    assert!(equity_vs_hand_with_options(&cards("AsAh"), &cards("QdJd"), Some(&board), 0, bad).is_err());
+}
+
+#[test]
+fn test_per_combo_equity_buckets() {
+    let board = cards("Ah7c6d2s9h");
+    let range: Vec<String> = ["KK", "85s"].iter().map(|s| s.to_string()).collect();
+    let plain = equity_vs_range(&cards("AsAd"), &range, Some(&board), 1000, true).unwrap();
+    assert!(plain.villain_combos.is_none());
+
+    let options = EquityOptions { per_combo: true, ..Default::default() };
+    let result = equity_vs_range_with_options(&cards("AsAd"), &range, Some(&board), 1000, true, options).unwrap();
+    let combos = result.villain_combos.as_ref().unwrap();
+    assert_eq!(combos.len(), 10);
+    assert!((combos.iter().map(|c| c.weight).sum::<f64>() - 1.0).abs() < 1e-12);
+    for c in combos {
+        // Kings lose to the set; every 8-5 suited makes a straight
+        let expected = if c.combo.starts_with('K') { 1.0 } else { 0.0 };
+        assert_eq!(c.equity, expected, "{}", c.combo);
+    }
+    assert!((result.equity() - 0.6).abs() < 1e-12);
+
+    let shown = format!("{}", result);
+    assert!(shown.contains("You beat (6 combos, 60.0% of range)"), "{}", shown);
+    assert!(shown.contains("You lose to (4 combos, 40.0% of range)"), "{}", shown);
+    assert!(!shown.contains("Coinflip"));
+}
@@ -1,6 +1,12 @@
 //! Tests for the flop solver.
 
+use gto_cli::bucketing::BucketingMode;
+use gto_cli::flop_report::{
+    group_by_texture, parse_flop_list, run_flop_report, write_csv, FlopReportConfig, FlopReportRow,
+    NodeFrequencies,
+};
 use gto_cli::flop_solver::{solve_flop, FlopSolverConfig};
+use gto_cli::postflop_tree::Player;
 
 // ---------------------------------------------------------------------------
 // Config validation
@@ -112,6 +118,36 @@ fn solver_nuts_vs_air() {
     }
 }
 
+#[test]
+fn combo_evs_rank_nuts_above_bluff_catchers() {
+    // Top set vs an underpair that only beats IP's air
+    let config = FlopSolverConfig::new("Ks9d4c", "KK,TT", "QQ,76s", 10.0, 50.0, 1000).unwrap();
+    let result = solve_flop(&config);
+    assert_eq!(result.oop_combo_evs.len(), result.oop_combos.len());
+    assert_eq!(result.ip_combo_evs.len(), result.ip_combos.len());
+
+    let evs = |prefix: char| -> Vec<f64> {
+        result
+            .oop_combos
+            .iter()
+            .zip(&result.oop_combo_evs)
+            .filter(|(c, _)| c.starts_with(prefix))
+            .map(|(_, ev)| ev.expect("every combo has opponents"))
+            .collect()
+    };
+    let sets = evs('K');
+    let underpairs = evs('T');
+    let worst_set = sets.iter().cloned().fold(f64::INFINITY, f64::min);
+    let best_underpair = underpairs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    assert!(
+        worst_set > best_underpair,
+        "KK EVs {:?} should all beat TT EVs {:?}",
+        sets,
+        underpairs
+    );
+    assert!(result.render().contains("combo EVs"));
+}
+
 #[test]
 fn solver_exploitability_finite() {
     let config = FlopSolverConfig::new(
@@ -132,6 +168,117 @@ fn solver_exploitability_finite() {
     );
 }
 
+#[test]
+fn real_exploitability_at_least_abstraction() {
+    // Coarse buckets force different combos to share a strategy, so a
+    // combo-level best response can only do better than a bucket-level one.
+    let mut config = FlopSolverConfig::new(
+        "Ks9d4c",
+        "AA,KK,98s",
+        "QQ,JJ,T9s",
+        10.0,
+        50.0,
+        1000,
+    )
+    .unwrap();
+    config.num_buckets = 3;
+
+    let result = solve_flop(&config);
+
+    assert!(result.real_exploitability.is_finite());
+    assert!(
+        result.exploitability >= -1e-9,
+        "Bucket-level best response can't lose to the average strategy, got {:.4}",
+        result.exploitability
+    );
+    assert!(
+        result.real_exploitability >= result.exploitability - 1e-9,
+        "Combo-level exploitability {:.4} should be >= abstraction {:.4}",
+        result.real_exploitability,
+        result.exploitability
+    );
+}
+
+#[test]
+fn bucket_info_and_combo_lookup() {
+    let mut config =
+        FlopSolverConfig::new("Ks9d4c", "AA,KK,98s", "QQ,JJ,T9s", 10.0, 50.0, 200).unwrap();
+    config.num_buckets = 3;
+
+    let result = solve_flop(&config);
+
+    // Every OOP combo appears in exactly one bucket
+    let listed: usize = result.oop_buckets.iter().map(|b| b.combos.len()).sum();
+    assert_eq!(listed, result.oop_combos.len());
+    assert!(result
+        .oop_buckets
+        .iter()
+        .all(|b| !b.combos.is_empty() && (0.0..=1.0).contains(&b.mean_equity)));
+
+    // Lookup is order-independent and matches the combo-level strategy
+    let root = &result.strategies[0];
+    let idx = result.oop_combos.iter().position(|c| c == "AhAd" || c == "AdAh").unwrap();
+    let strat = result.strategy_for_combo("AdAh").unwrap();
+    assert_eq!(strat.len(), root.actions.len());
+    for ((_, f), expected) in strat.iter().zip(&root.frequencies[idx]) {
+        assert!((f - expected).abs() < 1e-12);
+    }
+
+    assert!(result.strategy_for_combo("QhQc").is_none(), "IP combo at OOP root");
+    assert!(result.strategy_for_combo("Kx").is_none());
+}
+
+#[test]
+fn bucket_map_covers_every_combo_once() {
+    let mut config =
+        FlopSolverConfig::new("Ks9d4c", "AA,KK,98s", "QQ,JJ,T9s", 10.0, 50.0, 100).unwrap();
+    config.num_buckets = 4;
+
+    let result = solve_flop(&config);
+
+    for (player, combos) in [(Player::OOP, &result.oop_combos), (Player::IP, &result.ip_combos)] {
+        let map = result.bucket_map(player);
+        let mapped: Vec<&String> = map.iter().map(|(c, _)| c).collect();
+        assert_eq!(mapped, combos.iter().collect::<Vec<_>>(), "one entry per combo, in order");
+
+        let equities = result.bucket_equities(player);
+        assert_eq!(equities.len(), config.num_buckets);
+        assert!(map.iter().all(|&(_, b)| (b as usize) < equities.len()));
+        for b in 0..equities.len() as u16 {
+            assert!(map.iter().any(|&(_, x)| x == b), "bucket {} is empty", b);
+        }
+        assert!(equities.windows(2).all(|w| w[0] <= w[1]), "{:?}", equities);
+    }
+}
+
+#[test]
+fn combo_strategy_resolves_through_buckets() {
+    let mut config =
+        FlopSolverConfig::new("Ks9d4c", "AA,KK,98s", "QQ,JJ,T9s", 10.0, 50.0, 100).unwrap();
+    config.num_buckets = 3;
+
+    let result = solve_flop(&config);
+
+    for node in &result.strategies {
+        let player = if node.player == "OOP" { Player::OOP } else { Player::IP };
+        let map = result.bucket_map(player);
+        for (combo, bucket) in &map {
+            let strat = result.combo_strategy(combo, node.node_id).unwrap();
+            assert_eq!(strat.len(), node.actions.len());
+            // Every combo sharing the bucket plays exactly the same way
+            let mate = &map.iter().find(|(_, b)| b == bucket).unwrap().0;
+            assert_eq!(strat, result.combo_strategy(mate, node.node_id).unwrap());
+        }
+    }
+
+    let root = result.strategies[0].node_id;
+    assert!(result.combo_strategy("KsKh", root).is_none(), "blocked by the board");
+    assert!(result.combo_strategy("QhQc", root).is_none(), "IP combo at OOP root");
+    assert!(result.combo_strategy("AhAd", u32::MAX).is_none(), "no such node");
+    // Card order doesn't matter
+    assert_eq!(result.combo_strategy("AhAd", root), result.combo_strategy("AdAh", root));
+}
+
 #[test]
 fn solver_board_str_correct() {
     let config = FlopSolverConfig::new(
@@ -186,3 +333,107 @@ fn solver_multiple_actions_available() {
         root.actions.len()
     );
 }
+
+#[test]
+fn unabstracted_beats_bucketed_on_small_ranges() {
+    // With 6 combos a side, bucketing only throws information away: the
+    // unabstracted solve should be less exploitable on the same budget.
+    let solve = |bucketing: BucketingMode| {
+        let mut config = FlopSolverConfig::new("Qs7d2c", "AA", "KK", 10.0, 50.0, 8000).unwrap();
+        config.bucketing = bucketing;
+        config.seed = Some(5);
+        solve_flop(&config)
+    };
+
+    let bucketed = solve(BucketingMode::Ehs).real_exploitability;
+    let exact = solve(BucketingMode::Unabstracted);
+
+    assert_eq!(exact.bucketing, BucketingMode::Unabstracted);
+    assert_eq!(exact.num_buckets, 6 * 49, "one bucket per combo and card");
+    assert_eq!(exact.oop_buckets.len(), 6);
+    assert!(
+        exact.real_exploitability < bucketed,
+        "unabstracted {:.4} should beat bucketed {:.4}",
+        exact.real_exploitability,
+        bucketed
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Flop report
+// ---------------------------------------------------------------------------
+
+#[test]
+fn flop_list_skips_blanks_and_comments() {
+    let text = "# dry boards\nAs7d2c\n\n  Kh 8d 3c  # spaced\n";
+    assert_eq!(parse_flop_list(text), vec!["As7d2c", "Kh8d3c"]);
+}
+
+#[test]
+fn flop_report_rows_and_csv() {
+    let config = FlopReportConfig {
+        oop_range: "AA,KK".to_string(),
+        ip_range: "QQ,JJ".to_string(),
+        starting_pot: 10.0,
+        effective_stack: 50.0,
+        iterations: 500,
+        bucketing: BucketingMode::Unabstracted,
+        num_buckets: 10,
+        jobs: 2,
+    };
+    let boards = vec!["Ks9d".to_string(), "Ks9d4c".to_string()];
+    let results = run_flop_report(&config, &boards, |_, total, _, _| assert_eq!(total, 2));
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_err(), "a bad board should fail that flop only");
+    let rows: Vec<_> = results.into_iter().filter_map(Result::ok).collect();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].board, "Ks9d4c");
+    for row in &rows {
+        let oop_total: f64 = row.oop.frequencies.iter().sum();
+        assert!((oop_total - 1.0).abs() < 1e-6, "OOP root freqs sum to {}", oop_total);
+        assert!((row.oop.get("Check") + row.oop.bet() - 1.0).abs() < 1e-6);
+        assert!(!row.ip.actions.is_empty(), "IP should have a node after a check");
+    }
+
+    let mut out = Vec::new();
+    write_csv(&rows, &mut out).unwrap();
+    let csv = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("board,weight,oop_check,oop_bet,"));
+    assert!(lines[0].ends_with(",exploitability,exploitability_pct_pot"));
+    let columns = lines[0].split(',').count();
+    assert!(lines[1..].iter().all(|l| l.split(',').count() == columns));
+}
+
+#[test]
+fn flop_report_groups_rows_by_texture() {
+    let row = |board: &str, weight: u32, oop_bet: f64| FlopReportRow {
+        board: board.to_string(),
+        weight,
+        oop: NodeFrequencies {
+            actions: vec!["Check".to_string(), "Bet 3.3".to_string()],
+            frequencies: vec![1.0 - oop_bet, oop_bet],
+        },
+        ip: NodeFrequencies::default(),
+        exploitability: 0.0,
+        exploitability_pct: 1.0,
+    };
+    let rows = vec![
+        row("Ah7d2c", 1, 0.8),
+        row("Kd8h3s", 2, 0.5),
+        row("As8c3d", 3, 0.4),
+        row("not a board", 9, 0.0),
+    ];
+
+    let groups = group_by_texture(&rows);
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].texture, "ace-high, rainbow, unpaired");
+    assert_eq!((groups[0].flops, groups[0].weight), (2, 4));
+    // Weighted: (0.8 * 1 + 0.4 * 3) / 4
+    assert!((groups[0].oop_bet - 0.5).abs() < 1e-9);
+    assert!((groups[0].exploitability_pct - 1.0).abs() < 1e-9);
+    assert_eq!(groups[1].texture, "broadway-high, rainbow, unpaired");
+    assert_eq!(groups[1].ip_bet, 0.0);
+}
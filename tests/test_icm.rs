@@ -0,0 +1,67 @@
+use gto_cli::game_tree::PushFoldPayoffs;
+use gto_cli::icm::*;
+
+fn assert_close(actual: &[f64], expected: &[f64], tolerance: f64) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < tolerance, "got {:?}, expected {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn test_three_players_two_paid() {
+    // 1st: 50/30/20 by stack; 2nd place filled from whoever is left
+    let equity = icm_equity(&[50.0, 30.0, 20.0], &[0.7, 0.3]);
+    assert_close(&equity, &[0.451786, 0.3225, 0.225714], 1e-6);
+    assert!((equity.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_textbook_three_way_split() {
+    // The classic 5000/3000/2000 chips into a 50/30/20 payout
+    let equity = icm_equity(&[5000.0, 3000.0, 2000.0], &[50.0, 30.0, 20.0]);
+    assert_close(&equity, &[38.393, 32.75, 28.857], 1e-3);
+}
+
+#[test]
+fn test_equal_stacks_split_evenly() {
+    let equity = icm_equity(&[10.0, 10.0, 10.0], &[0.6, 0.4]);
+    assert_close(&equity, &[1.0 / 3.0; 3], 1e-12);
+}
+
+#[test]
+fn test_busted_player_takes_last_place() {
+    let equity = icm_equity(&[20.0, 0.0, 20.0], &[0.5, 0.3, 0.2]);
+    assert_close(&equity, &[0.4, 0.2, 0.4], 1e-12);
+}
+
+#[test]
+fn test_winner_take_all_is_chip_ev() {
+    let spot = IcmSpot::new(vec![10.0, 10.0, 20.0], vec![1.0]).unwrap();
+    let chips = PushFoldPayoffs::new(10.0, 0.0);
+    let icm = PushFoldPayoffs::icm(&spot);
+    // One prize for 40 chips: every chip is worth 1/40
+    for eq in [0.0, 0.35, 1.0] {
+        assert!((icm.sb_showdown(eq) - chips.sb_showdown(eq) / 40.0).abs() < 1e-12);
+        assert!((icm.bb_showdown(eq) - chips.bb_showdown(eq) / 40.0).abs() < 1e-12);
+    }
+    assert!((icm.sb_fold() - chips.sb_fold() / 40.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_bubble_makes_calls_cost_more_than_they_win() {
+    // Two paid, three left, a 2bb short stack waiting to bust
+    let spot = IcmSpot::new(vec![15.0, 15.0, 2.0], vec![0.6, 0.4]).unwrap();
+    let outcomes = spot.push_fold_outcomes();
+    assert_eq!(spot.effective_stack(), 15.0);
+    // For BB, doubling up gains less than busting loses
+    assert!(outcomes.sb_wins[1] < -outcomes.sb_loses[1]);
+}
+
+#[test]
+fn test_spot_rejects_bad_input() {
+    assert!(IcmSpot::new(vec![10.0], vec![1.0]).is_err());
+    assert!(IcmSpot::new(vec![10.0, -1.0], vec![1.0]).is_err());
+    assert!(IcmSpot::new(vec![10.0, 10.0], vec![]).is_err());
+    assert!(IcmSpot::new(vec![0.5, 10.0], vec![1.0]).is_err());
+}
@@ -5,7 +5,11 @@
 use gto_cli::card_encoding::{card_to_index, cards_to_indices, index_to_card};
 use gto_cli::cards::{parse_board, parse_card, Card};
 use gto_cli::hand_evaluator::{compare_hands, evaluate_hand, HandCategory};
-use gto_cli::lookup_eval::{category_from_score, evaluate_fast};
+use gto_cli::lookup_eval::{
+    best_five, category_from_score, evaluate_board_plus, evaluate_fast, evaluate_many,
+    evaluate_many_into,
+    evaluate_omaha, evaluate_short_deck, kickers_from_score, short_deck_category,
+};
 
 fn c(notation: &str) -> Card {
     parse_card(notation).unwrap()
@@ -294,3 +298,266 @@ fn speed_sanity_check() {
         per_sec
     );
 }
+
+// -------------------------------------------------------------------------
+// Batch evaluation
+// -------------------------------------------------------------------------
+
+fn random_hands(n: usize) -> Vec<[u8; 7]> {
+    use rand::rngs::SmallRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+
+    let mut rng = SmallRng::seed_from_u64(7);
+    let mut deck: Vec<u8> = (0..52).collect();
+    (0..n)
+        .map(|_| {
+            deck.shuffle(&mut rng);
+            [deck[0], deck[1], deck[2], deck[3], deck[4], deck[5], deck[6]]
+        })
+        .collect()
+}
+
+/// Every hole-card pair that doesn't overlap the board.
+fn all_holes(board: &[u8; 5]) -> Vec<[u8; 2]> {
+    let mut holes = Vec::new();
+    for a in 0..52u8 {
+        for b in (a + 1)..52 {
+            if !board.contains(&a) && !board.contains(&b) {
+                holes.push([a, b]);
+            }
+        }
+    }
+    holes
+}
+
+#[test]
+fn evaluate_many_matches_evaluate_fast() {
+    let hands = random_hands(20_000);
+    let expected: Vec<u32> = hands.iter().map(|h| evaluate_fast(h)).collect();
+    assert_eq!(evaluate_many(&hands), expected);
+
+    let mut out = vec![0; hands.len()];
+    evaluate_many_into(&hands, &mut out);
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn board_plus_matches_evaluate_fast_on_every_hole_pair() {
+    // Paired, trips, flushing and straightening boards exercise every branch
+    for notation in ["KsKhKd2s2h", "AsKsQs3s8d", "9h8c7d6s2h", "Td9d4c4h2s", "AhAd7c5s3h"] {
+        let board: Vec<u8> = cards_to_indices(&parse_board(notation).unwrap());
+        let board: [u8; 5] = board.try_into().unwrap();
+        let holes = all_holes(&board);
+        let scores = evaluate_board_plus(&board, &holes);
+        for (&[a, b], &score) in holes.iter().zip(&scores) {
+            let hand = [a, b, board[0], board[1], board[2], board[3], board[4]];
+            assert_eq!(score, evaluate_fast(&hand), "{} with {} {}", notation, a, b);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "output buffer must match hand count")]
+fn evaluate_many_into_rejects_mismatched_buffer() {
+    let hands = random_hands(3);
+    evaluate_many_into(&hands, &mut [0; 2]);
+}
+
+// Wall-clock timing depends on the machine and build profile, so this only
+// runs on request: `cargo test --release -- --ignored`
+#[test]
+#[ignore]
+fn board_plus_is_at_least_twice_as_fast_as_naive_loop() {
+    use std::time::{Duration, Instant};
+
+    let board: [u8; 5] = cards_to_indices(&parse_board("Qs9h7d4c2s").unwrap())
+        .try_into()
+        .unwrap();
+    let holes: Vec<[u8; 2]> = all_holes(&board).into_iter().cycle().take(10_000).collect();
+
+    // Best of several rounds to keep scheduler noise out of the ratio
+    let mut naive_best = Duration::MAX;
+    let mut batch_best = Duration::MAX;
+    let mut naive = Vec::new();
+    let mut batch = Vec::new();
+    for _ in 0..20 {
+        let start = Instant::now();
+        naive = holes
+            .iter()
+            .map(|&[a, b]| evaluate_fast(&[a, b, board[0], board[1], board[2], board[3], board[4]]))
+            .collect::<Vec<u32>>();
+        naive_best = naive_best.min(start.elapsed());
+
+        let start = Instant::now();
+        batch = evaluate_board_plus(&board, std::hint::black_box(&holes));
+        batch_best = batch_best.min(start.elapsed());
+    }
+    assert_eq!(naive, batch);
+
+    let speedup = naive_best.as_secs_f64() / batch_best.as_secs_f64();
+    assert!(speedup >= 2.0, "expected >= 2x speedup, got {:.2}x", speedup);
+}
+
+// -------------------------------------------------------------------------
+// Omaha: exactly two hole cards and three board cards
+// -------------------------------------------------------------------------
+
+fn omaha(hole: &str, board: &str) -> u32 {
+    let hole: [u8; 4] = cards_to_indices(&parse_board(hole).unwrap()).try_into().unwrap();
+    let board: [u8; 5] = cards_to_indices(&parse_board(board).unwrap()).try_into().unwrap();
+    evaluate_omaha(&hole, &board)
+}
+
+#[test]
+fn omaha_board_flush_needs_two_suited_hole_cards() {
+    let board = "AsKs7s4s2s";
+    // No spades in hand: the five-spade board doesn't play
+    assert_eq!(category_from_score(omaha("QhQdJcTc", board)), HandCategory::OnePair);
+    // A single spade isn't enough either
+    assert_eq!(category_from_score(omaha("QsQdJcTc", board)), HandCategory::OnePair);
+    // Two spades make a flush
+    assert_eq!(category_from_score(omaha("QsJsTcTd", board)), HandCategory::Flush);
+}
+
+#[test]
+fn omaha_board_quads_are_not_everyones_quads() {
+    let board = "KsKhKdKc2s";
+    // Only three board kings can play: a pocket pair fills up, not quads
+    assert_eq!(category_from_score(omaha("AhAd7c3d", board)), HandCategory::FullHouse);
+    assert_eq!(category_from_score(omaha("7c6d5h4s", board)), HandCategory::ThreeOfAKind);
+    assert!(omaha("AhAd7c3d", board) > omaha("QhQd7c3d", board));
+}
+
+#[test]
+fn omaha_board_straight_needs_two_hole_cards() {
+    // The board straight can't be played with just three of its cards
+    let board = "9h8c7d6s5h";
+    assert_eq!(category_from_score(omaha("AcAdKhKs", board)), HandCategory::OnePair);
+    assert_eq!(category_from_score(omaha("TcJdKhKs", board)), HandCategory::Straight);
+}
+
+#[test]
+fn omaha_matches_best_two_plus_three_by_brute_force() {
+    use rand::rngs::SmallRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+    use itertools::Itertools;
+
+    let mut rng = SmallRng::seed_from_u64(5);
+    let mut deck: Vec<u8> = (0..52).collect();
+    for _ in 0..500 {
+        deck.shuffle(&mut rng);
+        let hole: [u8; 4] = deck[..4].try_into().unwrap();
+        let board: [u8; 5] = deck[4..9].try_into().unwrap();
+        let best = hole
+            .iter()
+            .combinations(2)
+            .cartesian_product(board.iter().combinations(3))
+            .map(|(h, b)| evaluate_fast(&[*h[0], *h[1], *b[0], *b[1], *b[2]]))
+            .max()
+            .unwrap();
+        assert_eq!(evaluate_omaha(&hole, &board), best);
+    }
+}
+
+// -------------------------------------------------------------------------
+// Short deck
+// -------------------------------------------------------------------------
+
+fn short(cards: &str) -> u32 {
+    evaluate_short_deck(&cards_to_indices(&parse_board(cards).unwrap()))
+}
+
+#[test]
+fn short_deck_flush_beats_full_house() {
+    let flush = short("AsJs9s7s6sKdQh");
+    let boat = short("AhAdAcKsKh7d6c");
+    assert_eq!(short_deck_category(flush), HandCategory::Flush);
+    assert_eq!(short_deck_category(boat), HandCategory::FullHouse);
+    assert!(flush > boat);
+    // ...but still below quads, and unchanged in hold'em
+    assert!(short("6h6d6c6sAhKdQc") > flush);
+    let holdem = |c: &str| evaluate_fast(&cards_to_indices(&parse_board(c).unwrap()));
+    assert!(holdem("AsJs9s7s6sKdQh") < holdem("AhAdAcKsKh7d6c"));
+}
+
+#[test]
+fn short_deck_ace_plays_low_in_nine_high_straight() {
+    let wheel = short("As6h7d8c9sKhKd");
+    assert_eq!(short_deck_category(wheel), HandCategory::Straight);
+    assert_eq!(kickers_from_score(wheel)[0], 9);
+    // Beats the trips/pair it would otherwise be, loses to T-high
+    assert!(wheel > short("AsAhAd8c9sKhQd"));
+    assert!(wheel < short("Ts6h7d8c9sKhKd"));
+    // Suited, it is the lowest straight flush
+    let sf = short("As6s7s8s9sKhKd");
+    assert_eq!(short_deck_category(sf), HandCategory::StraightFlush);
+    assert!(sf < short("Ts6s7s8s9sKhKd"));
+    assert!(sf > short("AhAdAcAsKsQdJd"));
+}
+
+// -------------------------------------------------------------------------
+// Best five cards
+// -------------------------------------------------------------------------
+
+/// `best_five` of hole cards followed by board cards, as card notation.
+fn best(hole: &str, board: &str) -> (Vec<String>, u32) {
+    let cards = cards_to_indices(&parse_board(&format!("{}{}", hole, board)).unwrap());
+    let (five, score) = best_five(&cards);
+    (five.iter().map(|&c| index_to_card(c).to_string()).collect(), score)
+}
+
+#[test]
+fn best_five_orders_cards_the_way_the_hand_reads() {
+    let (five, score) = best("AhAd", "AsKsKd2c3h");
+    assert_eq!(five, ["Ah", "Ad", "As", "Ks", "Kd"]);
+    assert_eq!(category_from_score(score), HandCategory::FullHouse);
+
+    // The ace plays low in a wheel
+    let (five, _) = best("Ah2c", "3d4s5hKcQd");
+    assert_eq!(five, ["5h", "4s", "3d", "2c", "Ah"]);
+
+    // Only the five highest flush cards play
+    let (five, score) = best("Ah2h", "Kh9h7h4h4c");
+    assert_eq!(five, ["Ah", "Kh", "9h", "7h", "4h"]);
+    assert_eq!(category_from_score(score), HandCategory::Flush);
+}
+
+#[test]
+fn best_five_leaves_out_counterfeited_hole_cards() {
+    // Fours are beaten by the board's two pair; the nine kicker plays
+    let (five, score) = best("4h4d", "KsKd5c5h9s");
+    assert_eq!(five, ["Ks", "Kd", "5c", "5h", "9s"]);
+    assert_eq!(category_from_score(score), HandCategory::TwoPair);
+
+    // A pair of deuces under the board's trips and pair
+    let (five, _) = best("2c2d", "QsQhQd8s8h");
+    assert!(!five.contains(&"2c".to_string()) && !five.contains(&"2d".to_string()));
+
+    // The board's straight plays; the hole nine matches a board nine, so the
+    // board card is the one shown
+    let (five, score) = best("9c2d", "5h6d7c8s9h");
+    assert_eq!(five, ["9h", "8s", "7c", "6d", "5h"]);
+    assert_eq!(category_from_score(score), HandCategory::Straight);
+
+    // One hole card plays as the kicker, the other doesn't
+    let (five, _) = best("Kc3d", "AhAdJs8c5h");
+    assert!(five.contains(&"Kc".to_string()) && !five.contains(&"3d".to_string()));
+}
+
+#[test]
+fn best_five_score_matches_evaluate_fast() {
+    use rand::seq::SliceRandom;
+
+    let mut rng = rand::thread_rng();
+    let mut deck: Vec<u8> = (0..52).collect();
+    for n in [5, 6, 7].repeat(200) {
+        deck.shuffle(&mut rng);
+        let cards = &deck[..n];
+        let (five, score) = best_five(cards);
+        assert_eq!(score, evaluate_fast(cards), "cards {:?}", cards);
+        assert_eq!(evaluate_fast(&five), score, "five {:?} of {:?}", five, cards);
+        assert!(five.iter().all(|c| cards.contains(c)));
+    }
+}
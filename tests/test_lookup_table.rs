@@ -0,0 +1,82 @@
+//! Cross-validation of the precomputed 7-card lookup table against the
+//! rank-histogram evaluator.
+
+use once_cell::sync::Lazy;
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use gto_cli::card_encoding::cards_to_indices;
+use gto_cli::cards::parse_board;
+use gto_cli::lookup_eval::{evaluate_board_plus, evaluate_fast};
+use gto_cli::lookup_table::LookupTable;
+
+/// Generated once and shared; building it takes a while in debug builds.
+static TABLE: Lazy<LookupTable> = Lazy::new(LookupTable::generate);
+
+fn random_hands(n: usize, seed: u64) -> Vec<[u8; 7]> {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut deck: Vec<u8> = (0..52).collect();
+    (0..n)
+        .map(|_| {
+            deck.shuffle(&mut rng);
+            [deck[0], deck[1], deck[2], deck[3], deck[4], deck[5], deck[6]]
+        })
+        .collect()
+}
+
+#[test]
+fn table_matches_evaluate_fast_on_100k_random_hands() {
+    let hands = random_hands(100_000, 11);
+    let table: Vec<u32> = hands.iter().map(|h| TABLE.evaluate(h)).collect();
+    let fast: Vec<u32> = hands.iter().map(|h| evaluate_fast(h)).collect();
+
+    for (i, h) in hands.iter().enumerate() {
+        assert_eq!(table[i], fast[i], "hand {:?}", h);
+    }
+    // Same scores means the same ordering between any two hands
+    for i in 1..hands.len() {
+        assert_eq!(table[i - 1].cmp(&table[i]), fast[i - 1].cmp(&fast[i]));
+    }
+}
+
+#[test]
+fn table_has_one_state_per_distinct_partial_hand() {
+    // Same count as the classic 2+2 generator
+    assert_eq!(TABLE.num_states(), 612_977);
+}
+
+#[test]
+fn table_board_plus_matches_histogram_board_plus() {
+    for notation in ["KsKhKd2s2h", "AsKsQs3s8d", "9h8c7d6s2h"] {
+        let board: [u8; 5] = cards_to_indices(&parse_board(notation).unwrap())
+            .try_into()
+            .unwrap();
+        let holes: Vec<[u8; 2]> = (0..52u8)
+            .flat_map(|a| ((a + 1)..52).map(move |b| [a, b]))
+            .filter(|h| !board.contains(&h[0]) && !board.contains(&h[1]))
+            .collect();
+        assert_eq!(
+            TABLE.evaluate_board_plus(&board, &holes),
+            evaluate_board_plus(&board, &holes),
+            "{}",
+            notation
+        );
+    }
+}
+
+#[test]
+fn saved_table_round_trips_and_garbage_is_rejected() {
+    let dir = std::env::temp_dir().join(format!("gto-eval-{}", std::process::id()));
+    let path = dir.join("eval.bin");
+    TABLE.save(&path).unwrap();
+    let loaded = LookupTable::load(&path).unwrap();
+    assert_eq!(loaded.num_states(), TABLE.num_states());
+    for h in random_hands(1_000, 3) {
+        assert_eq!(loaded.evaluate(&h), evaluate_fast(&h));
+    }
+
+    std::fs::write(&path, b"not a table").unwrap();
+    assert!(LookupTable::load(&path).is_err());
+    std::fs::remove_dir_all(&dir).ok();
+}
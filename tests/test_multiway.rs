@@ -0,0 +1,94 @@
+use gto_cli::multiway::*;
+
+fn assert_close(actual: &[f64], expected: &[f64]) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-9, "got {:?}, expected {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn test_short_stack_wins_only_main_pot() {
+    // Short stack all-in for 10 with the best hand; the other two put in 50
+    let winnings = distribute_pots(&[10.0, 50.0, 50.0], &[300, 200, 100]);
+    // Main pot 30 to the short stack, side pot 80 to the second-best hand
+    assert_close(&winnings, &[30.0, 80.0, 0.0]);
+}
+
+#[test]
+fn test_three_distinct_all_ins() {
+    let winnings = distribute_pots(&[10.0, 30.0, 60.0], &[100, 300, 200]);
+    // Main 30 and first side pot 40 go to player 1; player 2 gets back 30
+    assert_close(&winnings, &[0.0, 70.0, 30.0]);
+    let total: f64 = winnings.iter().sum();
+    assert!((total - 100.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_tied_hands_split_the_pot_they_share() {
+    let winnings = distribute_pots(&[10.0, 50.0, 50.0], &[200, 200, 100]);
+    // Main pot 30 splits two ways; only player 1 is live for the side pot
+    assert_close(&winnings, &[15.0, 95.0, 0.0]);
+}
+
+#[test]
+fn test_heads_up_equal_stacks() {
+    assert_close(&distribute_pots(&[25.0, 25.0], &[100, 50]), &[50.0, 0.0]);
+    assert_close(&distribute_pots(&[25.0, 25.0], &[100, 100]), &[25.0, 25.0]);
+}
+
+// ---------------------------------------------------------------------------
+// Multiway equity
+// ---------------------------------------------------------------------------
+
+fn spec(notation: &str) -> PlayerSpec {
+    PlayerSpec::parse(notation)
+}
+
+fn board(notation: &str) -> Vec<gto_cli::cards::Card> {
+    gto_cli::cards::parse_board(notation).unwrap()
+}
+
+#[test]
+fn test_board_royal_flush_chops_three_ways() {
+    let players = [spec("2c3d"), spec("4h5h"), spec("7c8d")];
+    let result = multiway_equity(&players, Some(&board("AsKsQsJsTs")), 200).unwrap();
+    for share in &result.players {
+        assert!((share.equity - 1.0 / 3.0).abs() < 1e-9, "{:?}", result.players);
+        assert_eq!(share.win, 0.0);
+        assert_eq!(share.tie, 1.0);
+    }
+}
+
+#[test]
+fn test_two_players_split_and_the_third_loses() {
+    // Both AQ hands make aces with the same K-Q-8 kickers
+    let players = [spec("AcQd"), spec("AdQh"), spec("JhJd")];
+    let result = multiway_equity(&players, Some(&board("AhKd8c5s2h")), 200).unwrap();
+    let equities: Vec<f64> = result.players.iter().map(|s| s.equity).collect();
+    assert_close(&equities, &[0.5, 0.5, 0.0]);
+    assert_eq!(result.players[0].tie, 1.0);
+    assert_eq!(result.players[2].win + result.players[2].tie, 0.0);
+}
+
+#[test]
+fn test_hands_against_a_range_sum_to_one() {
+    let players = [spec("AsKs"), spec("QhQd"), spec("JJ-99")];
+    assert!(matches!(players[2], PlayerSpec::Range(_)));
+    let result = multiway_equity(&players, Some(&board("2s7d8c")), 20_000).unwrap();
+    assert_eq!(result.simulations, 20_000);
+    let total: f64 = result.players.iter().map(|s| s.equity).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+    // The overpair leads both the underpairs and the overcards
+    assert!(result.players[1].equity > result.players[0].equity);
+    assert!(result.players[1].equity > result.players[2].equity);
+}
+
+#[test]
+fn test_multiway_rejects_conflicts() {
+    // Both aces in the other hands leave no AA combo
+    let players = [spec("AsAh"), spec("AdAc"), spec("AA")];
+    assert!(multiway_equity(&players, None, 100).is_err());
+    assert!(multiway_equity(&[spec("AsAh"), spec("AsKd")], None, 100).is_err());
+    assert!(multiway_equity(&[spec("AsAh")], None, 100).is_err());
+}
@@ -0,0 +1,113 @@
+//! Tests for the N-way push/fold solver.
+
+use std::sync::OnceLock;
+
+use gto_cli::game_tree::{build_preflop_equity_matrix, hand_to_bucket, EquityTable};
+use gto_cli::multiway_pushfold::{solve_multiway_push_fold, MultiwayPushFoldResult, MultiwaySpot};
+
+fn equity_table() -> &'static EquityTable {
+    static TABLE: OnceLock<EquityTable> = OnceLock::new();
+    TABLE.get_or_init(|| EquityTable::from_matrix(&build_preflop_equity_matrix(200)))
+}
+
+fn solve(stacks: &[f64], ante: f64) -> MultiwayPushFoldResult {
+    let spot = MultiwaySpot::new(stacks.to_vec(), ante).unwrap();
+    solve_multiway_push_fold(&spot, 200_000, equity_table())
+}
+
+fn bucket(hand: &str) -> usize {
+    hand_to_bucket(hand).unwrap()
+}
+
+#[test]
+fn spot_validation() {
+    assert!(MultiwaySpot::new(vec![10.0], 0.0).is_err());
+    assert!(MultiwaySpot::new(vec![10.0; 4], 0.0).is_err());
+    assert!(MultiwaySpot::new(vec![10.0, 10.0, 10.0], -0.1).is_err());
+    // The big blind must have chips behind its blind and ante
+    assert!(MultiwaySpot::new(vec![10.0, 10.0, 1.1], 0.1).is_err());
+    let spot = MultiwaySpot::new(vec![10.0, 10.0, 10.0], 0.1).unwrap();
+    assert_eq!(
+        (0..3).map(|s| spot.position_name(s)).collect::<Vec<_>>(),
+        ["BTN", "SB", "BB"]
+    );
+    assert!((spot.posted(1) - 0.6).abs() < 1e-12);
+    let heads_up = MultiwaySpot::new(vec![10.0, 10.0], 0.0).unwrap();
+    assert_eq!(heads_up.position_name(0), "SB");
+}
+
+#[test]
+fn payoffs_handle_walks_and_side_pots() {
+    let table = equity_table();
+    let aa = bucket("AA");
+    let spot = MultiwaySpot::new(vec![10.0, 3.0, 10.0], 0.1).unwrap();
+
+    // Folded to the big blind: it collects the small blind and the antes
+    let walk = spot.payoffs(0b100, 0, &[aa, aa, aa], table);
+    assert!((walk[2] - 0.7).abs() < 1e-12, "{:?}", walk);
+    assert!((walk[0] + 0.1).abs() < 1e-12 && (walk[1] + 0.6).abs() < 1e-12);
+
+    // BTN shoves 10bb and the 3bb SB calls: only 3bb of the shove is called,
+    // and with equal hands the pot splits
+    let split = spot.payoffs(0b011, 0b011, &[aa, aa, aa], table);
+    assert!((split[2] + 1.1).abs() < 1e-12, "{:?}", split);
+    assert!((split[0] - 0.55).abs() < 1e-12 && (split[1] - 0.55).abs() < 1e-12);
+
+    // Three-way all in is zero-sum and the favourite wins the most
+    let spot = MultiwaySpot::new(vec![5.0, 10.0, 10.0], 0.0).unwrap();
+    let hands = [bucket("AA"), bucket("72o"), bucket("KQs")];
+    let all_in = spot.payoffs(0b111, 0b111, &hands, table);
+    assert!(all_in.iter().sum::<f64>().abs() < 1e-9, "{:?}", all_in);
+    assert!(all_in[0] > 0.0 && all_in[1] < 0.0);
+    // The short stack can win at most what it covers from each player
+    assert!(all_in[0] < 10.0);
+}
+
+#[test]
+fn tiny_stacks_shove_very_wide() {
+    let result = solve(&[2.0, 2.0, 2.0], 0.25);
+    // The button still has two hands to get through, both calling almost
+    // anything, so it shoves just over half; the small blind nearly all
+    let btn = result.all_in_pct(result.node(0, 0).unwrap());
+    let sb = result.all_in_pct(result.node(1, 0).unwrap());
+    assert!(btn > 50.0 && sb > 90.0, "BTN {:.1}%, SB {:.1}%", btn, sb);
+    // The big blind gets a great price to call any single shove
+    for all_in in [0b001, 0b010] {
+        let bb = result.node(2, all_in).unwrap();
+        assert!(result.all_in_pct(bb) > 90.0, "{}", result.describe(bb));
+    }
+    assert!(result.shove_strategy(2).is_none());
+}
+
+#[test]
+fn ranges_tighten_as_stacks_grow() {
+    let pcts: Vec<[f64; 2]> = [2.0, 8.0, 20.0]
+        .iter()
+        .map(|&stack| {
+            let result = solve(&[stack; 3], 0.0);
+            let aa = result.shove_strategy(0).unwrap()[bucket("AA")];
+            assert!(aa > 0.9, "BTN shoves AA only {:.2} at {}bb", aa, stack);
+            [0, 1].map(|seat| result.all_in_pct(result.node(seat, 0).unwrap()))
+        })
+        .collect();
+    for seat in 0..2 {
+        assert!(
+            pcts[0][seat] > pcts[1][seat] + 5.0 && pcts[1][seat] > pcts[2][seat] + 5.0,
+            "seat {} shove % by stack: {:?}",
+            seat,
+            pcts
+        );
+    }
+    // 20bb deep the button shoves a small range
+    assert!(pcts[2][0] < 30.0, "{:?}", pcts);
+}
+
+#[test]
+fn calling_three_way_needs_more_than_calling_one_shove() {
+    let result = solve(&[10.0; 3], 0.0);
+    let vs_shove = result.all_in_pct(result.node(2, 0b001).unwrap());
+    let vs_shove_and_call = result.all_in_pct(result.node(2, 0b011).unwrap());
+    assert!(vs_shove_and_call < vs_shove, "{:.1}% vs {:.1}%", vs_shove_and_call, vs_shove);
+    assert_eq!(result.describe(result.node(2, 0b011).unwrap()), "BB vs BTN shove, SB call");
+    assert_eq!(result.describe(result.node(1, 0).unwrap()), "SB first in");
+}
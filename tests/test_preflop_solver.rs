@@ -10,7 +10,8 @@
 //! where SB is OOP, or BTN vs SB with higher dead money).
 
 use gto_cli::game_tree::{
-    bucket_to_hand, hand_to_bucket, precompute_equity_table, NUM_HANDS,
+    bucket_to_hand, build_preflop_equity_matrix, hand_to_bucket, precompute_equity_table,
+    EquityTable, NUM_HANDS,
 };
 use gto_cli::preflop_solver::{solve_preflop_spot, Position};
 
@@ -301,3 +302,29 @@ fn sb_open_range_reasonable() {
         "SB open range {:.1}% should be 10-60%", pct,
     );
 }
+
+// ---------------------------------------------------------------------------
+// All-in equity matrix
+// ---------------------------------------------------------------------------
+
+#[test]
+fn equity_matrix_sanity() {
+    let matrix = build_preflop_equity_matrix(500);
+    let aa = hand_to_bucket("AA").unwrap();
+    let trash = hand_to_bucket("72o").unwrap();
+    assert!(
+        (matrix[aa][trash] - 0.88).abs() < 0.03,
+        "AA vs 72o should be about 88%, got {:.3}", matrix[aa][trash],
+    );
+
+    for (i, row) in matrix.iter().enumerate() {
+        for (j, &eq) in row.iter().enumerate() {
+            assert!((eq + matrix[j][i] - 1.0).abs() < 1e-12, "{} vs {}", i, j);
+        }
+    }
+
+    let table = EquityTable::from_matrix(&matrix);
+    assert_eq!(table.eq(aa, trash), matrix[aa][trash]);
+    // 72o never shares a card with AA, so all 6 x 12 combo pairs count
+    assert_eq!(table.weight(aa, trash), 72.0);
+}
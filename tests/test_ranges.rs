@@ -1,4 +1,5 @@
 use gto_cli::cards::*;
+use gto_cli::display::{range_grid, range_grid_weights, weighted_range_grid};
 use gto_cli::ranges::*;
 
 #[test]
@@ -65,6 +66,80 @@ fn test_parse_range_dash_suited() {
     assert!(!result.contains(&"K9s".to_string()));
 }
 
+fn strs(hands: &[&str]) -> Vec<String> {
+    hands.iter().map(|h| h.to_string()).collect()
+}
+
+fn sorted(mut hands: Vec<String>) -> Vec<String> {
+    hands.sort();
+    hands
+}
+
+#[test]
+fn test_parse_range_plus_suited_ace_wheel() {
+    let result = parse_range("A2s+");
+    assert_eq!(result.len(), 12);
+    for kicker in "23456789TJQK".chars() {
+        assert!(result.contains(&format!("A{}s", kicker)), "missing A{}s", kicker);
+    }
+    assert!(!result.iter().any(|h| h.ends_with('o')));
+}
+
+#[test]
+fn test_parse_range_plus_offsuit() {
+    assert_eq!(sorted(parse_range("KTo+")), sorted(strs(&["KTo", "KJo", "KQo"])));
+}
+
+#[test]
+fn test_parse_range_plus_keeps_the_high_card() {
+    // The kicker is already just below the high card, so there's nothing to raise
+    assert_eq!(parse_range("KQo+"), strs(&["KQo"]));
+    assert_eq!(parse_range("54s+"), strs(&["54s"]));
+}
+
+#[test]
+fn test_parse_range_dash_connectors() {
+    let result = parse_range("54s-T9s");
+    assert_eq!(sorted(result), sorted(strs(&["54s", "65s", "76s", "87s", "98s", "T9s"])));
+    assert_eq!(parse_range("T9s-54s"), parse_range("54s-T9s"));
+    assert_eq!(sorted(parse_range("QTo-86o")), sorted(strs(&["86o", "97o", "T8o", "J9o", "QTo"])));
+    assert_eq!(sorted(parse_range("KQ-JT")), sorted(strs(&["KQs", "QJs", "JTs", "KQo", "QJo", "JTo"])));
+}
+
+#[test]
+fn test_parse_range_dash_pairs_descending() {
+    assert_eq!(parse_range("55-22"), strs(&["55", "44", "33", "22"]));
+    assert_eq!(parse_range("99-55"), parse_range("55-99"));
+}
+
+#[test]
+fn test_parse_range_dash_offsuit_and_unsuffixed() {
+    assert_eq!(sorted(parse_range("A5o-A2o")), sorted(strs(&["A5o", "A4o", "A3o", "A2o"])));
+    assert_eq!(
+        sorted(parse_range("K9-KJ")),
+        sorted(strs(&["K9s", "KTs", "KJs", "K9o", "KTo", "KJo"]))
+    );
+}
+
+#[test]
+fn test_parse_range_unsuffixed_and_low_first() {
+    assert_eq!(sorted(parse_range("AK")), sorted(strs(&["AKs", "AKo"])));
+    assert_eq!(sorted(parse_range("AQ+")), sorted(strs(&["AQs", "AKs", "AQo", "AKo"])));
+    assert_eq!(parse_range("KAs"), strs(&["AKs"]));
+    assert_eq!(parse_range("AhKh"), strs(&["AhKh"]));
+}
+
+#[test]
+fn test_parse_range_pokerstove_string() {
+    let result = parse_range("AA,KK,AKs,55-22,A2s+");
+    let expected = sorted(strs(&[
+        "AA", "KK", "AKs", "55", "44", "33", "22", "A2s", "A3s", "A4s", "A5s", "A6s", "A7s",
+        "A8s", "A9s", "ATs", "AJs", "AQs",
+    ]));
+    assert_eq!(sorted(result), expected);
+    assert_eq!(total_combos(&parse_range("AA,KK,AKs,55-22,A2s+")), 6 + 6 + 4 * 6 + 4 * 12);
+}
+
 #[test]
 fn test_parse_range_mixed() {
     let result = parse_range("AA, KK, AKs, AQs+");
@@ -145,3 +220,196 @@ fn test_blocked_combos_count() {
     let count = blocked_combos("AA", &hero).unwrap();
     assert_eq!(count, 5); // holding 2 aces blocks 5 of 6 combos
 }
+
+#[test]
+fn test_hand_ranking_covers_all_hands() {
+    let unique: std::collections::HashSet<&str> = HAND_RANKING.iter().copied().collect();
+    assert_eq!(unique.len(), 169);
+    assert_eq!(HAND_RANKING.len(), 169);
+    assert_eq!(total_combos_strs(HAND_RANKING), 1326);
+}
+
+#[test]
+fn test_range_from_top_pct_full() {
+    assert_eq!(range_from_top_pct(100.0).unwrap().len(), 169);
+}
+
+#[test]
+fn test_range_from_top_pct_monotonic() {
+    let mut prev = 0;
+    for pct in 1..=100 {
+        let range = range_from_top_pct(pct as f64).unwrap();
+        assert!(range.len() >= prev, "shrank at {}%", pct);
+        assert!(total_combos(&range) as f64 >= 1326.0 * pct as f64 / 100.0);
+        prev = range.len();
+    }
+}
+
+#[test]
+fn test_range_from_top_pct_keeps_whole_class() {
+    // AA + KK = 12 combos; 1% (13.26 combos) cuts into QQ, which is kept whole
+    assert_eq!(range_from_top_pct(1.0).unwrap(), vec!["AA", "KK", "QQ"]);
+}
+
+#[test]
+fn test_suit_specific_combo_entries() {
+    let result = parse_range("AhKh,AsAd");
+    assert!(result.contains(&"AhKh".to_string()));
+    assert!(result.contains(&"AsAd".to_string()));
+    assert_eq!(combo_count("AhKh"), 1);
+    assert_eq!(total_combos(&result), 2);
+}
+
+#[test]
+fn test_range_subtract_suited_from_ak() {
+    let ak = parse_range("AKs,AKo");
+    let result = range_subtract(&ak, &parse_range("AKs"));
+    assert_eq!(result, vec!["AKo".to_string()]);
+}
+
+#[test]
+fn test_range_subtract_partial_class() {
+    let result = range_subtract(&parse_range("AKs"), &parse_range("AhKh"));
+    assert_eq!(result.len(), 3);
+    assert!(!result.contains(&"AhKh".to_string()));
+    assert_eq!(total_combos(&result), 3);
+}
+
+#[test]
+fn test_range_intersect_pairs() {
+    let result = range_intersect(&parse_range("TT+"), &parse_range("88-QQ"));
+    assert_eq!(result, vec!["QQ", "JJ", "TT"]);
+    assert!(range_intersect(&parse_range("22-55"), &parse_range("TT+")).is_empty());
+}
+
+#[test]
+fn test_parse_weighted_range_weights() {
+    let result = parse_weighted_range("AA:0.75,AKs,QQ+:0.5").unwrap();
+    let weight = |h: &str| result.iter().find(|(x, _)| x == h).map(|(_, w)| *w);
+    assert_eq!(weight("AA"), Some(0.5), "later QQ+ entry overrides AA:0.75");
+    assert_eq!(weight("KK"), Some(0.5));
+    assert_eq!(weight("QQ"), Some(0.5));
+    assert_eq!(weight("AKs"), Some(1.0));
+    assert_eq!(result.len(), 4);
+}
+
+#[test]
+fn test_parse_weighted_range_clamps() {
+    let result = parse_weighted_range("AA:1.5,KK:-0.2").unwrap();
+    assert!(result.contains(&("AA".to_string(), 1.0)));
+    assert!(result.contains(&("KK".to_string(), 0.0)));
+}
+
+#[test]
+fn test_parse_weighted_range_malformed() {
+    assert!(parse_weighted_range("AA:abc").is_err());
+    assert!(parse_weighted_range("AA:").is_err());
+    assert!(parse_weighted_range("AA:NaN").is_err());
+}
+
+#[test]
+fn test_parse_range_ignores_weights() {
+    let result = parse_range("AA:0.5,KK");
+    assert_eq!(result, vec!["AA".to_string(), "KK".to_string()]);
+}
+
+#[test]
+fn test_range_grid_weights_layout() {
+    let range = parse_weighted_range("AA,AKs:0.5,KQo:0.25").unwrap();
+    let weights = range_grid_weights(&range);
+    assert_eq!(weights[0], 1.0); // AA on the diagonal
+    assert_eq!(weights[1], 0.5); // AKs above it
+    assert_eq!(weights[13], 0.0); // AKo below it
+    assert_eq!(weights[2 * 13 + 1], 0.25); // KQo
+    assert_eq!(weights.iter().filter(|&&w| w > 0.0).count(), 3);
+}
+
+#[test]
+fn test_range_grid_weights_partial_class() {
+    let range = vec![("AhKh".to_string(), 1.0), ("AsKs".to_string(), 1.0)];
+    let weights = range_grid_weights(&range);
+    assert!((weights[1] - 0.5).abs() < 1e-9, "2 of 4 suited combos");
+}
+
+#[test]
+fn test_range_grid_empty_and_full() {
+    let empty = range_grid(&[], "Empty");
+    assert!(empty.contains("(empty range)"));
+    assert!(empty.contains("AA") && empty.contains("32o"));
+
+    let full: Vec<(String, f64)> = HAND_RANKING.iter().map(|h| (h.to_string(), 1.0)).collect();
+    assert!(range_grid_weights(&full).iter().all(|&w| w == 1.0));
+    let grid = weighted_range_grid(&full, "Full");
+    assert!(!grid.contains("(empty range)"));
+    assert!(grid.contains("72o") && grid.contains("AKs"));
+}
+
+#[test]
+fn test_named_range_round_trip() {
+    let dir = std::env::temp_dir().join(format!("gto-named-ranges-{}", std::process::id()));
+    let hands = parse_range("QQ+,AKs,AhQh");
+    let full: Vec<(String, f64)> = hands.iter().map(|h| (h.clone(), 1.0)).collect();
+
+    let path = save_named_range_in(&dir, "btn_open", &full).unwrap();
+    assert_eq!(path, dir.join("btn_open.txt"));
+    assert_eq!(load_named_range_in(&dir, "btn_open").unwrap(), hands);
+
+    // Weights survive the trip; zero-weight hands are dropped
+    let weighted = parse_weighted_range("AA:0.5,KK,AKs:0.25,72o:0").unwrap();
+    save_named_range_in(&dir, "mixed", &weighted).unwrap();
+    let saved = read_named_range_in(&dir, "mixed").unwrap();
+    assert_eq!(
+        parse_weighted_range(&saved).unwrap(),
+        vec![("AA".to_string(), 0.5), ("KK".to_string(), 1.0), ("AKs".to_string(), 0.25)]
+    );
+
+    std::fs::write(dir.join("weighted.txt"), "AA:0.5,\nKK\n").unwrap();
+    assert_eq!(read_named_range_in(&dir, "weighted").unwrap(), "AA:0.5,,KK");
+    assert_eq!(load_named_range_in(&dir, "weighted").unwrap(), vec!["AA", "KK"]);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_named_range_errors() {
+    let dir = std::env::temp_dir().join(format!("gto-named-missing-{}", std::process::id()));
+    let err = load_named_range_in(&dir, "nope").unwrap_err();
+    assert!(err.to_string().contains("named range 'nope'"), "{}", err);
+
+    assert!(load_named_range_in(&dir, "../etc/passwd").is_err());
+    assert!(save_named_range_in(&dir, "", &[("AA".to_string(), 1.0)]).is_err());
+    assert!(!dir.exists(), "a bad name must not create the directory");
+}
+
+#[test]
+fn test_blocker_report_removes_exact_cards() {
+    let range = parse_range("AA,AKs,KK,QQ");
+    let report = range_blocker_report("AsAh", &range).unwrap();
+
+    // Every villain combo holding As or Ah is gone: 5 of AA, 2 of AKs
+    let (a_s, a_h) = (parse_card("As").unwrap(), parse_card("Ah").unwrap());
+    let expected = range
+        .iter()
+        .flat_map(|h| hand_combos(h).unwrap())
+        .filter(|(c1, c2)| [a_s, a_h].iter().any(|c| c == c1 || c == c2))
+        .count() as u32;
+    assert_eq!(report.removed_combos, expected);
+    assert_eq!(report.removed_combos, 7);
+    assert_eq!(report.total_combos, 6 + 4 + 6 + 6);
+    assert_eq!(report.remaining_combos(), 15);
+    assert_eq!(report.removed_by_card, vec![(a_s, 4), (a_h, 4)]);
+    assert_eq!(
+        report.affected,
+        vec![("AA".to_string(), 5, 6), ("AKs".to_string(), 2, 4)]
+    );
+
+    assert!(range_blocker_report("AA", &range).is_err());
+}
+
+#[test]
+fn test_combos_in_common() {
+    let a = parse_range("AA,AKs,KQo");
+    let b = parse_range("AA,AK");
+    assert_eq!(combos_in_common(&a, &b), 6 + 4);
+    assert_eq!(combos_in_common(&a, &parse_range("22")), 0);
+}
@@ -1,11 +1,20 @@
 //! Tests for the river solver.
 
+use std::sync::Once;
+
 use gto_cli::card_encoding::card_to_index;
+use gto_cli::cfr::{CfrVariant, Precision, SolverAlgorithm};
 use gto_cli::cards::parse_card;
 use gto_cli::lookup_eval::evaluate_fast;
-use gto_cli::postflop_tree::{build_tree, Player, TerminalType, TreeConfig, TreeNode};
+use gto_cli::postflop_tree::{
+    build_tree, estimate_tree, same_for_both, BetSize, Player, RaiseSizing, TerminalType, TreeConfig,
+    TreeNode,
+};
+use gto_cli::ranges::parse_range;
 use gto_cli::river_solver::{
-    expand_range_to_combos, solve_river, Combo, RiverSolverConfig, ShowdownTable,
+    expand_range_to_combos, Abstraction, exploitability_of, range_advantage, resume_river, simplify_solution, solve_river,
+    solve_river_with_progress, Combo, NodeStrategy, RiverCfr, RiverSolution, RiverSolverConfig,
+    ShowdownTable, StrategyDiff, NUT_FRACTION,
 };
 
 // ---------------------------------------------------------------------------
@@ -27,6 +36,16 @@ fn board(s: &str) -> Vec<u8> {
         .collect()
 }
 
+/// Point the gto-cli home at a scratch directory so checkpoint and cache
+/// tests never touch the real `~/.gto-cli`.
+fn use_scratch_home() {
+    static HOME: Once = Once::new();
+    HOME.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("gto-cli-home-{}", std::process::id()));
+        std::env::set_var("GTO_CLI_HOME", dir);
+    });
+}
+
 // ---------------------------------------------------------------------------
 // Tree building tests
 // ---------------------------------------------------------------------------
@@ -56,11 +75,11 @@ fn tree_node_ids_sequential() {
     ids.dedup();
     assert_eq!(ids.len(), num_nodes as usize);
     for (i, &id) in ids.iter().enumerate() {
-        assert_eq!(id, i as u16, "Node IDs should be sequential");
+        assert_eq!(id, i as u32, "Node IDs should be sequential");
     }
 }
 
-fn collect_ids(node: &TreeNode, ids: &mut Vec<u16>) {
+fn collect_ids(node: &TreeNode, ids: &mut Vec<u32>) {
     if let TreeNode::Action {
         node_id, children, ..
     } = node
@@ -75,12 +94,19 @@ fn collect_ids(node: &TreeNode, ids: &mut Vec<u16>) {
 #[test]
 fn check_check_path_is_showdown() {
     let config = TreeConfig {
-        bet_sizes: vec![1.0],
-        raise_sizes: vec![],
+        bet_sizes: same_for_both(vec![BetSize::Pot(1.0)]),
+        raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![])),
         max_raises: 0,
         starting_pot: 10.0,
-        effective_stack: 20.0,
+        stacks: [20.0; 2],
         add_allin: false,
+        min_bet: 0.0,
+        bet_round_to: None,
+        merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+        allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+        force_allin_above_stack_pct: None,
+        can_raise: [true; 2],
+        max_check_raises: None,
     };
     let (root, _) = build_tree(&config);
 
@@ -103,12 +129,19 @@ fn check_check_path_is_showdown() {
 #[test]
 fn bet_clamped_to_stack() {
     let config = TreeConfig {
-        bet_sizes: vec![5.0], // 500% pot, way more than stack
-        raise_sizes: vec![],
+        bet_sizes: same_for_both(vec![BetSize::Pot(5.0)]), // 500% pot, way more than stack
+        raise_sizes: same_for_both(RaiseSizing::PotFraction(vec![])),
         max_raises: 0,
         starting_pot: 10.0,
-        effective_stack: 3.0,
+        stacks: [3.0; 2],
         add_allin: false,
+        min_bet: 0.0,
+        bet_round_to: None,
+        merge_tolerance: TreeConfig::DEFAULT_MERGE_TOLERANCE,
+        allin_threshold: TreeConfig::DEFAULT_ALLIN_THRESHOLD,
+        force_allin_above_stack_pct: None,
+        can_raise: [true; 2],
+        max_check_raises: None,
     };
     let (root, _) = build_tree(&config);
 
@@ -125,10 +158,222 @@ fn bet_clamped_to_stack() {
     }
 }
 
+#[test]
+fn solver_config_raises_by_bet_multiple() {
+    let mut config = RiverSolverConfig::new("Ks9d4c7h2s", "AA", "KK", 10.0, 100.0, 10).unwrap();
+    config.bet_sizes = same_for_both(vec![BetSize::Pot(1.0)]);
+    config.raise_sizes = same_for_both("3x".parse().unwrap());
+    let exact = config.config_hash();
+
+    let (root, _) = build_tree(&config.tree_config());
+    let TreeNode::Action { children, .. } = &root else { panic!("root acts") };
+    let TreeNode::Action { actions, .. } = &children[1] else { panic!("IP faces the bet") };
+    assert!(actions.contains(&gto_cli::postflop_tree::Action::Raise(30.0)), "{:?}", actions);
+
+    config.raise_sizes = same_for_both(RaiseSizing::PotFraction(vec![1.0]));
+    assert_ne!(config.config_hash(), exact);
+}
+
+/// Every showdown terminal under `node`, as (pot, stacks, invested).
+fn showdowns(node: &TreeNode, out: &mut Vec<(f64, [f64; 2], [f64; 2])>) {
+    match node {
+        TreeNode::Terminal { terminal_type: TerminalType::Showdown, pot, stacks, invested } => {
+            out.push((*pot, *stacks, *invested));
+        }
+        TreeNode::Action { children, .. } | TreeNode::Chance { children, .. } => {
+            for child in children {
+                showdowns(child, out);
+            }
+        }
+        TreeNode::Terminal { .. } => {}
+    }
+}
+
+#[test]
+fn asymmetric_stacks_cap_allin_at_shorter_stack() {
+    use gto_cli::postflop_tree::Action;
+
+    // OOP has 15 behind, IP 60, pot 10: nobody can put in more than 15
+    let mut config = TreeConfig::default_river(10.0, 15.0);
+    config.stacks = [15.0, 60.0];
+    config.bet_sizes = same_for_both(vec![BetSize::Pot(0.5)]);
+    config.raise_sizes = same_for_both(RaiseSizing::PotFraction(vec![1.0]));
+    assert_eq!(config.effective_stack(), 15.0);
+    let (root, num_nodes) = build_tree(&config);
+
+    let TreeNode::Action { actions, children, .. } = &root else { panic!("root is an action node") };
+    assert_eq!(actions, &vec![Action::Check, Action::Bet(5.0), Action::Bet(15.0)]);
+
+    // IP's all-in after a check is the 15 OOP can call, not 60
+    let TreeNode::Action { actions, .. } = &children[0] else { panic!("IP acts after a check") };
+    assert_eq!(actions, &vec![Action::Check, Action::Bet(5.0), Action::Bet(15.0)]);
+
+    // IP's pot-sized raise over the 5 bet is capped at OOP's stack
+    let TreeNode::Action { actions, .. } = &children[1] else { panic!("IP faces the bet") };
+    assert_eq!(actions, &vec![Action::Fold, Action::Call(5.0), Action::Raise(15.0)]);
+
+    // OOP all-in: IP calls 15 and can't raise a player with nothing behind
+    let TreeNode::Action { actions, children: facing, .. } = &children[2] else { panic!("IP faces the shove") };
+    assert_eq!(actions, &vec![Action::Fold, Action::Call(15.0)]);
+    let TreeNode::Terminal { pot, stacks, invested, .. } = &facing[1] else { panic!("call ends the hand") };
+    assert_eq!((*pot, *stacks, *invested), (40.0, [0.0, 45.0], [15.0, 15.0]));
+
+    // Every showdown has both players equally invested; IP keeps at least 45
+    let mut terminals = Vec::new();
+    showdowns(&root, &mut terminals);
+    for (pot, stacks, invested) in terminals {
+        assert!((invested[0] - invested[1]).abs() < 1e-9, "{:?}", invested);
+        assert!((pot - 10.0 - invested[0] - invested[1]).abs() < 1e-9);
+        assert!(stacks[1] >= 45.0 - 1e-9, "{:?}", stacks);
+    }
+    assert_eq!(gto_cli::postflop_tree::estimate_tree_nodes(&config), num_nodes as usize);
+}
+
+/// What the winner of each terminal under `node` nets, as (oop wins, ip wins).
+/// Folds only have one winner; the other entry is `None`.
+fn winning_payoffs(node: &TreeNode, out: &mut Vec<(Option<f64>, Option<f64>)>) {
+    match node {
+        TreeNode::Terminal { terminal_type, pot, invested, .. } => {
+            let win = |p: usize| Some(pot - invested[p]);
+            out.push(match terminal_type {
+                TerminalType::Showdown => (win(0), win(1)),
+                TerminalType::Fold { folder: Player::IP } => (win(0), None),
+                TerminalType::Fold { folder: Player::OOP } => (None, win(1)),
+            });
+        }
+        TreeNode::Action { children, .. } | TreeNode::Chance { children, .. } => {
+            for child in children {
+                winning_payoffs(child, out);
+            }
+        }
+    }
+}
+
+#[test]
+fn dead_money_raises_winning_payoffs_for_both_players() {
+    let mut config = RiverSolverConfig::new("2s7h9dJcQs", "AA", "KK", 10.0, 20.0, 10).unwrap();
+    config.bet_sizes = same_for_both(vec![BetSize::Pot(1.0)]);
+    // No raises, so both trees have the same shape whatever the pot
+    config.max_raises = 0;
+    let (live, _) = build_tree(&config.tree_config());
+    config.dead_money = 4.0;
+    assert_eq!(config.tree_config().starting_pot, 14.0);
+    let (dead, _) = build_tree(&config.tree_config());
+
+    let (mut live_payoffs, mut dead_payoffs) = (Vec::new(), Vec::new());
+    winning_payoffs(&live, &mut live_payoffs);
+    winning_payoffs(&dead, &mut dead_payoffs);
+    assert_eq!(live_payoffs.len(), dead_payoffs.len());
+    for (l, d) in live_payoffs.iter().zip(&dead_payoffs) {
+        for (lw, dw) in [(l.0, d.0), (l.1, d.1)] {
+            if let (Some(lw), Some(dw)) = (lw, dw) {
+                assert!(dw > lw, "dead money should raise a win from {} to more, got {}", lw, dw);
+            }
+        }
+    }
+
+    let result = solve_river(&config);
+    assert_eq!(result.dead_money, 4.0);
+}
+
+#[test]
+fn solver_handles_asymmetric_stacks() {
+    let mut config = RiverSolverConfig::new("2s7h9dJcQs", "AA,KK", "QQ,AKo", 10.0, 15.0, 200).unwrap();
+    config.stacks = Some([15.0, 60.0]);
+    config.bet_sizes = same_for_both(vec![BetSize::Pot(0.5)]);
+
+    let result = solve_river(&config);
+    assert!(result.exploitability.is_finite());
+    let root = result.strategies.first().unwrap();
+    assert_eq!(root.actions, vec!["Check", "Bet 5.0", "Bet 15.0"]);
+    assert_eq!(root.labels.last().unwrap(), "All-in (15.0)");
+    // IP never bets more than OOP can call
+    for strat in result.strategies.iter().filter(|s| s.player == "IP") {
+        assert!(!strat.actions.iter().any(|a| a == "Bet 60.0"), "{:?}", strat.actions);
+    }
+    assert_eq!(result.stacks, Some([15.0, 60.0]));
+    assert!(result.render().contains("Stacks: 15/60"));
+}
+
 // ---------------------------------------------------------------------------
 // Showdown tests
 // ---------------------------------------------------------------------------
 
+#[test]
+fn range_advantage_aa_vs_kk() {
+    let b = board("2s7h9dJcQs");
+    let oop = expand_range_to_combos(&parse_range("AA"), &b);
+    let ip = expand_range_to_combos(&parse_range("KK"), &b);
+    let advantage = range_advantage(&ShowdownTable::new(oop, ip, &b), NUT_FRACTION);
+
+    assert_eq!(advantage.oop_equity, 1.0);
+    assert_eq!(advantage.nut_share, [1.0, 0.0]);
+    assert_eq!(advantage.nut_advantage(), Some(Player::OOP));
+    assert_eq!(advantage.summary(), "OOP equity 100%, nut advantage OOP");
+
+    // Same hand both sides: a chop, nobody ahead
+    let oop = expand_range_to_combos(&parse_range("AKo"), &b);
+    let ip = expand_range_to_combos(&parse_range("AKo"), &b);
+    let even = range_advantage(&ShowdownTable::new(oop, ip, &b), NUT_FRACTION);
+    assert_eq!(even.oop_equity, 0.5);
+    assert_eq!(even.nut_advantage(), None);
+}
+
+#[test]
+fn solution_reports_range_advantage() {
+    let config = RiverSolverConfig::new("2s7h9dJcQs", "AA", "KK", 10.0, 20.0, 10).unwrap();
+    let result = solve_river(&config);
+    let advantage = result.range_advantage.expect("solved spot has a summary");
+    assert_eq!(advantage.nut_advantage(), Some(Player::OOP));
+    assert!(result.render().contains("OOP equity 100%, nut advantage OOP"));
+}
+
+#[test]
+fn saved_solution_keeps_its_tree() {
+    let config = RiverSolverConfig::new("2s7h9dJcQs", "AA", "KK", 10.0, 20.0, 10).unwrap();
+    let result = solve_river(&config);
+    let bytes = bincode::serialize(&result).unwrap();
+    let loaded: gto_cli::river_solver::RiverSolution = bincode::deserialize(&bytes).unwrap();
+
+    let tree = loaded.tree.expect("solved spot stores its tree");
+    assert_eq!(tree, build_tree(&config.tree_config()).0);
+    match &tree {
+        TreeNode::Action { actions, .. } => {
+            let labels: Vec<String> = actions.iter().map(|a| a.label()).collect();
+            assert_eq!(labels, loaded.strategies[0].actions);
+        }
+        other => panic!("root should be an action node, got {:?}", other),
+    }
+}
+
+#[test]
+fn dcfr_converges_in_fewer_iterations_than_cfr_plus() {
+    let solve = |variant, iterations| {
+        let mut config = RiverSolverConfig::new(
+            "Ks9d4c7h2s",
+            "AA,KK,99,AK,AQs,QJs,T8s",
+            "QQ,JJ,AK,KQ,98s,T9s",
+            10.0,
+            20.0,
+            iterations,
+        )
+        .unwrap();
+        config.cfr_variant = variant;
+        solve_river(&config)
+    };
+
+    let cfr_plus = solve(CfrVariant::CfrPlus, 400);
+    let dcfr = solve(CfrVariant::DCFR, 200);
+    assert!(
+        dcfr.exploitability < cfr_plus.exploitability,
+        "DCFR after 200 iterations ({:.4}) should beat CFR+ after 400 ({:.4})",
+        dcfr.exploitability,
+        cfr_plus.exploitability
+    );
+    assert_eq!(dcfr.cfr_variant, CfrVariant::DCFR);
+    assert!(dcfr.render().contains("dcfr (alpha 1.5, beta 0, gamma 2)"));
+}
+
 #[test]
 fn showdown_aa_beats_kk() {
     let b = board("2s3h4d5c8s");
@@ -176,6 +421,19 @@ fn showdown_board_blockers_filtered() {
     assert_eq!(combos2.len(), 3, "AA should have 3 combos with As on board");
 }
 
+#[test]
+fn suit_specific_combo_in_range() {
+    let range = parse_range("AhKh,7s7d");
+    let combos = expand_range_to_combos(&range, &board("2s3h4d5c8s"));
+    assert_eq!(combos.len(), 2);
+
+    // Ah on the board drops AhKh; overlapping AKs entry doesn't double count
+    let range = parse_range("AhKh,AKs");
+    assert_eq!(expand_range_to_combos(&range, &board("2s3h4d5c8s")).len(), 4);
+    let combos = expand_range_to_combos(&["AhKh".to_string()], &board("Ah3h4d5c8s"));
+    assert!(combos.is_empty());
+}
+
 #[test]
 fn showdown_conflicting_combos_excluded() {
     let b = board("2s3h4d5c8s");
@@ -243,6 +501,179 @@ fn solver_nuts_vs_air() {
     }
 }
 
+#[test]
+fn mccfr_converges_on_nuts_vs_air() {
+    let solve = |iterations| {
+        let mut config = RiverSolverConfig::new("2s3h4d5c8s", "AA", "72o", 10.0, 20.0, iterations).unwrap();
+        config.algorithm = SolverAlgorithm::Mccfr;
+        config.seed = Some(7);
+        solve_river(&config)
+    };
+
+    let early = solve(500);
+    let result = solve(5000);
+    assert_eq!(result.algorithm, SolverAlgorithm::Mccfr);
+    assert!(
+        result.exploitability < early.exploitability && result.exploitability < 1.0,
+        "exploitability {:.4} after 5000 iterations, {:.4} after 500",
+        result.exploitability,
+        early.exploitability
+    );
+
+    // 72o never wins a showdown, so it folds to every bet it reaches
+    for strat in result.strategies.iter().filter(|s| s.player == "IP" && s.actions[0] == "Fold") {
+        for (freq, &reach) in strat.frequencies.iter().zip(&strat.reach) {
+            if reach > 0.01 {
+                assert!(freq[0] > 0.95, "72o should fold at node {} (fold={:.2})", strat.node_id, freq[0]);
+            }
+        }
+    }
+
+    // The same seed replays the same samples
+    assert_eq!(solve(5000).strategies[0].frequencies, result.strategies[0].frequencies);
+}
+
+#[test]
+fn mccfr_average_strategy_approaches_full_cfr() {
+    let config = |algorithm, iterations| {
+        let mut config = RiverSolverConfig::new("Ks9d4c7h2s", "AA,KK,T8s", "QQ,KQ,T9s", 10.0, 20.0, iterations).unwrap();
+        config.bet_sizes = same_for_both(vec![BetSize::Pot(0.75)]);
+        config.algorithm = algorithm;
+        config.seed = Some(3);
+        config
+    };
+    let cfr = solve_river(&config(SolverAlgorithm::Cfr, 2000));
+    // Mean gap in action frequencies, each combo weighted by how often it
+    // reaches the node under the full-CFR strategy
+    let distance = |iterations| {
+        let mccfr = solve_river(&config(SolverAlgorithm::Mccfr, iterations));
+        let (mut gap, mut weight) = (0.0, 0.0);
+        for (a, b) in cfr.strategies.iter().zip(&mccfr.strategies) {
+            assert_eq!(a.node_id, b.node_id);
+            for (h, (fa, fb)) in a.frequencies.iter().zip(&b.frequencies).enumerate() {
+                let reach = a.reach.get(h).copied().unwrap_or(1.0);
+                gap += reach * fa.iter().zip(fb).map(|(x, y)| (x - y).abs()).sum::<f64>() / 2.0;
+                weight += reach;
+            }
+        }
+        gap / weight
+    };
+
+    let (early, late) = (distance(2000), distance(20000));
+    assert!(late < early && late < 0.15, "distance {:.4} after 20000 iterations, {:.4} after 2000", late, early);
+}
+
+#[test]
+fn resumed_solve_matches_an_uninterrupted_one() {
+    use_scratch_home();
+    let config = |iterations| {
+        RiverSolverConfig::new("Ks9d4c7h2s", "AA,KK,AK,T8s", "QQ,AK,KQ,T9s", 10.0, 20.0, iterations).unwrap()
+    };
+    let mut first_half = config(60);
+    first_half.checkpoint_every = Some(30);
+    let path = first_half.checkpoint_path();
+    solve_river(&first_half);
+    assert!(path.exists(), "checkpoint should be written to {}", path.display());
+
+    let resumed = resume_river(&config(120), None).unwrap();
+    let uninterrupted = solve_river(&config(120));
+    assert_eq!(resumed.iterations, 120);
+    assert!((resumed.exploitability - uninterrupted.exploitability).abs() < 1e-9);
+    assert_eq!(resumed.strategies[0].frequencies, uninterrupted.strategies[0].frequencies);
+
+    // A checkpoint moved under another spot's name is rejected
+    let other = RiverSolverConfig::new("Ks9d4c7h2s", "AA,KK,AK,T8s", "QQ,AK,KQ,T9s", 12.0, 20.0, 120).unwrap();
+    std::fs::rename(&path, other.checkpoint_path()).unwrap();
+    let err = resume_river(&other, None).unwrap_err();
+    assert!(err.contains("different config"), "{}", err);
+    std::fs::remove_file(other.checkpoint_path()).ok();
+}
+
+#[test]
+fn resumed_mccfr_solve_replays_the_same_samples() {
+    use_scratch_home();
+    let config = |iterations| {
+        let mut config =
+            RiverSolverConfig::new("Ks9d4c7h2s", "AA,KK,AK,T8s", "QQ,AK,KQ,T9s", 10.0, 20.0, iterations).unwrap();
+        config.algorithm = SolverAlgorithm::Mccfr;
+        config.seed = Some(9);
+        config
+    };
+    let mut first_half = config(300);
+    first_half.checkpoint_every = Some(150);
+    solve_river(&first_half);
+
+    // The checkpoint carries the RNG, so the second half draws what an
+    // uninterrupted run would have drawn
+    let resumed = resume_river(&config(600), None).unwrap();
+    let uninterrupted = solve_river(&config(600));
+    assert_eq!(resumed.strategies[0].frequencies, uninterrupted.strategies[0].frequencies);
+    assert!((resumed.exploitability - uninterrupted.exploitability).abs() < 1e-9);
+    std::fs::remove_file(first_half.checkpoint_path()).ok();
+}
+
+#[test]
+fn cache_is_keyed_by_full_config() {
+    use_scratch_home();
+    let config = |oop: &str, iterations| {
+        RiverSolverConfig::new("Ks9d4c7h2s", oop, "QQ,AK", 10.0, 20.0, iterations).unwrap()
+    };
+    let pos = format!("CacheTest{}", std::process::id());
+    let mut solution = solve_river(&config("AA,KK", 20));
+    solution.oop_pos = pos.clone();
+    solution.ip_pos = "BTN".to_string();
+    solution.save_cache();
+
+    let cached = RiverSolution::load_cache(&config("AA,KK", 20), &pos, "BTN").expect("cache hit");
+    assert_eq!(cached.oop_range, solution.oop_range);
+    // Same board, pot and stack, but different ranges or iterations miss
+    assert!(RiverSolution::load_cache(&config("AA,QQ", 20), &pos, "BTN").is_none());
+    assert!(RiverSolution::load_cache(&config("AA,KK", 40), &pos, "BTN").is_none());
+
+    // A file under the right name but written for another config is ignored
+    let path = solution.cache_path();
+    let stale = config("AA,QQ", 20);
+    solution.config_hash = stale.cache_hash();
+    let stale_path = solution.cache_path();
+    std::fs::rename(&path, &stale_path).unwrap();
+    assert!(RiverSolution::load_cache(&stale, &pos, "BTN").is_none());
+    std::fs::remove_file(stale_path).ok();
+}
+
+#[test]
+fn exploitability_of_grades_fixed_strategies() {
+    use std::collections::HashMap;
+
+    // OOP holds the nuts or air against a bluff-catcher: a value spot
+    let config = RiverSolverConfig::new("Ts8d4c3h2s", "AA,65s", "KK", 10.0, 20.0, 300).unwrap();
+    let solution = solve_river(&config);
+    let gto = exploitability_of(&config, &solution.strategy_map());
+    assert!((gto - solution.exploitability).abs() < 1e-9, "{} vs {}", gto, solution.exploitability);
+
+    // Check whenever possible, call otherwise
+    let mut passive = HashMap::new();
+    for node in &solution.strategies {
+        let action = node.actions.iter().position(|a| a == "Check" || a.starts_with("Call")).unwrap();
+        for combo in 0..node.frequencies.len() {
+            let mut freqs = vec![0.0; node.actions.len()];
+            freqs[action] = 1.0;
+            passive.insert((combo as u16, node.node_id), freqs);
+        }
+    }
+    let passive = exploitability_of(&config, &passive);
+    assert!(
+        passive > gto + 0.5,
+        "always checking ({:.3}) should be far more exploitable than GTO ({:.3})",
+        passive,
+        gto
+    );
+
+    // An empty map plays uniformly, and an empty range has nothing to exploit
+    assert!(exploitability_of(&config, &HashMap::new()) > gto);
+    let empty = RiverSolverConfig::new("TsTdTc3h2s", "AA", "TT", 10.0, 20.0, 10).unwrap();
+    assert_eq!(exploitability_of(&empty, &HashMap::new()), 0.0);
+}
+
 #[test]
 fn solver_strategies_valid_probabilities() {
     let config = RiverSolverConfig::new(
@@ -310,6 +741,116 @@ fn solver_exploitability_decreases() {
     );
 }
 
+#[test]
+fn f32_storage_matches_f64_exploitability() {
+    let make_config = || {
+        RiverSolverConfig::new(
+            "Ks9h5c3d2s",
+            "AA,KQ,98s",
+            "KJ,QQ,76s",
+            10.0,
+            20.0,
+            1000,
+        )
+        .unwrap()
+    };
+    let config = make_config();
+    let mut config_f32 = make_config();
+    config_f32.precision = Precision::F32;
+
+    let result = solve_river(&config);
+    let result_f32 = solve_river(&config_f32);
+
+    assert!(
+        (result.exploitability - result_f32.exploitability).abs() < 0.1,
+        "f32 storage drifted: f64={:.4} f32={:.4}",
+        result.exploitability,
+        result_f32.exploitability
+    );
+}
+
+#[test]
+fn f32_precision_halves_value_memory_at_matching_exploitability() {
+    let make_config = |precision| {
+        let mut config =
+            RiverSolverConfig::new("Ks9h5c3d2s", "AA,KQ,98s", "KJ,QQ,76s", 10.0, 20.0, 2000).unwrap();
+        config.precision = precision;
+        config
+    };
+    let solve = |precision| solve_river(&make_config(precision));
+    let f64_result = solve(Precision::F64);
+    let f32_result = solve(Precision::F32);
+
+    // Under 0.1% of the 10-chip pot
+    let drift = (f64_result.exploitability - f32_result.exploitability).abs();
+    assert!(drift < 0.01, "f32 drifted by {:.5} chips", drift);
+    assert_eq!(f32_result.precision, Precision::F32);
+
+    // The array layout is the same at either precision; the regret and
+    // strategy sums are what shrink, by 4 bytes apiece
+    let config = make_config(Precision::F64);
+    let combos = [f64_result.oop_combos.len(), f64_result.ip_combos.len()];
+    let estimate = estimate_tree(&config.tree_config());
+    let (nodes, entries) = (estimate.action_nodes, estimate.entries(combos));
+    assert_eq!(f64_result.trainer_bytes - f32_result.trainer_bytes, entries * 2 * 4);
+    assert_eq!(f64_result.trainer_bytes, RiverCfr::<f64>::estimated_bytes(nodes, entries, false));
+    assert!(f64_result.showdown_bytes > 0);
+}
+
+#[test]
+fn pruned_solve_matches_unpruned_exploitability() {
+    let make_config = || {
+        RiverSolverConfig::new(
+            "Ks9h5c3d2s",
+            "AA,KK,KQ,98s,76s,54s",
+            "KJ,QQ,JJ,T9s,A5s",
+            10.0,
+            40.0,
+            2000,
+        )
+        .unwrap()
+    };
+    let config = make_config();
+    let mut config_pruned = make_config();
+    config_pruned.prune_threshold = Some(100);
+    config_pruned.seed = Some(4);
+
+    let result = solve_river(&config);
+    let result_pruned = solve_river(&config_pruned);
+
+    // Pruning skips work the unpruned solve does
+    assert_eq!(result.skipped_subtrees, 0);
+    assert!(result_pruned.skipped_subtrees > 0);
+    // Pruning must not cost more than 0.1% of the pot in exploitability
+    assert!(
+        result_pruned.exploitability <= result.exploitability + 0.01,
+        "pruning drifted: unpruned={:.4} pruned={:.4}",
+        result.exploitability,
+        result_pruned.exploitability
+    );
+}
+
+#[test]
+fn pruned_nuts_vs_air_matches_unpruned_exploitability() {
+    let solve = |prune_threshold| {
+        let mut config = RiverSolverConfig::new("2s3h4d5c8s", "AA", "72o", 10.0, 20.0, 2000).unwrap();
+        config.prune_threshold = prune_threshold;
+        config.seed = Some(3);
+        solve_river(&config)
+    };
+    let unpruned = solve(None);
+    let pruned = solve(Some(100));
+
+    // Folds and checks with the nuts stop earning regret early, so this
+    // spot prunes heavily; the final strategy must still be as sound
+    assert!(
+        (pruned.exploitability - unpruned.exploitability).abs() < 0.05,
+        "unpruned={:.4} pruned={:.4}",
+        unpruned.exploitability,
+        pruned.exploitability
+    );
+}
+
 #[test]
 fn solver_check_only_ev_is_showdown_equity() {
     // With no bet sizes, the only option is check-check -> showdown
@@ -323,8 +864,8 @@ fn solver_check_only_ev_is_showdown_equity() {
         100,
     )
     .unwrap();
-    config.bet_sizes = vec![];
-    config.raise_sizes = vec![];
+    config.bet_sizes = same_for_both(vec![]);
+    config.raise_sizes = same_for_both(RaiseSizing::PotFraction(vec![]));
     config.max_raises = 0;
 
     let result = solve_river(&config);
@@ -342,6 +883,217 @@ fn solver_check_only_ev_is_showdown_equity() {
     }
 }
 
+#[test]
+fn solver_per_player_bet_sizes() {
+    // OOP may only check; IP keeps a bet when checked to
+    let mut config = RiverSolverConfig::new("2s3h4d5c8s", "AA", "KK", 10.0, 20.0, 100).unwrap();
+    config.bet_sizes = [vec![], vec![BetSize::Pot(0.5)]];
+    config.raise_sizes = same_for_both(RaiseSizing::PotFraction(vec![]));
+    config.max_raises = 0;
+
+    let result = solve_river(&config);
+    let bets = |player: &str| {
+        result
+            .strategies
+            .iter()
+            .filter(|s| s.player == player)
+            .flat_map(|s| &s.actions)
+            .filter(|a| a.starts_with("Bet"))
+            .count()
+    };
+    assert_eq!(bets("OOP"), 0, "OOP should never bet");
+    assert!(bets("IP") > 0, "IP should have a bet");
+
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["bet_sizes"], serde_json::json!([[], ["0.5"]]));
+}
+
+#[test]
+fn solver_records_allin_settings() {
+    // 10 pot, 10.87 behind: the pot bet is 92% of stack and becomes all-in
+    let mut config = RiverSolverConfig::new("2s3h4d5c8s", "AA", "KK", 10.0, 10.87, 50).unwrap();
+    config.bet_sizes = same_for_both(vec![BetSize::Pot(1.0)]);
+    config.force_allin_above_stack_pct = Some(90.0);
+
+    let result = solve_river(&config);
+    let root = result.strategies.first().unwrap();
+    assert_eq!(root.actions, vec!["Check", "Bet 10.9"]);
+    assert_eq!(root.labels, vec!["Check", "All-in (10.9)"]);
+
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["force_allin_above_stack_pct"], 90.0);
+    assert_eq!(json["allin_threshold"], 0.2);
+}
+
+#[test]
+fn solver_reports_progress() {
+    let config = RiverSolverConfig::new("2s3h4d5c8s", "AA", "KK", 10.0, 20.0, 1000).unwrap();
+    let calls = std::sync::Mutex::new(Vec::new());
+    let record = |current: usize, total: usize| calls.lock().unwrap().push((current, total));
+    solve_river_with_progress(&config, Some(&record));
+
+    let calls = calls.into_inner().unwrap();
+    assert_eq!(calls.len(), 50, "progress should be reported every 2%");
+    assert_eq!(calls.last(), Some(&(1000, 1000)));
+    assert!(calls.windows(2).all(|w| w[0].0 < w[1].0));
+}
+
+#[test]
+fn purify_snaps_small_frequencies() {
+    let mut node = NodeStrategy {
+        node_id: 0,
+        player: "OOP".to_string(),
+        actions: vec!["Check".to_string(), "Bet 3.3".to_string(), "Bet 10.0".to_string()],
+        labels: vec![],
+        frequencies: vec![vec![0.7, 0.26, 0.04], vec![0.03, 0.02, 0.95], vec![0.04, 0.03, 0.03]],
+        reach: vec![],
+    };
+    node.purify(0.05);
+
+    let expected = [[0.7 / 0.96, 0.26 / 0.96, 0.0], [0.0, 0.0, 1.0], [1.0, 0.0, 0.0]];
+    for (freqs, want) in node.frequencies.iter().zip(expected) {
+        for (f, w) in freqs.iter().zip(want) {
+            assert!((f - w).abs() < 1e-12, "{:?} != {:?}", freqs, want);
+        }
+    }
+}
+
+#[test]
+fn purified_solution_sums_to_one() {
+    let config = RiverSolverConfig::new("Ks9d4c7h2s", "AA,KK,QQ,AK,76s", "JJ,TT,AQ,98s", 10.0, 20.0, 300)
+        .unwrap();
+    let mut result = solve_river(&config);
+    result.purify(0.1);
+
+    for node in &result.strategies {
+        for freqs in &node.frequencies {
+            let sum: f64 = freqs.iter().sum();
+            assert!((sum - 1.0).abs() < 1e-9, "node {} sums to {}", node.node_id, sum);
+            assert!(freqs.iter().all(|&f| f == 0.0 || f >= 0.1));
+        }
+    }
+}
+
+#[test]
+fn simplified_solution_reports_cost_and_keeps_original() {
+    let config = RiverSolverConfig::new("Ks9d4c7h2s", "AA,KK,QQ,AK,76s", "JJ,TT,AQ,98s", 10.0, 20.0, 300)
+        .unwrap();
+    let mut result = solve_river(&config);
+    let solved = result.strategies.clone();
+
+    // A zero threshold changes nothing, so re-evaluating the stored
+    // strategies must reproduce the solver's own exploitability
+    simplify_solution(&mut result, 0.0).unwrap();
+    let unchanged = result.simplification.as_ref().unwrap().exploitability;
+    assert!((unchanged - result.exploitability).abs() < 1e-9, "{} vs {}", unchanged, result.exploitability);
+
+    simplify_solution(&mut result, 0.05).unwrap();
+    for node in &result.strategies {
+        assert!(node.frequencies.iter().flatten().all(|&f| f == 0.0 || f >= 0.05));
+    }
+    let original = result.original_strategies();
+    assert_eq!(original.len(), solved.len());
+    for (a, b) in original.iter().zip(&solved) {
+        assert_eq!(a.frequencies, b.frequencies);
+    }
+    let cost = result.simplification_cost_pct().unwrap();
+    assert!(cost.is_finite());
+    assert!(result.render().contains("simplification cost"));
+}
+
+#[test]
+fn convergence_series_sampled_at_interval() {
+    let mut config =
+        RiverSolverConfig::new("Ks9d4c7h2s", "AA,KK,QQ,AK,76s", "JJ,TT,AQ,98s", 10.0, 20.0, 200).unwrap();
+    assert!(solve_river(&config).convergence.is_empty(), "sampling is off by default");
+
+    config.convergence_every = Some(50);
+    let result = solve_river(&config);
+    let iterations: Vec<usize> = result.convergence.iter().map(|&(i, _)| i).collect();
+    assert_eq!(iterations, vec![50, 100, 150, 200]);
+    // The last point is the final strategy the solution reports
+    let (_, last) = result.convergence[3];
+    assert!((last - result.exploitability).abs() < 1e-9);
+    assert!(result.convergence[0].1 > last);
+    assert!(result.render().contains("Convergence: "));
+
+    let json: serde_json::Value = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["convergence"].as_array().unwrap().len(), 4);
+}
+
+#[test]
+fn diff_against_self_is_empty_and_against_purified_lists_changes() {
+    let config = RiverSolverConfig::new("Ks9d4c7h2s", "AA,KK,QQ,AK,76s", "JJ,TT,AQ,98s", 10.0, 20.0, 300)
+        .unwrap();
+    let solved = solve_river(&config);
+    assert!(solved.diff(&solve_river(&config)).is_empty());
+
+    let mut purified = solve_river(&config);
+    simplify_solution(&mut purified, 0.3).unwrap();
+    let diffs = solved.diff(&purified);
+    assert!(!diffs.is_empty(), "purifying at 30% should move some mixes");
+    for diff in &diffs {
+        let StrategyDiff::Changed { node_id, combo, action, old, new } = diff else {
+            panic!("same tree, so only frequencies change: {:?}", diff);
+        };
+        assert!((new - old).abs() > RiverSolution::DIFF_THRESHOLD);
+        let node = purified.strategies.iter().find(|n| n.node_id == *node_id).unwrap();
+        let combos = if node.player == "OOP" { &purified.oop_combos } else { &purified.ip_combos };
+        let i = combos.iter().position(|c| c == combo).unwrap();
+        let a = node.actions.iter().position(|x| x == action).unwrap();
+        assert_eq!(node.frequencies[i][a], *new);
+    }
+}
+
+#[test]
+fn diff_flags_actions_from_a_new_bet_size() {
+    let mut config = RiverSolverConfig::new("Ks9d4c7h2s", "AA,KK,AK", "QQ,JJ,AQ", 10.0, 20.0, 50).unwrap();
+    config.bet_sizes = same_for_both(vec![BetSize::Pot(0.5)]);
+    let half = solve_river(&config);
+    config.bet_sizes = same_for_both(vec![BetSize::Pot(0.75)]);
+    let three_quarters = solve_river(&config);
+
+    let diffs = half.diff(&three_quarters);
+    assert!(diffs.iter().any(|d| matches!(d, StrategyDiff::ActionRemoved { node_id: 0, .. })));
+    assert!(diffs.iter().any(|d| matches!(d, StrategyDiff::ActionAdded { node_id: 0, .. })));
+}
+
+#[test]
+fn aggregate_node_on_symmetric_spot() {
+    let config = RiverSolverConfig::new("2s3h4d5c8s", "AA,KK,QQ", "AA,KK,QQ", 10.0, 20.0, 500).unwrap();
+    let mut result = solve_river(&config);
+
+    // At the root every combo has reach 1: the aggregate is the plain mean
+    let root = result.aggregate_node(0).unwrap();
+    let node = &result.strategies[0];
+    for (a, (label, freq)) in root.iter().enumerate() {
+        assert_eq!(label, &node.actions[a]);
+        let mean = node.frequencies.iter().map(|f| f[a]).sum::<f64>() / node.frequencies.len() as f64;
+        assert!((freq - mean).abs() < 1e-12);
+    }
+    assert!((root.iter().map(|(_, f)| f).sum::<f64>() - 1.0).abs() < 1e-9);
+
+    // Every reached node's aggregate is a distribution
+    for node in &result.strategies {
+        if let Some(agg) = result.aggregate_node(node.node_id) {
+            assert!((agg.iter().map(|(_, f)| f).sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+    }
+
+    // A combo with zero reach is excluded however it would act
+    let before = result.aggregate_node(0).unwrap();
+    result.strategies[0].reach[0] = 0.0;
+    let without_first = result.aggregate_node(0).unwrap();
+    result.strategies[0].frequencies[0] = vec![0.0; before.len()];
+    result.strategies[0].frequencies[0][0] = 1.0;
+    assert_eq!(result.aggregate_node(0).unwrap(), without_first);
+
+    // Combo weights scale each combo's say; uniform weights change nothing
+    let uniform = vec![2.0; result.strategies[0].frequencies.len()];
+    assert_eq!(result.aggregate_node_weighted(0, Some(&uniform)), Some(without_first));
+    assert!(result.aggregate_node(9999).is_none());
+}
+
 #[test]
 fn solver_symmetric_ranges_balanced() {
     // Same range for both players on a board where rank matters
@@ -368,6 +1120,19 @@ fn solver_symmetric_ranges_balanced() {
     );
 }
 
+#[test]
+fn reach_weighted_average_strategy_converges_on_symmetric_ranges() {
+    // Weighting every reached node equally left this spot at ~0.159 after
+    // 2000 iterations; weighting by the traverser's own reach gets to ~0.132
+    let config = RiverSolverConfig::new("2s3h4d5c8s", "AA,KK", "AA,KK", 10.0, 20.0, 2000).unwrap();
+    let result = solve_river(&config);
+    assert!(
+        result.exploitability < 0.15,
+        "Expected exploitability below 0.15, got {:.4}",
+        result.exploitability
+    );
+}
+
 #[test]
 fn combo_expansion_correct_count() {
     let b = board("2s3h4d5c8s");
@@ -411,3 +1176,139 @@ fn empty_range_after_blockers() {
     // As and Ah on board, so only AdAc survives
     assert_eq!(combos.len(), 1, "Only AdAc should survive");
 }
+
+#[test]
+fn solution_json_lines_up_combos_and_frequencies() {
+    let config =
+        RiverSolverConfig::new("Ks9d4c7hQc", "AA,KK,76s", "QQ,JJ,AKs", 10.0, 20.0, 200).unwrap();
+    let result = solve_river(&config);
+    let json = serde_json::to_string_pretty(&result).unwrap();
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let strategies = value["strategies"].as_array().unwrap();
+    assert_eq!(strategies.len(), result.strategies.len());
+    for strat in strategies {
+        let combos = match strat["player"].as_str().unwrap() {
+            "OOP" => &value["oop_combos"],
+            _ => &value["ip_combos"],
+        };
+        let rows = strat["frequencies"].as_array().unwrap();
+        assert_eq!(rows.len(), combos.as_array().unwrap().len());
+        let num_actions = strat["actions"].as_array().unwrap().len();
+        assert!(rows.iter().all(|r| r.as_array().unwrap().len() == num_actions));
+    }
+
+    // Deterministic output, and it reads back as a solution
+    assert_eq!(serde_json::to_string_pretty(&result).unwrap(), json);
+    let back: gto_cli::river_solver::RiverSolution = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.oop_combos, result.oop_combos);
+    assert_eq!(back.strategies.len(), result.strategies.len());
+}
+
+#[test]
+fn plain_text_report_has_board_and_exploitability() {
+    let config = RiverSolverConfig::new("Ks9d4c7hQc", "AA,KK", "QQ,JJ", 10.0, 20.0, 200).unwrap();
+    let result = solve_river(&config);
+    let text = gto_cli::display::strip_ansi(&result.render());
+
+    assert!(!text.contains('\x1b'), "no escape codes left");
+    assert!(text.contains("GTO River Solution  |  Board: Ks9d4c7hQc"));
+    let line = text
+        .lines()
+        .find(|l| l.trim_start().starts_with("Exploitability:"))
+        .expect("exploitability line");
+    assert_eq!(line.trim(), format!("Exploitability: {:.4}", result.exploitability));
+}
+
+#[test]
+fn game_value_favors_the_nuts() {
+    // AA beats 72o on every runout of this board; IP can only bluff
+    let config = RiverSolverConfig::new("KsQd9c5h3s", "AA", "72o", 10.0, 20.0, 300).unwrap();
+    let mut result = solve_river(&config);
+    let (oop, ip) = result.game_value().unwrap();
+
+    assert!(oop > 9.5, "OOP EV {}", oop);
+    assert!(ip <= 1e-9, "IP EV {}", ip);
+    // Zero-sum: the values split the starting pot
+    assert!((oop + ip - 10.0).abs() < 1e-9, "{} + {}", oop, ip);
+    assert!(result.render().contains("Game value: OOP"));
+
+    result.tree = None;
+    assert!(result.game_value().is_err());
+}
+
+#[test]
+fn game_value_splits_the_pot_in_a_balanced_spot() {
+    let config = RiverSolverConfig::new("2s7h9dJcQs", "AA,KK", "QQ,AKo", 10.0, 20.0, 300).unwrap();
+    let result = solve_river(&config);
+    let (oop, ip) = result.game_value().unwrap();
+    assert!((oop + ip - 10.0).abs() < 1e-9, "{} + {}", oop, ip);
+    // Each side wins some of the pot
+    assert!(oop > 0.0 && ip > 0.0, "{} / {}", oop, ip);
+}
+
+#[test]
+fn bucketed_solve_of_wide_ranges_is_faster_and_close() {
+    use std::time::Instant;
+
+    let mut config = RiverSolverConfig::new(
+        "Ks9d4c7hQc",
+        "22+,A2s+,K8s+,Q9s+,J9s+,T9s,ATo+,KJo+",
+        "22+,A2s+,K9s+,QTs+,JTs,AJo+,KQo",
+        10.0,
+        20.0,
+        150,
+    )
+    .unwrap();
+    config.bet_sizes = same_for_both(vec![BetSize::Pot(0.75)]);
+
+    let start = Instant::now();
+    let exact = solve_river(&config);
+    let exact_time = start.elapsed();
+
+    config.abstraction = Abstraction::EquityBuckets(12);
+    let start = Instant::now();
+    let bucketed = solve_river(&config);
+    let bucketed_time = start.elapsed();
+
+    assert!(exact.oop_combos.len() > 150 && exact.ip_combos.len() > 150);
+    assert!(bucketed_time < exact_time, "bucketed {:?} vs exact {:?}", bucketed_time, exact_time);
+    assert_eq!(bucketed.abstraction, Abstraction::EquityBuckets(12));
+    assert!(bucketed.render().contains("12 equity buckets"));
+
+    // Strategies are reported per combo, but combos in a bucket play alike
+    assert_eq!(bucketed.oop_combos, exact.oop_combos);
+    let root = &bucketed.strategies[0];
+    assert_eq!(root.frequencies.len(), bucketed.oop_combos.len());
+    let mut rows: Vec<String> = root.frequencies.iter().map(|f| format!("{:?}", f)).collect();
+    rows.sort();
+    rows.dedup();
+    assert!(rows.len() <= 12, "{} distinct root strategies", rows.len());
+
+    // Measured against every combo, the abstraction stays roughly sane
+    assert!(
+        bucketed.exploitability < 2.0 * exact.exploitability,
+        "bucketed {} vs exact {}",
+        bucketed.exploitability,
+        exact.exploitability
+    );
+    let (exact_oop, _) = exact.game_value().unwrap();
+    let (bucketed_oop, bucketed_ip) = bucketed.game_value().unwrap();
+    assert!((bucketed_oop + bucketed_ip - 10.0).abs() < 1e-9);
+    assert!((bucketed_oop - exact_oop).abs() < 0.5, "{} vs {}", bucketed_oop, exact_oop);
+}
+
+#[test]
+fn abstraction_changes_the_config_hash() {
+    let mut config = RiverSolverConfig::new("Ks9d4c7hQc", "AA,KK", "QQ,JJ", 10.0, 20.0, 100).unwrap();
+    let exact = config.config_hash();
+    config.abstraction = Abstraction::EquityBuckets(8);
+    assert_ne!(config.config_hash(), exact);
+}
+
+#[test]
+fn strip_ansi_removes_color_codes() {
+    let colored = "\x1b[1mGTO\x1b[0m \x1b[32m80%\x1b[0m";
+    assert_eq!(gto_cli::display::strip_ansi(colored), "GTO 80%");
+    assert_eq!(gto_cli::display::strip_ansi("plain"), "plain");
+}
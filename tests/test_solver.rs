@@ -3,11 +3,31 @@
 //! Validates solver output against known push/fold charts and verifies
 //! exploitability convergence, strategy validity, and rake effects.
 
+use std::sync::OnceLock;
+
 use gto_cli::game_tree::{
-    bucket_to_hand, hand_to_bucket, solve_push_fold, NUM_HANDS,
+    bucket_to_hand, build_preflop_equity_matrix, hand_to_bucket, EquityTable, PushFoldResult, NUM_HANDS,
 };
+use gto_cli::icm::IcmSpot;
 use gto_cli::ranges::combo_count;
 
+// ---------------------------------------------------------------------------
+// Shared equity table, built here rather than read from ~/.gto-cli
+// ---------------------------------------------------------------------------
+
+fn equity_table() -> &'static EquityTable {
+    static TABLE: OnceLock<EquityTable> = OnceLock::new();
+    TABLE.get_or_init(|| EquityTable::from_matrix(&build_preflop_equity_matrix(2000)))
+}
+
+fn solve_push_fold(stack_bb: f64, iterations: usize, rake_pct: f64) -> PushFoldResult {
+    gto_cli::game_tree::solve_push_fold(stack_bb, iterations, rake_pct, equity_table())
+}
+
+fn solve_push_fold_icm(spot: &IcmSpot, iterations: usize) -> PushFoldResult {
+    gto_cli::game_tree::solve_push_fold_icm(spot, iterations, equity_table())
+}
+
 // ---------------------------------------------------------------------------
 // Helper: compute push/call percentages from a result
 // ---------------------------------------------------------------------------
@@ -218,6 +238,23 @@ fn rake_tightens_ranges() {
     );
 }
 
+#[test]
+fn bubble_icm_tightens_calls() {
+    // Three left, two paid, a 2bb stack about to blind out
+    let spot = IcmSpot::new(vec![10.0, 10.0, 2.0], vec![0.6, 0.4]).unwrap();
+    let chip_ev = solve_push_fold(10.0, 5000, 0.0);
+    let icm = solve_push_fold_icm(&spot, 5000);
+
+    assert!(icm.icm && !chip_ev.icm);
+    assert_eq!(icm.stack_bb, 10.0);
+    assert!(
+        call_pct(&icm) + 5.0 < call_pct(&chip_ev),
+        "ICM should tighten BB's calls on the bubble: chip EV {:.1}%, ICM {:.1}%",
+        call_pct(&chip_ev),
+        call_pct(&icm),
+    );
+}
+
 // ---------------------------------------------------------------------------
 // Hand bucket mapping
 // ---------------------------------------------------------------------------
@@ -0,0 +1,76 @@
+//! Tests for solver cache size management.
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use gto_cli::solver_cache::{cache_stats_in, clean_cache_in, CacheStats};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gto_solver_cache_{}_{}", std::process::id(), name));
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Write `bytes` zeros to `dir/name`, last modified `age_secs` ago.
+fn write_aged(dir: &std::path::Path, name: &str, bytes: usize, age_secs: u64) {
+    let path = dir.join(name);
+    std::fs::write(&path, vec![0u8; bytes]).unwrap();
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(SystemTime::now() - Duration::from_secs(age_secs)).unwrap();
+}
+
+#[test]
+fn eviction_removes_oldest_until_under_limit() {
+    let dir = temp_dir("evict");
+    write_aged(&dir, "river_old.bin", 400, 400);
+    write_aged(&dir, "turn_older.bin", 300, 500);
+    write_aged(&dir, "flop_mid.bin", 200, 300);
+    write_aged(&dir, "river_new.bin", 100, 10);
+    std::fs::create_dir(dir.join("subdir")).unwrap();
+
+    assert_eq!(cache_stats_in(&dir), CacheStats { total_bytes: 1000, files: 4 });
+
+    let removed = clean_cache_in(&dir, 450).unwrap();
+    assert_eq!(removed, CacheStats { total_bytes: 700, files: 2 });
+    let left = cache_stats_in(&dir);
+    assert!(left.total_bytes <= 450);
+    assert_eq!(left, CacheStats { total_bytes: 300, files: 2 });
+    assert!(dir.join("flop_mid.bin").exists());
+    assert!(dir.join("river_new.bin").exists());
+    assert!(!dir.join("turn_older.bin").exists());
+
+    // Already under the limit: nothing to do
+    assert_eq!(clean_cache_in(&dir, 450).unwrap(), CacheStats::default());
+    // A zero budget empties the cache but leaves directories alone
+    clean_cache_in(&dir, 0).unwrap();
+    assert_eq!(cache_stats_in(&dir), CacheStats::default());
+    assert!(dir.join("subdir").is_dir());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn checkpoints_are_never_evicted() {
+    let dir = temp_dir("ckpt");
+    write_aged(&dir, "river_As7d2c_00000000deadbeef.ckpt", 500, 1000);
+    // Left behind by a checkpoint write that never finished
+    write_aged(&dir, "turn_As7d2c3h_00000000deadbeef.tmp", 400, 900);
+    write_aged(&dir, "river_old.bin", 200, 500);
+    write_aged(&dir, "river_new.bin", 100, 10);
+
+    assert_eq!(cache_stats_in(&dir), CacheStats { total_bytes: 300, files: 2 });
+    assert_eq!(clean_cache_in(&dir, 0).unwrap(), CacheStats { total_bytes: 300, files: 2 });
+    assert!(dir.join("river_As7d2c_00000000deadbeef.ckpt").exists());
+    assert!(dir.join("turn_As7d2c3h_00000000deadbeef.tmp").exists());
+    assert!(!dir.join("river_old.bin").exists());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn missing_directory_is_an_empty_cache() {
+    let dir = temp_dir("missing").join("nope");
+    assert_eq!(cache_stats_in(&dir), CacheStats::default());
+    assert_eq!(clean_cache_in(&dir, 0).unwrap(), CacheStats::default());
+}
@@ -1,6 +1,37 @@
 //! Tests for the turn solver.
 
-use gto_cli::turn_solver::{solve_turn, TurnSolverConfig};
+use std::sync::Once;
+
+use gto_cli::card_encoding::{card_to_index, index_to_card};
+use gto_cli::cards::{parse_card, Rank};
+use gto_cli::postflop_tree::{
+    build_turn_tree, estimate_turn_tree, same_for_both, Action, BetSize, Player, TreeNode,
+    TurnTreeConfig,
+};
+use gto_cli::cfr::SolverAlgorithm;
+use gto_cli::river_solver::Combo;
+use gto_cli::turn_solver::{
+    resume_turn, river_classes, solve_turn, solve_turn_with_progress,
+    TurnSolution, TurnSolverConfig,
+};
+
+fn idx(card: &str) -> u8 {
+    card_to_index(&parse_card(card).unwrap())
+}
+
+fn combo(hand: &str) -> Combo {
+    Combo(idx(&hand[0..2]), idx(&hand[2..4]))
+}
+
+/// Point the gto-cli home at a scratch directory so checkpoint and cache
+/// tests never touch the real `~/.gto-cli`.
+fn use_scratch_home() {
+    static HOME: Once = Once::new();
+    HOME.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("gto-cli-home-{}", std::process::id()));
+        std::env::set_var("GTO_CLI_HOME", dir);
+    });
+}
 
 // ---------------------------------------------------------------------------
 // Config validation
@@ -118,6 +149,12 @@ fn solver_exploitability_reasonable() {
         result.exploitability.is_finite(),
         "Exploitability should be finite"
     );
+    // A best response can never do worse than the strategy it exploits
+    assert!(
+        result.exploitability >= -1e-6,
+        "Exploitability should be non-negative, got {:.4}",
+        result.exploitability
+    );
     // With 500 iterations, exploitability should be reasonable
     // (not necessarily tiny, but not huge)
     assert!(
@@ -127,6 +164,19 @@ fn solver_exploitability_reasonable() {
     );
 }
 
+#[test]
+fn solver_records_convergence_series() {
+    let mut config = TurnSolverConfig::new("Ks9d4c2h", "AA,KK", "QQ,JJ", 10.0, 20.0, 100).unwrap();
+    config.convergence_every = Some(25);
+
+    let result = solve_turn(&config);
+    let iterations: Vec<usize> = result.convergence.iter().map(|&(i, _)| i).collect();
+    assert_eq!(iterations, vec![25, 50, 75, 100]);
+    let (_, last) = result.convergence[3];
+    assert!((last - result.exploitability).abs() < 1e-6);
+    assert!(result.render().contains("Convergence: "));
+}
+
 #[test]
 fn solver_empty_range_after_blockers() {
     // Board uses As Ah — range AA has very few combos
@@ -200,3 +250,437 @@ fn solver_multiple_actions_available() {
         root.actions.len()
     );
 }
+
+// ---------------------------------------------------------------------------
+// Isomorphic river classes
+// ---------------------------------------------------------------------------
+
+#[test]
+fn rainbow_disconnected_turn_collapses_the_bricks() {
+    let board: Vec<u8> = ["Ks", "9d", "4c", "2h"].iter().map(|c| idx(c)).collect();
+    let aces = [combo("AsAh"), combo("AsAd"), combo("AsAc"), combo("AhAd"), combo("AhAc"), combo("AdAc")];
+    let queens = [combo("QsQh"), combo("QsQd"), combo("QsQc"), combo("QhQd"), combo("QhQc"), combo("QdQc")];
+
+    // Every ace and queen is held by some combo and stands alone; no other
+    // river changes AA beating QQ, so the other 40 cards are one class
+    let classes = river_classes(&board, &aces, &queens);
+    assert_eq!(classes.len(), 9);
+    assert_eq!(classes.iter().map(|&(_, n)| n).sum::<u32>(), 48);
+    let singles: Vec<u8> = classes.iter().filter(|&&(_, n)| n == 1).map(|&(c, _)| c).collect();
+    assert_eq!(singles.len(), 8);
+    assert!(singles.iter().all(|&c| aces.iter().chain(&queens).any(|h| h.0 == c || h.1 == c)));
+
+    // Against 6h5h only a three changes the result (a straight), so the
+    // threes split off into a class of their own
+    let classes = river_classes(&board, &aces, &[combo("6h5h")]);
+    let mut sizes: Vec<u32> = classes.iter().map(|&(_, n)| n).collect();
+    sizes.sort_unstable();
+    assert_eq!(sizes, vec![1, 1, 1, 1, 1, 1, 4, 38]);
+    let (threes, _) = classes.iter().find(|&&(_, n)| n == 4).unwrap();
+    assert_eq!(index_to_card(*threes).rank, Rank::Three);
+}
+
+#[test]
+fn collapsed_rivers_match_the_full_solve() {
+    let new_config = || {
+        let mut config =
+            TurnSolverConfig::new("Ks9d4c2h", "AA,KK,AKs", "QQ,JJ,KQs", 10.0, 20.0, 150).unwrap();
+        config.threads = 1;
+        config
+    };
+    let collapsed = new_config();
+    let mut full = new_config();
+    full.isomorphic_rivers = false;
+    assert_ne!(collapsed.config_hash(), full.config_hash());
+
+    let collapsed = solve_turn(&collapsed);
+    let full = solve_turn(&full);
+    assert!(
+        (collapsed.exploitability - full.exploitability).abs() < 1e-3,
+        "collapsed {:.6} vs full {:.6}",
+        collapsed.exploitability,
+        full.exploitability
+    );
+    for (c, f) in collapsed.strategies.iter().zip(&full.strategies) {
+        for (cf, ff) in c.frequencies.iter().zip(&f.frequencies) {
+            for (a, b) in cf.iter().zip(ff) {
+                assert!((a - b).abs() < 1e-3, "node {}: collapsed {} vs full {}", c.node_id, a, b);
+            }
+        }
+    }
+}
+
+#[test]
+fn size_estimate_counts_river_classes() {
+    let mut config = TurnSolverConfig::new("Ks9d4c2h", "AA", "QQ", 10.0, 20.0, 10).unwrap();
+    let collapsed = config.tree_config();
+    assert_eq!(collapsed.river_cards.as_ref().map(Vec::len), Some(9));
+    let (_, collapsed_nodes) = build_turn_tree(&collapsed);
+    assert_eq!(estimate_turn_tree(&collapsed).action_nodes, collapsed_nodes as usize);
+
+    config.isomorphic_rivers = false;
+    let full = config.tree_config();
+    assert!(full.river_cards.is_none());
+    let (_, full_nodes) = build_turn_tree(&full);
+    assert_eq!(estimate_turn_tree(&full).action_nodes, full_nodes as usize);
+    assert!(collapsed_nodes < full_nodes);
+}
+
+// ---------------------------------------------------------------------------
+// Parallel chance-node traversal
+// ---------------------------------------------------------------------------
+
+#[test]
+fn parallel_matches_serial_exploitability() {
+    let mut serial = TurnSolverConfig::new("Ks9d4c2h", "AA,KK,AKs", "QQ,JJ,KQs", 10.0, 20.0, 200)
+        .unwrap();
+    serial.threads = 1;
+    let mut parallel = TurnSolverConfig::new("Ks9d4c2h", "AA,KK,AKs", "QQ,JJ,KQs", 10.0, 20.0, 200)
+        .unwrap();
+    parallel.threads = 4;
+
+    let serial_result = solve_turn(&serial);
+    let parallel_result = solve_turn(&parallel);
+
+    assert!(
+        (serial_result.exploitability - parallel_result.exploitability).abs() < 1e-3,
+        "Parallel exploitability {:.6} should match serial {:.6}",
+        parallel_result.exploitability,
+        serial_result.exploitability
+    );
+}
+
+#[test]
+fn parallel_matches_serial_strategies() {
+    let mut serial = TurnSolverConfig::new("Ks9d4c2h", "AA,KK,AKs", "QQ,JJ,KQs", 10.0, 20.0, 100)
+        .unwrap();
+    serial.threads = 1;
+    let mut parallel = TurnSolverConfig::new("Ks9d4c2h", "AA,KK,AKs", "QQ,JJ,KQs", 10.0, 20.0, 100)
+        .unwrap();
+    parallel.threads = 4;
+
+    let serial_result = solve_turn(&serial);
+    let parallel_result = solve_turn(&parallel);
+
+    assert_eq!(serial_result.strategies.len(), parallel_result.strategies.len());
+    for (s, p) in serial_result.strategies.iter().zip(&parallel_result.strategies) {
+        assert_eq!(s.node_id, p.node_id);
+        for (sf, pf) in s.frequencies.iter().zip(&p.frequencies) {
+            for (a, b) in sf.iter().zip(pf) {
+                assert!((a - b).abs() < 1e-3, "node {}: serial {} vs parallel {}", s.node_id, a, b);
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Per-street sizing
+// ---------------------------------------------------------------------------
+
+#[test]
+fn river_bets_disabled_leaves_check_only_river_roots() {
+    let mut config = TurnSolverConfig::new("Ks9d4c2h", "AA", "KK", 10.0, 20.0, 10).unwrap();
+    config.river_sizing.bet_sizes = same_for_both(vec![]);
+    assert!(config.river_sizing.validate().is_ok());
+
+    let (root, _) = build_turn_tree(&config.tree_config());
+
+    fn river_roots<'a>(node: &'a TreeNode, out: &mut Vec<&'a TreeNode>) {
+        match node {
+            TreeNode::Chance { children, .. } => out.extend(children.iter()),
+            TreeNode::Action { children, .. } => {
+                for c in children {
+                    river_roots(c, out);
+                }
+            }
+            TreeNode::Terminal { .. } => {}
+        }
+    }
+
+    let mut roots = Vec::new();
+    river_roots(&root, &mut roots);
+    assert!(!roots.is_empty(), "Turn tree should have river subtrees");
+    for r in roots {
+        if let TreeNode::Action { actions, .. } = r {
+            assert_eq!(actions, &vec![Action::Check]);
+        }
+    }
+}
+
+#[test]
+fn single_river_size_shrinks_the_tree() {
+    let mut config = TurnSolverConfig::new("Ks9d4c2h", "AA", "KK", 10.0, 20.0, 10).unwrap();
+    let default_tree = config.tree_config();
+    // Defaults match the tree's own and pass straight through
+    let tree_defaults = TurnTreeConfig::new(config.board.clone(), 10.0, 20.0);
+    assert_eq!(tree_defaults.river_bet_sizes, config.river_sizing.bet_sizes);
+    assert_eq!(default_tree.river_bet_sizes, config.river_sizing.bet_sizes);
+    assert_eq!(default_tree.river_raise_sizes, config.river_sizing.raise_sizes);
+    assert_eq!(default_tree.river_max_raises, config.river_sizing.max_raises);
+    let (_, default_nodes) = build_turn_tree(&default_tree);
+
+    config.river_sizing.bet_sizes = same_for_both(vec![BetSize::Pot(1.0)]);
+    let (_, pot_only_nodes) = build_turn_tree(&config.tree_config());
+    assert!(
+        pot_only_nodes < default_nodes,
+        "pot-only river tree has {} nodes, default {}",
+        pot_only_nodes,
+        default_nodes
+    );
+}
+
+#[test]
+fn solution_echoes_street_sizing() {
+    let mut config = TurnSolverConfig::new("Ks9d4c2h", "AA", "KK", 10.0, 20.0, 10).unwrap();
+    config.turn_sizing.bet_sizes = same_for_both(vec![BetSize::Pot(0.75)]);
+    config.river_sizing.max_raises = 1;
+
+    let result = solve_turn(&config);
+    assert_eq!(result.turn_sizing, config.turn_sizing);
+    assert_eq!(result.river_sizing, config.river_sizing);
+
+    let json = serde_json::to_string(&result).unwrap();
+    assert!(json.contains("\"turn_sizing\""));
+    assert!(json.contains("\"river_sizing\""));
+}
+
+#[test]
+fn street_sizing_rejects_bad_values() {
+    let mut config = TurnSolverConfig::new("Ks9d4c2h", "AA", "KK", 10.0, 20.0, 10).unwrap();
+    config.turn_sizing.bet_sizes = [vec![BetSize::Pot(0.5)], vec![BetSize::Pot(-1.0)]];
+    assert!(config.turn_sizing.validate().is_err());
+    config.river_sizing.max_raises = 100;
+    assert!(config.river_sizing.validate().is_err());
+}
+
+// ---------------------------------------------------------------------------
+// Per-combo EV
+// ---------------------------------------------------------------------------
+
+#[test]
+fn combo_evs_cover_every_combo_and_sum_to_pot() {
+    let config = TurnSolverConfig::new("Ks9d4c2h", "AA,KK", "QQ,JJ", 10.0, 20.0, 200).unwrap();
+    let result = solve_turn(&config);
+
+    assert_eq!(result.oop_combo_evs.len(), result.oop_combos.len());
+    assert_eq!(result.ip_combo_evs.len(), result.ip_combos.len());
+
+    // No overlapping cards between these ranges: every pair is valid, so the
+    // average OOP EV plus the average IP EV must equal the pot.
+    let mean = |evs: &[Option<f64>]| {
+        evs.iter().map(|e| e.expect("no blocked combos")).sum::<f64>() / evs.len() as f64
+    };
+    let total = mean(&result.oop_combo_evs) + mean(&result.ip_combo_evs);
+    assert!((total - 10.0).abs() < 1e-3, "EVs should sum to the pot, got {:.4}", total);
+
+    // Sets and overpairs beat QQ/JJ far more often than not
+    assert!(mean(&result.oop_combo_evs) > 5.0);
+}
+
+#[test]
+fn combo_evs_handle_nodes_with_many_actions() {
+    let mut config = TurnSolverConfig::new("Ks9d4c2h", "AA", "KK", 10.0, 1000.0, 5).unwrap();
+    config.turn_sizing.bet_sizes = same_for_both((1..=18).map(|i| BetSize::Pot(i as f64 * 0.25)).collect());
+    config.turn_sizing.max_raises = 0;
+    config.river_sizing.bet_sizes = same_for_both(vec![]);
+
+    let (root, _) = build_turn_tree(&config.tree_config());
+    let TreeNode::Action { actions, .. } = &root else { panic!("root acts") };
+    assert!(actions.len() > 16, "{} actions at the root", actions.len());
+
+    let result = solve_turn(&config);
+    assert!(result.oop_combo_evs.iter().all(Option::is_some));
+    assert!(result.exploitability.is_finite());
+}
+
+#[test]
+fn fully_blocked_combo_has_no_ev() {
+    // Only AdAc survives the board for both players, so they always conflict
+    let config = TurnSolverConfig::new("AsAh4d5c", "AA", "AA", 10.0, 20.0, 10).unwrap();
+    let result = solve_turn(&config);
+    assert_eq!(result.oop_combo_evs, vec![None]);
+    assert_eq!(result.ip_combo_evs, vec![None]);
+}
+
+#[test]
+fn checkdown_evs_weight_rivers_by_card_removal() {
+    // Check-only on both streets, so each combo's EV is its showdown equity
+    // times the pot, over the rivers live for each pair of hands.
+    let mut config = TurnSolverConfig::new("Jh7h2c3d", "AKs", "QQ", 10.0, 20.0, 2).unwrap();
+    config.turn_sizing.bet_sizes = same_for_both(vec![]);
+    config.river_sizing.bet_sizes = same_for_both(vec![]);
+    let result = solve_turn(&config);
+
+    let ev = |hand: &str| {
+        let i = result.oop_combos.iter().position(|c| c == hand).unwrap();
+        result.oop_combo_evs[i].unwrap()
+    };
+    // AhKh wins on the 9 other hearts and 6 overcards out of 44 rivers, one
+    // heart fewer against the three queens holding Qh: (3 * 15 + 3 * 14) / 6
+    let hearts = 10.0 * 14.5 / 44.0;
+    assert!((ev("AhKh") - hearts).abs() < 1e-6, "AhKh EV {:.6}, want {:.6}", ev("AhKh"), hearts);
+    // AsKs has only the overcards, whichever queens it faces
+    let spades = 10.0 * 6.0 / 44.0;
+    assert!((ev("AsKs") - spades).abs() < 1e-6, "AsKs EV {:.6}, want {:.6}", ev("AsKs"), spades);
+    assert!(result.exploitability.abs() < 1e-6);
+}
+
+#[test]
+fn turn_solver_reports_progress() {
+    let mut config = TurnSolverConfig::new("Ks9d4c2h", "AA", "KK", 10.0, 20.0, 4).unwrap();
+    config.turn_sizing.bet_sizes = same_for_both(vec![]);
+    config.river_sizing.bet_sizes = same_for_both(vec![]);
+    let calls = std::sync::Mutex::new(Vec::new());
+    let record = |current: usize, total: usize| calls.lock().unwrap().push((current, total));
+    solve_turn_with_progress(&config, Some(&record));
+
+    assert_eq!(calls.into_inner().unwrap(), vec![(1, 4), (2, 4), (3, 4), (4, 4)]);
+}
+
+#[test]
+fn mccfr_turn_solve_converges_and_replays_with_seed() {
+    let solve = |iterations| {
+        let mut config = TurnSolverConfig::new("Ks9d4c2h", "AA", "72o", 10.0, 20.0, iterations).unwrap();
+        config.algorithm = SolverAlgorithm::Mccfr;
+        config.seed = Some(11);
+        solve_turn(&config)
+    };
+
+    let early = solve(200);
+    let result = solve(2000);
+    assert_eq!(result.algorithm, SolverAlgorithm::Mccfr);
+    assert!(
+        result.exploitability < early.exploitability,
+        "exploitability {:.4} after 2000 iterations, {:.4} after 200",
+        result.exploitability,
+        early.exploitability
+    );
+    assert_eq!(solve(2000).strategies[0].frequencies, result.strategies[0].frequencies);
+}
+
+#[test]
+fn resumed_turn_solve_matches_an_uninterrupted_one() {
+    use_scratch_home();
+    let config = |iterations| {
+        let mut config = TurnSolverConfig::new("Ks9d4c2h", "AA,KQs", "QQ", 10.0, 20.0, iterations).unwrap();
+        config.threads = 1;
+        config
+    };
+    let mut first_half = config(10);
+    first_half.checkpoint_every = Some(10);
+    let path = first_half.checkpoint_path();
+    solve_turn(&first_half);
+
+    let resumed = resume_turn(&config(20), None).unwrap();
+    let uninterrupted = solve_turn(&config(20));
+    assert_eq!(resumed.strategies[0].frequencies, uninterrupted.strategies[0].frequencies);
+    assert!((resumed.exploitability - uninterrupted.exploitability).abs() < 1e-9);
+    std::fs::remove_file(&path).ok();
+
+    let err = resume_turn(&config(20), None).unwrap_err();
+    assert!(err.contains("Cannot read checkpoint"), "{}", err);
+}
+
+#[test]
+fn resumed_mccfr_turn_solve_replays_the_same_samples() {
+    use_scratch_home();
+    let config = |iterations| {
+        let mut config = TurnSolverConfig::new("Ks9d4c2h", "AA,KQs", "QQ", 10.0, 20.0, iterations).unwrap();
+        config.threads = 1;
+        config.algorithm = SolverAlgorithm::Mccfr;
+        config.seed = Some(9);
+        config
+    };
+    let mut first_half = config(40);
+    first_half.checkpoint_every = Some(20);
+    solve_turn(&first_half);
+
+    let resumed = resume_turn(&config(80), None).unwrap();
+    let uninterrupted = solve_turn(&config(80));
+    assert_eq!(resumed.strategies[0].frequencies, uninterrupted.strategies[0].frequencies);
+    assert!((resumed.exploitability - uninterrupted.exploitability).abs() < 1e-9);
+    std::fs::remove_file(first_half.checkpoint_path()).ok();
+}
+
+#[test]
+fn turn_cache_misses_when_ranges_change() {
+    use_scratch_home();
+    let config = |ip: &str| {
+        let mut config = TurnSolverConfig::new("Ks9d4c2h", "AA,KQs", ip, 10.0, 20.0, 5).unwrap();
+        config.threads = 1;
+        config
+    };
+    let pos = format!("CacheTest{}", std::process::id());
+    let mut solution = solve_turn(&config("QQ"));
+    solution.oop_pos = pos.clone();
+    solution.ip_pos = "BTN".to_string();
+    solution.save_cache();
+
+    assert!(TurnSolution::load_cache(&config("QQ"), &pos, "BTN").is_some());
+    assert!(TurnSolution::load_cache(&config("JJ"), &pos, "BTN").is_none());
+    let mut more_sizes = config("QQ");
+    more_sizes.turn_sizing.max_raises += 1;
+    assert!(TurnSolution::load_cache(&more_sizes, &pos, "BTN").is_none());
+    std::fs::remove_file(solution.cache_path()).ok();
+}
+
+#[test]
+fn node_reach_starts_at_the_range_and_only_shrinks() {
+    let mut config = TurnSolverConfig::new("Ks9d4c2h", "AA,KQs,76s", "QQ,JTs", 10.0, 20.0, 30).unwrap();
+    config.threads = 1;
+    let solution = solve_turn(&config);
+
+    let root = &solution.node_reach[&0];
+    assert!(root[0].iter().chain(&root[1]).all(|&r| r == 1.0));
+    assert_eq!(root[0].len(), solution.oop_combos.len());
+    assert_eq!(root[1].len(), solution.ip_combos.len());
+    assert_eq!(solution.range_reach(0, Player::OOP), Some(1.0));
+    for strat in &solution.strategies {
+        assert!(solution.node_reach.contains_key(&strat.node_id));
+    }
+
+    // Along every line each combo's reach never grows, and only the acting
+    // player's reach changes at a node
+    fn walk(node: &TreeNode, reach: &std::collections::BTreeMap<u32, [Vec<f64>; 2]>) {
+        let TreeNode::Action { node_id, player, children, .. } = node else {
+            return;
+        };
+        let parent = &reach[node_id];
+        for child in children {
+            if let TreeNode::Action { node_id: child_id, .. } = child {
+                let child_reach = &reach[child_id];
+                let other = 1 - player.index();
+                assert_eq!(child_reach[other], parent[other]);
+                for (c, p) in child_reach[player.index()].iter().zip(&parent[player.index()]) {
+                    assert!(*c <= p + 1e-9, "reach grew from {} to {} below node {}", p, c, node_id);
+                }
+            }
+            walk(child, reach);
+        }
+    }
+    walk(solution.tree.as_ref().unwrap(), &solution.node_reach);
+
+    // Checking and betting split OOP's range at the root
+    let TreeNode::Action { children, .. } = solution.tree.as_ref().unwrap() else {
+        panic!("root should be an action node");
+    };
+    let split: f64 = children
+        .iter()
+        .filter_map(|child| match child {
+            TreeNode::Action { node_id, .. } => solution.range_reach(*node_id, Player::OOP),
+            _ => None,
+        })
+        .sum();
+    assert!((split - 1.0).abs() < 1e-4, "OOP's root actions should cover its range, got {}", split);
+}
+
+#[test]
+fn node_reach_serializes_in_node_order() {
+    let solve = || {
+        let mut config = TurnSolverConfig::new("Ks9d4c2h", "AA,KQs", "QQ", 10.0, 20.0, 10).unwrap();
+        config.threads = 1;
+        serde_json::to_string(&solve_turn(&config)).unwrap()
+    };
+    // Two solves of one spot write byte-identical JSON
+    assert_eq!(solve(), solve());
+}
@@ -0,0 +1,120 @@
+//! Tests for stepping through a solved river tree.
+
+use gto_cli::postflop_tree::TreeNode;
+use gto_cli::river_solver::{solve_river, RiverSolverConfig};
+use gto_cli::walk::{Walk, WalkCommand};
+
+fn solved_walk() -> Walk {
+    let config = RiverSolverConfig::new("2s7h9dJcQs", "AA,KK", "QQ,AKo", 10.0, 20.0, 200).unwrap();
+    Walk::new(solve_river(&config)).unwrap()
+}
+
+#[test]
+fn walk_starts_at_the_oop_root() {
+    let walk = solved_walk();
+    let strategy = walk.strategy().expect("root has a strategy");
+    assert_eq!(strategy.player, "OOP");
+    assert_eq!(walk.node_id(), Some(strategy.node_id));
+    assert!(walk.line().is_empty());
+    assert_eq!(walk.actions()[0], "Check");
+
+    let aggregate = walk.aggregate().unwrap();
+    assert_eq!(aggregate.len(), walk.actions().len());
+    assert!((aggregate.iter().map(|(_, f)| f).sum::<f64>() - 1.0).abs() < 1e-6);
+
+    // Every OOP combo reaches the root
+    let combos = walk.combo_frequencies();
+    assert_eq!(combos.len(), walk.solution().oop_combos.len());
+    assert!(combos.iter().all(|&(_, reach, freqs)| reach == 1.0 && freqs.len() == aggregate.len()));
+}
+
+#[test]
+fn actions_are_chosen_by_name_or_number() {
+    let mut walk = solved_walk();
+    assert_eq!(walk.parse("check"), Ok(WalkCommand::Take(0)));
+    assert_eq!(walk.parse(" CH "), Ok(WalkCommand::Take(0)));
+    assert_eq!(walk.parse("1"), Ok(WalkCommand::Take(0)));
+    assert_eq!(walk.parse("back"), Ok(WalkCommand::Back));
+    assert_eq!(walk.parse("root"), Ok(WalkCommand::Root));
+    assert_eq!(walk.parse("q"), Ok(WalkCommand::Quit));
+
+    // Check, then IP is to act at a different node
+    let root = walk.node_id().unwrap();
+    assert_eq!(walk.step("check"), Ok(true));
+    assert_eq!(walk.line(), vec!["Check"]);
+    assert_ne!(walk.node_id(), Some(root));
+    assert_eq!(walk.strategy().unwrap().player, "IP");
+
+    // A bet by its plain tree label matches the shown pot-relative one
+    let bet = walk.actions().iter().position(|a| a.starts_with("Bet")).expect("IP can bet");
+    let TreeNode::Action { actions, .. } = walk.node() else { panic!("IP acts here") };
+    let plain = actions[bet].label();
+    assert_eq!(walk.parse(&plain), Ok(WalkCommand::Take(bet)));
+    assert_eq!(walk.step(&(bet + 1).to_string()), Ok(true));
+    assert_eq!(walk.line(), vec!["Check".to_string(), plain]);
+    assert_eq!(walk.strategy().unwrap().player, "OOP");
+}
+
+#[test]
+fn back_and_root_retrace_the_line() {
+    let mut walk = solved_walk();
+    let root = walk.node_id();
+    assert_eq!(walk.step("back"), Err("Already at the root".to_string()));
+
+    walk.step("check").unwrap();
+    let after_check = walk.node_id();
+    walk.step("1").unwrap();
+    assert_eq!(walk.line().len(), 2);
+
+    assert!(walk.back());
+    assert_eq!(walk.node_id(), after_check);
+    walk.step("1").unwrap();
+    assert_eq!(walk.step("root"), Ok(true));
+    assert_eq!(walk.node_id(), root);
+    assert!(walk.line().is_empty());
+}
+
+#[test]
+fn fold_and_showdown_end_the_walk() {
+    let mut walk = solved_walk();
+    // Check-check goes to showdown
+    walk.step("check").unwrap();
+    walk.step("check").unwrap();
+    assert!(walk.outcome().unwrap().starts_with("Showdown"));
+    assert!(walk.actions().is_empty());
+    assert!(walk.strategy().is_none());
+    assert!(walk.combo_frequencies().is_empty());
+    assert!(walk.step("check").is_err());
+    assert!(walk.take(0).is_err());
+
+    // Check, bet, fold ends with IP taking the pot
+    walk.root();
+    walk.step("check").unwrap();
+    walk.step("bet 33").unwrap();
+    walk.step("fold").unwrap();
+    let outcome = walk.outcome().unwrap();
+    assert!(outcome.starts_with("OOP folds"), "{}", outcome);
+}
+
+#[test]
+fn bad_input_is_rejected_without_moving() {
+    let mut walk = solved_walk();
+    let n = walk.actions().len();
+    assert!(walk.step("").is_err());
+    assert!(walk.step("0").is_err());
+    assert!(walk.step(&(n + 1).to_string()).is_err());
+    assert!(walk.step("shove everything").unwrap_err().contains("No action matches"));
+    assert!(walk.take(n).is_err());
+    assert!(walk.line().is_empty());
+    assert_eq!(walk.step("quit"), Ok(false));
+}
+
+#[test]
+fn ambiguous_prefix_names_the_candidates() {
+    let walk = solved_walk();
+    // Three bet sizes at the root share the "bet" prefix
+    let err = walk.parse("bet").unwrap_err();
+    assert_eq!(err.matches("Bet").count(), 3, "{}", err);
+    assert!(matches!(walk.parse("bet 67"), Ok(WalkCommand::Take(_))));
+    assert!(walk.line().is_empty());
+}